@@ -55,6 +55,7 @@
 //! ```
 
 use std::any::Any;
+use std::collections::HashMap;
 use std::convert::From;
 use std::fmt;
 use std::io::Write;
@@ -65,6 +66,7 @@ use crate::array_data::{ArrayData, ArrayDataRef};
 use crate::buffer::{Buffer, MutableBuffer};
 use crate::builder::*;
 use crate::datatypes::*;
+use crate::error::{ArrowError, Result};
 use crate::memory;
 use crate::util::bit_util;
 
@@ -115,10 +117,11 @@ pub type ArrayRef = Arc<Array>;
 
 /// Constructs an array using the input `data`. Returns a reference-counted `Array`
 /// instance.
-fn make_array(data: ArrayDataRef) -> ArrayRef {
+pub fn make_array(data: ArrayDataRef) -> ArrayRef {
     // TODO: here data_type() needs to clone the type - maybe add a type tag enum to
     // avoid the cloning.
     match data.data_type().clone() {
+        DataType::Null => Arc::new(NullArray::from(data)) as ArrayRef,
         DataType::Boolean => Arc::new(BooleanArray::from(data)) as ArrayRef,
         DataType::Int8 => Arc::new(Int8Array::from(data)) as ArrayRef,
         DataType::Int16 => Arc::new(Int16Array::from(data)) as ArrayRef,
@@ -130,9 +133,60 @@ fn make_array(data: ArrayDataRef) -> ArrayRef {
         DataType::UInt64 => Arc::new(UInt64Array::from(data)) as ArrayRef,
         DataType::Float32 => Arc::new(Float32Array::from(data)) as ArrayRef,
         DataType::Float64 => Arc::new(Float64Array::from(data)) as ArrayRef,
-        DataType::Utf8 => Arc::new(BinaryArray::from(data)) as ArrayRef,
+        DataType::Date(DateUnit::Day) => Arc::new(Date32Array::from(data)) as ArrayRef,
+        DataType::Date(DateUnit::Millisecond) => {
+            Arc::new(Date64Array::from(data)) as ArrayRef
+        }
+        DataType::Time32(TimeUnit::Second) => {
+            Arc::new(Time32SecondArray::from(data)) as ArrayRef
+        }
+        DataType::Time32(TimeUnit::Millisecond) => {
+            Arc::new(Time32MillisecondArray::from(data)) as ArrayRef
+        }
+        DataType::Time64(TimeUnit::Microsecond) => {
+            Arc::new(Time64MicrosecondArray::from(data)) as ArrayRef
+        }
+        DataType::Time64(TimeUnit::Nanosecond) => {
+            Arc::new(Time64NanosecondArray::from(data)) as ArrayRef
+        }
+        DataType::Timestamp(TimeUnit::Second, _) => {
+            Arc::new(TimestampSecondArray::from(data)) as ArrayRef
+        }
+        DataType::Timestamp(TimeUnit::Millisecond, _) => {
+            Arc::new(TimestampMillisecondArray::from(data)) as ArrayRef
+        }
+        DataType::Timestamp(TimeUnit::Microsecond, _) => {
+            Arc::new(TimestampMicrosecondArray::from(data)) as ArrayRef
+        }
+        DataType::Timestamp(TimeUnit::Nanosecond, _) => {
+            Arc::new(TimestampNanosecondArray::from(data)) as ArrayRef
+        }
+        DataType::Interval(IntervalUnit::YearMonth) => {
+            Arc::new(IntervalYearMonthArray::from(data)) as ArrayRef
+        }
+        DataType::Interval(IntervalUnit::DayTime) => {
+            Arc::new(IntervalDayTimeArray::from(data)) as ArrayRef
+        }
+        DataType::Binary | DataType::Utf8 => {
+            Arc::new(BinaryArray::from(data)) as ArrayRef
+        }
         DataType::List(_) => Arc::new(ListArray::from(data)) as ArrayRef,
+        DataType::FixedSizeList(_, _) => {
+            Arc::new(FixedSizeListArray::from(data)) as ArrayRef
+        }
         DataType::Struct(_) => Arc::new(StructArray::from(data)) as ArrayRef,
+        DataType::Union(_, _) => Arc::new(UnionArray::from(data)) as ArrayRef,
+        DataType::Dictionary(key_type, _) => match *key_type {
+            DataType::Int8 => Arc::new(Int8DictionaryArray::from(data)) as ArrayRef,
+            DataType::Int16 => Arc::new(Int16DictionaryArray::from(data)) as ArrayRef,
+            DataType::Int32 => Arc::new(Int32DictionaryArray::from(data)) as ArrayRef,
+            DataType::Int64 => Arc::new(Int64DictionaryArray::from(data)) as ArrayRef,
+            DataType::UInt8 => Arc::new(UInt8DictionaryArray::from(data)) as ArrayRef,
+            DataType::UInt16 => Arc::new(UInt16DictionaryArray::from(data)) as ArrayRef,
+            DataType::UInt32 => Arc::new(UInt32DictionaryArray::from(data)) as ArrayRef,
+            DataType::UInt64 => Arc::new(UInt64DictionaryArray::from(data)) as ArrayRef,
+            dt => panic!("Unexpected dictionary key type {:?}", dt),
+        },
         dt => panic!("Unexpected data type {:?}", dt),
     }
 }
@@ -179,6 +233,18 @@ pub type UInt32Array = PrimitiveArray<UInt32Type>;
 pub type UInt64Array = PrimitiveArray<UInt64Type>;
 pub type Float32Array = PrimitiveArray<Float32Type>;
 pub type Float64Array = PrimitiveArray<Float64Type>;
+pub type Date32Array = PrimitiveArray<Date32Type>;
+pub type Date64Array = PrimitiveArray<Date64Type>;
+pub type Time32SecondArray = PrimitiveArray<Time32SecondType>;
+pub type Time32MillisecondArray = PrimitiveArray<Time32MillisecondType>;
+pub type Time64MicrosecondArray = PrimitiveArray<Time64MicrosecondType>;
+pub type Time64NanosecondArray = PrimitiveArray<Time64NanosecondType>;
+pub type TimestampSecondArray = PrimitiveArray<TimestampSecondType>;
+pub type TimestampMillisecondArray = PrimitiveArray<TimestampMillisecondType>;
+pub type TimestampMicrosecondArray = PrimitiveArray<TimestampMicrosecondType>;
+pub type TimestampNanosecondArray = PrimitiveArray<TimestampNanosecondType>;
+pub type IntervalYearMonthArray = PrimitiveArray<IntervalYearMonthType>;
+pub type IntervalDayTimeArray = PrimitiveArray<IntervalDayTimeType>;
 
 impl<T: ArrowPrimitiveType> Array for PrimitiveArray<T> {
     fn as_any(&self) -> &Any {
@@ -313,7 +379,7 @@ impl fmt::Debug for PrimitiveArray<BooleanType> {
 // otherwise with both `From<Vec<T::Native>>` and `From<Vec<Option<T::Native>>>`.
 // We should revisit this in future.
 macro_rules! def_numeric_from_vec {
-    ( $ty:ident, $native_ty:ident, $ty_id:path ) => {
+    ( $ty:ident, $native_ty:ident, $ty_id:expr ) => {
         impl From<Vec<$native_ty>> for PrimitiveArray<$ty> {
             fn from(data: Vec<$native_ty>) -> Self {
                 let array_data = ArrayData::builder($ty_id)
@@ -370,6 +436,54 @@ def_numeric_from_vec!(UInt32Type, u32, DataType::UInt32);
 def_numeric_from_vec!(UInt64Type, u64, DataType::UInt64);
 def_numeric_from_vec!(Float32Type, f32, DataType::Float32);
 def_numeric_from_vec!(Float64Type, f64, DataType::Float64);
+def_numeric_from_vec!(Date32Type, i32, DataType::Date(DateUnit::Day));
+def_numeric_from_vec!(Date64Type, i64, DataType::Date(DateUnit::Millisecond));
+def_numeric_from_vec!(Time32SecondType, i32, DataType::Time32(TimeUnit::Second));
+def_numeric_from_vec!(
+    Time32MillisecondType,
+    i32,
+    DataType::Time32(TimeUnit::Millisecond)
+);
+def_numeric_from_vec!(
+    Time64MicrosecondType,
+    i64,
+    DataType::Time64(TimeUnit::Microsecond)
+);
+def_numeric_from_vec!(
+    Time64NanosecondType,
+    i64,
+    DataType::Time64(TimeUnit::Nanosecond)
+);
+def_numeric_from_vec!(
+    TimestampSecondType,
+    i64,
+    DataType::Timestamp(TimeUnit::Second, None)
+);
+def_numeric_from_vec!(
+    TimestampMillisecondType,
+    i64,
+    DataType::Timestamp(TimeUnit::Millisecond, None)
+);
+def_numeric_from_vec!(
+    TimestampMicrosecondType,
+    i64,
+    DataType::Timestamp(TimeUnit::Microsecond, None)
+);
+def_numeric_from_vec!(
+    TimestampNanosecondType,
+    i64,
+    DataType::Timestamp(TimeUnit::Nanosecond, None)
+);
+def_numeric_from_vec!(
+    IntervalYearMonthType,
+    i32,
+    DataType::Interval(IntervalUnit::YearMonth)
+);
+def_numeric_from_vec!(
+    IntervalDayTimeType,
+    i64,
+    DataType::Interval(IntervalUnit::DayTime)
+);
 
 /// Constructs a boolean array from a vector. Should only be used for testing.
 impl From<Vec<bool>> for BooleanArray {
@@ -534,7 +648,91 @@ impl Array for ListArray {
     }
 }
 
-/// A special type of `ListArray` whose elements are binaries.
+/// A list array where each element is a sequence of the same number of values of the
+/// same type. Unlike `ListArray` there are no value offsets: the values of element `i`
+/// start at `i * value_length()` in the values array.
+pub struct FixedSizeListArray {
+    data: ArrayDataRef,
+    values: ArrayRef,
+    length: i32,
+}
+
+impl FixedSizeListArray {
+    /// Returns an reference to the values of this list.
+    pub fn values(&self) -> ArrayRef {
+        self.values.clone()
+    }
+
+    /// Returns a clone of the value type of this list.
+    pub fn value_type(&self) -> DataType {
+        self.values.data().data_type().clone()
+    }
+
+    /// Returns the offset for value at index `i`.
+    ///
+    /// Note this doesn't do any bound checking, for performance reason.
+    #[inline]
+    pub fn value_offset(&self, i: usize) -> i32 {
+        (self.data.offset() + i) as i32 * self.length
+    }
+
+    /// Returns the length of each value, which is the same for every index.
+    #[inline]
+    pub fn value_length(&self) -> i32 {
+        self.length
+    }
+}
+
+/// Constructs a `FixedSizeListArray` from an array data reference.
+impl From<ArrayDataRef> for FixedSizeListArray {
+    fn from(data: ArrayDataRef) -> Self {
+        assert_eq!(
+            data.buffers().len(),
+            0,
+            "FixedSizeListArray data should not contain a buffer for value offsets"
+        );
+        assert_eq!(
+            data.child_data().len(),
+            1,
+            "FixedSizeListArray should contain a single child array (values array)"
+        );
+        let length = match data.data_type() {
+            DataType::FixedSizeList(_, length) => *length,
+            _ => panic!("FixedSizeListArray data should have a FixedSizeList data type"),
+        };
+        assert!(
+            length >= 0,
+            "FixedSizeListArray length should not be negative"
+        );
+        let values = make_array(data.child_data()[0].clone());
+        assert!(
+            values.len() >= (data.offset() + data.len()) * length as usize,
+            "FixedSizeListArray child array is too short for its length"
+        );
+        Self {
+            data: data.clone(),
+            values,
+            length,
+        }
+    }
+}
+
+impl Array for FixedSizeListArray {
+    fn as_any(&self) -> &Any {
+        self
+    }
+
+    fn data(&self) -> ArrayDataRef {
+        self.data.clone()
+    }
+
+    fn data_ref(&self) -> &ArrayDataRef {
+        &self.data
+    }
+}
+
+/// A special type of `ListArray` whose elements are binaries. The data type of the
+/// array is `Utf8` for arrays of strings and `Binary` for arrays of arbitrary bytes.
 pub struct BinaryArray {
     data: ArrayDataRef,
     value_offsets: RawPtrBox<i32>,
@@ -584,6 +782,35 @@ impl BinaryArray {
     fn value_offset_at(&self, i: usize) -> i32 {
         unsafe { *self.value_offsets.get().offset(i as isize) }
     }
+
+    /// Creates a `BinaryArray` of the given data type, which is either `Utf8` or
+    /// `Binary`, from a `List<u8>` array
+    pub fn from_list(v: ListArray, data_type: DataType) -> Self {
+        assert_eq!(
+            v.data().child_data()[0].child_data().len(),
+            0,
+            "BinaryArray can only be created from list array of u8 values \
+             (i.e. List<PrimitiveArray<u8>>)."
+        );
+        assert_eq!(
+            v.data().child_data()[0].data_type(),
+            &DataType::UInt8,
+            "BinaryArray can only be created from List<u8> arrays, mismatched data types."
+        );
+
+        let mut builder = ArrayData::builder(data_type)
+            .len(v.len())
+            .add_buffer(v.data().buffers()[0].clone())
+            .add_buffer(v.data().child_data()[0].buffers()[0].clone());
+        if let Some(bitmap) = v.data().null_bitmap() {
+            builder = builder
+                .null_count(v.data().null_count())
+                .null_bit_buffer(bitmap.bits.clone())
+        }
+
+        let data = builder.build();
+        Self::from(data)
+    }
 }
 
 impl From<ArrayDataRef> for BinaryArray {
@@ -627,33 +854,31 @@ impl<'a> From<Vec<&'a str>> for BinaryArray {
     }
 }
 
-/// Creates a `BinaryArray` from `List<u8>` array
-impl From<ListArray> for BinaryArray {
-    fn from(v: ListArray) -> Self {
-        assert_eq!(
-            v.data().child_data()[0].child_data().len(),
-            0,
-            "BinaryArray can only be created from list array of u8 values \
-             (i.e. List<PrimitiveArray<u8>>)."
-        );
-        assert_eq!(
-            v.data().child_data()[0].data_type(),
-            &DataType::UInt8,
-            "BinaryArray can only be created from List<u8> arrays, mismatched data types."
-        );
-
-        let mut builder = ArrayData::builder(DataType::Utf8)
-            .len(v.len())
-            .add_buffer(v.data().buffers()[0].clone())
-            .add_buffer(v.data().child_data()[0].buffers()[0].clone());
-        if let Some(bitmap) = v.data().null_bitmap() {
-            builder = builder
-                .null_count(v.data().null_count())
-                .null_bit_buffer(bitmap.bits.clone())
+/// Creates a `BinaryArray` of byte values, with the `Binary` data type
+impl<'a> From<Vec<&'a [u8]>> for BinaryArray {
+    fn from(v: Vec<&'a [u8]>) -> Self {
+        let mut offsets = vec![];
+        let mut values = vec![];
+        let mut length_so_far = 0;
+        offsets.push(length_so_far);
+        for s in &v {
+            length_so_far += s.len() as i32;
+            offsets.push(length_so_far as i32);
+            values.extend_from_slice(s);
         }
+        let array_data = ArrayData::builder(DataType::Binary)
+            .len(v.len())
+            .add_buffer(Buffer::from(offsets.to_byte_slice()))
+            .add_buffer(Buffer::from(&values[..]))
+            .build();
+        BinaryArray::from(array_data)
+    }
+}
 
-        let data = builder.build();
-        Self::from(data)
+/// Creates a `BinaryArray` of strings, with the `Utf8` data type, from `List<u8>` array
+impl From<ListArray> for BinaryArray {
+    fn from(v: ListArray) -> Self {
+        BinaryArray::from_list(v, DataType::Utf8)
     }
 }
 
@@ -735,6 +960,344 @@ impl From<Vec<(Field, ArrayRef)>> for StructArray {
     }
 }
 
+/// A union array, whose values each have the type of one of its children. The type id
+/// of a value is the index of the child that holds it. In a sparse union the value of
+/// slot `i` is at index `i` of its child, while in a dense union it is at the value
+/// offset of the slot.
+pub struct UnionArray {
+    data: ArrayDataRef,
+    children: Vec<ArrayRef>,
+    type_ids: RawPtrBox<i8>,
+    value_offsets: Option<RawPtrBox<i32>>,
+}
+
+impl UnionArray {
+    /// Returns the layout of this union.
+    pub fn mode(&self) -> UnionMode {
+        match self.value_offsets {
+            Some(_) => UnionMode::Dense,
+            None => UnionMode::Sparse,
+        }
+    }
+
+    /// Returns the number of children of this union.
+    pub fn num_children(&self) -> usize {
+        self.children.len()
+    }
+
+    /// Returns the child holding the values of type id `type_id`.
+    pub fn child(&self, type_id: i8) -> &ArrayRef {
+        &self.children[type_id as usize]
+    }
+
+    /// Returns the type id of the value at index `i`.
+    ///
+    /// Note this doesn't do any bound checking, for performance reason.
+    #[inline]
+    pub fn type_id(&self, i: usize) -> i8 {
+        let i = self.data.offset() + i;
+        unsafe { *self.type_ids.get().offset(i as isize) }
+    }
+
+    /// Returns the index in its child of the value at index `i`.
+    ///
+    /// Note this doesn't do any bound checking, for performance reason.
+    #[inline]
+    pub fn value_offset(&self, i: usize) -> i32 {
+        let i = self.data.offset() + i;
+        match &self.value_offsets {
+            Some(offsets) => unsafe { *offsets.get().offset(i as isize) },
+            None => i as i32,
+        }
+    }
+}
+
+/// Constructs a `UnionArray` from an array data reference.
+impl From<ArrayDataRef> for UnionArray {
+    fn from(data: ArrayDataRef) -> Self {
+        let (num_fields, mode) = match data.data_type() {
+            DataType::Union(fields, mode) => (fields.len(), mode.clone()),
+            _ => panic!("UnionArray data should have a Union data type"),
+        };
+        let num_buffers = match mode {
+            UnionMode::Sparse => 1,
+            UnionMode::Dense => 2,
+        };
+        assert_eq!(
+            data.buffers().len(),
+            num_buffers,
+            "UnionArray data should contain type ids, and value offsets if dense"
+        );
+        assert_eq!(
+            data.child_data().len(),
+            num_fields,
+            "UnionArray should contain a child array for each field"
+        );
+        let children: Vec<ArrayRef> =
+            data.child_data().iter().cloned().map(make_array).collect();
+        if mode == UnionMode::Sparse {
+            for child in &children {
+                assert!(
+                    child.len() >= data.offset() + data.len(),
+                    "sparse UnionArray child arrays should have a value for every slot"
+                );
+            }
+        }
+        let type_ids = data.buffers()[0].raw_data() as *const i8;
+        let value_offsets = match mode {
+            UnionMode::Sparse => None,
+            UnionMode::Dense => {
+                let raw_value_offsets = data.buffers()[1].raw_data();
+                assert!(
+                    memory::is_aligned(raw_value_offsets, mem::align_of::<i32>()),
+                    "memory is not aligned"
+                );
+                Some(RawPtrBox::new(raw_value_offsets as *const i32))
+            }
+        };
+        Self {
+            data: data.clone(),
+            children,
+            type_ids: RawPtrBox::new(type_ids),
+            value_offsets,
+        }
+    }
+}
+
+impl Array for UnionArray {
+    fn as_any(&self) -> &Any {
+        self
+    }
+
+    fn data(&self) -> ArrayDataRef {
+        self.data.clone()
+    }
+
+    fn data_ref(&self) -> &ArrayDataRef {
+        &self.data
+    }
+}
+
+/// An array whose values are stored as integer keys into a dictionary, which is an
+/// array of the distinct values. The value of slot `i` is the value at index `key(i)`
+/// of the dictionary, and the slot is null if its key is null.
+pub struct DictionaryArray<K: ArrowDictionaryKeyType> {
+    data: ArrayDataRef,
+    keys: PrimitiveArray<K>,
+    values: ArrayRef,
+}
+
+pub type Int8DictionaryArray = DictionaryArray<Int8Type>;
+pub type Int16DictionaryArray = DictionaryArray<Int16Type>;
+pub type Int32DictionaryArray = DictionaryArray<Int32Type>;
+pub type Int64DictionaryArray = DictionaryArray<Int64Type>;
+pub type UInt8DictionaryArray = DictionaryArray<UInt8Type>;
+pub type UInt16DictionaryArray = DictionaryArray<UInt16Type>;
+pub type UInt32DictionaryArray = DictionaryArray<UInt32Type>;
+pub type UInt64DictionaryArray = DictionaryArray<UInt64Type>;
+
+impl<K: ArrowDictionaryKeyType> DictionaryArray<K> {
+    /// Creates a dictionary array from its keys and its dictionary of values. Returns an
+    /// error if a key that is not null is not the index of a value.
+    pub fn try_new(keys: &PrimitiveArray<K>, values: ArrayRef) -> Result<Self> {
+        for i in 0..keys.len() {
+            if keys.is_valid(i) && K::to_index(keys.value(i)) >= values.len() {
+                return Err(ArrowError::ComputeError(format!(
+                    "Dictionary key {:?} is out of bounds for {} values",
+                    keys.value(i),
+                    values.len()
+                )));
+            }
+        }
+        let keys_data = keys.data();
+        let data_type = DataType::Dictionary(
+            Box::new(K::get_data_type()),
+            Box::new(values.data_type().clone()),
+        );
+        let mut builder = ArrayData::builder(data_type)
+            .len(keys.len())
+            .null_count(keys.null_count())
+            .offset(keys.offset())
+            .add_buffer(keys_data.buffers()[0].clone())
+            .add_child_data(values.data());
+        if let Some(bitmap) = keys_data.null_bitmap() {
+            builder = builder.null_bit_buffer(bitmap.bits.clone());
+        }
+        Ok(Self::from(builder.build()))
+    }
+
+    /// Creates a dictionary array of `Utf8` values that stores each distinct string
+    /// once. Returns an error if there are more distinct strings than keys.
+    pub fn try_from_strings(strings: &[Option<&str>]) -> Result<Self> {
+        let mut keys = PrimitiveArray::<K>::builder(strings.len());
+        let mut values = vec![];
+        let mut indices = HashMap::new();
+        for string in strings {
+            match string {
+                Some(string) => {
+                    let key = match indices.get(string).cloned() {
+                        Some(key) => key,
+                        None => {
+                            let key = K::from_index(values.len()).ok_or_else(|| {
+                                ArrowError::ComputeError(format!(
+                                    "Too many distinct values for {:?} dictionary keys",
+                                    K::get_data_type()
+                                ))
+                            })?;
+                            indices.insert(*string, key);
+                            values.push(*string);
+                            key
+                        }
+                    };
+                    keys.append_value(key)?;
+                }
+                None => keys.append_null()?,
+            }
+        }
+        Self::try_new(&keys.finish(), Arc::new(BinaryArray::from(values)))
+    }
+
+    /// Returns the keys of this array, which are the indices of its values in the
+    /// dictionary.
+    pub fn keys(&self) -> &PrimitiveArray<K> {
+        &self.keys
+    }
+
+    /// Returns the dictionary of values.
+    pub fn values(&self) -> ArrayRef {
+        self.values.clone()
+    }
+
+    /// Returns the key at index `i`.
+    ///
+    /// Note this doesn't do any bound checking, for performance reason.
+    #[inline]
+    pub fn key(&self, i: usize) -> K::Native {
+        self.keys.value(i)
+    }
+
+    /// Returns the index in the dictionary of the value at index `i`.
+    ///
+    /// Note this doesn't do any bound checking, for performance reason.
+    #[inline]
+    pub fn value_index(&self, i: usize) -> usize {
+        K::to_index(self.key(i))
+    }
+}
+
+/// Constructs a `DictionaryArray` from an array data reference.
+impl<K: ArrowDictionaryKeyType> From<ArrayDataRef> for DictionaryArray<K> {
+    fn from(data: ArrayDataRef) -> Self {
+        assert_eq!(
+            data.buffers().len(),
+            1,
+            "DictionaryArray data should contain a single buffer only (keys)"
+        );
+        assert_eq!(
+            data.child_data().len(),
+            1,
+            "DictionaryArray should contain a single child array (values array)"
+        );
+        match data.data_type() {
+            DataType::Dictionary(key_type, _) => assert_eq!(
+                **key_type,
+                K::get_data_type(),
+                "DictionaryArray data should have the key type of the array"
+            ),
+            _ => panic!("DictionaryArray data should have a Dictionary data type"),
+        }
+        let mut builder = ArrayData::builder(K::get_data_type())
+            .len(data.len())
+            .null_count(data.null_count())
+            .offset(data.offset())
+            .add_buffer(data.buffers()[0].clone());
+        if let Some(bitmap) = data.null_bitmap() {
+            builder = builder.null_bit_buffer(bitmap.bits.clone());
+        }
+        Self {
+            data: data.clone(),
+            keys: PrimitiveArray::from(builder.build()),
+            values: make_array(data.child_data()[0].clone()),
+        }
+    }
+}
+
+impl<K: ArrowDictionaryKeyType> Array for DictionaryArray<K> {
+    fn as_any(&self) -> &Any {
+        self
+    }
+
+    fn data(&self) -> ArrayDataRef {
+        self.data.clone()
+    }
+
+    fn data_ref(&self) -> &ArrayDataRef {
+        &self.data
+    }
+}
+
+/// An array of null values of no particular type. The array has no buffers, so it
+/// takes no memory besides its length no matter how long it is.
+pub struct NullArray {
+    data: ArrayDataRef,
+}
+
+impl NullArray {
+    /// Creates an array of `length` null values.
+    pub fn new(length: usize) -> Self {
+        let data = ArrayData::builder(DataType::Null)
+            .len(length)
+            .null_count(length)
+            .build();
+        Self::from(data)
+    }
+}
+
+/// Constructs a `NullArray` from an array data reference.
+impl From<ArrayDataRef> for NullArray {
+    fn from(data: ArrayDataRef) -> Self {
+        assert_eq!(
+            data.buffers().len(),
+            0,
+            "NullArray data should not contain any buffers"
+        );
+        assert!(
+            data.null_bitmap().is_none(),
+            "NullArray data should not contain a null bitmap"
+        );
+        Self { data }
+    }
+}
+
+impl Array for NullArray {
+    fn as_any(&self) -> &Any {
+        self
+    }
+
+    fn data(&self) -> ArrayDataRef {
+        self.data.clone()
+    }
+
+    fn data_ref(&self) -> &ArrayDataRef {
+        &self.data
+    }
+
+    /// Returns whether the element at index `i` is null, which is always true
+    fn is_null(&self, _i: usize) -> bool {
+        true
+    }
+
+    /// Returns whether the element at index `i` is not null, which is always false
+    fn is_valid(&self, _i: usize) -> bool {
+        false
+    }
+
+    /// Returns the total number of nulls in this array, which is its length
+    fn null_count(&self) -> usize {
+        self.data.len()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -744,7 +1307,7 @@ mod tests {
 
     use crate::array_data::ArrayData;
     use crate::buffer::Buffer;
-    use crate::datatypes::{DataType, Field};
+    use crate::datatypes::{DataType, Field, UnionMode};
     use crate::memory;
 
     #[test]
@@ -1064,6 +1627,194 @@ mod tests {
         ListArray::from(list_data);
     }
 
+    #[test]
+    fn test_fixed_size_list_array() {
+        // Construct a value array
+        let value_data = ArrayData::builder(DataType::Int32)
+            .len(9)
+            .add_buffer(Buffer::from(&[0, 1, 2, 3, 4, 5, 6, 7, 8].to_byte_slice()))
+            .build();
+
+        // Construct a fixed size list array from the above, for the nested array:
+        //  [[0, 1, 2], [3, 4, 5], [6, 7, 8]]
+        let list_data_type = DataType::FixedSizeList(Box::new(DataType::Int32), 3);
+        let list_data = ArrayData::builder(list_data_type.clone())
+            .len(3)
+            .add_child_data(value_data.clone())
+            .build();
+        let list_array = FixedSizeListArray::from(list_data);
+
+        let values = list_array.values();
+        assert_eq!(value_data, values.data());
+        assert_eq!(DataType::Int32, list_array.value_type());
+        assert_eq!(3, list_array.len());
+        assert_eq!(0, list_array.null_count());
+        assert_eq!(6, list_array.value_offset(2));
+        assert_eq!(3, list_array.value_length());
+        for i in 0..3 {
+            assert!(list_array.is_valid(i));
+            assert!(!list_array.is_null(i));
+        }
+
+        // Now test with a non-zero offset
+        let list_data = ArrayData::builder(list_data_type)
+            .len(2)
+            .offset(1)
+            .add_child_data(value_data.clone())
+            .build();
+        let list_array = make_array(list_data);
+        let list_array = list_array
+            .as_any()
+            .downcast_ref::<FixedSizeListArray>()
+            .unwrap();
+
+        assert_eq!(value_data, list_array.values().data());
+        assert_eq!(2, list_array.len());
+        assert_eq!(0, list_array.null_count());
+        assert_eq!(6, list_array.value_offset(1));
+        assert_eq!(3, list_array.value_length());
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "FixedSizeListArray child array is too short for its length"
+    )]
+    fn test_fixed_size_list_array_invalid_child_array_len() {
+        let value_data = ArrayData::builder(DataType::Int32)
+            .len(8)
+            .add_buffer(Buffer::from(&[0, 1, 2, 3, 4, 5, 6, 7].to_byte_slice()))
+            .build();
+        let list_data_type = DataType::FixedSizeList(Box::new(DataType::Int32), 3);
+        let list_data = ArrayData::builder(list_data_type)
+            .len(3)
+            .add_child_data(value_data)
+            .build();
+        FixedSizeListArray::from(list_data);
+    }
+
+    #[test]
+    fn test_union_array() {
+        let fields = vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", DataType::Float64, true),
+        ];
+        // dense union of [1, 2.5, 3], sliced to [2.5, 3]
+        let data = ArrayData::builder(DataType::Union(fields.clone(), UnionMode::Dense))
+            .len(2)
+            .offset(1)
+            .add_buffer(Buffer::from(&[0i8, 1, 0].to_byte_slice()))
+            .add_buffer(Buffer::from(&[0i32, 0, 1].to_byte_slice()))
+            .add_child_data(Int32Array::from(vec![1, 3]).data())
+            .add_child_data(Float64Array::from(vec![2.5]).data())
+            .build();
+        let union = make_array(data);
+        let union = union.as_any().downcast_ref::<UnionArray>().unwrap();
+        assert_eq!(UnionMode::Dense, union.mode());
+        assert_eq!(2, union.num_children());
+        assert_eq!(2, union.len());
+        assert_eq!(1, union.type_id(0));
+        assert_eq!(0, union.value_offset(0));
+        assert_eq!(0, union.type_id(1));
+        assert_eq!(1, union.value_offset(1));
+
+        // sparse union of [1, 2.5, null]
+        let data = ArrayData::builder(DataType::Union(fields, UnionMode::Sparse))
+            .len(3)
+            .null_count(1)
+            .null_bit_buffer(Buffer::from([0b011_u8]))
+            .add_buffer(Buffer::from(&[0i8, 1, 0].to_byte_slice()))
+            .add_child_data(Int32Array::from(vec![Some(1), None, None]).data())
+            .add_child_data(Float64Array::from(vec![None, Some(2.5), None]).data())
+            .build();
+        let union = UnionArray::from(data);
+        assert_eq!(UnionMode::Sparse, union.mode());
+        assert_eq!(1, union.null_count());
+        assert!(union.is_null(2));
+        assert_eq!(1, union.type_id(1));
+        assert_eq!(1, union.value_offset(1));
+        let b = union.child(1);
+        let b = b.as_any().downcast_ref::<Float64Array>().unwrap();
+        assert_eq!(2.5, b.value(1));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "sparse UnionArray child arrays should have a value for every slot"
+    )]
+    fn test_sparse_union_array_invalid_child_array_len() {
+        let fields = vec![Field::new("a", DataType::Int32, true)];
+        let data = ArrayData::builder(DataType::Union(fields, UnionMode::Sparse))
+            .len(3)
+            .add_buffer(Buffer::from(&[0i8, 0, 0].to_byte_slice()))
+            .add_child_data(Int32Array::from(vec![1, 2]).data())
+            .build();
+        UnionArray::from(data);
+    }
+
+    #[test]
+    fn test_dictionary_array() {
+        let array = Int8DictionaryArray::try_from_strings(&[
+            Some("east"),
+            Some("west"),
+            None,
+            Some("east"),
+        ])
+        .unwrap();
+        assert_eq!(
+            &DataType::Dictionary(Box::new(DataType::Int8), Box::new(DataType::Utf8)),
+            array.data_type()
+        );
+        assert_eq!(4, array.len());
+        assert_eq!(1, array.null_count());
+        assert!(array.is_null(2));
+
+        // repeated values are stored once
+        let values = array.values();
+        let values = values.as_any().downcast_ref::<BinaryArray>().unwrap();
+        assert_eq!(2, values.len());
+        assert_eq!(0, array.key(0));
+        assert_eq!(1, array.value_index(1));
+        assert_eq!(0, array.value_index(3));
+        assert_eq!("west", values.get_string(array.value_index(1)));
+
+        // the array can be rebuilt from its data
+        let array = make_array(array.data());
+        let array = array
+            .as_any()
+            .downcast_ref::<Int8DictionaryArray>()
+            .unwrap();
+        assert_eq!(4, array.keys().len());
+        assert!(array.keys().is_null(2));
+        assert_eq!(0, array.key(3));
+    }
+
+    #[test]
+    fn test_dictionary_array_invalid_key() {
+        let keys = UInt8Array::from(vec![0, 2]);
+        let values: ArrayRef = Arc::new(BinaryArray::from(vec!["a", "b"]));
+        assert!(UInt8DictionaryArray::try_new(&keys, values.clone()).is_err());
+
+        let keys = Int8Array::from(vec![Some(1), None]);
+        let array = Int8DictionaryArray::try_new(&keys, values).unwrap();
+        assert_eq!(1, array.null_count());
+    }
+
+    #[test]
+    fn test_null_array() {
+        let array = NullArray::new(32);
+        assert_eq!(&DataType::Null, array.data_type());
+        assert_eq!(32, array.len());
+        assert_eq!(32, array.null_count());
+        assert!(array.is_null(0));
+        assert!(!array.is_valid(31));
+        assert_eq!(0, array.data().buffers().len());
+
+        // the null count does not depend on how the data was created
+        let array = make_array(ArrayData::builder(DataType::Null).len(5).build());
+        assert_eq!(5, array.null_count());
+        assert!(array.is_null(4));
+    }
+
     #[test]
     fn test_binary_array() {
         let values: [u8; 12] = [
@@ -1115,6 +1866,21 @@ mod tests {
         assert_eq!(7, binary_array.value_length(1));
     }
 
+    #[test]
+    fn test_binary_array_from_bytes() {
+        let values: Vec<&[u8]> = vec![&[0xde, 0xad], &[], &[0xbe, 0xef]];
+        let binary_array = BinaryArray::from(values);
+        assert_eq!(&DataType::Binary, binary_array.data_type());
+        assert_eq!(3, binary_array.len());
+        assert_eq!([0xde, 0xad], binary_array.value(0));
+        assert_eq!(0, binary_array.value_length(1));
+        assert_eq!([0xbe, 0xef], binary_array.value(2));
+
+        let array = make_array(binary_array.data());
+        assert_eq!(&DataType::Binary, array.data_type());
+        assert!(array.as_any().downcast_ref::<BinaryArray>().is_some());
+    }
+
     #[test]
     fn test_binary_array_from_list_array() {
         let values: [u8; 12] = [
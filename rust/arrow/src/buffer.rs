@@ -21,7 +21,9 @@
 //!
 use packed_simd::u8x64;
 
+use std::any::Any;
 use std::cmp;
+use std::fmt;
 use std::io::{Error as IoError, ErrorKind, Result as IoResult, Write};
 use std::mem;
 use std::ops::{BitAnd, BitOr, Not};
@@ -30,7 +32,7 @@ use std::sync::Arc;
 
 use crate::builder::{BufferBuilderTrait, UInt8BufferBuilder};
 use crate::error::{ArrowError, Result};
-use crate::memory;
+use crate::memory::{self, Allocator};
 use crate::util::bit_util;
 
 /// Buffer is a contiguous memory region of fixed size and is aligned at a 64-byte
@@ -44,13 +46,28 @@ pub struct Buffer {
     offset: usize,
 }
 
-#[derive(Debug)]
 struct BufferData {
     /// The raw pointer into the buffer bytes
     ptr: *const u8,
 
     /// The length (num of bytes) of the buffer
     len: usize,
+
+    /// The owner of memory that was not allocated by this crate's default allocator,
+    /// such as memory imported through the C data interface or allocated by an
+    /// `Allocator`. The memory is released by dropping the owner instead of being
+    /// freed by this buffer.
+    owner: Option<Arc<Any + Send + Sync>>,
+}
+
+impl fmt::Debug for BufferData {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("BufferData")
+            .field("ptr", &self.ptr)
+            .field("len", &self.len)
+            .field("foreign", &self.owner.is_some())
+            .finish()
+    }
 }
 
 impl PartialEq for BufferData {
@@ -65,7 +82,9 @@ impl PartialEq for BufferData {
 /// Release the underlying memory when the current buffer goes out of scope
 impl Drop for BufferData {
     fn drop(&mut self) {
-        memory::free_aligned(self.ptr);
+        if self.owner.is_none() {
+            memory::free_aligned(self.ptr);
+        }
     }
 }
 
@@ -73,7 +92,36 @@ impl Buffer {
     /// Creates a buffer from an existing memory region (must already be byte-aligned)
     pub fn from_raw_parts(ptr: *const u8, len: usize) -> Self {
         assert!(memory::is_aligned(ptr, 64), "memory not aligned");
-        let buf_data = BufferData { ptr, len };
+        let buf_data = BufferData {
+            ptr,
+            len,
+            owner: None,
+        };
+        Buffer {
+            data: Arc::new(buf_data),
+            offset: 0,
+        }
+    }
+
+    /// Creates a buffer from a memory region that is owned by `owner`, without copying
+    /// the memory. The memory is released when `owner` is dropped, after the last
+    /// buffer referencing it is dropped.
+    ///
+    /// The memory is not required to be padded. Arrays read their values in place, so
+    /// it must be aligned for the type of the values of an array built on the buffer,
+    /// which callers such as the C data interface check. This function is unsafe
+    /// because `len` bytes from `ptr` must stay valid and unchanged for as long as
+    /// `owner` is alive.
+    pub unsafe fn from_unowned(
+        ptr: *const u8,
+        len: usize,
+        owner: Arc<Any + Send + Sync>,
+    ) -> Self {
+        let buf_data = BufferData {
+            ptr,
+            len,
+            owner: Some(owner),
+        };
         Buffer {
             data: Arc::new(buf_data),
             offset: 0,
@@ -154,7 +202,8 @@ where
 {
     let mut result = MutableBuffer::new(left.len()).with_bitset(left.len(), false);
     let lanes = u8x64::lanes();
-    for i in (0..left.len()).step_by(lanes) {
+    let simd_len = left.len() - left.len() % lanes;
+    for i in (0..simd_len).step_by(lanes) {
         let left_data =
             unsafe { from_raw_parts(left.raw_data().offset(i as isize), lanes) };
         let right_data =
@@ -169,6 +218,19 @@ where
             bit_util::bitwise_bin_op_simd(&left_data, &right_data, result_slice, &op)
         };
     }
+    if simd_len < left.len() {
+        // copy the remaining bytes, as buffers of foreign memory may not be padded
+        let tail_len = left.len() - simd_len;
+        let mut left_tail = [0u8; 64];
+        let mut right_tail = [0u8; 64];
+        let mut result_tail = [0u8; 64];
+        left_tail[..tail_len].copy_from_slice(&left.data()[simd_len..]);
+        right_tail[..tail_len].copy_from_slice(&right.data()[simd_len..]);
+        unsafe {
+            bit_util::bitwise_bin_op_simd(&left_tail, &right_tail, &mut result_tail, &op)
+        };
+        result.data_mut()[simd_len..].copy_from_slice(&result_tail[..tail_len]);
+    }
     return result.freeze();
 }
 
@@ -250,7 +312,8 @@ impl Not for &Buffer {
             let mut result =
                 MutableBuffer::new(self.len()).with_bitset(self.len(), false);
             let lanes = u8x64::lanes();
-            for i in (0..self.len()).step_by(lanes) {
+            let simd_len = self.len() - self.len() % lanes;
+            for i in (0..simd_len).step_by(lanes) {
                 unsafe {
                     let data = from_raw_parts(self.raw_data().offset(i as isize), lanes);
                     let data_simd = u8x64::from_slice_unaligned_unchecked(data);
@@ -262,6 +325,11 @@ impl Not for &Buffer {
                     simd_result.write_to_slice_unaligned_unchecked(result_slice);
                 }
             }
+            // the remaining bytes are not read as a vector, as buffers of foreign
+            // memory may not be padded
+            for i in simd_len..self.len() {
+                result.data_mut()[i] = !self.data()[i];
+            }
             return result.freeze();
         }
 
@@ -282,6 +350,23 @@ impl Not for &Buffer {
 unsafe impl Sync for Buffer {}
 unsafe impl Send for Buffer {}
 
+/// Memory allocated by an `Allocator` for a frozen `MutableBuffer`, which is released
+/// through the allocator when the last buffer referencing it is dropped
+struct Allocation {
+    ptr: *mut u8,
+    capacity: usize,
+    allocator: Arc<Allocator>,
+}
+
+impl Drop for Allocation {
+    fn drop(&mut self) {
+        self.allocator.free(self.ptr, self.capacity);
+    }
+}
+
+unsafe impl Sync for Allocation {}
+unsafe impl Send for Allocation {}
+
 /// Similar to `Buffer`, but is growable and can be mutated. A mutable buffer can be
 /// converted into a immutable buffer via the `freeze` method.
 #[derive(Debug)]
@@ -289,6 +374,8 @@ pub struct MutableBuffer {
     data: *mut u8,
     len: usize,
     capacity: usize,
+    /// The allocator of the memory, or `None` for the default allocator
+    allocator: Option<Arc<Allocator>>,
 }
 
 impl MutableBuffer {
@@ -300,9 +387,45 @@ impl MutableBuffer {
             data: ptr,
             len: 0,
             capacity: new_capacity,
+            allocator: None,
         }
     }
 
+    /// Allocate a new mutable buffer with initial capacity to be `capacity`, from
+    /// `allocator`. The buffer grows through the same allocator, and its memory is
+    /// released to it when the buffer, or the `Buffer` it is frozen into, is dropped.
+    pub fn new_in(capacity: usize, allocator: Arc<Allocator>) -> Self {
+        let new_capacity = bit_util::round_upto_multiple_of_64(capacity);
+        let ptr = allocator.allocate(new_capacity).unwrap();
+        Self {
+            data: ptr,
+            len: 0,
+            capacity: new_capacity,
+            allocator: Some(allocator),
+        }
+    }
+
+    /// Returns the allocator of this buffer's memory, or `None` if it was allocated by
+    /// the default allocator.
+    pub fn allocator(&self) -> Option<&Arc<Allocator>> {
+        self.allocator.as_ref()
+    }
+
+    /// Moves the memory of this buffer into a new region of `new_capacity` bytes
+    fn reallocate(&mut self, new_capacity: usize) -> Result<()> {
+        let new_data = match self.allocator {
+            Some(ref allocator) => {
+                allocator.reallocate(self.capacity, new_capacity, self.data)?
+            }
+            None => {
+                memory::reallocate(self.capacity, new_capacity, self.data)? as *mut u8
+            }
+        };
+        self.data = new_data;
+        self.capacity = new_capacity;
+        Ok(())
+    }
+
     /// Set the bits in the range of `[0, end)` to 0 (if `val` is false), or 1 (if `val`
     /// is true). Also extend the length of this buffer to be `end`.
     ///
@@ -339,9 +462,7 @@ impl MutableBuffer {
         if capacity > self.capacity {
             let new_capacity = bit_util::round_upto_multiple_of_64(capacity);
             let new_capacity = cmp::max(new_capacity, self.capacity * 2);
-            let new_data = memory::reallocate(self.capacity, new_capacity, self.data)?;
-            self.data = new_data as *mut u8;
-            self.capacity = new_capacity;
+            self.reallocate(new_capacity)?;
         }
         Ok(self.capacity)
     }
@@ -359,10 +480,7 @@ impl MutableBuffer {
         } else {
             let new_capacity = bit_util::round_upto_multiple_of_64(new_len);
             if new_capacity < self.capacity {
-                let new_data =
-                    memory::reallocate(self.capacity, new_capacity, self.data)?;
-                self.data = new_data as *mut u8;
-                self.capacity = new_capacity;
+                self.reallocate(new_capacity)?;
             }
         }
         self.len = new_len;
@@ -410,10 +528,18 @@ impl MutableBuffer {
     }
 
     /// Freezes this buffer and return an immutable version of it.
-    pub fn freeze(self) -> Buffer {
+    pub fn freeze(mut self) -> Buffer {
+        let owner = self.allocator.take().map(|allocator| {
+            Arc::new(Allocation {
+                ptr: self.data,
+                capacity: self.capacity,
+                allocator,
+            }) as Arc<Any + Send + Sync>
+        });
         let buffer_data = BufferData {
             ptr: self.data,
             len: self.len,
+            owner,
         };
         ::std::mem::forget(self);
         Buffer {
@@ -425,7 +551,10 @@ impl MutableBuffer {
 
 impl Drop for MutableBuffer {
     fn drop(&mut self) {
-        memory::free_aligned(self.data);
+        match self.allocator {
+            Some(ref allocator) => allocator.free(self.data, self.capacity),
+            None => memory::free_aligned(self.data),
+        }
     }
 }
 
@@ -691,6 +820,37 @@ mod tests {
         assert_eq!("aaaa bbbb cccc dddd".as_bytes(), immutable_buf.data());
     }
 
+    #[test]
+    fn test_mutable_allocator() {
+        let allocator = Arc::new(memory::PoolAllocator::new(1024));
+        let mut buf = MutableBuffer::new_in(1, allocator.clone());
+        assert_eq!(64, allocator.allocated_bytes());
+        assert!(buf.allocator().is_some());
+
+        buf.reserve(100).unwrap();
+        assert_eq!(128, buf.capacity());
+        assert_eq!(128, allocator.allocated_bytes());
+        assert_eq!(64, allocator.pooled_bytes());
+
+        buf.write("aaaa bbbb".as_bytes()).unwrap();
+        let immutable_buf = buf.freeze();
+        let immutable_buf2 = immutable_buf.clone();
+        assert_eq!("aaaa bbbb".as_bytes(), immutable_buf.data());
+        assert_eq!(128, allocator.allocated_bytes());
+
+        drop(immutable_buf);
+        assert_eq!(128, allocator.allocated_bytes());
+        drop(immutable_buf2);
+        assert_eq!(0, allocator.allocated_bytes());
+        assert_eq!(192, allocator.pooled_bytes());
+
+        // the memory of the dropped buffer is reused
+        let buf = MutableBuffer::new_in(128, allocator.clone());
+        assert_eq!(64, allocator.pooled_bytes());
+        drop(buf);
+        assert_eq!(192, allocator.pooled_bytes());
+    }
+
     #[test]
     fn test_access_concurrently() {
         let buffer = Buffer::from(vec![1, 2, 3, 4, 5]);
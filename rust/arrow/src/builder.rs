@@ -29,6 +29,7 @@ use crate::array_data::ArrayData;
 use crate::buffer::{Buffer, MutableBuffer};
 use crate::datatypes::*;
 use crate::error::{ArrowError, Result};
+use crate::memory::Allocator;
 use crate::util::bit_util;
 
 /// Buffer builder with zero-copy build method
@@ -117,13 +118,22 @@ impl<T: ArrowPrimitiveType> BufferBuilderTrait<T> for BufferBuilder<T> {
 
     /// Reset this builder and returns an immutable `Buffer`.
     default fn finish(&mut self) -> Buffer {
-        let buf = ::std::mem::replace(&mut self.buffer, MutableBuffer::new(0));
+        let buf = ::std::mem::replace(&mut self.buffer, empty_like(&self.buffer));
         self.len = 0;
         buf.freeze()
     }
 }
 
 impl<T: ArrowPrimitiveType> BufferBuilder<T> {
+    /// Creates a builder with a fixed initial capacity, whose memory is allocated from
+    /// `allocator`
+    pub fn new_in(capacity: usize, allocator: Arc<Allocator>) -> Self {
+        let mut builder = Self::new(0);
+        builder.buffer = MutableBuffer::new_in(0, allocator);
+        builder.reserve(capacity).unwrap();
+        builder
+    }
+
     /// Writes a byte slice to the underlying buffer and updates the `len`, i.e. the
     /// number array elements in the builder.  Also, converts the `io::Result`
     /// required by the `Write` trait to the Arrow `Result` type.
@@ -142,6 +152,14 @@ impl<T: ArrowPrimitiveType> BufferBuilder<T> {
     }
 }
 
+/// Creates an empty buffer with the same allocator as `buffer`
+fn empty_like(buffer: &MutableBuffer) -> MutableBuffer {
+    match buffer.allocator() {
+        Some(allocator) => MutableBuffer::new_in(0, allocator.clone()),
+        None => MutableBuffer::new(0),
+    }
+}
+
 impl BufferBuilderTrait<BooleanType> for BufferBuilder<BooleanType> {
     /// Creates a builder with a fixed initial capacity.
     fn new(capacity: usize) -> Self {
@@ -205,7 +223,7 @@ impl BufferBuilderTrait<BooleanType> for BufferBuilder<BooleanType> {
         // `append` does not update the buffer's `len` so do it before `freeze` is called.
         let new_buffer_len = bit_util::ceil(self.len, 8);
         debug_assert!(new_buffer_len >= self.buffer.len());
-        let mut buf = ::std::mem::replace(&mut self.buffer, MutableBuffer::new(0));
+        let mut buf = ::std::mem::replace(&mut self.buffer, empty_like(&self.buffer));
         self.len = 0;
         buf.resize(new_buffer_len).unwrap();
         buf.freeze()
@@ -238,6 +256,90 @@ pub trait ArrayBuilder: Any {
     fn into_box_any(self: Box<Self>) -> Box<Any>;
 }
 
+/// Boxed builders are builders too, so that nested builders such as `ListBuilder` can be
+/// composed at runtime from builders returned by `make_builder`.
+impl ArrayBuilder for Box<ArrayBuilder> {
+    /// Returns the number of array slots in the builder
+    fn len(&self) -> usize {
+        (**self).len()
+    }
+
+    /// Builds the array
+    fn finish(&mut self) -> ArrayRef {
+        (**self).finish()
+    }
+
+    /// Returns the inner builder as an non-mutable `Any` reference.
+    fn as_any(&self) -> &Any {
+        (**self).as_any()
+    }
+
+    /// Returns the inner builder as an mutable `Any` reference.
+    fn as_any_mut(&mut self) -> &mut Any {
+        (**self).as_any_mut()
+    }
+
+    /// Returns the inner builder as a box of `Any`.
+    fn into_box_any(self: Box<Self>) -> Box<Any> {
+        (*self).into_box_any()
+    }
+}
+
+/// Returns a builder for arrays of the given data type, with room for `capacity` values.
+///
+/// Lists, structs and unions are built from builders of their child types, so arbitrarily
+/// nested types are supported. The returned builder can be downcast to the concrete
+/// builder type through `as_any_mut`, and the child builders of a list through
+/// `ListBuilder::values` or `FixedSizeListBuilder::values`.
+pub fn make_builder(datatype: &DataType, capacity: usize) -> Box<ArrayBuilder> {
+    match datatype {
+        DataType::Boolean => Box::new(BooleanBuilder::new(capacity)),
+        DataType::Int8 => Box::new(Int8Builder::new(capacity)),
+        DataType::Int16 => Box::new(Int16Builder::new(capacity)),
+        DataType::Int32 => Box::new(Int32Builder::new(capacity)),
+        DataType::Int64 => Box::new(Int64Builder::new(capacity)),
+        DataType::UInt8 => Box::new(UInt8Builder::new(capacity)),
+        DataType::UInt16 => Box::new(UInt16Builder::new(capacity)),
+        DataType::UInt32 => Box::new(UInt32Builder::new(capacity)),
+        DataType::UInt64 => Box::new(UInt64Builder::new(capacity)),
+        DataType::Float32 => Box::new(Float32Builder::new(capacity)),
+        DataType::Float64 => Box::new(Float64Builder::new(capacity)),
+        DataType::Date(DateUnit::Day) => Box::new(Date32Builder::new(capacity)),
+        DataType::Date(DateUnit::Millisecond) => Box::new(Date64Builder::new(capacity)),
+        DataType::Time32(TimeUnit::Second) => {
+            Box::new(Time32SecondBuilder::new(capacity))
+        }
+        DataType::Time32(TimeUnit::Millisecond) => {
+            Box::new(Time32MillisecondBuilder::new(capacity))
+        }
+        DataType::Time64(TimeUnit::Microsecond) => {
+            Box::new(Time64MicrosecondBuilder::new(capacity))
+        }
+        DataType::Time64(TimeUnit::Nanosecond) => {
+            Box::new(Time64NanosecondBuilder::new(capacity))
+        }
+        DataType::Binary => Box::new(BinaryBuilder::new_binary(capacity)),
+        DataType::Utf8 => Box::new(BinaryBuilder::new(capacity)),
+        DataType::List(values_type) => {
+            Box::new(ListBuilder::new(make_builder(values_type, capacity)))
+        }
+        DataType::FixedSizeList(values_type, length) => {
+            let values_builder = make_builder(values_type, capacity * *length as usize);
+            Box::new(FixedSizeListBuilder::new(values_builder, *length))
+        }
+        DataType::Struct(fields) => {
+            let schema = Schema::new(fields.clone());
+            Box::new(StructBuilder::from_schema(schema, capacity))
+        }
+        DataType::Union(fields, mode) => Box::new(UnionBuilder::from_fields(
+            fields.clone(),
+            mode.clone(),
+            capacity,
+        )),
+        t @ _ => panic!("Data type {:?} is not currently supported", t),
+    }
+}
+
 ///  Array builder for fixed-width primitive types
 pub struct PrimitiveBuilder<T: ArrowPrimitiveType> {
     values_builder: BufferBuilder<T>,
@@ -255,6 +357,12 @@ pub type UInt32Builder = PrimitiveBuilder<UInt32Type>;
 pub type UInt64Builder = PrimitiveBuilder<UInt64Type>;
 pub type Float32Builder = PrimitiveBuilder<Float32Type>;
 pub type Float64Builder = PrimitiveBuilder<Float64Type>;
+pub type Date32Builder = PrimitiveBuilder<Date32Type>;
+pub type Date64Builder = PrimitiveBuilder<Date64Type>;
+pub type Time32SecondBuilder = PrimitiveBuilder<Time32SecondType>;
+pub type Time32MillisecondBuilder = PrimitiveBuilder<Time32MillisecondType>;
+pub type Time64MicrosecondBuilder = PrimitiveBuilder<Time64MicrosecondType>;
+pub type Time64NanosecondBuilder = PrimitiveBuilder<Time64NanosecondType>;
 
 impl<T: ArrowPrimitiveType> ArrayBuilder for PrimitiveBuilder<T> {
     /// Returns the builder as an non-mutable `Any` reference.
@@ -292,6 +400,15 @@ impl<T: ArrowPrimitiveType> PrimitiveBuilder<T> {
         }
     }
 
+    /// Creates a new primitive array builder, whose buffers are allocated from
+    /// `allocator`
+    pub fn new_in(capacity: usize, allocator: Arc<Allocator>) -> Self {
+        Self {
+            values_builder: BufferBuilder::<T>::new_in(capacity, allocator.clone()),
+            bitmap_builder: BooleanBufferBuilder::new_in(capacity, allocator),
+        }
+    }
+
     /// Returns the capacity of this builder measured in slots of type `T`
     pub fn capacity(&self) -> usize {
         self.values_builder.capacity()
@@ -422,12 +539,7 @@ where
     pub fn finish(&mut self) -> ListArray {
         let len = self.len();
         self.len = 0;
-        let values_arr = self
-            .values_builder
-            .as_any_mut()
-            .downcast_mut::<T>()
-            .unwrap()
-            .finish();
+        let values_arr = self.values_builder.finish();
         let values_data = values_arr.data();
 
         let offset_buffer = self.offsets_builder.finish();
@@ -446,9 +558,116 @@ where
     }
 }
 
+///  Array builder for `FixedSizeListArray`
+pub struct FixedSizeListBuilder<T: ArrayBuilder> {
+    bitmap_builder: BooleanBufferBuilder,
+    values_builder: T,
+    len: usize,
+    list_len: i32,
+}
+
+impl<T: ArrayBuilder> FixedSizeListBuilder<T> {
+    /// Creates a new `FixedSizeListBuilder` from a given values array builder, where
+    /// every list has `length` values
+    pub fn new(values_builder: T, length: i32) -> Self {
+        Self {
+            bitmap_builder: BooleanBufferBuilder::new(values_builder.len()),
+            values_builder,
+            len: 0,
+            list_len: length,
+        }
+    }
+}
+
+impl<T: ArrayBuilder> ArrayBuilder for FixedSizeListBuilder<T>
+where
+    T: 'static,
+{
+    /// Returns the builder as an non-mutable `Any` reference.
+    fn as_any(&self) -> &Any {
+        self
+    }
+
+    /// Returns the builder as an mutable `Any` reference.
+    fn as_any_mut(&mut self) -> &mut Any {
+        self
+    }
+
+    /// Returns the boxed builder as a box of `Any`.
+    fn into_box_any(self: Box<Self>) -> Box<Any> {
+        self
+    }
+
+    /// Returns the number of array slots in the builder
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Builds the array and reset this builder.
+    fn finish(&mut self) -> ArrayRef {
+        Arc::new(self.finish())
+    }
+}
+
+impl<T: ArrayBuilder> FixedSizeListBuilder<T>
+where
+    T: 'static,
+{
+    /// Returns the child array builder as a mutable reference.
+    ///
+    /// This mutable reference can be used to append values into the child array builder,
+    /// but you must call `append` to delimit each distinct list value.
+    pub fn values(&mut self) -> &mut T {
+        &mut self.values_builder
+    }
+
+    /// Returns the number of values of each list
+    pub fn value_length(&self) -> i32 {
+        self.list_len
+    }
+
+    /// Finish the current fixed-length list array slot. Null slots still take up
+    /// `value_length` values, so nulls must be appended to the child builder for them.
+    pub fn append(&mut self, is_valid: bool) -> Result<()> {
+        let expected = (self.len + 1) * self.list_len as usize;
+        if self.values_builder.len() != expected {
+            return Err(ArrowError::ComputeError(format!(
+                "Fixed size list builder expected {} child values, found {}",
+                expected,
+                self.values_builder.len()
+            )));
+        }
+        self.bitmap_builder.append(is_valid)?;
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Builds the `FixedSizeListArray` and reset this builder.
+    pub fn finish(&mut self) -> FixedSizeListArray {
+        let len = self.len();
+        self.len = 0;
+        let values_arr = self.values_builder.finish();
+        let values_data = values_arr.data();
+
+        let null_bit_buffer = self.bitmap_builder.finish();
+        let data = ArrayData::builder(DataType::FixedSizeList(
+            Box::new(values_data.data_type().clone()),
+            self.list_len,
+        ))
+        .len(len)
+        .null_count(len - bit_util::count_set_bits(null_bit_buffer.data()))
+        .add_child_data(values_data)
+        .null_bit_buffer(null_bit_buffer)
+        .build();
+
+        FixedSizeListArray::from(data)
+    }
+}
+
 ///  Array builder for `BinaryArray`
 pub struct BinaryBuilder {
     builder: ListBuilder<UInt8Builder>,
+    data_type: DataType,
 }
 
 impl ArrayBuilder for BinaryBuilder {
@@ -485,6 +704,17 @@ impl BinaryBuilder {
         let values_builder = UInt8Builder::new(capacity);
         Self {
             builder: ListBuilder::new(values_builder),
+            data_type: DataType::Utf8,
+        }
+    }
+
+    /// Creates a new `BinaryBuilder` for an array of arbitrary bytes, which has the
+    /// `Binary` data type rather than `Utf8`
+    pub fn new_binary(capacity: usize) -> Self {
+        let values_builder = UInt8Builder::new(capacity);
+        Self {
+            builder: ListBuilder::new(values_builder),
+            data_type: DataType::Binary,
         }
     }
 
@@ -507,6 +737,13 @@ impl BinaryBuilder {
         Ok(())
     }
 
+    /// Appends a byte slice into the builder as a distinct array element.
+    pub fn append_bytes(&mut self, value: &[u8]) -> Result<()> {
+        self.builder.values().append_slice(value)?;
+        self.builder.append(true)?;
+        Ok(())
+    }
+
     /// Finish the current variable-length list array slot.
     pub fn append(&mut self, is_valid: bool) -> Result<()> {
         self.builder.append(is_valid)
@@ -519,7 +756,7 @@ impl BinaryBuilder {
 
     /// Builds the `BinaryArray` and reset this builder.
     pub fn finish(&mut self) -> BinaryArray {
-        BinaryArray::from(self.builder.finish())
+        BinaryArray::from_list(self.builder.finish(), self.data_type.clone())
     }
 }
 
@@ -604,33 +841,11 @@ impl StructBuilder {
         let fields = schema.fields();
         let mut builders = Vec::with_capacity(fields.len());
         for f in schema.fields() {
-            builders.push(Self::from_field(f.clone(), capacity));
+            builders.push(make_builder(f.data_type(), capacity));
         }
         Self::new(schema.fields, builders)
     }
 
-    fn from_field(f: Field, capacity: usize) -> Box<ArrayBuilder> {
-        match f.data_type() {
-            DataType::Boolean => Box::new(BooleanBuilder::new(capacity)),
-            DataType::Int8 => Box::new(Int8Builder::new(capacity)),
-            DataType::Int16 => Box::new(Int16Builder::new(capacity)),
-            DataType::Int32 => Box::new(Int32Builder::new(capacity)),
-            DataType::Int64 => Box::new(Int64Builder::new(capacity)),
-            DataType::UInt8 => Box::new(UInt8Builder::new(capacity)),
-            DataType::UInt16 => Box::new(UInt16Builder::new(capacity)),
-            DataType::UInt32 => Box::new(UInt32Builder::new(capacity)),
-            DataType::UInt64 => Box::new(UInt64Builder::new(capacity)),
-            DataType::Float32 => Box::new(Float32Builder::new(capacity)),
-            DataType::Float64 => Box::new(Float64Builder::new(capacity)),
-            DataType::Utf8 => Box::new(BinaryBuilder::new(capacity)),
-            DataType::Struct(fields) => {
-                let schema = Schema::new(fields.clone());
-                Box::new(Self::from_schema(schema, capacity))
-            }
-            t @ _ => panic!("Data type {:?} is not currently supported", t),
-        }
-    }
-
     /// Returns a mutable reference to the child field builder at index `i`.
     /// Result will be `None` if the input type `T` provided doesn't match the actual
     /// field builder's type.
@@ -664,10 +879,12 @@ impl StructBuilder {
             child_data.push(arr.data());
         }
 
+        let len = self.len;
+        self.len = 0;
         let null_bit_buffer = self.bitmap_builder.finish();
-        let null_count = self.len - bit_util::count_set_bits(null_bit_buffer.data());
+        let null_count = len - bit_util::count_set_bits(null_bit_buffer.data());
         let mut builder = ArrayData::builder(DataType::Struct(self.fields.clone()))
-            .len(self.len)
+            .len(len)
             .child_data(child_data);
         if null_count > 0 {
             builder = builder
@@ -686,12 +903,162 @@ impl Drop for StructBuilder {
     }
 }
 
+/// Array builder for Union types.
+///
+/// Each value is appended to the child field builder of its type before `append`
+/// records its type id. For a sparse union the other child field builders must be given
+/// a value as well, usually a null, so that every child has a value for every slot.
+pub struct UnionBuilder {
+    fields: Vec<Field>,
+    mode: UnionMode,
+    field_builders: Vec<Box<ArrayBuilder>>,
+    type_ids_builder: Int8BufferBuilder,
+    offsets_builder: Int32BufferBuilder,
+    bitmap_builder: BooleanBufferBuilder,
+    child_lens: Vec<usize>,
+    len: usize,
+}
+
+impl ArrayBuilder for UnionBuilder {
+    /// Returns the number of array slots in the builder
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Builds the array.
+    fn finish(&mut self) -> ArrayRef {
+        Arc::new(self.finish())
+    }
+
+    /// Returns the builder as an non-mutable `Any` reference.
+    fn as_any(&self) -> &Any {
+        self
+    }
+
+    /// Returns the builder as an mutable `Any` reference.
+    fn as_any_mut(&mut self) -> &mut Any {
+        self
+    }
+
+    /// Returns the boxed builder as a box of `Any`.
+    fn into_box_any(self: Box<Self>) -> Box<Any> {
+        self
+    }
+}
+
+impl UnionBuilder {
+    pub fn new(
+        fields: Vec<Field>,
+        builders: Vec<Box<ArrayBuilder>>,
+        mode: UnionMode,
+    ) -> Self {
+        Self {
+            fields,
+            mode,
+            child_lens: vec![0; builders.len()],
+            field_builders: builders,
+            type_ids_builder: Int8BufferBuilder::new(0),
+            offsets_builder: Int32BufferBuilder::new(0),
+            bitmap_builder: BooleanBufferBuilder::new(0),
+            len: 0,
+        }
+    }
+
+    pub fn from_fields(fields: Vec<Field>, mode: UnionMode, capacity: usize) -> Self {
+        let builders = fields
+            .iter()
+            .map(|f| make_builder(f.data_type(), capacity))
+            .collect();
+        Self::new(fields, builders, mode)
+    }
+
+    /// Returns a mutable reference to the child field builder at index `i`, which is
+    /// the builder of the values with type id `i`.
+    /// Result will be `None` if the input type `T` provided doesn't match the actual
+    /// field builder's type.
+    pub fn field_builder<T: ArrayBuilder>(&mut self, i: usize) -> Option<&mut T> {
+        self.field_builders[i].as_any_mut().downcast_mut::<T>()
+    }
+
+    /// Returns the number of fields for the union this builder is building.
+    pub fn num_fields(&self) -> usize {
+        self.field_builders.len()
+    }
+
+    /// Appends an element (either null or non-null) of type id `type_id` to the union,
+    /// after its value has been appended to the child field builder at index `type_id`.
+    pub fn append(&mut self, type_id: i8, is_valid: bool) -> Result<()> {
+        let i = type_id as usize;
+        if type_id < 0 || i >= self.field_builders.len() {
+            return Err(ArrowError::ComputeError(format!(
+                "Type id {} is out of range for a union of {} fields",
+                type_id,
+                self.field_builders.len()
+            )));
+        }
+        match self.mode {
+            UnionMode::Sparse => {
+                if self.field_builders.iter().any(|b| b.len() != self.len + 1) {
+                    return Err(ArrowError::ComputeError(
+                        "Every child of a sparse union needs a value for each slot"
+                            .to_string(),
+                    ));
+                }
+            }
+            UnionMode::Dense => {
+                if self.field_builders[i].len() != self.child_lens[i] + 1 {
+                    return Err(ArrowError::ComputeError(format!(
+                        "Expected a single new value in the child with type id {}",
+                        type_id
+                    )));
+                }
+                self.offsets_builder.append(self.child_lens[i] as i32)?;
+            }
+        }
+        self.child_lens[i] += 1;
+        self.type_ids_builder.append(type_id)?;
+        self.bitmap_builder.append(is_valid)?;
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Builds the `UnionArray` and reset this builder.
+    pub fn finish(&mut self) -> UnionArray {
+        let child_data = self
+            .field_builders
+            .iter_mut()
+            .map(|f| f.finish().data())
+            .collect();
+
+        let len = self.len;
+        self.len = 0;
+        self.child_lens = vec![0; self.field_builders.len()];
+        let null_bit_buffer = self.bitmap_builder.finish();
+        let null_count = len - bit_util::count_set_bits(null_bit_buffer.data());
+        let mut builder =
+            ArrayData::builder(DataType::Union(self.fields.clone(), self.mode.clone()))
+                .len(len)
+                .add_buffer(self.type_ids_builder.finish())
+                .child_data(child_data);
+        if self.mode == UnionMode::Dense {
+            builder = builder.add_buffer(self.offsets_builder.finish());
+        }
+        if null_count > 0 {
+            builder = builder
+                .null_count(null_count)
+                .null_bit_buffer(null_bit_buffer);
+        }
+        UnionArray::from(builder.build())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     use crate::array::Array;
     use crate::bitmap::Bitmap;
+    use crate::memory::PoolAllocator;
 
     #[test]
     fn test_builder_i32_empty() {
@@ -970,6 +1337,32 @@ mod tests {
         assert_eq!(0, builder.len());
     }
 
+    #[test]
+    fn test_primitive_array_builder_allocator() {
+        let allocator = Arc::new(PoolAllocator::new(1024));
+        let mut builder = Int32Builder::new_in(5, allocator.clone());
+        assert_eq!(128, allocator.allocated_bytes());
+        builder.append_slice(&[2, 4, 6]).unwrap();
+        builder.append_null().unwrap();
+        let arr = builder.finish();
+        assert_eq!(4, arr.len());
+        assert_eq!(1, arr.null_count());
+        assert!(builder.values_builder.buffer.allocator().is_some());
+        assert!(builder.bitmap_builder.buffer.allocator().is_some());
+        assert_eq!(128, allocator.allocated_bytes());
+
+        drop(arr);
+        assert_eq!(0, allocator.allocated_bytes());
+        assert_eq!(128, allocator.pooled_bytes());
+
+        // the buffers of the dropped array are reused by the next batch
+        builder.append_slice(&[1, 3, 5, 7, 9]).unwrap();
+        assert_eq!(128, allocator.allocated_bytes());
+        assert_eq!(0, allocator.pooled_bytes());
+        let arr = builder.finish();
+        assert_eq!(&[1, 3, 5, 7, 9], arr.value_slice(0, 5));
+    }
+
     #[test]
     fn test_list_array_builder() {
         let values_builder = Int32Builder::new(10);
@@ -1112,6 +1505,52 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_fixed_size_list_array_builder() {
+        let values_builder = Int32Builder::new(10);
+        let mut builder = FixedSizeListBuilder::new(values_builder, 2);
+
+        //  [[0, 1], null, [2, null]]
+        builder.values().append_slice(&[0, 1]).unwrap();
+        builder.append(true).unwrap();
+        builder.values().append_null().unwrap();
+        builder.values().append_null().unwrap();
+        builder.append(false).unwrap();
+        builder.values().append_value(2).unwrap();
+        builder.values().append_null().unwrap();
+        builder.append(true).unwrap();
+        assert_eq!(3, builder.len());
+        let list_array = builder.finish();
+
+        assert_eq!(
+            &DataType::FixedSizeList(Box::new(DataType::Int32), 2),
+            list_array.data_type()
+        );
+        assert_eq!(DataType::Int32, list_array.value_type());
+        assert_eq!(3, list_array.len());
+        assert_eq!(1, list_array.null_count());
+        assert!(list_array.is_null(1));
+        assert_eq!(4, list_array.value_offset(2));
+        assert_eq!(2, list_array.value_length());
+        assert_eq!(6, list_array.values().len());
+        assert_eq!(3, list_array.values().null_count());
+        assert_eq!(0, builder.len());
+    }
+
+    #[test]
+    fn test_fixed_size_list_array_builder_wrong_length() {
+        let values_builder = Int32Builder::new(10);
+        let mut builder = FixedSizeListBuilder::new(values_builder, 3);
+
+        builder.values().append_slice(&[0, 1]).unwrap();
+        assert!(builder.append(true).is_err());
+        builder.values().append_value(2).unwrap();
+        builder.append(true).unwrap();
+        builder.values().append_slice(&[3, 4, 5, 6]).unwrap();
+        assert!(builder.append(true).is_err());
+        assert_eq!(1, builder.len());
+    }
+
     #[test]
     fn test_binary_array_builder() {
         let mut builder = BinaryBuilder::new(20);
@@ -1188,6 +1627,22 @@ mod tests {
         assert_eq!(5, binary_array.value_length(2));
     }
 
+    #[test]
+    fn test_binary_array_builder_append_bytes() {
+        let mut builder = BinaryBuilder::new_binary(8);
+        builder.append_bytes(&[0xde, 0xad]).unwrap();
+        builder.append_null().unwrap();
+        builder.append_bytes(&[0xbe, 0xef, 0x00]).unwrap();
+
+        let binary_array = builder.finish();
+        assert_eq!(&DataType::Binary, binary_array.data_type());
+        assert_eq!(3, binary_array.len());
+        assert_eq!(1, binary_array.null_count());
+        assert_eq!([0xde, 0xad], binary_array.value(0));
+        assert!(binary_array.is_null(1));
+        assert_eq!([0xbe, 0xef, 0x00], binary_array.value(2));
+    }
+
     #[test]
     fn test_struct_array_builder() {
         let string_builder = BinaryBuilder::new(4);
@@ -1338,11 +1793,11 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "Data type List(Int64) is not currently supported")]
+    #[should_panic(expected = "Data type Float16 is not currently supported")]
     fn test_struct_array_builder_from_schema_unsupported_type() {
         let mut fields = Vec::new();
         fields.push(Field::new("f1", DataType::Int16, false));
-        let list_type = DataType::List(Box::new(DataType::Int64));
+        let list_type = DataType::List(Box::new(DataType::Float16));
         fields.push(Field::new("f2", list_type, false));
 
         let _ = StructBuilder::from_schema(Schema::new(fields), 5);
@@ -1361,4 +1816,299 @@ mod tests {
         assert!(builder.field_builder::<BinaryBuilder>(0).is_none());
     }
 
+    #[test]
+    fn test_struct_array_builder_append_finish() {
+        let fields = vec![Field::new("f1", DataType::Int32, true)];
+        let mut builder = StructBuilder::from_schema(Schema::new(fields), 4);
+        for v in &[Some(1), None, Some(3)] {
+            builder
+                .field_builder::<Int32Builder>(0)
+                .unwrap()
+                .append_option(*v)
+                .unwrap();
+            builder.append(v.is_some()).unwrap();
+        }
+        let arr = builder.finish();
+        assert_eq!(3, arr.len());
+        assert_eq!(1, arr.null_count());
+        assert_eq!(0, builder.len());
+
+        builder
+            .field_builder::<Int32Builder>(0)
+            .unwrap()
+            .append_value(4)
+            .unwrap();
+        builder.append(true).unwrap();
+        let arr = builder.finish();
+        assert_eq!(1, arr.len());
+        assert_eq!(0, arr.null_count());
+    }
+
+    #[test]
+    fn test_make_builder_list() {
+        let list_type = DataType::List(Box::new(DataType::Int32));
+        let mut builder = make_builder(&list_type, 8);
+        {
+            let list_builder = builder
+                .as_any_mut()
+                .downcast_mut::<ListBuilder<Box<ArrayBuilder>>>()
+                .unwrap();
+            // [[0, 1], null, [2]]
+            {
+                let values = list_builder
+                    .values()
+                    .as_any_mut()
+                    .downcast_mut::<Int32Builder>()
+                    .unwrap();
+                values.append_slice(&[0, 1]).unwrap();
+            }
+            list_builder.append(true).unwrap();
+            list_builder.append(false).unwrap();
+            list_builder
+                .values()
+                .as_any_mut()
+                .downcast_mut::<Int32Builder>()
+                .unwrap()
+                .append_value(2)
+                .unwrap();
+            list_builder.append(true).unwrap();
+        }
+        assert_eq!(3, builder.len());
+
+        let arr = builder.finish();
+        assert_eq!(&list_type, arr.data_type());
+        let list = arr.as_any().downcast_ref::<ListArray>().unwrap();
+        assert_eq!(3, list.len());
+        assert_eq!(1, list.null_count());
+        assert!(list.is_null(1));
+        assert_eq!(2, list.value_length(0));
+        assert_eq!(0, list.value_length(1));
+        assert_eq!(1, list.value_length(2));
+        let values = list.values();
+        let values = values.as_any().downcast_ref::<Int32Array>().unwrap();
+        assert_eq!(&[0, 1, 2], values.value_slice(0, 3));
+    }
+
+    #[test]
+    fn test_make_builder_fixed_size_list() {
+        let list_type = DataType::FixedSizeList(Box::new(DataType::Float32), 3);
+        let mut builder = make_builder(&list_type, 2);
+        {
+            let list_builder = builder
+                .as_any_mut()
+                .downcast_mut::<FixedSizeListBuilder<Box<ArrayBuilder>>>()
+                .unwrap();
+            list_builder
+                .values()
+                .as_any_mut()
+                .downcast_mut::<Float32Builder>()
+                .unwrap()
+                .append_slice(&[1.0, 2.0, 3.0])
+                .unwrap();
+            list_builder.append(true).unwrap();
+        }
+
+        let arr = builder.finish();
+        assert_eq!(&list_type, arr.data_type());
+        let list = arr.as_any().downcast_ref::<FixedSizeListArray>().unwrap();
+        assert_eq!(1, list.len());
+        assert_eq!(3, list.values().len());
+    }
+
+    #[test]
+    fn test_struct_array_builder_from_schema_nested_list() {
+        let item_fields = vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("tag", DataType::Utf8, true),
+        ];
+        let fields = vec![
+            Field::new("name", DataType::Utf8, false),
+            Field::new(
+                "items",
+                DataType::List(Box::new(DataType::Struct(item_fields.clone()))),
+                true,
+            ),
+        ];
+        let mut builder = StructBuilder::from_schema(Schema::new(fields.clone()), 4);
+
+        // {name: "a", items: [{id: 1, tag: "x"}, {id: 2, tag: null}]}
+        builder
+            .field_builder::<BinaryBuilder>(0)
+            .unwrap()
+            .append_string("a")
+            .unwrap();
+        {
+            let items = builder
+                .field_builder::<ListBuilder<Box<ArrayBuilder>>>(1)
+                .unwrap();
+            {
+                let item = items
+                    .values()
+                    .as_any_mut()
+                    .downcast_mut::<StructBuilder>()
+                    .unwrap();
+                for (id, tag) in &[(1, Some("x")), (2, None)] {
+                    item.field_builder::<Int64Builder>(0)
+                        .unwrap()
+                        .append_value(*id)
+                        .unwrap();
+                    let tags = item.field_builder::<BinaryBuilder>(1).unwrap();
+                    match tag {
+                        Some(tag) => tags.append_string(tag).unwrap(),
+                        None => tags.append_null().unwrap(),
+                    }
+                    item.append(true).unwrap();
+                }
+            }
+            items.append(true).unwrap();
+        }
+        builder.append(true).unwrap();
+
+        let arr = builder.finish();
+        assert_eq!(&DataType::Struct(fields), arr.data_type());
+        assert_eq!(1, arr.len());
+        let items = arr.column(1);
+        let items = items.as_any().downcast_ref::<ListArray>().unwrap();
+        assert_eq!(1, items.len());
+        assert_eq!(2, items.value_length(0));
+        assert_eq!(DataType::Struct(item_fields), items.value_type());
+        let item = items.values();
+        let item = item.as_any().downcast_ref::<StructArray>().unwrap();
+        assert_eq!(2, item.len());
+        let tags = item.column(1);
+        let tags = tags.as_any().downcast_ref::<BinaryArray>().unwrap();
+        assert_eq!("x", tags.get_string(0));
+        assert!(tags.is_null(1));
+    }
+
+    #[test]
+    fn test_dense_union_array_builder() {
+        let fields = vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", DataType::Utf8, true),
+        ];
+        let mut builder = UnionBuilder::from_fields(fields.clone(), UnionMode::Dense, 4);
+
+        // [1, "x", null, 3]
+        builder
+            .field_builder::<Int32Builder>(0)
+            .unwrap()
+            .append_value(1)
+            .unwrap();
+        builder.append(0, true).unwrap();
+        builder
+            .field_builder::<BinaryBuilder>(1)
+            .unwrap()
+            .append_string("x")
+            .unwrap();
+        builder.append(1, true).unwrap();
+        builder
+            .field_builder::<BinaryBuilder>(1)
+            .unwrap()
+            .append_null()
+            .unwrap();
+        builder.append(1, false).unwrap();
+        builder
+            .field_builder::<Int32Builder>(0)
+            .unwrap()
+            .append_value(3)
+            .unwrap();
+        builder.append(0, true).unwrap();
+        assert_eq!(4, builder.len());
+
+        // a type id out of range, and a type id without a new child value
+        assert!(builder.append(2, true).is_err());
+        assert!(builder.append(0, true).is_err());
+
+        let arr = builder.finish();
+        assert_eq!(0, builder.len());
+        assert_eq!(&DataType::Union(fields, UnionMode::Dense), arr.data_type());
+        assert_eq!(UnionMode::Dense, arr.mode());
+        assert_eq!(4, arr.len());
+        assert_eq!(1, arr.null_count());
+        assert!(arr.is_null(2));
+        assert_eq!(
+            vec![0, 1, 1, 0],
+            (0..4).map(|i| arr.type_id(i)).collect::<Vec<i8>>()
+        );
+        assert_eq!(
+            vec![0, 0, 1, 1],
+            (0..4).map(|i| arr.value_offset(i)).collect::<Vec<i32>>()
+        );
+        let a = arr.child(0).as_any().downcast_ref::<Int32Array>().unwrap();
+        assert_eq!(&[1, 3], a.value_slice(0, 2));
+        let b = arr.child(1).as_any().downcast_ref::<BinaryArray>().unwrap();
+        assert_eq!(2, b.len());
+        assert_eq!("x", b.get_string(0));
+    }
+
+    #[test]
+    fn test_sparse_union_array_builder() {
+        let fields = vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", DataType::Float64, true),
+        ];
+        let mut builder = UnionBuilder::from_fields(fields, UnionMode::Sparse, 2);
+
+        // [1, 2.5]
+        builder
+            .field_builder::<Int32Builder>(0)
+            .unwrap()
+            .append_value(1)
+            .unwrap();
+        // the other child has no value yet
+        assert!(builder.append(0, true).is_err());
+        builder
+            .field_builder::<Float64Builder>(1)
+            .unwrap()
+            .append_null()
+            .unwrap();
+        builder.append(0, true).unwrap();
+        builder
+            .field_builder::<Int32Builder>(0)
+            .unwrap()
+            .append_null()
+            .unwrap();
+        builder
+            .field_builder::<Float64Builder>(1)
+            .unwrap()
+            .append_value(2.5)
+            .unwrap();
+        builder.append(1, true).unwrap();
+
+        let arr = builder.finish();
+        assert_eq!(UnionMode::Sparse, arr.mode());
+        assert_eq!(2, arr.len());
+        assert_eq!(0, arr.null_count());
+        assert_eq!(1, arr.type_id(1));
+        assert_eq!(1, arr.value_offset(1));
+        let b = arr
+            .child(1)
+            .as_any()
+            .downcast_ref::<Float64Array>()
+            .unwrap();
+        assert_eq!(2.5, b.value(1));
+    }
+
+    #[test]
+    fn test_make_builder_union() {
+        let union_type = DataType::Union(
+            vec![Field::new("a", DataType::Boolean, true)],
+            UnionMode::Dense,
+        );
+        let mut builder = make_builder(&union_type, 2);
+        {
+            let union_builder =
+                builder.as_any_mut().downcast_mut::<UnionBuilder>().unwrap();
+            union_builder
+                .field_builder::<BooleanBuilder>(0)
+                .unwrap()
+                .append_value(true)
+                .unwrap();
+            union_builder.append(0, true).unwrap();
+        }
+        let arr = builder.finish();
+        assert_eq!(&union_type, arr.data_type());
+        assert_eq!(1, arr.len());
+    }
 }
@@ -18,78 +18,119 @@
 //! Defines primitive computations on arrays, e.g. addition, equality, boolean logic.
 
 use std::ops::Add;
+use std::sync::Arc;
 
-use crate::array::{Array, BooleanArray, PrimitiveArray};
-use crate::datatypes::ArrowNumericType;
+use crate::array::*;
+use crate::array_data::{ArrayData, ArrayDataRef};
+use crate::buffer::{Buffer, MutableBuffer};
+use crate::datatypes::*;
 use crate::error::{ArrowError, Result};
+use crate::util::bit_util;
 
-/// Returns the minimum value in the array, according to the natural order.
+/// Returns the minimum value in the array, according to the natural order, ignoring
+/// null values.
+///
+/// Returns `None` if the array is empty or only contains null values.
 pub fn min<T>(array: &PrimitiveArray<T>) -> Option<T::Native>
 where
     T: ArrowNumericType,
 {
-    min_max_helper(array, |a, b| a < b)
+    fold_valid(array, |a, b| if b < a { b } else { a })
 }
 
-/// Returns the maximum value in the array, according to the natural order.
+/// Returns the maximum value in the array, according to the natural order, ignoring
+/// null values.
+///
+/// Returns `None` if the array is empty or only contains null values.
 pub fn max<T>(array: &PrimitiveArray<T>) -> Option<T::Native>
 where
     T: ArrowNumericType,
 {
-    min_max_helper(array, |a, b| a > b)
+    fold_valid(array, |a, b| if b > a { b } else { a })
 }
 
-/// Helper function to perform min/max lambda function on values from a numeric array.
-fn min_max_helper<T, F>(array: &PrimitiveArray<T>, cmp: F) -> Option<T::Native>
+/// Returns the sum of values in the array, ignoring null values.
+///
+/// Returns `None` if the array is empty or only contains null values.
+pub fn sum<T>(array: &PrimitiveArray<T>) -> Option<T::Native>
 where
     T: ArrowNumericType,
-    F: Fn(T::Native, T::Native) -> bool,
+    T::Native: Add<Output = T::Native>,
 {
-    let mut n: Option<T::Native> = None;
-    let data = array.data();
-    for i in 0..data.len() {
-        if data.is_null(i) {
-            continue;
-        }
-        let m = array.value(i);
-        match n {
-            None => n = Some(m),
-            Some(nn) => {
-                if cmp(m, nn) {
-                    n = Some(m)
-                }
-            }
-        }
-    }
-    n
+    fold_valid(array, |a, b| a + b)
 }
 
-/// Returns the sum of values in the array.
-///
-/// Returns `None` if the array is empty or only contains null values.
-pub fn sum<T>(array: &PrimitiveArray<T>) -> Option<T::Native>
+/// Returns the number of non-null values in the array.
+pub fn count(array: &Array) -> usize {
+    array.len() - array.null_count()
+}
+
+/// Helper function to combine the non-null values of an array with `op`, which must be
+/// associative. Values are combined in chunks of eight, where the validity of a whole
+/// chunk is read from one byte of the validity bitmap.
+fn fold_valid<T, F>(array: &PrimitiveArray<T>, op: F) -> Option<T::Native>
 where
     T: ArrowNumericType,
-    T::Native: Add<Output = T::Native>,
+    F: Fn(T::Native, T::Native) -> T::Native,
 {
-    let mut n: T::Native = T::default_value();
-    // iteratively track whether all values are null (or array is empty)
-    let mut all_nulls = true;
+    let values = array.value_slice(0, array.len());
+    let fold_chunk = |chunk: &[T::Native]| {
+        // the two halves of the chunk are independent, to allow instruction-level
+        // parallelism
+        let a = op(op(chunk[0], chunk[1]), op(chunk[2], chunk[3]));
+        let b = op(op(chunk[4], chunk[5]), op(chunk[6], chunk[7]));
+        op(a, b)
+    };
+    let combine = |acc: Option<T::Native>, v: T::Native| match acc {
+        Some(acc) => Some(op(acc, v)),
+        None => Some(v),
+    };
+
     let data = array.data();
-    for i in 0..data.len() {
-        if data.is_null(i) {
-            continue;
+    let offset = data.offset();
+    match data.null_bitmap() {
+        Some(bitmap) if array.null_count() > 0 => {
+            let mut acc = None;
+            if offset % 8 == 0 {
+                let validity = &bitmap.bits.data()[offset / 8..];
+                let mut chunks = values.chunks_exact(8);
+                for (chunk, &valid) in (&mut chunks).zip(validity) {
+                    match valid {
+                        0xFF => acc = combine(acc, fold_chunk(chunk)),
+                        0 => {}
+                        _ => {
+                            for (i, v) in chunk.iter().enumerate() {
+                                if valid & (1 << i) != 0 {
+                                    acc = combine(acc, *v);
+                                }
+                            }
+                        }
+                    }
+                }
+                let start = values.len() - chunks.remainder().len();
+                for i in start..values.len() {
+                    if data.is_valid(offset + i) {
+                        acc = combine(acc, values[i]);
+                    }
+                }
+            } else {
+                for (i, v) in values.iter().enumerate() {
+                    if data.is_valid(offset + i) {
+                        acc = combine(acc, *v);
+                    }
+                }
+            }
+            acc
         }
-        if all_nulls {
-            all_nulls = false;
+        _ => {
+            let mut chunks = values.chunks_exact(8);
+            let acc =
+                (&mut chunks).fold(None, |acc, chunk| combine(acc, fold_chunk(chunk)));
+            chunks
+                .remainder()
+                .iter()
+                .fold(acc, |acc, v| combine(acc, *v))
         }
-        let m = array.value(i);
-        n = n + m;
-    }
-    if all_nulls {
-        None
-    } else {
-        Some(n)
     }
 }
 
@@ -171,6 +212,695 @@ where
     })
 }
 
+/// Compare the values of two arrays with `op`. Unlike `eq`, `lt` and the other
+/// comparisons above, the result is null where either value is null, as in SQL.
+pub fn compare_op<T, F>(
+    left: &PrimitiveArray<T>,
+    right: &PrimitiveArray<T>,
+    op: F,
+) -> Result<BooleanArray>
+where
+    T: ArrowNumericType,
+    F: Fn(T::Native, T::Native) -> bool,
+{
+    if left.len() != right.len() {
+        return Err(ArrowError::ComputeError(
+            "Cannot perform comparison operation on arrays of different length"
+                .to_string(),
+        ));
+    }
+    let has_nulls = left.null_count() > 0 || right.null_count() > 0;
+    let left_values = left.value_slice(0, left.len());
+    let right_values = right.value_slice(0, right.len());
+    Ok(boolean_array(
+        left.len(),
+        has_nulls,
+        |i| left.is_valid(i) && right.is_valid(i),
+        |i| op(left_values[i], right_values[i]),
+    ))
+}
+
+/// Compare the values of an array with a scalar value using `op`. The result is null
+/// where the array value is null.
+pub fn compare_op_scalar<T, F>(
+    left: &PrimitiveArray<T>,
+    right: T::Native,
+    op: F,
+) -> Result<BooleanArray>
+where
+    T: ArrowNumericType,
+    F: Fn(T::Native, T::Native) -> bool,
+{
+    let values = left.value_slice(0, left.len());
+    Ok(boolean_array(
+        left.len(),
+        left.null_count() > 0,
+        |i| left.is_valid(i),
+        |i| op(values[i], right),
+    ))
+}
+
+/// Perform `left == right` operation on an array and a scalar value.
+pub fn eq_scalar<T>(left: &PrimitiveArray<T>, right: T::Native) -> Result<BooleanArray>
+where
+    T: ArrowNumericType,
+{
+    compare_op_scalar(left, right, |a, b| a == b)
+}
+
+/// Perform `left != right` operation on an array and a scalar value.
+pub fn neq_scalar<T>(left: &PrimitiveArray<T>, right: T::Native) -> Result<BooleanArray>
+where
+    T: ArrowNumericType,
+{
+    compare_op_scalar(left, right, |a, b| a != b)
+}
+
+/// Perform `left < right` operation on an array and a scalar value.
+pub fn lt_scalar<T>(left: &PrimitiveArray<T>, right: T::Native) -> Result<BooleanArray>
+where
+    T: ArrowNumericType,
+{
+    compare_op_scalar(left, right, |a, b| a < b)
+}
+
+/// Perform `left <= right` operation on an array and a scalar value.
+pub fn lt_eq_scalar<T>(left: &PrimitiveArray<T>, right: T::Native) -> Result<BooleanArray>
+where
+    T: ArrowNumericType,
+{
+    compare_op_scalar(left, right, |a, b| a <= b)
+}
+
+/// Perform `left > right` operation on an array and a scalar value.
+pub fn gt_scalar<T>(left: &PrimitiveArray<T>, right: T::Native) -> Result<BooleanArray>
+where
+    T: ArrowNumericType,
+{
+    compare_op_scalar(left, right, |a, b| a > b)
+}
+
+/// Perform `left >= right` operation on an array and a scalar value.
+pub fn gt_eq_scalar<T>(left: &PrimitiveArray<T>, right: T::Native) -> Result<BooleanArray>
+where
+    T: ArrowNumericType,
+{
+    compare_op_scalar(left, right, |a, b| a >= b)
+}
+
+/// Helper function to build a boolean array by setting the value and validity bits
+/// directly, rather than appending each value to a builder.
+fn boolean_array<V, F>(len: usize, has_nulls: bool, is_valid: V, value: F) -> BooleanArray
+where
+    V: Fn(usize) -> bool,
+    F: Fn(usize) -> bool,
+{
+    let num_bytes = bit_util::ceil(len, 8);
+    let mut values = MutableBuffer::new(num_bytes).with_bitset(num_bytes, false);
+    let null_bit_buffer = if has_nulls {
+        let mut validity = MutableBuffer::new(num_bytes).with_bitset(num_bytes, false);
+        {
+            let value_bits = values.data_mut();
+            let validity_bits = validity.data_mut();
+            for i in 0..len {
+                if is_valid(i) {
+                    bit_util::set_bit(validity_bits, i);
+                    if value(i) {
+                        bit_util::set_bit(value_bits, i);
+                    }
+                }
+            }
+        }
+        Some(validity.freeze())
+    } else {
+        let value_bits = values.data_mut();
+        for i in 0..len {
+            if value(i) {
+                bit_util::set_bit(value_bits, i);
+            }
+        }
+        None
+    };
+    let data = ArrayData::new(
+        DataType::Boolean,
+        len,
+        None,
+        null_bit_buffer,
+        0,
+        vec![values.freeze()],
+        vec![],
+    );
+    BooleanArray::from(Arc::new(data))
+}
+
+/// Filter an array with a boolean mask, returning the values for which the mask is
+/// true. Null values in the mask are treated as false, so that the rows for which a
+/// predicate evaluated to null are not selected.
+pub fn filter(array: &ArrayRef, filter: &BooleanArray) -> Result<ArrayRef> {
+    if array.len() != filter.len() {
+        return Err(ArrowError::ComputeError(
+            "Cannot filter an array with a mask of different length".to_string(),
+        ));
+    }
+    let positions: Vec<Position> = (0..filter.len())
+        .filter(|&i| filter.is_valid(i) && filter.value(i))
+        .map(|i| Some((0, i)))
+        .collect();
+    select(&[array.clone()], &positions)
+}
+
+/// Take the values of an array at the given indices. The result has a value for each
+/// index, which is null where the index is null.
+pub fn take(values: &ArrayRef, indices: &UInt32Array) -> Result<ArrayRef> {
+    let mut positions = Vec::with_capacity(indices.len());
+    for i in 0..indices.len() {
+        if indices.is_null(i) {
+            positions.push(None);
+        } else {
+            let index = indices.value(i) as usize;
+            if index >= values.len() {
+                return Err(ArrowError::ComputeError(format!(
+                    "Index {} is out of bounds for an array of length {}",
+                    index,
+                    values.len()
+                )));
+            }
+            positions.push(Some((0, index)));
+        }
+    }
+    select(&[values.clone()], &positions)
+}
+
+/// Concatenate arrays of the same type into a single array, copying their values
+pub fn concat(arrays: &[ArrayRef]) -> Result<ArrayRef> {
+    let data_type = match arrays.first() {
+        Some(array) => array.data_type(),
+        None => {
+            return Err(ArrowError::ComputeError(
+                "Cannot concatenate an empty list of arrays".to_string(),
+            ));
+        }
+    };
+    if let Some(array) = arrays.iter().find(|a| a.data_type() != data_type) {
+        return Err(ArrowError::ComputeError(format!(
+            "Cannot concatenate arrays of type {:?} and {:?}",
+            data_type,
+            array.data_type()
+        )));
+    }
+    let positions: Vec<Position> = arrays
+        .iter()
+        .enumerate()
+        .flat_map(|(a, array)| (0..array.len()).map(move |j| Some((a, j))))
+        .collect();
+    select(arrays, &positions)
+}
+
+/// Returns the dictionary of values of a dictionary array, and the index in the
+/// dictionary of the value at each index of the array, or `None` for null values
+pub fn dictionary_indices(array: &ArrayRef) -> Result<(ArrayRef, Vec<Option<usize>>)> {
+    match array.data_type() {
+        DataType::Dictionary(key_type, _) => match **key_type {
+            DataType::Int8 => dictionary_indices_of::<Int8Type>(array),
+            DataType::Int16 => dictionary_indices_of::<Int16Type>(array),
+            DataType::Int32 => dictionary_indices_of::<Int32Type>(array),
+            DataType::Int64 => dictionary_indices_of::<Int64Type>(array),
+            DataType::UInt8 => dictionary_indices_of::<UInt8Type>(array),
+            DataType::UInt16 => dictionary_indices_of::<UInt16Type>(array),
+            DataType::UInt32 => dictionary_indices_of::<UInt32Type>(array),
+            DataType::UInt64 => dictionary_indices_of::<UInt64Type>(array),
+            ref other => Err(ArrowError::ComputeError(format!(
+                "Unsupported dictionary key type {:?}",
+                other
+            ))),
+        },
+        other => Err(ArrowError::ComputeError(format!(
+            "Expected a dictionary array but got an array of type {:?}",
+            other
+        ))),
+    }
+}
+
+fn dictionary_indices_of<K>(array: &ArrayRef) -> Result<(ArrayRef, Vec<Option<usize>>)>
+where
+    K: ArrowDictionaryKeyType,
+{
+    let dictionary = array.as_any().downcast_ref::<DictionaryArray<K>>().unwrap();
+    let values = dictionary.values();
+    let mut indices = Vec::with_capacity(dictionary.len());
+    for i in 0..dictionary.len() {
+        if dictionary.is_null(i) {
+            indices.push(None);
+            continue;
+        }
+        let index = dictionary.value_index(i);
+        if index >= values.len() {
+            return Err(ArrowError::ComputeError(format!(
+                "Dictionary key {:?} is out of bounds for {} values",
+                dictionary.key(i),
+                values.len()
+            )));
+        }
+        indices.push(Some(index));
+    }
+    Ok((values, indices))
+}
+
+/// Create a dictionary array with keys of type `key_type` from the index of each value
+/// in `values`, or `None` for null values. This is the inverse of `dictionary_indices`.
+pub fn dictionary_from_indices(
+    key_type: &DataType,
+    indices: &[Option<usize>],
+    values: ArrayRef,
+) -> Result<ArrayRef> {
+    match key_type {
+        DataType::Int8 => dictionary_from_indices_of::<Int8Type>(indices, values),
+        DataType::Int16 => dictionary_from_indices_of::<Int16Type>(indices, values),
+        DataType::Int32 => dictionary_from_indices_of::<Int32Type>(indices, values),
+        DataType::Int64 => dictionary_from_indices_of::<Int64Type>(indices, values),
+        DataType::UInt8 => dictionary_from_indices_of::<UInt8Type>(indices, values),
+        DataType::UInt16 => dictionary_from_indices_of::<UInt16Type>(indices, values),
+        DataType::UInt32 => dictionary_from_indices_of::<UInt32Type>(indices, values),
+        DataType::UInt64 => dictionary_from_indices_of::<UInt64Type>(indices, values),
+        other => Err(ArrowError::ComputeError(format!(
+            "Unsupported dictionary key type {:?}",
+            other
+        ))),
+    }
+}
+
+fn dictionary_from_indices_of<K>(
+    indices: &[Option<usize>],
+    values: ArrayRef,
+) -> Result<ArrayRef>
+where
+    K: ArrowDictionaryKeyType,
+{
+    let mut keys = PrimitiveArray::<K>::builder(indices.len());
+    for index in indices {
+        match index {
+            Some(index) => match K::from_index(*index) {
+                Some(key) => keys.append_value(key)?,
+                None => {
+                    return Err(ArrowError::ComputeError(format!(
+                        "Index {} is too large for a dictionary key of type {:?}",
+                        index,
+                        K::get_data_type()
+                    )));
+                }
+            },
+            None => keys.append_null()?,
+        }
+    }
+    let array = DictionaryArray::try_new(&keys.finish(), values)?;
+    Ok(Arc::new(array))
+}
+
+/// Unpack a dictionary array into an array of the type of its values, copying the
+/// dictionary value of each key
+pub fn unpack_dictionary(array: &ArrayRef) -> Result<ArrayRef> {
+    let (values, indices) = dictionary_indices(array)?;
+    let positions: Vec<Position> = indices
+        .into_iter()
+        .map(|index| index.map(|index| (0, index)))
+        .collect();
+    select(&[values], &positions)
+}
+
+/// The position of a value to select, as the index of the array that it is selected
+/// from and its index in that array, or `None` for a null value
+type Position = Option<(usize, usize)>;
+
+/// Downcast arrays that are known to have the same type
+fn downcast<T: 'static>(arrays: &[ArrayRef]) -> Vec<&T> {
+    arrays
+        .iter()
+        .map(|array| array.as_any().downcast_ref::<T>().unwrap())
+        .collect()
+}
+
+/// Create an array of the values of `arrays`, which all have the same type, at the
+/// given positions, copying the selected values and validity bits directly into the
+/// buffers of the new array.
+fn select(arrays: &[ArrayRef], positions: &[Position]) -> Result<ArrayRef> {
+    let data_type = arrays[0].data_type().clone();
+    match data_type {
+        DataType::Null => Ok(Arc::new(NullArray::new(positions.len()))),
+        DataType::Boolean => {
+            let booleans = downcast::<BooleanArray>(arrays);
+            let num_bytes = bit_util::ceil(positions.len(), 8);
+            let mut values = MutableBuffer::new(num_bytes).with_bitset(num_bytes, false);
+            {
+                let value_bits = values.data_mut();
+                for (i, position) in positions.iter().enumerate() {
+                    match position {
+                        Some((a, j)) if booleans[*a].value(*j) => {
+                            bit_util::set_bit(value_bits, i)
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            let data = ArrayData::new(
+                DataType::Boolean,
+                positions.len(),
+                None,
+                select_null_bits(arrays, positions),
+                0,
+                vec![values.freeze()],
+                vec![],
+            );
+            Ok(Arc::new(BooleanArray::from(Arc::new(data))))
+        }
+        DataType::Int8 => select_primitive::<Int8Type>(arrays, positions),
+        DataType::Int16 => select_primitive::<Int16Type>(arrays, positions),
+        DataType::Int32 => select_primitive::<Int32Type>(arrays, positions),
+        DataType::Int64 => select_primitive::<Int64Type>(arrays, positions),
+        DataType::UInt8 => select_primitive::<UInt8Type>(arrays, positions),
+        DataType::UInt16 => select_primitive::<UInt16Type>(arrays, positions),
+        DataType::UInt32 => select_primitive::<UInt32Type>(arrays, positions),
+        DataType::UInt64 => select_primitive::<UInt64Type>(arrays, positions),
+        DataType::Float32 => select_primitive::<Float32Type>(arrays, positions),
+        DataType::Float64 => select_primitive::<Float64Type>(arrays, positions),
+        DataType::Date(DateUnit::Day) => {
+            select_primitive::<Date32Type>(arrays, positions)
+        }
+        DataType::Date(DateUnit::Millisecond) => {
+            select_primitive::<Date64Type>(arrays, positions)
+        }
+        DataType::Time32(TimeUnit::Second) => {
+            select_primitive::<Time32SecondType>(arrays, positions)
+        }
+        DataType::Time32(TimeUnit::Millisecond) => {
+            select_primitive::<Time32MillisecondType>(arrays, positions)
+        }
+        DataType::Time64(TimeUnit::Microsecond) => {
+            select_primitive::<Time64MicrosecondType>(arrays, positions)
+        }
+        DataType::Time64(TimeUnit::Nanosecond) => {
+            select_primitive::<Time64NanosecondType>(arrays, positions)
+        }
+        DataType::Timestamp(TimeUnit::Second, _) => {
+            select_primitive::<TimestampSecondType>(arrays, positions)
+        }
+        DataType::Timestamp(TimeUnit::Millisecond, _) => {
+            select_primitive::<TimestampMillisecondType>(arrays, positions)
+        }
+        DataType::Timestamp(TimeUnit::Microsecond, _) => {
+            select_primitive::<TimestampMicrosecondType>(arrays, positions)
+        }
+        DataType::Timestamp(TimeUnit::Nanosecond, _) => {
+            select_primitive::<TimestampNanosecondType>(arrays, positions)
+        }
+        DataType::Interval(IntervalUnit::YearMonth) => {
+            select_primitive::<IntervalYearMonthType>(arrays, positions)
+        }
+        DataType::Interval(IntervalUnit::DayTime) => {
+            select_primitive::<IntervalDayTimeType>(arrays, positions)
+        }
+        DataType::Utf8 | DataType::Binary => {
+            let binaries = downcast::<BinaryArray>(arrays);
+            let mut offsets = Vec::with_capacity(positions.len() + 1);
+            let mut values = vec![];
+            offsets.push(0i32);
+            for position in positions {
+                match position {
+                    Some((a, j)) if binaries[*a].is_valid(*j) => {
+                        values.extend_from_slice(binaries[*a].value(*j))
+                    }
+                    _ => {}
+                }
+                offsets.push(values.len() as i32);
+            }
+            let data = ArrayData::new(
+                data_type,
+                positions.len(),
+                None,
+                select_null_bits(arrays, positions),
+                0,
+                vec![
+                    Buffer::from(offsets.to_byte_slice()),
+                    Buffer::from(&values[..]),
+                ],
+                vec![],
+            );
+            Ok(Arc::new(BinaryArray::from(Arc::new(data))))
+        }
+        DataType::List(_) => {
+            let lists = downcast::<ListArray>(arrays);
+            let values: Vec<ArrayRef> = lists.iter().map(|list| list.values()).collect();
+            // the positions of the elements of the selected lists in the list values
+            let mut offsets = Vec::with_capacity(positions.len() + 1);
+            let mut value_positions = vec![];
+            offsets.push(0i32);
+            for position in positions {
+                match position {
+                    Some((a, j)) if lists[*a].is_valid(*j) => {
+                        let start = lists[*a].value_offset(*j) as usize;
+                        let end = start + lists[*a].value_length(*j) as usize;
+                        value_positions.extend((start..end).map(|k| Some((*a, k))));
+                    }
+                    _ => {}
+                }
+                offsets.push(value_positions.len() as i32);
+            }
+            let values = select(&values, &value_positions)?;
+            let data = ArrayData::new(
+                data_type,
+                positions.len(),
+                None,
+                select_null_bits(arrays, positions),
+                0,
+                vec![Buffer::from(offsets.to_byte_slice())],
+                vec![values.data()],
+            );
+            Ok(Arc::new(ListArray::from(Arc::new(data))))
+        }
+        DataType::FixedSizeList(_, _) => {
+            let lists = downcast::<FixedSizeListArray>(arrays);
+            let values: Vec<ArrayRef> = lists.iter().map(|list| list.values()).collect();
+            // every selected list takes up the same number of values, which are null
+            // for null positions
+            let length = lists[0].value_length() as usize;
+            let mut value_positions = Vec::with_capacity(positions.len() * length);
+            for position in positions {
+                match position {
+                    Some((a, j)) => {
+                        let start = lists[*a].value_offset(*j) as usize;
+                        value_positions
+                            .extend((start..start + length).map(|k| Some((*a, k))));
+                    }
+                    None => value_positions.extend((0..length).map(|_| None)),
+                }
+            }
+            let values = select(&values, &value_positions)?;
+            let data = ArrayData::new(
+                data_type,
+                positions.len(),
+                None,
+                select_null_bits(arrays, positions),
+                0,
+                vec![],
+                vec![values.data()],
+            );
+            Ok(Arc::new(FixedSizeListArray::from(Arc::new(data))))
+        }
+        DataType::Struct(_) => {
+            let structs = downcast::<StructArray>(arrays);
+            let fields = (0..arrays[0].data().child_data().len())
+                .map(|i| {
+                    let columns: Vec<ArrayRef> =
+                        structs.iter().map(|s| s.column(i).clone()).collect();
+                    select(&columns, positions).map(|field| field.data())
+                })
+                .collect::<Result<Vec<ArrayDataRef>>>()?;
+            let data = ArrayData::new(
+                data_type,
+                positions.len(),
+                None,
+                select_null_bits(arrays, positions),
+                0,
+                vec![],
+                fields,
+            );
+            Ok(Arc::new(StructArray::from(Arc::new(data))))
+        }
+        DataType::Union(_, ref mode) => {
+            let unions = downcast::<UnionArray>(arrays);
+            let num_children = unions[0].num_children();
+            let children_of = |i: usize| -> Vec<ArrayRef> {
+                unions.iter().map(|u| u.child(i as i8).clone()).collect()
+            };
+            // null positions take a null value of the first child
+            let type_ids: Vec<i8> = positions
+                .iter()
+                .map(|position| position.map_or(0, |(a, j)| unions[a].type_id(j)))
+                .collect();
+            let mut buffers = vec![Buffer::from(type_ids.to_byte_slice())];
+            let children = match mode {
+                UnionMode::Sparse => (0..num_children)
+                    .map(|i| select(&children_of(i), positions))
+                    .collect::<Result<Vec<ArrayRef>>>()?,
+                UnionMode::Dense => {
+                    // the positions of the selected values in each child
+                    let mut child_positions = vec![vec![]; num_children];
+                    let mut offsets = Vec::with_capacity(positions.len());
+                    for (position, type_id) in positions.iter().zip(&type_ids) {
+                        let child_positions = &mut child_positions[*type_id as usize];
+                        offsets.push(child_positions.len() as i32);
+                        child_positions.push(
+                            position
+                                .map(|(a, j)| (a, unions[a].value_offset(j) as usize)),
+                        );
+                    }
+                    buffers.push(Buffer::from(offsets.to_byte_slice()));
+                    child_positions
+                        .iter()
+                        .enumerate()
+                        .map(|(i, p)| select(&children_of(i), p))
+                        .collect::<Result<Vec<ArrayRef>>>()?
+                }
+            };
+            let data = ArrayData::new(
+                data_type.clone(),
+                positions.len(),
+                None,
+                select_null_bits(arrays, positions),
+                0,
+                buffers,
+                children.iter().map(|child| child.data()).collect(),
+            );
+            Ok(Arc::new(UnionArray::from(Arc::new(data))))
+        }
+        DataType::Dictionary(ref key_type, _) => match **key_type {
+            DataType::Int8 => select_dictionary::<Int8Type>(arrays, positions),
+            DataType::Int16 => select_dictionary::<Int16Type>(arrays, positions),
+            DataType::Int32 => select_dictionary::<Int32Type>(arrays, positions),
+            DataType::Int64 => select_dictionary::<Int64Type>(arrays, positions),
+            DataType::UInt8 => select_dictionary::<UInt8Type>(arrays, positions),
+            DataType::UInt16 => select_dictionary::<UInt16Type>(arrays, positions),
+            DataType::UInt32 => select_dictionary::<UInt32Type>(arrays, positions),
+            DataType::UInt64 => select_dictionary::<UInt64Type>(arrays, positions),
+            ref other => Err(ArrowError::ComputeError(format!(
+                "Unsupported dictionary key type {:?}",
+                other
+            ))),
+        },
+        other => Err(ArrowError::ComputeError(format!(
+            "Selecting values is not supported for {:?}",
+            other
+        ))),
+    }
+}
+
+/// Select the values of primitive arrays at the given positions. The result has the
+/// data type of the arrays, which keeps the timezone of timestamp arrays.
+fn select_primitive<T>(arrays: &[ArrayRef], positions: &[Position]) -> Result<ArrayRef>
+where
+    T: ArrowNumericType,
+{
+    let values: Vec<&[T::Native]> = downcast::<PrimitiveArray<T>>(arrays)
+        .into_iter()
+        .map(|array| array.value_slice(0, array.len()))
+        .collect();
+    let selected: Vec<T::Native> = positions
+        .iter()
+        .map(|position| match position {
+            Some((a, j)) => values[*a][*j],
+            None => T::default_value(),
+        })
+        .collect();
+    let data = ArrayData::new(
+        arrays[0].data_type().clone(),
+        positions.len(),
+        None,
+        select_null_bits(arrays, positions),
+        0,
+        vec![Buffer::from(selected.to_byte_slice())],
+        vec![],
+    );
+    Ok(Arc::new(PrimitiveArray::<T>::from(Arc::new(data))))
+}
+
+/// Select the values of dictionary arrays at the given positions by selecting their
+/// keys. The dictionary is reused when all the arrays share it, and otherwise the
+/// dictionaries are concatenated and the keys offset into the concatenation.
+fn select_dictionary<K>(arrays: &[ArrayRef], positions: &[Position]) -> Result<ArrayRef>
+where
+    K: ArrowDictionaryKeyType,
+{
+    let dictionaries = downcast::<DictionaryArray<K>>(arrays);
+    let shared = arrays.iter().all(|array| {
+        Arc::ptr_eq(
+            &array.data_ref().child_data()[0],
+            &arrays[0].data_ref().child_data()[0],
+        )
+    });
+    let (values, value_offsets) = if shared {
+        (dictionaries[0].values(), vec![0; arrays.len()])
+    } else {
+        let values: Vec<ArrayRef> = dictionaries.iter().map(|d| d.values()).collect();
+        let value_offsets: Vec<usize> = values
+            .iter()
+            .scan(0, |offset, values| {
+                let value_offset = *offset;
+                *offset += values.len();
+                Some(value_offset)
+            })
+            .collect();
+        (concat(&values)?, value_offsets)
+    };
+    let keys = positions
+        .iter()
+        .map(|position| match position {
+            Some((a, j)) if dictionaries[*a].is_valid(*j) => {
+                K::from_index(value_offsets[*a] + dictionaries[*a].value_index(*j))
+                    .ok_or_else(|| {
+                        ArrowError::ComputeError(format!(
+                            "Too many dictionary values for {:?} keys",
+                            K::get_data_type()
+                        ))
+                    })
+            }
+            _ => Ok(K::default_value()),
+        })
+        .collect::<Result<Vec<K::Native>>>()?;
+    let data = ArrayData::new(
+        arrays[0].data_type().clone(),
+        positions.len(),
+        None,
+        select_null_bits(arrays, positions),
+        0,
+        vec![Buffer::from(keys.to_byte_slice())],
+        vec![values.data()],
+    );
+    Ok(Arc::new(DictionaryArray::<K>::from(Arc::new(data))))
+}
+
+/// Create the validity bitmap for the values of arrays at the given positions, or
+/// `None` if none of the selected values are null
+fn select_null_bits(arrays: &[ArrayRef], positions: &[Position]) -> Option<Buffer> {
+    if arrays.iter().all(|array| array.null_count() == 0)
+        && positions.iter().all(Option::is_some)
+    {
+        return None;
+    }
+    let num_bytes = bit_util::ceil(positions.len(), 8);
+    let mut validity = MutableBuffer::new(num_bytes).with_bitset(num_bytes, false);
+    {
+        let validity_bits = validity.data_mut();
+        for (i, position) in positions.iter().enumerate() {
+            match position {
+                Some((a, j)) if arrays[*a].is_valid(*j) => {
+                    bit_util::set_bit(validity_bits, i)
+                }
+                _ => {}
+            }
+        }
+    }
+    Some(validity.freeze())
+}
+
 /// Helper function to perform boolean lambda function on values from two arrays.
 fn bool_op<T, F>(
     left: &PrimitiveArray<T>,
@@ -207,7 +937,8 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::array::{Float64Array, Int32Array};
+
+    use crate::builder::{BinaryBuilder, Int32Builder, ListBuilder, UnionBuilder};
 
     #[test]
     fn test_primitive_array_sum() {
@@ -345,6 +1076,539 @@ mod tests {
         assert_eq!(true, c.value(2));
     }
 
+    #[test]
+    fn test_primitive_array_compare_op_nulls() {
+        let a = Int32Array::from(vec![Some(1), None, Some(3), Some(4)]);
+        let b = Int32Array::from(vec![Some(1), Some(2), None, Some(5)]);
+        let c = compare_op(&a, &b, |a, b| a < b).unwrap();
+        assert_eq!(2, c.null_count());
+        assert_eq!(false, c.value(0));
+        assert!(c.is_null(1));
+        assert!(c.is_null(2));
+        assert_eq!(true, c.value(3));
+    }
+
+    #[test]
+    fn test_primitive_array_compare_op_sliced() {
+        // [3, 4, 5] and [null, 3, 5]
+        let a = Int32Array::from(vec![1, 2, 3, 4, 5]);
+        let a = Int32Array::from(Arc::new(ArrayData::new(
+            DataType::Int32,
+            3,
+            Some(0),
+            None,
+            2,
+            vec![a.values()],
+            vec![],
+        )));
+        let b = Int32Array::from(vec![Some(3), None, Some(3), Some(5), Some(9)]);
+        let null_bits = b.data().null_bitmap().as_ref().unwrap().bits.clone();
+        let b = Int32Array::from(Arc::new(ArrayData::new(
+            DataType::Int32,
+            3,
+            Some(1),
+            Some(null_bits),
+            1,
+            vec![b.values()],
+            vec![],
+        )));
+        let c = compare_op(&a, &b, |a, b| a == b).unwrap();
+        assert_eq!(1, c.null_count());
+        assert!(c.is_null(0));
+        assert_eq!(false, c.value(1));
+        assert_eq!(true, c.value(2));
+    }
+
+    #[test]
+    fn test_primitive_array_compare_op_different_length() {
+        let a = Int32Array::from(vec![1, 2]);
+        let b = Int32Array::from(vec![1]);
+        assert!(compare_op(&a, &b, |a, b| a == b).is_err());
+    }
+
+    #[test]
+    fn test_primitive_array_scalar_comparisons() {
+        let a =
+            Int32Array::from(vec![Some(6), Some(7), None, Some(8), Some(9), Some(10)]);
+        let values = |c: BooleanArray| -> Vec<Option<bool>> {
+            (0..c.len())
+                .map(|i| if c.is_null(i) { None } else { Some(c.value(i)) })
+                .collect()
+        };
+        let (t, f) = (Some(true), Some(false));
+        assert_eq!(vec![f, f, None, t, f, f], values(eq_scalar(&a, 8).unwrap()));
+        assert_eq!(
+            vec![t, t, None, f, t, t],
+            values(neq_scalar(&a, 8).unwrap())
+        );
+        assert_eq!(vec![t, t, None, f, f, f], values(lt_scalar(&a, 8).unwrap()));
+        assert_eq!(
+            vec![t, t, None, t, f, f],
+            values(lt_eq_scalar(&a, 8).unwrap())
+        );
+        assert_eq!(vec![f, f, None, f, t, t], values(gt_scalar(&a, 8).unwrap()));
+        assert_eq!(
+            vec![f, f, None, t, t, t],
+            values(gt_eq_scalar(&a, 8).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_primitive_array_float_scalar_comparison() {
+        let a = Float64Array::from(vec![1.5, 2.5, 3.5]);
+        let c = gt_scalar(&a, 2.0).unwrap();
+        assert_eq!(0, c.null_count());
+        assert_eq!(false, c.value(0));
+        assert_eq!(true, c.value(1));
+        assert_eq!(true, c.value(2));
+    }
+
+    #[test]
+    fn test_filter_primitive_array() {
+        let a: ArrayRef =
+            Arc::new(Int32Array::from(vec![Some(5), None, Some(7), Some(8)]));
+        let mask = BooleanArray::from(vec![Some(true), Some(true), None, Some(false)]);
+        let c = filter(&a, &mask).unwrap();
+        let c = c.as_any().downcast_ref::<Int32Array>().unwrap();
+        assert_eq!(2, c.len());
+        assert_eq!(1, c.null_count());
+        assert_eq!(5, c.value(0));
+        assert!(c.is_null(1));
+    }
+
+    #[test]
+    fn test_filter_boolean_array() {
+        let a: ArrayRef = Arc::new(BooleanArray::from(vec![true, false, true, false]));
+        let mask = BooleanArray::from(vec![false, true, true, true]);
+        let c = filter(&a, &mask).unwrap();
+        let c = c.as_any().downcast_ref::<BooleanArray>().unwrap();
+        assert_eq!(3, c.len());
+        assert_eq!(0, c.null_count());
+        assert_eq!(false, c.value(0));
+        assert_eq!(true, c.value(1));
+        assert_eq!(false, c.value(2));
+    }
+
+    #[test]
+    fn test_filter_string_array() {
+        let a: ArrayRef = Arc::new(BinaryArray::from(vec!["hello", "", "world", "!"]));
+        let mask = BooleanArray::from(vec![true, true, false, true]);
+        let c = filter(&a, &mask).unwrap();
+        let c = c.as_any().downcast_ref::<BinaryArray>().unwrap();
+        assert_eq!(&DataType::Utf8, c.data_type());
+        assert_eq!(3, c.len());
+        assert_eq!("hello", c.get_string(0));
+        assert_eq!("", c.get_string(1));
+        assert_eq!("!", c.get_string(2));
+    }
+
+    #[test]
+    fn test_filter_mask_of_different_length() {
+        let a: ArrayRef = Arc::new(Int32Array::from(vec![1, 2]));
+        let mask = BooleanArray::from(vec![true]);
+        assert!(filter(&a, &mask).is_err());
+    }
+
+    #[test]
+    fn test_take_primitive_array() {
+        let a: ArrayRef = Arc::new(Int32Array::from(vec![Some(5), None, Some(7)]));
+        let indices = UInt32Array::from(vec![Some(2), None, Some(1), Some(0), Some(2)]);
+        let c = take(&a, &indices).unwrap();
+        let c = c.as_any().downcast_ref::<Int32Array>().unwrap();
+        assert_eq!(5, c.len());
+        assert_eq!(2, c.null_count());
+        assert_eq!(7, c.value(0));
+        assert!(c.is_null(1));
+        assert!(c.is_null(2));
+        assert_eq!(5, c.value(3));
+        assert_eq!(7, c.value(4));
+    }
+
+    #[test]
+    fn test_take_date_array() {
+        let a: ArrayRef = Arc::new(Date32Array::from(vec![Some(17897), None, Some(0)]));
+        let indices = UInt32Array::from(vec![2, 0, 1]);
+        let c = take(&a, &indices).unwrap();
+        assert_eq!(&DataType::Date(DateUnit::Day), c.data_type());
+        let c = c.as_any().downcast_ref::<Date32Array>().unwrap();
+        assert_eq!(0, c.value(0));
+        assert_eq!(17897, c.value(1));
+        assert!(c.is_null(2));
+    }
+
+    #[test]
+    fn test_take_timestamp_array_keeps_timezone() {
+        let data_type =
+            DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".to_string()));
+        let data = ArrayData::builder(data_type.clone())
+            .len(3)
+            .add_buffer(Buffer::from(vec![1i64, 2, 3].to_byte_slice()))
+            .build();
+        let a = make_array(data);
+        let indices = UInt32Array::from(vec![Some(2), None, Some(0)]);
+        let c = take(&a, &indices).unwrap();
+        assert_eq!(&data_type, c.data_type());
+        let c = c
+            .as_any()
+            .downcast_ref::<TimestampMillisecondArray>()
+            .unwrap();
+        assert_eq!(3, c.value(0));
+        assert!(c.is_null(1));
+        assert_eq!(1, c.value(2));
+
+        let mask = BooleanArray::from(vec![false, true, true]);
+        let c = filter(&a, &mask).unwrap();
+        assert_eq!(&data_type, c.data_type());
+        assert_eq!(2, c.len());
+    }
+
+    #[test]
+    fn test_filter_interval_array() {
+        let a: ArrayRef =
+            Arc::new(IntervalYearMonthArray::from(vec![Some(1), None, Some(13)]));
+        let mask = BooleanArray::from(vec![true, true, false]);
+        let c = filter(&a, &mask).unwrap();
+        assert_eq!(&DataType::Interval(IntervalUnit::YearMonth), c.data_type());
+        let c = c.as_any().downcast_ref::<IntervalYearMonthArray>().unwrap();
+        assert_eq!(1, c.value(0));
+        assert!(c.is_null(1));
+    }
+
+    #[test]
+    fn test_date_array_compare() {
+        let a = Date64Array::from(vec![Some(1546300800000), None, Some(0)]);
+        let b = Date64Array::from(vec![Some(1546300800000), Some(0), Some(86400000)]);
+        let c = compare_op(&a, &b, |a, b| a < b).unwrap();
+        assert_eq!(false, c.value(0));
+        assert!(c.is_null(1));
+        assert_eq!(true, c.value(2));
+        let c = eq_scalar(&a, 0).unwrap();
+        assert_eq!(false, c.value(0));
+        assert_eq!(true, c.value(2));
+        assert_eq!(Some(1546300800000), max(&a));
+    }
+
+    #[test]
+    fn test_take_string_array() {
+        let a: ArrayRef = Arc::new(BinaryArray::from(vec!["one", "two", "three"]));
+        let indices = UInt32Array::from(vec![Some(2), Some(0), None]);
+        let c = take(&a, &indices).unwrap();
+        let c = c.as_any().downcast_ref::<BinaryArray>().unwrap();
+        assert_eq!(3, c.len());
+        assert_eq!("three", c.get_string(0));
+        assert_eq!("one", c.get_string(1));
+        assert!(c.is_null(2));
+    }
+
+    #[test]
+    fn test_take_list_array() {
+        // [[0, 1], [2], [3, 4, 5]]
+        let values = Int32Array::from(vec![0, 1, 2, 3, 4, 5]);
+        let data = ArrayData::builder(DataType::List(Box::new(DataType::Int32)))
+            .len(3)
+            .add_buffer(Buffer::from([0i32, 2, 3, 6].to_byte_slice()))
+            .add_child_data(values.data())
+            .build();
+        let a: ArrayRef = Arc::new(ListArray::from(data));
+        let indices = UInt32Array::from(vec![Some(2), None, Some(0)]);
+        let c = take(&a, &indices).unwrap();
+        let c = c.as_any().downcast_ref::<ListArray>().unwrap();
+        assert_eq!(3, c.len());
+        assert!(c.is_null(1));
+        assert_eq!(0, c.value_offset(0));
+        assert_eq!(3, c.value_length(0));
+        assert_eq!(0, c.value_length(1));
+        assert_eq!(2, c.value_length(2));
+        let values = c.values();
+        let values = values.as_any().downcast_ref::<Int32Array>().unwrap();
+        assert_eq!(&[3, 4, 5, 0, 1], values.value_slice(0, 5));
+    }
+
+    #[test]
+    fn test_take_fixed_size_list_array() {
+        // [[0, 1], [2, 3], [4, 5]], sliced to [[2, 3], [4, 5]]
+        let values = Int32Array::from(vec![0, 1, 2, 3, 4, 5]);
+        let data =
+            ArrayData::builder(DataType::FixedSizeList(Box::new(DataType::Int32), 2))
+                .len(2)
+                .offset(1)
+                .add_child_data(values.data())
+                .build();
+        let a: ArrayRef = Arc::new(FixedSizeListArray::from(data));
+        let indices = UInt32Array::from(vec![Some(1), None, Some(0)]);
+        let c = take(&a, &indices).unwrap();
+        let c = c.as_any().downcast_ref::<FixedSizeListArray>().unwrap();
+        assert_eq!(3, c.len());
+        assert!(c.is_null(1));
+        assert_eq!(2, c.value_length());
+        let values = c.values();
+        let values = values.as_any().downcast_ref::<Int32Array>().unwrap();
+        assert_eq!(6, values.len());
+        assert_eq!(2, values.null_count());
+        assert_eq!(&[4, 5], values.value_slice(0, 2));
+        assert_eq!(&[2, 3], values.value_slice(4, 2));
+    }
+
+    #[test]
+    fn test_take_struct_array() {
+        let a: ArrayRef = Arc::new(StructArray::from(vec![
+            (
+                Field::new("a", DataType::Boolean, false),
+                Arc::new(BooleanArray::from(vec![true, false])) as ArrayRef,
+            ),
+            (
+                Field::new("b", DataType::Int32, false),
+                Arc::new(Int32Array::from(vec![1, 2])) as ArrayRef,
+            ),
+        ]));
+        let indices = UInt32Array::from(vec![1, 1, 0]);
+        let c = take(&a, &indices).unwrap();
+        let c = c.as_any().downcast_ref::<StructArray>().unwrap();
+        assert_eq!(3, c.len());
+        let b = c.column(1).as_any().downcast_ref::<Int32Array>().unwrap();
+        assert_eq!(&[2, 2, 1], b.value_slice(0, 3));
+    }
+
+    #[test]
+    fn test_take_union_array() {
+        let fields = vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", DataType::Utf8, true),
+        ];
+        for mode in vec![UnionMode::Dense, UnionMode::Sparse] {
+            // [1, "x", 2]
+            let mut builder = UnionBuilder::from_fields(fields.clone(), mode.clone(), 3);
+            for (type_id, value) in vec![(0, "1"), (1, "x"), (0, "2")] {
+                let number = builder.field_builder::<Int32Builder>(0).unwrap();
+                if type_id == 0 {
+                    number.append_value(value.parse().unwrap()).unwrap();
+                } else if mode == UnionMode::Sparse {
+                    number.append_null().unwrap();
+                }
+                let string = builder.field_builder::<BinaryBuilder>(1).unwrap();
+                if type_id == 1 {
+                    string.append_string(value).unwrap();
+                } else if mode == UnionMode::Sparse {
+                    string.append_null().unwrap();
+                }
+                builder.append(type_id, true).unwrap();
+            }
+            let a: ArrayRef = Arc::new(builder.finish());
+
+            let indices = UInt32Array::from(vec![Some(2), None, Some(1)]);
+            let c = take(&a, &indices).unwrap();
+            let c = c.as_any().downcast_ref::<UnionArray>().unwrap();
+            assert_eq!(mode, c.mode());
+            assert_eq!(3, c.len());
+            assert!(c.is_null(1));
+            assert_eq!(0, c.type_id(0));
+            assert_eq!(1, c.type_id(2));
+            let numbers = c.child(0);
+            let numbers = numbers.as_any().downcast_ref::<Int32Array>().unwrap();
+            assert_eq!(2, numbers.value(c.value_offset(0) as usize));
+            let strings = c.child(1);
+            let strings = strings.as_any().downcast_ref::<BinaryArray>().unwrap();
+            assert_eq!("x", strings.get_string(c.value_offset(2) as usize));
+        }
+    }
+
+    #[test]
+    fn test_take_null_array() {
+        let a: ArrayRef = Arc::new(NullArray::new(4));
+        let c = take(&a, &UInt32Array::from(vec![Some(3), None, Some(0)])).unwrap();
+        assert_eq!(&DataType::Null, c.data_type());
+        assert_eq!(3, c.len());
+        assert_eq!(3, c.null_count());
+
+        let c = filter(&a, &BooleanArray::from(vec![true, false, true, false])).unwrap();
+        assert_eq!(2, c.len());
+    }
+
+    #[test]
+    fn test_take_dictionary_array() {
+        let a: ArrayRef = Arc::new(
+            Int8DictionaryArray::try_from_strings(&[Some("a"), None, Some("b")]).unwrap(),
+        );
+        let c = take(
+            &a,
+            &UInt32Array::from(vec![Some(2), Some(1), None, Some(2)]),
+        )
+        .unwrap();
+        assert_eq!(a.data_type(), c.data_type());
+        assert_eq!(2, c.null_count());
+        // the dictionary is shared with the taken array
+        assert!(Arc::ptr_eq(
+            &a.data().child_data()[0],
+            &c.data().child_data()[0]
+        ));
+        let (_, indices) = dictionary_indices(&c).unwrap();
+        assert_eq!(vec![Some(1), None, None, Some(1)], indices);
+    }
+
+    #[test]
+    fn test_concat_dictionary_arrays() {
+        let a: ArrayRef = Arc::new(
+            UInt8DictionaryArray::try_from_strings(&[Some("a"), Some("b")]).unwrap(),
+        );
+        let b: ArrayRef = Arc::new(
+            UInt8DictionaryArray::try_from_strings(&[None, Some("c"), Some("a")])
+                .unwrap(),
+        );
+        let c = concat(&[a, b]).unwrap();
+        assert_eq!(5, c.len());
+        assert_eq!(1, c.null_count());
+
+        let c = unpack_dictionary(&c).unwrap();
+        let c = c.as_any().downcast_ref::<BinaryArray>().unwrap();
+        assert_eq!(&DataType::Utf8, c.data_type());
+        assert_eq!("a", c.get_string(0));
+        assert_eq!("b", c.get_string(1));
+        assert!(c.is_null(2));
+        assert_eq!("c", c.get_string(3));
+        assert_eq!("a", c.get_string(4));
+    }
+
+    #[test]
+    fn test_dictionary_indices_of_other_array() {
+        let a: ArrayRef = Arc::new(Int32Array::from(vec![1]));
+        assert!(dictionary_indices(&a).is_err());
+        assert!(unpack_dictionary(&a).is_err());
+    }
+
+    #[test]
+    fn test_dictionary_from_indices() {
+        let values: ArrayRef = Arc::new(BinaryArray::from(vec!["a", "b"]));
+        let indices = vec![Some(1), None, Some(0), Some(1)];
+        let a =
+            dictionary_from_indices(&DataType::UInt16, &indices, values.clone()).unwrap();
+        assert_eq!(
+            &DataType::Dictionary(Box::new(DataType::UInt16), Box::new(DataType::Utf8)),
+            a.data_type()
+        );
+        assert_eq!(indices, dictionary_indices(&a).unwrap().1);
+
+        // indices must be in bounds and fit in the key type
+        let indices = vec![Some(2)];
+        assert!(
+            dictionary_from_indices(&DataType::Int8, &indices, values.clone()).is_err()
+        );
+        let values: ArrayRef = Arc::new(Int32Array::from(vec![0; 200]));
+        let indices = vec![Some(199)];
+        assert!(
+            dictionary_from_indices(&DataType::Int8, &indices, values.clone()).is_err()
+        );
+        assert!(dictionary_from_indices(&DataType::UInt8, &indices, values).is_ok());
+    }
+
+    #[test]
+    fn test_concat_primitive_arrays() {
+        let a: ArrayRef = Arc::new(Int32Array::from(vec![Some(1), None]));
+        // values of an array at an offset
+        let data = ArrayData::builder(DataType::Int32)
+            .len(2)
+            .offset(1)
+            .add_buffer(Buffer::from(&[3, 4, 5].to_byte_slice()))
+            .build();
+        let b = make_array(data);
+        let c = concat(&[a, b]).unwrap();
+        let c = c.as_any().downcast_ref::<Int32Array>().unwrap();
+        assert_eq!(4, c.len());
+        assert_eq!(1, c.null_count());
+        assert_eq!(1, c.value(0));
+        assert!(c.is_null(1));
+        assert_eq!(4, c.value(2));
+        assert_eq!(5, c.value(3));
+    }
+
+    #[test]
+    fn test_concat_list_arrays() {
+        let mut builder = ListBuilder::new(Int32Builder::new(4));
+        builder.values().append_slice(&[1, 2]).unwrap();
+        builder.append(true).unwrap();
+        builder.append(false).unwrap();
+        let a: ArrayRef = Arc::new(builder.finish());
+        let mut builder = ListBuilder::new(Int32Builder::new(4));
+        builder.values().append_slice(&[3]).unwrap();
+        builder.append(true).unwrap();
+        let b: ArrayRef = Arc::new(builder.finish());
+
+        let c = concat(&[a, b]).unwrap();
+        let c = c.as_any().downcast_ref::<ListArray>().unwrap();
+        assert_eq!(3, c.len());
+        assert!(c.is_null(1));
+        assert_eq!(2, c.value_length(0));
+        assert_eq!(1, c.value_length(2));
+        let values = c.values();
+        let values = values.as_any().downcast_ref::<Int32Array>().unwrap();
+        assert_eq!(3, values.value(c.value_offset(2) as usize));
+    }
+
+    #[test]
+    fn test_concat_mismatched_types() {
+        let a: ArrayRef = Arc::new(Int32Array::from(vec![1]));
+        let b: ArrayRef = Arc::new(Int64Array::from(vec![1]));
+        assert!(concat(&[a, b]).is_err());
+        assert!(concat(&[]).is_err());
+    }
+
+    #[test]
+    fn test_take_out_of_bounds() {
+        let a: ArrayRef = Arc::new(Int32Array::from(vec![1, 2]));
+        assert!(take(&a, &UInt32Array::from(vec![2])).is_err());
+    }
+
+    #[test]
+    fn test_primitive_array_aggregates_with_nulls_in_chunks() {
+        // enough values for several chunks of eight, with a chunk of only null values
+        let values: Vec<Option<i64>> = (0..30)
+            .map(|i| {
+                if i % 3 == 0 || (i >= 8 && i < 16) {
+                    None
+                } else {
+                    Some(i)
+                }
+            })
+            .collect();
+        let expected: Vec<i64> = values.iter().filter_map(|v| *v).collect();
+        let a = Int64Array::from(values);
+        assert_eq!(expected.iter().sum::<i64>(), sum(&a).unwrap());
+        assert_eq!(1, min(&a).unwrap());
+        assert_eq!(29, max(&a).unwrap());
+        assert_eq!(expected.len(), count(&a));
+    }
+
+    #[test]
+    fn test_primitive_array_aggregates_without_nulls() {
+        let a = Float64Array::from((1..=20).map(|i| i as f64).collect::<Vec<f64>>());
+        assert_eq!(210.0, sum(&a).unwrap());
+        assert_eq!(1.0, min(&a).unwrap());
+        assert_eq!(20.0, max(&a).unwrap());
+        assert_eq!(20, count(&a));
+
+        let a = Int32Array::from(vec![] as Vec<i32>);
+        assert_eq!(None, sum(&a));
+        assert_eq!(None, min(&a));
+        assert_eq!(0, count(&a));
+    }
+
+    #[test]
+    fn test_primitive_array_aggregates_with_offset() {
+        // [3, null, 5, null, 7]
+        let a =
+            Int32Array::from(vec![Some(1), None, Some(3), None, Some(5), None, Some(7)]);
+        let data = ArrayData::builder(DataType::Int32)
+            .len(5)
+            .offset(2)
+            .null_count(2)
+            .null_bit_buffer(a.data().null_bitmap().as_ref().unwrap().bits.clone())
+            .add_buffer(a.data().buffers()[0].clone())
+            .build();
+        let a = Int32Array::from(data);
+        assert_eq!(15, sum(&a).unwrap());
+        assert_eq!(3, min(&a).unwrap());
+        assert_eq!(7, max(&a).unwrap());
+    }
+
     #[test]
     fn test_buffer_array_min_max() {
         let a = Int32Array::from(vec![5, 6, 7, 8, 9]);
@@ -24,12 +24,15 @@
 
 use std::sync::Arc;
 
+use std::cmp;
+
 use crate::array::{Array, BooleanArray};
 use crate::array_data::ArrayData;
-use crate::buffer::Buffer;
+use crate::buffer::{Buffer, MutableBuffer};
 use crate::compute::util::apply_bin_op_to_option_bitmap;
 use crate::datatypes::DataType;
 use crate::error::{ArrowError, Result};
+use crate::util::bit_util;
 
 /// Helper function to implement binary kernels
 fn binary_boolean_kernel<F>(
@@ -101,6 +104,111 @@ pub fn not(left: &BooleanArray) -> Result<BooleanArray> {
     Ok(BooleanArray::from(Arc::new(data)))
 }
 
+/// Reads the 64 bits starting at byte `start` of a bitmap, where bytes past the end of
+/// the bitmap are read as zero
+fn load_word(bitmap: &[u8], start: usize) -> u64 {
+    let mut bytes = [0u8; 8];
+    let end = cmp::min(start + 8, bitmap.len());
+    if start < end {
+        bytes[..end - start].copy_from_slice(&bitmap[start..end]);
+    }
+    u64::from_le_bytes(bytes)
+}
+
+/// Writes the 64 bits of `word` to the bitmap starting at byte `start`, truncated to the
+/// length of the bitmap
+fn store_word(bitmap: &mut [u8], start: usize, word: u64) {
+    let end = cmp::min(start + 8, bitmap.len());
+    bitmap[start..end].copy_from_slice(&word.to_le_bytes()[..end - start]);
+}
+
+/// Helper function to implement three-valued logical kernels. `op` is applied to 64
+/// bits at a time of the values and validity bitmaps of both arrays, in the order
+/// `(left values, left validity, right values, right validity)`, and returns the values
+/// and validity of the result. A missing validity bitmap is read as all bits set.
+fn kleene_boolean_kernel<F>(
+    left: &BooleanArray,
+    right: &BooleanArray,
+    op: F,
+) -> Result<BooleanArray>
+where
+    F: Fn(u64, u64, u64, u64) -> (u64, u64),
+{
+    if left.len() != right.len() {
+        return Err(ArrowError::ComputeError(
+            "Cannot apply boolean kernel to arrays of different length".to_string(),
+        ));
+    }
+    if left.offset() != right.offset() {
+        return Err(ArrowError::ComputeError(
+            "Cannot apply boolean kernel when arrays have different offsets.".to_string(),
+        ));
+    }
+
+    let left_data = left.data();
+    let right_data = right.data();
+    let left_values = left_data.buffers()[0].data();
+    let right_values = right_data.buffers()[0].data();
+    let left_validity = left_data.null_bitmap().as_ref().map(|b| b.bits.data());
+    let right_validity = right_data.null_bitmap().as_ref().map(|b| b.bits.data());
+
+    let end = left.offset() + left.len();
+    let num_bytes = bit_util::ceil(end, 8);
+    let mut values = MutableBuffer::new(num_bytes).with_bitset(num_bytes, false);
+    let mut validity = MutableBuffer::new(num_bytes).with_bitset(num_bytes, false);
+    for start in (0..num_bytes).step_by(8) {
+        let (value_word, validity_word) = op(
+            load_word(left_values, start),
+            left_validity.map_or(!0, |v| load_word(v, start)),
+            load_word(right_values, start),
+            right_validity.map_or(!0, |v| load_word(v, start)),
+        );
+        store_word(values.data_mut(), start, value_word);
+        store_word(validity.data_mut(), start, validity_word);
+    }
+
+    let null_bit_buffer = if left_validity.is_none() && right_validity.is_none() {
+        None
+    } else {
+        // clear the bits past the end of the array, so that they are not counted as
+        // valid values
+        if end % 8 != 0 {
+            validity.data_mut()[num_bytes - 1] &= (1 << (end % 8)) - 1;
+        }
+        Some(validity.freeze())
+    };
+    let data = ArrayData::new(
+        DataType::Boolean,
+        left.len(),
+        None,
+        null_bit_buffer,
+        left.offset(),
+        vec![values.freeze()],
+        vec![],
+    );
+    Ok(BooleanArray::from(Arc::new(data)))
+}
+
+/// Performs SQL `AND` operation on two arrays, where a null value is unknown. The result
+/// is false if either value is false, even when the other value is null, and otherwise
+/// null if either value is null.
+pub fn and_kleene(left: &BooleanArray, right: &BooleanArray) -> Result<BooleanArray> {
+    kleene_boolean_kernel(left, right, |l, l_valid, r, r_valid| {
+        let known_false = (l_valid & !l) | (r_valid & !r);
+        (l & r, (l_valid & r_valid) | known_false)
+    })
+}
+
+/// Performs SQL `OR` operation on two arrays, where a null value is unknown. The result
+/// is true if either value is true, even when the other value is null, and otherwise
+/// null if either value is null.
+pub fn or_kleene(left: &BooleanArray, right: &BooleanArray) -> Result<BooleanArray> {
+    kleene_boolean_kernel(left, right, |l, l_valid, r, r_valid| {
+        let known_true = (l_valid & l) | (r_valid & r);
+        (known_true, (l_valid & r_valid) | known_true)
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -158,4 +266,136 @@ mod tests {
         assert_eq!(true, c.is_null(2));
         assert_eq!(false, c.is_null(3));
     }
+
+    #[test]
+    fn test_bool_array_and_kleene() {
+        let a = BooleanArray::from(vec![
+            Some(true),
+            Some(true),
+            Some(true),
+            Some(false),
+            Some(false),
+            Some(false),
+            None,
+            None,
+            None,
+        ]);
+        let b = BooleanArray::from(vec![
+            Some(true),
+            Some(false),
+            None,
+            Some(true),
+            Some(false),
+            None,
+            Some(true),
+            Some(false),
+            None,
+        ]);
+        let c = and_kleene(&a, &b).unwrap();
+        let expected = vec![
+            Some(true),
+            Some(false),
+            None,
+            Some(false),
+            Some(false),
+            Some(false),
+            None,
+            Some(false),
+            None,
+        ];
+        assert_eq!(3, c.null_count());
+        for (i, e) in expected.iter().enumerate() {
+            assert_eq!(*e, if c.is_null(i) { None } else { Some(c.value(i)) });
+        }
+    }
+
+    #[test]
+    fn test_bool_array_or_kleene() {
+        let a = BooleanArray::from(vec![
+            Some(true),
+            Some(true),
+            Some(true),
+            Some(false),
+            Some(false),
+            Some(false),
+            None,
+            None,
+            None,
+        ]);
+        let b = BooleanArray::from(vec![
+            Some(true),
+            Some(false),
+            None,
+            Some(true),
+            Some(false),
+            None,
+            Some(true),
+            Some(false),
+            None,
+        ]);
+        let c = or_kleene(&a, &b).unwrap();
+        let expected = vec![
+            Some(true),
+            Some(true),
+            Some(true),
+            Some(true),
+            Some(false),
+            None,
+            Some(true),
+            None,
+            None,
+        ];
+        assert_eq!(3, c.null_count());
+        for (i, e) in expected.iter().enumerate() {
+            assert_eq!(*e, if c.is_null(i) { None } else { Some(c.value(i)) });
+        }
+    }
+
+    #[test]
+    fn test_bool_array_kleene_many_words() {
+        // more than 64 values, where only the right array has a validity bitmap
+        let a = BooleanArray::from((0..150).map(|i| i % 2 == 0).collect::<Vec<bool>>());
+        let b = BooleanArray::from(
+            (0..150)
+                .map(|i| if i % 3 == 0 { None } else { Some(i % 5 == 0) })
+                .collect::<Vec<Option<bool>>>(),
+        );
+        let and = and_kleene(&a, &b).unwrap();
+        let or = or_kleene(&a, &b).unwrap();
+        for i in 0..150 {
+            let right = if i % 3 == 0 { None } else { Some(i % 5 == 0) };
+            let expected_and = match (i % 2 == 0, right) {
+                (false, _) => Some(false),
+                (true, r) => r,
+            };
+            let expected_or = match (i % 2 == 0, right) {
+                (true, _) => Some(true),
+                (false, r) => r,
+            };
+            assert_eq!(
+                expected_and,
+                if and.is_null(i) {
+                    None
+                } else {
+                    Some(and.value(i))
+                }
+            );
+            assert_eq!(
+                expected_or,
+                if or.is_null(i) {
+                    None
+                } else {
+                    Some(or.value(i))
+                }
+            );
+        }
+        assert_eq!(0, and_kleene(&a, &a).unwrap().null_count());
+    }
+
+    #[test]
+    fn test_bool_array_kleene_different_length() {
+        let a = BooleanArray::from(vec![true, false]);
+        let b = BooleanArray::from(vec![true]);
+        assert!(and_kleene(&a, &b).is_err());
+    }
 }
@@ -0,0 +1,750 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the cast kernel, which converts the values of an array to another data type.
+//!
+//! Casts are supported between all numeric types, between numeric types and booleans,
+//! and between these types and `Utf8`. Date and time types can be cast to and from
+//! integers, which reinterprets their values, to other units of the same kind, and to
+//! and from `Utf8` in the formats of `util::temporal_util`. Dictionary arrays are cast
+//! by casting the values of their keys. Null values remain null.
+
+use std::fmt::Display;
+use std::sync::Arc;
+
+use num::{NumCast, Zero};
+
+use crate::array::*;
+use crate::array_data::ArrayData;
+use crate::builder::{BinaryBuilder, PrimitiveBuilder};
+use crate::compute::array_ops::unpack_dictionary;
+use crate::datatypes::*;
+use crate::error::{ArrowError, Result};
+use crate::util::temporal_util;
+
+/// What a cast produces for a value that cannot be represented in the target type
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CastFailure {
+    /// Fail the cast with an error
+    Error,
+    /// Produce a null value
+    Null,
+}
+
+/// Options that control how values that cannot be cast are handled
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CastOptions {
+    /// Numeric values outside of the range of the target type, and NaN or infinite
+    /// values cast to an integer type
+    pub overflow: CastFailure,
+    /// Strings that cannot be parsed as a value of the target type
+    pub parse_failure: CastFailure,
+}
+
+impl Default for CastOptions {
+    fn default() -> Self {
+        Self {
+            overflow: CastFailure::Error,
+            parse_failure: CastFailure::Error,
+        }
+    }
+}
+
+/// Cast an array to another data type, failing if a value cannot be represented in
+/// the target type.
+pub fn cast(array: &ArrayRef, to_type: &DataType) -> Result<ArrayRef> {
+    cast_with_options(array, to_type, &CastOptions::default())
+}
+
+macro_rules! cast_from_numeric {
+    ($ARRAY:expr, $FROM:ty, $TO_TYPE:expr, $OPTIONS:expr) => {{
+        match $TO_TYPE {
+            DataType::Int8 => cast_numeric::<$FROM, Int8Type>($ARRAY, $OPTIONS),
+            DataType::Int16 => cast_numeric::<$FROM, Int16Type>($ARRAY, $OPTIONS),
+            DataType::Int32 => cast_numeric::<$FROM, Int32Type>($ARRAY, $OPTIONS),
+            DataType::Int64 => cast_numeric::<$FROM, Int64Type>($ARRAY, $OPTIONS),
+            DataType::UInt8 => cast_numeric::<$FROM, UInt8Type>($ARRAY, $OPTIONS),
+            DataType::UInt16 => cast_numeric::<$FROM, UInt16Type>($ARRAY, $OPTIONS),
+            DataType::UInt32 => cast_numeric::<$FROM, UInt32Type>($ARRAY, $OPTIONS),
+            DataType::UInt64 => cast_numeric::<$FROM, UInt64Type>($ARRAY, $OPTIONS),
+            DataType::Float32 => cast_numeric::<$FROM, Float32Type>($ARRAY, $OPTIONS),
+            DataType::Float64 => cast_numeric::<$FROM, Float64Type>($ARRAY, $OPTIONS),
+            DataType::Boolean => cast_numeric_to_boolean::<$FROM>($ARRAY),
+            DataType::Utf8 => cast_numeric_to_string::<$FROM>($ARRAY),
+            DataType::Date(_) | DataType::Time32(_) | DataType::Time64(_) => {
+                cast_integer_to_temporal($ARRAY, $TO_TYPE, $OPTIONS)
+            }
+            other => Err(unsupported_cast($ARRAY.data_type(), other)),
+        }
+    }};
+}
+
+macro_rules! cast_to_numeric {
+    ($ARRAY:expr, $TO_TYPE:expr, $CAST:ident $(, $ARGS:expr)*) => {{
+        match $TO_TYPE {
+            DataType::Int8 => $CAST::<Int8Type>($ARRAY $(, $ARGS)*),
+            DataType::Int16 => $CAST::<Int16Type>($ARRAY $(, $ARGS)*),
+            DataType::Int32 => $CAST::<Int32Type>($ARRAY $(, $ARGS)*),
+            DataType::Int64 => $CAST::<Int64Type>($ARRAY $(, $ARGS)*),
+            DataType::UInt8 => $CAST::<UInt8Type>($ARRAY $(, $ARGS)*),
+            DataType::UInt16 => $CAST::<UInt16Type>($ARRAY $(, $ARGS)*),
+            DataType::UInt32 => $CAST::<UInt32Type>($ARRAY $(, $ARGS)*),
+            DataType::UInt64 => $CAST::<UInt64Type>($ARRAY $(, $ARGS)*),
+            DataType::Float32 => $CAST::<Float32Type>($ARRAY $(, $ARGS)*),
+            DataType::Float64 => $CAST::<Float64Type>($ARRAY $(, $ARGS)*),
+            other => Err(unsupported_cast($ARRAY.data_type(), other)),
+        }
+    }};
+}
+
+/// Cast an array to another data type, handling values that cannot be represented in
+/// the target type according to the options.
+pub fn cast_with_options(
+    array: &ArrayRef,
+    to_type: &DataType,
+    options: &CastOptions,
+) -> Result<ArrayRef> {
+    let from_type = array.data_type();
+    if from_type == to_type {
+        return Ok(array.clone());
+    }
+    match from_type {
+        DataType::Int8 => cast_from_numeric!(array, Int8Type, to_type, options),
+        DataType::Int16 => cast_from_numeric!(array, Int16Type, to_type, options),
+        DataType::Int32 => cast_from_numeric!(array, Int32Type, to_type, options),
+        DataType::Int64 => cast_from_numeric!(array, Int64Type, to_type, options),
+        DataType::UInt8 => cast_from_numeric!(array, UInt8Type, to_type, options),
+        DataType::UInt16 => cast_from_numeric!(array, UInt16Type, to_type, options),
+        DataType::UInt32 => cast_from_numeric!(array, UInt32Type, to_type, options),
+        DataType::UInt64 => cast_from_numeric!(array, UInt64Type, to_type, options),
+        DataType::Float32 => cast_from_numeric!(array, Float32Type, to_type, options),
+        DataType::Float64 => cast_from_numeric!(array, Float64Type, to_type, options),
+        DataType::Boolean => match to_type {
+            DataType::Utf8 => cast_boolean_to_string(array),
+            _ => cast_to_numeric!(array, to_type, cast_boolean_to_numeric),
+        },
+        DataType::Utf8 => match to_type {
+            DataType::Boolean => cast_string_to_boolean(array, options),
+            DataType::Date(_) | DataType::Time32(_) | DataType::Time64(_) => {
+                cast_string_to_temporal(array, to_type, options)
+            }
+            _ => cast_to_numeric!(array, to_type, cast_string_to_numeric, options),
+        },
+        DataType::Date(_) | DataType::Time32(_) | DataType::Time64(_) => {
+            cast_from_temporal(array, to_type, options)
+        }
+        DataType::Dictionary(_, _) => {
+            cast_with_options(&unpack_dictionary(array)?, to_type, options)
+        }
+        _ => Err(unsupported_cast(from_type, to_type)),
+    }
+}
+
+fn unsupported_cast(from_type: &DataType, to_type: &DataType) -> ArrowError {
+    ArrowError::ComputeError(format!(
+        "Casting from {:?} to {:?} is not supported",
+        from_type, to_type
+    ))
+}
+
+/// Returns the integer type that stores the values of a date or time type, or `None`
+/// if the type is not a supported date or time type
+fn temporal_storage_type(data_type: &DataType) -> Option<DataType> {
+    match data_type {
+        DataType::Date(DateUnit::Day)
+        | DataType::Time32(TimeUnit::Second)
+        | DataType::Time32(TimeUnit::Millisecond) => Some(DataType::Int32),
+        DataType::Date(DateUnit::Millisecond)
+        | DataType::Time64(TimeUnit::Microsecond)
+        | DataType::Time64(TimeUnit::Nanosecond) => Some(DataType::Int64),
+        _ => None,
+    }
+}
+
+fn is_integer(data_type: &DataType) -> bool {
+    match data_type {
+        DataType::Int8
+        | DataType::Int16
+        | DataType::Int32
+        | DataType::Int64
+        | DataType::UInt8
+        | DataType::UInt16
+        | DataType::UInt32
+        | DataType::UInt64 => true,
+        _ => false,
+    }
+}
+
+/// Reinterpret the values of an array as another type with the same layout, without
+/// copying them
+fn reinterpret(array: &ArrayRef, to_type: &DataType) -> ArrayRef {
+    let data = array.data();
+    make_array(Arc::new(ArrayData::new(
+        to_type.clone(),
+        data.len(),
+        Some(data.null_count()),
+        data.null_bitmap()
+            .as_ref()
+            .map(|bitmap| bitmap.bits.clone()),
+        data.offset(),
+        data.buffers().to_vec(),
+        vec![],
+    )))
+}
+
+/// Cast an integer array to a date or time type, whose values are the integers
+fn cast_integer_to_temporal(
+    array: &ArrayRef,
+    to_type: &DataType,
+    options: &CastOptions,
+) -> Result<ArrayRef> {
+    match temporal_storage_type(to_type) {
+        Some(ref storage_type) if is_integer(array.data_type()) => {
+            let values = cast_with_options(array, storage_type, options)?;
+            Ok(reinterpret(&values, to_type))
+        }
+        _ => Err(unsupported_cast(array.data_type(), to_type)),
+    }
+}
+
+/// Cast a date or time array to an integer type, to another unit of the same kind, or
+/// to strings
+fn cast_from_temporal(
+    array: &ArrayRef,
+    to_type: &DataType,
+    options: &CastOptions,
+) -> Result<ArrayRef> {
+    let from_type = array.data_type();
+    let storage_type = match temporal_storage_type(from_type) {
+        Some(storage_type) => storage_type,
+        None => return Err(unsupported_cast(from_type, to_type)),
+    };
+    match (from_type, to_type) {
+        (_, DataType::Utf8) => cast_temporal_to_string(array),
+        (_, to_type) if is_integer(to_type) => {
+            cast_with_options(&reinterpret(array, &storage_type), to_type, options)
+        }
+        (DataType::Date(from_unit), DataType::Date(to_unit)) => {
+            let per_day = |unit: &DateUnit| match unit {
+                DateUnit::Day => 1,
+                DateUnit::Millisecond => temporal_util::MILLISECONDS_PER_DAY,
+            };
+            cast_temporal_unit(
+                array,
+                to_type,
+                per_day(from_unit),
+                per_day(to_unit),
+                options,
+            )
+        }
+        (DataType::Time32(from_unit), DataType::Time32(to_unit))
+        | (DataType::Time32(from_unit), DataType::Time64(to_unit))
+        | (DataType::Time64(from_unit), DataType::Time32(to_unit))
+        | (DataType::Time64(from_unit), DataType::Time64(to_unit)) => cast_temporal_unit(
+            array,
+            to_type,
+            temporal_util::units_per_second(from_unit),
+            temporal_util::units_per_second(to_unit),
+            options,
+        ),
+        _ => Err(unsupported_cast(from_type, to_type)),
+    }
+}
+
+/// Cast a date or time array to another unit, given the number of units of each type
+/// in a common period such as a day. Values are truncated to the start of the larger
+/// unit they fall in.
+fn cast_temporal_unit(
+    array: &ArrayRef,
+    to_type: &DataType,
+    from_units: i64,
+    to_units: i64,
+    options: &CastOptions,
+) -> Result<ArrayRef> {
+    let to_storage_type = match temporal_storage_type(to_type) {
+        Some(storage_type) => storage_type,
+        None => return Err(unsupported_cast(array.data_type(), to_type)),
+    };
+    let values = temporal_values(array)?;
+    let values = values.as_any().downcast_ref::<Int64Array>().unwrap();
+    let mut builder = Int64Array::builder(values.len());
+    for i in 0..values.len() {
+        if values.is_null(i) {
+            builder.append_null()?;
+            continue;
+        }
+        let value = values.value(i);
+        if to_units >= from_units {
+            match value.checked_mul(to_units / from_units) {
+                Some(v) => builder.append_value(v)?,
+                None => append_failure(&mut builder, options.overflow, || {
+                    format!("Value {} is out of range for {:?}", value, to_type)
+                })?,
+            }
+        } else {
+            builder
+                .append_value(temporal_util::floor_div(value, from_units / to_units))?;
+        }
+    }
+    let values: ArrayRef = Arc::new(builder.finish());
+    let values = cast_with_options(&values, &to_storage_type, options)?;
+    Ok(reinterpret(&values, to_type))
+}
+
+/// Returns the values of a date or time array as an `Int64Array`
+fn temporal_values(array: &ArrayRef) -> Result<ArrayRef> {
+    let storage_type = temporal_storage_type(array.data_type()).unwrap();
+    cast(&reinterpret(array, &storage_type), &DataType::Int64)
+}
+
+fn cast_temporal_to_string(array: &ArrayRef) -> Result<ArrayRef> {
+    let values = temporal_values(array)?;
+    let values = values.as_any().downcast_ref::<Int64Array>().unwrap();
+    let mut builder = BinaryBuilder::new(values.len());
+    for i in 0..values.len() {
+        if values.is_null(i) {
+            builder.append_null()?;
+            continue;
+        }
+        let value = values.value(i);
+        let s = match array.data_type() {
+            DataType::Date(DateUnit::Day) => temporal_util::format_date(value),
+            DataType::Date(DateUnit::Millisecond) => temporal_util::format_date(
+                temporal_util::floor_div(value, temporal_util::MILLISECONDS_PER_DAY),
+            ),
+            DataType::Time32(unit) | DataType::Time64(unit) => {
+                temporal_util::format_time(value, unit)
+            }
+            other => unreachable!("unexpected temporal type {:?}", other),
+        };
+        builder.append_string(&s)?;
+    }
+    Ok(Arc::new(builder.finish()))
+}
+
+/// Parse the strings of a `Utf8` array as dates or times
+fn cast_string_to_temporal(
+    array: &ArrayRef,
+    to_type: &DataType,
+    options: &CastOptions,
+) -> Result<ArrayRef> {
+    let storage_type = match temporal_storage_type(to_type) {
+        Some(storage_type) => storage_type,
+        None => return Err(unsupported_cast(array.data_type(), to_type)),
+    };
+    let array = array.as_any().downcast_ref::<BinaryArray>().unwrap();
+    let mut builder = Int64Array::builder(array.len());
+    for i in 0..array.len() {
+        if array.is_null(i) {
+            builder.append_null()?;
+            continue;
+        }
+        let s = String::from_utf8_lossy(array.value(i));
+        let value = match to_type {
+            DataType::Date(DateUnit::Day) => temporal_util::parse_date(&s).map(i64::from),
+            DataType::Date(DateUnit::Millisecond) => temporal_util::parse_date(&s)
+                .map(|days| i64::from(days) * temporal_util::MILLISECONDS_PER_DAY),
+            DataType::Time32(unit) | DataType::Time64(unit) => {
+                temporal_util::parse_time(&s, unit)
+            }
+            other => unreachable!("unexpected temporal type {:?}", other),
+        };
+        match value {
+            Some(v) => builder.append_value(v)?,
+            None => append_failure(&mut builder, options.parse_failure, || {
+                format!("Cannot cast string '{}' to {:?}", s, to_type)
+            })?,
+        }
+    }
+    let values: ArrayRef = Arc::new(builder.finish());
+    let values = cast(&values, &storage_type)?;
+    Ok(reinterpret(&values, to_type))
+}
+
+/// Append a value that could not be cast, which is either a null value or an error
+/// depending on the failure policy
+fn append_failure<T>(
+    builder: &mut PrimitiveBuilder<T>,
+    on_failure: CastFailure,
+    message: impl FnOnce() -> String,
+) -> Result<()>
+where
+    T: ArrowPrimitiveType,
+{
+    match on_failure {
+        CastFailure::Error => Err(ArrowError::ComputeError(message())),
+        CastFailure::Null => builder.append_null(),
+    }
+}
+
+/// Cast the values of a numeric array to another numeric type
+fn cast_numeric<F, T>(array: &ArrayRef, options: &CastOptions) -> Result<ArrayRef>
+where
+    F: ArrowNumericType,
+    T: ArrowNumericType,
+    F::Native: NumCast + Display,
+    T::Native: NumCast,
+{
+    let array = array.as_any().downcast_ref::<PrimitiveArray<F>>().unwrap();
+    let mut builder = PrimitiveArray::<T>::builder(array.len());
+    for i in 0..array.len() {
+        if array.is_null(i) {
+            builder.append_null()?;
+            continue;
+        }
+        let value = array.value(i);
+        match num::cast::<F::Native, T::Native>(value) {
+            Some(v) => builder.append_value(v)?,
+            None => append_failure(&mut builder, options.overflow, || {
+                format!(
+                    "Value {} is out of range for {:?}",
+                    value,
+                    T::get_data_type()
+                )
+            })?,
+        }
+    }
+    Ok(Arc::new(builder.finish()))
+}
+
+/// Cast a numeric array to booleans, where zero is false and all other values are
+/// true
+fn cast_numeric_to_boolean<F>(array: &ArrayRef) -> Result<ArrayRef>
+where
+    F: ArrowNumericType,
+    F::Native: Zero,
+{
+    let array = array.as_any().downcast_ref::<PrimitiveArray<F>>().unwrap();
+    let mut builder = BooleanArray::builder(array.len());
+    for i in 0..array.len() {
+        if array.is_null(i) {
+            builder.append_null()?;
+        } else {
+            builder.append_value(!array.value(i).is_zero())?;
+        }
+    }
+    Ok(Arc::new(builder.finish()))
+}
+
+fn cast_numeric_to_string<F>(array: &ArrayRef) -> Result<ArrayRef>
+where
+    F: ArrowNumericType,
+    F::Native: Display,
+{
+    let array = array.as_any().downcast_ref::<PrimitiveArray<F>>().unwrap();
+    let mut builder = BinaryBuilder::new(array.len());
+    for i in 0..array.len() {
+        if array.is_null(i) {
+            builder.append_null()?;
+        } else {
+            builder.append_string(&array.value(i).to_string())?;
+        }
+    }
+    Ok(Arc::new(builder.finish()))
+}
+
+/// Cast a boolean array to a numeric type, where true is one and false is zero
+fn cast_boolean_to_numeric<T>(array: &ArrayRef) -> Result<ArrayRef>
+where
+    T: ArrowNumericType,
+    T::Native: NumCast,
+{
+    let array = array.as_any().downcast_ref::<BooleanArray>().unwrap();
+    let mut builder = PrimitiveArray::<T>::builder(array.len());
+    for i in 0..array.len() {
+        if array.is_null(i) {
+            builder.append_null()?;
+        } else {
+            let value: u8 = if array.value(i) { 1 } else { 0 };
+            // zero and one can be represented in every numeric type
+            builder.append_value(num::cast::<u8, T::Native>(value).unwrap())?;
+        }
+    }
+    Ok(Arc::new(builder.finish()))
+}
+
+fn cast_boolean_to_string(array: &ArrayRef) -> Result<ArrayRef> {
+    let array = array.as_any().downcast_ref::<BooleanArray>().unwrap();
+    let mut builder = BinaryBuilder::new(array.len());
+    for i in 0..array.len() {
+        if array.is_null(i) {
+            builder.append_null()?;
+        } else {
+            builder.append_string(if array.value(i) { "true" } else { "false" })?;
+        }
+    }
+    Ok(Arc::new(builder.finish()))
+}
+
+/// Parse the strings of a `Utf8` array as numbers, ignoring leading and trailing
+/// whitespace
+fn cast_string_to_numeric<T>(array: &ArrayRef, options: &CastOptions) -> Result<ArrayRef>
+where
+    T: ArrowNumericType,
+{
+    let array = array.as_any().downcast_ref::<BinaryArray>().unwrap();
+    let mut builder = PrimitiveArray::<T>::builder(array.len());
+    for i in 0..array.len() {
+        if array.is_null(i) {
+            builder.append_null()?;
+            continue;
+        }
+        let s = String::from_utf8_lossy(array.value(i));
+        match s.trim().parse::<T::Native>() {
+            Ok(v) => builder.append_value(v)?,
+            Err(_) => append_failure(&mut builder, options.parse_failure, || {
+                format!("Cannot cast string '{}' to {:?}", s, T::get_data_type())
+            })?,
+        }
+    }
+    Ok(Arc::new(builder.finish()))
+}
+
+/// Parse the strings of a `Utf8` array as booleans, which are `true` or `false` in any
+/// case
+fn cast_string_to_boolean(array: &ArrayRef, options: &CastOptions) -> Result<ArrayRef> {
+    let array = array.as_any().downcast_ref::<BinaryArray>().unwrap();
+    let mut builder = BooleanArray::builder(array.len());
+    for i in 0..array.len() {
+        if array.is_null(i) {
+            builder.append_null()?;
+            continue;
+        }
+        let s = String::from_utf8_lossy(array.value(i));
+        match s.trim().to_lowercase().as_str() {
+            "true" => builder.append_value(true)?,
+            "false" => builder.append_value(false)?,
+            _ => append_failure(&mut builder, options.parse_failure, || {
+                format!("Cannot cast string '{}' to Boolean", s)
+            })?,
+        }
+    }
+    Ok(Arc::new(builder.finish()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn null_options() -> CastOptions {
+        CastOptions {
+            overflow: CastFailure::Null,
+            parse_failure: CastFailure::Null,
+        }
+    }
+
+    #[test]
+    fn test_cast_int32_to_float64() {
+        let a: ArrayRef = Arc::new(Int32Array::from(vec![Some(5), None, Some(-7)]));
+        let b = cast(&a, &DataType::Float64).unwrap();
+        let c = b.as_any().downcast_ref::<Float64Array>().unwrap();
+        assert_eq!(5.0, c.value(0));
+        assert!(c.is_null(1));
+        assert_eq!(-7.0, c.value(2));
+    }
+
+    #[test]
+    fn test_cast_int32_to_uint8_overflow() {
+        let a: ArrayRef = Arc::new(Int32Array::from(vec![5, 300, -1]));
+        assert!(cast(&a, &DataType::UInt8).is_err());
+
+        let b = cast_with_options(&a, &DataType::UInt8, &null_options()).unwrap();
+        let c = b.as_any().downcast_ref::<UInt8Array>().unwrap();
+        assert_eq!(5, c.value(0));
+        assert!(c.is_null(1));
+        assert!(c.is_null(2));
+    }
+
+    #[test]
+    fn test_cast_float64_to_int32() {
+        let a: ArrayRef = Arc::new(Float64Array::from(vec![2.7, -2.7, std::f64::NAN]));
+        assert!(cast(&a, &DataType::Int32).is_err());
+
+        let b = cast_with_options(&a, &DataType::Int32, &null_options()).unwrap();
+        let c = b.as_any().downcast_ref::<Int32Array>().unwrap();
+        assert_eq!(2, c.value(0));
+        assert_eq!(-2, c.value(1));
+        assert!(c.is_null(2));
+    }
+
+    #[test]
+    fn test_cast_numeric_to_string() {
+        let a: ArrayRef = Arc::new(Int64Array::from(vec![Some(-12), None]));
+        let b = cast(&a, &DataType::Utf8).unwrap();
+        let c = b.as_any().downcast_ref::<BinaryArray>().unwrap();
+        assert_eq!(&DataType::Utf8, c.data_type());
+        assert_eq!("-12", c.get_string(0));
+        assert!(c.is_null(1));
+    }
+
+    #[test]
+    fn test_cast_string_to_numeric() {
+        let a: ArrayRef = Arc::new(BinaryArray::from(vec![" 42 ", "1.5", "x"]));
+        assert!(cast(&a, &DataType::Int32).is_err());
+
+        let b = cast_with_options(&a, &DataType::Int32, &null_options()).unwrap();
+        let c = b.as_any().downcast_ref::<Int32Array>().unwrap();
+        assert_eq!(42, c.value(0));
+        assert!(c.is_null(1));
+        assert!(c.is_null(2));
+
+        let b = cast_with_options(&a, &DataType::Float64, &null_options()).unwrap();
+        let c = b.as_any().downcast_ref::<Float64Array>().unwrap();
+        assert_eq!(42.0, c.value(0));
+        assert_eq!(1.5, c.value(1));
+        assert!(c.is_null(2));
+    }
+
+    #[test]
+    fn test_cast_boolean() {
+        let a: ArrayRef =
+            Arc::new(BooleanArray::from(vec![Some(true), None, Some(false)]));
+        let b = cast(&a, &DataType::UInt16).unwrap();
+        let c = b.as_any().downcast_ref::<UInt16Array>().unwrap();
+        assert_eq!(1, c.value(0));
+        assert!(c.is_null(1));
+        assert_eq!(0, c.value(2));
+
+        let b = cast(&a, &DataType::Utf8).unwrap();
+        let c = b.as_any().downcast_ref::<BinaryArray>().unwrap();
+        assert_eq!("true", c.get_string(0));
+        assert_eq!("false", c.get_string(2));
+
+        let a: ArrayRef = Arc::new(Float32Array::from(vec![0.0, 0.5]));
+        let b = cast(&a, &DataType::Boolean).unwrap();
+        let c = b.as_any().downcast_ref::<BooleanArray>().unwrap();
+        assert_eq!(false, c.value(0));
+        assert_eq!(true, c.value(1));
+
+        let a: ArrayRef = Arc::new(BinaryArray::from(vec!["TRUE", "false", "yes"]));
+        assert!(cast(&a, &DataType::Boolean).is_err());
+        let b = cast_with_options(&a, &DataType::Boolean, &null_options()).unwrap();
+        let c = b.as_any().downcast_ref::<BooleanArray>().unwrap();
+        assert_eq!(true, c.value(0));
+        assert_eq!(false, c.value(1));
+        assert!(c.is_null(2));
+    }
+
+    #[test]
+    fn test_cast_unsupported() {
+        let a: ArrayRef = Arc::new(BinaryArray::from(vec!["a"]));
+        assert!(cast(&a, &DataType::List(Box::new(DataType::Int32))).is_err());
+    }
+
+    #[test]
+    fn test_cast_date_integers() {
+        let a: ArrayRef = Arc::new(Int32Array::from(vec![Some(17897), None]));
+        let b = cast(&a, &DataType::Date(DateUnit::Day)).unwrap();
+        let c = b.as_any().downcast_ref::<Date32Array>().unwrap();
+        assert_eq!(17897, c.value(0));
+        assert!(c.is_null(1));
+
+        let d = cast(&b, &DataType::Int64).unwrap();
+        let d = d.as_any().downcast_ref::<Int64Array>().unwrap();
+        assert_eq!(17897, d.value(0));
+        assert!(d.is_null(1));
+
+        let a: ArrayRef = Arc::new(Float64Array::from(vec![1.0]));
+        assert!(cast(&a, &DataType::Date(DateUnit::Day)).is_err());
+    }
+
+    #[test]
+    fn test_cast_date_units() {
+        let a: ArrayRef = Arc::new(Date32Array::from(vec![Some(17897), Some(-1), None]));
+        let b = cast(&a, &DataType::Date(DateUnit::Millisecond)).unwrap();
+        let c = b.as_any().downcast_ref::<Date64Array>().unwrap();
+        assert_eq!(1546300800000, c.value(0));
+        assert_eq!(-86400000, c.value(1));
+        assert!(c.is_null(2));
+
+        // milliseconds within a day are truncated to the start of the day
+        let a: ArrayRef = Arc::new(Date64Array::from(vec![1546300800001, -1]));
+        let b = cast(&a, &DataType::Date(DateUnit::Day)).unwrap();
+        let c = b.as_any().downcast_ref::<Date32Array>().unwrap();
+        assert_eq!(17897, c.value(0));
+        assert_eq!(-1, c.value(1));
+
+        assert!(cast(&a, &DataType::Time64(TimeUnit::Microsecond)).is_err());
+    }
+
+    #[test]
+    fn test_cast_time_units() {
+        let a: ArrayRef = Arc::new(Time32SecondArray::from(vec![Some(45296), None]));
+        let b = cast(&a, &DataType::Time64(TimeUnit::Nanosecond)).unwrap();
+        let c = b.as_any().downcast_ref::<Time64NanosecondArray>().unwrap();
+        assert_eq!(45296000000000, c.value(0));
+        assert!(c.is_null(1));
+
+        let a: ArrayRef = Arc::new(Time64MicrosecondArray::from(vec![45296999999]));
+        let b = cast(&a, &DataType::Time32(TimeUnit::Millisecond)).unwrap();
+        let c = b.as_any().downcast_ref::<Time32MillisecondArray>().unwrap();
+        assert_eq!(45296999, c.value(0));
+
+        assert!(cast(&a, &DataType::Time32(TimeUnit::Microsecond)).is_err());
+    }
+
+    #[test]
+    fn test_cast_temporal_strings() {
+        let a: ArrayRef = Arc::new(BinaryArray::from(vec!["2019-01-01", "1969-12-31"]));
+        let b = cast(&a, &DataType::Date(DateUnit::Millisecond)).unwrap();
+        let c = b.as_any().downcast_ref::<Date64Array>().unwrap();
+        assert_eq!(1546300800000, c.value(0));
+        assert_eq!(-86400000, c.value(1));
+        let d = cast(&b, &DataType::Utf8).unwrap();
+        let d = d.as_any().downcast_ref::<BinaryArray>().unwrap();
+        assert_eq!("2019-01-01", d.get_string(0));
+        assert_eq!("1969-12-31", d.get_string(1));
+
+        let a: ArrayRef = Arc::new(BinaryArray::from(vec!["12:34:56.5", "noon"]));
+        let to_type = DataType::Time32(TimeUnit::Millisecond);
+        assert!(cast(&a, &to_type).is_err());
+        let b = cast_with_options(&a, &to_type, &null_options()).unwrap();
+        let c = b.as_any().downcast_ref::<Time32MillisecondArray>().unwrap();
+        assert_eq!(45296500, c.value(0));
+        assert!(c.is_null(1));
+        let d = cast(&b, &DataType::Utf8).unwrap();
+        let d = d.as_any().downcast_ref::<BinaryArray>().unwrap();
+        assert_eq!("12:34:56.500", d.get_string(0));
+        assert!(d.is_null(1));
+    }
+
+    #[test]
+    fn test_cast_to_same_type() {
+        let a: ArrayRef = Arc::new(Int32Array::from(vec![1, 2]));
+        let b = cast(&a, &DataType::Int32).unwrap();
+        assert_eq!(2, b.len());
+        assert_eq!(&DataType::Int32, b.data_type());
+    }
+
+    #[test]
+    fn test_cast_dictionary() {
+        let a: ArrayRef = Arc::new(
+            Int16DictionaryArray::try_from_strings(&[Some("1"), None, Some("1")])
+                .unwrap(),
+        );
+        let b = cast(&a, &DataType::Utf8).unwrap();
+        let b = b.as_any().downcast_ref::<BinaryArray>().unwrap();
+        assert_eq!("1", b.get_string(2));
+        assert!(b.is_null(1));
+
+        let b = cast(&a, &DataType::Int64).unwrap();
+        let b = b.as_any().downcast_ref::<Int64Array>().unwrap();
+        assert_eq!(1, b.value(0));
+        assert!(b.is_null(1));
+        assert_eq!(1, b.value(2));
+    }
+}
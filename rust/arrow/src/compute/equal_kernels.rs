@@ -0,0 +1,641 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the deep equality of arrays of any type, including nested types, and the
+//! `eq_any` and `neq_any` kernels that compare arrays of any type value by value.
+//!
+//! Arrays are equal if they have the same data type and length, and the same nulls
+//! and values at every index. Values are compared logically: the offsets of the arrays,
+//! the values under null slots and the layout of their buffers do not matter.
+
+use crate::array::*;
+use crate::builder::BooleanBuilder;
+use crate::datatypes::*;
+use crate::error::{ArrowError, Result};
+
+/// Deep equality of arrays, e.g. for assertions on the results of kernels
+pub trait ArrayEq {
+    /// Returns whether this array is equal to `other`.
+    fn equals(&self, other: &Array) -> bool;
+
+    /// Returns whether this array is equal to `other`, where floating point values are
+    /// equal if they differ by at most `epsilon`.
+    fn approx_equals(&self, other: &Array, epsilon: f64) -> bool;
+}
+
+impl ArrayEq for Array {
+    fn equals(&self, other: &Array) -> bool {
+        array_equals(self, other)
+    }
+
+    fn approx_equals(&self, other: &Array, epsilon: f64) -> bool {
+        array_approx_equals(self, other, epsilon)
+    }
+}
+
+macro_rules! impl_array_eq {
+    ($($array_ty:ty),*) => {
+        $(
+            impl ArrayEq for $array_ty {
+                fn equals(&self, other: &Array) -> bool {
+                    array_equals(self, other)
+                }
+
+                fn approx_equals(&self, other: &Array, epsilon: f64) -> bool {
+                    array_approx_equals(self, other, epsilon)
+                }
+            }
+        )*
+    };
+}
+
+impl<T: ArrowPrimitiveType> ArrayEq for PrimitiveArray<T> {
+    fn equals(&self, other: &Array) -> bool {
+        array_equals(self, other)
+    }
+
+    fn approx_equals(&self, other: &Array, epsilon: f64) -> bool {
+        array_approx_equals(self, other, epsilon)
+    }
+}
+
+impl<K: ArrowDictionaryKeyType> ArrayEq for DictionaryArray<K> {
+    fn equals(&self, other: &Array) -> bool {
+        array_equals(self, other)
+    }
+
+    fn approx_equals(&self, other: &Array, epsilon: f64) -> bool {
+        array_approx_equals(self, other, epsilon)
+    }
+}
+
+impl_array_eq!(
+    NullArray,
+    BinaryArray,
+    ListArray,
+    FixedSizeListArray,
+    StructArray,
+    UnionArray
+);
+
+/// Returns whether two arrays are equal.
+pub fn array_equals(left: &Array, right: &Array) -> bool {
+    left.data_type() == right.data_type()
+        && left.len() == right.len()
+        && range_equals(left, 0, right, 0, left.len(), None)
+}
+
+/// Returns whether two arrays are equal, where floating point values are equal if they
+/// differ by at most `epsilon`, including the values of nested arrays.
+pub fn array_approx_equals(left: &Array, right: &Array, epsilon: f64) -> bool {
+    left.data_type() == right.data_type()
+        && left.len() == right.len()
+        && range_equals(left, 0, right, 0, left.len(), Some(epsilon))
+}
+
+/// Perform `left == right` on two arrays of the same type, which may be of any type
+/// including nested types. The result is null where either value is null.
+///
+/// The values are compared one by one, so `eq` is faster for numeric types.
+pub fn eq_any(left: &Array, right: &Array) -> Result<BooleanArray> {
+    compare_any(left, right, true)
+}
+
+/// Perform `left != right` on two arrays of the same type, which may be of any type
+/// including nested types. The result is null where either value is null.
+pub fn neq_any(left: &Array, right: &Array) -> Result<BooleanArray> {
+    compare_any(left, right, false)
+}
+
+fn compare_any(left: &Array, right: &Array, equal: bool) -> Result<BooleanArray> {
+    if left.data_type() != right.data_type() {
+        return Err(ArrowError::ComputeError(format!(
+            "Cannot compare arrays of type {:?} and {:?}",
+            left.data_type(),
+            right.data_type()
+        )));
+    }
+    if left.len() != right.len() {
+        return Err(ArrowError::ComputeError(
+            "Cannot compare arrays of different length".to_string(),
+        ));
+    }
+    check_comparable(left.data_type())?;
+    let mut builder = BooleanBuilder::new(left.len());
+    for i in 0..left.len() {
+        if left.is_null(i) || right.is_null(i) {
+            builder.append_null()?;
+        } else {
+            builder.append_value(range_equals(left, i, right, i, 1, None) == equal)?;
+        }
+    }
+    Ok(builder.finish())
+}
+
+/// Returns an error if values of the given type, or of any type nested in it, cannot
+/// be compared by `range_equals`
+fn check_comparable(data_type: &DataType) -> Result<()> {
+    match data_type {
+        DataType::List(value_type)
+        | DataType::FixedSizeList(value_type, _)
+        | DataType::Dictionary(_, value_type) => check_comparable(value_type),
+        DataType::Struct(fields) | DataType::Union(fields, _) => fields
+            .iter()
+            .try_for_each(|field| check_comparable(field.data_type())),
+        DataType::Float16 => Err(ArrowError::ComputeError(format!(
+            "Comparing arrays of type {:?} is not supported",
+            data_type
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Returns whether two floating point values are equal. NaN values are equal to each
+/// other, so that arrays containing them can be equal.
+fn float_equals(left: f64, right: f64, epsilon: Option<f64>) -> bool {
+    match epsilon {
+        _ if left == right || (left.is_nan() && right.is_nan()) => true,
+        Some(epsilon) => (left - right).abs() <= epsilon,
+        None => false,
+    }
+}
+
+macro_rules! values_equal {
+    ($left:expr, $left_start:expr, $right:expr, $right_start:expr, $len:expr,
+     $array_ty:ty, $eq:expr) => {{
+        let left = $left.as_any().downcast_ref::<$array_ty>().unwrap();
+        let right = $right.as_any().downcast_ref::<$array_ty>().unwrap();
+        (0..$len).all(|i| {
+            left.is_null($left_start + i)
+                || $eq(left.value($left_start + i), right.value($right_start + i))
+        })
+    }};
+}
+
+/// Returns whether `len` values of `left` from index `left_start` are equal to the
+/// values of `right` from index `right_start`. Both arrays must have the same type.
+fn range_equals(
+    left: &Array,
+    left_start: usize,
+    right: &Array,
+    right_start: usize,
+    len: usize,
+    epsilon: Option<f64>,
+) -> bool {
+    // the nulls must match, and values are only compared where both are valid
+    if (0..len).any(|i| left.is_null(left_start + i) != right.is_null(right_start + i)) {
+        return false;
+    }
+    let (l, ls, r, rs) = (left, left_start, right, right_start);
+    match left.data_type() {
+        DataType::Null => true,
+        DataType::Boolean => {
+            values_equal!(l, ls, r, rs, len, BooleanArray, |a, b| a == b)
+        }
+        DataType::Int8 => values_equal!(l, ls, r, rs, len, Int8Array, |a, b| a == b),
+        DataType::Int16 => values_equal!(l, ls, r, rs, len, Int16Array, |a, b| a == b),
+        DataType::Int32 => values_equal!(l, ls, r, rs, len, Int32Array, |a, b| a == b),
+        DataType::Int64 => values_equal!(l, ls, r, rs, len, Int64Array, |a, b| a == b),
+        DataType::UInt8 => values_equal!(l, ls, r, rs, len, UInt8Array, |a, b| a == b),
+        DataType::UInt16 => {
+            values_equal!(l, ls, r, rs, len, UInt16Array, |a, b| a == b)
+        }
+        DataType::UInt32 => {
+            values_equal!(l, ls, r, rs, len, UInt32Array, |a, b| a == b)
+        }
+        DataType::UInt64 => {
+            values_equal!(l, ls, r, rs, len, UInt64Array, |a, b| a == b)
+        }
+        DataType::Float32 => {
+            values_equal!(l, ls, r, rs, len, Float32Array, |a: f32, b: f32| {
+                float_equals(f64::from(a), f64::from(b), epsilon)
+            })
+        }
+        DataType::Float64 => {
+            values_equal!(l, ls, r, rs, len, Float64Array, |a, b| {
+                float_equals(a, b, epsilon)
+            })
+        }
+        DataType::Date(DateUnit::Day) => {
+            values_equal!(l, ls, r, rs, len, Date32Array, |a, b| a == b)
+        }
+        DataType::Date(DateUnit::Millisecond) => {
+            values_equal!(l, ls, r, rs, len, Date64Array, |a, b| a == b)
+        }
+        DataType::Time32(TimeUnit::Second) => {
+            values_equal!(l, ls, r, rs, len, Time32SecondArray, |a, b| a == b)
+        }
+        DataType::Time32(TimeUnit::Millisecond) => {
+            values_equal!(l, ls, r, rs, len, Time32MillisecondArray, |a, b| a == b)
+        }
+        DataType::Time64(TimeUnit::Microsecond) => {
+            values_equal!(l, ls, r, rs, len, Time64MicrosecondArray, |a, b| a == b)
+        }
+        DataType::Time64(TimeUnit::Nanosecond) => {
+            values_equal!(l, ls, r, rs, len, Time64NanosecondArray, |a, b| a == b)
+        }
+        DataType::Timestamp(TimeUnit::Second, _) => {
+            values_equal!(l, ls, r, rs, len, TimestampSecondArray, |a, b| a == b)
+        }
+        DataType::Timestamp(TimeUnit::Millisecond, _) => {
+            values_equal!(l, ls, r, rs, len, TimestampMillisecondArray, |a, b| a == b)
+        }
+        DataType::Timestamp(TimeUnit::Microsecond, _) => {
+            values_equal!(l, ls, r, rs, len, TimestampMicrosecondArray, |a, b| a == b)
+        }
+        DataType::Timestamp(TimeUnit::Nanosecond, _) => {
+            values_equal!(l, ls, r, rs, len, TimestampNanosecondArray, |a, b| a == b)
+        }
+        DataType::Interval(IntervalUnit::YearMonth) => {
+            values_equal!(l, ls, r, rs, len, IntervalYearMonthArray, |a, b| a == b)
+        }
+        DataType::Interval(IntervalUnit::DayTime) => {
+            values_equal!(l, ls, r, rs, len, IntervalDayTimeArray, |a, b| a == b)
+        }
+        DataType::Binary | DataType::Utf8 => {
+            values_equal!(l, ls, r, rs, len, BinaryArray, |a, b| a == b)
+        }
+        DataType::List(_) => {
+            let left = left.as_any().downcast_ref::<ListArray>().unwrap();
+            let right = right.as_any().downcast_ref::<ListArray>().unwrap();
+            let (left_values, right_values) = (left.values(), right.values());
+            (0..len).filter(|i| left.is_valid(ls + i)).all(|i| {
+                let length = left.value_length(ls + i);
+                length == right.value_length(rs + i)
+                    && range_equals(
+                        &*left_values,
+                        left.value_offset(ls + i) as usize,
+                        &*right_values,
+                        right.value_offset(rs + i) as usize,
+                        length as usize,
+                        epsilon,
+                    )
+            })
+        }
+        DataType::FixedSizeList(_, length) => {
+            let left = left.as_any().downcast_ref::<FixedSizeListArray>().unwrap();
+            let right = right.as_any().downcast_ref::<FixedSizeListArray>().unwrap();
+            let (left_values, right_values) = (left.values(), right.values());
+            (0..len).filter(|i| left.is_valid(ls + i)).all(|i| {
+                range_equals(
+                    &*left_values,
+                    left.value_offset(ls + i) as usize,
+                    &*right_values,
+                    right.value_offset(rs + i) as usize,
+                    *length as usize,
+                    epsilon,
+                )
+            })
+        }
+        DataType::Struct(fields) => {
+            let left = left.as_any().downcast_ref::<StructArray>().unwrap();
+            let right = right.as_any().downcast_ref::<StructArray>().unwrap();
+            (0..fields.len()).all(|c| {
+                let (left_field, right_field) = (left.column(c), right.column(c));
+                if left.null_count() == 0 {
+                    range_equals(&**left_field, ls, &**right_field, rs, len, epsilon)
+                } else {
+                    // the values of the fields of null structs do not matter
+                    (0..len).filter(|i| left.is_valid(ls + i)).all(|i| {
+                        range_equals(
+                            &**left_field,
+                            ls + i,
+                            &**right_field,
+                            rs + i,
+                            1,
+                            epsilon,
+                        )
+                    })
+                }
+            })
+        }
+        DataType::Union(_, _) => {
+            let left = left.as_any().downcast_ref::<UnionArray>().unwrap();
+            let right = right.as_any().downcast_ref::<UnionArray>().unwrap();
+            (0..len).filter(|i| left.is_valid(ls + i)).all(|i| {
+                let type_id = left.type_id(ls + i);
+                type_id == right.type_id(rs + i)
+                    && range_equals(
+                        &**left.child(type_id),
+                        left.value_offset(ls + i) as usize,
+                        &**right.child(type_id),
+                        right.value_offset(rs + i) as usize,
+                        1,
+                        epsilon,
+                    )
+            })
+        }
+        DataType::Dictionary(key_type, _) => match **key_type {
+            DataType::Int8 => {
+                dictionary_range_equals::<Int8Type>(l, ls, r, rs, len, epsilon)
+            }
+            DataType::Int16 => {
+                dictionary_range_equals::<Int16Type>(l, ls, r, rs, len, epsilon)
+            }
+            DataType::Int32 => {
+                dictionary_range_equals::<Int32Type>(l, ls, r, rs, len, epsilon)
+            }
+            DataType::Int64 => {
+                dictionary_range_equals::<Int64Type>(l, ls, r, rs, len, epsilon)
+            }
+            DataType::UInt8 => {
+                dictionary_range_equals::<UInt8Type>(l, ls, r, rs, len, epsilon)
+            }
+            DataType::UInt16 => {
+                dictionary_range_equals::<UInt16Type>(l, ls, r, rs, len, epsilon)
+            }
+            DataType::UInt32 => {
+                dictionary_range_equals::<UInt32Type>(l, ls, r, rs, len, epsilon)
+            }
+            DataType::UInt64 => {
+                dictionary_range_equals::<UInt64Type>(l, ls, r, rs, len, epsilon)
+            }
+            ref other => panic!("Unexpected dictionary key type {:?}", other),
+        },
+        // `compare_any` rejects these types up front, and no arrays of them can be
+        // constructed for `array_equals`
+        other => panic!("Comparing arrays of type {:?} is not supported", other),
+    }
+}
+
+/// Returns whether the values of dictionary arrays are equal, which are the values of
+/// their keys in their dictionaries, so arrays with different dictionaries can be equal
+fn dictionary_range_equals<K: ArrowDictionaryKeyType>(
+    left: &Array,
+    left_start: usize,
+    right: &Array,
+    right_start: usize,
+    len: usize,
+    epsilon: Option<f64>,
+) -> bool {
+    let left = left.as_any().downcast_ref::<DictionaryArray<K>>().unwrap();
+    let right = right.as_any().downcast_ref::<DictionaryArray<K>>().unwrap();
+    let (left_values, right_values) = (left.values(), right.values());
+    (0..len).filter(|i| left.is_valid(left_start + i)).all(|i| {
+        range_equals(
+            &*left_values,
+            left.value_index(left_start + i),
+            &*right_values,
+            right.value_index(right_start + i),
+            1,
+            epsilon,
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::Arc;
+
+    use crate::array_data::ArrayData;
+    use crate::buffer::Buffer;
+    use crate::builder::{
+        BinaryBuilder, FixedSizeListBuilder, Int32Builder, ListBuilder, UnionBuilder,
+    };
+
+    #[test]
+    fn test_primitive_equals() {
+        let a = Int32Array::from(vec![Some(1), None, Some(3)]);
+        assert!(a.equals(&Int32Array::from(vec![Some(1), None, Some(3)])));
+        assert!(!a.equals(&Int32Array::from(vec![Some(1), Some(2), Some(3)])));
+        assert!(!a.equals(&Int32Array::from(vec![Some(1), None])));
+        assert!(!a.equals(&Int64Array::from(vec![Some(1), None, Some(3)])));
+
+        // the values under null slots do not matter
+        let data = ArrayData::builder(DataType::Int32)
+            .len(3)
+            .null_bit_buffer(Buffer::from([0b101_u8]))
+            .add_buffer(Buffer::from(&[1, 42, 3].to_byte_slice()))
+            .build();
+        assert!(a.equals(&Int32Array::from(data)));
+
+        let a: ArrayRef = Arc::new(BooleanArray::from(vec![true, false]));
+        let b: ArrayRef = Arc::new(BooleanArray::from(vec![true, false]));
+        assert!(a.equals(&*b));
+    }
+
+    #[test]
+    fn test_float_approx_equals() {
+        let a = Float64Array::from(vec![1.0, std::f64::NAN, 3.0]);
+        let b = Float64Array::from(vec![1.0, std::f64::NAN, 3.0000001]);
+        assert!(a.equals(&a));
+        assert!(!a.equals(&b));
+        assert!(a.approx_equals(&b, 1e-6));
+        assert!(!a.approx_equals(&b, 1e-9));
+
+        let a = Float32Array::from(vec![0.3]);
+        assert!(!a.equals(&Float32Array::from(vec![0.3000001])));
+        assert!(a.approx_equals(&Float32Array::from(vec![0.3000001]), 1e-6));
+    }
+
+    #[test]
+    fn test_binary_equals() {
+        let a = BinaryArray::from(vec!["hello", "", "arrow"]);
+        assert!(a.equals(&BinaryArray::from(vec!["hello", "", "arrow"])));
+        assert!(!a.equals(&BinaryArray::from(vec!["hello", "arrow", ""])));
+        let b = BinaryArray::from(vec![&b"hello"[..], b"", b"arrow"]);
+        assert!(!a.equals(&b), "binary values are not equal to strings");
+    }
+
+    fn list_array(values: Vec<Option<Vec<i32>>>) -> ListArray {
+        let mut builder = ListBuilder::new(Int32Builder::new(10));
+        for value in values {
+            match value {
+                Some(v) => {
+                    builder.values().append_slice(&v).unwrap();
+                    builder.append(true).unwrap();
+                }
+                None => builder.append(false).unwrap(),
+            }
+        }
+        builder.finish()
+    }
+
+    #[test]
+    fn test_list_equals() {
+        let a = list_array(vec![Some(vec![1, 2]), None, Some(vec![3])]);
+        assert!(a.equals(&list_array(vec![Some(vec![1, 2]), None, Some(vec![3])])));
+        assert!(!a.equals(&list_array(vec![Some(vec![1]), None, Some(vec![2, 3])])));
+        assert!(!a.equals(&list_array(vec![
+            Some(vec![1, 2]),
+            Some(vec![]),
+            Some(vec![3])
+        ])));
+
+        // the same lists at a different offset in the values
+        let values = Int32Array::from(vec![9, 9, 1, 2, 3]);
+        let data = ArrayData::builder(DataType::List(Box::new(DataType::Int32)))
+            .len(2)
+            .offset(1)
+            .add_buffer(Buffer::from(&[0, 2, 4, 5].to_byte_slice()))
+            .add_child_data(values.data())
+            .build();
+        let b = list_array(vec![Some(vec![1, 2]), Some(vec![3])]);
+        assert!(b.equals(&ListArray::from(data)));
+    }
+
+    #[test]
+    fn test_fixed_size_list_equals() {
+        let build = |values: &[i32], valid: &[bool]| {
+            let mut builder = FixedSizeListBuilder::new(Int32Builder::new(6), 2);
+            for (chunk, is_valid) in values.chunks(2).zip(valid) {
+                builder.values().append_slice(chunk).unwrap();
+                builder.append(*is_valid).unwrap();
+            }
+            builder.finish()
+        };
+        let a = build(&[1, 2, 3, 4, 5, 6], &[true, false, true]);
+        assert!(a.equals(&build(&[1, 2, 0, 0, 5, 6], &[true, false, true])));
+        assert!(!a.equals(&build(&[1, 2, 3, 4, 6, 5], &[true, false, true])));
+        assert!(!a.equals(&build(&[1, 2, 3, 4, 5, 6], &[true, true, true])));
+    }
+
+    #[test]
+    fn test_struct_equals() {
+        let a = StructArray::from(vec![
+            (
+                Field::new("a", DataType::Boolean, false),
+                Arc::new(BooleanArray::from(vec![true, false])) as ArrayRef,
+            ),
+            (
+                Field::new("b", DataType::Float64, false),
+                Arc::new(Float64Array::from(vec![1.0, 2.0])) as ArrayRef,
+            ),
+        ]);
+        let b = StructArray::from(vec![
+            (
+                Field::new("a", DataType::Boolean, false),
+                Arc::new(BooleanArray::from(vec![true, false])) as ArrayRef,
+            ),
+            (
+                Field::new("b", DataType::Float64, false),
+                Arc::new(Float64Array::from(vec![1.0, 2.0001])) as ArrayRef,
+            ),
+        ]);
+        assert!(a.equals(&a));
+        assert!(!a.equals(&b));
+        assert!(a.approx_equals(&b, 0.001));
+    }
+
+    #[test]
+    fn test_union_equals() {
+        let build = |values: Vec<Result<i32, &str>>| {
+            let fields = vec![
+                Field::new("a", DataType::Int32, true),
+                Field::new("b", DataType::Utf8, true),
+            ];
+            let mut builder = UnionBuilder::from_fields(fields, UnionMode::Dense, 4);
+            for value in values {
+                match value {
+                    Ok(n) => {
+                        let numbers = builder.field_builder::<Int32Builder>(0).unwrap();
+                        numbers.append_value(n).unwrap();
+                        builder.append(0, true).unwrap();
+                    }
+                    Err(s) => {
+                        let strings = builder.field_builder::<BinaryBuilder>(1).unwrap();
+                        strings.append_string(s).unwrap();
+                        builder.append(1, true).unwrap();
+                    }
+                }
+            }
+            builder.finish()
+        };
+        let a = build(vec![Ok(1), Err("x"), Ok(2)]);
+        assert!(a.equals(&build(vec![Ok(1), Err("x"), Ok(2)])));
+        assert!(!a.equals(&build(vec![Ok(1), Err("y"), Ok(2)])));
+        assert!(!a.equals(&build(vec![Ok(1), Ok(0), Ok(2)])));
+    }
+
+    #[test]
+    fn test_dictionary_equals() {
+        let a =
+            Int8DictionaryArray::try_from_strings(&[Some("x"), None, Some("y")]).unwrap();
+        // the same values with a different dictionary
+        let b = Int8DictionaryArray::try_new(
+            &Int8Array::from(vec![Some(1), None, Some(0)]),
+            Arc::new(BinaryArray::from(vec!["y", "x"])),
+        )
+        .unwrap();
+        assert!(a.equals(&b));
+        let c =
+            Int8DictionaryArray::try_from_strings(&[Some("x"), None, Some("x")]).unwrap();
+        assert!(!a.equals(&c));
+
+        let c = eq_any(&a, &c).unwrap();
+        assert!(c.value(0));
+        assert!(c.is_null(1));
+        assert!(!c.value(2));
+    }
+
+    #[test]
+    fn test_eq_any() {
+        let a = list_array(vec![Some(vec![1, 2]), None, Some(vec![3]), Some(vec![])]);
+        let b = list_array(vec![
+            Some(vec![1, 2]),
+            Some(vec![]),
+            Some(vec![4]),
+            Some(vec![]),
+        ]);
+        let c = eq_any(&a, &b).unwrap();
+        assert_eq!(4, c.len());
+        assert!(c.value(0));
+        assert!(c.is_null(1));
+        assert!(!c.value(2));
+        assert!(c.value(3));
+        let c = neq_any(&a, &b).unwrap();
+        assert!(!c.value(0));
+        assert!(c.value(2));
+
+        assert!(eq_any(&a, &Int32Array::from(vec![1, 2, 3, 4])).is_err());
+        assert!(eq_any(&a, &list_array(vec![None])).is_err());
+    }
+
+    #[test]
+    fn test_eq_any_struct_with_timestamp() {
+        let timestamps = |values: Vec<i64>| {
+            let data_type =
+                DataType::Timestamp(TimeUnit::Nanosecond, Some("UTC".to_string()));
+            let data = ArrayData::builder(data_type.clone())
+                .len(values.len())
+                .add_buffer(Buffer::from(values.to_byte_slice()))
+                .build();
+            StructArray::from(vec![(Field::new("t", data_type, false), make_array(data))])
+        };
+        let a = timestamps(vec![1, 2]);
+        let b = timestamps(vec![1, 3]);
+        let c = eq_any(&a, &b).unwrap();
+        assert!(c.value(0));
+        assert!(!c.value(1));
+        assert!(a.equals(&a));
+        assert!(!a.equals(&b));
+
+        let a = IntervalDayTimeArray::from(vec![1, 2]);
+        let c = neq_any(&a, &IntervalDayTimeArray::from(vec![1, 1])).unwrap();
+        assert!(!c.value(0));
+        assert!(c.value(1));
+    }
+
+    #[test]
+    fn test_eq_any_unsupported_type() {
+        let fields = vec![Field::new("h", DataType::Float16, false)];
+        let a = StructArray::from(ArrayData::builder(DataType::Struct(fields)).build());
+        assert!(eq_any(&a, &a).is_err());
+    }
+}
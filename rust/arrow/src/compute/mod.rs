@@ -20,9 +20,13 @@
 pub mod arithmetic_kernels;
 pub mod array_ops;
 pub mod boolean_kernels;
+pub mod cast_kernels;
+pub mod equal_kernels;
 
 mod util;
 
 pub use self::arithmetic_kernels::*;
 pub use self::array_ops::*;
 pub use self::boolean_kernels::*;
+pub use self::cast_kernels::*;
+pub use self::equal_kernels::*;
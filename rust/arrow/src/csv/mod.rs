@@ -18,6 +18,9 @@
 //! Transfer data between the Arrow memory format and CSV (comma-separated values).
 
 pub mod reader;
+pub mod writer;
 
 pub use self::reader::Reader;
 pub use self::reader::ReaderBuilder;
+pub use self::writer::Writer;
+pub use self::writer::WriterBuilder;
@@ -50,11 +50,12 @@ use csv as csv_crate;
 
 use crate::array::ArrayRef;
 use crate::builder::*;
+use crate::compute;
 use crate::datatypes::*;
 use crate::error::{ArrowError, Result};
 use crate::record_batch::RecordBatch;
 
-use self::csv_crate::{StringRecord, StringRecordsIntoIter};
+use self::csv_crate::ByteRecord;
 
 lazy_static! {
     static ref DECIMAL_RE: Regex = Regex::new(r"^-?(\d+\.\d+)$").unwrap();
@@ -90,13 +91,12 @@ fn infer_field_schema(string: &str) -> DataType {
 /// If `max_read_records` is not set, the whole file is read to infer its schema.
 fn infer_file_schema<R: Read + Seek>(
     reader: &mut BufReader<R>,
-    delimiter: u8,
+    csv_builder: &csv::ReaderBuilder,
     max_read_records: Option<usize>,
     has_headers: bool,
+    null_values: &[String],
 ) -> Result<Schema> {
-    let mut csv_reader = csv::ReaderBuilder::new()
-        .delimiter(delimiter)
-        .from_reader(reader);
+    let mut csv_reader = csv_builder.from_reader(reader);
 
     // get or create header names
     // when has_headers is false, creates default column names with column_ prefix
@@ -135,7 +135,7 @@ fn infer_file_schema<R: Read + Seek>(
             let string: Option<&str> = record.get(i);
             match string {
                 Some(s) => {
-                    if s == "" {
+                    if s == "" || null_values.iter().any(|null| null == s) {
                         nulls[i] = true;
                     } else {
                         column_types[i].insert(infer_field_schema(s));
@@ -188,9 +188,14 @@ pub struct Reader<R: Read> {
     /// Optional projection for which columns to load (zero-based column indices)
     projection: Option<Vec<usize>>,
     /// File reader
-    record_iter: StringRecordsIntoIter<BufReader<R>>,
+    reader: csv_crate::Reader<BufReader<R>>,
+    /// Records that are reused for each batch, so that reading rows does not allocate
+    rows: Vec<ByteRecord>,
     /// Batch size (number of records to load each time)
     batch_size: usize,
+    /// Field values that are read as nulls, in addition to empty fields in columns that
+    /// are not strings
+    null_values: Vec<String>,
 }
 
 impl<R: Read> Reader<R> {
@@ -226,38 +231,41 @@ impl<R: Read> Reader<R> {
         batch_size: usize,
         projection: Option<Vec<usize>>,
     ) -> Self {
-        let csv_reader = csv::ReaderBuilder::new()
+        let reader = csv::ReaderBuilder::new()
             .has_headers(has_headers)
             .from_reader(buf_reader);
-        let record_iter = csv_reader.into_records();
         Self {
             schema,
             projection,
-            record_iter,
+            reader,
+            rows: vec![],
             batch_size,
+            null_values: vec![],
         }
     }
 
     /// Read the next batch of rows
     pub fn next(&mut self) -> Result<Option<RecordBatch>> {
-        // read a batch of rows into memory
-        let mut rows: Vec<StringRecord> = Vec::with_capacity(self.batch_size);
-        for _ in 0..self.batch_size {
-            match self.record_iter.next() {
-                Some(Ok(r)) => {
-                    rows.push(r);
-                }
-                Some(Err(_)) => {
+        // read a batch of rows into memory, only splitting them into fields so that
+        // columns that are not projected are never converted
+        let mut num_rows = 0;
+        while num_rows < self.batch_size {
+            if num_rows == self.rows.len() {
+                self.rows.push(ByteRecord::new());
+            }
+            match self.reader.read_byte_record(&mut self.rows[num_rows]) {
+                Ok(true) => num_rows += 1,
+                Ok(false) => break,
+                Err(_) => {
                     return Err(ArrowError::ParseError(
                         "Error reading CSV file".to_string(),
                     ));
                 }
-                None => break,
             }
         }
 
         // return early if no data was loaded
-        if rows.is_empty() {
+        if num_rows == 0 {
             return Ok(None);
         }
 
@@ -272,7 +280,7 @@ impl<R: Read> Reader<R> {
                 .collect(),
         };
 
-        let rows = &rows[..];
+        let rows = &self.rows[..num_rows];
         let arrays: Result<Vec<ArrayRef>> = projection
             .iter()
             .map(|i| {
@@ -301,12 +309,17 @@ impl<R: Read> Reader<R> {
                     &DataType::Float64 => {
                         self.build_primitive_array::<Float64Type>(rows, i)
                     }
+                    &DataType::Date(_) | &DataType::Time32(_) | &DataType::Time64(_) => {
+                        self.build_temporal_array(rows, i, field.data_type())
+                    }
                     &DataType::Utf8 => {
                         let mut builder = BinaryBuilder::new(rows.len());
-                        for row_index in 0..rows.len() {
-                            match rows[row_index].get(*i) {
-                                Some(s) => builder.append_string(s).unwrap(),
-                                _ => builder.append(false).unwrap(),
+                        for row in rows {
+                            match get_str(row, *i)? {
+                                Some(s) if !self.is_null(s) => {
+                                    builder.append_string(s)?
+                                }
+                                _ => builder.append(false)?,
                             }
                         }
                         Ok(Arc::new(builder.finish()) as ArrayRef)
@@ -336,15 +349,15 @@ impl<R: Read> Reader<R> {
 
     fn build_primitive_array<T: ArrowPrimitiveType>(
         &self,
-        rows: &[StringRecord],
+        rows: &[ByteRecord],
         col_idx: &usize,
     ) -> Result<ArrayRef> {
         let mut builder = PrimitiveBuilder::<T>::new(rows.len());
         let is_boolean_type =
             *self.schema.field(*col_idx).data_type() == DataType::Boolean;
-        for row_index in 0..rows.len() {
-            match rows[row_index].get(*col_idx) {
-                Some(s) if s.len() > 0 => {
+        for row in rows {
+            match get_str(row, *col_idx)? {
+                Some(s) if s.len() > 0 && !self.is_null(s) => {
                     let t = if is_boolean_type {
                         s.to_lowercase().parse::<T::Native>()
                     } else {
@@ -366,6 +379,45 @@ impl<R: Read> Reader<R> {
         }
         Ok(Arc::new(builder.finish()))
     }
+
+    /// Build a date or time array by parsing the field values with the cast kernel,
+    /// which reads dates as `YYYY-MM-DD` and times as `HH:MM:SS[.fraction]`
+    fn build_temporal_array(
+        &self,
+        rows: &[ByteRecord],
+        col_idx: &usize,
+        data_type: &DataType,
+    ) -> Result<ArrayRef> {
+        let mut builder = BinaryBuilder::new(rows.len());
+        for row in rows {
+            match get_str(row, *col_idx)? {
+                Some(s) if s.len() > 0 && !self.is_null(s) => builder.append_string(s)?,
+                _ => builder.append_null()?,
+            }
+        }
+        let strings: ArrayRef = Arc::new(builder.finish());
+        compute::cast(&strings, data_type)
+    }
+
+    /// Whether a field value is one of the values that are read as nulls
+    fn is_null(&self, value: &str) -> bool {
+        self.null_values.iter().any(|null| null == value)
+    }
+}
+
+/// Get a field of a record as a string, so that only the fields that are read are
+/// validated as UTF-8
+fn get_str(row: &ByteRecord, i: usize) -> Result<Option<&str>> {
+    match row.get(i) {
+        Some(bytes) => match std::str::from_utf8(bytes) {
+            Ok(s) => Ok(Some(s)),
+            Err(_) => Err(ArrowError::ParseError(format!(
+                "Invalid UTF-8 in column {}",
+                i
+            ))),
+        },
+        None => Ok(None),
+    }
 }
 
 /// CSV file reader builder
@@ -382,6 +434,16 @@ pub struct ReaderBuilder {
     has_headers: bool,
     /// An optional column delimiter. Defauits to `b','`
     delimiter: Option<u8>,
+    /// An optional quote character. Defaults to `b'\"'`
+    quote: Option<u8>,
+    /// An optional escape character for quotes within quoted fields
+    ///
+    /// If no escape character is set, quotes are escaped by doubling them.
+    escape: Option<u8>,
+    /// An optional comment character
+    ///
+    /// Lines that start with the comment character are skipped.
+    comment: Option<u8>,
     /// Optional maximum number of records to read during schema inference
     ///
     /// If a number is not provided, all the records are read.
@@ -390,6 +452,10 @@ pub struct ReaderBuilder {
     ///
     /// The default batch size when using the `ReaderBuilder` is 1024 records
     batch_size: usize,
+    /// Field values that are read as nulls, such as `NULL` or `\N`
+    ///
+    /// Empty fields are always read as nulls in columns that are not strings.
+    null_values: Vec<String>,
     /// Optional projection for which columns to load (zero-based column indices)
     projection: Option<Vec<usize>>,
 }
@@ -400,9 +466,13 @@ impl Default for ReaderBuilder {
             schema: None,
             has_headers: false,
             delimiter: None,
+            quote: None,
+            escape: None,
+            comment: None,
             max_records: None,
             batch_size: 1024,
             projection: None,
+            null_values: vec![],
         }
     }
 }
@@ -453,6 +523,31 @@ impl ReaderBuilder {
         self
     }
 
+    /// Set the CSV file's quote character as a byte character
+    pub fn with_quote(mut self, quote: u8) -> Self {
+        self.quote = Some(quote);
+        self
+    }
+
+    /// Set the CSV file's escape character as a byte character
+    pub fn with_escape(mut self, escape: u8) -> Self {
+        self.escape = Some(escape);
+        self
+    }
+
+    /// Set the CSV file's comment character as a byte character, so that lines
+    /// starting with it are skipped
+    pub fn with_comment(mut self, comment: u8) -> Self {
+        self.comment = Some(comment);
+        self
+    }
+
+    /// Set the field values that are read as nulls, such as `NULL` or an empty string
+    pub fn with_null_values(mut self, null_values: Vec<String>) -> Self {
+        self.null_values = null_values;
+        self
+    }
+
     /// Set the CSV reader to infer the schema of the file
     pub fn infer_schema(mut self, max_records: Option<usize>) -> Self {
         // remove any schema that is set
@@ -473,6 +568,17 @@ impl ReaderBuilder {
         self
     }
 
+    /// Create a builder for the underlying CSV parser with the configured format
+    fn csv_builder(&self) -> csv::ReaderBuilder {
+        let mut builder = csv::ReaderBuilder::new();
+        builder
+            .delimiter(self.delimiter.unwrap_or(b','))
+            .quote(self.quote.unwrap_or(b'"'))
+            .escape(self.escape)
+            .comment(self.comment);
+        builder
+    }
+
     /// Create a new `Reader` from the `ReaderBuilder`
     pub fn build<R: Read + Seek>(self, reader: R) -> Result<Reader<R>> {
         // check if schema should be inferred
@@ -482,25 +588,48 @@ impl ReaderBuilder {
             None => {
                 let inferred_schema = infer_file_schema(
                     &mut buf_reader,
-                    self.delimiter.unwrap_or(b','),
+                    &self.csv_builder(),
                     self.max_records,
                     self.has_headers,
+                    &self.null_values,
                 )?;
 
                 Arc::new(inferred_schema)
             }
         };
-        let csv_reader = csv::ReaderBuilder::new()
-            .delimiter(self.delimiter.unwrap_or(b','))
+        Ok(self.build_with_schema(buf_reader, schema))
+    }
+
+    /// Create a new `Reader` from the `ReaderBuilder` for an input that cannot seek,
+    /// such as a decompression stream. The schema must be set, because inferring it
+    /// requires reading the input twice.
+    pub fn build_unseekable<R: Read>(self, reader: R) -> Result<Reader<R>> {
+        match self.schema.clone() {
+            Some(schema) => Ok(self.build_with_schema(BufReader::new(reader), schema)),
+            None => Err(ArrowError::ParseError(
+                "A schema is required to read CSV from an input that cannot seek"
+                    .to_string(),
+            )),
+        }
+    }
+
+    fn build_with_schema<R: Read>(
+        self,
+        buf_reader: BufReader<R>,
+        schema: Arc<Schema>,
+    ) -> Reader<R> {
+        let reader = self
+            .csv_builder()
             .has_headers(self.has_headers)
             .from_reader(buf_reader);
-        let record_iter = csv_reader.into_records();
-        Ok(Reader {
+        Reader {
             schema,
-            projection: self.projection.clone(),
-            record_iter,
+            projection: self.projection,
+            reader,
+            rows: vec![],
             batch_size: self.batch_size,
-        })
+            null_values: self.null_values,
+        }
     }
 }
 
@@ -715,4 +844,166 @@ mod tests {
         assert_eq!(false, batch.column(1).is_null(3));
         assert_eq!(false, batch.column(1).is_null(4));
     }
+
+    #[test]
+    fn test_csv_with_format_options() {
+        let data = "# a comment\n'a\\'b';1\n'c;d';2\n";
+        let schema = Schema::new(vec![
+            Field::new("c1", DataType::Utf8, false),
+            Field::new("c2", DataType::Int64, false),
+        ]);
+
+        let builder = ReaderBuilder::new()
+            .with_schema(Arc::new(schema))
+            .with_delimiter(b';')
+            .with_quote(b'\'')
+            .with_escape(b'\\')
+            .with_comment(b'#');
+
+        let mut csv = builder.build(Cursor::new(data)).unwrap();
+        let batch = csv.next().unwrap().unwrap();
+        assert_eq!(2, batch.num_rows());
+
+        let c1 = batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<BinaryArray>()
+            .unwrap();
+        assert_eq!("a'b", c1.get_string(0));
+        assert_eq!("c;d", c1.get_string(1));
+
+        let c2 = batch
+            .column(1)
+            .as_any()
+            .downcast_ref::<Int64Array>()
+            .unwrap();
+        assert_eq!(1, c2.value(0));
+        assert_eq!(2, c2.value(1));
+    }
+
+    #[test]
+    fn test_csv_from_unseekable_input() {
+        let data = "1,2\n3,4\n".as_bytes();
+        let schema = Schema::new(vec![
+            Field::new("a", DataType::Int64, false),
+            Field::new("b", DataType::Int64, false),
+        ]);
+
+        let builder = ReaderBuilder::new().with_schema(Arc::new(schema));
+        let mut csv = builder.build_unseekable(data).unwrap();
+        let batch = csv.next().unwrap().unwrap();
+        assert_eq!(2, batch.num_rows());
+        assert_eq!(2, batch.num_columns());
+
+        assert!(ReaderBuilder::new().build_unseekable(data).is_err());
+    }
+
+    #[test]
+    fn test_csv_skips_unprojected_columns() {
+        // the second column is not valid UTF-8, but it is never read
+        let mut data = b"1,".to_vec();
+        data.extend_from_slice(&[0xff, 0xfe]);
+        data.extend_from_slice(b",x\n2,y,z\n3,,w\n");
+        let schema = Schema::new(vec![
+            Field::new("a", DataType::Int64, false),
+            Field::new("b", DataType::Utf8, false),
+            Field::new("c", DataType::Utf8, false),
+        ]);
+
+        let builder = ReaderBuilder::new()
+            .with_schema(Arc::new(schema))
+            .with_projection(vec![2, 0])
+            .with_batch_size(2);
+        let mut csv = builder.build(Cursor::new(data)).unwrap();
+
+        let batch = csv.next().unwrap().unwrap();
+        assert_eq!(2, batch.num_rows());
+        assert_eq!(2, batch.num_columns());
+        let c = batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<BinaryArray>()
+            .unwrap();
+        assert_eq!("x", c.get_string(0));
+        assert_eq!("z", c.get_string(1));
+
+        let batch = csv.next().unwrap().unwrap();
+        assert_eq!(1, batch.num_rows());
+        let a = batch
+            .column(1)
+            .as_any()
+            .downcast_ref::<Int64Array>()
+            .unwrap();
+        assert_eq!(3, a.value(0));
+
+        assert!(csv.next().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_csv_with_null_values() {
+        let data = "a,NULL,1.5\nNA,\\N,\nc,x,NA\n";
+        let schema = Schema::new(vec![
+            Field::new("c1", DataType::Utf8, true),
+            Field::new("c2", DataType::Utf8, true),
+            Field::new("c3", DataType::Float64, true),
+        ]);
+
+        let builder = ReaderBuilder::new()
+            .with_schema(Arc::new(schema))
+            .with_null_values(vec![
+                "NULL".to_string(),
+                "\\N".to_string(),
+                "NA".to_string(),
+            ]);
+        let mut csv = builder.build(Cursor::new(data)).unwrap();
+        let batch = csv.next().unwrap().unwrap();
+        assert_eq!(3, batch.num_rows());
+
+        assert_eq!(false, batch.column(0).is_null(0));
+        assert_eq!(true, batch.column(0).is_null(1));
+        assert_eq!(true, batch.column(1).is_null(0));
+        assert_eq!(true, batch.column(1).is_null(1));
+        assert_eq!(false, batch.column(1).is_null(2));
+        assert_eq!(false, batch.column(2).is_null(0));
+        assert_eq!(true, batch.column(2).is_null(1));
+        assert_eq!(true, batch.column(2).is_null(2));
+        assert_eq!(2, batch.column(2).null_count());
+    }
+
+    #[test]
+    fn test_csv_with_temporal_types() {
+        let data = "2019-01-01,12:34:56.5\n1969-12-31,\n,00:00:00\n";
+        let schema = Schema::new(vec![
+            Field::new("d", DataType::Date(DateUnit::Day), true),
+            Field::new("t", DataType::Time64(TimeUnit::Microsecond), true),
+        ]);
+
+        let builder = ReaderBuilder::new().with_schema(Arc::new(schema));
+        let mut csv = builder.build(Cursor::new(data)).unwrap();
+        let batch = csv.next().unwrap().unwrap();
+        assert_eq!(3, batch.num_rows());
+
+        let d = batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<Date32Array>()
+            .unwrap();
+        assert_eq!(17897, d.value(0));
+        assert_eq!(-1, d.value(1));
+        assert!(d.is_null(2));
+        let t = batch
+            .column(1)
+            .as_any()
+            .downcast_ref::<Time64MicrosecondArray>()
+            .unwrap();
+        assert_eq!(45296500000, t.value(0));
+        assert!(t.is_null(1));
+        assert_eq!(0, t.value(2));
+
+        let schema =
+            Schema::new(vec![Field::new("d", DataType::Date(DateUnit::Day), true)]);
+        let builder = ReaderBuilder::new().with_schema(Arc::new(schema));
+        let mut csv = builder.build(Cursor::new("2019-02-29\n")).unwrap();
+        assert!(csv.next().is_err());
+    }
 }
@@ -0,0 +1,415 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! CSV Writer
+//!
+//! This CSV writer allows Arrow data (in record batches) to be written as CSV files.
+//! Records are written row by row, with the field names of the schema as the header.
+//!
+//! Example:
+//!
+//! ```
+//! use arrow::array::*;
+//! use arrow::csv;
+//! use arrow::datatypes::*;
+//! use arrow::record_batch::RecordBatch;
+//! use std::sync::Arc;
+//!
+//! let schema = Schema::new(vec![
+//!     Field::new("city", DataType::Utf8, false),
+//!     Field::new("population", DataType::UInt32, true),
+//! ]);
+//! let batch = RecordBatch::new(
+//!     Arc::new(schema),
+//!     vec![
+//!         Arc::new(BinaryArray::from(vec!["Elgin, Scotland", "Stoke-on-Trent"])),
+//!         Arc::new(UInt32Array::from(vec![Some(23000), None])),
+//!     ],
+//! );
+//!
+//! let mut writer = csv::Writer::new(vec![]);
+//! writer.write(&batch).unwrap();
+//! let output = writer.into_inner().unwrap();
+//! ```
+
+use std::io::Write;
+
+use csv as csv_crate;
+
+use crate::array::*;
+use crate::datatypes::*;
+use crate::error::{ArrowError, Result};
+use crate::record_batch::RecordBatch;
+use crate::util::temporal_util::{
+    floor_div, format_date, format_time, MILLISECONDS_PER_DAY,
+};
+
+/// Converts the value at `row` of a primitive array to a string
+macro_rules! primitive_value {
+    ($ARRAY:expr, $ARRAY_TYPE:ident, $ROW:expr) => {{
+        let array = $ARRAY.as_any().downcast_ref::<$ARRAY_TYPE>().unwrap();
+        array.value($ROW).to_string()
+    }};
+}
+
+/// Converts the value at `row` of a time array to a string
+macro_rules! time_value {
+    ($ARRAY:expr, $ARRAY_TYPE:ident, $ROW:expr, $UNIT:expr) => {{
+        let array = $ARRAY.as_any().downcast_ref::<$ARRAY_TYPE>().unwrap();
+        format_time(i64::from(array.value($ROW)), $UNIT)
+    }};
+}
+
+/// CSV file writer
+pub struct Writer<W: Write> {
+    /// The underlying CSV writer
+    writer: csv_crate::Writer<W>,
+    /// Whether to write the field names as a header before the first batch
+    has_headers: bool,
+    /// The string that null values are written as
+    null_value: String,
+    /// Whether the header is still to be written
+    header_pending: bool,
+}
+
+impl<W: Write> Writer<W> {
+    /// Create a new CSV writer with the default options, writing a header and null
+    /// values as empty fields.
+    ///
+    /// To customise the writer, such as to change the delimiter, use `WriterBuilder`.
+    pub fn new(writer: W) -> Self {
+        WriterBuilder::new().build(writer)
+    }
+
+    /// Write a record batch. The header is written before the first batch if enabled,
+    /// so all batches must have the schema of the first batch.
+    pub fn write(&mut self, batch: &RecordBatch) -> Result<()> {
+        // check the types before writing anything, so that no partial rows are written
+        for field in batch.schema().fields() {
+            match field.data_type() {
+                DataType::Boolean
+                | DataType::Int8
+                | DataType::Int16
+                | DataType::Int32
+                | DataType::Int64
+                | DataType::UInt8
+                | DataType::UInt16
+                | DataType::UInt32
+                | DataType::UInt64
+                | DataType::Float32
+                | DataType::Float64
+                | DataType::Date(_)
+                | DataType::Time32(TimeUnit::Second)
+                | DataType::Time32(TimeUnit::Millisecond)
+                | DataType::Time64(TimeUnit::Microsecond)
+                | DataType::Time64(TimeUnit::Nanosecond)
+                | DataType::Utf8 => {}
+                other => {
+                    return Err(ArrowError::CsvError(format!(
+                        "CSV Writer does not support {:?} data type",
+                        other
+                    )));
+                }
+            }
+        }
+
+        if self.header_pending {
+            if self.has_headers {
+                let names: Vec<&String> =
+                    batch.schema().fields().iter().map(|f| f.name()).collect();
+                self.writer.write_record(&names)?;
+            }
+            self.header_pending = false;
+        }
+
+        let mut record: Vec<String> = Vec::with_capacity(batch.num_columns());
+        for row in 0..batch.num_rows() {
+            record.clear();
+            for i in 0..batch.num_columns() {
+                record.push(self.value_to_string(batch.column(i), row));
+            }
+            self.writer.write_record(&record)?;
+        }
+        Ok(())
+    }
+
+    /// Flush the buffered records to the underlying writer
+    pub fn flush(&mut self) -> Result<()> {
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    /// Flush the buffered records and return the underlying writer
+    pub fn into_inner(self) -> Result<W> {
+        self.writer.into_inner().map_err(|e| {
+            ArrowError::CsvError(format!("Failed to flush the CSV writer: {}", e))
+        })
+    }
+
+    fn value_to_string(&self, array: &ArrayRef, row: usize) -> String {
+        if array.is_null(row) {
+            return self.null_value.clone();
+        }
+        match array.data_type() {
+            DataType::Boolean => primitive_value!(array, BooleanArray, row),
+            DataType::Int8 => primitive_value!(array, Int8Array, row),
+            DataType::Int16 => primitive_value!(array, Int16Array, row),
+            DataType::Int32 => primitive_value!(array, Int32Array, row),
+            DataType::Int64 => primitive_value!(array, Int64Array, row),
+            DataType::UInt8 => primitive_value!(array, UInt8Array, row),
+            DataType::UInt16 => primitive_value!(array, UInt16Array, row),
+            DataType::UInt32 => primitive_value!(array, UInt32Array, row),
+            DataType::UInt64 => primitive_value!(array, UInt64Array, row),
+            DataType::Float32 => primitive_value!(array, Float32Array, row),
+            DataType::Float64 => primitive_value!(array, Float64Array, row),
+            DataType::Date(DateUnit::Day) => {
+                let array = array.as_any().downcast_ref::<Date32Array>().unwrap();
+                format_date(i64::from(array.value(row)))
+            }
+            DataType::Date(DateUnit::Millisecond) => {
+                let array = array.as_any().downcast_ref::<Date64Array>().unwrap();
+                format_date(floor_div(array.value(row), MILLISECONDS_PER_DAY))
+            }
+            DataType::Time32(unit @ TimeUnit::Second) => {
+                time_value!(array, Time32SecondArray, row, unit)
+            }
+            DataType::Time32(unit @ TimeUnit::Millisecond) => {
+                time_value!(array, Time32MillisecondArray, row, unit)
+            }
+            DataType::Time64(unit @ TimeUnit::Microsecond) => {
+                time_value!(array, Time64MicrosecondArray, row, unit)
+            }
+            DataType::Time64(unit @ TimeUnit::Nanosecond) => {
+                time_value!(array, Time64NanosecondArray, row, unit)
+            }
+            DataType::Utf8 => {
+                let array = array.as_any().downcast_ref::<BinaryArray>().unwrap();
+                array.get_string(row)
+            }
+            other => unreachable!("unsupported data type {:?}", other),
+        }
+    }
+}
+
+/// CSV file writer builder
+pub struct WriterBuilder {
+    /// Whether to write the field names as a header. Defaults to `true`
+    has_headers: bool,
+    /// An optional column delimiter. Defaults to `b','`
+    delimiter: Option<u8>,
+    /// An optional quote character. Defaults to `b'\"'`
+    quote: Option<u8>,
+    /// The string that null values are written as. Defaults to an empty string
+    null_value: String,
+}
+
+impl Default for WriterBuilder {
+    fn default() -> WriterBuilder {
+        WriterBuilder {
+            has_headers: true,
+            delimiter: None,
+            quote: None,
+            null_value: String::new(),
+        }
+    }
+}
+
+impl WriterBuilder {
+    /// Create a new builder for configuring CSV writing options.
+    ///
+    /// To convert a builder into a writer, call `WriterBuilder::build`
+    pub fn new() -> WriterBuilder {
+        WriterBuilder::default()
+    }
+
+    /// Set whether to write the field names as a header
+    pub fn has_headers(mut self, has_headers: bool) -> Self {
+        self.has_headers = has_headers;
+        self
+    }
+
+    /// Set the CSV file's column delimiter as a byte character
+    pub fn with_delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = Some(delimiter);
+        self
+    }
+
+    /// Set the CSV file's quote character as a byte character
+    pub fn with_quote(mut self, quote: u8) -> Self {
+        self.quote = Some(quote);
+        self
+    }
+
+    /// Set the string that null values are written as, such as `NULL` or `\N`
+    pub fn with_null_value(mut self, null_value: &str) -> Self {
+        self.null_value = null_value.to_string();
+        self
+    }
+
+    /// Create a new `Writer` from the `WriterBuilder`
+    pub fn build<W: Write>(self, writer: W) -> Writer<W> {
+        let writer = csv_crate::WriterBuilder::new()
+            .delimiter(self.delimiter.unwrap_or(b','))
+            .quote(self.quote.unwrap_or(b'"'))
+            .from_writer(writer);
+        Writer {
+            writer,
+            has_headers: self.has_headers,
+            null_value: self.null_value,
+            header_pending: true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::io::Cursor;
+    use std::sync::Arc;
+
+    use crate::csv::ReaderBuilder;
+
+    fn test_batch() -> RecordBatch {
+        let schema = Schema::new(vec![
+            Field::new("c1", DataType::Utf8, false),
+            Field::new("c2", DataType::Float64, true),
+            Field::new("c3", DataType::UInt32, false),
+            Field::new("c4", DataType::Boolean, true),
+        ]);
+        RecordBatch::new(
+            Arc::new(schema),
+            vec![
+                Arc::new(BinaryArray::from(vec![
+                    "Lorem, ipsum",
+                    "consectetur",
+                    "\"a\"",
+                ])),
+                Arc::new(Float64Array::from(vec![Some(123.564532), None, Some(-0.5)])),
+                Arc::new(UInt32Array::from(vec![3, 2, 1])),
+                Arc::new(BooleanArray::from(vec![Some(true), Some(false), None])),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_write_csv() {
+        let batch = test_batch();
+        let mut writer = Writer::new(vec![]);
+        writer.write(&batch).unwrap();
+        writer.write(&batch).unwrap();
+        let output = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+
+        let expected = "c1,c2,c3,c4\n\
+                        \"Lorem, ipsum\",123.564532,3,true\n\
+                        consectetur,,2,false\n\
+                        \"\"\"a\"\"\",-0.5,1,\n";
+        assert_eq!(format!("{}{}", expected, &expected[12..]), output);
+    }
+
+    #[test]
+    fn test_write_csv_with_options() {
+        let batch = test_batch();
+        let mut writer = WriterBuilder::new()
+            .has_headers(false)
+            .with_delimiter(b'|')
+            .with_null_value("NULL")
+            .build(vec![]);
+        writer.write(&batch).unwrap();
+        let output = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+
+        let expected = "Lorem, ipsum|123.564532|3|true\n\
+                        consectetur|NULL|2|false\n\
+                        \"\"\"a\"\"\"|-0.5|1|NULL\n";
+        assert_eq!(expected, output);
+    }
+
+    #[test]
+    fn test_write_csv_round_trip() {
+        let batch = test_batch();
+        let mut writer = Writer::new(vec![]);
+        writer.write(&batch).unwrap();
+        let output = writer.into_inner().unwrap();
+
+        let mut reader = ReaderBuilder::new()
+            .with_schema(batch.schema().clone())
+            .has_headers(true)
+            .build(Cursor::new(output))
+            .unwrap();
+        let read = reader.next().unwrap().unwrap();
+        assert_eq!(3, read.num_rows());
+        let c1 = read
+            .column(0)
+            .as_any()
+            .downcast_ref::<BinaryArray>()
+            .unwrap();
+        assert_eq!("Lorem, ipsum", c1.get_string(0));
+        assert_eq!("\"a\"", c1.get_string(2));
+        let c2 = read
+            .column(1)
+            .as_any()
+            .downcast_ref::<Float64Array>()
+            .unwrap();
+        assert_eq!(123.564532, c2.value(0));
+        assert!(c2.is_null(1));
+        let c4 = read
+            .column(3)
+            .as_any()
+            .downcast_ref::<BooleanArray>()
+            .unwrap();
+        assert!(c4.is_null(2));
+    }
+
+    #[test]
+    fn test_write_temporal_types() {
+        let schema = Schema::new(vec![
+            Field::new("d32", DataType::Date(DateUnit::Day), true),
+            Field::new("d64", DataType::Date(DateUnit::Millisecond), false),
+            Field::new("t32", DataType::Time32(TimeUnit::Second), false),
+            Field::new("t64", DataType::Time64(TimeUnit::Nanosecond), false),
+        ]);
+        let batch = RecordBatch::new(
+            Arc::new(schema),
+            vec![
+                Arc::new(Date32Array::from(vec![Some(17897), None])),
+                Arc::new(Date64Array::from(vec![1546300800000, -1])),
+                Arc::new(Time32SecondArray::from(vec![45296, 0])),
+                Arc::new(Time64NanosecondArray::from(vec![45296000000001, 0])),
+            ],
+        );
+        let mut writer = Writer::new(vec![]);
+        writer.write(&batch).unwrap();
+        let output = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+
+        let expected = "d32,d64,t32,t64\n\
+                        2019-01-01,2019-01-01,12:34:56,12:34:56.000000001\n\
+                        ,1969-12-31,00:00:00,00:00:00.000000000\n";
+        assert_eq!(expected, output);
+    }
+
+    #[test]
+    fn test_write_unsupported_type() {
+        let schema = Schema::new(vec![Field::new("b", DataType::Binary, false)]);
+        let batch = RecordBatch::new(
+            Arc::new(schema),
+            vec![Arc::new(BinaryArray::from(vec!["a"]))],
+        );
+        let mut writer = Writer::new(vec![]);
+        assert!(writer.write(&batch).is_err());
+        assert!(writer.into_inner().unwrap().is_empty());
+    }
+}
@@ -21,6 +21,7 @@
 //! information regarding data-types and memory layouts see
 //! [here](https://arrow.apache.org/docs/memory_layout.html).
 
+use std::collections::BTreeMap;
 use std::fmt;
 use std::mem::size_of;
 use std::ops::{Add, Div, Mul, Sub};
@@ -39,13 +40,17 @@ use crate::error::{ArrowError, Result};
 /// nested types.
 /// Currently the Rust implementation supports the following  nested types:
 ///  - `List<T>`
+///  - `FixedSizeList<T>`
 ///  - `Struct<T, U, V, ...>`
+///  - `Union<T, U, V, ...>`
 ///
 /// Nested types can themselves be nested within other arrays.
 /// For more information on these types please see
 /// [here](https://arrow.apache.org/docs/memory_layout.html).
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum DataType {
+    /// The type of arrays whose values are all null, which have no buffers
+    Null,
     Boolean,
     Int8,
     Int16,
@@ -58,14 +63,27 @@ pub enum DataType {
     Float16,
     Float32,
     Float64,
-    Timestamp(TimeUnit),
+    /// A timestamp with a unit and an optional timezone. Values are always stored
+    /// relative to the UNIX epoch in UTC; the timezone, such as `"America/New_York"`
+    /// or `"+05:30"`, describes how they should be displayed and interpreted.
+    Timestamp(TimeUnit, Option<String>),
     Date(DateUnit),
     Time32(TimeUnit),
     Time64(TimeUnit),
     Interval(IntervalUnit),
+    Binary,
     Utf8,
     List(Box<DataType>),
+    /// A list where every element has the same number of values, e.g. the
+    /// coordinates of a point or an embedding vector
+    FixedSizeList(Box<DataType>, i32),
     Struct(Vec<Field>),
+    /// A union of the types of its fields, where each value has the type of one of
+    /// them. The type id of a value is the index of its field.
+    Union(Vec<Field>, UnionMode),
+    /// Values that are stored as integer keys of the first type into a dictionary of
+    /// distinct values of the second type, such as a low-cardinality string column
+    Dictionary(Box<DataType>, Box<DataType>),
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
@@ -88,14 +106,25 @@ pub enum IntervalUnit {
     DayTime,
 }
 
+/// The layout of a union array. The children of a sparse union have a value for
+/// every slot of the union, while the children of a dense union only have the values
+/// of their own type, which the slots reference through value offsets.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum UnionMode {
+    Sparse,
+    Dense,
+}
+
 /// Contains the meta-data for a single relative type.
 ///
 /// The `Schema` object is an ordered collection of `Field` objects.
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct Field {
     name: String,
     data_type: DataType,
     nullable: bool,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    metadata: BTreeMap<String, String>,
 }
 
 pub trait ArrowNativeType:
@@ -120,10 +149,20 @@ pub trait ArrowPrimitiveType: 'static {
     fn default_value() -> Self::Native;
 }
 
-macro_rules! make_type {
-    ($name:ident, $native_ty:ty, $data_ty:path, $bit_width:expr, $default_val:expr) => {
-        impl ArrowNativeType for $native_ty {}
+impl ArrowNativeType for bool {}
+impl ArrowNativeType for i8 {}
+impl ArrowNativeType for i16 {}
+impl ArrowNativeType for i32 {}
+impl ArrowNativeType for i64 {}
+impl ArrowNativeType for u8 {}
+impl ArrowNativeType for u16 {}
+impl ArrowNativeType for u32 {}
+impl ArrowNativeType for u64 {}
+impl ArrowNativeType for f32 {}
+impl ArrowNativeType for f64 {}
 
+macro_rules! make_type {
+    ($name:ident, $native_ty:ty, $data_ty:expr, $bit_width:expr, $default_val:expr) => {
         pub struct $name {}
 
         impl ArrowPrimitiveType for $name {
@@ -155,6 +194,84 @@ make_type!(UInt32Type, u32, DataType::UInt32, 32, 0u32);
 make_type!(UInt64Type, u64, DataType::UInt64, 64, 0u64);
 make_type!(Float32Type, f32, DataType::Float32, 32, 0.0f32);
 make_type!(Float64Type, f64, DataType::Float64, 64, 0.0f64);
+make_type!(Date32Type, i32, DataType::Date(DateUnit::Day), 32, 0i32);
+make_type!(
+    Date64Type,
+    i64,
+    DataType::Date(DateUnit::Millisecond),
+    64,
+    0i64
+);
+make_type!(
+    Time32SecondType,
+    i32,
+    DataType::Time32(TimeUnit::Second),
+    32,
+    0i32
+);
+make_type!(
+    Time32MillisecondType,
+    i32,
+    DataType::Time32(TimeUnit::Millisecond),
+    32,
+    0i32
+);
+make_type!(
+    Time64MicrosecondType,
+    i64,
+    DataType::Time64(TimeUnit::Microsecond),
+    64,
+    0i64
+);
+make_type!(
+    Time64NanosecondType,
+    i64,
+    DataType::Time64(TimeUnit::Nanosecond),
+    64,
+    0i64
+);
+make_type!(
+    TimestampSecondType,
+    i64,
+    DataType::Timestamp(TimeUnit::Second, None),
+    64,
+    0i64
+);
+make_type!(
+    TimestampMillisecondType,
+    i64,
+    DataType::Timestamp(TimeUnit::Millisecond, None),
+    64,
+    0i64
+);
+make_type!(
+    TimestampMicrosecondType,
+    i64,
+    DataType::Timestamp(TimeUnit::Microsecond, None),
+    64,
+    0i64
+);
+make_type!(
+    TimestampNanosecondType,
+    i64,
+    DataType::Timestamp(TimeUnit::Nanosecond, None),
+    64,
+    0i64
+);
+make_type!(
+    IntervalYearMonthType,
+    i32,
+    DataType::Interval(IntervalUnit::YearMonth),
+    32,
+    0i32
+);
+make_type!(
+    IntervalDayTimeType,
+    i64,
+    DataType::Interval(IntervalUnit::DayTime),
+    64,
+    0i64
+);
 
 /// A subtype of primitive type that represents numeric values.
 ///
@@ -234,6 +351,57 @@ make_numeric_type!(UInt32Type, u32, u32x16);
 make_numeric_type!(UInt64Type, u64, u64x8);
 make_numeric_type!(Float32Type, f32, f32x16);
 make_numeric_type!(Float64Type, f64, f64x8);
+make_numeric_type!(Date32Type, i32, i32x16);
+make_numeric_type!(Date64Type, i64, i64x8);
+make_numeric_type!(Time32SecondType, i32, i32x16);
+make_numeric_type!(Time32MillisecondType, i32, i32x16);
+make_numeric_type!(Time64MicrosecondType, i64, i64x8);
+make_numeric_type!(Time64NanosecondType, i64, i64x8);
+make_numeric_type!(TimestampSecondType, i64, i64x8);
+make_numeric_type!(TimestampMillisecondType, i64, i64x8);
+make_numeric_type!(TimestampMicrosecondType, i64, i64x8);
+make_numeric_type!(TimestampNanosecondType, i64, i64x8);
+make_numeric_type!(IntervalYearMonthType, i32, i32x16);
+make_numeric_type!(IntervalDayTimeType, i64, i64x8);
+
+/// A primitive type whose values can be the keys of a dictionary array, which are the
+/// indices of values in the dictionary
+pub trait ArrowDictionaryKeyType: ArrowNumericType {
+    /// Convert a key to the index of its value in the dictionary. Negative keys are
+    /// converted to indices that are out of bounds.
+    fn to_index(key: Self::Native) -> usize;
+
+    /// Convert the index of a value in the dictionary to a key, or `None` if the index
+    /// is too large for this type
+    fn from_index(index: usize) -> Option<Self::Native>;
+}
+
+macro_rules! make_dictionary_key_type {
+    ($impl_ty:ty, $native_ty:ty) => {
+        impl ArrowDictionaryKeyType for $impl_ty {
+            fn to_index(key: $native_ty) -> usize {
+                key as usize
+            }
+
+            fn from_index(index: usize) -> Option<$native_ty> {
+                if index as u64 <= <$native_ty>::max_value() as u64 {
+                    Some(index as $native_ty)
+                } else {
+                    None
+                }
+            }
+        }
+    };
+}
+
+make_dictionary_key_type!(Int8Type, i8);
+make_dictionary_key_type!(Int16Type, i16);
+make_dictionary_key_type!(Int32Type, i32);
+make_dictionary_key_type!(Int64Type, i64);
+make_dictionary_key_type!(UInt8Type, u8);
+make_dictionary_key_type!(UInt16Type, u16);
+make_dictionary_key_type!(UInt32Type, u32);
+make_dictionary_key_type!(UInt64Type, u64);
 
 /// Allows conversion from supported Arrow types to a byte slice.
 pub trait ToByteSlice {
@@ -257,10 +425,12 @@ impl<T: ArrowNativeType> ToByteSlice for T {
 
 impl DataType {
     /// Parse a data type from a JSON representation
-    fn from(json: &Value) -> Result<DataType> {
+    pub fn from(json: &Value) -> Result<DataType> {
         match *json {
             Value::Object(ref map) => match map.get("name") {
+                Some(s) if s == "null" => Ok(DataType::Null),
                 Some(s) if s == "bool" => Ok(DataType::Boolean),
+                Some(s) if s == "binary" => Ok(DataType::Binary),
                 Some(s) if s == "utf8" => Ok(DataType::Utf8),
                 Some(s) if s == "floatingpoint" => match map.get("precision") {
                     Some(p) if p == "HALF" => Ok(DataType::Float16),
@@ -270,21 +440,25 @@ impl DataType {
                         "floatingpoint precision missing or invalid".to_string(),
                     )),
                 },
-                Some(s) if s == "timestamp" => match map.get("unit") {
-                    Some(p) if p == "SECOND" => Ok(DataType::Timestamp(TimeUnit::Second)),
-                    Some(p) if p == "MILLISECOND" => {
-                        Ok(DataType::Timestamp(TimeUnit::Millisecond))
-                    }
-                    Some(p) if p == "MICROSECOND" => {
-                        Ok(DataType::Timestamp(TimeUnit::Microsecond))
-                    }
-                    Some(p) if p == "NANOSECOND" => {
-                        Ok(DataType::Timestamp(TimeUnit::Nanosecond))
-                    }
-                    _ => Err(ArrowError::ParseError(
-                        "timestamp unit missing or invalid".to_string(),
-                    )),
-                },
+                Some(s) if s == "timestamp" => {
+                    let unit = match map.get("unit") {
+                        Some(p) if p == "SECOND" => Ok(TimeUnit::Second),
+                        Some(p) if p == "MILLISECOND" => Ok(TimeUnit::Millisecond),
+                        Some(p) if p == "MICROSECOND" => Ok(TimeUnit::Microsecond),
+                        Some(p) if p == "NANOSECOND" => Ok(TimeUnit::Nanosecond),
+                        _ => Err(ArrowError::ParseError(
+                            "timestamp unit missing or invalid".to_string(),
+                        )),
+                    };
+                    let timezone = match map.get("timezone") {
+                        Some(Value::String(tz)) => Ok(Some(tz.clone())),
+                        Some(Value::Null) | None => Ok(None),
+                        _ => Err(ArrowError::ParseError(
+                            "timestamp timezone invalid".to_string(),
+                        )),
+                    };
+                    Ok(DataType::Timestamp(unit?, timezone?))
+                }
                 Some(s) if s == "date" => match map.get("unit") {
                     Some(p) if p == "DAY" => Ok(DataType::Date(DateUnit::Day)),
                     Some(p) if p == "MILLISECOND" => {
@@ -304,9 +478,11 @@ impl DataType {
                             "time unit missing or invalid".to_string(),
                         )),
                     };
+                    // the bit width is written as a string, but the integration format
+                    // uses a number
                     match map.get("bitWidth") {
-                        Some(p) if p == "32" => Ok(DataType::Time32(unit?)),
-                        Some(p) if p == "64" => Ok(DataType::Time32(unit?)),
+                        Some(p) if p == "32" || p == 32 => Ok(DataType::Time32(unit?)),
+                        Some(p) if p == "64" || p == 64 => Ok(DataType::Time64(unit?)),
                         _ => Err(ArrowError::ParseError(
                             "time bitWidth missing or invalid".to_string(),
                         )),
@@ -329,7 +505,7 @@ impl DataType {
                             Some(8) => Ok(DataType::Int8),
                             Some(16) => Ok(DataType::Int16),
                             Some(32) => Ok(DataType::Int32),
-                            Some(64) => Ok(DataType::Int32),
+                            Some(64) => Ok(DataType::Int64),
                             _ => Err(ArrowError::ParseError(
                                 "int bitWidth missing or invalid".to_string(),
                             )),
@@ -380,6 +556,7 @@ impl DataType {
     /// Generate a JSON representation of the data type
     pub fn to_json(&self) -> Value {
         match self {
+            DataType::Null => json!({"name": "null"}),
             DataType::Boolean => json!({"name": "bool"}),
             DataType::Int8 => json!({"name": "int", "bitWidth": 8, "isSigned": true}),
             DataType::Int16 => json!({"name": "int", "bitWidth": 16, "isSigned": true}),
@@ -392,6 +569,7 @@ impl DataType {
             DataType::Float16 => json!({"name": "floatingpoint", "precision": "HALF"}),
             DataType::Float32 => json!({"name": "floatingpoint", "precision": "SINGLE"}),
             DataType::Float64 => json!({"name": "floatingpoint", "precision": "DOUBLE"}),
+            DataType::Binary => json!({"name": "binary"}),
             DataType::Utf8 => json!({"name": "utf8"}),
             DataType::Struct(ref fields) => {
                 let field_json_array = Value::Array(
@@ -403,6 +581,26 @@ impl DataType {
                 let child_json = t.to_json();
                 json!({ "name": "list", "children": child_json })
             }
+            DataType::Union(ref fields, ref mode) => {
+                let mode = match mode {
+                    UnionMode::Sparse => "SPARSE",
+                    UnionMode::Dense => "DENSE",
+                };
+                let type_ids = (0..fields.len()).collect::<Vec<usize>>();
+                let field_json_array = Value::Array(
+                    fields.iter().map(|f| f.to_json()).collect::<Vec<Value>>(),
+                );
+                json!({
+                    "name": "union",
+                    "mode": mode,
+                    "typeIds": type_ids,
+                    "children": field_json_array
+                })
+            }
+            DataType::FixedSizeList(ref t, size) => {
+                let child_json = t.to_json();
+                json!({ "name": "fixedsizelist", "listSize": size, "children": child_json })
+            }
             DataType::Time32(unit) => {
                 json!({"name": "time", "bitWidth": "32", "unit": match unit {
                     TimeUnit::Second => "SECOND",
@@ -423,16 +621,27 @@ impl DataType {
                 DateUnit::Day => "DAY",
                 DateUnit::Millisecond => "MILLISECOND",
             }}),
-            DataType::Timestamp(unit) => json!({"name": "timestamp", "unit": match unit {
-                TimeUnit::Second => "SECOND",
-                TimeUnit::Millisecond => "MILLISECOND",
-                TimeUnit::Microsecond => "MICROSECOND",
-                TimeUnit::Nanosecond => "NANOSECOND",
-            }}),
+            DataType::Timestamp(unit, tz) => {
+                let unit = match unit {
+                    TimeUnit::Second => "SECOND",
+                    TimeUnit::Millisecond => "MILLISECOND",
+                    TimeUnit::Microsecond => "MICROSECOND",
+                    TimeUnit::Nanosecond => "NANOSECOND",
+                };
+                match tz {
+                    Some(tz) => {
+                        json!({"name": "timestamp", "unit": unit, "timezone": tz})
+                    }
+                    None => json!({"name": "timestamp", "unit": unit}),
+                }
+            }
             DataType::Interval(unit) => json!({"name": "interval", "unit": match unit {
                 IntervalUnit::YearMonth => "YEAR_MONTH",
                 IntervalUnit::DayTime => "DAY_TIME",
             }}),
+            // the type of a dictionary is the type of its values, and the type of its
+            // keys is written by `Field::to_json`
+            DataType::Dictionary(_, ref value_type) => value_type.to_json(),
         }
     }
 }
@@ -444,9 +653,16 @@ impl Field {
             name: name.to_string(),
             data_type,
             nullable,
+            metadata: BTreeMap::new(),
         }
     }
 
+    /// Returns the `Field` with its custom key-value metadata replaced by `metadata`
+    pub fn with_metadata(mut self, metadata: BTreeMap<String, String>) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
     /// Returns an immutable reference to the `Field`'s name
     pub fn name(&self) -> &String {
         &self.name
@@ -462,6 +678,11 @@ impl Field {
         self.nullable
     }
 
+    /// Returns an immutable reference to the `Field`'s custom key-value metadata
+    pub fn metadata(&self) -> &BTreeMap<String, String> {
+        &self.metadata
+    }
+
     /// Parse a `Field` definition from a JSON representation
     pub fn from(json: &Value) -> Result<Self> {
         match *json {
@@ -490,10 +711,30 @@ impl Field {
                         ));
                     }
                 };
+                let data_type = match map.get("dictionary") {
+                    Some(dictionary) => match dictionary.get("indexType") {
+                        Some(key_type) => DataType::Dictionary(
+                            Box::new(DataType::from(key_type)?),
+                            Box::new(data_type),
+                        ),
+                        None => {
+                            return Err(ArrowError::ParseError(
+                                "Field dictionary missing 'indexType' attribute"
+                                    .to_string(),
+                            ));
+                        }
+                    },
+                    None => data_type,
+                };
+                let metadata = match map.get("metadata") {
+                    Some(metadata) => metadata_from_json(metadata)?,
+                    None => BTreeMap::new(),
+                };
                 Ok(Field {
                     name,
                     nullable,
                     data_type,
+                    metadata,
                 })
             }
             _ => Err(ArrowError::ParseError(
@@ -504,11 +745,22 @@ impl Field {
 
     /// Generate a JSON representation of the `Field`
     pub fn to_json(&self) -> Value {
-        json!({
+        let mut json = json!({
             "name": self.name,
             "nullable": self.nullable,
             "type": self.data_type.to_json(),
-        })
+        });
+        if let DataType::Dictionary(ref key_type, _) = self.data_type {
+            json["dictionary"] = json!({
+                "id": 0,
+                "indexType": key_type.to_json(),
+                "isOrdered": false,
+            });
+        }
+        if !self.metadata.is_empty() {
+            json["metadata"] = metadata_to_json(&self.metadata);
+        }
+        json
     }
 
     /// Converts to a `String` representation of the the `Field`
@@ -517,6 +769,20 @@ impl Field {
     }
 }
 
+impl fmt::Debug for Field {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut s = f.debug_struct("Field");
+        s.field("name", &self.name)
+            .field("data_type", &self.data_type)
+            .field("nullable", &self.nullable);
+        // metadata is rare, so only show it when present to keep the output short
+        if !self.metadata.is_empty() {
+            s.field("metadata", &self.metadata);
+        }
+        s.finish()
+    }
+}
+
 impl fmt::Display for Field {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{}", self.to_string())
@@ -527,15 +793,20 @@ impl fmt::Display for Field {
 ///
 /// Note that this information is only part of the meta-data and not part of the physical
 /// memory layout.
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Schema {
     pub(crate) fields: Vec<Field>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub(crate) metadata: BTreeMap<String, String>,
 }
 
 impl Schema {
     /// Creates an empty `Schema`
     pub fn empty() -> Self {
-        Self { fields: vec![] }
+        Self {
+            fields: vec![],
+            metadata: BTreeMap::new(),
+        }
     }
 
     /// Creates a new `Schema` from a sequence of `Field` values
@@ -551,7 +822,16 @@ impl Schema {
     /// let schema = Schema::new(vec![field_a, field_b]);
     /// ```
     pub fn new(fields: Vec<Field>) -> Self {
-        Self { fields }
+        Self::new_with_metadata(fields, BTreeMap::new())
+    }
+
+    /// Creates a new `Schema` from a sequence of `Field` values and custom key-value
+    /// metadata, such as the file or partition a batch was read from
+    pub fn new_with_metadata(
+        fields: Vec<Field>,
+        metadata: BTreeMap<String, String>,
+    ) -> Self {
+        Self { fields, metadata }
     }
 
     /// Returns an immutable reference of the vector of `Field` instances
@@ -565,6 +845,11 @@ impl Schema {
         &self.fields[i]
     }
 
+    /// Returns an immutable reference to the custom key-value metadata of the `Schema`
+    pub fn metadata(&self) -> &BTreeMap<String, String> {
+        &self.metadata
+    }
+
     /// Look up a column by name and return a immutable reference to the column along with
     /// it's index
     pub fn column_with_name(&self, name: &str) -> Option<(usize, &Field)> {
@@ -576,12 +861,53 @@ impl Schema {
 
     /// Generate a JSON representation of the `Field`
     pub fn to_json(&self) -> Value {
-        json!({
+        let mut json = json!({
             "fields": self.fields.iter().map(|field| field.to_json()).collect::<Vec<Value>>(),
-        })
+        });
+        if !self.metadata.is_empty() {
+            json["metadata"] = metadata_to_json(&self.metadata);
+        }
+        json
+    }
+}
+
+/// Parse custom key-value metadata from its JSON representation, a list of
+/// `{"key": .., "value": ..}` objects
+pub(crate) fn metadata_from_json(json: &Value) -> Result<BTreeMap<String, String>> {
+    match *json {
+        Value::Array(ref entries) => {
+            let mut metadata = BTreeMap::new();
+            for entry in entries {
+                match (entry.get("key"), entry.get("value")) {
+                    (Some(&Value::String(ref k)), Some(&Value::String(ref v))) => {
+                        metadata.insert(k.to_string(), v.to_string());
+                    }
+                    _ => {
+                        return Err(ArrowError::ParseError(
+                            "Metadata entry must have string 'key' and 'value' attributes"
+                                .to_string(),
+                        ));
+                    }
+                }
+            }
+            Ok(metadata)
+        }
+        _ => Err(ArrowError::ParseError(
+            "Invalid json value type for metadata".to_string(),
+        )),
     }
 }
 
+/// Generate a JSON representation of custom key-value metadata
+pub(crate) fn metadata_to_json(metadata: &BTreeMap<String, String>) -> Value {
+    Value::Array(
+        metadata
+            .iter()
+            .map(|(k, v)| json!({"key": k, "value": v}))
+            .collect(),
+    )
+}
+
 impl fmt::Display for Schema {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.write_str(
@@ -698,6 +1024,27 @@ mod tests {
         assert_eq!(expected, dt);
     }
 
+    #[test]
+    fn field_metadata_json_round_trip() {
+        let mut metadata = BTreeMap::new();
+        metadata.insert("source".to_string(), "part-0.csv".to_string());
+        let f = Field::new("c1", DataType::Utf8, true).with_metadata(metadata.clone());
+        assert_eq!(&metadata, f.metadata());
+
+        let json = f.to_json();
+        assert_eq!(
+            "{\"name\":\"c1\",\"nullable\":true,\"type\":{\"name\":\"utf8\"},\
+             \"metadata\":[{\"key\":\"source\",\"value\":\"part-0.csv\"}]}",
+            json.to_string()
+        );
+        assert_eq!(f, Field::from(&json).unwrap());
+
+        let json = "{\"name\":\"c1\",\"nullable\":true,\"type\":{\"name\":\"utf8\"},\
+                    \"metadata\":[{\"key\":\"source\"}]}";
+        let value: Value = serde_json::from_str(json).unwrap();
+        assert!(Field::from(&value).is_err());
+    }
+
     #[test]
     fn parse_utf8_from_json() {
         let json = "{\"name\":\"utf8\"}";
@@ -706,6 +1053,102 @@ mod tests {
         assert_eq!(DataType::Utf8, dt);
     }
 
+    #[test]
+    fn parse_null_from_json() {
+        let json = "{\"name\":\"null\"}";
+        let value: Value = serde_json::from_str(json).unwrap();
+        let dt = DataType::from(&value).unwrap();
+        assert_eq!(DataType::Null, dt);
+        assert_eq!(value, dt.to_json());
+    }
+
+    #[test]
+    fn parse_binary_from_json() {
+        let json = "{\"name\":\"binary\"}";
+        let value: Value = serde_json::from_str(json).unwrap();
+        let dt = DataType::from(&value).unwrap();
+        assert_eq!(DataType::Binary, dt);
+        assert_eq!(value, dt.to_json());
+    }
+
+    #[test]
+    fn parse_timestamp_with_timezone_from_json() {
+        let json = "{\"name\":\"timestamp\",\"unit\":\"SECOND\",\"timezone\":\"+05:30\"}";
+        let value: Value = serde_json::from_str(json).unwrap();
+        let dt = DataType::from(&value).unwrap();
+        assert_eq!(
+            DataType::Timestamp(TimeUnit::Second, Some("+05:30".to_string())),
+            dt
+        );
+        assert_eq!(value, dt.to_json());
+
+        let json = "{\"name\":\"timestamp\",\"unit\":\"SECOND\"}";
+        let value: Value = serde_json::from_str(json).unwrap();
+        let dt = DataType::from(&value).unwrap();
+        assert_eq!(DataType::Timestamp(TimeUnit::Second, None), dt);
+        assert_eq!(value, dt.to_json());
+    }
+
+    #[test]
+    fn fixed_size_list_to_json() {
+        let dt = DataType::FixedSizeList(Box::new(DataType::Float32), 3);
+        assert_eq!(
+            "{\"name\":\"fixedsizelist\",\"listSize\":3,\
+             \"children\":{\"name\":\"floatingpoint\",\"precision\":\"SINGLE\"}}",
+            dt.to_json().to_string()
+        );
+    }
+
+    #[test]
+    fn union_to_json() {
+        let dt = DataType::Union(
+            vec![
+                Field::new("a", DataType::Int32, true),
+                Field::new("b", DataType::Utf8, true),
+            ],
+            UnionMode::Dense,
+        );
+        assert_eq!(
+            "{\"name\":\"union\",\"mode\":\"DENSE\",\"typeIds\":[0,1],\"children\":[\
+             {\"name\":\"a\",\"nullable\":true,\"type\":{\"name\":\"int\",\"bitWidth\":32,\"isSigned\":true}},\
+             {\"name\":\"b\",\"nullable\":true,\"type\":{\"name\":\"utf8\"}}]}",
+            dt.to_json().to_string()
+        );
+    }
+
+    #[test]
+    fn dictionary_field_json_round_trip() {
+        let field = Field::new(
+            "city",
+            DataType::Dictionary(Box::new(DataType::Int8), Box::new(DataType::Utf8)),
+            true,
+        );
+        let json = field.to_json();
+        assert_eq!(
+            json!({
+                "name": "city",
+                "nullable": true,
+                "type": {"name": "utf8"},
+                "dictionary": {
+                    "id": 0,
+                    "indexType": {"name": "int", "bitWidth": 8, "isSigned": true},
+                    "isOrdered": false,
+                },
+            }),
+            json
+        );
+        assert_eq!(field, Field::from(&json).unwrap());
+    }
+
+    #[test]
+    fn dictionary_key_index_conversion() {
+        assert_eq!(Some(127), Int8Type::from_index(127));
+        assert_eq!(None, Int8Type::from_index(128));
+        assert_eq!(Some(255), UInt8Type::from_index(255));
+        assert_eq!(3, Int16Type::to_index(3));
+        assert!(Int32Type::to_index(-1) > i32::max_value() as usize);
+    }
+
     #[test]
     fn parse_int32_from_json() {
         let json = "{\"name\": \"int\", \"isSigned\": true, \"bitWidth\": 32}";
@@ -714,6 +1157,22 @@ mod tests {
         assert_eq!(DataType::Int32, dt);
     }
 
+    #[test]
+    fn parse_int64_from_json() {
+        let json = "{\"name\": \"int\", \"isSigned\": true, \"bitWidth\": 64}";
+        let value: Value = serde_json::from_str(json).unwrap();
+        let dt = DataType::from(&value).unwrap();
+        assert_eq!(DataType::Int64, dt);
+    }
+
+    #[test]
+    fn parse_time_with_numeric_bit_width_from_json() {
+        let json = "{\"name\": \"time\", \"unit\": \"NANOSECOND\", \"bitWidth\": 64}";
+        let value: Value = serde_json::from_str(json).unwrap();
+        let dt = DataType::from(&value).unwrap();
+        assert_eq!(DataType::Time64(TimeUnit::Nanosecond), dt);
+    }
+
     #[test]
     fn schema_json() {
         let schema = Schema::new(vec![
@@ -728,10 +1187,27 @@ mod tests {
             Field::new("c12", DataType::Time64(TimeUnit::Millisecond), false),
             Field::new("c13", DataType::Time64(TimeUnit::Microsecond), false),
             Field::new("c14", DataType::Time64(TimeUnit::Nanosecond), false),
-            Field::new("c15", DataType::Timestamp(TimeUnit::Second), false),
-            Field::new("c16", DataType::Timestamp(TimeUnit::Millisecond), false),
-            Field::new("c17", DataType::Timestamp(TimeUnit::Microsecond), false),
-            Field::new("c18", DataType::Timestamp(TimeUnit::Nanosecond), false),
+            Field::new("c15", DataType::Timestamp(TimeUnit::Second, None), false),
+            Field::new(
+                "c16",
+                DataType::Timestamp(TimeUnit::Millisecond, None),
+                false,
+            ),
+            Field::new(
+                "c17",
+                DataType::Timestamp(TimeUnit::Microsecond, None),
+                false,
+            ),
+            Field::new(
+                "c18",
+                DataType::Timestamp(TimeUnit::Nanosecond, None),
+                false,
+            ),
+            Field::new(
+                "c18a",
+                DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".to_string())),
+                false,
+            ),
             Field::new("c19", DataType::Interval(IntervalUnit::DayTime), false),
             Field::new("c20", DataType::Interval(IntervalUnit::YearMonth), false),
             Field::new(
@@ -760,6 +1236,7 @@ mod tests {
         {\"name\":\"c16\",\"nullable\":false,\"type\":{\"name\":\"timestamp\",\"unit\":\"MILLISECOND\"}},\
         {\"name\":\"c17\",\"nullable\":false,\"type\":{\"name\":\"timestamp\",\"unit\":\"MICROSECOND\"}},\
         {\"name\":\"c18\",\"nullable\":false,\"type\":{\"name\":\"timestamp\",\"unit\":\"NANOSECOND\"}},\
+        {\"name\":\"c18a\",\"nullable\":false,\"type\":{\"name\":\"timestamp\",\"unit\":\"MILLISECOND\",\"timezone\":\"UTC\"}},\
         {\"name\":\"c19\",\"nullable\":false,\"type\":{\"name\":\"interval\",\"unit\":\"DAY_TIME\"}},\
         {\"name\":\"c20\",\"nullable\":false,\"type\":{\"name\":\"interval\",\"unit\":\"YEAR_MONTH\"}},\
         {\"name\":\"c21\",\"nullable\":false,\"type\":{\"fields\":[\
@@ -778,6 +1255,32 @@ mod tests {
         }
     }
 
+    #[test]
+    fn schema_metadata() {
+        let mut metadata = BTreeMap::new();
+        metadata.insert("partition".to_string(), "year=2019".to_string());
+        let schema = Schema::new_with_metadata(
+            vec![Field::new("c1", DataType::Int32, false)],
+            metadata.clone(),
+        );
+        assert_eq!(&metadata, schema.metadata());
+        assert!(Schema::new(schema.fields().clone()).metadata().is_empty());
+        assert_ne!(schema, Schema::new(schema.fields().clone()));
+
+        assert_eq!(
+            "{\"fields\":[{\"name\":\"c1\",\"nullable\":false,\
+             \"type\":{\"name\":\"int\",\"bitWidth\":32,\"isSigned\":true}}],\
+             \"metadata\":[{\"key\":\"partition\",\"value\":\"year=2019\"}]}",
+            schema.to_json().to_string()
+        );
+
+        // the derived serialization omits empty metadata
+        let serialized = serde_json::to_string(&schema).unwrap();
+        assert_eq!(schema, serde_json::from_str(&serialized).unwrap());
+        let serialized = serde_json::to_string(&Schema::empty()).unwrap();
+        assert_eq!("{\"fields\":[]}", serialized);
+    }
+
     #[test]
     fn create_schema_string() {
         let _person = Schema::new(vec![
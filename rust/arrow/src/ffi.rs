@@ -0,0 +1,1018 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Import and export of arrays and schemas through the Arrow C data interface, which
+//! hands Arrow data to other libraries in the same process, such as the C++
+//! implementation or pyarrow, without copying it.
+//!
+//! Exported structs hold a reference to the exported data until the consumer calls
+//! their `release` callback. Imported arrays reference the memory of the imported
+//! struct, which is released when the last buffer referencing it is dropped.
+
+use std::collections::BTreeMap;
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_void};
+use std::ptr;
+use std::sync::Arc;
+
+use crate::array::{make_array, Array, ArrayRef, StructArray};
+use crate::array_data::{ArrayData, ArrayDataRef};
+use crate::buffer::Buffer;
+use crate::datatypes::*;
+use crate::error::{ArrowError, Result};
+use crate::memory;
+use crate::record_batch::RecordBatch;
+use crate::util::bit_util;
+
+/// Flag of a schema struct whose field is nullable
+pub const ARROW_FLAG_NULLABLE: i64 = 2;
+
+/// The `ArrowSchema` struct of the C data interface, which describes the type of an
+/// array
+#[repr(C)]
+#[derive(Debug)]
+pub struct FFI_ArrowSchema {
+    pub format: *const c_char,
+    pub name: *const c_char,
+    pub metadata: *const c_char,
+    pub flags: i64,
+    pub n_children: i64,
+    pub children: *mut *mut FFI_ArrowSchema,
+    pub dictionary: *mut FFI_ArrowSchema,
+    pub release: Option<unsafe extern "C" fn(schema: *mut FFI_ArrowSchema)>,
+    pub private_data: *mut c_void,
+}
+
+/// The `ArrowArray` struct of the C data interface, which holds the buffers of an array
+#[repr(C)]
+#[derive(Debug)]
+pub struct FFI_ArrowArray {
+    pub length: i64,
+    pub null_count: i64,
+    pub offset: i64,
+    pub n_buffers: i64,
+    pub n_children: i64,
+    pub buffers: *mut *const c_void,
+    pub children: *mut *mut FFI_ArrowArray,
+    pub dictionary: *mut FFI_ArrowArray,
+    pub release: Option<unsafe extern "C" fn(array: *mut FFI_ArrowArray)>,
+    pub private_data: *mut c_void,
+}
+
+// the release callback of a struct may be called from any thread
+unsafe impl Send for FFI_ArrowArray {}
+unsafe impl Sync for FFI_ArrowArray {}
+
+impl FFI_ArrowSchema {
+    /// Creates a released struct, which a producer can write a schema to
+    pub fn empty() -> Self {
+        Self {
+            format: ptr::null(),
+            name: ptr::null(),
+            metadata: ptr::null(),
+            flags: 0,
+            n_children: 0,
+            children: ptr::null_mut(),
+            dictionary: ptr::null_mut(),
+            release: None,
+            private_data: ptr::null_mut(),
+        }
+    }
+
+    /// Moves the struct at `schema` out, and marks `schema` as released so that its
+    /// producer does not release it again. This function is unsafe because `schema`
+    /// must point to a valid struct.
+    pub unsafe fn from_raw(schema: *mut FFI_ArrowSchema) -> Self {
+        let moved = ptr::read(schema);
+        (*schema).release = None;
+        moved
+    }
+
+    /// Returns whether the struct is released, i.e. no longer describes a schema
+    pub fn is_released(&self) -> bool {
+        self.release.is_none()
+    }
+}
+
+impl Drop for FFI_ArrowSchema {
+    fn drop(&mut self) {
+        if let Some(release) = self.release {
+            unsafe { release(self) };
+        }
+    }
+}
+
+impl FFI_ArrowArray {
+    /// Creates a released struct, which a producer can write an array to
+    pub fn empty() -> Self {
+        Self {
+            length: 0,
+            null_count: 0,
+            offset: 0,
+            n_buffers: 0,
+            n_children: 0,
+            buffers: ptr::null_mut(),
+            children: ptr::null_mut(),
+            dictionary: ptr::null_mut(),
+            release: None,
+            private_data: ptr::null_mut(),
+        }
+    }
+
+    /// Moves the struct at `array` out, and marks `array` as released so that its
+    /// producer does not release it again. This function is unsafe because `array`
+    /// must point to a valid struct.
+    pub unsafe fn from_raw(array: *mut FFI_ArrowArray) -> Self {
+        let moved = ptr::read(array);
+        (*array).release = None;
+        moved
+    }
+
+    /// Returns whether the struct is released, i.e. no longer holds an array
+    pub fn is_released(&self) -> bool {
+        self.release.is_none()
+    }
+}
+
+impl Drop for FFI_ArrowArray {
+    fn drop(&mut self) {
+        if let Some(release) = self.release {
+            unsafe { release(self) };
+        }
+    }
+}
+
+/// Returns the format string of a data type
+fn format_string(data_type: &DataType) -> Result<String> {
+    let unit = |unit: &TimeUnit| match unit {
+        TimeUnit::Second => 's',
+        TimeUnit::Millisecond => 'm',
+        TimeUnit::Microsecond => 'u',
+        TimeUnit::Nanosecond => 'n',
+    };
+    let format = match data_type {
+        DataType::Boolean => "b",
+        DataType::Int8 => "c",
+        DataType::UInt8 => "C",
+        DataType::Int16 => "s",
+        DataType::UInt16 => "S",
+        DataType::Int32 => "i",
+        DataType::UInt32 => "I",
+        DataType::Int64 => "l",
+        DataType::UInt64 => "L",
+        DataType::Float16 => "e",
+        DataType::Float32 => "f",
+        DataType::Float64 => "g",
+        DataType::Binary => "z",
+        DataType::Utf8 => "u",
+        DataType::Date(DateUnit::Day) => "tdD",
+        DataType::Date(DateUnit::Millisecond) => "tdm",
+        DataType::Time32(TimeUnit::Second) => "tts",
+        DataType::Time32(TimeUnit::Millisecond) => "ttm",
+        DataType::Time64(TimeUnit::Microsecond) => "ttu",
+        DataType::Time64(TimeUnit::Nanosecond) => "ttn",
+        DataType::Timestamp(u, tz) => {
+            return Ok(format!(
+                "ts{}:{}",
+                unit(u),
+                tz.as_ref().map(|tz| tz.as_str()).unwrap_or("")
+            ));
+        }
+        DataType::Interval(IntervalUnit::YearMonth) => "tiM",
+        DataType::Interval(IntervalUnit::DayTime) => "tiD",
+        DataType::List(_) => "+l",
+        DataType::FixedSizeList(_, size) => return Ok(format!("+w:{}", size)),
+        DataType::Struct(_) => "+s",
+        other => {
+            return Err(ArrowError::CDataInterface(format!(
+                "The data type {:?} cannot be exported",
+                other
+            )));
+        }
+    };
+    Ok(format.to_string())
+}
+
+/// Private data of an exported schema struct, which owns the strings and children
+/// that the struct points to
+struct SchemaPrivateData {
+    format: CString,
+    name: CString,
+    metadata: Option<Vec<u8>>,
+    children: Vec<*mut FFI_ArrowSchema>,
+}
+
+unsafe extern "C" fn release_schema(schema: *mut FFI_ArrowSchema) {
+    if schema.is_null() {
+        return;
+    }
+    let schema = &mut *schema;
+    let private_data = Box::from_raw(schema.private_data as *mut SchemaPrivateData);
+    for child in &private_data.children {
+        // dropping a child releases it, unless the consumer moved it out
+        drop(Box::from_raw(*child));
+    }
+    schema.release = None;
+}
+
+/// Encodes custom metadata in the binary layout of the C data interface: the number of
+/// entries, followed by the length and bytes of each key and value, with all lengths
+/// as native-endian 32-bit integers. Empty metadata is encoded as a null pointer.
+fn encode_metadata(metadata: &BTreeMap<String, String>) -> Option<Vec<u8>> {
+    if metadata.is_empty() {
+        return None;
+    }
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(&(metadata.len() as i32).to_ne_bytes());
+    for (key, value) in metadata {
+        for s in &[key, value] {
+            bytes.extend_from_slice(&(s.len() as i32).to_ne_bytes());
+            bytes.extend_from_slice(s.as_bytes());
+        }
+    }
+    Some(bytes)
+}
+
+/// Decodes custom metadata encoded by `encode_metadata`. This function is unsafe
+/// because `metadata` must be null or point to validly encoded metadata.
+unsafe fn decode_metadata(metadata: *const c_char) -> Result<BTreeMap<String, String>> {
+    let mut decoded = BTreeMap::new();
+    if metadata.is_null() {
+        return Ok(decoded);
+    }
+    unsafe fn read_i32(pos: &mut *const u8) -> i32 {
+        let v = ptr::read_unaligned(*pos as *const i32);
+        *pos = pos.add(4);
+        v
+    }
+    let mut pos = metadata as *const u8;
+    let count = read_i32(&mut pos);
+    for _ in 0..count {
+        let mut entry = Vec::with_capacity(2);
+        for _ in 0..2 {
+            let len = read_i32(&mut pos);
+            if len < 0 {
+                return Err(ArrowError::CDataInterface(
+                    "The metadata contains a negative length".to_string(),
+                ));
+            }
+            let bytes = std::slice::from_raw_parts(pos, len as usize);
+            pos = pos.add(len as usize);
+            entry.push(String::from_utf8(bytes.to_vec()).map_err(|_| {
+                ArrowError::CDataInterface("The metadata is not valid UTF-8".to_string())
+            })?);
+        }
+        let value = entry.pop().unwrap();
+        decoded.insert(entry.pop().unwrap(), value);
+    }
+    Ok(decoded)
+}
+
+fn new_schema(
+    data_type: &DataType,
+    name: &str,
+    flags: i64,
+    metadata: &BTreeMap<String, String>,
+) -> Result<FFI_ArrowSchema> {
+    let format = CString::new(format_string(data_type)?).map_err(|_| {
+        ArrowError::CDataInterface("The format string contains a NUL byte".to_string())
+    })?;
+    let name = CString::new(name).map_err(|_| {
+        ArrowError::CDataInterface("The field name contains a NUL byte".to_string())
+    })?;
+    let children = match data_type {
+        DataType::List(value_type) | DataType::FixedSizeList(value_type, _) => {
+            vec![new_schema(
+                value_type,
+                "item",
+                ARROW_FLAG_NULLABLE,
+                &BTreeMap::new(),
+            )?]
+        }
+        DataType::Struct(fields) => fields
+            .iter()
+            .map(export_field)
+            .collect::<Result<Vec<FFI_ArrowSchema>>>()?,
+        _ => vec![],
+    };
+
+    let mut private_data = Box::new(SchemaPrivateData {
+        format,
+        name,
+        metadata: encode_metadata(metadata),
+        children: children
+            .into_iter()
+            .map(|child| Box::into_raw(Box::new(child)))
+            .collect(),
+    });
+    Ok(FFI_ArrowSchema {
+        format: private_data.format.as_ptr(),
+        name: private_data.name.as_ptr(),
+        metadata: private_data
+            .metadata
+            .as_ref()
+            .map_or(ptr::null(), |m| m.as_ptr() as *const c_char),
+        flags,
+        n_children: private_data.children.len() as i64,
+        children: private_data.children.as_mut_ptr(),
+        dictionary: ptr::null_mut(),
+        release: Some(release_schema),
+        private_data: Box::into_raw(private_data) as *mut c_void,
+    })
+}
+
+/// Exports a field as a schema struct
+pub fn export_field(field: &Field) -> Result<FFI_ArrowSchema> {
+    let flags = if field.is_nullable() {
+        ARROW_FLAG_NULLABLE
+    } else {
+        0
+    };
+    new_schema(field.data_type(), field.name(), flags, field.metadata())
+}
+
+/// Exports a schema as a schema struct of struct type, with a child for each field
+pub fn export_schema(schema: &Schema) -> Result<FFI_ArrowSchema> {
+    new_schema(
+        &DataType::Struct(schema.fields().clone()),
+        "",
+        0,
+        schema.metadata(),
+    )
+}
+
+/// Private data of an exported array struct, which holds a reference to the exported
+/// data and owns the buffer pointers and children that the struct points to
+struct ArrayPrivateData {
+    data: ArrayDataRef,
+    buffers: Vec<*const c_void>,
+    children: Vec<*mut FFI_ArrowArray>,
+}
+
+unsafe extern "C" fn release_array(array: *mut FFI_ArrowArray) {
+    if array.is_null() {
+        return;
+    }
+    let array = &mut *array;
+    let private_data = Box::from_raw(array.private_data as *mut ArrayPrivateData);
+    for child in &private_data.children {
+        // dropping a child releases it, unless the consumer moved it out
+        drop(Box::from_raw(*child));
+    }
+    array.release = None;
+}
+
+/// Exports array data as an array struct, without copying its buffers. The buffers
+/// stay alive until the struct is released.
+pub fn export_array_data(data: &ArrayDataRef) -> Result<FFI_ArrowArray> {
+    // fail for data types that the consumer could not import
+    format_string(data.data_type())?;
+
+    let mut buffers = vec![match data.null_bitmap() {
+        Some(bitmap) => bitmap.bits.raw_data() as *const c_void,
+        None => ptr::null(),
+    }];
+    buffers.extend(data.buffers().iter().map(|b| b.raw_data() as *const c_void));
+    let children = data
+        .child_data()
+        .iter()
+        .map(export_array_data)
+        .collect::<Result<Vec<FFI_ArrowArray>>>()?;
+
+    let mut private_data = Box::new(ArrayPrivateData {
+        data: data.clone(),
+        buffers,
+        children: children
+            .into_iter()
+            .map(|child| Box::into_raw(Box::new(child)))
+            .collect(),
+    });
+    Ok(FFI_ArrowArray {
+        length: private_data.data.len() as i64,
+        null_count: private_data.data.null_count() as i64,
+        offset: private_data.data.offset() as i64,
+        n_buffers: private_data.buffers.len() as i64,
+        n_children: private_data.children.len() as i64,
+        buffers: private_data.buffers.as_mut_ptr(),
+        children: private_data.children.as_mut_ptr(),
+        dictionary: ptr::null_mut(),
+        release: Some(release_array),
+        private_data: Box::into_raw(private_data) as *mut c_void,
+    })
+}
+
+/// Exports an array as an array struct, without copying its buffers
+pub fn export_array(array: &ArrayRef) -> Result<FFI_ArrowArray> {
+    export_array_data(&array.data())
+}
+
+/// Exports a record batch as an array struct of struct type, with a child for each
+/// column, and the schema struct of the batch
+pub fn export_record_batch(
+    batch: &RecordBatch,
+) -> Result<(FFI_ArrowArray, FFI_ArrowSchema)> {
+    let schema = export_schema(batch.schema())?;
+    let data = ArrayData::builder(DataType::Struct(batch.schema().fields().clone()))
+        .len(batch.num_rows())
+        .child_data(
+            (0..batch.num_columns())
+                .map(|i| batch.column(i).data())
+                .collect(),
+        )
+        .build();
+    Ok((export_array_data(&data)?, schema))
+}
+
+/// Returns the `i`th child of a schema struct
+unsafe fn schema_child(schema: &FFI_ArrowSchema, i: usize) -> Result<&FFI_ArrowSchema> {
+    if i as i64 >= schema.n_children || schema.children.is_null() {
+        return Err(ArrowError::CDataInterface(format!(
+            "The schema has no child {}",
+            i
+        )));
+    }
+    Ok(&**schema.children.add(i))
+}
+
+unsafe fn import_data_type(schema: &FFI_ArrowSchema) -> Result<DataType> {
+    if schema.format.is_null() {
+        return Err(ArrowError::CDataInterface(
+            "The schema has no format string".to_string(),
+        ));
+    }
+    let format = CStr::from_ptr(schema.format).to_str().map_err(|_| {
+        ArrowError::CDataInterface("The format string is not valid UTF-8".to_string())
+    })?;
+    let data_type = match format {
+        "b" => DataType::Boolean,
+        "c" => DataType::Int8,
+        "C" => DataType::UInt8,
+        "s" => DataType::Int16,
+        "S" => DataType::UInt16,
+        "i" => DataType::Int32,
+        "I" => DataType::UInt32,
+        "l" => DataType::Int64,
+        "L" => DataType::UInt64,
+        "e" => DataType::Float16,
+        "f" => DataType::Float32,
+        "g" => DataType::Float64,
+        "z" => DataType::Binary,
+        "u" => DataType::Utf8,
+        "tdD" => DataType::Date(DateUnit::Day),
+        "tdm" => DataType::Date(DateUnit::Millisecond),
+        "tts" => DataType::Time32(TimeUnit::Second),
+        "ttm" => DataType::Time32(TimeUnit::Millisecond),
+        "ttu" => DataType::Time64(TimeUnit::Microsecond),
+        "ttn" => DataType::Time64(TimeUnit::Nanosecond),
+        "tiM" => DataType::Interval(IntervalUnit::YearMonth),
+        "tiD" => DataType::Interval(IntervalUnit::DayTime),
+        "+l" => DataType::List(Box::new(import_data_type(schema_child(schema, 0)?)?)),
+        "+s" => DataType::Struct(
+            (0..schema.n_children as usize)
+                .map(|i| import_field(schema_child(schema, i)?))
+                .collect::<Result<Vec<Field>>>()?,
+        ),
+        _ if format.starts_with("+w:") => {
+            let size = format[3..].parse::<i32>().map_err(|_| {
+                ArrowError::CDataInterface(format!(
+                    "Invalid list size in format string {:?}",
+                    format
+                ))
+            })?;
+            let value_type = import_data_type(schema_child(schema, 0)?)?;
+            DataType::FixedSizeList(Box::new(value_type), size)
+        }
+        _ if format.starts_with("ts") && format.get(3..4) == Some(":") => {
+            let unit = match &format[2..3] {
+                "s" => TimeUnit::Second,
+                "m" => TimeUnit::Millisecond,
+                "u" => TimeUnit::Microsecond,
+                "n" => TimeUnit::Nanosecond,
+                _ => {
+                    return Err(ArrowError::CDataInterface(format!(
+                        "Unsupported format string {:?}",
+                        format
+                    )));
+                }
+            };
+            let tz = &format[4..];
+            DataType::Timestamp(
+                unit,
+                if tz.is_empty() {
+                    None
+                } else {
+                    Some(tz.to_string())
+                },
+            )
+        }
+        _ => {
+            return Err(ArrowError::CDataInterface(format!(
+                "Unsupported format string {:?}",
+                format
+            )));
+        }
+    };
+    Ok(data_type)
+}
+
+/// Imports a field from a schema struct. The struct is not released, as the field
+/// does not reference its memory. This function is unsafe because `schema` must be a
+/// valid struct.
+pub unsafe fn import_field(schema: &FFI_ArrowSchema) -> Result<Field> {
+    if schema.is_released() {
+        return Err(ArrowError::CDataInterface(
+            "Cannot import a released schema".to_string(),
+        ));
+    }
+    let name = if schema.name.is_null() {
+        ""
+    } else {
+        CStr::from_ptr(schema.name).to_str().map_err(|_| {
+            ArrowError::CDataInterface("The field name is not valid UTF-8".to_string())
+        })?
+    };
+    Ok(Field::new(
+        name,
+        import_data_type(schema)?,
+        schema.flags & ARROW_FLAG_NULLABLE != 0,
+    )
+    .with_metadata(decode_metadata(schema.metadata)?))
+}
+
+/// Imports a schema from a schema struct of struct type. This function is unsafe
+/// because `schema` must be a valid struct.
+pub unsafe fn import_schema(schema: &FFI_ArrowSchema) -> Result<Schema> {
+    let field = import_field(schema)?;
+    match field.data_type() {
+        DataType::Struct(fields) => Ok(Schema::new_with_metadata(
+            fields.clone(),
+            field.metadata().clone(),
+        )),
+        other => Err(ArrowError::CDataInterface(format!(
+            "Expected a schema of struct type, found {:?}",
+            other
+        ))),
+    }
+}
+
+/// Returns the number of bytes of each value of a fixed-width data type
+fn byte_width(data_type: &DataType) -> Option<usize> {
+    match data_type {
+        DataType::Int8 | DataType::UInt8 => Some(1),
+        DataType::Int16 | DataType::UInt16 => Some(2),
+        DataType::Int32
+        | DataType::UInt32
+        | DataType::Float32
+        | DataType::Date(DateUnit::Day)
+        | DataType::Time32(_)
+        | DataType::Interval(IntervalUnit::YearMonth) => Some(4),
+        DataType::Int64
+        | DataType::UInt64
+        | DataType::Float64
+        | DataType::Date(DateUnit::Millisecond)
+        | DataType::Time64(_)
+        | DataType::Timestamp(_, _)
+        | DataType::Interval(IntervalUnit::DayTime) => Some(8),
+        _ => None,
+    }
+}
+
+/// Imports the buffers and children of an array struct as array data, where the
+/// memory is kept alive by `owner`, the imported struct at the root of the array
+unsafe fn import_array_data(
+    array: &FFI_ArrowArray,
+    data_type: &DataType,
+    owner: &Arc<FFI_ArrowArray>,
+) -> Result<ArrayDataRef> {
+    let len = array.length as usize;
+    let offset = array.offset as usize;
+    let end = offset + len;
+    let expected_buffers = match data_type {
+        DataType::Struct(_) | DataType::FixedSizeList(_, _) => 1,
+        DataType::Binary | DataType::Utf8 => 3,
+        DataType::Boolean | DataType::List(_) => 2,
+        dt if byte_width(dt).is_some() => 2,
+        other => {
+            return Err(ArrowError::CDataInterface(format!(
+                "Importing arrays of type {:?} is not supported",
+                other
+            )));
+        }
+    };
+    if array.n_buffers != expected_buffers || array.buffers.is_null() {
+        return Err(ArrowError::CDataInterface(format!(
+            "Expected {} buffers for an array of type {:?}, found {}",
+            expected_buffers, data_type, array.n_buffers
+        )));
+    }
+
+    let buffer_ptr = |i: usize| *array.buffers.add(i) as *const u8;
+    // arrays read their values in place, so the memory must be aligned for the type of
+    // the values, which is the width of the values for all imported types
+    let aligned_buffer = |i: usize, len: usize, align: usize| -> Result<Buffer> {
+        let ptr = buffer_ptr(i);
+        if ptr.is_null() {
+            if len == 0 {
+                return Ok(Buffer::empty());
+            }
+            return Err(ArrowError::CDataInterface(format!(
+                "Buffer {} of an array of type {:?} is null",
+                i, data_type
+            )));
+        }
+        if !memory::is_aligned(ptr, align) {
+            return Err(ArrowError::CDataInterface(format!(
+                "Buffer {} of an array of type {:?} is not aligned to {} bytes",
+                i, data_type, align
+            )));
+        }
+        Ok(Buffer::from_unowned(ptr, len, owner.clone()))
+    };
+    let buffer = |i: usize, len: usize| aligned_buffer(i, len, 1);
+
+    let validity = if buffer_ptr(0).is_null() {
+        None
+    } else {
+        Some(buffer(0, bit_util::ceil(end, 8))?)
+    };
+    let null_count = match &validity {
+        None => 0,
+        Some(_) if array.null_count >= 0 => array.null_count as usize,
+        // the producer did not compute the null count
+        Some(bits) => (offset..end)
+            .filter(|i| !bit_util::get_bit(bits.data(), *i))
+            .count(),
+    };
+
+    let (buffers, children) = match data_type {
+        DataType::Boolean => (vec![buffer(1, bit_util::ceil(end, 8))?], vec![]),
+        DataType::Binary | DataType::Utf8 => {
+            let offsets = aligned_buffer(1, (end + 1) * 4, 4)?;
+            let values_len = *(offsets.raw_data() as *const i32).add(end) as usize;
+            (vec![offsets, buffer(2, values_len)?], vec![])
+        }
+        DataType::List(value_type) => {
+            if array.n_children != 1 || array.children.is_null() {
+                return Err(ArrowError::CDataInterface(
+                    "Expected one child for a list array".to_string(),
+                ));
+            }
+            let values = import_array_data(&**array.children, value_type, owner)?;
+            (vec![aligned_buffer(1, (end + 1) * 4, 4)?], vec![values])
+        }
+        DataType::FixedSizeList(value_type, _) => {
+            if array.n_children != 1 || array.children.is_null() {
+                return Err(ArrowError::CDataInterface(
+                    "Expected one child for a fixed size list array".to_string(),
+                ));
+            }
+            let values = import_array_data(&**array.children, value_type, owner)?;
+            (vec![], vec![values])
+        }
+        DataType::Struct(fields) => {
+            if array.n_children != fields.len() as i64
+                || (!fields.is_empty() && array.children.is_null())
+            {
+                return Err(ArrowError::CDataInterface(format!(
+                    "Expected {} children for a struct array",
+                    fields.len()
+                )));
+            }
+            let children = fields
+                .iter()
+                .enumerate()
+                .map(|(i, f)| {
+                    import_array_data(&**array.children.add(i), f.data_type(), owner)
+                })
+                .collect::<Result<Vec<ArrayDataRef>>>()?;
+            (vec![], children)
+        }
+        dt => {
+            let width = byte_width(dt).unwrap();
+            (vec![aligned_buffer(1, end * width, width)?], vec![])
+        }
+    };
+
+    Ok(Arc::new(ArrayData::new(
+        data_type.clone(),
+        len,
+        Some(null_count),
+        validity,
+        offset,
+        buffers,
+        children,
+    )))
+}
+
+/// Imports an array from an array struct of the given data type, without copying its
+/// buffers. The array takes ownership of the struct, which is released when the last
+/// buffer referencing its memory is dropped. This function is unsafe because `array`
+/// must be a valid struct of the given type.
+pub unsafe fn import_array(
+    array: FFI_ArrowArray,
+    data_type: &DataType,
+) -> Result<ArrayRef> {
+    if array.is_released() {
+        return Err(ArrowError::CDataInterface(
+            "Cannot import a released array".to_string(),
+        ));
+    }
+    let owner = Arc::new(array);
+    let data = import_array_data(&owner, data_type, &owner)?;
+    Ok(make_array(data))
+}
+
+/// Imports a record batch from an array struct of struct type and its schema struct.
+/// This function is unsafe because both structs must be valid.
+pub unsafe fn import_record_batch(
+    array: FFI_ArrowArray,
+    schema: &FFI_ArrowSchema,
+) -> Result<RecordBatch> {
+    let schema = import_schema(schema)?;
+    let array = import_array(array, &DataType::Struct(schema.fields().clone()))?;
+    let array = array.as_any().downcast_ref::<StructArray>().unwrap();
+    let columns = (0..schema.fields().len())
+        .map(|i| array.column(i).clone())
+        .collect();
+    Ok(RecordBatch::new(Arc::new(schema), columns))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::array::*;
+    use crate::builder::{FixedSizeListBuilder, Int32Builder, ListBuilder};
+
+    /// Exports an array and imports it again, as a consumer would
+    fn round_trip(array: &ArrayRef) -> ArrayRef {
+        let exported = export_array(array).unwrap();
+        unsafe { import_array(exported, array.data_type()).unwrap() }
+    }
+
+    #[test]
+    fn test_primitive_round_trip() {
+        let array: ArrayRef = Arc::new(Int32Array::from(vec![Some(1), None, Some(3)]));
+        let imported = round_trip(&array);
+        let imported = imported.as_any().downcast_ref::<Int32Array>().unwrap();
+        assert_eq!(3, imported.len());
+        assert_eq!(1, imported.null_count());
+        assert_eq!(1, imported.value(0));
+        assert!(imported.is_null(1));
+        assert_eq!(3, imported.value(2));
+
+        // the imported array shares the memory of the exported array
+        assert_eq!(
+            array.data().buffers()[0].raw_data(),
+            imported.data().buffers()[0].raw_data()
+        );
+    }
+
+    /// Round trips an array of a primitive type and checks that its values and data
+    /// type survive
+    fn check_primitive_round_trip<T: ArrowNumericType>(array: PrimitiveArray<T>) {
+        let array: ArrayRef = Arc::new(array);
+        let imported = round_trip(&array);
+        assert_eq!(array.data_type(), imported.data_type());
+        let expected = array.as_any().downcast_ref::<PrimitiveArray<T>>().unwrap();
+        let imported = imported
+            .as_any()
+            .downcast_ref::<PrimitiveArray<T>>()
+            .unwrap();
+        assert_eq!(expected.len(), imported.len());
+        assert_eq!(expected.null_count(), imported.null_count());
+        for i in 0..expected.len() {
+            assert_eq!(expected.is_null(i), imported.is_null(i));
+            if expected.is_valid(i) {
+                assert_eq!(expected.value(i), imported.value(i));
+            }
+        }
+    }
+
+    #[test]
+    fn test_temporal_round_trip() {
+        check_primitive_round_trip(Date32Array::from(vec![Some(1), None, Some(18000)]));
+        check_primitive_round_trip(Date64Array::from(vec![Some(86_400_000), None]));
+        check_primitive_round_trip(Time32SecondArray::from(vec![Some(3600), None]));
+        check_primitive_round_trip(Time32MillisecondArray::from(vec![None, Some(1)]));
+        check_primitive_round_trip(Time64MicrosecondArray::from(vec![Some(1), None]));
+        check_primitive_round_trip(Time64NanosecondArray::from(vec![Some(1), None]));
+        check_primitive_round_trip(TimestampSecondArray::from(vec![Some(1), None]));
+        check_primitive_round_trip(TimestampMillisecondArray::from(vec![Some(1), None]));
+        check_primitive_round_trip(TimestampMicrosecondArray::from(vec![Some(1), None]));
+        check_primitive_round_trip(TimestampNanosecondArray::from(vec![Some(1), None]));
+        check_primitive_round_trip(IntervalYearMonthArray::from(vec![Some(13), None]));
+        check_primitive_round_trip(IntervalDayTimeArray::from(vec![None, Some(1 << 32)]));
+    }
+
+    #[test]
+    fn test_timestamp_with_timezone_round_trip() {
+        let data_type =
+            DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".to_string()));
+        let data = ArrayData::builder(data_type.clone())
+            .len(2)
+            .add_buffer(Buffer::from(vec![1i64, 2].to_byte_slice()))
+            .build();
+        let array = make_array(data);
+        let imported = round_trip(&array);
+        assert_eq!(&data_type, imported.data_type());
+        let imported = imported
+            .as_any()
+            .downcast_ref::<TimestampMillisecondArray>()
+            .unwrap();
+        assert_eq!(1, imported.value(0));
+        assert_eq!(2, imported.value(1));
+    }
+
+    #[test]
+    fn test_boolean_and_string_round_trip() {
+        let array: ArrayRef = Arc::new(BooleanArray::from(vec![true, false, true]));
+        let imported = round_trip(&array);
+        let imported = imported.as_any().downcast_ref::<BooleanArray>().unwrap();
+        assert_eq!(0, imported.null_count());
+        assert_eq!(true, imported.value(0));
+        assert_eq!(false, imported.value(1));
+
+        let array: ArrayRef = Arc::new(BinaryArray::from(vec!["foo", "", "barbaz"]));
+        let imported = round_trip(&array);
+        let imported = imported.as_any().downcast_ref::<BinaryArray>().unwrap();
+        assert_eq!(&DataType::Utf8, imported.data_type());
+        assert_eq!("foo", imported.get_string(0));
+        assert_eq!("", imported.get_string(1));
+        assert_eq!("barbaz", imported.get_string(2));
+    }
+
+    #[test]
+    fn test_list_round_trip() {
+        let mut builder = ListBuilder::new(Int32Builder::new(8));
+        builder.values().append_value(1).unwrap();
+        builder.values().append_value(2).unwrap();
+        builder.append(true).unwrap();
+        builder.append(false).unwrap();
+        builder.values().append_value(3).unwrap();
+        builder.append(true).unwrap();
+        let array: ArrayRef = Arc::new(builder.finish());
+
+        let imported = round_trip(&array);
+        let imported = imported.as_any().downcast_ref::<ListArray>().unwrap();
+        assert_eq!(3, imported.len());
+        assert!(imported.is_null(1));
+        assert_eq!(2, imported.value_length(0));
+        let values = imported.values();
+        let values = values.as_any().downcast_ref::<Int32Array>().unwrap();
+        assert_eq!(3, values.len());
+        assert_eq!(3, values.value(2));
+    }
+
+    #[test]
+    fn test_fixed_size_list_round_trip() {
+        let mut builder = FixedSizeListBuilder::new(Int32Builder::new(6), 2);
+        builder.values().append_slice(&[1, 2]).unwrap();
+        builder.append(true).unwrap();
+        builder.values().append_null().unwrap();
+        builder.values().append_null().unwrap();
+        builder.append(false).unwrap();
+        builder.values().append_slice(&[3, 4]).unwrap();
+        builder.append(true).unwrap();
+        let array: ArrayRef = Arc::new(builder.finish());
+
+        let field = Field::new("points", array.data_type().clone(), true);
+        let schema = export_field(&field).unwrap();
+        assert_eq!(field, unsafe { import_field(&schema).unwrap() });
+
+        let imported = round_trip(&array);
+        let imported = imported
+            .as_any()
+            .downcast_ref::<FixedSizeListArray>()
+            .unwrap();
+        assert_eq!(array.data_type(), imported.data_type());
+        assert_eq!(3, imported.len());
+        assert!(imported.is_null(1));
+        assert_eq!(2, imported.value_length());
+        let values = imported.values();
+        let values = values.as_any().downcast_ref::<Int32Array>().unwrap();
+        assert_eq!(6, values.len());
+        assert_eq!(&[3, 4], values.value_slice(4, 2));
+    }
+
+    #[test]
+    fn test_record_batch_round_trip() {
+        let schema = Schema::new(vec![
+            Field::new("a", DataType::Int64, false),
+            Field::new("b", DataType::Utf8, true),
+        ]);
+        let batch = RecordBatch::new(
+            Arc::new(schema.clone()),
+            vec![
+                Arc::new(Int64Array::from(vec![1, 2])),
+                Arc::new(BinaryArray::from(vec!["x", "y"])),
+            ],
+        );
+        let (array, exported_schema) = export_record_batch(&batch).unwrap();
+        let imported = unsafe { import_record_batch(array, &exported_schema).unwrap() };
+        assert_eq!(&schema, imported.schema().as_ref());
+        assert_eq!(2, imported.num_rows());
+        let b = imported
+            .column(1)
+            .as_any()
+            .downcast_ref::<BinaryArray>()
+            .unwrap();
+        assert_eq!("y", b.get_string(1));
+    }
+
+    #[test]
+    fn test_schema_round_trip() {
+        let schema = Schema::new(vec![
+            Field::new(
+                "ts",
+                DataType::Timestamp(TimeUnit::Millisecond, Some("+05:30".to_string())),
+                true,
+            ),
+            Field::new("t", DataType::Timestamp(TimeUnit::Nanosecond, None), false),
+            Field::new("d", DataType::Date(DateUnit::Day), false),
+            Field::new("l", DataType::List(Box::new(DataType::Float64)), true),
+            Field::new(
+                "s",
+                DataType::Struct(vec![Field::new("x", DataType::UInt16, false)]),
+                false,
+            ),
+        ]);
+        let exported = export_schema(&schema).unwrap();
+        assert_eq!(5, exported.n_children);
+        let imported = unsafe { import_schema(&exported).unwrap() };
+        assert_eq!(schema, imported);
+
+        let format =
+            unsafe { CStr::from_ptr(schema_child(&exported, 0).unwrap().format) };
+        assert_eq!("tsm:+05:30", format.to_str().unwrap());
+    }
+
+    #[test]
+    fn test_metadata_round_trip() {
+        let mut field_metadata = BTreeMap::new();
+        field_metadata.insert("unit".to_string(), "ms".to_string());
+        field_metadata.insert("empty".to_string(), "".to_string());
+        let mut schema_metadata = BTreeMap::new();
+        schema_metadata.insert("file".to_string(), "data/part-0.csv".to_string());
+        let schema = Schema::new_with_metadata(
+            vec![
+                Field::new("a", DataType::Int64, false).with_metadata(field_metadata),
+                Field::new("b", DataType::Utf8, true),
+            ],
+            schema_metadata,
+        );
+        let exported = export_schema(&schema).unwrap();
+        assert!(!exported.metadata.is_null());
+        assert!(unsafe { schema_child(&exported, 1).unwrap().metadata.is_null() });
+        let imported = unsafe { import_schema(&exported).unwrap() };
+        assert_eq!(schema, imported);
+    }
+
+    #[test]
+    fn test_import_unaligned_buffer() {
+        // a producer hands over values that are not aligned for their type
+        let bytes = Buffer::from(&[0u8; 13][..]).slice(1);
+        let data = ArrayData::builder(DataType::Int32)
+            .len(3)
+            .add_buffer(bytes)
+            .build();
+        let exported = export_array_data(&data).unwrap();
+        let result = unsafe { import_array(exported, &DataType::Int32) };
+        match result {
+            Err(ArrowError::CDataInterface(message)) => {
+                assert!(message.contains("not aligned"), message)
+            }
+            _ => panic!("expected an alignment error"),
+        }
+    }
+
+    #[test]
+    fn test_release() {
+        let array: ArrayRef = Arc::new(Int32Array::from(vec![1, 2, 3]));
+        let mut exported = export_array(&array).unwrap();
+        assert!(!exported.is_released());
+
+        // a consumer moves the struct out and releases it when it is done
+        let moved = unsafe { FFI_ArrowArray::from_raw(&mut exported) };
+        assert!(exported.is_released());
+        assert!(!moved.is_released());
+        drop(moved);
+
+        // importing a released struct fails, as it no longer holds the buffers
+        assert!(unsafe { import_array(exported, &DataType::Int32) }.is_err());
+    }
+}
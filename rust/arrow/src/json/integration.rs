@@ -0,0 +1,1039 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Integration JSON Reader and Writer
+//!
+//! The Arrow integration tests exchange data between implementations as JSON documents
+//! that spell out a schema and the buffers of every column of every record batch:
+//!
+//! ```text
+//! {
+//!   "schema": {
+//!     "fields": [{"name": "a", "nullable": true, "type": {..}, "children": []}]
+//!   },
+//!   "batches": [{
+//!     "count": 2,
+//!     "columns": [{"name": "a", "count": 2, "VALIDITY": [1, 0], "DATA": [1, 0]}]
+//!   }]
+//! }
+//! ```
+//!
+//! Columns hold `VALIDITY`, `DATA`, `OFFSET`, `TYPE_ID` and `children` entries
+//! depending on their type. 64-bit integers are written as strings and binary values
+//! as hex strings. As the format is readable, it is also a convenient way to write
+//! test fixtures by hand.
+//!
+//! Example:
+//!
+//! ```
+//! use arrow::array::Int32Array;
+//! use arrow::datatypes::{DataType, Field, Schema};
+//! use arrow::json::integration;
+//! use arrow::record_batch::RecordBatch;
+//! use std::sync::Arc;
+//!
+//! let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, true)]));
+//! let array = Int32Array::from(vec![Some(1), None, Some(3)]);
+//! let batch = RecordBatch::new(schema.clone(), vec![Arc::new(array)]);
+//!
+//! let mut json = vec![];
+//! integration::write(&mut json, &schema, &[batch]).unwrap();
+//! let (read_schema, batches) = integration::read(&json[..]).unwrap();
+//! assert_eq!(schema, read_schema);
+//! assert_eq!(3, batches[0].num_rows());
+//! ```
+
+use std::io::{Read, Write};
+use std::slice::from_raw_parts;
+use std::sync::Arc;
+
+use serde_json::{json, Map, Value};
+
+use crate::array::{make_array, ArrayRef};
+use crate::array_data::{ArrayData, ArrayDataRef};
+use crate::buffer::Buffer;
+use crate::datatypes::*;
+use crate::error::{ArrowError, Result};
+use crate::record_batch::RecordBatch;
+use crate::util::bit_util;
+
+fn json_error<S: Into<String>>(message: S) -> ArrowError {
+    ArrowError::JsonError(message.into())
+}
+
+/// Reads a schema and its record batches from an integration JSON document
+pub fn read<R: Read>(reader: R) -> Result<(Arc<Schema>, Vec<RecordBatch>)> {
+    let json: Value = serde_json::from_reader(reader)
+        .map_err(|e| json_error(format!("Not valid JSON: {}", e)))?;
+    let schema = match json.get("schema") {
+        Some(schema) => Arc::new(schema_from_json(schema)?),
+        None => return Err(json_error("Document missing 'schema' attribute")),
+    };
+    let batches = match json.get("batches") {
+        Some(Value::Array(batches)) => batches
+            .iter()
+            .map(|batch| batch_from_json(&schema, batch))
+            .collect::<Result<Vec<RecordBatch>>>()?,
+        None => vec![],
+        _ => return Err(json_error("Document 'batches' attribute must be a list")),
+    };
+    Ok((schema, batches))
+}
+
+/// Writes a schema and its record batches as an integration JSON document
+pub fn write<W: Write>(
+    writer: W,
+    schema: &Schema,
+    batches: &[RecordBatch],
+) -> Result<()> {
+    let batches = batches
+        .iter()
+        .map(batch_to_json)
+        .collect::<Result<Vec<Value>>>()?;
+    let json = json!({
+        "schema": schema_to_json(schema),
+        "batches": batches,
+    });
+    serde_json::to_writer_pretty(writer, &json)
+        .map_err(|e| json_error(format!("Failed to write JSON: {}", e)))
+}
+
+/// Generate the integration JSON representation of a `Schema`
+pub fn schema_to_json(schema: &Schema) -> Value {
+    let mut json = json!({
+        "fields": schema.fields().iter().map(field_to_json).collect::<Vec<Value>>(),
+    });
+    if !schema.metadata().is_empty() {
+        json["metadata"] = metadata_to_json(schema.metadata());
+    }
+    json
+}
+
+/// Parse a `Schema` from its integration JSON representation
+pub fn schema_from_json(json: &Value) -> Result<Schema> {
+    let fields = match json.get("fields") {
+        Some(Value::Array(fields)) => fields
+            .iter()
+            .map(field_from_json)
+            .collect::<Result<Vec<Field>>>()?,
+        _ => return Err(json_error("Schema missing 'fields' attribute")),
+    };
+    let metadata = match json.get("metadata") {
+        Some(metadata) => metadata_from_json(metadata)?,
+        None => Default::default(),
+    };
+    Ok(Schema::new_with_metadata(fields, metadata))
+}
+
+/// Unlike `Field::to_json`, the integration format describes the children of nested
+/// types as fields of the parent field rather than as part of its type
+fn field_to_json(field: &Field) -> Value {
+    let children = match field.data_type() {
+        DataType::List(value_type) | DataType::FixedSizeList(value_type, _) => {
+            vec![field_to_json(&Field::new(
+                "item",
+                (**value_type).clone(),
+                true,
+            ))]
+        }
+        DataType::Struct(fields) | DataType::Union(fields, _) => {
+            fields.iter().map(field_to_json).collect()
+        }
+        _ => vec![],
+    };
+    let mut json = json!({
+        "name": field.name(),
+        "nullable": field.is_nullable(),
+        "type": type_to_json(field.data_type()),
+        "children": children,
+    });
+    if !field.metadata().is_empty() {
+        json["metadata"] = metadata_to_json(field.metadata());
+    }
+    json
+}
+
+fn type_to_json(data_type: &DataType) -> Value {
+    match data_type {
+        DataType::List(_) => json!({"name": "list"}),
+        DataType::FixedSizeList(_, size) => {
+            json!({"name": "fixedsizelist", "listSize": size})
+        }
+        DataType::Struct(_) => json!({"name": "struct"}),
+        DataType::Union(fields, mode) => json!({
+            "name": "union",
+            "mode": match mode {
+                UnionMode::Sparse => "SPARSE",
+                UnionMode::Dense => "DENSE",
+            },
+            "typeIds": (0..fields.len()).collect::<Vec<usize>>(),
+        }),
+        DataType::Time32(_) | DataType::Time64(_) => {
+            let mut json = data_type.to_json();
+            let bit_width = if let DataType::Time32(_) = data_type {
+                32
+            } else {
+                64
+            };
+            json["bitWidth"] = json!(bit_width);
+            json
+        }
+        _ => data_type.to_json(),
+    }
+}
+
+fn field_from_json(json: &Value) -> Result<Field> {
+    let name = match json.get("name") {
+        Some(Value::String(name)) => name,
+        _ => return Err(json_error("Field missing 'name' attribute")),
+    };
+    let nullable = match json.get("nullable") {
+        Some(&Value::Bool(nullable)) => nullable,
+        _ => {
+            return Err(json_error(format!(
+                "Field '{}' missing 'nullable' attribute",
+                name
+            )));
+        }
+    };
+    let mut children = match json.get("children") {
+        Some(Value::Array(children)) => children
+            .iter()
+            .map(field_from_json)
+            .collect::<Result<Vec<Field>>>()?,
+        None => vec![],
+        _ => {
+            return Err(json_error(format!(
+                "Field '{}' 'children' attribute must be a list",
+                name
+            )));
+        }
+    };
+    let json_type = match json.get("type") {
+        Some(json_type) => json_type,
+        None => {
+            return Err(json_error(format!(
+                "Field '{}' missing 'type' attribute",
+                name
+            )));
+        }
+    };
+    let type_name = json_type.get("name").and_then(Value::as_str);
+    let data_type = match type_name {
+        Some("list") | Some("fixedsizelist") => {
+            if children.len() != 1 {
+                return Err(json_error(format!(
+                    "Field '{}' of list type must have exactly one child",
+                    name
+                )));
+            }
+            let value_type = Box::new(children.remove(0).data_type().clone());
+            if type_name == Some("list") {
+                DataType::List(value_type)
+            } else {
+                match json_type.get("listSize").and_then(Value::as_i64) {
+                    Some(size) if size >= 0 => {
+                        DataType::FixedSizeList(value_type, size as i32)
+                    }
+                    _ => {
+                        return Err(json_error(format!(
+                            "Field '{}' missing or invalid 'listSize' attribute",
+                            name
+                        )));
+                    }
+                }
+            }
+        }
+        Some("struct") => DataType::Struct(children),
+        Some("union") => {
+            let mode = match json_type.get("mode").and_then(Value::as_str) {
+                Some("SPARSE") | Some("Sparse") => UnionMode::Sparse,
+                Some("DENSE") | Some("Dense") => UnionMode::Dense,
+                _ => {
+                    return Err(json_error(format!(
+                        "Field '{}' missing or invalid union 'mode' attribute",
+                        name
+                    )));
+                }
+            };
+            // the type id of a value is the index of its field
+            let expected_type_ids = json!((0..children.len()).collect::<Vec<usize>>());
+            match json_type.get("typeIds") {
+                Some(type_ids) if type_ids == &expected_type_ids => {}
+                _ => {
+                    return Err(json_error(format!(
+                        "Field '{}' union 'typeIds' must be the indices of its children",
+                        name
+                    )));
+                }
+            }
+            DataType::Union(children, mode)
+        }
+        _ => DataType::from(json_type)?,
+    };
+    let metadata = match json.get("metadata") {
+        Some(metadata) => metadata_from_json(metadata)?,
+        None => Default::default(),
+    };
+    Ok(Field::new(name, data_type, nullable).with_metadata(metadata))
+}
+
+/// Generate the integration JSON representation of a `RecordBatch`
+pub fn batch_to_json(batch: &RecordBatch) -> Result<Value> {
+    let columns = (0..batch.num_columns())
+        .map(|i| {
+            let data = batch.column(i).data();
+            column_to_json(
+                batch.schema().field(i).name(),
+                &data,
+                data.offset(),
+                data.len(),
+            )
+        })
+        .collect::<Result<Vec<Value>>>()?;
+    Ok(json!({
+        "count": batch.num_rows(),
+        "columns": columns,
+    }))
+}
+
+/// Returns the values of a buffer of `data` as a slice that ends at `end`. This
+/// function is unsafe because the buffer must hold at least `end` values of type `T`.
+unsafe fn typed_values<T>(data: &ArrayDataRef, buffer: usize, end: usize) -> &[T] {
+    from_raw_parts(data.buffers()[buffer].raw_data() as *const T, end)
+}
+
+macro_rules! values_to_json {
+    ($data:expr, $start:expr, $len:expr, $native_ty:ty, $to_json:expr) => {{
+        let values = unsafe { typed_values::<$native_ty>($data, 0, $start + $len) };
+        values[$start..]
+            .iter()
+            .map($to_json)
+            .collect::<Vec<Value>>()
+    }};
+}
+
+/// Generate the integration JSON representation of the `len` slots of `data` from
+/// `start`, an index that already includes the offset of `data`
+fn column_to_json(
+    name: &str,
+    data: &ArrayDataRef,
+    start: usize,
+    len: usize,
+) -> Result<Value> {
+    let mut json = Map::new();
+    json.insert("name".to_string(), json!(name));
+    json.insert("count".to_string(), json!(len));
+    if data.data_type() != &DataType::Null {
+        let validity = (start..start + len)
+            .map(|i| if data.is_valid(i) { 1 } else { 0 })
+            .collect::<Vec<u8>>();
+        json.insert("VALIDITY".to_string(), json!(validity));
+    }
+
+    // 64-bit integers are written as strings, as JSON numbers may not represent them
+    let int64 = |v: &i64| json!(v.to_string());
+    let uint64 = |v: &u64| json!(v.to_string());
+    let values = match data.data_type() {
+        DataType::Null => None,
+        DataType::Boolean => Some(
+            (start..start + len)
+                .map(|i| json!(bit_util::get_bit(data.buffers()[0].data(), i)))
+                .collect(),
+        ),
+        DataType::Int8 => Some(values_to_json!(data, start, len, i8, |v| json!(v))),
+        DataType::Int16 => Some(values_to_json!(data, start, len, i16, |v| json!(v))),
+        DataType::Int32 | DataType::Date(DateUnit::Day) | DataType::Time32(_) => {
+            Some(values_to_json!(data, start, len, i32, |v| json!(v)))
+        }
+        DataType::Int64 | DataType::Date(DateUnit::Millisecond) | DataType::Time64(_) => {
+            Some(values_to_json!(data, start, len, i64, int64))
+        }
+        DataType::UInt8 => Some(values_to_json!(data, start, len, u8, |v| json!(v))),
+        DataType::UInt16 => Some(values_to_json!(data, start, len, u16, |v| json!(v))),
+        DataType::UInt32 => Some(values_to_json!(data, start, len, u32, |v| json!(v))),
+        DataType::UInt64 => Some(values_to_json!(data, start, len, u64, uint64)),
+        DataType::Float32 => Some(values_to_json!(data, start, len, f32, |v| json!(v))),
+        DataType::Float64 => Some(values_to_json!(data, start, len, f64, |v| json!(v))),
+        DataType::Binary | DataType::Utf8 => {
+            let offsets = unsafe { typed_values::<i32>(data, 0, start + len + 1) };
+            json.insert("OFFSET".to_string(), offsets_to_json(offsets, start, len));
+            let bytes = data.buffers()[1].data();
+            let values = (start..start + len)
+                .map(|i| {
+                    let value = &bytes[offsets[i] as usize..offsets[i + 1] as usize];
+                    if data.data_type() == &DataType::Utf8 {
+                        std::str::from_utf8(value).map(|s| json!(s)).map_err(|_| {
+                            json_error(format!("Column '{}' is not valid UTF-8", name))
+                        })
+                    } else {
+                        Ok(json!(value
+                            .iter()
+                            .map(|b| format!("{:02X}", b))
+                            .collect::<String>()))
+                    }
+                })
+                .collect::<Result<Vec<Value>>>()?;
+            Some(values)
+        }
+        DataType::List(_) => {
+            let offsets = unsafe { typed_values::<i32>(data, 0, start + len + 1) };
+            json.insert("OFFSET".to_string(), offsets_to_json(offsets, start, len));
+            let child = &data.child_data()[0];
+            let child_json = column_to_json(
+                "item",
+                child,
+                child.offset() + offsets[start] as usize,
+                (offsets[start + len] - offsets[start]) as usize,
+            )?;
+            json.insert("children".to_string(), json!([child_json]));
+            None
+        }
+        DataType::FixedSizeList(_, size) => {
+            let size = *size as usize;
+            let child = &data.child_data()[0];
+            let child_json =
+                column_to_json("item", child, child.offset() + start * size, len * size)?;
+            json.insert("children".to_string(), json!([child_json]));
+            None
+        }
+        DataType::Struct(fields) => {
+            let children = fields
+                .iter()
+                .zip(data.child_data())
+                .map(|(field, child)| {
+                    column_to_json(field.name(), child, child.offset() + start, len)
+                })
+                .collect::<Result<Vec<Value>>>()?;
+            json.insert("children".to_string(), json!(children));
+            None
+        }
+        DataType::Union(fields, mode) => {
+            let type_ids = values_to_json!(data, start, len, i8, |v| json!(v));
+            json.insert("TYPE_ID".to_string(), json!(type_ids));
+            let children = fields
+                .iter()
+                .zip(data.child_data())
+                .map(|(field, child)| match mode {
+                    UnionMode::Sparse => {
+                        column_to_json(field.name(), child, child.offset() + start, len)
+                    }
+                    // the value offsets index into the whole of each child
+                    UnionMode::Dense => {
+                        column_to_json(field.name(), child, child.offset(), child.len())
+                    }
+                })
+                .collect::<Result<Vec<Value>>>()?;
+            if *mode == UnionMode::Dense {
+                let offsets = unsafe { typed_values::<i32>(data, 1, start + len) };
+                json.insert("OFFSET".to_string(), json!(offsets[start..]));
+            }
+            json.insert("children".to_string(), json!(children));
+            None
+        }
+        other => {
+            return Err(json_error(format!(
+                "Writing columns of type {:?} to integration JSON is not supported",
+                other
+            )));
+        }
+    };
+    if let Some(values) = values {
+        json.insert("DATA".to_string(), Value::Array(values));
+    }
+    Ok(Value::Object(json))
+}
+
+/// Generate the offsets of `len` slots from `start`, rebased to start at zero
+fn offsets_to_json(offsets: &[i32], start: usize, len: usize) -> Value {
+    json!(offsets[start..=start + len]
+        .iter()
+        .map(|offset| offset - offsets[start])
+        .collect::<Vec<i32>>())
+}
+
+/// Parse a `RecordBatch` of `schema` from its integration JSON representation
+pub fn batch_from_json(schema: &Arc<Schema>, json: &Value) -> Result<RecordBatch> {
+    let count = match json.get("count").and_then(Value::as_u64) {
+        Some(count) => count as usize,
+        None => return Err(json_error("Record batch missing 'count' attribute")),
+    };
+    let columns = match json.get("columns") {
+        Some(Value::Array(columns)) if columns.len() == schema.fields().len() => columns,
+        _ => {
+            return Err(json_error(format!(
+                "Record batch must have a list of {} columns",
+                schema.fields().len()
+            )));
+        }
+    };
+    if columns.is_empty() {
+        return Err(json_error("Record batch must have at least one column"));
+    }
+    let arrays = schema
+        .fields()
+        .iter()
+        .zip(columns)
+        .map(|(field, column)| {
+            let data = column_from_json(field, column)?;
+            if data.len() != count {
+                return Err(json_error(format!(
+                    "Column '{}' has {} values, but the record batch has {} rows",
+                    field.name(),
+                    data.len(),
+                    count
+                )));
+            }
+            Ok(make_array(data))
+        })
+        .collect::<Result<Vec<ArrayRef>>>()?;
+    Ok(RecordBatch::new(schema.clone(), arrays))
+}
+
+/// Returns the list stored under `key`, checking that it has `len` entries
+fn json_list<'a>(
+    field: &Field,
+    json: &'a Value,
+    key: &str,
+    len: usize,
+) -> Result<&'a Vec<Value>> {
+    match json.get(key) {
+        Some(Value::Array(values)) if values.len() == len => Ok(values),
+        _ => Err(json_error(format!(
+            "Column '{}' must have a '{}' list of {} entries",
+            field.name(),
+            key,
+            len
+        ))),
+    }
+}
+
+fn json_i64(value: &Value) -> Option<i64> {
+    match value {
+        Value::Number(n) => n.as_i64(),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+fn json_u64(value: &Value) -> Option<u64> {
+    match value {
+        Value::Number(n) => n.as_u64(),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+fn json_f64(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+macro_rules! values_from_json {
+    ($field:expr, $values:expr, $native_ty:ty, $parse:ident) => {{
+        let values = $values
+            .iter()
+            .map(|v| {
+                $parse(v).map(|v| v as $native_ty).ok_or_else(|| {
+                    json_error(format!(
+                        "Column '{}' has invalid value {}",
+                        $field.name(),
+                        v
+                    ))
+                })
+            })
+            .collect::<Result<Vec<$native_ty>>>()?;
+        Buffer::from(values.to_byte_slice())
+    }};
+}
+
+fn bits_from_json(
+    field: &Field,
+    values: &[Value],
+    is_set: fn(&Value) -> Option<bool>,
+) -> Result<(Buffer, usize)> {
+    let mut bits = vec![0; bit_util::ceil(values.len(), 8)];
+    let mut unset_count = 0;
+    for (i, value) in values.iter().enumerate() {
+        match is_set(value) {
+            Some(true) => bit_util::set_bit(&mut bits, i),
+            Some(false) => unset_count += 1,
+            None => {
+                return Err(json_error(format!(
+                    "Column '{}' has invalid bit {}",
+                    field.name(),
+                    value
+                )));
+            }
+        }
+    }
+    Ok((Buffer::from(bits), unset_count))
+}
+
+fn offsets_from_json(field: &Field, json: &Value, len: usize) -> Result<Vec<i32>> {
+    let offsets = json_list(field, json, "OFFSET", len + 1)?
+        .iter()
+        .map(|v| json_i64(v).map(|v| v as i32))
+        .collect::<Option<Vec<i32>>>();
+    match offsets {
+        Some(ref offsets)
+            if offsets[0] >= 0 && offsets.windows(2).all(|w| w[0] <= w[1]) => {}
+        _ => {
+            return Err(json_error(format!(
+                "Column '{}' offsets must be increasing integers",
+                field.name()
+            )));
+        }
+    }
+    Ok(offsets.unwrap())
+}
+
+fn hex_to_bytes(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 || !hex.is_ascii() {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
+}
+
+fn children_from_json(
+    field: &Field,
+    json: &Value,
+    child_fields: &[Field],
+) -> Result<Vec<ArrayDataRef>> {
+    json_list(field, json, "children", child_fields.len())?
+        .iter()
+        .zip(child_fields)
+        .map(|(child, child_field)| column_from_json(child_field, child))
+        .collect()
+}
+
+/// Parse the data of a column of type `field` from its integration JSON representation
+fn column_from_json(field: &Field, json: &Value) -> Result<ArrayDataRef> {
+    let count = match json.get("count").and_then(Value::as_u64) {
+        Some(count) => count as usize,
+        None => {
+            return Err(json_error(format!(
+                "Column '{}' missing 'count' attribute",
+                field.name()
+            )));
+        }
+    };
+    let invalid =
+        |message: &str| Err(json_error(format!("Column '{}' {}", field.name(), message)));
+    let mut builder = ArrayData::builder(field.data_type().clone()).len(count);
+    // null arrays have no validity bitmap
+    if field.data_type() != &DataType::Null && json.get("VALIDITY").is_some() {
+        let validity = json_list(field, json, "VALIDITY", count)?;
+        let (bitmap, null_count) =
+            bits_from_json(field, validity, |v| match v.as_u64() {
+                Some(1) => Some(true),
+                Some(0) => Some(false),
+                _ => None,
+            })?;
+        builder = builder.null_count(null_count).null_bit_buffer(bitmap);
+    }
+
+    let data = || json_list(field, json, "DATA", count);
+    builder = match field.data_type() {
+        DataType::Null => builder,
+        DataType::Boolean => {
+            builder.add_buffer(bits_from_json(field, data()?, Value::as_bool)?.0)
+        }
+        DataType::Int8 => {
+            builder.add_buffer(values_from_json!(field, data()?, i8, json_i64))
+        }
+        DataType::Int16 => {
+            builder.add_buffer(values_from_json!(field, data()?, i16, json_i64))
+        }
+        DataType::Int32 | DataType::Date(DateUnit::Day) | DataType::Time32(_) => {
+            builder.add_buffer(values_from_json!(field, data()?, i32, json_i64))
+        }
+        DataType::Int64 | DataType::Date(DateUnit::Millisecond) | DataType::Time64(_) => {
+            builder.add_buffer(values_from_json!(field, data()?, i64, json_i64))
+        }
+        DataType::UInt8 => {
+            builder.add_buffer(values_from_json!(field, data()?, u8, json_u64))
+        }
+        DataType::UInt16 => {
+            builder.add_buffer(values_from_json!(field, data()?, u16, json_u64))
+        }
+        DataType::UInt32 => {
+            builder.add_buffer(values_from_json!(field, data()?, u32, json_u64))
+        }
+        DataType::UInt64 => {
+            builder.add_buffer(values_from_json!(field, data()?, u64, json_u64))
+        }
+        DataType::Float32 => {
+            builder.add_buffer(values_from_json!(field, data()?, f32, json_f64))
+        }
+        DataType::Float64 => {
+            builder.add_buffer(values_from_json!(field, data()?, f64, json_f64))
+        }
+        DataType::Binary | DataType::Utf8 => {
+            let offsets = offsets_from_json(field, json, count)?;
+            let mut bytes = vec![];
+            for (i, value) in data()?.iter().enumerate() {
+                match (field.data_type(), value) {
+                    (DataType::Utf8, Value::String(s)) => {
+                        bytes.extend_from_slice(s.as_bytes())
+                    }
+                    (DataType::Binary, Value::String(s)) => match hex_to_bytes(s) {
+                        Some(value) => bytes.extend_from_slice(&value),
+                        None => return invalid("has a value that is not a hex string"),
+                    },
+                    _ => return invalid("values must be strings"),
+                }
+                if offsets[0] != 0 || offsets[i + 1] as usize != bytes.len() {
+                    return invalid("offsets do not match the lengths of its values");
+                }
+            }
+            builder
+                .add_buffer(Buffer::from(offsets.to_byte_slice()))
+                .add_buffer(Buffer::from(bytes))
+        }
+        DataType::List(value_type) => {
+            let offsets = offsets_from_json(field, json, count)?;
+            let child_field = Field::new("item", (**value_type).clone(), true);
+            let child = children_from_json(field, json, &[child_field])?.remove(0);
+            if offsets[count] as usize > child.len() {
+                return invalid("offsets exceed the length of its child");
+            }
+            builder
+                .add_buffer(Buffer::from(offsets.to_byte_slice()))
+                .add_child_data(child)
+        }
+        DataType::FixedSizeList(value_type, size) => {
+            let child_field = Field::new("item", (**value_type).clone(), true);
+            let child = children_from_json(field, json, &[child_field])?.remove(0);
+            if child.len() < count * *size as usize {
+                return invalid("child does not have enough values");
+            }
+            builder.add_child_data(child)
+        }
+        DataType::Struct(fields) => {
+            let children = children_from_json(field, json, fields)?;
+            if children.iter().any(|child| child.len() != count) {
+                return invalid("children must have the same length as the column");
+            }
+            builder.child_data(children)
+        }
+        DataType::Union(fields, mode) => {
+            let children = children_from_json(field, json, fields)?;
+            let type_ids = json_list(field, json, "TYPE_ID", count)?
+                .iter()
+                .map(|v| match json_i64(v) {
+                    Some(id) if id >= 0 && (id as usize) < fields.len() => Some(id as i8),
+                    _ => None,
+                })
+                .collect::<Option<Vec<i8>>>();
+            let type_ids = match type_ids {
+                Some(type_ids) => type_ids,
+                None => return invalid("has an invalid type id"),
+            };
+            builder = builder
+                .add_buffer(Buffer::from(type_ids.to_byte_slice()))
+                .child_data(children.clone());
+            match mode {
+                UnionMode::Sparse => {
+                    if children.iter().any(|child| child.len() < count) {
+                        return invalid("children must have a value for every slot");
+                    }
+                    builder
+                }
+                UnionMode::Dense => {
+                    let offsets = json_list(field, json, "OFFSET", count)?
+                        .iter()
+                        .zip(&type_ids)
+                        .map(|(v, id)| match json_i64(v) {
+                            Some(offset)
+                                if offset >= 0
+                                    && (offset as usize)
+                                        < children[*id as usize].len() =>
+                            {
+                                Some(offset as i32)
+                            }
+                            _ => None,
+                        })
+                        .collect::<Option<Vec<i32>>>();
+                    match offsets {
+                        Some(offsets) => {
+                            builder.add_buffer(Buffer::from(offsets.to_byte_slice()))
+                        }
+                        None => return invalid("has an invalid value offset"),
+                    }
+                }
+            }
+        }
+        other => {
+            return Err(json_error(format!(
+                "Reading columns of type {:?} from integration JSON is not supported",
+                other
+            )));
+        }
+    };
+    Ok(builder.build())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::fs::File;
+
+    use crate::array::*;
+    use crate::builder::*;
+    use crate::compute::array_equals;
+
+    fn round_trip(schema: Schema, columns: Vec<ArrayRef>) {
+        let schema = Arc::new(schema);
+        let batch = RecordBatch::new(schema.clone(), columns.clone());
+        let mut json = vec![];
+        write(&mut json, &schema, &[batch]).unwrap();
+        let (read_schema, batches) = read(&json[..]).unwrap();
+        assert_eq!(schema, read_schema);
+        assert_eq!(1, batches.len());
+        for (i, column) in columns.iter().enumerate() {
+            assert!(array_equals(column.as_ref(), batches[0].column(i).as_ref()));
+        }
+    }
+
+    #[test]
+    fn test_primitive_round_trip() {
+        let schema = Schema::new(vec![
+            Field::new("b", DataType::Boolean, true),
+            Field::new("i8", DataType::Int8, true),
+            Field::new("i64", DataType::Int64, false),
+            Field::new("u64", DataType::UInt64, false),
+            Field::new("f32", DataType::Float32, true),
+            Field::new("d", DataType::Date(DateUnit::Millisecond), true),
+            Field::new("t", DataType::Time32(TimeUnit::Second), true),
+            Field::new("n", DataType::Null, true),
+        ]);
+        round_trip(
+            schema,
+            vec![
+                Arc::new(BooleanArray::from(vec![Some(true), None, Some(false)])),
+                Arc::new(Int8Array::from(vec![Some(-1), Some(2), None])),
+                Arc::new(Int64Array::from(vec![
+                    i64::min_value(),
+                    0,
+                    i64::max_value(),
+                ])),
+                Arc::new(UInt64Array::from(vec![0, 1, u64::max_value()])),
+                Arc::new(Float32Array::from(vec![Some(1.5), None, Some(-0.25)])),
+                Arc::new(Date64Array::from(vec![Some(86_400_000), None, Some(0)])),
+                Arc::new(Time32SecondArray::from(vec![None, Some(60), Some(3600)])),
+                Arc::new(NullArray::new(3)),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_nested_round_trip() {
+        let mut list = ListBuilder::new(BinaryBuilder::new(8));
+        list.values().append_string("ab").unwrap();
+        list.append(true).unwrap();
+        list.append(false).unwrap();
+        list.values().append_string("").unwrap();
+        list.values().append_string("c").unwrap();
+        list.append(true).unwrap();
+
+        let mut fixed = FixedSizeListBuilder::new(Int32Builder::new(6), 2);
+        fixed.values().append_slice(&[1, 2]).unwrap();
+        fixed.append(true).unwrap();
+        fixed.values().append_slice(&[0, 0]).unwrap();
+        fixed.append(false).unwrap();
+        fixed.values().append_slice(&[5, 6]).unwrap();
+        fixed.append(true).unwrap();
+
+        let struct_fields = vec![
+            Field::new("x", DataType::Float64, false),
+            Field::new("y", DataType::Binary, true),
+        ];
+        let structs = StructArray::from(vec![
+            (
+                struct_fields[0].clone(),
+                Arc::new(Float64Array::from(vec![1.0, 2.0, 3.0])) as ArrayRef,
+            ),
+            (
+                struct_fields[1].clone(),
+                Arc::new(BinaryArray::from(vec![
+                    &b"\x00\xff"[..],
+                    &b""[..],
+                    &b"z"[..],
+                ])) as ArrayRef,
+            ),
+        ]);
+
+        let union_fields = vec![
+            Field::new("i", DataType::Int32, true),
+            Field::new("s", DataType::Utf8, true),
+        ];
+        let mut union =
+            UnionBuilder::from_fields(union_fields.clone(), UnionMode::Dense, 3);
+        union
+            .field_builder::<Int32Builder>(0)
+            .unwrap()
+            .append_value(1)
+            .unwrap();
+        union.append(0, true).unwrap();
+        union
+            .field_builder::<BinaryBuilder>(1)
+            .unwrap()
+            .append_string("u")
+            .unwrap();
+        union.append(1, true).unwrap();
+        union
+            .field_builder::<Int32Builder>(0)
+            .unwrap()
+            .append_null()
+            .unwrap();
+        union.append(0, false).unwrap();
+
+        let schema = Schema::new(vec![
+            Field::new("l", DataType::List(Box::new(DataType::Utf8)), true),
+            Field::new(
+                "f",
+                DataType::FixedSizeList(Box::new(DataType::Int32), 2),
+                true,
+            ),
+            Field::new("s", DataType::Struct(struct_fields), false),
+            Field::new("u", DataType::Union(union_fields, UnionMode::Dense), true),
+        ]);
+        round_trip(
+            schema,
+            vec![
+                Arc::new(list.finish()),
+                Arc::new(fixed.finish()),
+                Arc::new(structs),
+                Arc::new(union.finish()),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_write_sliced_list() {
+        let mut list = ListBuilder::new(Int32Builder::new(8));
+        for values in &[vec![1], vec![2, 3], vec![4, 5, 6]] {
+            list.values().append_slice(values).unwrap();
+            list.append(true).unwrap();
+        }
+        let list = list.finish();
+        let sliced = ArrayData::builder(list.data_type().clone())
+            .len(2)
+            .offset(1)
+            .add_buffer(list.data().buffers()[0].clone())
+            .add_child_data(list.data().child_data()[0].clone())
+            .build();
+        let json = column_to_json("l", &sliced, 1, 2).unwrap();
+        assert_eq!(json!([0, 2, 5]), json["OFFSET"]);
+        assert_eq!(
+            json!(["2", "3", "4", "5", "6"]),
+            json["children"][0]["DATA"]
+        );
+    }
+
+    #[test]
+    fn test_read_fixture() {
+        let file = File::open("test/data/integration.json").unwrap();
+        let (schema, batches) = read(file).unwrap();
+
+        assert_eq!(3, schema.fields().len());
+        assert_eq!(
+            &DataType::List(Box::new(DataType::Int64)),
+            schema.field(1).data_type()
+        );
+        assert_eq!("fixture", schema.metadata()["source"]);
+        assert_eq!(2, batches.len());
+
+        let batch = &batches[0];
+        assert_eq!(3, batch.num_rows());
+        let a = batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<Int32Array>()
+            .unwrap();
+        assert_eq!(1, a.value(0));
+        assert!(a.is_null(1));
+        let b = batch
+            .column(1)
+            .as_any()
+            .downcast_ref::<ListArray>()
+            .unwrap();
+        assert_eq!(2, b.value_length(0));
+        assert_eq!(0, b.value_length(2));
+        let c = batch
+            .column(2)
+            .as_any()
+            .downcast_ref::<BinaryArray>()
+            .unwrap();
+        assert_eq!("héllo", c.get_string(0));
+        assert!(c.is_null(2));
+
+        assert_eq!(1, batches[1].num_rows());
+    }
+
+    #[test]
+    fn test_read_invalid_columns() {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, true)]));
+        let invalid = vec![
+            json!({"count": 2, "columns": []}),
+            json!({"count": 2, "columns": [{"name": "a", "count": 1, "DATA": [1]}]}),
+            json!({"count": 2, "columns": [{"name": "a", "count": 2, "DATA": [1]}]}),
+            json!({"count": 1, "columns": [{"name": "a", "count": 1, "DATA": ["x"]}]}),
+            json!({"count": 1, "columns": [
+                {"name": "a", "count": 1, "VALIDITY": [2], "DATA": [1]}
+            ]}),
+        ];
+        for batch in &invalid {
+            assert!(batch_from_json(&schema, batch).is_err(), "{}", batch);
+        }
+
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Utf8, true)]));
+        let batch = json!({"count": 1, "columns": [
+            {"name": "a", "count": 1, "OFFSET": [0, 5], "DATA": ["abc"]}
+        ]});
+        assert!(batch_from_json(&schema, &batch).is_err());
+    }
+
+    #[test]
+    fn test_schema_json() {
+        let schema = Schema::new(vec![
+            Field::new("l", DataType::List(Box::new(DataType::Int16)), true),
+            Field::new("t", DataType::Time64(TimeUnit::Nanosecond), false),
+        ]);
+        let json = schema_to_json(&schema);
+        assert_eq!(
+            "{\"fields\":[\
+             {\"name\":\"l\",\"nullable\":true,\"type\":{\"name\":\"list\"},\"children\":[\
+             {\"name\":\"item\",\"nullable\":true,\
+             \"type\":{\"name\":\"int\",\"bitWidth\":16,\"isSigned\":true},\
+             \"children\":[]}]},\
+             {\"name\":\"t\",\"nullable\":false,\
+             \"type\":{\"name\":\"time\",\"bitWidth\":64,\"unit\":\"NANOSECOND\"},\
+             \"children\":[]}]}",
+            json.to_string()
+        );
+        assert_eq!(schema, schema_from_json(&json).unwrap());
+    }
+}
@@ -15,9 +15,12 @@
 // specific language governing permissions and limitations
 // under the License.
 
-//! Transfer data between the Arrow memory format and JSON line-delimited records.
+//! Transfer data between the Arrow memory format and JSON line-delimited records, and
+//! read and write the JSON format of the Arrow integration tests.
 
+pub mod integration;
 pub mod reader;
 
+pub use self::reader::infer_json_schema;
 pub use self::reader::Reader;
 pub use self::reader::ReaderBuilder;
@@ -44,7 +44,6 @@
 
 use indexmap::map::IndexMap as HashMap;
 use indexmap::set::IndexSet as HashSet;
-use std::fs::File;
 use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
 use std::sync::Arc;
 
@@ -157,23 +156,43 @@ fn generate_schema(spec: HashMap<String, HashSet<DataType>>) -> Result<Arc<Schem
     }
 }
 
-/// Infer the fields of a JSON file by reading the first n records of the file, with
-/// `max_read_records` controlling the maximum number of records to read.
-///
-/// If `max_read_records` is not set, the whole file is read to infer its field types.
-fn infer_json_schema(file: File, max_read_records: Option<usize>) -> Result<Arc<Schema>> {
-    let mut values: HashMap<String, HashSet<DataType>> = HashMap::new();
-    let mut reader = BufReader::new(file.try_clone()?);
-
+/// Reads the next non-empty line of line-delimited JSON as a value, or returns `None`
+/// at the end of the input
+fn read_record<R: BufRead>(reader: &mut R) -> Result<Option<Value>> {
     let mut line = String::new();
-    for _ in 0..max_read_records.unwrap_or(std::usize::MAX) {
-        &reader.read_line(&mut line)?;
-        if line.is_empty() {
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        if !line.trim().is_empty() {
             break;
         }
-        let record: Value = serde_json::from_str(&line.trim()).expect("Not valid JSON");
+    }
+    serde_json::from_str(line.trim())
+        .map(Some)
+        .map_err(|e| ArrowError::JsonError(format!("Not valid JSON: {}", e)))
+}
 
-        line = String::new();
+/// Infer the fields of line-delimited JSON by reading the first n records from the
+/// current position of `reader`, with `max_read_records` controlling the maximum number
+/// of records to read. The reader is returned to its initial position afterwards.
+///
+/// If `max_read_records` is not set, all the records are read to infer their field
+/// types.
+pub fn infer_json_schema<R: Read + Seek>(
+    reader: &mut R,
+    max_read_records: Option<usize>,
+) -> Result<Arc<Schema>> {
+    let mut values: HashMap<String, HashSet<DataType>> = HashMap::new();
+    let start = reader.seek(SeekFrom::Current(0))?;
+    let mut buf_reader = BufReader::new(&mut *reader);
+
+    for _ in 0..max_read_records.unwrap_or(std::usize::MAX) {
+        let record = match read_record(&mut buf_reader)? {
+            Some(record) => record,
+            None => break,
+        };
 
         match record {
             Value::Object(map) => {
@@ -308,8 +327,9 @@ fn infer_json_schema(file: File, max_read_records: Option<usize>) -> Result<Arc<
 
     let schema = generate_schema(values)?;
 
-    // return the reader seek back to the start
-    &reader.into_inner().seek(SeekFrom::Start(0))?;
+    // return the reader back to where the records started
+    drop(buf_reader);
+    reader.seek(SeekFrom::Start(start))?;
 
     Ok(schema)
 }
@@ -329,8 +349,8 @@ pub struct Reader<R: Read> {
 impl<R: Read> Reader<R> {
     /// Create a new JSON Reader from any value that implements the `Read` trait.
     ///
-    /// If reading a `File`, you can customise the Reader, such as to enable schema
-    /// inference, use `ReaderBuilder`.
+    /// To customise the Reader, such as to enable schema inference, use
+    /// `ReaderBuilder`.
     pub fn new(
         reader: BufReader<R>,
         schema: Arc<Schema>,
@@ -348,16 +368,15 @@ impl<R: Read> Reader<R> {
     /// Read the next batch of records
     pub fn next(&mut self) -> Result<Option<RecordBatch>> {
         let mut rows: Vec<Value> = Vec::with_capacity(self.batch_size);
-        let mut line = String::new();
-        for _ in 0..self.batch_size {
-            self.reader.read_line(&mut line)?;
-            if !line.is_empty() {
-                rows.push(serde_json::from_str(&line).expect("Not valid JSON"));
-                line = String::new();
-            } else {
-                break;
+        while rows.len() < self.batch_size {
+            match read_record(&mut self.reader)? {
+                Some(row) => rows.push(row),
+                None => break,
             }
         }
+        if rows.is_empty() {
+            return Ok(None);
+        }
 
         let rows = &rows[..];
         let projection = self.projection.clone().unwrap_or(vec![]);
@@ -703,18 +722,15 @@ impl ReaderBuilder {
         self
     }
 
-    /// Create a new `Reader` from the `ReaderBuilder`
-    pub fn build<R: Read>(self, file: File) -> Result<Reader<File>> {
+    /// Create a new `Reader` from the `ReaderBuilder`, reading records from any source
+    /// that can be rewound after inferring the schema, such as a `File` or a `Cursor`
+    pub fn build<R: Read + Seek>(self, mut source: R) -> Result<Reader<R>> {
         // check if schema should be inferred
         let schema = match self.schema {
             Some(schema) => schema,
-            None => {
-                let inferred = infer_json_schema(file.try_clone()?, self.max_records)?;
-
-                inferred
-            }
+            None => infer_json_schema(&mut source, self.max_records)?,
         };
-        let buf_reader = BufReader::new(file);
+        let buf_reader = BufReader::new(source);
         Ok(Reader::new(
             buf_reader,
             schema,
@@ -728,6 +744,9 @@ impl ReaderBuilder {
 mod tests {
     use super::*;
 
+    use std::fs::File;
+    use std::io::Cursor;
+
     #[test]
     fn test_json_basic() {
         let builder = ReaderBuilder::new().infer_schema(None).with_batch_size(64);
@@ -982,6 +1001,59 @@ mod tests {
         assert_eq!(false, cc.is_valid(5));
     }
 
+    #[test]
+    fn test_json_from_memory() {
+        let json = "{\"a\": 1, \"b\": \"x\"}\n\n\
+                    {\"a\": null, \"b\": \"y\"}\n  \n{\"a\": 3}\n";
+        let builder = ReaderBuilder::new().infer_schema(None).with_batch_size(2);
+        let mut reader = builder.build(Cursor::new(json)).unwrap();
+
+        let batch = reader.next().unwrap().unwrap();
+        assert_eq!(2, batch.num_rows());
+        assert_eq!(
+            &Schema::new(vec![
+                Field::new("a", DataType::Int64, true),
+                Field::new("b", DataType::Utf8, true),
+            ]),
+            batch.schema().as_ref()
+        );
+        let a = batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<Int64Array>()
+            .unwrap();
+        assert_eq!(1, a.value(0));
+        assert!(a.is_null(1));
+
+        // empty lines are skipped, and missing values are null
+        let batch = reader.next().unwrap().unwrap();
+        assert_eq!(1, batch.num_rows());
+        assert!(batch.column(1).is_null(0));
+        assert!(reader.next().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_infer_schema_restores_position() {
+        let json = "{\"a\": 1.5}\n{\"a\": 2}\n";
+        let mut cursor = Cursor::new(json);
+        let schema = infer_json_schema(&mut cursor, Some(1)).unwrap();
+        assert_eq!(&Field::new("a", DataType::Float64, true), schema.field(0));
+        assert_eq!(0, cursor.position());
+    }
+
+    #[test]
+    fn test_invalid_json_is_an_error() {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int64, true)]));
+        let json = "{\"a\": 1}\n{\"a\": \n";
+        let mut reader = Reader::new(BufReader::new(Cursor::new(json)), schema, 10, None);
+        match reader.next() {
+            Err(ArrowError::JsonError(message)) => {
+                assert!(message.starts_with("Not valid JSON"))
+            }
+            _ => panic!("expected a JSON error"),
+        }
+    }
+
     #[test]
     #[should_panic(expected = "Not valid JSON")]
     fn test_invalid_file() {
@@ -39,6 +39,7 @@ pub mod compute;
 pub mod csv;
 pub mod datatypes;
 pub mod error;
+pub mod ffi;
 pub mod json;
 pub mod memory;
 pub mod record_batch;
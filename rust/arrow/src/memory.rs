@@ -20,7 +20,11 @@
 
 use libc;
 use std::cmp;
+use std::collections::HashMap;
+use std::fmt::Debug;
 use std::mem;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
 
 use crate::error::{ArrowError, Result};
 
@@ -106,6 +110,156 @@ pub fn is_aligned<T>(p: *const T, a: usize) -> bool {
     pmoda == 0
 }
 
+/// An allocator of 64-byte aligned memory regions for `MutableBuffer`s, and so for the
+/// buffers they are frozen into.
+///
+/// Memory allocated by an allocator is always released through the same allocator, so
+/// implementations can track how much memory is in use, or keep released memory to
+/// reuse it for later allocations.
+pub trait Allocator: Debug + Send + Sync {
+    /// Allocate `size` bytes of memory aligned to a 64-byte boundary
+    fn allocate(&self, size: usize) -> Result<*mut u8>;
+
+    /// Release `size` bytes of memory at `ptr` that were allocated by this allocator
+    fn free(&self, ptr: *mut u8, size: usize);
+
+    /// Move the `old_size` bytes of memory at `ptr` into a new region of `new_size`
+    /// bytes, copying as much of the data as fits
+    fn reallocate(
+        &self,
+        old_size: usize,
+        new_size: usize,
+        ptr: *mut u8,
+    ) -> Result<*mut u8> {
+        let new_ptr = self.allocate(new_size)?;
+        unsafe {
+            memcpy(new_ptr, ptr, cmp::min(old_size, new_size));
+        }
+        self.free(ptr, old_size);
+        Ok(new_ptr)
+    }
+
+    /// Returns the number of bytes currently allocated by this allocator and not yet
+    /// released
+    fn allocated_bytes(&self) -> usize;
+}
+
+/// An allocator that allocates directly from the system, keeping count of the memory
+/// that is in use.
+#[derive(Debug, Default)]
+pub struct SystemAllocator {
+    allocated: AtomicUsize,
+}
+
+impl SystemAllocator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Allocator for SystemAllocator {
+    fn allocate(&self, size: usize) -> Result<*mut u8> {
+        let ptr = allocate_aligned(size)?;
+        self.allocated.fetch_add(size, Ordering::SeqCst);
+        Ok(ptr)
+    }
+
+    fn free(&self, ptr: *mut u8, size: usize) {
+        free_aligned(ptr);
+        self.allocated.fetch_sub(size, Ordering::SeqCst);
+    }
+
+    fn allocated_bytes(&self) -> usize {
+        self.allocated.load(Ordering::SeqCst)
+    }
+}
+
+/// An allocator that keeps released memory in a pool instead of returning it to the
+/// system, and reuses it for later allocations of the same size.
+///
+/// Buffers grow in multiples of 64 bytes, so the batches of a query that have the same
+/// number of rows and schema usually need buffers of the same sizes, and can reuse the
+/// buffers of batches that have already been dropped. At most `max_pooled_bytes` are
+/// kept in the pool; memory released beyond that is returned to the system.
+#[derive(Debug)]
+pub struct PoolAllocator {
+    /// The maximum number of released bytes to keep in the pool
+    max_pooled_bytes: usize,
+    /// The addresses of the released memory regions, by their size
+    pool: Mutex<HashMap<usize, Vec<usize>>>,
+    /// The number of bytes in the pool
+    pooled: AtomicUsize,
+    /// The number of bytes allocated and not yet released
+    allocated: AtomicUsize,
+}
+
+impl PoolAllocator {
+    /// Create an allocator that keeps at most `max_pooled_bytes` of released memory
+    pub fn new(max_pooled_bytes: usize) -> Self {
+        Self {
+            max_pooled_bytes,
+            pool: Mutex::new(HashMap::new()),
+            pooled: AtomicUsize::new(0),
+            allocated: AtomicUsize::new(0),
+        }
+    }
+
+    /// Returns the number of released bytes that are kept in the pool
+    pub fn pooled_bytes(&self) -> usize {
+        self.pooled.load(Ordering::SeqCst)
+    }
+
+    /// Return all the memory in the pool to the system
+    pub fn clear(&self) {
+        let mut pool = self.pool.lock().unwrap();
+        for (size, ptrs) in pool.drain() {
+            for ptr in ptrs {
+                free_aligned(ptr as *const u8);
+                self.pooled.fetch_sub(size, Ordering::SeqCst);
+            }
+        }
+    }
+}
+
+impl Allocator for PoolAllocator {
+    fn allocate(&self, size: usize) -> Result<*mut u8> {
+        let pooled = {
+            let mut pool = self.pool.lock().unwrap();
+            pool.get_mut(&size).and_then(|ptrs| ptrs.pop())
+        };
+        let ptr = match pooled {
+            Some(ptr) => {
+                self.pooled.fetch_sub(size, Ordering::SeqCst);
+                ptr as *mut u8
+            }
+            None => allocate_aligned(size)?,
+        };
+        self.allocated.fetch_add(size, Ordering::SeqCst);
+        Ok(ptr)
+    }
+
+    fn free(&self, ptr: *mut u8, size: usize) {
+        self.allocated.fetch_sub(size, Ordering::SeqCst);
+        let mut pool = self.pool.lock().unwrap();
+        if self.pooled.load(Ordering::SeqCst) + size <= self.max_pooled_bytes {
+            pool.entry(size).or_insert_with(Vec::new).push(ptr as usize);
+            self.pooled.fetch_add(size, Ordering::SeqCst);
+        } else {
+            free_aligned(ptr);
+        }
+    }
+
+    fn allocated_bytes(&self) -> usize {
+        self.allocated.load(Ordering::SeqCst)
+    }
+}
+
+impl Drop for PoolAllocator {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -133,4 +287,62 @@ mod tests {
         assert_eq!(false, is_aligned::<u8>(ptr, 2));
         assert_eq!(false, is_aligned::<u8>(ptr, 4));
     }
+
+    #[test]
+    fn test_system_allocator() {
+        let allocator = SystemAllocator::new();
+        let p = allocator.allocate(128).unwrap();
+        assert!(is_aligned(p, 64));
+        assert_eq!(128, allocator.allocated_bytes());
+
+        let p = allocator.reallocate(128, 256, p).unwrap();
+        assert!(is_aligned(p, 64));
+        assert_eq!(256, allocator.allocated_bytes());
+
+        allocator.free(p, 256);
+        assert_eq!(0, allocator.allocated_bytes());
+    }
+
+    #[test]
+    fn test_pool_allocator_reuse() {
+        let allocator = PoolAllocator::new(1024);
+        let p1 = allocator.allocate(128).unwrap();
+        let p2 = allocator.allocate(128).unwrap();
+        assert_eq!(256, allocator.allocated_bytes());
+        assert_eq!(0, allocator.pooled_bytes());
+
+        allocator.free(p1, 128);
+        assert_eq!(128, allocator.allocated_bytes());
+        assert_eq!(128, allocator.pooled_bytes());
+
+        // memory of a different size is not reused
+        let p3 = allocator.allocate(64).unwrap();
+        assert_ne!(p1, p3);
+        assert_eq!(128, allocator.pooled_bytes());
+
+        let p4 = allocator.allocate(128).unwrap();
+        assert_eq!(p1, p4);
+        assert_eq!(0, allocator.pooled_bytes());
+        assert_eq!(320, allocator.allocated_bytes());
+
+        allocator.free(p2, 128);
+        allocator.free(p3, 64);
+        allocator.free(p4, 128);
+        assert_eq!(0, allocator.allocated_bytes());
+        assert_eq!(320, allocator.pooled_bytes());
+
+        allocator.clear();
+        assert_eq!(0, allocator.pooled_bytes());
+    }
+
+    #[test]
+    fn test_pool_allocator_limit() {
+        let allocator = PoolAllocator::new(192);
+        let p1 = allocator.allocate(128).unwrap();
+        let p2 = allocator.allocate(128).unwrap();
+        allocator.free(p1, 128);
+        allocator.free(p2, 128);
+        assert_eq!(0, allocator.allocated_bytes());
+        assert_eq!(128, allocator.pooled_bytes());
+    }
 }
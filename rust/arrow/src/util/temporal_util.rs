@@ -0,0 +1,263 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Utils for converting the values of date and time types to and from their string
+//! representations.
+//!
+//! Dates are counted in days since the UNIX epoch in the proleptic Gregorian calendar
+//! and written as `YYYY-MM-DD`. Times are counted in units since midnight and written
+//! as `HH:MM:SS`, with as many digits of the fraction of a second as the unit has.
+
+use crate::datatypes::TimeUnit;
+
+pub const MILLISECONDS_PER_DAY: i64 = 86_400_000;
+
+/// Returns the number of units of `unit` in a second
+pub fn units_per_second(unit: &TimeUnit) -> i64 {
+    match unit {
+        TimeUnit::Second => 1,
+        TimeUnit::Millisecond => 1_000,
+        TimeUnit::Microsecond => 1_000_000,
+        TimeUnit::Nanosecond => 1_000_000_000,
+    }
+}
+
+/// Returns the number of digits of the fraction of a second that `unit` can represent
+fn fraction_digits(unit: &TimeUnit) -> usize {
+    match unit {
+        TimeUnit::Second => 0,
+        TimeUnit::Millisecond => 3,
+        TimeUnit::Microsecond => 6,
+        TimeUnit::Nanosecond => 9,
+    }
+}
+
+/// Divide `a` by `b`, rounding towards negative infinity, so that times before the
+/// epoch fall on the day they belong to
+pub fn floor_div(a: i64, b: i64) -> i64 {
+    let d = a / b;
+    if a % b != 0 && (a < 0) != (b < 0) {
+        d - 1
+    } else {
+        d
+    }
+}
+
+fn is_leap_year(year: i64) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Returns the number of days since the UNIX epoch of a date
+pub fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = floor_div(year, 400);
+    let year_of_era = year - era * 400;
+    // months are counted from March, so that the leap day is the last day of the year
+    let month = i64::from(month);
+    let month = if month > 2 { month - 3 } else { month + 9 };
+    let day_of_year = (153 * month + 2) / 5 + i64::from(day) - 1;
+    let day_of_era =
+        year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Returns the year, month and day of the date `days` days after the UNIX epoch
+pub fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = floor_div(days, 146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524
+        - day_of_era / 146_096)
+        / 365;
+    let day_of_year =
+        day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+    let month = (if mp < 10 { mp + 3 } else { mp - 9 }) as u32;
+    let year = year_of_era + era * 400;
+    (if month <= 2 { year + 1 } else { year }, month, day)
+}
+
+/// Parse a number of at least one and at most `max_digits` ASCII digits
+fn parse_digits(s: &str, max_digits: usize) -> Option<i64> {
+    if s.is_empty() || s.len() > max_digits || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    s.parse().ok()
+}
+
+/// Parse a date written as `YYYY-MM-DD` into the number of days since the UNIX epoch.
+/// Returns `None` if the string is not a valid date.
+pub fn parse_date(s: &str) -> Option<i32> {
+    let mut parts = s.trim().split('-');
+    let year = parse_digits(parts.next()?, 4)?;
+    let month = parse_digits(parts.next()?, 2)? as u32;
+    let day = parse_digits(parts.next()?, 2)? as u32;
+    if parts.next().is_some()
+        || month < 1
+        || month > 12
+        || day < 1
+        || day > days_in_month(year, month)
+    {
+        return None;
+    }
+    Some(days_from_civil(year, month, day) as i32)
+}
+
+/// Format a number of days since the UNIX epoch as a `YYYY-MM-DD` date
+pub fn format_date(days: i64) -> String {
+    let (year, month, day) = civil_from_days(days);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// Parse a time of day written as `HH:MM:SS`, optionally followed by a fraction of a
+/// second, into the number of units of `unit` since midnight. Returns `None` if the
+/// string is not a valid time, or if the fraction is more precise than the unit.
+pub fn parse_time(s: &str, unit: &TimeUnit) -> Option<i64> {
+    let s = s.trim();
+    let (s, fraction) = match s.find('.') {
+        Some(i) => (&s[..i], Some(&s[i + 1..])),
+        None => (s, None),
+    };
+    let mut parts = s.split(':');
+    let hours = parse_digits(parts.next()?, 2)?;
+    let minutes = parse_digits(parts.next()?, 2)?;
+    let seconds = parse_digits(parts.next()?, 2)?;
+    if parts.next().is_some() || hours > 23 || minutes > 59 || seconds > 59 {
+        return None;
+    }
+
+    let digits = fraction_digits(unit);
+    let fraction = match fraction {
+        Some(fraction) => {
+            // scale the fraction to the unit, e.g. ".5" is 500 milliseconds
+            let value = parse_digits(fraction, digits)?;
+            value * 10i64.pow((digits - fraction.len()) as u32)
+        }
+        None => 0,
+    };
+    let seconds = (hours * 60 + minutes) * 60 + seconds;
+    Some(seconds * units_per_second(unit) + fraction)
+}
+
+/// Format a number of units of `unit` since midnight as a `HH:MM:SS` time, followed by
+/// the fraction of the second if the unit is smaller than a second
+pub fn format_time(value: i64, unit: &TimeUnit) -> String {
+    let units_per_second = units_per_second(unit);
+    let seconds = floor_div(value, units_per_second);
+    let fraction = value - seconds * units_per_second;
+    let time = format!(
+        "{:02}:{:02}:{:02}",
+        floor_div(seconds, 3600),
+        seconds / 60 % 60,
+        seconds % 60
+    );
+    match fraction_digits(unit) {
+        0 => time,
+        digits => format!("{}.{:0width$}", time, fraction, width = digits),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_civil_days() {
+        assert_eq!(0, days_from_civil(1970, 1, 1));
+        assert_eq!(17897, days_from_civil(2019, 1, 1));
+        assert_eq!(-1, days_from_civil(1969, 12, 31));
+        assert_eq!(11016, days_from_civil(2000, 2, 29));
+        for days in -800_000..800_000 {
+            let (year, month, day) = civil_from_days(days);
+            assert_eq!(days, days_from_civil(year, month, day));
+        }
+    }
+
+    #[test]
+    fn test_parse_date() {
+        assert_eq!(Some(0), parse_date("1970-01-01"));
+        assert_eq!(Some(17897), parse_date(" 2019-01-01 "));
+        assert_eq!(Some(-1), parse_date("1969-12-31"));
+        assert_eq!(Some(11016), parse_date("2000-2-29"));
+        assert_eq!(None, parse_date("2001-02-29"));
+        assert_eq!(None, parse_date("2019-13-01"));
+        assert_eq!(None, parse_date("2019-01-00"));
+        assert_eq!(None, parse_date("2019-01"));
+        assert_eq!(None, parse_date("2019-01-01-01"));
+        assert_eq!(None, parse_date("2019/01/01"));
+        assert_eq!(None, parse_date("+2019-01-01"));
+    }
+
+    #[test]
+    fn test_format_date() {
+        assert_eq!("1970-01-01", format_date(0));
+        assert_eq!("2019-01-01", format_date(17897));
+        assert_eq!("1969-12-31", format_date(-1));
+        assert_eq!("2000-02-29", format_date(11016));
+    }
+
+    #[test]
+    fn test_parse_time() {
+        assert_eq!(Some(0), parse_time("00:00:00", &TimeUnit::Second));
+        assert_eq!(Some(45296), parse_time("12:34:56", &TimeUnit::Second));
+        assert_eq!(
+            Some(45296500),
+            parse_time("12:34:56.5", &TimeUnit::Millisecond)
+        );
+        assert_eq!(
+            Some(86399999999),
+            parse_time("23:59:59.999999", &TimeUnit::Microsecond)
+        );
+        assert_eq!(
+            Some(1_000_000_001),
+            parse_time("00:00:01.000000001", &TimeUnit::Nanosecond)
+        );
+        assert_eq!(None, parse_time("12:34:56.5", &TimeUnit::Second));
+        assert_eq!(None, parse_time("12:34:56.", &TimeUnit::Millisecond));
+        assert_eq!(None, parse_time("24:00:00", &TimeUnit::Second));
+        assert_eq!(None, parse_time("12:60:00", &TimeUnit::Second));
+        assert_eq!(None, parse_time("12:34", &TimeUnit::Second));
+    }
+
+    #[test]
+    fn test_format_time() {
+        assert_eq!("00:00:00", format_time(0, &TimeUnit::Second));
+        assert_eq!("12:34:56", format_time(45296, &TimeUnit::Second));
+        assert_eq!(
+            "12:34:56.500",
+            format_time(45296500, &TimeUnit::Millisecond)
+        );
+        assert_eq!(
+            "23:59:59.999999",
+            format_time(86399999999, &TimeUnit::Microsecond)
+        );
+        assert_eq!(
+            "00:00:01.000000001",
+            format_time(1_000_000_001, &TimeUnit::Nanosecond)
+        );
+    }
+}
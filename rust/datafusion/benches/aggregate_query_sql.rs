@@ -55,7 +55,8 @@ fn aggregate_query(sql: &str) {
         "../../testing/data/csv/aggregate_test_100.csv",
         &schema,
         true,
-    );
+    )
+    .unwrap();
 
     // execute the query
     let relation = ctx.sql(&sql).unwrap();
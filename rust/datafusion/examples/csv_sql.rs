@@ -54,13 +54,14 @@ fn main() {
         "../../testing/data/csv/aggregate_test_100.csv",
         &schema,
         true,
-    );
+    )
+    .unwrap();
 
     // simple projection and selection
     let sql = "SELECT c1, MIN(c12), MAX(c12) FROM aggregate_test_100 WHERE c11 > 0.1 AND c11 < 0.9 GROUP BY c1";
 
     // execute the query
-    let relation = ctx.sql(&sql, 1024 * 1024).unwrap();
+    let relation = ctx.sql(&sql).unwrap();
 
     // display the relation
     let mut results = relation.borrow_mut();
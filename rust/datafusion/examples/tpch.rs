@@ -0,0 +1,179 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::time::{Duration, Instant};
+
+extern crate arrow;
+extern crate clap;
+extern crate datafusion;
+
+use arrow::datatypes::{DataType, Field, Schema};
+use clap::{App, Arg};
+
+use datafusion::execution::context::{ExecutionConfig, ExecutionContext};
+use datafusion::execution::datasource::CsvReadOptions;
+use datafusion::execution::error::Result;
+
+/// The TPC-H queries that can be run. DataFusion does not support joins, date
+/// arithmetic or sorting yet, so only the queries that read a single table are
+/// included, without their date predicates and ORDER BY clauses.
+const QUERIES: &[(usize, &str)] = &[
+    (
+        1,
+        "SELECT l_returnflag, l_linestatus, \
+         SUM(l_quantity), SUM(l_extendedprice), \
+         SUM(l_extendedprice * (1 - l_discount)), \
+         SUM(l_extendedprice * (1 - l_discount) * (1 + l_tax)), \
+         AVG(l_quantity), AVG(l_extendedprice), AVG(l_discount), COUNT(l_orderkey) \
+         FROM lineitem \
+         GROUP BY l_returnflag, l_linestatus",
+    ),
+    (
+        6,
+        "SELECT SUM(l_extendedprice * l_discount) \
+         FROM lineitem \
+         WHERE l_discount >= 0.05 AND l_discount <= 0.07 AND l_quantity < 24",
+    ),
+];
+
+/// This example runs TPC-H queries against tables generated by the TPC-H `dbgen` tool
+/// and prints how long each query took, so that changes to the performance of the
+/// executor can be measured. The tables are read from `<path>/<table>.tbl` files in
+/// the pipe-delimited format written by `dbgen`, or from `<path>/<table>` Parquet
+/// files or directories.
+///
+/// cargo run --release --example tpch -- --path ./tpch-data --query 1 --iterations 5
+fn main() -> Result<()> {
+    let matches = App::new("TPC-H benchmarks")
+        .arg(
+            Arg::with_name("path")
+                .help("Directory that contains the TPC-H tables")
+                .long("path")
+                .takes_value(true)
+                .required(true),
+        )
+        .arg(
+            Arg::with_name("format")
+                .help("File format of the tables")
+                .long("format")
+                .takes_value(true)
+                .possible_values(&["csv", "parquet"])
+                .default_value("csv"),
+        )
+        .arg(
+            Arg::with_name("query")
+                .help("Number of the query to run, or all supported queries if omitted")
+                .long("query")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("iterations")
+                .help("Number of times to run each query")
+                .long("iterations")
+                .takes_value(true)
+                .default_value("3"),
+        )
+        .arg(
+            Arg::with_name("batch-size")
+                .help("Number of rows in each batch")
+                .long("batch-size")
+                .takes_value(true)
+                .default_value("4096"),
+        )
+        .get_matches();
+
+    let path = matches.value_of("path").unwrap();
+    let format = matches.value_of("format").unwrap();
+    let iterations: usize = matches.value_of("iterations").unwrap().parse().unwrap();
+    let batch_size: usize = matches.value_of("batch-size").unwrap().parse().unwrap();
+    let query: Option<usize> = matches.value_of("query").map(|q| q.parse().unwrap());
+
+    let queries: Vec<&(usize, &str)> = QUERIES
+        .iter()
+        .filter(|(number, _)| query.map_or(true, |q| q == *number))
+        .collect();
+    if queries.is_empty() {
+        println!("Query {} is not supported", query.unwrap());
+        return Ok(());
+    }
+
+    let config = ExecutionConfig::new().with_batch_size(batch_size);
+    let mut ctx = ExecutionContext::with_config(config);
+    register_lineitem(&mut ctx, path, format)?;
+
+    for (number, sql) in queries {
+        let mut durations = vec![];
+        for i in 0..iterations {
+            let start = Instant::now();
+            let batches = ctx.sql_collect(sql)?;
+            let elapsed = start.elapsed();
+            let rows: usize = batches.iter().map(|b| b.num_rows()).sum();
+            println!(
+                "Query {} iteration {} took {} ms and returned {} rows",
+                number,
+                i,
+                millis(elapsed),
+                rows
+            );
+            durations.push(elapsed);
+        }
+        if !durations.is_empty() {
+            let total: f64 = durations.iter().map(|d| millis(*d)).sum();
+            println!(
+                "Query {} average time: {:.2} ms",
+                number,
+                total / durations.len() as f64
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Register the `lineitem` table, which is the only table used by the supported
+/// queries. Decimal columns are read as Float64 and date columns as strings.
+fn register_lineitem(ctx: &mut ExecutionContext, path: &str, format: &str) -> Result<()> {
+    match format {
+        "csv" => {
+            let schema = Schema::new(vec![
+                Field::new("l_orderkey", DataType::Int32, false),
+                Field::new("l_partkey", DataType::Int32, false),
+                Field::new("l_suppkey", DataType::Int32, false),
+                Field::new("l_linenumber", DataType::Int32, false),
+                Field::new("l_quantity", DataType::Float64, false),
+                Field::new("l_extendedprice", DataType::Float64, false),
+                Field::new("l_discount", DataType::Float64, false),
+                Field::new("l_tax", DataType::Float64, false),
+                Field::new("l_returnflag", DataType::Utf8, false),
+                Field::new("l_linestatus", DataType::Utf8, false),
+                Field::new("l_shipdate", DataType::Utf8, false),
+                Field::new("l_commitdate", DataType::Utf8, false),
+                Field::new("l_receiptdate", DataType::Utf8, false),
+                Field::new("l_shipinstruct", DataType::Utf8, false),
+                Field::new("l_shipmode", DataType::Utf8, false),
+                Field::new("l_comment", DataType::Utf8, false),
+            ]);
+            let options = CsvReadOptions::new().has_header(false).with_delimiter(b'|');
+            let filename = format!("{}/lineitem.tbl", path);
+            ctx.register_csv_with_options("lineitem", &filename, &schema, &options)
+        }
+        _ => ctx.register_parquet("lineitem", &format!("{}/lineitem", path)),
+    }
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs() as f64 * 1000.0 + duration.subsec_nanos() as f64 / 1_000_000.0
+}
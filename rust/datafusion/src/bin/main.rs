@@ -0,0 +1,118 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Interactive SQL shell for DataFusion. Tables are registered with
+//! `CREATE EXTERNAL TABLE` statements and queried with SQL, and query results are
+//! printed as tables.
+
+use std::time::Instant;
+
+use clap::{App, Arg};
+use rustyline::Editor;
+
+use datafusion::execution::context::{ExecutionConfig, ExecutionContext};
+use datafusion::execution::error::Result;
+use datafusion::execution::pretty::print_batches;
+
+const HISTORY_FILE: &str = ".datafusion_history";
+
+fn main() {
+    let matches = App::new("DataFusion")
+        .version(env!("CARGO_PKG_VERSION"))
+        .about(
+            "Interactive SQL shell for DataFusion. Statements end with a semicolon, \
+             and tables are registered with CREATE EXTERNAL TABLE statements.",
+        )
+        .arg(
+            Arg::with_name("batch-size")
+                .help("The number of rows in each batch that is read by a query")
+                .short("c")
+                .long("batch-size")
+                .takes_value(true)
+                .validator(|size| match size.parse::<usize>() {
+                    Ok(size) if size > 0 => Ok(()),
+                    _ => Err(format!("Invalid batch size '{}'", size)),
+                }),
+        )
+        .get_matches();
+
+    let mut config = ExecutionConfig::new();
+    if let Some(size) = matches.value_of("batch-size") {
+        config = config.with_batch_size(size.parse().unwrap());
+    }
+    let mut ctx = ExecutionContext::with_config(config);
+
+    let mut editor = Editor::<()>::new();
+    editor.load_history(HISTORY_FILE).ok();
+
+    // a statement can span several lines, and is run once a line ends with a semicolon
+    let mut statement = String::new();
+    loop {
+        let prompt = if statement.is_empty() { "> " } else { "| " };
+        let line = match editor.readline(prompt) {
+            Ok(line) => line,
+            Err(_) => break,
+        };
+        let line = line.trim_end();
+        if statement.is_empty() && (line == "quit" || line == "exit") {
+            break;
+        }
+
+        statement.push_str(line);
+        if statement.ends_with(';') {
+            editor.add_history_entry(statement.as_str());
+            let sql = statement.trim_end_matches(';').trim();
+            if let Err(e) = execute_and_print(&mut ctx, sql) {
+                println!("Error: {}", e);
+            }
+            statement.clear();
+        } else if !statement.is_empty() {
+            statement.push(' ');
+        }
+    }
+
+    editor.save_history(HISTORY_FILE).ok();
+}
+
+/// Execute a SQL statement and print the results of a query
+fn execute_and_print(ctx: &mut ExecutionContext, sql: &str) -> Result<()> {
+    let start = Instant::now();
+    let relation = match ctx.execute_sql(sql)? {
+        Some(relation) => relation,
+        None => {
+            println!("OK");
+            return Ok(());
+        }
+    };
+
+    let mut relation = relation.borrow_mut();
+    let mut batches = vec![];
+    while let Some(batch) = relation.next()? {
+        batches.push(batch);
+    }
+    let elapsed = start.elapsed();
+
+    print_batches(&batches)?;
+    let rows: usize = batches.iter().map(|b| b.num_rows()).sum();
+    println!(
+        "{} row(s) in set. Query took {}.{:03} seconds.",
+        rows,
+        elapsed.as_secs(),
+        elapsed.subsec_millis()
+    );
+    Ok(())
+}
@@ -57,6 +57,27 @@ pub enum DFASTNode {
     },
 }
 
+/// Rewrite hex literals such as `X'DEADBEEF'`, which the tokenizer reads as an
+/// identifier followed by a string, into calls to the `from_hex` function
+fn hex_literals_to_calls(tokens: Vec<Token>) -> Vec<Token> {
+    let mut result = Vec::with_capacity(tokens.len());
+    let mut tokens = tokens.into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        match (&token, tokens.peek()) {
+            (Token::Identifier(id), Some(Token::SingleQuotedString(_)))
+                if id.eq_ignore_ascii_case("x") =>
+            {
+                result.push(Token::Identifier("from_hex".to_string()));
+                result.push(Token::LParen);
+                result.extend(tokens.next());
+                result.push(Token::RParen);
+            }
+            _ => result.push(token),
+        }
+    }
+    result
+}
+
 /// SQL Parser
 pub struct DFParser {
     parser: Parser,
@@ -69,7 +90,7 @@ impl DFParser {
         let mut tokenizer = Tokenizer::new(&dialect, &sql);
         let tokens = tokenizer.tokenize()?;
         Ok(DFParser {
-            parser: Parser::new(tokens),
+            parser: Parser::new(hex_literals_to_calls(tokens)),
         })
     }
 
@@ -127,18 +148,17 @@ impl DFParser {
                                         true
                                     };
 
-                                    match self.parser.peek_token() {
-                                        Some(Token::Comma) => {
-                                            self.parser.next_token();
-                                            columns.push(SQLColumnDef {
-                                                name: column_name,
-                                                data_type: data_type,
-                                                allow_null,
-                                                default: None,
-                                                is_primary: false,
-                                                is_unique: false,
-                                            });
-                                        }
+                                    columns.push(SQLColumnDef {
+                                        name: column_name,
+                                        data_type: data_type,
+                                        allow_null,
+                                        default: None,
+                                        is_primary: false,
+                                        is_unique: false,
+                                    });
+
+                                    match self.parser.next_token() {
+                                        Some(Token::Comma) => {}
                                         Some(Token::RParen) => break,
                                         _ => {
                                             return parser_err!(
@@ -19,6 +19,7 @@
 //! functions with optional GROUP BY columns
 
 use std::cell::RefCell;
+use std::mem;
 use std::rc::Rc;
 use std::str;
 use std::sync::Arc;
@@ -31,11 +32,68 @@ use arrow::record_batch::RecordBatch;
 
 use super::error::{ExecutionError, Result};
 use super::expression::{AggregateType, RuntimeExpr};
+use super::hash::create_hashes;
+use super::memory::{MemoryManager, MemoryReservation};
+use super::physicalplan::ExecutionPlan;
 use super::relation::Relation;
 use crate::logicalplan::ScalarValue;
 
 use fnv::FnvHashMap;
 
+/// Execution plan for an aggregate, which is applied to each partition of its input
+/// separately
+pub struct AggregateExec {
+    input: Arc<ExecutionPlan>,
+    group_expr: Vec<RuntimeExpr>,
+    aggr_expr: Vec<RuntimeExpr>,
+    schema: Arc<Schema>,
+    memory_manager: Arc<MemoryManager>,
+}
+
+impl AggregateExec {
+    pub fn new(
+        input: Arc<ExecutionPlan>,
+        group_expr: Vec<RuntimeExpr>,
+        aggr_expr: Vec<RuntimeExpr>,
+        schema: Arc<Schema>,
+        memory_manager: Arc<MemoryManager>,
+    ) -> Self {
+        Self {
+            input,
+            group_expr,
+            aggr_expr,
+            schema,
+            memory_manager,
+        }
+    }
+}
+
+impl ExecutionPlan for AggregateExec {
+    fn schema(&self) -> Arc<Schema> {
+        self.schema.clone()
+    }
+
+    fn children(&self) -> Vec<Arc<ExecutionPlan>> {
+        vec![self.input.clone()]
+    }
+
+    fn output_partitions(&self) -> usize {
+        self.input.output_partitions()
+    }
+
+    fn execute(&self, partition: usize) -> Result<Rc<RefCell<Relation>>> {
+        let input = self.input.execute(partition)?;
+        let relation = AggregateRelation::new(
+            self.schema.clone(),
+            input,
+            self.group_expr.clone(),
+            self.aggr_expr.clone(),
+        )
+        .with_memory_manager(self.memory_manager.clone());
+        Ok(Rc::new(RefCell::new(relation)))
+    }
+}
+
 /// An aggregate relation is made up of zero or more grouping expressions and one
 /// or more aggregate expressions
 pub struct AggregateRelation {
@@ -44,6 +102,7 @@ pub struct AggregateRelation {
     group_expr: Vec<RuntimeExpr>,
     aggr_expr: Vec<RuntimeExpr>,
     end_of_results: bool,
+    memory: MemoryReservation,
 }
 
 impl AggregateRelation {
@@ -59,14 +118,26 @@ impl AggregateRelation {
             group_expr,
             aggr_expr,
             end_of_results: false,
+            memory: MemoryReservation::new(
+                Arc::new(MemoryManager::new(None)),
+                "aggregate",
+            ),
         }
     }
+
+    /// Track the memory used for the groups of this aggregate with a memory manager,
+    /// so that the query fails if there are too many groups for its memory limit
+    pub fn with_memory_manager(mut self, memory_manager: Arc<MemoryManager>) -> Self {
+        self.memory = MemoryReservation::new(memory_manager, "aggregate");
+        self
+    }
 }
 
 /// Enumeration of types that can be used in a GROUP BY expression (all primitives except
-/// for floating point numerics)
+/// for floating point numerics), where all null values form a single group
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
 enum GroupByScalar {
+    Null,
     UInt8(u8),
     UInt16(u16),
     UInt32(u32),
@@ -76,6 +147,7 @@ enum GroupByScalar {
     Int32(i32),
     Int64(i64),
     Utf8(String),
+    Binary(Vec<u8>),
 }
 
 /// Common trait for all aggregation functions
@@ -294,6 +366,46 @@ impl AggregateFunction for SumFunction {
     }
 }
 
+#[derive(Debug)]
+struct CountFunction {
+    data_type: DataType,
+    value: Option<ScalarValue>,
+}
+
+impl CountFunction {
+    fn new() -> Self {
+        Self {
+            data_type: DataType::UInt64,
+            value: Some(ScalarValue::UInt64(0)),
+        }
+    }
+}
+
+impl AggregateFunction for CountFunction {
+    fn name(&self) -> &str {
+        "count"
+    }
+
+    /// Accumulates the number of non-null values counted in a batch or row
+    fn accumulate_scalar(&mut self, value: &Option<ScalarValue>) {
+        self.value = match (&self.value, value) {
+            (Some(ScalarValue::UInt64(a)), Some(ScalarValue::UInt64(b))) => {
+                Some(ScalarValue::UInt64(*a + b))
+            }
+            (_, None) => self.value.clone(),
+            _ => panic!("unsupported data type for COUNT"),
+        }
+    }
+
+    fn result(&self) -> &Option<ScalarValue> {
+        &self.value
+    }
+
+    fn data_type(&self) -> &DataType {
+        &self.data_type
+    }
+}
+
 struct AccumulatorSet {
     aggr_values: Vec<Rc<RefCell<AggregateFunction>>>,
 }
@@ -318,6 +430,26 @@ struct MapEntry {
     v: Vec<Option<ScalarValue>>,
 }
 
+/// Estimate the number of bytes used by a group and its entry in the hash map
+fn estimate_group_size(key: &Vec<GroupByScalar>, aggr_expr_count: usize) -> usize {
+    let key_size: usize = key
+        .iter()
+        .map(|k| match k {
+            GroupByScalar::Utf8(s) => mem::size_of::<GroupByScalar>() + s.capacity(),
+            GroupByScalar::Binary(b) => mem::size_of::<GroupByScalar>() + b.capacity(),
+            _ => mem::size_of::<GroupByScalar>(),
+        })
+        .sum();
+    mem::size_of::<Vec<GroupByScalar>>()
+        + key_size
+        + mem::size_of::<(u64, Vec<usize>)>()
+        + mem::size_of::<AccumulatorSet>()
+        // every accumulator holds a single value, like the MIN accumulator
+        + aggr_expr_count
+            * (mem::size_of::<Rc<RefCell<AggregateFunction>>>()
+                + mem::size_of::<RefCell<MinFunction>>())
+}
+
 /// Create an initial aggregate entry
 fn create_accumulators(aggr_expr: &Vec<RuntimeExpr>) -> Result<AccumulatorSet> {
     let aggr_values: Vec<Rc<RefCell<AggregateFunction>>> = aggr_expr
@@ -330,6 +462,8 @@ fn create_accumulators(aggr_expr: &Vec<RuntimeExpr>) -> Result<AccumulatorSet> {
                     as Rc<RefCell<AggregateFunction>>),
                 AggregateType::Sum => Ok(Rc::new(RefCell::new(SumFunction::new(t)))
                     as Rc<RefCell<AggregateFunction>>),
+                AggregateType::Count => Ok(Rc::new(RefCell::new(CountFunction::new()))
+                    as Rc<RefCell<AggregateFunction>>),
                 _ => Err(ExecutionError::ExecutionError(
                     "unsupported aggregate function".to_string(),
                 )),
@@ -556,10 +690,20 @@ fn update_accumulators(
     // update the accumulators
     for j in 0..accumulator_set.aggr_values.len() {
         match &aggr_expr[j] {
-            RuntimeExpr::AggregateFunction { args, t, .. } => {
+            RuntimeExpr::AggregateFunction { f, args, t, .. } => {
                 // evaluate argument to aggregate function
                 match args[0](&batch) {
                     Ok(array) => {
+                        if *f == AggregateType::Count {
+                            let n = if array.is_valid(row) { 1 } else { 0 };
+                            accumulator_set
+                                .accumulate_scalar(j, Some(ScalarValue::UInt64(n)));
+                            continue;
+                        }
+                        if array.is_null(row) {
+                            // null values are ignored by MIN, MAX and SUM
+                            continue;
+                        }
                         let value: Option<ScalarValue> = match t {
                             DataType::UInt8 => {
                                 let z =
@@ -679,6 +823,7 @@ macro_rules! group_array_from_map_entries {
         for j in 0..$ENTRIES.len() {
             match $ENTRIES[j].k[$COL_INDEX] {
                 GroupByScalar::$TY(n) => builder.append_value(n).unwrap(),
+                GroupByScalar::Null => builder.append_null().unwrap(),
                 _ => err = true,
             }
         }
@@ -715,6 +860,135 @@ macro_rules! aggr_array_from_map_entries {
     }};
 }
 
+/// Get the value of a GROUP BY expression for a row
+fn group_by_scalar(col: &ArrayRef, row: usize) -> Result<GroupByScalar> {
+    if col.is_null(row) {
+        return Ok(GroupByScalar::Null);
+    }
+    match col.data_type() {
+        DataType::UInt8 => {
+            let array = col.as_any().downcast_ref::<UInt8Array>().unwrap();
+            Ok(GroupByScalar::UInt8(array.value(row)))
+        }
+        DataType::UInt16 => {
+            let array = col.as_any().downcast_ref::<UInt16Array>().unwrap();
+            Ok(GroupByScalar::UInt16(array.value(row)))
+        }
+        DataType::UInt32 => {
+            let array = col.as_any().downcast_ref::<UInt32Array>().unwrap();
+            Ok(GroupByScalar::UInt32(array.value(row)))
+        }
+        DataType::UInt64 => {
+            let array = col.as_any().downcast_ref::<UInt64Array>().unwrap();
+            Ok(GroupByScalar::UInt64(array.value(row)))
+        }
+        DataType::Int8 => {
+            let array = col.as_any().downcast_ref::<Int8Array>().unwrap();
+            Ok(GroupByScalar::Int8(array.value(row)))
+        }
+        DataType::Int16 => {
+            let array = col.as_any().downcast_ref::<Int16Array>().unwrap();
+            Ok(GroupByScalar::Int16(array.value(row)))
+        }
+        DataType::Int32 => {
+            let array = col.as_any().downcast_ref::<Int32Array>().unwrap();
+            Ok(GroupByScalar::Int32(array.value(row)))
+        }
+        DataType::Int64 => {
+            let array = col.as_any().downcast_ref::<Int64Array>().unwrap();
+            Ok(GroupByScalar::Int64(array.value(row)))
+        }
+        DataType::Utf8 => {
+            let array = col.as_any().downcast_ref::<BinaryArray>().unwrap();
+            Ok(GroupByScalar::Utf8(String::from(
+                str::from_utf8(array.value(row)).unwrap(),
+            )))
+        }
+        DataType::Binary => {
+            let array = col.as_any().downcast_ref::<BinaryArray>().unwrap();
+            Ok(GroupByScalar::Binary(array.value(row).to_vec()))
+        }
+        _ => Err(ExecutionError::ExecutionError(
+            "Unsupported GROUP BY data type".to_string(),
+        )),
+    }
+}
+
+/// The values of a GROUP BY expression for a batch. The values of a dictionary column
+/// are kept in its dictionary, and rows are grouped by the index of their value.
+enum GroupColumn {
+    Array(ArrayRef),
+    Dictionary(ArrayRef, Vec<Option<usize>>),
+}
+
+impl GroupColumn {
+    fn try_new(array: ArrayRef) -> Result<Self> {
+        match array.data_type() {
+            DataType::Dictionary(_, _) => {
+                let (values, indices) = compute::dictionary_indices(&array)?;
+                Ok(GroupColumn::Dictionary(values, indices))
+            }
+            _ => Ok(GroupColumn::Array(array)),
+        }
+    }
+
+    /// Get the array that holds the value of a row and the index of the value in that
+    /// array, or `None` if the value is null
+    fn value(&self, row: usize) -> Option<(&ArrayRef, usize)> {
+        let (array, index) = match self {
+            GroupColumn::Array(array) => (array, Some(row)),
+            GroupColumn::Dictionary(values, indices) => (values, indices[row]),
+        };
+        index.filter(|&i| array.is_valid(i)).map(|i| (array, i))
+    }
+}
+
+/// Get the value of a GROUP BY column for a row
+fn group_column_scalar(col: &GroupColumn, row: usize) -> Result<GroupByScalar> {
+    match col.value(row) {
+        Some((array, i)) => group_by_scalar(array, i),
+        None => Ok(GroupByScalar::Null),
+    }
+}
+
+/// Compare the value of a GROUP BY expression for a row with the value of a group
+macro_rules! group_value_equals {
+    ($COL:expr, $ROW:expr, $ARRAY_TYPE:ident, $VALUE:expr) => {{
+        match $COL.as_any().downcast_ref::<$ARRAY_TYPE>() {
+            Some(array) => array.value($ROW) == $VALUE,
+            None => false,
+        }
+    }};
+}
+
+/// Determine whether the values of the GROUP BY expressions for a row are equal to the
+/// key of a group, without creating the key of the row
+fn group_key_equals(key: &[GroupByScalar], cols: &[GroupColumn], row: usize) -> bool {
+    key.iter().zip(cols).all(|(value, col)| {
+        let (col, row) = match col.value(row) {
+            Some(position) => position,
+            None => return *value == GroupByScalar::Null,
+        };
+        match value {
+            GroupByScalar::Null => false,
+            GroupByScalar::UInt8(v) => group_value_equals!(col, row, UInt8Array, *v),
+            GroupByScalar::UInt16(v) => group_value_equals!(col, row, UInt16Array, *v),
+            GroupByScalar::UInt32(v) => group_value_equals!(col, row, UInt32Array, *v),
+            GroupByScalar::UInt64(v) => group_value_equals!(col, row, UInt64Array, *v),
+            GroupByScalar::Int8(v) => group_value_equals!(col, row, Int8Array, *v),
+            GroupByScalar::Int16(v) => group_value_equals!(col, row, Int16Array, *v),
+            GroupByScalar::Int32(v) => group_value_equals!(col, row, Int32Array, *v),
+            GroupByScalar::Int64(v) => group_value_equals!(col, row, Int64Array, *v),
+            GroupByScalar::Utf8(v) => {
+                group_value_equals!(col, row, BinaryArray, v.as_bytes())
+            }
+            GroupByScalar::Binary(v) => {
+                group_value_equals!(col, row, BinaryArray, &v[..])
+            }
+        }
+    })
+}
+
 impl AggregateRelation {
     /// perform simple aggregate on entire columns without grouping logic
     fn without_group_by(&mut self) -> Result<Option<RecordBatch>> {
@@ -734,6 +1008,13 @@ impl AggregateRelation {
                                     .accumulate_scalar(i, array_max(array, &t)?),
                                 AggregateType::Sum => accumulator_set
                                     .accumulate_scalar(i, array_sum(array, &t)?),
+                                AggregateType::Count => {
+                                    let n = compute::count(array.as_ref()) as u64;
+                                    accumulator_set.accumulate_scalar(
+                                        i,
+                                        Some(ScalarValue::UInt64(n)),
+                                    )
+                                }
                                 _ => {
                                     return Err(ExecutionError::NotImplemented(
                                         "Unsupported aggregate function".to_string(),
@@ -809,9 +1090,10 @@ impl AggregateRelation {
         // can be re-implemented in a much more efficient way that takes better
         // advantage of Arrow
 
-        // create map to store aggregate results
-        let mut map: FnvHashMap<Vec<GroupByScalar>, Rc<RefCell<AccumulatorSet>>> =
-            FnvHashMap::default();
+        // groups are looked up by the hash of their keys, and the values of a row are
+        // only compared with the keys of the groups that have the same hash
+        let mut map: FnvHashMap<u64, Vec<usize>> = FnvHashMap::default();
+        let mut groups: Vec<(Vec<GroupByScalar>, AccumulatorSet)> = vec![];
 
         while let Some(batch) = self.input.borrow_mut().next()? {
             // evaulate the group by expressions on this batch
@@ -820,104 +1102,68 @@ impl AggregateRelation {
                 .iter()
                 .map(|e| e.get_func()(&batch))
                 .collect::<Result<Vec<ArrayRef>>>()?;
+            let hashes = create_hashes(&group_by_keys)?;
+            let group_columns = group_by_keys
+                .into_iter()
+                .map(GroupColumn::try_new)
+                .collect::<Result<Vec<GroupColumn>>>()?;
+
+            // when every GROUP BY expression is dictionary encoded, rows with the same
+            // keys are in the same group, so the group of each combination of keys is
+            // only looked up once per batch
+            let dictionary_indices: Option<Vec<&[Option<usize>]>> = group_columns
+                .iter()
+                .map(|col| match col {
+                    GroupColumn::Dictionary(_, indices) => Some(&indices[..]),
+                    GroupColumn::Array(_) => None,
+                })
+                .collect();
+            let mut dictionary_groups: FnvHashMap<Vec<Option<usize>>, usize> =
+                FnvHashMap::default();
 
             // iterate over each row in the batch
             for row in 0..batch.num_rows() {
-                // create key
-                let key: Vec<GroupByScalar> = group_by_keys
+                let dictionary_key = dictionary_indices.as_ref().map(|indices| {
+                    indices.iter().map(|col| col[row]).collect::<Vec<_>>()
+                });
+                if let Some(&i) = dictionary_key
+                    .as_ref()
+                    .and_then(|key| dictionary_groups.get(key))
+                {
+                    update_accumulators(&batch, row, &mut groups[i].1, &self.aggr_expr);
+                    continue;
+                }
+
+                let indices = map.entry(hashes[row]).or_insert_with(Vec::new);
+                let group = indices
                     .iter()
-                    .map(|col| match col.data_type() {
-                        DataType::UInt8 => {
-                            let array =
-                                col.as_any().downcast_ref::<UInt8Array>().unwrap();
-                            Ok(GroupByScalar::UInt8(array.value(row)))
-                        }
-                        DataType::UInt16 => {
-                            let array =
-                                col.as_any().downcast_ref::<UInt16Array>().unwrap();
-                            Ok(GroupByScalar::UInt16(array.value(row)))
-                        }
-                        DataType::UInt32 => {
-                            let array =
-                                col.as_any().downcast_ref::<UInt32Array>().unwrap();
-                            Ok(GroupByScalar::UInt32(array.value(row)))
-                        }
-                        DataType::UInt64 => {
-                            let array =
-                                col.as_any().downcast_ref::<UInt64Array>().unwrap();
-                            Ok(GroupByScalar::UInt64(array.value(row)))
-                        }
-                        DataType::Int8 => {
-                            let array = col.as_any().downcast_ref::<Int8Array>().unwrap();
-                            Ok(GroupByScalar::Int8(array.value(row)))
-                        }
-                        DataType::Int16 => {
-                            let array =
-                                col.as_any().downcast_ref::<Int16Array>().unwrap();
-                            Ok(GroupByScalar::Int16(array.value(row)))
-                        }
-                        DataType::Int32 => {
-                            let array =
-                                col.as_any().downcast_ref::<Int32Array>().unwrap();
-                            Ok(GroupByScalar::Int32(array.value(row)))
-                        }
-                        DataType::Int64 => {
-                            let array =
-                                col.as_any().downcast_ref::<Int64Array>().unwrap();
-                            Ok(GroupByScalar::Int64(array.value(row)))
-                        }
-                        DataType::Utf8 => {
-                            let array =
-                                col.as_any().downcast_ref::<BinaryArray>().unwrap();
-                            Ok(GroupByScalar::Utf8(String::from(
-                                str::from_utf8(array.value(row)).unwrap(),
-                            )))
-                        }
-                        _ => Err(ExecutionError::ExecutionError(
-                            "Unsupported GROUP BY data type".to_string(),
-                        )),
-                    })
-                    .collect::<Result<Vec<GroupByScalar>>>()?;
-
-                //TODO: find more elegant way to write this instead of hacking around
-                // ownership issues
-
-                let updated = match map.get(&key) {
-                    Some(entry) => {
-                        let mut accumulator_set = entry.borrow_mut();
-                        update_accumulators(
-                            &batch,
-                            row,
-                            &mut accumulator_set,
-                            &self.aggr_expr,
-                        );
-                        true
+                    .cloned()
+                    .find(|&i| group_key_equals(&groups[i].0, &group_columns, row));
+                let group = match group {
+                    Some(i) => i,
+                    None => {
+                        let key = group_columns
+                            .iter()
+                            .map(|col| group_column_scalar(col, row))
+                            .collect::<Result<Vec<GroupByScalar>>>()?;
+                        self.memory
+                            .try_grow(estimate_group_size(&key, self.aggr_expr.len()))?;
+                        indices.push(groups.len());
+                        groups.push((key, create_accumulators(&self.aggr_expr)?));
+                        groups.len() - 1
                     }
-                    None => false,
                 };
-
-                if !updated {
-                    let accumulator_set =
-                        Rc::new(RefCell::new(create_accumulators(&self.aggr_expr)?));
-                    {
-                        let mut entry_mut = accumulator_set.borrow_mut();
-                        update_accumulators(&batch, row, &mut entry_mut, &self.aggr_expr);
-                    }
-                    map.insert(key.clone(), accumulator_set);
+                update_accumulators(&batch, row, &mut groups[group].1, &self.aggr_expr);
+                if let Some(key) = dictionary_key {
+                    dictionary_groups.insert(key, group);
                 }
             }
         }
 
-        // convert the map to a vec to make it easier to build arrays
-        let entries: Vec<MapEntry> = map
-            .iter()
-            .map(|(k, v)| {
-                let x = v.borrow();
-                MapEntry {
-                    k: k.clone(),
-                    v: x.values(),
-                }
-            })
+        // convert the groups to a vec of entries to make it easier to build arrays
+        let entries: Vec<MapEntry> = groups
+            .into_iter()
+            .map(|(k, v)| MapEntry { k, v: v.values() })
             .collect();
 
         // build the result arrays
@@ -926,7 +1172,12 @@ impl AggregateRelation {
 
         // grouping values
         for i in 0..self.group_expr.len() {
-            let array: Result<ArrayRef> = match self.group_expr[i].get_type() {
+            let data_type = self.group_expr[i].get_type();
+            let value_type = match data_type {
+                DataType::Dictionary(_, ref value_type) => (**value_type).clone(),
+                ref other => other.clone(),
+            };
+            let array: Result<ArrayRef> = match value_type {
                 DataType::UInt8 => {
                     group_array_from_map_entries!(UInt8Builder, UInt8, entries, i)
                 }
@@ -956,6 +1207,18 @@ impl AggregateRelation {
                     for j in 0..entries.len() {
                         match &entries[j].k[i] {
                             GroupByScalar::Utf8(s) => builder.append_string(&s).unwrap(),
+                            GroupByScalar::Null => builder.append_null().unwrap(),
+                            _ => {}
+                        }
+                    }
+                    Ok(Arc::new(builder.finish()) as ArrayRef)
+                }
+                DataType::Binary => {
+                    let mut builder = BinaryBuilder::new_binary(entries.len());
+                    for j in 0..entries.len() {
+                        match &entries[j].k[i] {
+                            GroupByScalar::Binary(b) => builder.append_bytes(&b)?,
+                            GroupByScalar::Null => builder.append_null()?,
                             _ => {}
                         }
                     }
@@ -965,7 +1228,22 @@ impl AggregateRelation {
                     "Unsupported group by expr".to_string(),
                 )),
             };
-            result_arrays.push(array?);
+            let array = match data_type {
+                DataType::Dictionary(ref key_type, _) => {
+                    // the key of each group is the index of its value
+                    let indices: Vec<Option<usize>> = entries
+                        .iter()
+                        .enumerate()
+                        .map(|(j, entry)| match entry.k[i] {
+                            GroupByScalar::Null => None,
+                            _ => Some(j),
+                        })
+                        .collect();
+                    compute::dictionary_from_indices(key_type, &indices, array?)?
+                }
+                _ => array?,
+            };
+            result_arrays.push(array);
         }
 
         // aggregate values
@@ -1008,6 +1286,16 @@ impl AggregateRelation {
             result_arrays.push(array?);
         }
 
+        debug!(
+            "Aggregated {} groups using an estimated {} bytes",
+            map.len(),
+            self.memory.size()
+        );
+
+        // the groups are no longer needed once the results have been built
+        drop(map);
+        self.memory.free();
+
         Ok(Some(RecordBatch::new(self.schema.clone(), result_arrays)))
     }
 }
@@ -1036,6 +1324,7 @@ mod tests {
                 return_type: DataType::Float64,
             },
             &schema,
+            context.config(),
         )
         .unwrap()];
 
@@ -1071,6 +1360,7 @@ mod tests {
                 return_type: DataType::Float64,
             },
             &schema,
+            context.config(),
         )
         .unwrap()];
 
@@ -1099,8 +1389,13 @@ mod tests {
 
         let context = ExecutionContext::new();
 
-        let group_by_expr =
-            expression::compile_expr(&context, &Expr::Column(1), &schema).unwrap();
+        let group_by_expr = expression::compile_expr(
+            &context,
+            &Expr::Column(1),
+            &schema,
+            context.config(),
+        )
+        .unwrap();
 
         let min_expr = expression::compile_expr(
             &context,
@@ -1110,6 +1405,7 @@ mod tests {
                 return_type: DataType::Float64,
             },
             &schema,
+            context.config(),
         )
         .unwrap();
 
@@ -1121,6 +1417,7 @@ mod tests {
                 return_type: DataType::Float64,
             },
             &schema,
+            context.config(),
         )
         .unwrap();
 
@@ -1132,6 +1429,7 @@ mod tests {
                 return_type: DataType::Float64,
             },
             &schema,
+            context.config(),
         )
         .unwrap();
 
@@ -1189,6 +1487,54 @@ mod tests {
         assert_eq!(6.037181692266781, sum.value(2));
     }
 
+    #[test]
+    fn group_by_exceeds_memory_limit() {
+        let schema = aggr_test_schema();
+        let relation = load_csv("../../testing/data/csv/aggregate_test_100.csv", &schema);
+        let context = ExecutionContext::new();
+
+        let group_by_expr = expression::compile_expr(
+            &context,
+            &Expr::Column(1),
+            &schema,
+            context.config(),
+        )
+        .unwrap();
+
+        let min_expr = expression::compile_expr(
+            &context,
+            &Expr::AggregateFunction {
+                name: String::from("min"),
+                args: vec![Expr::Column(11)],
+                return_type: DataType::Float64,
+            },
+            &schema,
+            context.config(),
+        )
+        .unwrap();
+
+        let aggr_schema = Arc::new(Schema::new(vec![
+            Field::new("c2", DataType::UInt32, false),
+            Field::new("min", DataType::Float64, false),
+        ]));
+
+        let memory_manager = Arc::new(MemoryManager::new(Some(1)));
+        let mut aggregate = AggregateRelation::new(
+            aggr_schema,
+            relation,
+            vec![group_by_expr],
+            vec![min_expr],
+        )
+        .with_memory_manager(memory_manager.clone());
+
+        match aggregate.next() {
+            Err(ExecutionError::ResourcesExhausted(_)) => {}
+            _ => panic!("Expected ResourcesExhausted"),
+        }
+        drop(aggregate);
+        assert_eq!(0, memory_manager.used());
+    }
+
     fn aggr_test_schema() -> Arc<Schema> {
         Arc::new(Schema::new(vec![
             Field::new("c1", DataType::Utf8, false),
@@ -0,0 +1,189 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Built-in scalar functions on binary values, which are registered with every
+//! execution context. Hex literals such as `X'DEADBEEF'` are parsed as calls to
+//! `from_hex`, which the SQL planner evaluates when the argument is a literal.
+
+use std::sync::Arc;
+
+use arrow::array::*;
+use arrow::builder::BinaryBuilder;
+use arrow::datatypes::DataType;
+
+use super::error::{ExecutionError, Result};
+
+/// Create a builder for an array of the given type, which is either `Utf8` or `Binary`
+pub fn binary_builder(data_type: &DataType, capacity: usize) -> BinaryBuilder {
+    match data_type {
+        DataType::Binary => BinaryBuilder::new_binary(capacity),
+        _ => BinaryBuilder::new(capacity),
+    }
+}
+
+/// Decode a string of hex digits, returning `None` if the string has an odd length or
+/// contains a character that is not a hex digit
+pub fn decode_hex(s: &str) -> Option<Vec<u8>> {
+    if s.len() % 2 != 0 {
+        return None;
+    }
+    s.as_bytes()
+        .chunks(2)
+        .map(|pair| {
+            let high = (pair[0] as char).to_digit(16)?;
+            let low = (pair[1] as char).to_digit(16)?;
+            Some((high * 16 + low) as u8)
+        })
+        .collect()
+}
+
+fn as_binary_array<'a>(array: &'a ArrayRef, function: &str) -> Result<&'a BinaryArray> {
+    match array.as_any().downcast_ref::<BinaryArray>() {
+        Some(array) => Ok(array),
+        None => Err(ExecutionError::ExecutionError(format!(
+            "{} is not supported for {:?}",
+            function,
+            array.data_type()
+        ))),
+    }
+}
+
+fn as_int64_array<'a>(array: &'a ArrayRef, function: &str) -> Result<&'a Int64Array> {
+    match array.as_any().downcast_ref::<Int64Array>() {
+        Some(array) => Ok(array),
+        None => Err(ExecutionError::ExecutionError(format!(
+            "{} expects Int64 positions but was called with {:?}",
+            function,
+            array.data_type()
+        ))),
+    }
+}
+
+/// `substring(value, start [, length])` returns the bytes of a binary value from the
+/// one-based position `start`, up to `length` bytes. Positions before the start of the
+/// value are counted but produce no bytes, as in SQL, and a negative length produces a
+/// null value.
+pub fn substring(args: &[ArrayRef]) -> Result<ArrayRef> {
+    let values = as_binary_array(&args[0], "substring")?;
+    let starts = as_int64_array(&args[1], "substring")?;
+    let lengths = match args.get(2) {
+        Some(lengths) => Some(as_int64_array(lengths, "substring")?),
+        None => None,
+    };
+
+    let mut builder = binary_builder(values.data_type(), values.len());
+    for i in 0..values.len() {
+        if values.is_null(i) || starts.is_null(i) {
+            builder.append_null()?;
+            continue;
+        }
+        let value = values.value(i);
+        let start = starts.value(i);
+        let end = match lengths {
+            Some(lengths) if lengths.is_null(i) || lengths.value(i) < 0 => {
+                builder.append_null()?;
+                continue;
+            }
+            Some(lengths) => start.saturating_add(lengths.value(i)),
+            None => value.len() as i64 + 1,
+        };
+        let from = (start.max(1) - 1).min(value.len() as i64) as usize;
+        let to = (end.max(1) - 1).min(value.len() as i64) as usize;
+        builder.append_bytes(&value[from..to.max(from)])?;
+    }
+    Ok(Arc::new(builder.finish()))
+}
+
+/// `from_hex(string)` decodes a string of hex digits into a binary value. The result is
+/// null for strings that are not valid hex.
+pub fn from_hex(args: &[ArrayRef]) -> Result<ArrayRef> {
+    let strings = as_binary_array(&args[0], "from_hex")?;
+    let mut builder = BinaryBuilder::new_binary(strings.len());
+    for i in 0..strings.len() {
+        let bytes = if strings.is_null(i) {
+            None
+        } else {
+            decode_hex(&String::from_utf8_lossy(strings.value(i)))
+        };
+        match bytes {
+            Some(bytes) => builder.append_bytes(&bytes)?,
+            None => builder.append_null()?,
+        }
+    }
+    Ok(Arc::new(builder.finish()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn binary_values() -> ArrayRef {
+        let mut builder = BinaryBuilder::new_binary(8);
+        builder.append_bytes(&[1, 2, 3, 4]).unwrap();
+        builder.append_null().unwrap();
+        builder.append_bytes(&[5, 6]).unwrap();
+        Arc::new(builder.finish())
+    }
+
+    fn bytes(array: &ArrayRef) -> Vec<Option<Vec<u8>>> {
+        let array = array.as_any().downcast_ref::<BinaryArray>().unwrap();
+        (0..array.len())
+            .map(|i| {
+                if array.is_null(i) {
+                    None
+                } else {
+                    Some(array.value(i).to_vec())
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn decode_hex_strings() {
+        assert_eq!(Some(vec![0xde, 0xad, 0xbe, 0xef]), decode_hex("DEADbeef"));
+        assert_eq!(Some(vec![]), decode_hex(""));
+        assert_eq!(None, decode_hex("ABC"));
+        assert_eq!(None, decode_hex("0G"));
+    }
+
+    #[test]
+    fn substring_of_binary() {
+        let starts: ArrayRef = Arc::new(Int64Array::from(vec![2, 1, 0]));
+        let lengths: ArrayRef = Arc::new(Int64Array::from(vec![2, 1, 2]));
+        let result = substring(&[binary_values(), starts.clone(), lengths]).unwrap();
+        assert_eq!(&DataType::Binary, result.data_type());
+        assert_eq!(vec![Some(vec![2, 3]), None, Some(vec![5])], bytes(&result));
+
+        let result = substring(&[binary_values(), starts.clone()]).unwrap();
+        assert_eq!(
+            vec![Some(vec![2, 3, 4]), None, Some(vec![5, 6])],
+            bytes(&result)
+        );
+
+        let lengths: ArrayRef = Arc::new(Int64Array::from(vec![-1, 1, 10]));
+        let result = substring(&[binary_values(), starts, lengths]).unwrap();
+        assert_eq!(vec![None, None, Some(vec![5, 6])], bytes(&result));
+    }
+
+    #[test]
+    fn from_hex_strings() {
+        let strings: ArrayRef = Arc::new(BinaryArray::from(vec!["0102", "xyz", ""]));
+        let result = from_hex(&[strings]).unwrap();
+        assert_eq!(&DataType::Binary, result.data_type());
+        assert_eq!(vec![Some(vec![1, 2]), None, Some(vec![])], bytes(&result));
+    }
+}
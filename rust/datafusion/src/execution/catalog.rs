@@ -0,0 +1,348 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Catalog of the tables that are registered with an execution context, which are
+//! grouped into named schemas so that tables with the same name can be registered in
+//! different schemas, such as `analytics.events` and `staging.events`. The catalog also
+//! provides the `information_schema.columns` table, which describes the columns of all
+//! registered tables.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use arrow::array::UInt64Array;
+use arrow::builder::BinaryBuilder;
+use arrow::datatypes::{DataType, Field, IntervalUnit, Schema};
+use arrow::record_batch::RecordBatch;
+
+use super::datasource::{DataSource, MemTable, TableProvider};
+use super::error::Result;
+
+/// The schema that contains tables whose names are not qualified by a schema name
+pub const DEFAULT_SCHEMA: &str = "default";
+
+/// The schema that contains the tables describing the catalog itself
+pub const INFORMATION_SCHEMA: &str = "information_schema";
+
+/// Split a table name that may be qualified by a schema name, such as
+/// `analytics.events`, into the schema name and the table name. Names that are not
+/// qualified belong to the default schema.
+pub fn split_table_name(name: &str) -> (&str, &str) {
+    match name.find('.') {
+        Some(i) => (&name[..i], &name[i + 1..]),
+        None => (DEFAULT_SCHEMA, name),
+    }
+}
+
+/// The tables that are registered with an execution context, by schema name and table
+/// name
+pub struct Catalog {
+    schemas: HashMap<String, HashMap<String, Arc<TableProvider>>>,
+}
+
+impl Catalog {
+    /// Create a catalog that contains an empty default schema
+    pub fn new() -> Self {
+        let mut schemas = HashMap::new();
+        schemas.insert(DEFAULT_SCHEMA.to_string(), HashMap::new());
+        Self { schemas }
+    }
+
+    /// Register a table in a schema, which is created if it does not exist yet. This
+    /// replaces any table that was already registered with the same name in the schema.
+    pub fn register_table(
+        &mut self,
+        schema_name: &str,
+        table_name: &str,
+        provider: Arc<TableProvider>,
+    ) {
+        self.schemas
+            .entry(schema_name.to_string())
+            .or_insert_with(HashMap::new)
+            .insert(table_name.to_string(), provider);
+    }
+
+    /// Get the provider of a table in a schema
+    pub fn table(
+        &self,
+        schema_name: &str,
+        table_name: &str,
+    ) -> Option<Arc<TableProvider>> {
+        if schema_name == INFORMATION_SCHEMA && table_name == "columns" {
+            return Some(self.columns_table());
+        }
+        self.schemas.get(schema_name)?.get(table_name).cloned()
+    }
+
+    /// Get the provider of a table by a name that may be qualified by a schema name. A
+    /// table in the default schema whose name contains a dot is found by its full name.
+    pub fn resolve(&self, name: &str) -> Option<Arc<TableProvider>> {
+        self.table(DEFAULT_SCHEMA, name).or_else(|| {
+            let (schema_name, table_name) = split_table_name(name);
+            self.table(schema_name, table_name)
+        })
+    }
+
+    /// Get the names of all schemas, in sorted order
+    pub fn schema_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.schemas.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// Get the names of the tables in a schema, in sorted order
+    pub fn table_names(&self, schema_name: &str) -> Vec<String> {
+        let mut names: Vec<String> = match self.schemas.get(schema_name) {
+            Some(tables) => tables.keys().cloned().collect(),
+            None => vec![],
+        };
+        names.sort();
+        names
+    }
+
+    /// Create the `information_schema.columns` table for the tables that are currently
+    /// registered. The rows of the table are only built when it is scanned.
+    fn columns_table(&self) -> Arc<TableProvider> {
+        let mut tables = vec![];
+        for schema_name in self.schema_names() {
+            for table_name in self.table_names(&schema_name) {
+                let schema = self.schemas[&schema_name][&table_name].schema().clone();
+                tables.push((schema_name.clone(), table_name, schema));
+            }
+        }
+        Arc::new(ColumnsTable::new(tables))
+    }
+}
+
+/// The `information_schema.columns` table, with one row per column of the tables it
+/// describes ordered by schema name, table name and the position of the column in its
+/// table
+struct ColumnsTable {
+    schema: Arc<Schema>,
+    /// The schema name, table name and schema of each table, in sorted order
+    tables: Vec<(String, String, Arc<Schema>)>,
+}
+
+impl ColumnsTable {
+    fn new(tables: Vec<(String, String, Arc<Schema>)>) -> Self {
+        let schema = Schema::new(vec![
+            Field::new("table_schema", DataType::Utf8, false),
+            Field::new("table_name", DataType::Utf8, false),
+            Field::new("column_name", DataType::Utf8, false),
+            Field::new("ordinal_position", DataType::UInt64, false),
+            Field::new("data_type", DataType::Utf8, false),
+            Field::new("is_nullable", DataType::Utf8, false),
+        ]);
+        Self {
+            schema: Arc::new(schema),
+            tables,
+        }
+    }
+
+    /// Build the rows of the table
+    fn build_batch(&self) -> Result<RecordBatch> {
+        let mut table_schemas = BinaryBuilder::new(64);
+        let mut table_names = BinaryBuilder::new(64);
+        let mut column_names = BinaryBuilder::new(64);
+        let mut ordinal_positions = vec![];
+        let mut data_types = BinaryBuilder::new(64);
+        let mut is_nullables = BinaryBuilder::new(64);
+        for (schema_name, table_name, schema) in &self.tables {
+            for (i, field) in schema.fields().iter().enumerate() {
+                table_schemas.append_string(schema_name)?;
+                table_names.append_string(table_name)?;
+                column_names.append_string(field.name())?;
+                ordinal_positions.push(i as u64 + 1);
+                data_types.append_string(&sql_type_name(field.data_type()))?;
+                let nullable = if field.is_nullable() { "YES" } else { "NO" };
+                is_nullables.append_string(nullable)?;
+            }
+        }
+
+        Ok(RecordBatch::new(
+            self.schema.clone(),
+            vec![
+                Arc::new(table_schemas.finish()),
+                Arc::new(table_names.finish()),
+                Arc::new(column_names.finish()),
+                Arc::new(UInt64Array::from(ordinal_positions)),
+                Arc::new(data_types.finish()),
+                Arc::new(is_nullables.finish()),
+            ],
+        ))
+    }
+}
+
+impl TableProvider for ColumnsTable {
+    fn schema(&self) -> &Arc<Schema> {
+        &self.schema
+    }
+
+    fn scan(
+        &self,
+        projection: &Option<Vec<usize>>,
+        batch_size: usize,
+    ) -> Result<Rc<RefCell<DataSource>>> {
+        let table = MemTable::try_new(&self.schema, vec![self.build_batch()?])?;
+        table.scan(projection, batch_size)
+    }
+}
+
+/// Get the name of the SQL type that corresponds to a data type, such as `INTEGER` for
+/// `Int32`, as it is shown in the `data_type` column of `information_schema.columns`
+fn sql_type_name(data_type: &DataType) -> String {
+    let fields = |fields: &[Field]| {
+        let fields: Vec<String> = fields
+            .iter()
+            .map(|f| format!("{} {}", f.name(), sql_type_name(f.data_type())))
+            .collect();
+        fields.join(", ")
+    };
+    match data_type {
+        DataType::Null => "NULL".to_string(),
+        DataType::Boolean => "BOOLEAN".to_string(),
+        DataType::Int8 => "TINYINT".to_string(),
+        DataType::Int16 => "SMALLINT".to_string(),
+        DataType::Int32 => "INTEGER".to_string(),
+        DataType::Int64 => "BIGINT".to_string(),
+        DataType::UInt8 => "TINYINT UNSIGNED".to_string(),
+        DataType::UInt16 => "SMALLINT UNSIGNED".to_string(),
+        DataType::UInt32 => "INTEGER UNSIGNED".to_string(),
+        DataType::UInt64 => "BIGINT UNSIGNED".to_string(),
+        DataType::Float16 => "HALF".to_string(),
+        DataType::Float32 => "REAL".to_string(),
+        DataType::Float64 => "DOUBLE".to_string(),
+        DataType::Timestamp(_, None) => "TIMESTAMP".to_string(),
+        DataType::Timestamp(_, Some(_)) => "TIMESTAMP WITH TIME ZONE".to_string(),
+        DataType::Date(_) => "DATE".to_string(),
+        DataType::Time32(_) | DataType::Time64(_) => "TIME".to_string(),
+        DataType::Interval(IntervalUnit::YearMonth) => {
+            "INTERVAL YEAR TO MONTH".to_string()
+        }
+        DataType::Interval(IntervalUnit::DayTime) => "INTERVAL DAY TO SECOND".to_string(),
+        DataType::Binary => "VARBINARY".to_string(),
+        DataType::Utf8 => "VARCHAR".to_string(),
+        DataType::List(t) => format!("{}[]", sql_type_name(t)),
+        DataType::FixedSizeList(t, n) => format!("{}[{}]", sql_type_name(t), n),
+        DataType::Struct(f) => format!("STRUCT({})", fields(f)),
+        DataType::Union(f, _) => format!("UNION({})", fields(f)),
+        // dictionary encoding is not visible in SQL
+        DataType::Dictionary(_, t) => sql_type_name(t),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution::datasource::MemTable;
+    use arrow::datatypes::{DataType, Field, Schema};
+
+    fn table(column: &str) -> Arc<TableProvider> {
+        let schema = Schema::new(vec![Field::new(column, DataType::Int32, false)]);
+        Arc::new(MemTable::try_new(&schema, vec![]).unwrap())
+    }
+
+    #[test]
+    fn split_qualified_names() {
+        assert_eq!(
+            ("analytics", "events"),
+            split_table_name("analytics.events")
+        );
+        assert_eq!((DEFAULT_SCHEMA, "events"), split_table_name("events"));
+    }
+
+    #[test]
+    fn tables_in_different_schemas() {
+        let mut catalog = Catalog::new();
+        catalog.register_table("analytics", "events", table("a"));
+        catalog.register_table("staging", "events", table("b"));
+        catalog.register_table(DEFAULT_SCHEMA, "events.1.5", table("c"));
+
+        let column = |provider: Option<Arc<TableProvider>>| {
+            provider.unwrap().schema().field(0).name().clone()
+        };
+        assert_eq!("a", column(catalog.resolve("analytics.events")));
+        assert_eq!("b", column(catalog.table("staging", "events")));
+        assert_eq!("c", column(catalog.resolve("events.1.5")));
+        assert!(catalog.resolve("events").is_none());
+
+        assert_eq!(
+            vec!["analytics", "default", "staging"],
+            catalog.schema_names()
+        );
+        assert_eq!(vec!["events"], catalog.table_names("staging"));
+        assert!(catalog.table_names("missing").is_empty());
+    }
+
+    #[test]
+    fn information_schema_columns() {
+        let mut catalog = Catalog::new();
+        catalog.register_table("analytics", "events", table("a"));
+        catalog.register_table(DEFAULT_SCHEMA, "t", table("b"));
+
+        let provider = catalog.resolve("information_schema.columns").unwrap();
+        assert_eq!(6, provider.schema().fields().len());
+        assert_eq!("ordinal_position", provider.schema().field(3).name());
+
+        let batch = provider
+            .scan(&None, 1024)
+            .unwrap()
+            .borrow_mut()
+            .next()
+            .unwrap()
+            .unwrap();
+        assert_eq!(2, batch.num_rows());
+        let ordinal_positions = batch
+            .column(3)
+            .as_any()
+            .downcast_ref::<UInt64Array>()
+            .unwrap();
+        assert_eq!(1, ordinal_positions.value(0));
+        assert!(catalog
+            .schema_names()
+            .iter()
+            .all(|name| name != INFORMATION_SCHEMA));
+    }
+
+    #[test]
+    fn sql_type_names() {
+        assert_eq!("INTEGER", sql_type_name(&DataType::Int32));
+        assert_eq!("BIGINT UNSIGNED", sql_type_name(&DataType::UInt64));
+        assert_eq!("DOUBLE", sql_type_name(&DataType::Float64));
+        assert_eq!("VARCHAR", sql_type_name(&DataType::Utf8));
+        assert_eq!(
+            "VARCHAR[]",
+            sql_type_name(&DataType::List(Box::new(DataType::Utf8)))
+        );
+        assert_eq!(
+            "VARCHAR",
+            sql_type_name(&DataType::Dictionary(
+                Box::new(DataType::Int8),
+                Box::new(DataType::Utf8)
+            ))
+        );
+        assert_eq!(
+            "STRUCT(x REAL, y REAL)",
+            sql_type_name(&DataType::Struct(vec![
+                Field::new("x", DataType::Float32, false),
+                Field::new("y", DataType::Float32, false),
+            ]))
+        );
+    }
+}
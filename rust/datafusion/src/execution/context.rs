@@ -18,131 +18,1062 @@
 //! ExecutionContext contains methods for registering data sources and executing SQL queries
 
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
 use std::rc::Rc;
 use std::string::String;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
 
+use arrow::array::ArrayRef;
 use arrow::datatypes::*;
+use arrow::record_batch::RecordBatch;
 
-use super::super::dfparser::{DFASTNode, DFParser};
+use sqlparser::sqlast::ASTNode;
+
+use super::super::dfparser::{DFASTNode, DFParser, FileType};
 use super::super::logicalplan::*;
+use super::super::optimizer::aggregate_statistics::AggregateStatistics;
+use super::super::optimizer::common_subexpr_eliminate::CommonSubexprEliminate;
+use super::super::optimizer::filter_push_down::FilterPushDown;
+use super::super::optimizer::limit_push_down::LimitPushDown;
 use super::super::optimizer::optimizer::OptimizerRule;
 use super::super::optimizer::projection_push_down::ProjectionPushDown;
-use super::super::sqlplanner::{SchemaProvider, SqlToRel};
-use super::aggregate::AggregateRelation;
-use super::datasource::{CsvProvider, DataSourceProvider};
+use super::super::optimizer::remove_redundant_projections::RemoveRedundantProjections;
+use super::super::optimizer::simplify_filters::SimplifyFilters;
+use super::super::optimizer::utils;
+use super::super::sqlplanner::{convert_data_type, SchemaProvider, SqlToRel};
+use super::aggregate::AggregateExec;
+use super::binary::{from_hex, substring};
+use super::catalog::{split_table_name, Catalog};
+use super::dataframe::DataFrame;
+use super::datasource::{
+    CsvProvider, CsvReadOptions, MemTable, RangeTable, Statistics, TableProvider,
+};
 use super::error::{ExecutionError, Result};
 use super::expression::*;
-use super::filter::FilterRelation;
-use super::limit::LimitRelation;
-use super::projection::ProjectRelation;
-use super::relation::{DataSourceRelation, Relation};
+use super::filter::FilterExec;
+use super::functions::aggregate_function;
+use super::limit::LimitExec;
+use super::list::array_length;
+use super::memory::{batch_memory_size, MemoryManager, MemoryReservation};
+use super::merge::{MergeExec, SortExpr, SortPreservingMergeExec};
+use super::object_store::{url_scheme, LocalFileSystem, ObjectStore};
+use super::parquet::ParquetProvider;
+use super::partitioned::PartitionedTable;
+use super::physicalplan::ExecutionPlan;
+use super::projection::ProjectionExec;
+use super::pruning::PruningPredicate;
+use super::relation::{
+    DataSourceExec, DataSourceRelation, EmptyExec, Relation, ValuesExec,
+};
+use super::scheduler::Scheduler;
+use super::sort::SortExec;
+use super::stream::RecordBatchStream;
+use super::udf::{ScalarUdf, TableFunction};
+use super::unnest::UnnestExec;
+
+/// How integer arithmetic handles results that do not fit in the type of the result
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum OverflowMode {
+    /// Fail the query with an error
+    Checked,
+    /// Wrap around at the boundary of the type, as in two's complement arithmetic
+    Wrapping,
+    /// Clamp the result to the minimum or maximum value of the type
+    Saturating,
+}
+
+/// How NaN values of floating point columns are ordered relative to other values, in
+/// sorts and in comparisons
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum NanOrdering {
+    /// NaN values are equal to each other and greater than all other values, including
+    /// positive infinity
+    NanLast,
+    /// The IEEE 754 totalOrder predicate, where negative NaN values are less than all
+    /// other values, positive NaN values are greater than all other values, and -0.0
+    /// is less than +0.0
+    TotalOrder,
+}
+
+/// How strings are compared for equality and ordering
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Collation {
+    /// Strings are ordered by their code points
+    Binary,
+    /// Strings are ordered by their code points after converting them to lowercase, so
+    /// that strings that only differ in case are equal
+    CaseInsensitive,
+}
+
+/// Configuration options for executing queries, which are set when an execution context
+/// is created and can be overridden for individual queries
+#[derive(Clone, Debug)]
+pub struct ExecutionConfig {
+    batch_size: usize,
+    concurrency: usize,
+    memory_limit: Option<usize>,
+    optimize: bool,
+    cache_results: bool,
+    result_cache_size: usize,
+    overflow_mode: OverflowMode,
+    error_on_divide_by_zero: bool,
+    nan_ordering: NanOrdering,
+    collation: Collation,
+}
+
+impl ExecutionConfig {
+    /// Create the default configuration
+    pub fn new() -> Self {
+        Self {
+            batch_size: 1024 * 1024,
+            concurrency: num_cpus::get(),
+            memory_limit: None,
+            optimize: true,
+            cache_results: false,
+            result_cache_size: 256 * 1024 * 1024,
+            overflow_mode: OverflowMode::Checked,
+            error_on_divide_by_zero: false,
+            nan_ordering: NanOrdering::NanLast,
+            collation: Collation::Binary,
+        }
+    }
+
+    /// Set the maximum number of rows in each batch produced by a query (which
+    /// defaults to 1048576)
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Set the maximum number of partitions that will be executed concurrently (which
+    /// defaults to the number of CPU cores)
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Set the maximum number of bytes that the operators of each query may reserve
+    /// (which is unlimited by default). A query that needs more memory fails with
+    /// `ExecutionError::ResourcesExhausted`.
+    pub fn with_memory_limit(mut self, memory_limit: usize) -> Self {
+        self.memory_limit = Some(memory_limit);
+        self
+    }
+
+    /// Enable or disable the optimizer rules that are applied to logical plans (which
+    /// are enabled by default)
+    pub fn with_optimizer(mut self, optimize: bool) -> Self {
+        self.optimize = optimize;
+        self
+    }
+
+    /// Enable or disable caching of query results (which is disabled by default). When
+    /// enabled, the results of a query that only scans static tables, such as registered
+    /// files, are kept in memory and returned when the same plan is executed again.
+    /// Changes to the files are not detected, so the cache should only be enabled for
+    /// files that are not modified while the context is in use.
+    pub fn with_result_cache(mut self, cache_results: bool) -> Self {
+        self.cache_results = cache_results;
+        self
+    }
+
+    /// Set the maximum number of bytes of query results that are cached by a context
+    /// created with this configuration (which defaults to 256 MiB). The cache is also
+    /// bounded by the memory limit. When a result does not fit, the least recently used
+    /// results are evicted, and results that are larger than the cache are not cached.
+    pub fn with_result_cache_size(mut self, result_cache_size: usize) -> Self {
+        self.result_cache_size = result_cache_size;
+        self
+    }
+
+    /// Set how integer arithmetic in expressions handles overflow (which defaults to
+    /// `OverflowMode::Checked`)
+    pub fn with_overflow_mode(mut self, overflow_mode: OverflowMode) -> Self {
+        self.overflow_mode = overflow_mode;
+        self
+    }
+
+    /// Set whether division or modulo by zero fails the query. By default the result
+    /// of such an operation is null, as in standard SQL.
+    pub fn with_error_on_divide_by_zero(mut self, error_on_divide_by_zero: bool) -> Self {
+        self.error_on_divide_by_zero = error_on_divide_by_zero;
+        self
+    }
+
+    /// Set how NaN values are ordered when floating point values are sorted or compared
+    /// (which defaults to `NanOrdering::NanLast`)
+    pub fn with_nan_ordering(mut self, nan_ordering: NanOrdering) -> Self {
+        self.nan_ordering = nan_ordering;
+        self
+    }
+
+    /// Set how strings are compared and sorted (which defaults to
+    /// `Collation::Binary`)
+    pub fn with_collation(mut self, collation: Collation) -> Self {
+        self.collation = collation;
+        self
+    }
+
+    /// Get the maximum number of rows in each batch
+    pub fn batch_size(&self) -> usize {
+        self.batch_size
+    }
+
+    /// Get the maximum number of partitions that will be executed concurrently
+    pub fn concurrency(&self) -> usize {
+        self.concurrency
+    }
+
+    /// Get the maximum number of bytes that the operators of each query may reserve
+    pub fn memory_limit(&self) -> Option<usize> {
+        self.memory_limit
+    }
+
+    /// Determine whether optimizer rules are applied to logical plans
+    pub fn optimize(&self) -> bool {
+        self.optimize
+    }
+
+    /// Determine whether query results are cached
+    pub fn cache_results(&self) -> bool {
+        self.cache_results
+    }
+
+    /// Get the maximum number of bytes of query results that are cached
+    pub fn result_cache_size(&self) -> usize {
+        self.result_cache_size
+    }
+
+    /// Get how integer arithmetic handles overflow
+    pub fn overflow_mode(&self) -> OverflowMode {
+        self.overflow_mode
+    }
+
+    /// Determine whether division or modulo by zero fails the query
+    pub fn error_on_divide_by_zero(&self) -> bool {
+        self.error_on_divide_by_zero
+    }
+
+    /// Get how NaN values are ordered
+    pub fn nan_ordering(&self) -> NanOrdering {
+        self.nan_ordering
+    }
+
+    /// Get how strings are compared and sorted
+    pub fn collation(&self) -> Collation {
+        self.collation
+    }
+
+    /// Get the settings of this configuration that can change the results of a query
+    fn result_settings(&self) -> ResultSettings {
+        ResultSettings {
+            overflow_mode: self.overflow_mode,
+            error_on_divide_by_zero: self.error_on_divide_by_zero,
+            nan_ordering: self.nan_ordering,
+            collation: self.collation,
+        }
+    }
+}
+
+/// The settings of a configuration that can change the results of a query, so that
+/// results are only returned from the cache for queries with the same settings
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct ResultSettings {
+    overflow_mode: OverflowMode,
+    error_on_divide_by_zero: bool,
+    nan_ordering: NanOrdering,
+    collation: Collation,
+}
 
 pub struct ExecutionContext {
-    datasources: Rc<RefCell<HashMap<String, Rc<DataSourceProvider>>>>,
+    datasources: Arc<Mutex<Catalog>>,
+    functions: Arc<Mutex<HashMap<String, Arc<ScalarUdf>>>>,
+    table_functions: Arc<Mutex<HashMap<String, Arc<TableFunction>>>>,
+    object_stores: HashMap<String, Arc<ObjectStore>>,
+    config: ExecutionConfig,
+    scheduler: Arc<Scheduler>,
+    result_cache: Mutex<ResultCache>,
+}
+
+/// The results of a query, which are cached by the hash of its logical plan and of the
+/// settings it was executed with. Plans are not thread safe, so the plan is stored as
+/// JSON to check that it is the same plan.
+struct CachedResult {
+    plan_json: String,
+    settings: ResultSettings,
+    schema: Arc<Schema>,
+    batches: Vec<RecordBatch>,
+    memory: MemoryReservation,
+}
+
+/// Query results that are cached by an execution context. The memory used by the
+/// batches is reserved from a memory manager whose limit is the smaller of the cache
+/// size and the memory limit, and the least recently used results are evicted to make
+/// room for new ones.
+struct ResultCache {
+    memory_manager: Arc<MemoryManager>,
+    results: HashMap<u64, CachedResult>,
+    /// Keys of the cached results, from the least to the most recently used
+    usage: VecDeque<u64>,
+}
+
+impl ResultCache {
+    fn new(config: &ExecutionConfig) -> Self {
+        let limit = match config.memory_limit {
+            Some(memory_limit) => memory_limit.min(config.result_cache_size),
+            None => config.result_cache_size,
+        };
+        Self {
+            memory_manager: Arc::new(MemoryManager::new(Some(limit))),
+            results: HashMap::new(),
+            usage: VecDeque::new(),
+        }
+    }
+
+    /// Get the schema and batches of a cached result, marking it as the most recently
+    /// used
+    fn get(
+        &mut self,
+        key: u64,
+        plan_json: &str,
+        settings: &ResultSettings,
+    ) -> Option<(Arc<Schema>, Vec<RecordBatch>)> {
+        let result = match self.results.get(&key) {
+            Some(result)
+                if result.plan_json == plan_json && result.settings == *settings =>
+            {
+                (result.schema.clone(), result.batches.clone())
+            }
+            _ => return None,
+        };
+        self.touch(key);
+        Some(result)
+    }
+
+    /// Cache the result of a query, evicting the least recently used results until it
+    /// fits. Results that are larger than the cache are not cached.
+    fn insert(
+        &mut self,
+        key: u64,
+        plan_json: String,
+        settings: ResultSettings,
+        schema: Arc<Schema>,
+        batches: Vec<RecordBatch>,
+    ) -> Result<()> {
+        self.remove(key);
+        let size: usize = batches.iter().map(batch_memory_size).sum();
+        let limit = self.memory_manager.limit().unwrap_or(usize::max_value());
+        if size > limit {
+            debug!(
+                "Not caching a result of {} bytes in a cache of {} bytes",
+                size, limit
+            );
+            return Ok(());
+        }
+        while size > limit - self.memory_manager.used() {
+            match self.usage.front() {
+                Some(&lru) => self.remove(lru),
+                None => break,
+            }
+        }
+        let mut memory =
+            MemoryReservation::new(self.memory_manager.clone(), "result cache");
+        memory.try_grow(size)?;
+        self.results.insert(
+            key,
+            CachedResult {
+                plan_json,
+                settings,
+                schema,
+                batches,
+                memory,
+            },
+        );
+        self.usage.push_back(key);
+        Ok(())
+    }
+
+    /// Remove a cached result, releasing its memory
+    fn remove(&mut self, key: u64) {
+        if self.results.remove(&key).is_some() {
+            self.usage.retain(|k| *k != key);
+        }
+    }
+
+    fn touch(&mut self, key: u64) {
+        self.usage.retain(|k| *k != key);
+        self.usage.push_back(key);
+    }
+
+    fn clear(&mut self) {
+        self.results.clear();
+        self.usage.clear();
+    }
 }
 
 impl ExecutionContext {
     /// Create a new excution context for in-memory queries
     pub fn new() -> Self {
-        Self {
-            datasources: Rc::new(RefCell::new(HashMap::new())),
-        }
+        Self::with_config(ExecutionConfig::new())
+    }
+
+    /// Create a new execution context for in-memory queries with the given
+    /// configuration
+    pub fn with_config(config: ExecutionConfig) -> Self {
+        let mut object_stores: HashMap<String, Arc<ObjectStore>> = HashMap::new();
+        object_stores.insert("file".to_string(), Arc::new(LocalFileSystem));
+        let mut ctx = Self {
+            datasources: Arc::new(Mutex::new(Catalog::new())),
+            functions: Arc::new(Mutex::new(HashMap::new())),
+            table_functions: Arc::new(Mutex::new(HashMap::new())),
+            object_stores,
+            scheduler: Arc::new(Scheduler::new(config.concurrency)),
+            result_cache: Mutex::new(ResultCache::new(&config)),
+            config,
+        };
+        ctx.register_table_function("range", range);
+        ctx.register_udf("length", vec![DataType::Binary], DataType::Int32, |args| {
+            array_length(&args[0])
+        });
+        ctx.register_udf_with_signatures(
+            "substring",
+            vec![
+                Signature::Exact(vec![DataType::Binary, DataType::Int64]),
+                Signature::Exact(vec![
+                    DataType::Binary,
+                    DataType::Int64,
+                    DataType::Int64,
+                ]),
+            ],
+            DataType::Binary,
+            substring,
+        );
+        ctx.register_udf("from_hex", vec![DataType::Utf8], DataType::Binary, from_hex);
+        ctx
+    }
+
+    /// Get the configuration that is used for queries by default
+    pub fn config(&self) -> &ExecutionConfig {
+        &self.config
     }
 
     /// Execute a SQL query and produce a Relation (a schema-aware iterator over a series
     /// of RecordBatch instances)
-    pub fn sql(&mut self, sql: &str, batch_size: usize) -> Result<Rc<RefCell<Relation>>> {
+    pub fn sql(&self, sql: &str) -> Result<Rc<RefCell<Relation>>> {
+        let config = self.config.clone();
+        self.sql_with_config(sql, &config)
+    }
+
+    /// Execute a SQL query with a configuration that overrides the configuration of
+    /// this context
+    pub fn sql_with_config(
+        &self,
+        sql: &str,
+        config: &ExecutionConfig,
+    ) -> Result<Rc<RefCell<Relation>>> {
+        let plan = self.create_logical_plan_with_config(sql, config)?;
+        self.execute_with_config(&plan, config)
+    }
+
+    /// Execute a SQL query and collect all of the results into memory
+    pub fn sql_collect(&self, sql: &str) -> Result<Vec<RecordBatch>> {
+        let relation = self.sql(sql)?;
+        collect_relation(&relation)
+    }
+
+    /// Execute a SQL query on a background thread and produce a stream of RecordBatch
+    /// instances, so that the caller is not blocked while the query runs
+    pub fn sql_stream(&self, sql: &str) -> Result<RecordBatchStream> {
+        let config = self.config.clone();
+        let plan = self.create_logical_plan_with_config(sql, &config)?;
+        self.execute_stream_with_config(&plan, &config)
+    }
+
+    /// Create an optimized logical plan from a SQL query
+    pub fn create_logical_plan(&self, sql: &str) -> Result<Rc<LogicalPlan>> {
+        self.create_logical_plan_with_config(sql, &self.config)
+    }
+
+    /// Create a logical plan from a SQL query, which is optimized unless the optimizer
+    /// has been disabled in the configuration
+    fn create_logical_plan_with_config(
+        &self,
+        sql: &str,
+        config: &ExecutionConfig,
+    ) -> Result<Rc<LogicalPlan>> {
         let ast = DFParser::parse_sql(String::from(sql))?;
 
         match ast {
-            DFASTNode::ANSI(ansi) => {
-                let schema_provider: Rc<SchemaProvider> =
-                    Rc::new(ExecutionContextSchemaProvider {
-                        datasources: self.datasources.clone(),
-                    });
+            DFASTNode::ANSI(ansi) => self
+                .create_query_plan(&ansi, config)
+                .map_err(|e| e.locate(sql)),
+            DFASTNode::CreateExternalTable { .. } => Err(ExecutionError::General(
+                "CREATE EXTERNAL TABLE does not produce a query plan and must be run \
+                 with ExecutionContext::execute_sql"
+                    .to_string(),
+            )),
+        }
+    }
 
-                // create a query planner
-                let query_planner = SqlToRel::new(schema_provider);
+    /// Create a logical plan for a query that has been parsed
+    fn create_query_plan(
+        &self,
+        ansi: &ASTNode,
+        config: &ExecutionConfig,
+    ) -> Result<Rc<LogicalPlan>> {
+        let schema_provider: Arc<SchemaProvider> =
+            Arc::new(ExecutionContextSchemaProvider {
+                datasources: self.datasources.clone(),
+                functions: self.functions.clone(),
+                table_functions: self.table_functions.clone(),
+            });
 
-                // plan the query (create a logical relational plan)
-                let plan = query_planner.sql_to_rel(&ansi)?;
+        // create a query planner
+        let query_planner = SqlToRel::new(schema_provider);
 
-                let optimized_plan = self.optimize(&plan)?;
+        // plan the query (create a logical relational plan)
+        let plan = query_planner.sql_to_rel(ansi)?;
 
-                let relation = self.execute(&optimized_plan, batch_size)?;
+        if config.optimize {
+            self.optimize(&plan)
+        } else {
+            Ok(plan)
+        }
+    }
 
-                Ok(relation)
+    /// Execute a SQL statement, which is either a query or a `CREATE EXTERNAL TABLE`
+    /// statement that registers a CSV or Parquet file as a table. Returns the relation
+    /// produced by a query, or `None` for a statement that does not produce results.
+    pub fn execute_sql(&mut self, sql: &str) -> Result<Option<Rc<RefCell<Relation>>>> {
+        let ast = DFParser::parse_sql(String::from(sql))?;
+
+        match ast {
+            DFASTNode::ANSI(ansi) => {
+                let config = self.config.clone();
+                let plan = self
+                    .create_query_plan(&ansi, &config)
+                    .map_err(|e| e.locate(sql))?;
+                Ok(Some(self.execute_with_config(&plan, &config)?))
+            }
+            DFASTNode::CreateExternalTable {
+                name,
+                columns,
+                file_type,
+                header_row,
+                location,
+            } => {
+                match file_type {
+                    FileType::CSV => {
+                        if columns.is_empty() {
+                            return Err(ExecutionError::General(format!(
+                                "CSV table '{}' must be created with a list of columns",
+                                name
+                            )));
+                        }
+                        let fields = columns
+                            .iter()
+                            .map(|c| {
+                                let data_type = convert_data_type(&c.data_type)?;
+                                Ok(Field::new(&c.name, data_type, c.allow_null))
+                            })
+                            .collect::<Result<Vec<_>>>()?;
+                        let schema = Schema::new(fields);
+                        self.register_csv(&name, &location, &schema, header_row)?;
+                    }
+                    FileType::Parquet => self.register_parquet(&name, &location)?,
+                    FileType::NdJson => {
+                        return Err(ExecutionError::NotImplemented(
+                            "NDJSON tables are not supported".to_string(),
+                        ));
+                    }
+                }
+                Ok(None)
             }
-            _ => unimplemented!(),
         }
     }
 
-    /// Register a CSV file as a table so that it can be queried from SQL
+    /// Create a DataFrame for a registered table, so that a query can be built
+    /// programmatically instead of from SQL
+    pub fn table(&self, name: &str) -> Result<DataFrame> {
+        let (schema_name, table_name) = split_table_name(name);
+        let provider = self.get_table(schema_name, table_name)?;
+        let plan = LogicalPlan::TableScan {
+            schema_name: schema_name.to_string(),
+            table_name: table_name.to_string(),
+            function_args: None,
+            schema: provider.schema().clone(),
+            projection: None,
+            limit: None,
+        };
+        Ok(DataFrame::new(self, Rc::new(plan)))
+    }
+
+    /// Register a CSV file as a table so that it can be queried from SQL. The filename
+    /// can also be a directory or a glob pattern such as `data/*.csv`, in which case
+    /// each matching file is a separate partition of the table.
     pub fn register_csv(
         &mut self,
         name: &str,
         filename: &str,
         schema: &Schema,
         has_header: bool,
+    ) -> Result<()> {
+        let options = CsvReadOptions::new().has_header(has_header);
+        self.register_csv_with_options(name, filename, schema, &options)
+    }
+
+    /// Register a CSV file in the format described by the options as a table, such as a
+    /// tab-separated file or a file with comment lines. The filename can also be a
+    /// directory or a glob pattern. Returns an error if no object store is registered
+    /// for the scheme of the filename, or if a directory or glob pattern does not match
+    /// any files.
+    pub fn register_csv_with_options(
+        &mut self,
+        name: &str,
+        filename: &str,
+        schema: &Schema,
+        options: &CsvReadOptions,
+    ) -> Result<()> {
+        let store = self.object_store(filename)?;
+        let filenames = store.list(filename)?;
+        let filenames: Vec<&str> = filenames.iter().map(|f| f.as_str()).collect();
+        let provider = CsvProvider::with_object_store(store, &filenames, schema, options);
+        self.register_table_provider(name, Arc::new(provider));
+        Ok(())
+    }
+
+    /// Register a table that is partitioned across several CSV files with the same
+    /// schema, so that the files can be scanned concurrently
+    pub fn register_partitioned_csv(
+        &mut self,
+        name: &str,
+        filenames: &[&str],
+        schema: &Schema,
+        has_header: bool,
     ) {
-        self.datasources.borrow_mut().insert(
-            name.to_string(),
-            Rc::new(CsvProvider::new(filename, schema, has_header)),
+        self.register_table_provider(
+            name,
+            Arc::new(CsvProvider::new_partitioned(filenames, schema, has_header)),
         );
     }
 
-    /// Optimize the logical plan by applying optimizer rules
-    fn optimize(&self, plan: &LogicalPlan) -> Result<Rc<LogicalPlan>> {
-        let mut rule = ProjectionPushDown::new();
-        Ok(rule.optimize(plan)?)
+    /// Register a Parquet file as a table so that it can be queried from SQL, using the
+    /// schema stored in the file. The filename can also be a directory or a glob pattern
+    /// such as `data/*.parquet`, in which case all matching files must have the same
+    /// schema. Each row group is a separate partition.
+    pub fn register_parquet(&mut self, name: &str, filename: &str) -> Result<()> {
+        let store = self.object_store(filename)?;
+        let filenames = store.list(filename)?;
+        let filenames: Vec<&str> = filenames.iter().map(|f| f.as_str()).collect();
+        let provider = ParquetProvider::try_new_with_object_store(store, &filenames)?;
+        self.register_table_provider(name, Arc::new(provider));
+        Ok(())
     }
 
-    /// Execute a logical plan and produce a Relation (a schema-aware iterator over a series
-    /// of RecordBatch instances)
-    pub fn execute(
+    /// Register a table that is stored in Parquet files in Hive-style partition
+    /// directories, such as `events/date=2019-01-01/part-0.parquet`. The partition keys
+    /// are added to the table as columns, and partitions that cannot match a query's
+    /// predicate are skipped.
+    pub fn register_parquet_with_partitions(
+        &mut self,
+        name: &str,
+        path: &str,
+    ) -> Result<()> {
+        let provider = PartitionedTable::try_new(path, |filenames| {
+            let provider = ParquetProvider::try_new_partitioned(filenames)?;
+            Ok(Arc::new(provider) as Arc<TableProvider>)
+        })?;
+        self.register_table_provider(name, Arc::new(provider));
+        Ok(())
+    }
+
+    /// Register record batches held in memory as a table so that they can be queried
+    /// from SQL. Each batch must have the given schema.
+    pub fn register_table(
+        &mut self,
+        name: &str,
+        schema: &Schema,
+        batches: Vec<RecordBatch>,
+    ) -> Result<()> {
+        let provider = MemTable::try_new(schema, batches)?;
+        self.register_table_provider(name, Arc::new(provider));
+        Ok(())
+    }
+
+    /// Read all of the results of a query and register them as a table held in memory,
+    /// so that an intermediate result can be used by several later queries without
+    /// running the query again
+    pub fn register_result(
+        &mut self,
+        name: &str,
+        relation: Rc<RefCell<Relation>>,
+    ) -> Result<()> {
+        let schema = relation.borrow().schema().clone();
+        let batches = collect_relation(&relation)?;
+        self.register_table(name, &schema, batches)
+    }
+
+    /// Register a table provider so that its table can be queried from SQL. The name
+    /// can be qualified by a schema name, such as `analytics.events`, and otherwise the
+    /// table is registered in the default schema. This replaces any table that was
+    /// already registered with the same name.
+    pub fn register_table_provider(&mut self, name: &str, provider: Arc<TableProvider>) {
+        let (schema_name, table_name) = split_table_name(name);
+        self.datasources.lock().unwrap().register_table(
+            schema_name,
+            table_name,
+            provider,
+        );
+        // cached results may have been read from the table that was replaced
+        self.clear_result_cache();
+    }
+
+    /// Remove all cached query results
+    pub fn clear_result_cache(&self) {
+        self.result_cache.lock().unwrap().clear();
+    }
+
+    /// Register a scalar function so that it can be called from SQL. The function is
+    /// called with an array of values for each argument and must return an array with
+    /// one value for each row, or an error that fails the query. Function names are not
+    /// case sensitive, and registering a function replaces any function that was already
+    /// registered with the same name.
+    pub fn register_udf<F>(
         &mut self,
+        name: &str,
+        arg_types: Vec<DataType>,
+        return_type: DataType,
+        fun: F,
+    ) where
+        F: Fn(&[ArrayRef]) -> Result<ArrayRef> + Send + Sync + 'static,
+    {
+        let signatures = vec![Signature::Exact(arg_types)];
+        self.register_udf_with_signatures(name, signatures, return_type, fun);
+    }
+
+    /// Register a scalar function with several overloads, such as `round(x)` and
+    /// `round(x, digits)`. Each call is matched to the signature that needs the fewest
+    /// casts of its arguments, and the function is called with the arguments cast to the
+    /// types of that signature.
+    pub fn register_udf_with_signatures<F>(
+        &mut self,
+        name: &str,
+        signatures: Vec<Signature>,
+        return_type: DataType,
+        fun: F,
+    ) where
+        F: Fn(&[ArrayRef]) -> Result<ArrayRef> + Send + Sync + 'static,
+    {
+        let fun = Arc::new(fun);
+        let udf = ScalarUdf::with_signatures(name, signatures, return_type, fun);
+        self.functions
+            .lock()
+            .unwrap()
+            .insert(name.to_lowercase(), Arc::new(udf));
+        // cached results may have been computed by the function that was replaced
+        self.clear_result_cache();
+    }
+
+    /// Get a scalar function that has been registered with this context
+    pub fn get_udf(&self, name: &str) -> Option<Arc<ScalarUdf>> {
+        self.functions
+            .lock()
+            .unwrap()
+            .get(&name.to_lowercase())
+            .cloned()
+    }
+
+    /// Register a table function so that it can be called in the FROM clause of a
+    /// query, such as `SELECT * FROM range(0, 100)`. The function is called with the
+    /// values of its arguments, which must be literals, when the query is planned and
+    /// again when it is executed. The table it returns is only used by that query and is
+    /// not registered with this context.
+    pub fn register_table_function<F>(&mut self, name: &str, fun: F)
+    where
+        F: Fn(&[ScalarValue]) -> Result<Arc<TableProvider>> + Send + Sync + 'static,
+    {
+        let function = TableFunction::new(name, Arc::new(fun));
+        self.table_functions
+            .lock()
+            .unwrap()
+            .insert(name.to_lowercase(), Arc::new(function));
+        // cached results may have been read from a table of the function that was
+        // replaced
+        self.clear_result_cache();
+    }
+
+    /// Register an object store for the URL scheme of table locations, such as `s3`, so
+    /// that tables can be read from locations like `s3://bucket/key`. Local files use
+    /// the `file` scheme, which is registered by default.
+    pub fn register_object_store(&mut self, scheme: &str, store: Arc<ObjectStore>) {
+        self.object_stores.insert(scheme.to_string(), store);
+    }
+
+    /// Get the object store for a table location, based on the scheme of its URL
+    fn object_store(&self, path: &str) -> Result<Arc<ObjectStore>> {
+        let scheme = url_scheme(path).unwrap_or("file");
+        match self.object_stores.get(scheme) {
+            Some(store) => Ok(store.clone()),
+            None => Err(ExecutionError::General(format!(
+                "No object store is registered for the URL scheme '{}'",
+                scheme
+            ))),
+        }
+    }
+
+    /// Optimize the logical plan by applying optimizer rules
+    pub fn optimize(&self, plan: &LogicalPlan) -> Result<Rc<LogicalPlan>> {
+        let schema_provider: Arc<SchemaProvider> =
+            Arc::new(ExecutionContextSchemaProvider {
+                datasources: self.datasources.clone(),
+                functions: self.functions.clone(),
+                table_functions: self.table_functions.clone(),
+            });
+
+        let rules: Vec<Box<OptimizerRule>> = vec![
+            Box::new(FilterPushDown::new()),
+            Box::new(SimplifyFilters::new()),
+            Box::new(LimitPushDown::new()),
+            Box::new(AggregateStatistics::new(schema_provider)),
+            Box::new(ProjectionPushDown::new()),
+            Box::new(CommonSubexprEliminate::new()),
+            Box::new(RemoveRedundantProjections::new()),
+        ];
+        let mut plan = Rc::new(plan.clone());
+        for mut rule in rules {
+            plan = rule.optimize(&plan)?;
+        }
+        debug!("Optimized logical plan:\n{:?}", plan);
+        Ok(plan)
+    }
+
+    /// Execute a logical plan and produce a Relation (a schema-aware iterator over a
+    /// series of RecordBatch instances)
+    pub fn execute(&self, plan: &LogicalPlan) -> Result<Rc<RefCell<Relation>>> {
+        let config = self.config.clone();
+        self.execute_with_config(plan, &config)
+    }
+
+    /// Execute a logical plan with a configuration that overrides the configuration of
+    /// this context
+    pub fn execute_with_config(
+        &self,
+        plan: &LogicalPlan,
+        config: &ExecutionConfig,
+    ) -> Result<Rc<RefCell<Relation>>> {
+        if config.cache_results && self.scans_static_tables(plan) {
+            return self.execute_cached(plan, config);
+        }
+        let physical_plan = self.create_physical_plan(plan, config)?;
+        info!(
+            "Executing query with {} partitions",
+            physical_plan.output_partitions()
+        );
+        let relation = self.merge_partitions(physical_plan, config).execute(0)?;
+        Ok(Rc::new(RefCell::new(QueryRelation::new(relation))))
+    }
+
+    /// Execute a logical plan whose results can be cached, returning the cached results
+    /// if the same plan has already been executed
+    fn execute_cached(
+        &self,
         plan: &LogicalPlan,
-        batch_size: usize,
+        config: &ExecutionConfig,
     ) -> Result<Rc<RefCell<Relation>>> {
+        let settings = config.result_settings();
+        let mut hasher = DefaultHasher::new();
+        plan.hash(&mut hasher);
+        settings.hash(&mut hasher);
+        let key = hasher.finish();
+        let plan_json = plan.to_json()?;
+
+        let cached = self
+            .result_cache
+            .lock()
+            .unwrap()
+            .get(key, &plan_json, &settings);
+        let (schema, batches) = match cached {
+            Some(result) => {
+                debug!("Returning cached results for plan:\n{:?}", plan);
+                result
+            }
+            None => {
+                let physical_plan = self.create_physical_plan(plan, config)?;
+                let relation = self.merge_partitions(physical_plan, config).execute(0)?;
+                let relation: Rc<RefCell<Relation>> =
+                    Rc::new(RefCell::new(QueryRelation::new(relation)));
+                let schema = relation.borrow().schema().clone();
+                let batches = collect_relation(&relation)?;
+                self.result_cache.lock().unwrap().insert(
+                    key,
+                    plan_json,
+                    settings,
+                    schema.clone(),
+                    batches.clone(),
+                )?;
+                (schema, batches)
+            }
+        };
+        let table = MemTable::try_new(&schema, batches)?;
+        let relation = DataSourceRelation::new(table.scan(&None, config.batch_size)?);
+        Ok(Rc::new(RefCell::new(relation)))
+    }
+
+    /// Determine whether all of the tables that are scanned by a plan are static, in
+    /// which case the results of the plan can be cached
+    fn scans_static_tables(&self, plan: &LogicalPlan) -> bool {
+        struct StaticTables<'a> {
+            ctx: &'a ExecutionContext,
+        }
+
+        impl<'a> PlanVisitor for StaticTables<'a> {
+            fn pre_visit(&mut self, plan: &LogicalPlan) -> bool {
+                match plan {
+                    LogicalPlan::TableScan {
+                        schema_name,
+                        table_name,
+                        function_args,
+                        ..
+                    } => self
+                        .ctx
+                        .get_scan_provider(schema_name, table_name, function_args)
+                        .map(|provider| provider.is_static())
+                        .unwrap_or(false),
+                    _ => true,
+                }
+            }
+        }
+
+        plan.accept(&mut StaticTables { ctx: self })
+    }
+
+    /// Execute a logical plan and collect all of the results into memory
+    pub fn collect(&self, plan: &LogicalPlan) -> Result<Vec<RecordBatch>> {
+        let relation = self.execute(plan)?;
+        collect_relation(&relation)
+    }
+
+    /// Execute a logical plan on a background thread and produce a stream of
+    /// RecordBatch instances
+    pub fn execute_stream(&self, plan: &LogicalPlan) -> Result<RecordBatchStream> {
+        let config = self.config.clone();
+        self.execute_stream_with_config(plan, &config)
+    }
+
+    /// Execute a logical plan on a background thread with a configuration that
+    /// overrides the configuration of this context
+    pub fn execute_stream_with_config(
+        &self,
+        plan: &LogicalPlan,
+        config: &ExecutionConfig,
+    ) -> Result<RecordBatchStream> {
+        let physical_plan = self.create_physical_plan(plan, config)?;
+        info!(
+            "Executing query on a background thread with {} partitions",
+            physical_plan.output_partitions()
+        );
+        Ok(RecordBatchStream::new(
+            self.merge_partitions(physical_plan, config),
+        ))
+    }
+
+    /// Merge the partitions of a physical plan so that all results can be read from
+    /// the first partition
+    fn merge_partitions(
+        &self,
+        plan: Arc<ExecutionPlan>,
+        config: &ExecutionConfig,
+    ) -> Arc<ExecutionPlan> {
+        if plan.output_partitions() > 1 {
+            Arc::new(MergeExec::new(plan, config.concurrency))
+        } else {
+            plan
+        }
+    }
+
+    /// Create a physical plan from a logical plan, choosing how each relation will be
+    /// executed and compiling its expressions
+    pub fn create_physical_plan(
+        &self,
+        plan: &LogicalPlan,
+        config: &ExecutionConfig,
+    ) -> Result<Arc<ExecutionPlan>> {
+        let memory_manager = Arc::new(MemoryManager::new(config.memory_limit));
+        self.create_execution_plan(plan, config, &memory_manager)
+    }
+
+    /// Create a physical plan for a logical plan, where all operators of the query
+    /// reserve memory from the same memory manager
+    fn create_execution_plan(
+        &self,
+        plan: &LogicalPlan,
+        config: &ExecutionConfig,
+        memory_manager: &Arc<MemoryManager>,
+    ) -> Result<Arc<ExecutionPlan>> {
         match *plan {
             LogicalPlan::TableScan {
+                ref schema_name,
                 ref table_name,
+                ref function_args,
                 ref projection,
+                ref limit,
                 ..
-            } => match self.datasources.borrow().get(table_name) {
-                Some(provider) => {
-                    let ds = provider.scan(projection, batch_size);
-                    Ok(Rc::new(RefCell::new(DataSourceRelation::new(ds))))
-                }
-                _ => Err(ExecutionError::General(format!(
-                    "No table registered as '{}'",
-                    table_name
-                ))),
-            },
+            } => {
+                let provider =
+                    self.get_scan_provider(schema_name, table_name, function_args)?;
+                self.create_scan(provider, projection, limit, None, config)
+            }
+            LogicalPlan::EmptyRelation { ref schema } => {
+                Ok(Arc::new(EmptyExec::new(schema.clone())))
+            }
+            LogicalPlan::Values {
+                ref values,
+                ref schema,
+            } => Ok(Arc::new(ValuesExec::new(schema.clone(), values.clone()))),
             LogicalPlan::Selection {
                 ref expr,
                 ref input,
+            } => match input.as_ref() {
+                LogicalPlan::TableScan {
+                    ref schema_name,
+                    ref table_name,
+                    ref function_args,
+                    ref projection,
+                    limit: None,
+                    ..
+                } => self.create_filtered_scan(
+                    schema_name,
+                    table_name,
+                    function_args,
+                    projection,
+                    expr,
+                    config,
+                ),
+                _ => {
+                    let input =
+                        self.create_execution_plan(input, config, memory_manager)?;
+                    self.create_filter(input, expr, config)
+                }
+            },
+            LogicalPlan::Unnest {
+                ref input,
+                column,
+                ref schema,
             } => {
-                let input_rel = self.execute(input, batch_size)?;
-                let input_schema = input_rel.as_ref().borrow().schema().clone();
-                let runtime_expr = compile_scalar_expr(&self, expr, &input_schema)?;
-                let rel = FilterRelation::new(
-                    input_rel,
-                    runtime_expr, /* .get_func().clone() */
-                    input_schema,
-                );
-                Ok(Rc::new(RefCell::new(rel)))
+                let input = self.create_execution_plan(input, config, memory_manager)?;
+                Ok(Arc::new(UnnestExec::new(input, column, schema.clone())))
             }
             LogicalPlan::Projection {
                 ref expr,
                 ref input,
                 ..
             } => {
-                let input_rel = self.execute(input, batch_size)?;
+                let input = self.create_execution_plan(input, config, memory_manager)?;
 
-                let input_schema = input_rel.as_ref().borrow().schema().clone();
+                let input_schema = input.schema();
 
                 let project_columns: Vec<Field> =
                     exprlist_to_fields(&expr, &input_schema);
@@ -151,52 +1082,98 @@ impl ExecutionContext {
 
                 let compiled_expr: Result<Vec<RuntimeExpr>> = expr
                     .iter()
-                    .map(|e| compile_scalar_expr(&self, e, &input_schema))
+                    .map(|e| compile_scalar_expr(&self, e, &input_schema, config))
                     .collect();
 
-                let rel = ProjectRelation::new(input_rel, compiled_expr?, project_schema);
-
-                Ok(Rc::new(RefCell::new(rel)))
+                Ok(Arc::new(
+                    ProjectionExec::new(input, compiled_expr?, project_schema)
+                        .with_scheduler(self.scheduler.clone()),
+                ))
             }
             LogicalPlan::Aggregate {
                 ref input,
                 ref group_expr,
                 ref aggr_expr,
-                ..
+                ref schema,
             } => {
-                let input_rel = self.execute(&input, batch_size)?;
+                let input = self.create_execution_plan(&input, config, memory_manager)?;
 
-                let input_schema = input_rel.as_ref().borrow().schema().clone();
+                let input_schema = input.schema();
 
                 let compiled_group_expr_result: Result<Vec<RuntimeExpr>> = group_expr
                     .iter()
-                    .map(|e| compile_scalar_expr(&self, e, &input_schema))
+                    .map(|e| compile_scalar_expr(&self, e, &input_schema, config))
                     .collect();
                 let compiled_group_expr = compiled_group_expr_result?;
 
                 let compiled_aggr_expr_result: Result<Vec<RuntimeExpr>> = aggr_expr
                     .iter()
-                    .map(|e| compile_expr(&self, e, &input_schema))
+                    .map(|e| compile_expr(&self, e, &input_schema, config))
                     .collect();
                 let compiled_aggr_expr = compiled_aggr_expr_result?;
 
-                let rel = AggregateRelation::new(
-                    Arc::new(Schema::empty()), //(expr_to_field(&compiled_group_expr, &input_schema))),
-                    input_rel,
-                    compiled_group_expr,
-                    compiled_aggr_expr,
-                );
+                if input.output_partitions() == 1 {
+                    return Ok(Arc::new(AggregateExec::new(
+                        input,
+                        compiled_group_expr,
+                        compiled_aggr_expr,
+                        schema.clone(),
+                        memory_manager.clone(),
+                    )));
+                }
+
+                match final_aggregate_expr(group_expr.len(), aggr_expr) {
+                    Some((final_group_expr, final_aggr_expr)) => {
+                        // aggregate each partition, then combine the partial results
+                        let partial_aggregate = Arc::new(AggregateExec::new(
+                            input,
+                            compiled_group_expr,
+                            compiled_aggr_expr,
+                            schema.clone(),
+                            memory_manager.clone(),
+                        ));
+                        let merge = Arc::new(MergeExec::new(
+                            partial_aggregate,
+                            config.concurrency,
+                        ));
+
+                        let compiled_final_group_expr = final_group_expr
+                            .iter()
+                            .map(|e| compile_scalar_expr(&self, e, &schema, config))
+                            .collect::<Result<Vec<RuntimeExpr>>>()?;
+                        let compiled_final_aggr_expr = final_aggr_expr
+                            .iter()
+                            .map(|e| compile_expr(&self, e, &schema, config))
+                            .collect::<Result<Vec<RuntimeExpr>>>()?;
 
-                Ok(Rc::new(RefCell::new(rel)))
+                        Ok(Arc::new(AggregateExec::new(
+                            merge,
+                            compiled_final_group_expr,
+                            compiled_final_aggr_expr,
+                            schema.clone(),
+                            memory_manager.clone(),
+                        )))
+                    }
+                    None => {
+                        // the partial results cannot be combined, so aggregate all
+                        // partitions in a single thread
+                        let merge = Arc::new(MergeExec::new(input, config.concurrency));
+                        Ok(Arc::new(AggregateExec::new(
+                            merge,
+                            compiled_group_expr,
+                            compiled_aggr_expr,
+                            schema.clone(),
+                            memory_manager.clone(),
+                        )))
+                    }
+                }
             }
             LogicalPlan::Limit {
                 ref expr,
                 ref input,
                 ..
             } => {
-                let input_rel = self.execute(input, batch_size)?;
-
-                let input_schema = input_rel.as_ref().borrow().schema().clone();
+                let input = self.create_execution_plan(input, config, memory_manager)?;
 
                 match expr {
                     &Expr::Literal(ref scalar_value) => {
@@ -214,25 +1191,336 @@ impl ExecutionContext {
                                     .to_string(),
                             )),
                         }?;
-                        let rel = LimitRelation::new(input_rel, limit, input_schema);
-                        Ok(Rc::new(RefCell::new(rel)))
+                        Ok(self.create_limit(input, limit, config))
                     }
                     _ => Err(ExecutionError::ExecutionError(
                         "Limit only support positive integer literals".to_string(),
                     )),
                 }
             }
+            LogicalPlan::Sort {
+                ref expr,
+                ref input,
+                ..
+            } => {
+                let input = self.create_execution_plan(input, config, memory_manager)?;
+                let input_schema = input.schema();
+                let sort_expr = expr
+                    .iter()
+                    .map(|e| match e {
+                        Expr::Sort {
+                            expr,
+                            asc,
+                            nulls_first,
+                        } => {
+                            let expr =
+                                compile_scalar_expr(&self, expr, &input_schema, config)?;
+                            Ok(SortExpr {
+                                expr,
+                                asc: *asc,
+                                nulls_first: *nulls_first,
+                            })
+                        }
+                        other => Err(ExecutionError::InternalError(format!(
+                            "Sort expects sort expressions but found {:?}",
+                            other
+                        ))),
+                    })
+                    .collect::<Result<Vec<SortExpr>>>()?;
+
+                // sort each partition, then merge the sorted partitions
+                let partitions = input.output_partitions();
+                let sort = Arc::new(SortExec::new(
+                    input,
+                    sort_expr.clone(),
+                    config.nan_ordering,
+                    config.collation,
+                    config.batch_size,
+                    memory_manager.clone(),
+                ));
+                if partitions > 1 {
+                    Ok(Arc::new(SortPreservingMergeExec::new(
+                        sort,
+                        sort_expr,
+                        config.nan_ordering,
+                        config.collation,
+                        config.batch_size,
+                    )))
+                } else {
+                    Ok(sort)
+                }
+            }
+            LogicalPlan::Join { .. } => Err(ExecutionError::NotImplemented(
+                "Joins are not supported by the execution engine yet".to_string(),
+            )),
+        }
+    }
+
+    /// Create a plan for scanning a table, which reads at most `limit` rows and may skip
+    /// data that cannot match a predicate
+    fn create_scan(
+        &self,
+        provider: Arc<TableProvider>,
+        projection: &Option<Vec<usize>>,
+        limit: &Option<usize>,
+        predicate: Option<PruningPredicate>,
+        config: &ExecutionConfig,
+    ) -> Result<Arc<ExecutionPlan>> {
+        // avoid reading more rows than needed to satisfy the limit
+        let batch_size = match limit {
+            Some(n) => config.batch_size.min(*n).max(1),
+            None => config.batch_size,
+        };
+        let mut scan = DataSourceExec::new(provider, projection.clone(), batch_size);
+        if let Some(predicate) = predicate {
+            scan = scan.with_predicate(predicate);
+        }
+
+        match limit {
+            Some(n) => Ok(self.create_limit(Arc::new(scan), *n, config)),
+            None => Ok(Arc::new(scan)),
+        }
+    }
+
+    /// Create a plan for scanning a table with a predicate that refers to the projected
+    /// columns. The conjuncts of the predicate are pushed down to the table provider, and
+    /// the ones that it does not apply exactly are evaluated on the scanned rows.
+    fn create_filtered_scan(
+        &self,
+        schema_name: &str,
+        table_name: &str,
+        function_args: &Option<Vec<ScalarValue>>,
+        projection: &Option<Vec<usize>>,
+        predicate: &Expr,
+        config: &ExecutionConfig,
+    ) -> Result<Arc<ExecutionPlan>> {
+        let provider = self.get_scan_provider(schema_name, table_name, function_args)?;
+
+        let mut filters = vec![];
+        utils::split_conjunction(predicate, &mut filters);
+
+        // table providers refer to the columns of the table rather than the projection
+        let table_filters = match projection {
+            Some(p) => {
+                let columns = p.iter().map(|i| Expr::Column(*i)).collect();
+                filters
+                    .iter()
+                    .map(|filter| utils::replace_columns(filter, &columns))
+                    .collect::<arrow::error::Result<Vec<Expr>>>()?
+            }
+            None => filters.clone(),
+        };
+
+        // let the data source skip data that cannot match the predicate
+        let pruning_predicate = utils::combine_conjunction(table_filters.clone())
+            .and_then(|predicate| PruningPredicate::try_new(&predicate));
+
+        let (provider, filters) = match provider.push_down_filters(&table_filters)? {
+            Some(filtered) => {
+                let filters: Vec<Expr> = filters
+                    .into_iter()
+                    .zip(filtered.handled)
+                    .filter(|(_, handled)| !handled)
+                    .map(|(filter, _)| filter)
+                    .collect();
+                debug!(
+                    "{} of {} filters on table '{}' are applied by the table provider",
+                    table_filters.len() - filters.len(),
+                    table_filters.len(),
+                    table_name
+                );
+                (filtered.provider, filters)
+            }
+            None => (provider, filters),
+        };
+
+        let scan =
+            self.create_scan(provider, projection, &None, pruning_predicate, config)?;
+        match utils::combine_conjunction(filters) {
+            Some(predicate) => self.create_filter(scan, &predicate, config),
+            None => Ok(scan),
+        }
+    }
+
+    /// Create a filter that evaluates a predicate on the rows of its input
+    fn create_filter(
+        &self,
+        input: Arc<ExecutionPlan>,
+        predicate: &Expr,
+        config: &ExecutionConfig,
+    ) -> Result<Arc<ExecutionPlan>> {
+        let runtime_expr =
+            compile_scalar_expr(&self, predicate, &input.schema(), config)?;
+        Ok(Arc::new(
+            FilterExec::new(input, runtime_expr).with_scheduler(self.scheduler.clone()),
+        ))
+    }
+
+    /// Get the provider of a registered table
+    fn get_table(
+        &self,
+        schema_name: &str,
+        table_name: &str,
+    ) -> Result<Arc<TableProvider>> {
+        match self
+            .datasources
+            .lock()
+            .unwrap()
+            .table(schema_name, table_name)
+        {
+            Some(provider) => Ok(provider),
+            None => Err(ExecutionError::General(format!(
+                "No table registered as '{}.{}'",
+                schema_name, table_name
+            ))),
+        }
+    }
+
+    /// Get the provider of the table that is read by a table scan, which is the table
+    /// returned by calling a table function when the scan has function arguments
+    fn get_scan_provider(
+        &self,
+        schema_name: &str,
+        table_name: &str,
+        function_args: &Option<Vec<ScalarValue>>,
+    ) -> Result<Arc<TableProvider>> {
+        match function_args {
+            Some(args) => {
+                let function = self
+                    .table_functions
+                    .lock()
+                    .unwrap()
+                    .get(&table_name.to_lowercase())
+                    .cloned();
+                match function {
+                    Some(function) => function.invoke(args),
+                    None => Err(ExecutionError::General(format!(
+                        "No table function registered as '{}'",
+                        table_name
+                    ))),
+                }
+            }
+            None => self.get_table(schema_name, table_name),
+        }
+    }
 
-            _ => unimplemented!(),
+    /// Create a limit, which is first applied to each partition of the input so that
+    /// the partitions can stop early, and then to the merged partitions
+    fn create_limit(
+        &self,
+        input: Arc<ExecutionPlan>,
+        limit: usize,
+        config: &ExecutionConfig,
+    ) -> Arc<ExecutionPlan> {
+        if input.output_partitions() > 1 {
+            let partial_limit = Arc::new(LimitExec::new(input, limit));
+            let merge = Arc::new(MergeExec::new(partial_limit, config.concurrency));
+            Arc::new(LimitExec::new(merge, limit))
+        } else {
+            Arc::new(LimitExec::new(input, limit))
         }
     }
 }
 
+/// Get the total number of rows in a set of batches
+pub fn row_count(batches: &[RecordBatch]) -> usize {
+    batches.iter().map(|batch| batch.num_rows()).sum()
+}
+
+/// Read all of the batches from a relation
+fn collect_relation(relation: &Rc<RefCell<Relation>>) -> Result<Vec<RecordBatch>> {
+    let mut relation = relation.borrow_mut();
+    let mut batches = vec![];
+    while let Some(batch) = relation.next()? {
+        batches.push(batch);
+    }
+    Ok(batches)
+}
+
+/// The relation returned for a query, which logs when the query finishes
+struct QueryRelation {
+    schema: Arc<Schema>,
+    input: Rc<RefCell<Relation>>,
+    start: Instant,
+    batch_count: usize,
+    row_count: usize,
+}
+
+impl QueryRelation {
+    fn new(input: Rc<RefCell<Relation>>) -> Self {
+        let schema = input.borrow().schema().clone();
+        Self {
+            schema,
+            input,
+            start: Instant::now(),
+            batch_count: 0,
+            row_count: 0,
+        }
+    }
+}
+
+impl Relation for QueryRelation {
+    fn next(&mut self) -> Result<Option<RecordBatch>> {
+        let result = self.input.borrow_mut().next();
+        match result {
+            Ok(Some(ref batch)) => {
+                self.batch_count += 1;
+                self.row_count += batch.num_rows();
+            }
+            Ok(None) => info!(
+                "Query finished in {:?}: {} rows in {} batches",
+                self.start.elapsed(),
+                self.row_count,
+                self.batch_count
+            ),
+            Err(ref e) => warn!("Query failed after {:?}: {:?}", self.start.elapsed(), e),
+        }
+        result
+    }
+
+    fn schema(&self) -> &Arc<Schema> {
+        &self.schema
+    }
+}
+
+/// Create the expressions for combining the results of an aggregate that has been
+/// applied to each partition separately, where the partial results have one column per
+/// grouping expression followed by one column per aggregate expression. Returns `None`
+/// if any of the aggregate functions cannot be combined this way.
+fn final_aggregate_expr(
+    group_expr_count: usize,
+    aggr_expr: &Vec<Expr>,
+) -> Option<(Vec<Expr>, Vec<Expr>)> {
+    let group_expr: Vec<Expr> = (0..group_expr_count).map(Expr::Column).collect();
+    let aggr_expr = aggr_expr
+        .iter()
+        .enumerate()
+        .map(|(i, e)| match e {
+            Expr::AggregateFunction {
+                name, return_type, ..
+            } => {
+                let final_name = aggregate_function(name)?.final_function()?;
+                Some(Expr::AggregateFunction {
+                    name: final_name.to_string(),
+                    args: vec![Expr::Column(group_expr_count + i)],
+                    return_type: return_type.clone(),
+                })
+            }
+            _ => None,
+        })
+        .collect::<Option<Vec<Expr>>>()?;
+    Some((group_expr, aggr_expr))
+}
+
 /// Create field meta-data from an expression, for use in a result set schema
 pub fn expr_to_field(e: &Expr, input_schema: &Schema) -> Field {
     match e {
         Expr::Column(i) => input_schema.fields()[*i].clone(),
         Expr::Literal(ref lit) => Field::new("lit", lit.get_datatype(), true),
+        Expr::Alias(ref expr, ref alias) => {
+            let field = expr_to_field(expr, input_schema);
+            Field::new(alias, field.data_type().clone(), field.is_nullable())
+        }
         Expr::ScalarFunction {
             ref name,
             ref return_type,
@@ -244,6 +1532,9 @@ pub fn expr_to_field(e: &Expr, input_schema: &Schema) -> Field {
             ..
         } => Field::new(&name, return_type.clone(), true),
         Expr::Cast { ref data_type, .. } => Field::new("cast", data_type.clone(), true),
+        Expr::GetField { ref name, .. } => {
+            Field::new(name, e.get_type(input_schema), true)
+        }
         Expr::BinaryExpr {
             ref left,
             ref right,
@@ -269,17 +1560,63 @@ pub fn exprlist_to_fields(expr: &Vec<Expr>, input_schema: &Schema) -> Vec<Field>
 }
 
 struct ExecutionContextSchemaProvider {
-    datasources: Rc<RefCell<HashMap<String, Rc<DataSourceProvider>>>>,
+    datasources: Arc<Mutex<Catalog>>,
+    functions: Arc<Mutex<HashMap<String, Arc<ScalarUdf>>>>,
+    table_functions: Arc<Mutex<HashMap<String, Arc<TableFunction>>>>,
 }
 impl SchemaProvider for ExecutionContextSchemaProvider {
     fn get_table_meta(&self, name: &str) -> Option<Arc<Schema>> {
-        match self.datasources.borrow().get(name) {
+        match self.datasources.lock().unwrap().resolve(name) {
             Some(ds) => Some(ds.schema().clone()),
             None => None,
         }
     }
 
-    fn get_function_meta(&self, _name: &str) -> Option<Arc<FunctionMeta>> {
-        unimplemented!()
+    fn get_function_meta(&self, name: &str) -> Option<Arc<FunctionMeta>> {
+        match self.functions.lock().unwrap().get(&name.to_lowercase()) {
+            Some(udf) => Some(Arc::new(udf.function_meta())),
+            None => None,
+        }
+    }
+
+    fn get_table_statistics(&self, name: &str) -> Option<Statistics> {
+        match self.datasources.lock().unwrap().resolve(name) {
+            Some(ds) => Some(ds.statistics()),
+            None => None,
+        }
+    }
+
+    fn get_table_function_meta(
+        &self,
+        name: &str,
+        args: &[ScalarValue],
+    ) -> Option<Result<Arc<Schema>>> {
+        let name = name.to_lowercase();
+        let function = self.table_functions.lock().unwrap().get(&name)?.clone();
+        let provider = function.invoke(args);
+        Some(provider.map(|provider| provider.schema().clone()))
+    }
+}
+
+/// Table function that creates a table of the numbers in a range, which is called as
+/// `range(end)` or `range(start, end)`
+fn range(args: &[ScalarValue]) -> Result<Arc<TableProvider>> {
+    let bounds = args
+        .iter()
+        .map(|arg| match arg {
+            ScalarValue::Int64(n) => Ok(*n),
+            other => Err(ExecutionError::General(format!(
+                "Arguments of range must be integers, not {:?}",
+                other
+            ))),
+        })
+        .collect::<Result<Vec<i64>>>()?;
+    match bounds.as_slice() {
+        [end] => Ok(Arc::new(RangeTable::new(0, *end))),
+        [start, end] => Ok(Arc::new(RangeTable::new(*start, *end))),
+        _ => Err(ExecutionError::General(format!(
+            "range expects 1 or 2 arguments but {} were given",
+            args.len()
+        ))),
     }
 }
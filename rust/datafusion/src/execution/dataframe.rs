@@ -0,0 +1,98 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! DataFrame API, which builds a logical plan by applying relational operators such as
+//! `filter` and `select` to a table, as an alternative to writing a SQL query
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use arrow::datatypes::Schema;
+use arrow::record_batch::RecordBatch;
+
+use super::super::logicalplan::{Expr, LogicalPlan, LogicalPlanBuilder};
+use super::context::ExecutionContext;
+use super::error::Result;
+use super::relation::Relation;
+
+/// A DataFrame is a logical plan that is executed by the context that created it.
+/// Columns can be referenced by name with `col`, and are resolved against the schema
+/// of the DataFrame when an operator is applied, so that invalid column names are
+/// reported before the query is executed.
+pub struct DataFrame<'a> {
+    ctx: &'a ExecutionContext,
+    plan: Rc<LogicalPlan>,
+}
+
+impl<'a> DataFrame<'a> {
+    /// Create a DataFrame for a logical plan
+    pub fn new(ctx: &'a ExecutionContext, plan: Rc<LogicalPlan>) -> Self {
+        Self { ctx, plan }
+    }
+
+    /// Keep only the rows for which a boolean predicate is true
+    pub fn filter(&self, expr: Expr) -> Result<DataFrame<'a>> {
+        let plan = LogicalPlanBuilder::from(&self.plan).filter(expr)?.build()?;
+        Ok(DataFrame::new(self.ctx, Rc::new(plan)))
+    }
+
+    /// Compute a new DataFrame with one column per expression
+    pub fn select(&self, expr: Vec<Expr>) -> Result<DataFrame<'a>> {
+        let plan = LogicalPlanBuilder::from(&self.plan)
+            .project(expr)?
+            .build()?;
+        Ok(DataFrame::new(self.ctx, Rc::new(plan)))
+    }
+
+    /// Get the schema of the rows produced by this DataFrame
+    pub fn schema(&self) -> &Arc<Schema> {
+        self.plan.schema()
+    }
+
+    /// Get the logical plan of this DataFrame
+    pub fn logical_plan(&self) -> Rc<LogicalPlan> {
+        self.plan.clone()
+    }
+
+    /// Describe the plan that is executed for this DataFrame, with the output schema of
+    /// each plan node in verbose mode
+    pub fn explain(&self, verbose: bool) -> Result<String> {
+        Ok(self.optimized_plan()?.explain(verbose))
+    }
+
+    /// Execute this DataFrame and produce a Relation (a schema-aware iterator over a
+    /// series of RecordBatch instances)
+    pub fn execute(&self) -> Result<Rc<RefCell<Relation>>> {
+        self.ctx.execute(&self.optimized_plan()?)
+    }
+
+    /// Execute this DataFrame and collect all of the results into memory
+    pub fn collect(&self) -> Result<Vec<RecordBatch>> {
+        self.ctx.collect(&self.optimized_plan()?)
+    }
+
+    /// Get the logical plan of this DataFrame, which is optimized unless the optimizer
+    /// has been disabled in the configuration of the context
+    fn optimized_plan(&self) -> Result<Rc<LogicalPlan>> {
+        if self.ctx.config().optimize() {
+            self.ctx.optimize(&self.plan)
+        } else {
+            Ok(self.plan.clone())
+        }
+    }
+}
@@ -18,26 +18,186 @@
 //! Data sources
 
 use std::cell::RefCell;
-use std::fs::File;
+use std::cmp;
+use std::fs;
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom};
+use std::ops::Range;
+use std::path::Path;
 use std::rc::Rc;
 use std::string::String;
-use std::sync::Arc;
+use std::sync::mpsc::Receiver;
+use std::sync::{Arc, Mutex};
 
+use arrow::array::{ArrayRef, Int64Array};
 use arrow::csv;
-use arrow::datatypes::{Field, Schema};
+use arrow::datatypes::{DataType, Field, Schema};
 use arrow::record_batch::RecordBatch;
+use bzip2::read::BzDecoder;
+use flate2::read::MultiGzDecoder;
 
-use super::error::Result;
+use super::super::logicalplan::{Expr, ScalarValue};
+use super::error::{ExecutionError, Result};
+use super::object_store::{LocalFileSystem, ObjectStore};
+use super::pruning::PruningPredicate;
 
+/// A data source produces the record batches of a single scan of a table
 pub trait DataSource {
+    /// Get the schema of the batches, which only contains the projected columns
     fn schema(&self) -> &Arc<Schema>;
+    /// Get the next batch, or `None` when there are no more batches
     fn next(&mut self) -> Result<Option<RecordBatch>>;
 }
 
+/// Find the files that a path refers to, in sorted order. The path can be a directory, in
+/// which case all files in it are returned except hidden files (starting with `.` or
+/// `_`), or a glob pattern such as `data/*.csv`. Any other path is returned as is.
+pub fn list_files(path: &str) -> Result<Vec<String>> {
+    let mut filenames = vec![];
+    if Path::new(path).is_dir() {
+        for entry in fs::read_dir(path)? {
+            let entry = entry?;
+            let hidden = entry
+                .file_name()
+                .to_string_lossy()
+                .starts_with(|c| c == '.' || c == '_');
+            if entry.file_type()?.is_file() && !hidden {
+                filenames.push(entry.path().to_string_lossy().to_string());
+            }
+        }
+    } else if path.contains(|c| c == '*' || c == '?' || c == '[') {
+        let paths = glob::glob(path).map_err(|e| {
+            ExecutionError::General(format!("Invalid glob pattern '{}': {}", path, e))
+        })?;
+        for entry in paths {
+            let entry = entry.map_err(|e| e.into_error())?;
+            if entry.is_file() {
+                filenames.push(entry.to_string_lossy().to_string());
+            }
+        }
+    } else {
+        filenames.push(path.to_string());
+    }
+
+    if filenames.is_empty() {
+        return Err(ExecutionError::General(format!(
+            "No files found in '{}'",
+            path
+        )));
+    }
+    filenames.sort();
+    Ok(filenames)
+}
+
+/// Compression of CSV files, which are decompressed while they are read
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CsvCompression {
+    Uncompressed,
+    Gzip,
+    Bzip2,
+}
+
+impl CsvCompression {
+    /// Detect the compression of a file from its extension (`.gz` or `.bz2`)
+    pub fn from_filename(filename: &str) -> Self {
+        if filename.ends_with(".gz") {
+            CsvCompression::Gzip
+        } else if filename.ends_with(".bz2") {
+            CsvCompression::Bzip2
+        } else {
+            CsvCompression::Uncompressed
+        }
+    }
+}
+
+/// Options for reading CSV files, which default to comma-separated files with a header
+/// row and double-quoted fields
+#[derive(Debug, Clone)]
+pub struct CsvReadOptions {
+    has_header: bool,
+    delimiter: u8,
+    quote: u8,
+    escape: Option<u8>,
+    comment: Option<u8>,
+    compression: Option<CsvCompression>,
+    null_values: Vec<String>,
+    partition_size: Option<u64>,
+}
+
+impl CsvReadOptions {
+    /// Create options for comma-separated files with a header row
+    pub fn new() -> Self {
+        Self {
+            has_header: true,
+            delimiter: b',',
+            quote: b'"',
+            escape: None,
+            comment: None,
+            compression: None,
+            null_values: vec![],
+            partition_size: None,
+        }
+    }
+
+    /// Set whether the first line of each file is a header row
+    pub fn has_header(mut self, has_header: bool) -> Self {
+        self.has_header = has_header;
+        self
+    }
+
+    /// Set the column delimiter, such as `b'\t'` for tab-separated files
+    pub fn with_delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Set the quote character
+    pub fn with_quote(mut self, quote: u8) -> Self {
+        self.quote = quote;
+        self
+    }
+
+    /// Set the escape character for quotes within quoted fields. By default quotes are
+    /// escaped by doubling them.
+    pub fn with_escape(mut self, escape: u8) -> Self {
+        self.escape = Some(escape);
+        self
+    }
+
+    /// Set the comment character. Lines starting with it are skipped.
+    pub fn with_comment(mut self, comment: u8) -> Self {
+        self.comment = Some(comment);
+        self
+    }
+
+    /// Set the field values that are read as nulls, such as `"NULL"`, `"\\N"` or `""`.
+    /// Empty fields are always nulls in columns that are not strings.
+    pub fn with_null_values(mut self, null_values: &[&str]) -> Self {
+        self.null_values = null_values.iter().map(|v| v.to_string()).collect();
+        self
+    }
+
+    /// Set the compression of the files. By default the compression is detected from
+    /// the file extension.
+    pub fn with_compression(mut self, compression: CsvCompression) -> Self {
+        self.compression = Some(compression);
+        self
+    }
+
+    /// Split uncompressed files that are larger than `size` bytes into byte ranges of
+    /// that size, which are scanned as separate partitions so that a single large file
+    /// can be scanned on several threads. Ranges are aligned to line boundaries, so
+    /// this must not be used for files with quoted fields that contain line breaks.
+    /// Scanning fails if the size is 0.
+    pub fn with_partition_size(mut self, size: u64) -> Self {
+        self.partition_size = Some(size);
+        self
+    }
+}
+
 /// CSV data source
 pub struct CsvDataSource {
     schema: Arc<Schema>,
-    reader: csv::Reader<File>,
+    reader: csv::Reader<Box<Read>>,
 }
 
 impl CsvDataSource {
@@ -48,14 +208,112 @@ impl CsvDataSource {
         projection: &Option<Vec<usize>>,
         batch_size: usize,
     ) -> Self {
-        let file = File::open(filename).unwrap();
-        let reader = csv::Reader::new(
-            file,
-            schema.clone(),
-            has_header,
+        let options = CsvReadOptions::new().has_header(has_header);
+        Self::try_from_object_store(
+            &LocalFileSystem,
+            filename,
+            schema,
+            &options,
+            projection,
             batch_size,
-            projection.clone(),
-        );
+        )
+        .unwrap()
+    }
+
+    /// Create a data source for a CSV file that is read from an object store
+    pub fn try_from_object_store(
+        store: &ObjectStore,
+        filename: &str,
+        schema: Arc<Schema>,
+        options: &CsvReadOptions,
+        projection: &Option<Vec<usize>>,
+        batch_size: usize,
+    ) -> Result<Self> {
+        Self::try_new(
+            store, filename, None, schema, options, projection, batch_size,
+        )
+    }
+
+    /// Create a data source for the lines of an uncompressed CSV file that start within
+    /// a byte range of it, so that a large file can be scanned as several partitions.
+    /// Only the range at the start of the file has a header row.
+    pub fn try_from_byte_range(
+        store: &ObjectStore,
+        filename: &str,
+        range: Range<u64>,
+        schema: Arc<Schema>,
+        options: &CsvReadOptions,
+        projection: &Option<Vec<usize>>,
+        batch_size: usize,
+    ) -> Result<Self> {
+        Self::try_new(
+            store,
+            filename,
+            Some(range),
+            schema,
+            options,
+            projection,
+            batch_size,
+        )
+    }
+
+    fn try_new(
+        store: &ObjectStore,
+        filename: &str,
+        range: Option<Range<u64>>,
+        schema: Arc<Schema>,
+        options: &CsvReadOptions,
+        projection: &Option<Vec<usize>>,
+        batch_size: usize,
+    ) -> Result<Self> {
+        if options.partition_size == Some(0) {
+            return Err(ExecutionError::General(
+                "The partition size of CSV files must be positive".to_string(),
+            ));
+        }
+        let file = store
+            .open(filename)
+            .map_err(|e| ExecutionError::data_source(filename, e))?;
+        let compression = options
+            .compression
+            .unwrap_or_else(|| CsvCompression::from_filename(filename));
+        let has_header = match range {
+            Some(ref range) => options.has_header && range.start == 0,
+            None => options.has_header,
+        };
+        let input: Box<Read> = match (compression, range) {
+            (CsvCompression::Uncompressed, None) => Box::new(file),
+            (CsvCompression::Uncompressed, Some(range)) => Box::new(
+                LineRangeReader::try_new(file, range)
+                    .map_err(|e| ExecutionError::data_source(filename, e.into()))?,
+            ),
+            (CsvCompression::Gzip, None) => Box::new(MultiGzDecoder::new(file)),
+            (CsvCompression::Bzip2, None) => Box::new(BzDecoder::new(file)),
+            (_, Some(_)) => {
+                return Err(ExecutionError::General(format!(
+                    "Cannot read a byte range of compressed file {}",
+                    filename
+                )));
+            }
+        };
+
+        let mut builder = csv::ReaderBuilder::new()
+            .with_schema(schema.clone())
+            .has_headers(has_header)
+            .with_delimiter(options.delimiter)
+            .with_quote(options.quote)
+            .with_null_values(options.null_values.clone())
+            .with_batch_size(batch_size);
+        if let Some(escape) = options.escape {
+            builder = builder.with_escape(escape);
+        }
+        if let Some(comment) = options.comment {
+            builder = builder.with_comment(comment);
+        }
+        if let Some(p) = projection {
+            builder = builder.with_projection(p.clone());
+        }
+        let reader = builder.build_unseekable(input)?;
 
         let projected_schema = match projection {
             Some(p) => {
@@ -67,10 +325,10 @@ impl CsvDataSource {
             None => schema,
         };
 
-        Self {
+        Ok(Self {
             schema: projected_schema,
             reader,
-        }
+        })
     }
 }
 
@@ -84,33 +342,553 @@ impl DataSource for CsvDataSource {
     }
 }
 
-pub trait DataSourceProvider {
+/// Reads the lines of a source that start within a byte range of it. A line that starts
+/// in the range is read to its end, even past the end of the range, and a line that
+/// starts before the range is skipped, so that adjacent ranges read each line once.
+struct LineRangeReader<R> {
+    reader: BufReader<R>,
+    /// The position in the source of the next line
+    position: u64,
+    end: u64,
+    line: Vec<u8>,
+    line_offset: usize,
+}
+
+impl<R: Read + Seek> LineRangeReader<R> {
+    fn try_new(source: R, range: Range<u64>) -> io::Result<Self> {
+        let mut reader = BufReader::new(source);
+        let mut position = 0;
+        if range.start > 0 {
+            // skip the rest of the line that the byte before the range is part of, which
+            // is nothing when that byte ends a line
+            position = reader.seek(SeekFrom::Start(range.start - 1))?;
+            position += reader.read_until(b'\n', &mut vec![])? as u64;
+        }
+        Ok(Self {
+            reader,
+            position,
+            end: range.end,
+            line: vec![],
+            line_offset: 0,
+        })
+    }
+}
+
+impl<R: Read> Read for LineRangeReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.line_offset == self.line.len() {
+            if self.position >= self.end {
+                return Ok(0);
+            }
+            self.line.clear();
+            self.line_offset = 0;
+            self.position += self.reader.read_until(b'\n', &mut self.line)? as u64;
+        }
+        let len = cmp::min(buf.len(), self.line.len() - self.line_offset);
+        buf[..len].copy_from_slice(&self.line[self.line_offset..self.line_offset + len]);
+        self.line_offset += len;
+        Ok(len)
+    }
+}
+
+/// Statistics for a data source, which can be used by the query optimizer. Each value
+/// is `None` when it is not known (or too expensive to compute).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Statistics {
+    /// The number of rows in the data source
+    pub num_rows: Option<usize>,
+    /// The total size of the data source in bytes
+    pub total_byte_size: Option<usize>,
+    /// Statistics for each column, in the same order as the schema
+    pub column_statistics: Option<Vec<ColumnStatistics>>,
+}
+
+/// Statistics for a single column of a data source
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ColumnStatistics {
+    /// The number of null values in the column
+    pub null_count: Option<usize>,
+    /// The minimum value in the column
+    pub min_value: Option<ScalarValue>,
+    /// The maximum value in the column
+    pub max_value: Option<ScalarValue>,
+}
+
+/// A table provider describes a table and creates data sources to scan it. Custom
+/// storage engines can implement this trait and register it with
+/// `ExecutionContext::register_table_provider` to query it from SQL.
+///
+/// A table provider can be shared between threads, so that the partitions of a table
+/// can be scanned concurrently.
+pub trait TableProvider: Send + Sync {
+    /// Get the schema of the table
     fn schema(&self) -> &Arc<Schema>;
+
+    /// Scan the whole table, reading only the projected columns (or all columns when
+    /// the projection is `None`) in batches of up to `batch_size` rows. Returns an
+    /// error if the data cannot be opened, such as when a file does not exist.
     fn scan(
         &self,
         projection: &Option<Vec<usize>>,
         batch_size: usize,
-    ) -> Rc<RefCell<DataSource>>;
+    ) -> Result<Rc<RefCell<DataSource>>>;
+
+    /// Get the number of partitions that this data source can be scanned as
+    fn partitions(&self) -> usize {
+        1
+    }
+
+    /// Scan a single partition of this data source. The default implementation is for
+    /// data sources with a single partition and scans the whole data source.
+    fn scan_partition(
+        &self,
+        projection: &Option<Vec<usize>>,
+        batch_size: usize,
+        _partition: usize,
+    ) -> Result<Rc<RefCell<DataSource>>> {
+        self.scan(projection, batch_size)
+    }
+
+    /// Scan a single partition of this data source, skipping data that cannot contain
+    /// rows matching the predicate where possible. The rows that are read must still be
+    /// filtered. The default implementation ignores the predicate.
+    fn scan_partition_with_predicate(
+        &self,
+        projection: &Option<Vec<usize>>,
+        _predicate: &PruningPredicate,
+        batch_size: usize,
+        partition: usize,
+    ) -> Result<Rc<RefCell<DataSource>>> {
+        self.scan_partition(projection, batch_size, partition)
+    }
+
+    /// Get the statistics for this data source. The default implementation returns
+    /// statistics where every value is unknown.
+    fn statistics(&self) -> Statistics {
+        Statistics::default()
+    }
+
+    /// Push filters, which refer to the columns of the table, down into the provider so
+    /// that rows can be removed before they are returned by a scan. Returns `None` when
+    /// the provider cannot apply any of the filters, which is what the default
+    /// implementation does.
+    fn push_down_filters(&self, _filters: &[Expr]) -> Result<Option<FilteredTable>> {
+        Ok(None)
+    }
+
+    /// Determine whether every scan of this table returns the same rows, so that the
+    /// results of queries over it can be cached. The default implementation returns
+    /// false.
+    fn is_static(&self) -> bool {
+        false
+    }
 }
 
-/// Represents a CSV file with a provided schema
+/// The result of pushing filters down into a table provider
+pub struct FilteredTable {
+    /// A provider whose scans only return rows that match the filters it handles
+    pub provider: Arc<TableProvider>,
+    /// Whether each filter is handled exactly by the provider, in which case it is not
+    /// evaluated again on the scanned rows
+    pub handled: Vec<bool>,
+}
+
+/// Represents one or more CSV files with a provided schema, where each file is a
+/// separate partition, unless the options split files into byte ranges
 pub struct CsvProvider {
-    filename: String,
+    store: Arc<ObjectStore>,
+    filenames: Vec<String>,
+    /// The file of each partition, and the byte range of it when it is split
+    partitions: Vec<(String, Option<Range<u64>>)>,
     schema: Arc<Schema>,
-    has_header: bool,
+    options: CsvReadOptions,
 }
 
 impl CsvProvider {
     pub fn new(filename: &str, schema: &Schema, has_header: bool) -> Self {
+        Self::new_partitioned(&[filename], schema, has_header)
+    }
+
+    /// Create a provider for a table that is partitioned across several CSV files that
+    /// all have the same schema
+    pub fn new_partitioned(
+        filenames: &[&str],
+        schema: &Schema,
+        has_header: bool,
+    ) -> Self {
+        let options = CsvReadOptions::new().has_header(has_header);
+        Self::with_options(filenames, schema, &options)
+    }
+
+    /// Create a provider for one or more CSV files in the format described by the
+    /// options
+    pub fn with_options(
+        filenames: &[&str],
+        schema: &Schema,
+        options: &CsvReadOptions,
+    ) -> Self {
+        Self::with_object_store(Arc::new(LocalFileSystem), filenames, schema, options)
+    }
+
+    /// Create a provider for one or more CSV files that are read from an object store,
+    /// such as files in a remote storage service
+    pub fn with_object_store(
+        store: Arc<ObjectStore>,
+        filenames: &[&str],
+        schema: &Schema,
+        options: &CsvReadOptions,
+    ) -> Self {
+        let mut partitions = vec![];
+        for filename in filenames {
+            let uncompressed = options
+                .compression
+                .unwrap_or_else(|| CsvCompression::from_filename(filename))
+                == CsvCompression::Uncompressed;
+            // a file whose size is unknown is scanned whole, and a missing file is
+            // reported when it is scanned
+            match (options.partition_size, store.size(filename)) {
+                (Some(partition_size), Ok(size))
+                    if uncompressed && partition_size > 0 && size > partition_size =>
+                {
+                    let mut start = 0;
+                    while start < size {
+                        let end = cmp::min(start + partition_size, size);
+                        partitions.push((filename.to_string(), Some(start..end)));
+                        start = end;
+                    }
+                }
+                _ => partitions.push((filename.to_string(), None)),
+            }
+        }
+        Self {
+            store,
+            filenames: filenames.iter().map(|f| String::from(*f)).collect(),
+            partitions,
+            schema: Arc::new(schema.clone()),
+            options: options.clone(),
+        }
+    }
+
+    fn open(
+        &self,
+        partition: usize,
+        projection: &Option<Vec<usize>>,
+        batch_size: usize,
+    ) -> Result<CsvDataSource> {
+        let (filename, range) = &self.partitions[partition];
+        match range {
+            Some(range) => CsvDataSource::try_from_byte_range(
+                self.store.as_ref(),
+                filename,
+                range.clone(),
+                self.schema.clone(),
+                &self.options,
+                projection,
+                batch_size,
+            ),
+            None => CsvDataSource::try_from_object_store(
+                self.store.as_ref(),
+                filename,
+                self.schema.clone(),
+                &self.options,
+                projection,
+                batch_size,
+            ),
+        }
+    }
+}
+
+impl TableProvider for CsvProvider {
+    fn schema(&self) -> &Arc<Schema> {
+        &self.schema
+    }
+
+    fn scan(
+        &self,
+        projection: &Option<Vec<usize>>,
+        batch_size: usize,
+    ) -> Result<Rc<RefCell<DataSource>>> {
+        let sources = (0..self.partitions.len())
+            .map(|partition| {
+                let source = self.open(partition, projection, batch_size)?;
+                Ok(Box::new(source) as Box<DataSource>)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Rc::new(RefCell::new(ConcatDataSource::new(sources))))
+    }
+
+    fn partitions(&self) -> usize {
+        self.partitions.len()
+    }
+
+    fn scan_partition(
+        &self,
+        projection: &Option<Vec<usize>>,
+        batch_size: usize,
+        partition: usize,
+    ) -> Result<Rc<RefCell<DataSource>>> {
+        let source = self.open(partition, projection, batch_size)?;
+        Ok(Rc::new(RefCell::new(source)))
+    }
+
+    fn statistics(&self) -> Statistics {
+        // the number of rows is not known without reading the whole files
+        let total_byte_size = self.filenames.iter().try_fold(0, |total, filename| {
+            self.store
+                .size(filename)
+                .ok()
+                .map(|size| total + size as usize)
+        });
+        Statistics {
+            num_rows: None,
+            total_byte_size,
+            column_statistics: None,
+        }
+    }
+
+    fn is_static(&self) -> bool {
+        true
+    }
+}
+
+/// Represents a table that is held in memory as a series of record batches, where each
+/// batch is a separate partition
+pub struct MemTable {
+    schema: Arc<Schema>,
+    batches: Vec<RecordBatch>,
+}
+
+impl MemTable {
+    /// Create a table from record batches, which must all have the given schema
+    pub fn try_new(schema: &Schema, batches: Vec<RecordBatch>) -> Result<Self> {
+        if let Some(batch) = batches
+            .iter()
+            .find(|batch| batch.schema().fields() != schema.fields())
+        {
+            return Err(ExecutionError::General(format!(
+                "Record batch schema {:?} does not match table schema {:?}",
+                batch.schema().fields(),
+                schema.fields()
+            )));
+        }
+        Ok(Self {
+            schema: Arc::new(schema.clone()),
+            batches,
+        })
+    }
+
+    fn open(
+        &self,
+        batches: &[RecordBatch],
+        projection: &Option<Vec<usize>>,
+    ) -> MemDataSource {
+        let projection = match projection {
+            Some(p) => p.clone(),
+            None => (0..self.schema.fields().len()).collect(),
+        };
+        let schema = Arc::new(Schema::new(
+            projection
+                .iter()
+                .map(|i| self.schema.field(*i).clone())
+                .collect(),
+        ));
+        let batches = batches
+            .iter()
+            .map(|batch| {
+                projection
+                    .iter()
+                    .map(|i| batch.column(*i).clone())
+                    .collect()
+            })
+            .collect();
+        MemDataSource {
+            schema,
+            batches,
+            current: 0,
+        }
+    }
+}
+
+impl TableProvider for MemTable {
+    fn schema(&self) -> &Arc<Schema> {
+        &self.schema
+    }
+
+    /// Scan all batches of the table. The batches are returned as they were provided,
+    /// so the batch size is ignored.
+    fn scan(
+        &self,
+        projection: &Option<Vec<usize>>,
+        _batch_size: usize,
+    ) -> Result<Rc<RefCell<DataSource>>> {
+        Ok(Rc::new(RefCell::new(self.open(&self.batches, projection))))
+    }
+
+    fn partitions(&self) -> usize {
+        self.batches.len().max(1)
+    }
+
+    fn scan_partition(
+        &self,
+        projection: &Option<Vec<usize>>,
+        _batch_size: usize,
+        partition: usize,
+    ) -> Result<Rc<RefCell<DataSource>>> {
+        let batches = self.batches.get(partition..=partition).unwrap_or(&[]);
+        Ok(Rc::new(RefCell::new(self.open(batches, projection))))
+    }
+
+    fn statistics(&self) -> Statistics {
+        Statistics {
+            num_rows: Some(self.batches.iter().map(|batch| batch.num_rows()).sum()),
+            total_byte_size: None,
+            column_statistics: None,
+        }
+    }
+
+    fn is_static(&self) -> bool {
+        true
+    }
+}
+
+/// Data source that returns the projected columns of record batches held in memory
+struct MemDataSource {
+    schema: Arc<Schema>,
+    batches: Vec<Vec<ArrayRef>>,
+    current: usize,
+}
+
+impl DataSource for MemDataSource {
+    fn schema(&self) -> &Arc<Schema> {
+        &self.schema
+    }
+
+    fn next(&mut self) -> Result<Option<RecordBatch>> {
+        match self.batches.get(self.current) {
+            Some(columns) => {
+                self.current += 1;
+                Ok(Some(RecordBatch::new(self.schema.clone(), columns.clone())))
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+/// Represents a table whose record batches are produced by an iterator or a channel, so
+/// that queries can run over data as it is produced by another part of an application.
+/// Every batch is only returned once, so the batches are shared by all scans of the
+/// table and a scan ends when the iterator ends or the channel is closed.
+pub struct StreamTable {
+    schema: Arc<Schema>,
+    batches: Arc<Mutex<Box<Iterator<Item = RecordBatch> + Send>>>,
+}
+
+impl StreamTable {
+    /// Create a table from an iterator of record batches with the given schema
+    pub fn new<I>(schema: &Schema, batches: I) -> Self
+    where
+        I: Iterator<Item = RecordBatch> + Send + 'static,
+    {
         Self {
-            filename: String::from(filename),
             schema: Arc::new(schema.clone()),
-            has_header,
+            batches: Arc::new(Mutex::new(Box::new(batches))),
         }
     }
+
+    /// Create a table from the record batches received from a channel
+    pub fn from_receiver(schema: &Schema, receiver: Receiver<RecordBatch>) -> Self {
+        Self::new(schema, receiver.into_iter())
+    }
 }
 
-impl DataSourceProvider for CsvProvider {
+impl TableProvider for StreamTable {
+    fn schema(&self) -> &Arc<Schema> {
+        &self.schema
+    }
+
+    fn scan(
+        &self,
+        projection: &Option<Vec<usize>>,
+        _batch_size: usize,
+    ) -> Result<Rc<RefCell<DataSource>>> {
+        let projection = match projection {
+            Some(p) => p.clone(),
+            None => (0..self.schema.fields().len()).collect(),
+        };
+        let schema = Arc::new(Schema::new(
+            projection
+                .iter()
+                .map(|i| self.schema.field(*i).clone())
+                .collect(),
+        ));
+        Ok(Rc::new(RefCell::new(StreamDataSource {
+            table_schema: self.schema.clone(),
+            schema,
+            projection,
+            batches: self.batches.clone(),
+        })))
+    }
+}
+
+/// Data source that returns the projected columns of the record batches of a stream
+struct StreamDataSource {
+    table_schema: Arc<Schema>,
+    schema: Arc<Schema>,
+    projection: Vec<usize>,
+    batches: Arc<Mutex<Box<Iterator<Item = RecordBatch> + Send>>>,
+}
+
+impl DataSource for StreamDataSource {
+    fn schema(&self) -> &Arc<Schema> {
+        &self.schema
+    }
+
+    fn next(&mut self) -> Result<Option<RecordBatch>> {
+        let batch = match self.batches.lock().unwrap().next() {
+            Some(batch) => batch,
+            None => return Ok(None),
+        };
+        if batch.schema().fields() != self.table_schema.fields() {
+            return Err(ExecutionError::General(format!(
+                "Record batch schema {:?} does not match table schema {:?}",
+                batch.schema().fields(),
+                self.table_schema.fields()
+            )));
+        }
+
+        let columns = self
+            .projection
+            .iter()
+            .map(|i| batch.column(*i).clone())
+            .collect();
+        Ok(Some(RecordBatch::new(self.schema.clone(), columns)))
+    }
+}
+
+/// Represents a table with a single Int64 column `value` that contains the numbers in a
+/// range, which is useful for generating test data
+pub struct RangeTable {
+    schema: Arc<Schema>,
+    start: i64,
+    end: i64,
+}
+
+impl RangeTable {
+    /// Create a table of the numbers from `start` up to, but not including, `end`
+    pub fn new(start: i64, end: i64) -> Self {
+        Self {
+            schema: Arc::new(Schema::new(vec![Field::new(
+                "value",
+                DataType::Int64,
+                false,
+            )])),
+            start,
+            end: end.max(start),
+        }
+    }
+}
+
+impl TableProvider for RangeTable {
     fn schema(&self) -> &Arc<Schema> {
         &self.schema
     }
@@ -119,13 +897,442 @@ impl DataSourceProvider for CsvProvider {
         &self,
         projection: &Option<Vec<usize>>,
         batch_size: usize,
-    ) -> Rc<RefCell<DataSource>> {
-        Rc::new(RefCell::new(CsvDataSource::new(
-            &self.filename,
-            self.schema.clone(),
-            self.has_header,
+    ) -> Result<Rc<RefCell<DataSource>>> {
+        let projection = match projection {
+            Some(p) => p.clone(),
+            None => vec![0],
+        };
+        let schema = Arc::new(Schema::new(
+            projection
+                .iter()
+                .map(|i| self.schema.field(*i).clone())
+                .collect(),
+        ));
+        Ok(Rc::new(RefCell::new(RangeDataSource {
+            schema,
             projection,
+            next: self.start,
+            end: self.end,
             batch_size,
-        )))
+        })))
+    }
+
+    fn statistics(&self) -> Statistics {
+        let (min_value, max_value) = if self.start < self.end {
+            (
+                Some(ScalarValue::Int64(self.start)),
+                Some(ScalarValue::Int64(self.end - 1)),
+            )
+        } else {
+            (None, None)
+        };
+        Statistics {
+            num_rows: Some((self.end - self.start) as usize),
+            total_byte_size: None,
+            column_statistics: Some(vec![ColumnStatistics {
+                null_count: Some(0),
+                min_value,
+                max_value,
+            }]),
+        }
+    }
+
+    fn is_static(&self) -> bool {
+        true
+    }
+}
+
+/// Data source that generates the numbers of a range in batches
+struct RangeDataSource {
+    schema: Arc<Schema>,
+    projection: Vec<usize>,
+    next: i64,
+    end: i64,
+    batch_size: usize,
+}
+
+impl DataSource for RangeDataSource {
+    fn schema(&self) -> &Arc<Schema> {
+        &self.schema
+    }
+
+    fn next(&mut self) -> Result<Option<RecordBatch>> {
+        if self.next >= self.end {
+            return Ok(None);
+        }
+        let end = self
+            .end
+            .min(self.next.saturating_add(self.batch_size as i64));
+        let values: Vec<i64> = (self.next..end).collect();
+        let array: ArrayRef = Arc::new(Int64Array::from(values));
+        self.next = end;
+        let columns = self.projection.iter().map(|_| array.clone()).collect();
+        Ok(Some(RecordBatch::new(self.schema.clone(), columns)))
+    }
+}
+
+/// Data source that reads a series of data sources with the same schema, one after
+/// another
+pub struct ConcatDataSource {
+    sources: Vec<Box<DataSource>>,
+    current: usize,
+}
+
+impl ConcatDataSource {
+    /// Create a data source that reads the given data sources, of which there must be at
+    /// least one
+    pub fn new(sources: Vec<Box<DataSource>>) -> Self {
+        Self {
+            sources,
+            current: 0,
+        }
+    }
+}
+
+impl DataSource for ConcatDataSource {
+    fn schema(&self) -> &Arc<Schema> {
+        self.sources[0].schema()
+    }
+
+    fn next(&mut self) -> Result<Option<RecordBatch>> {
+        while self.current < self.sources.len() {
+            match self.sources[self.current].next()? {
+                Some(batch) => return Ok(Some(batch)),
+                None => self.current += 1,
+            }
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{BinaryArray, Int32Array};
+    use arrow::datatypes::DataType;
+    use bzip2::write::BzEncoder;
+    use flate2::write::GzEncoder;
+    use std::env;
+    use std::fs::File;
+    use std::io::Write;
+
+    #[test]
+    fn csv_statistics() {
+        let schema = Schema::new(vec![Field::new("c1", DataType::Utf8, false)]);
+        let provider = CsvProvider::new(
+            "../../testing/data/csv/aggregate_test_100.csv",
+            &schema,
+            true,
+        );
+        let statistics = provider.statistics();
+        assert!(statistics.total_byte_size.unwrap() > 0);
+        assert_eq!(None, statistics.num_rows);
+        assert_eq!(None, statistics.column_statistics);
+    }
+
+    #[test]
+    fn csv_statistics_missing_file() {
+        let schema = Schema::new(vec![Field::new("c1", DataType::Utf8, false)]);
+        let provider = CsvProvider::new("does_not_exist.csv", &schema, true);
+        assert_eq!(Statistics::default(), provider.statistics());
+    }
+
+    #[test]
+    fn csv_scan_missing_file() {
+        let schema = Schema::new(vec![Field::new("c1", DataType::Utf8, false)]);
+        let provider = CsvProvider::new("does_not_exist.csv", &schema, true);
+        assert!(provider.scan(&None, 1024).is_err());
+        assert!(provider.scan_partition(&None, 1024, 0).is_err());
+    }
+
+    #[test]
+    fn list_files_in_directory() {
+        let dir = env::temp_dir().join("datafusion_list_files_in_directory");
+        fs::create_dir_all(&dir).unwrap();
+        for name in &["b.csv", "a.csv", "c.txt", "_SUCCESS", ".a.csv.crc"] {
+            fs::write(dir.join(name), "").unwrap();
+        }
+        let path = dir.to_str().unwrap();
+
+        let names = |filenames: Vec<String>| -> Vec<String> {
+            filenames
+                .iter()
+                .map(|f| {
+                    Path::new(f)
+                        .file_name()
+                        .unwrap()
+                        .to_string_lossy()
+                        .to_string()
+                })
+                .collect()
+        };
+        assert_eq!(
+            vec!["a.csv", "b.csv", "c.txt"],
+            names(list_files(path).unwrap())
+        );
+        assert_eq!(
+            vec!["a.csv", "b.csv"],
+            names(list_files(&format!("{}/*.csv", path)).unwrap())
+        );
+        assert!(list_files(&format!("{}/*.parquet", path)).is_err());
+        assert_eq!(vec!["file.csv"], list_files("file.csv").unwrap());
+    }
+
+    #[test]
+    fn csv_compression_from_filename() {
+        assert_eq!(
+            CsvCompression::Gzip,
+            CsvCompression::from_filename("data.csv.gz")
+        );
+        assert_eq!(
+            CsvCompression::Bzip2,
+            CsvCompression::from_filename("data.csv.bz2")
+        );
+        assert_eq!(
+            CsvCompression::Uncompressed,
+            CsvCompression::from_filename("data.csv")
+        );
+    }
+
+    #[test]
+    fn read_compressed_csv() {
+        let data = "c1,c2\na,1\nb,2\nc,3\n";
+        let dir = env::temp_dir();
+
+        let gzip_path = dir.join("datafusion_read_compressed_csv.csv.gz");
+        let mut encoder = GzEncoder::new(
+            File::create(&gzip_path).unwrap(),
+            flate2::Compression::default(),
+        );
+        encoder.write_all(data.as_bytes()).unwrap();
+        encoder.finish().unwrap();
+
+        let bzip2_path = dir.join("datafusion_read_compressed_csv.csv.bz2");
+        let mut encoder = BzEncoder::new(
+            File::create(&bzip2_path).unwrap(),
+            bzip2::Compression::Default,
+        );
+        encoder.write_all(data.as_bytes()).unwrap();
+        encoder.finish().unwrap();
+
+        let schema = Schema::new(vec![
+            Field::new("c1", DataType::Utf8, false),
+            Field::new("c2", DataType::Int32, false),
+        ]);
+        for path in &[gzip_path, bzip2_path] {
+            let provider = CsvProvider::new(path.to_str().unwrap(), &schema, true);
+            let batch = provider
+                .scan(&None, 1024)
+                .unwrap()
+                .borrow_mut()
+                .next()
+                .unwrap()
+                .unwrap();
+            assert_eq!(3, batch.num_rows());
+            assert_eq!(2, batch.num_columns());
+        }
+    }
+
+    #[test]
+    fn csv_partitions() {
+        let schema = Schema::new(vec![Field::new("c1", DataType::Utf8, false)]);
+        let filename = "../../testing/data/csv/aggregate_test_100.csv";
+        let provider = CsvProvider::new_partitioned(&[filename, filename], &schema, true);
+        assert_eq!(2, provider.partitions());
+
+        let single = CsvProvider::new(filename, &schema, true).statistics();
+        assert_eq!(
+            single.total_byte_size.map(|size| size * 2),
+            provider.statistics().total_byte_size
+        );
+
+        let count_rows = |ds: Rc<RefCell<DataSource>>| {
+            let mut rows = 0;
+            while let Some(batch) = ds.borrow_mut().next().unwrap() {
+                rows += batch.num_rows();
+            }
+            rows
+        };
+        assert_eq!(
+            100,
+            count_rows(provider.scan_partition(&None, 1024, 1).unwrap())
+        );
+        assert_eq!(200, count_rows(provider.scan(&None, 1024).unwrap()));
+    }
+
+    #[test]
+    fn line_range_reader() {
+        let data = "a\nbb\n\nccc\nd";
+        let len = data.len() as u64;
+        for partition_size in 1..=len + 1 {
+            let mut lines = String::new();
+            let mut start = 0;
+            while start < len {
+                let end = cmp::min(start + partition_size, len);
+                LineRangeReader::try_new(io::Cursor::new(data), start..end)
+                    .unwrap()
+                    .read_to_string(&mut lines)
+                    .unwrap();
+                start = end;
+            }
+            assert_eq!(data, lines, "partition size {}", partition_size);
+        }
+    }
+
+    #[test]
+    fn csv_byte_range_partitions() {
+        let path = env::temp_dir().join("datafusion_csv_byte_range_partitions.csv");
+        let mut data = "c1,c2\n".to_string();
+        for i in 0..100 {
+            data.push_str(&format!("{},{}\n", i, i * 2));
+        }
+        fs::write(&path, &data).unwrap();
+        let filename = path.to_str().unwrap();
+        let schema = Schema::new(vec![
+            Field::new("c1", DataType::Int32, false),
+            Field::new("c2", DataType::Int32, false),
+        ]);
+
+        let options = CsvReadOptions::new().with_partition_size(64);
+        let provider = CsvProvider::with_options(&[filename], &schema, &options);
+        let partitions = (data.len() + 63) / 64;
+        assert_eq!(partitions, provider.partitions());
+
+        // every row is read once, by the partition that its line starts in
+        let mut values = vec![];
+        for partition in 0..partitions {
+            let ds = provider
+                .scan_partition(&Some(vec![0]), 1024, partition)
+                .unwrap();
+            while let Some(batch) = ds.borrow_mut().next().unwrap() {
+                let c1 = batch
+                    .column(0)
+                    .as_any()
+                    .downcast_ref::<Int32Array>()
+                    .unwrap();
+                values.extend((0..c1.len()).map(|i| c1.value(i)));
+            }
+        }
+        assert_eq!((0..100).collect::<Vec<i32>>(), values);
+
+        // compressed files cannot be split
+        let options = options.with_compression(CsvCompression::Gzip);
+        let provider = CsvProvider::with_options(&[filename], &schema, &options);
+        assert_eq!(1, provider.partitions());
+
+        // a partition size of 0 is rejected when the file is scanned
+        let options = CsvReadOptions::new().with_partition_size(0);
+        let provider = CsvProvider::with_options(&[filename], &schema, &options);
+        assert_eq!(1, provider.partitions());
+        assert!(provider.scan_partition(&None, 1024, 0).is_err());
+    }
+
+    #[test]
+    fn mem_table_scan() {
+        let schema = Schema::new(vec![
+            Field::new("a", DataType::Int32, false),
+            Field::new("b", DataType::Utf8, false),
+        ]);
+        let batch = || {
+            RecordBatch::new(
+                Arc::new(schema.clone()),
+                vec![
+                    Arc::new(Int32Array::from(vec![1, 2, 3])),
+                    Arc::new(BinaryArray::from(vec!["x", "y", "z"])),
+                ],
+            )
+        };
+        let provider = MemTable::try_new(&schema, vec![batch(), batch()]).unwrap();
+        assert_eq!(2, provider.partitions());
+        assert_eq!(Some(6), provider.statistics().num_rows);
+
+        let ds = provider.scan_partition(&Some(vec![1]), 1024, 0).unwrap();
+        let mut ds = ds.borrow_mut();
+        assert_eq!("b", ds.schema().field(0).name());
+        let batch = ds.next().unwrap().unwrap();
+        assert_eq!(1, batch.num_columns());
+        assert_eq!(3, batch.num_rows());
+        assert!(ds.next().unwrap().is_none());
+    }
+
+    #[test]
+    fn mem_table_schema_mismatch() {
+        let schema = Schema::new(vec![Field::new("a", DataType::Int32, false)]);
+        let other = Schema::new(vec![Field::new("a", DataType::Int64, false)]);
+        let batch = RecordBatch::new(
+            Arc::new(schema),
+            vec![Arc::new(Int32Array::from(vec![1, 2, 3]))],
+        );
+        assert!(MemTable::try_new(&other, vec![batch]).is_err());
+    }
+
+    #[test]
+    fn range_table_scan() {
+        let provider = RangeTable::new(5, 15);
+        assert_eq!(Some(10), provider.statistics().num_rows);
+
+        let ds = provider.scan(&None, 4).unwrap();
+        let mut ds = ds.borrow_mut();
+        let mut values = vec![];
+        while let Some(batch) = ds.next().unwrap() {
+            assert!(batch.num_rows() <= 4);
+            let array = batch
+                .column(0)
+                .as_any()
+                .downcast_ref::<Int64Array>()
+                .unwrap();
+            values.extend((0..array.len()).map(|i| array.value(i)));
+        }
+        assert_eq!((5..15).collect::<Vec<i64>>(), values);
+
+        let empty = RangeTable::new(10, 0);
+        assert_eq!(Some(0), empty.statistics().num_rows);
+        let ds = empty.scan(&None, 4).unwrap();
+        assert!(ds.borrow_mut().next().unwrap().is_none());
+    }
+
+    #[test]
+    fn stream_table_scan() {
+        let schema = Schema::new(vec![
+            Field::new("a", DataType::Int32, false),
+            Field::new("b", DataType::Int32, false),
+        ]);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let provider = StreamTable::from_receiver(&schema, receiver);
+        for i in 0..3 {
+            sender
+                .send(RecordBatch::new(
+                    Arc::new(schema.clone()),
+                    vec![
+                        Arc::new(Int32Array::from(vec![i, i])),
+                        Arc::new(Int32Array::from(vec![i * 10, i * 10])),
+                    ],
+                ))
+                .unwrap();
+        }
+        drop(sender);
+
+        let ds = provider.scan(&Some(vec![1]), 1024).unwrap();
+        let mut ds = ds.borrow_mut();
+        let mut values = vec![];
+        while let Some(batch) = ds.next().unwrap() {
+            assert_eq!(1, batch.num_columns());
+            let b = batch
+                .column(0)
+                .as_any()
+                .downcast_ref::<Int32Array>()
+                .unwrap();
+            values.push(b.value(0));
+        }
+        assert_eq!(vec![0, 10, 20], values);
+
+        // the stream has been consumed by the first scan
+        assert!(provider
+            .scan(&None, 1024)
+            .unwrap()
+            .borrow_mut()
+            .next()
+            .unwrap()
+            .is_none());
     }
 }
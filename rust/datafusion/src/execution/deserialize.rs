@@ -0,0 +1,367 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Deserialization of the rows of record batches into Rust values with serde, so that
+//! query results can be read as a `Vec<T>` instead of by downcasting each column.
+//!
+//! Each row is deserialized as a map from column names to values, so structs are
+//! matched to columns by field name and columns without a matching field are ignored.
+//! Rows can also be deserialized as tuples, in which case the columns are matched by
+//! position. Null values can only be deserialized into `Option` fields, and binary
+//! values are deserialized as bytes, such as into a `serde_bytes::ByteBuf`.
+
+use std::error;
+use std::fmt;
+
+use arrow::array::*;
+use arrow::datatypes::DataType;
+use arrow::record_batch::RecordBatch;
+
+use serde::de::{
+    self, DeserializeOwned, DeserializeSeed, IntoDeserializer, MapAccess, SeqAccess,
+    Visitor,
+};
+
+use super::error::{ExecutionError, Result};
+
+/// Deserialize each row of a batch into a value of type `T`
+pub fn from_batch<T: DeserializeOwned>(batch: &RecordBatch) -> Result<Vec<T>> {
+    (0..batch.num_rows())
+        .map(|row| {
+            T::deserialize(RowDeserializer { batch, row })
+                .map_err(|e| ExecutionError::General(e.0))
+        })
+        .collect()
+}
+
+/// Deserialize each row of a series of batches into a value of type `T`
+pub fn from_batches<T: DeserializeOwned>(batches: &[RecordBatch]) -> Result<Vec<T>> {
+    let mut values = vec![];
+    for batch in batches {
+        values.extend(from_batch(batch)?);
+    }
+    Ok(values)
+}
+
+/// Error produced when a row cannot be deserialized
+#[derive(Debug)]
+struct Error(String);
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl error::Error for Error {}
+
+impl de::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Error(msg.to_string())
+    }
+}
+
+/// Deserializes a single row of a batch
+struct RowDeserializer<'a> {
+    batch: &'a RecordBatch,
+    row: usize,
+}
+
+impl<'de, 'a> de::Deserializer<'de> for RowDeserializer<'a> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> std::result::Result<V::Value, Error> {
+        visitor.visit_map(RowAccess {
+            batch: self.batch,
+            row: self.row,
+            column: 0,
+        })
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> std::result::Result<V::Value, Error> {
+        visitor.visit_seq(RowAccess {
+            batch: self.batch,
+            row: self.row,
+            column: 0,
+        })
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> std::result::Result<V::Value, Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> std::result::Result<V::Value, Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 u8 u16 u32 u64 f32 f64 char str string bytes
+        byte_buf option unit unit_struct newtype_struct map struct enum identifier
+        ignored_any
+    }
+}
+
+/// Accesses the columns of a row, either as map entries keyed by column name or as a
+/// sequence of values
+struct RowAccess<'a> {
+    batch: &'a RecordBatch,
+    row: usize,
+    column: usize,
+}
+
+impl<'de, 'a> MapAccess<'de> for RowAccess<'a> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> std::result::Result<Option<K::Value>, Error> {
+        if self.column == self.batch.num_columns() {
+            return Ok(None);
+        }
+        let name = self.batch.schema().field(self.column).name().as_str();
+        seed.deserialize(name.into_deserializer()).map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> std::result::Result<V::Value, Error> {
+        let value = ValueDeserializer {
+            array: self.batch.column(self.column),
+            row: self.row,
+        };
+        self.column += 1;
+        seed.deserialize(value)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.batch.num_columns() - self.column)
+    }
+}
+
+impl<'de, 'a> SeqAccess<'de> for RowAccess<'a> {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> std::result::Result<Option<T::Value>, Error> {
+        if self.column == self.batch.num_columns() {
+            return Ok(None);
+        }
+        self.next_value_seed(seed).map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.batch.num_columns() - self.column)
+    }
+}
+
+/// Deserializes a single value of an array
+struct ValueDeserializer<'a> {
+    array: &'a ArrayRef,
+    row: usize,
+}
+
+macro_rules! visit_primitive {
+    ($ARRAY:expr, $ROW:expr, $VISITOR:expr, $ARRAY_TYPE:ident, $VISIT:ident) => {{
+        let array = $ARRAY.as_any().downcast_ref::<$ARRAY_TYPE>().unwrap();
+        $VISITOR.$VISIT(array.value($ROW))
+    }};
+}
+
+impl<'de, 'a> de::Deserializer<'de> for ValueDeserializer<'a> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> std::result::Result<V::Value, Error> {
+        let (array, row) = (self.array, self.row);
+        if array.is_null(row) {
+            return visitor.visit_none();
+        }
+        match array.data_type() {
+            DataType::Boolean => {
+                visit_primitive!(array, row, visitor, BooleanArray, visit_bool)
+            }
+            DataType::Int8 => visit_primitive!(array, row, visitor, Int8Array, visit_i8),
+            DataType::Int16 => {
+                visit_primitive!(array, row, visitor, Int16Array, visit_i16)
+            }
+            DataType::Int32 => {
+                visit_primitive!(array, row, visitor, Int32Array, visit_i32)
+            }
+            DataType::Int64 => {
+                visit_primitive!(array, row, visitor, Int64Array, visit_i64)
+            }
+            DataType::UInt8 => {
+                visit_primitive!(array, row, visitor, UInt8Array, visit_u8)
+            }
+            DataType::UInt16 => {
+                visit_primitive!(array, row, visitor, UInt16Array, visit_u16)
+            }
+            DataType::UInt32 => {
+                visit_primitive!(array, row, visitor, UInt32Array, visit_u32)
+            }
+            DataType::UInt64 => {
+                visit_primitive!(array, row, visitor, UInt64Array, visit_u64)
+            }
+            DataType::Float32 => {
+                visit_primitive!(array, row, visitor, Float32Array, visit_f32)
+            }
+            DataType::Float64 => {
+                visit_primitive!(array, row, visitor, Float64Array, visit_f64)
+            }
+            DataType::Utf8 => {
+                let array = array.as_any().downcast_ref::<BinaryArray>().unwrap();
+                match std::str::from_utf8(array.value(row)) {
+                    Ok(value) => visitor.visit_str(value),
+                    Err(e) => Err(Error(format!("Invalid UTF-8 string: {}", e))),
+                }
+            }
+            DataType::Binary => {
+                let array = array.as_any().downcast_ref::<BinaryArray>().unwrap();
+                visitor.visit_bytes(array.value(row))
+            }
+            other => Err(Error(format!(
+                "Cannot deserialize values of type {:?}",
+                other
+            ))),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> std::result::Result<V::Value, Error> {
+        if self.array.is_null(self.row) {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> std::result::Result<V::Value, Error> {
+        // columns that are not mapped to a field can have any type
+        visitor.visit_unit()
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 u8 u16 u32 u64 f32 f64 char str string bytes
+        byte_buf unit unit_struct newtype_struct seq tuple tuple_struct map struct enum
+        identifier
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::builder::BinaryBuilder;
+    use arrow::datatypes::{Field, Schema};
+    use std::sync::Arc;
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Person {
+        name: String,
+        age: Option<u8>,
+    }
+
+    fn people() -> RecordBatch {
+        let schema = Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("name", DataType::Utf8, false),
+            Field::new("age", DataType::UInt8, true),
+        ]);
+        let mut names = BinaryBuilder::new(16);
+        names.append_string("alice").unwrap();
+        names.append_string("bob").unwrap();
+        RecordBatch::new(
+            Arc::new(schema),
+            vec![
+                Arc::new(Int32Array::from(vec![1, 2])),
+                Arc::new(names.finish()),
+                Arc::new(UInt8Array::from(vec![Some(30), None])),
+            ],
+        )
+    }
+
+    #[test]
+    fn deserialize_structs() {
+        let people: Vec<Person> = from_batch(&people()).unwrap();
+        assert_eq!(
+            vec![
+                Person {
+                    name: "alice".to_string(),
+                    age: Some(30),
+                },
+                Person {
+                    name: "bob".to_string(),
+                    age: None,
+                },
+            ],
+            people
+        );
+    }
+
+    #[test]
+    fn deserialize_tuples() {
+        let rows: Vec<(i32, String, Option<u8>)> =
+            from_batches(&[people(), people()]).unwrap();
+        assert_eq!(4, rows.len());
+        assert_eq!((2, "bob".to_string(), None), rows[1]);
+    }
+
+    #[test]
+    fn deserialize_null_into_required_field() {
+        #[derive(Debug, Deserialize)]
+        struct Age {
+            #[allow(dead_code)]
+            age: u8,
+        }
+        assert!(from_batch::<Age>(&people()).is_err());
+    }
+
+    #[test]
+    fn deserialize_missing_column() {
+        #[derive(Debug, Deserialize)]
+        struct Email {
+            #[allow(dead_code)]
+            email: String,
+        }
+        assert!(from_batch::<Email>(&people()).is_err());
+    }
+}
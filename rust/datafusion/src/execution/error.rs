@@ -17,11 +17,15 @@
 
 //! Error types
 
+use std::error;
+use std::fmt;
 use std::io::Error;
 use std::result;
 
 use arrow::error::ArrowError;
 
+use parquet::errors::ParquetError;
+
 use sqlparser::sqlparser::ParserError;
 
 pub type Result<T> = result::Result<T, ExecutionError>;
@@ -35,9 +39,121 @@ pub enum ExecutionError {
     NotImplemented(String),
     InternalError(String),
     ArrowError(ArrowError),
+    ParquetError(ParquetError),
     ExecutionError(String),
+    /// A query needed more memory than its limit allows
+    ResourcesExhausted(String),
+    /// A query could not be planned, such as because it refers to a table, column or
+    /// function that does not exist
+    PlanError(PlanError),
+    /// A data source could not be read
+    DataSourceError {
+        /// The location of the data, such as a file name
+        location: String,
+        /// The error produced while reading the data
+        source: Box<ExecutionError>,
+    },
+}
+
+impl ExecutionError {
+    /// Create an error for a query that could not be planned because of the given
+    /// identifier or expression
+    pub fn plan(message: String, context: &str) -> Self {
+        ExecutionError::PlanError(PlanError {
+            message,
+            context: Some(context.to_string()),
+            position: None,
+        })
+    }
+
+    /// Create an error for a data source that could not be read
+    pub fn data_source(location: &str, source: ExecutionError) -> Self {
+        ExecutionError::DataSourceError {
+            location: location.to_string(),
+            source: Box::new(source),
+        }
+    }
+
+    /// Find the position in the SQL text of the identifier or expression that caused a
+    /// planning error, if the position is not known yet and the identifier or
+    /// expression appears in the text
+    pub fn locate(self, sql: &str) -> Self {
+        match self {
+            ExecutionError::PlanError(mut e) => {
+                if e.position.is_none() {
+                    e.position = e.context.as_ref().and_then(|c| find_token(sql, c));
+                }
+                ExecutionError::PlanError(e)
+            }
+            other => other,
+        }
+    }
+}
+
+/// Find the byte offset of the first occurrence of a token in SQL text that is not
+/// part of a longer identifier
+fn find_token(sql: &str, token: &str) -> Option<usize> {
+    let is_identifier = |c: char| c.is_alphanumeric() || c == '_';
+    sql.match_indices(token).map(|(i, _)| i).find(|&i| {
+        let before = sql[..i].chars().next_back();
+        let after = sql[i + token.len()..].chars().next();
+        !before.map_or(false, is_identifier) && !after.map_or(false, is_identifier)
+    })
+}
+
+/// Details of an error produced while planning a query
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlanError {
+    /// Description of the problem
+    pub message: String,
+    /// The identifier or expression that caused the error, as written in the query
+    pub context: Option<String>,
+    /// The byte offset of the identifier or expression in the SQL text, if known
+    pub position: Option<usize>,
+}
+
+impl fmt::Display for PlanError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.position {
+            Some(position) => write!(f, "{} at position {}", self.message, position),
+            None => write!(f, "{}", self.message),
+        }
+    }
 }
 
+impl fmt::Display for ExecutionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ExecutionError::IoError(e) => write!(f, "IO error: {}", e),
+            ExecutionError::ParserError(e) => write!(f, "SQL parser error: {:?}", e),
+            ExecutionError::General(message) => write!(f, "{}", message),
+            ExecutionError::InvalidColumn(message) => {
+                write!(f, "Invalid column: {}", message)
+            }
+            ExecutionError::NotImplemented(message) => {
+                write!(f, "Not implemented: {}", message)
+            }
+            ExecutionError::InternalError(message) => {
+                write!(f, "Internal error: {}", message)
+            }
+            ExecutionError::ArrowError(e) => write!(f, "Arrow error: {:?}", e),
+            ExecutionError::ParquetError(e) => write!(f, "Parquet error: {:?}", e),
+            ExecutionError::ExecutionError(message) => {
+                write!(f, "Execution error: {}", message)
+            }
+            ExecutionError::ResourcesExhausted(message) => {
+                write!(f, "Resources exhausted: {}", message)
+            }
+            ExecutionError::PlanError(e) => write!(f, "Planning error: {}", e),
+            ExecutionError::DataSourceError { location, source } => {
+                write!(f, "Error reading data source '{}': {}", location, source)
+            }
+        }
+    }
+}
+
+impl error::Error for ExecutionError {}
+
 impl From<Error> for ExecutionError {
     fn from(e: Error) -> Self {
         ExecutionError::IoError(e)
@@ -62,8 +178,50 @@ impl From<ArrowError> for ExecutionError {
     }
 }
 
+impl From<ParquetError> for ExecutionError {
+    fn from(e: ParquetError) -> Self {
+        ExecutionError::ParquetError(e)
+    }
+}
+
 impl From<ParserError> for ExecutionError {
     fn from(e: ParserError) -> Self {
         ExecutionError::ParserError(e)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locate_plan_error() {
+        let sql = "SELECT id, name FROM people WHERE nam = 'x'";
+        let e = ExecutionError::plan("Invalid identifier 'nam'".to_string(), "nam");
+        match e.locate(sql) {
+            ExecutionError::PlanError(e) => {
+                assert_eq!(Some(34), e.position);
+                assert_eq!("Invalid identifier 'nam' at position 34", e.to_string());
+            }
+            other => panic!("unexpected error {:?}", other),
+        }
+
+        let e = ExecutionError::plan("Invalid table 'other'".to_string(), "other");
+        assert_eq!(
+            "Planning error: Invalid table 'other'",
+            e.locate(sql).to_string()
+        );
+    }
+
+    #[test]
+    fn display_data_source_error() {
+        let e = ExecutionError::data_source(
+            "data.csv",
+            ExecutionError::General("file not found".to_string()),
+        );
+        assert_eq!(
+            "Error reading data source 'data.csv': file not found",
+            e.to_string()
+        );
+    }
+}
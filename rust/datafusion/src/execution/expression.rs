@@ -15,23 +15,31 @@
 // specific language governing permissions and limitations
 // under the License.
 
-use std::rc::Rc;
+use std::cmp::Ordering;
 use std::sync::Arc;
 
 use arrow::array::*;
+use arrow::builder::BinaryBuilder;
 use arrow::compute;
-use arrow::datatypes::{DataType, Schema};
+use arrow::datatypes::{ArrowNumericType, DataType, DateUnit, Schema, TimeUnit};
+use arrow::error::ArrowError;
 use arrow::record_batch::RecordBatch;
 
 use super::super::logicalplan::{Expr, Operator, ScalarValue};
-use super::context::ExecutionContext;
+use super::context::{
+    Collation, ExecutionConfig, ExecutionContext, NanOrdering, OverflowMode,
+};
 use super::error::{ExecutionError, Result};
+use super::filter::filter;
+use super::functions::aggregate_function;
+use super::list::list_function;
 
 /// Compiled Expression (basically just a closure to evaluate the expression at runtime)
-pub type CompiledExpr = Rc<Fn(&RecordBatch) -> Result<ArrayRef>>;
+pub type CompiledExpr = Arc<Fn(&RecordBatch) -> Result<ArrayRef> + Send + Sync>;
 
-pub type CompiledCastFunction = Rc<Fn(&ArrayRef) -> Result<ArrayRef>>;
+pub type CompiledCastFunction = Arc<Fn(&ArrayRef) -> Result<ArrayRef> + Send + Sync>;
 
+#[derive(Debug, Clone, PartialEq)]
 pub enum AggregateType {
     Min,
     Max,
@@ -42,6 +50,7 @@ pub enum AggregateType {
 }
 
 /// Runtime expression
+#[derive(Clone)]
 pub enum RuntimeExpr {
     Compiled {
         name: String,
@@ -84,6 +93,7 @@ pub fn compile_expr(
     ctx: &ExecutionContext,
     expr: &Expr,
     input_schema: &Schema,
+    config: &ExecutionConfig,
 ) -> Result<RuntimeExpr> {
     match *expr {
         Expr::AggregateFunction {
@@ -95,15 +105,12 @@ pub fn compile_expr(
 
             let compiled_args: Result<Vec<RuntimeExpr>> = args
                 .iter()
-                .map(|e| compile_scalar_expr(&ctx, e, input_schema))
+                .map(|e| compile_scalar_expr(&ctx, e, input_schema, config))
                 .collect();
 
-            let func = match name.to_lowercase().as_ref() {
-                "min" => Ok(AggregateType::Min),
-                "max" => Ok(AggregateType::Max),
-                "count" => Ok(AggregateType::Count),
-                "sum" => Ok(AggregateType::Sum),
-                _ => Err(ExecutionError::General(format!(
+            let func = match aggregate_function(name) {
+                Some(aggregate) => Ok(aggregate.aggregate_type().clone()),
+                None => Err(ExecutionError::General(format!(
                     "Unsupported aggregate function '{}'",
                     name
                 ))),
@@ -119,111 +126,743 @@ pub fn compile_expr(
                 t: return_type.clone(),
             })
         }
-        _ => Ok(compile_scalar_expr(&ctx, expr, input_schema)?),
+        _ => Ok(compile_scalar_expr(&ctx, expr, input_schema, config)?),
     }
 }
 
-macro_rules! binary_op {
-    ($LEFT:expr, $RIGHT:expr, $OP:ident, $DT:ident) => {{
+/// Integer arithmetic that handles overflow according to an `OverflowMode`
+pub trait OverflowArithmetic: Copy {
+    /// Apply an arithmetic operator, returning `None` if the result overflows
+    fn checked_op(self, op: &Operator, rhs: Self) -> Option<Self>;
+    /// Apply an arithmetic operator, wrapping around on overflow
+    fn wrapping_op(self, op: &Operator, rhs: Self) -> Self;
+    /// Apply an arithmetic operator, clamping the result on overflow
+    fn saturating_op(self, op: &Operator, rhs: Self) -> Self;
+}
+
+macro_rules! overflow_arithmetic {
+    ($T:ty) => {
+        impl OverflowArithmetic for $T {
+            fn checked_op(self, op: &Operator, rhs: Self) -> Option<Self> {
+                match op {
+                    Operator::Plus => self.checked_add(rhs),
+                    Operator::Minus => self.checked_sub(rhs),
+                    Operator::Multiply => self.checked_mul(rhs),
+                    Operator::Divide => self.checked_div(rhs),
+                    Operator::Modulus => self.checked_rem(rhs),
+                    _ => unreachable!("{:?} is not an arithmetic operator", op),
+                }
+            }
+
+            fn wrapping_op(self, op: &Operator, rhs: Self) -> Self {
+                match op {
+                    Operator::Plus => self.wrapping_add(rhs),
+                    Operator::Minus => self.wrapping_sub(rhs),
+                    Operator::Multiply => self.wrapping_mul(rhs),
+                    Operator::Divide => self.wrapping_div(rhs),
+                    Operator::Modulus => self.wrapping_rem(rhs),
+                    _ => unreachable!("{:?} is not an arithmetic operator", op),
+                }
+            }
+
+            fn saturating_op(self, op: &Operator, rhs: Self) -> Self {
+                match op {
+                    Operator::Plus => self.saturating_add(rhs),
+                    Operator::Minus => self.saturating_sub(rhs),
+                    Operator::Multiply => self.saturating_mul(rhs),
+                    // the only division that overflows is the minimum value divided by
+                    // -1, which saturates to the maximum value, and the remainder of
+                    // that division is zero
+                    Operator::Divide => {
+                        self.checked_div(rhs).unwrap_or(<$T>::max_value())
+                    }
+                    Operator::Modulus => self.checked_rem(rhs).unwrap_or(0),
+                    _ => unreachable!("{:?} is not an arithmetic operator", op),
+                }
+            }
+        }
+    };
+}
+
+overflow_arithmetic!(i8);
+overflow_arithmetic!(i16);
+overflow_arithmetic!(i32);
+overflow_arithmetic!(i64);
+overflow_arithmetic!(u8);
+overflow_arithmetic!(u16);
+overflow_arithmetic!(u32);
+overflow_arithmetic!(u64);
+
+/// Apply an arithmetic operation to two arrays. The result is null where either value
+/// is null, and where the divisor of a division or modulo is zero, unless
+/// `error_on_zero` is set, in which case the operation fails with
+/// `ArrowError::DivideByZero` instead.
+pub fn arithmetic_op<T, F>(
+    left: &PrimitiveArray<T>,
+    right: &PrimitiveArray<T>,
+    op: &Operator,
+    error_on_zero: bool,
+    f: F,
+) -> Result<PrimitiveArray<T>>
+where
+    T: ArrowNumericType,
+    F: Fn(T::Native, T::Native) -> std::result::Result<T::Native, ArrowError>,
+{
+    if left.len() != right.len() {
+        return Err(ExecutionError::ExecutionError(
+            "Cannot perform arithmetic on arrays of different length".to_string(),
+        ));
+    }
+    let has_divisor = *op == Operator::Divide || *op == Operator::Modulus;
+    let mut builder = PrimitiveBuilder::<T>::new(left.len());
+    for i in 0..left.len() {
+        if left.is_null(i) || right.is_null(i) {
+            builder.append_null()?;
+        } else if has_divisor && right.value(i) == T::default_value() {
+            if error_on_zero {
+                return Err(ArrowError::DivideByZero.into());
+            }
+            builder.append_null()?;
+        } else {
+            builder.append_value(f(left.value(i), right.value(i))?)?;
+        }
+    }
+    Ok(builder.finish())
+}
+
+/// Apply an arithmetic operator to two integer arrays, handling overflow according to
+/// the given mode
+pub fn integer_math_op<T>(
+    left: &PrimitiveArray<T>,
+    right: &PrimitiveArray<T>,
+    op: &Operator,
+    mode: OverflowMode,
+    error_on_zero: bool,
+) -> Result<PrimitiveArray<T>>
+where
+    T: ArrowNumericType,
+    T::Native: OverflowArithmetic,
+{
+    arithmetic_op(left, right, op, error_on_zero, |a, b| match mode {
+        OverflowMode::Checked => a.checked_op(op, b).ok_or_else(|| {
+            ArrowError::ComputeError(format!(
+                "Integer overflow in {:?} {:?} {:?}",
+                a, op, b
+            ))
+        }),
+        OverflowMode::Wrapping => Ok(a.wrapping_op(op, b)),
+        OverflowMode::Saturating => Ok(a.saturating_op(op, b)),
+    })
+}
+
+macro_rules! integer_op {
+    ($LEFT:expr, $RIGHT:expr, $OPERATOR:expr, $MODE:expr, $ZERO_ERROR:expr, $DT:ident) => {{
         let ll = $LEFT.as_any().downcast_ref::<$DT>().unwrap();
         let rr = $RIGHT.as_any().downcast_ref::<$DT>().unwrap();
-        Ok(Arc::new(compute::$OP(&ll, &rr)?))
+        Ok(Arc::new(integer_math_op(
+            &ll,
+            &rr,
+            &$OPERATOR,
+            $MODE,
+            $ZERO_ERROR,
+        )?))
     }};
 }
 
+/// Addition, subtraction and multiplication of floating point values use the compute
+/// kernels, while division and modulo check for zero divisors
+macro_rules! float_op {
+    ($LEFT:expr, $RIGHT:expr, $OPERATOR:expr, $ZERO_ERROR:expr, $DT:ident) => {{
+        let ll = $LEFT.as_any().downcast_ref::<$DT>().unwrap();
+        let rr = $RIGHT.as_any().downcast_ref::<$DT>().unwrap();
+        let result = match $OPERATOR {
+            Operator::Plus => compute::add(&ll, &rr)?,
+            Operator::Minus => compute::subtract(&ll, &rr)?,
+            Operator::Multiply => compute::multiply(&ll, &rr)?,
+            Operator::Divide => {
+                arithmetic_op(&ll, &rr, &$OPERATOR, $ZERO_ERROR, |a, b| Ok(a / b))?
+            }
+            _ => arithmetic_op(&ll, &rr, &$OPERATOR, $ZERO_ERROR, |a, b| Ok(a % b))?,
+        };
+        Ok(Arc::new(result))
+    }};
+}
+
+/// Integer operands are evaluated with `integer_math_op`, so that overflow is handled
+/// according to the mode of the context
 macro_rules! math_ops {
-    ($LEFT:expr, $RIGHT:expr, $BATCH:expr, $OP:ident) => {{
+    ($LEFT:expr, $RIGHT:expr, $BATCH:expr, $OPERATOR:expr, $MODE:expr, $ZERO_ERROR:expr) => {{
         let left_values = $LEFT.get_func()($BATCH)?;
         let right_values = $RIGHT.get_func()($BATCH)?;
         match (left_values.data_type(), right_values.data_type()) {
-            (DataType::Int8, DataType::Int8) => {
-                binary_op!(left_values, right_values, $OP, Int8Array)
-            }
-            (DataType::Int16, DataType::Int16) => {
-                binary_op!(left_values, right_values, $OP, Int16Array)
-            }
-            (DataType::Int32, DataType::Int32) => {
-                binary_op!(left_values, right_values, $OP, Int32Array)
-            }
-            (DataType::Int64, DataType::Int64) => {
-                binary_op!(left_values, right_values, $OP, Int64Array)
-            }
-            (DataType::UInt8, DataType::UInt8) => {
-                binary_op!(left_values, right_values, $OP, UInt8Array)
-            }
-            (DataType::UInt16, DataType::UInt16) => {
-                binary_op!(left_values, right_values, $OP, UInt16Array)
-            }
-            (DataType::UInt32, DataType::UInt32) => {
-                binary_op!(left_values, right_values, $OP, UInt32Array)
-            }
-            (DataType::UInt64, DataType::UInt64) => {
-                binary_op!(left_values, right_values, $OP, UInt64Array)
-            }
+            (DataType::Int8, DataType::Int8) => integer_op!(
+                left_values,
+                right_values,
+                $OPERATOR,
+                $MODE,
+                $ZERO_ERROR,
+                Int8Array
+            ),
+            (DataType::Int16, DataType::Int16) => integer_op!(
+                left_values,
+                right_values,
+                $OPERATOR,
+                $MODE,
+                $ZERO_ERROR,
+                Int16Array
+            ),
+            (DataType::Int32, DataType::Int32) => integer_op!(
+                left_values,
+                right_values,
+                $OPERATOR,
+                $MODE,
+                $ZERO_ERROR,
+                Int32Array
+            ),
+            (DataType::Int64, DataType::Int64) => integer_op!(
+                left_values,
+                right_values,
+                $OPERATOR,
+                $MODE,
+                $ZERO_ERROR,
+                Int64Array
+            ),
+            (DataType::UInt8, DataType::UInt8) => integer_op!(
+                left_values,
+                right_values,
+                $OPERATOR,
+                $MODE,
+                $ZERO_ERROR,
+                UInt8Array
+            ),
+            (DataType::UInt16, DataType::UInt16) => integer_op!(
+                left_values,
+                right_values,
+                $OPERATOR,
+                $MODE,
+                $ZERO_ERROR,
+                UInt16Array
+            ),
+            (DataType::UInt32, DataType::UInt32) => integer_op!(
+                left_values,
+                right_values,
+                $OPERATOR,
+                $MODE,
+                $ZERO_ERROR,
+                UInt32Array
+            ),
+            (DataType::UInt64, DataType::UInt64) => integer_op!(
+                left_values,
+                right_values,
+                $OPERATOR,
+                $MODE,
+                $ZERO_ERROR,
+                UInt64Array
+            ),
             (DataType::Float32, DataType::Float32) => {
-                binary_op!(left_values, right_values, $OP, Float32Array)
+                float_op!(
+                    left_values,
+                    right_values,
+                    $OPERATOR,
+                    $ZERO_ERROR,
+                    Float32Array
+                )
             }
             (DataType::Float64, DataType::Float64) => {
-                binary_op!(left_values, right_values, $OP, Float64Array)
+                float_op!(
+                    left_values,
+                    right_values,
+                    $OPERATOR,
+                    $ZERO_ERROR,
+                    Float64Array
+                )
             }
             _ => Err(ExecutionError::ExecutionError(format!("math_ops"))),
         }
     }};
 }
 
+macro_rules! compare_op {
+    ($LEFT:expr, $RIGHT:expr, $OP:expr, $DT:ident) => {{
+        let ll = $LEFT.as_any().downcast_ref::<$DT>().unwrap();
+        let rr = $RIGHT.as_any().downcast_ref::<$DT>().unwrap();
+        Ok(Arc::new(compute::compare_op(&ll, &rr, |a, b| {
+            ($OP)(a.cmp(&b))
+        })?))
+    }};
+}
+
+macro_rules! compare_scalar_op {
+    ($ARRAY:expr, $SCALAR:expr, $OP:expr, $DT:ident) => {{
+        let array = $ARRAY.as_any().downcast_ref::<$DT>().unwrap();
+        let scalar = *$SCALAR;
+        Ok(Arc::new(compute::compare_op_scalar(
+            &array,
+            scalar,
+            |a, b| ($OP)(a.cmp(&b)),
+        )?))
+    }};
+}
+
+macro_rules! compare_float_op {
+    ($LEFT:expr, $RIGHT:expr, $OP:expr, $NAN_ORDERING:expr, $DT:ident) => {{
+        let ll = $LEFT.as_any().downcast_ref::<$DT>().unwrap();
+        let rr = $RIGHT.as_any().downcast_ref::<$DT>().unwrap();
+        Ok(Arc::new(compute::compare_op(&ll, &rr, |a, b| {
+            ($OP)(a.compare(b, $NAN_ORDERING))
+        })?))
+    }};
+}
+
+macro_rules! compare_float_scalar_op {
+    ($ARRAY:expr, $SCALAR:expr, $OP:expr, $NAN_ORDERING:expr, $DT:ident) => {{
+        let array = $ARRAY.as_any().downcast_ref::<$DT>().unwrap();
+        let scalar = *$SCALAR;
+        Ok(Arc::new(compute::compare_op_scalar(
+            &array,
+            scalar,
+            |a, b| ($OP)(a.compare(b, $NAN_ORDERING)),
+        )?))
+    }};
+}
+
+/// Ordering of floating point values that is total, unlike `PartialOrd`, so that sorts
+/// and comparisons of values that include NaN are deterministic
+pub trait FloatOrdering: Copy {
+    /// Compare two values, placing NaN values according to the given ordering
+    fn compare(self, other: Self, nan_ordering: NanOrdering) -> Ordering;
+}
+
+macro_rules! float_ordering {
+    ($T:ty, $SIGNED:ty, $UNSIGNED:ty, $SIGN_BIT:expr) => {
+        impl FloatOrdering for $T {
+            fn compare(self, other: Self, nan_ordering: NanOrdering) -> Ordering {
+                match nan_ordering {
+                    NanOrdering::NanLast => match (self.is_nan(), other.is_nan()) {
+                        (true, true) => Ordering::Equal,
+                        (true, false) => Ordering::Greater,
+                        (false, true) => Ordering::Less,
+                        (false, false) => self.partial_cmp(&other).unwrap(),
+                    },
+                    NanOrdering::TotalOrder => {
+                        // flip the bits other than the sign of negative values, so that
+                        // the bits compare as signed integers in the totalOrder order
+                        let key = |value: $T| {
+                            let bits = value.to_bits() as $SIGNED;
+                            bits ^ (((bits >> $SIGN_BIT) as $UNSIGNED >> 1) as $SIGNED)
+                        };
+                        key(self).cmp(&key(other))
+                    }
+                }
+            }
+        }
+    };
+}
+
+float_ordering!(f32, i32, u32, 31);
+float_ordering!(f64, i64, u64, 63);
+
+/// SQL `AND` of two values, which is false if either value is false even when the other
+/// is null
+fn and_values(left: Option<bool>, right: Option<bool>) -> Option<bool> {
+    match (left, right) {
+        (Some(false), _) | (_, Some(false)) => Some(false),
+        (Some(true), Some(true)) => Some(true),
+        _ => None,
+    }
+}
+
+/// SQL `OR` of two values, which is true if either value is true even when the other
+/// is null
+fn or_values(left: Option<bool>, right: Option<bool>) -> Option<bool> {
+    match (left, right) {
+        (Some(true), _) | (_, Some(true)) => Some(true),
+        (Some(false), Some(false)) => Some(false),
+        _ => None,
+    }
+}
+
+fn boolean_value(array: &BooleanArray, i: usize) -> Option<bool> {
+    if array.is_null(i) {
+        None
+    } else {
+        Some(array.value(i))
+    }
+}
+
+fn as_boolean_array(array: &ArrayRef) -> Result<&BooleanArray> {
+    array
+        .as_any()
+        .downcast_ref::<BooleanArray>()
+        .ok_or_else(|| {
+            ExecutionError::ExecutionError(
+                "AND and OR can only be applied to boolean expressions".to_string(),
+            )
+        })
+}
+
+/// Evaluate `left AND right` (or `left OR right` if `is_and` is false), evaluating the
+/// right side only on the rows whose result is not already determined by the left
+/// side. This avoids wasted work when the left side is selective, and errors such as
+/// division by zero on rows that the left side excludes.
+fn evaluate_short_circuit(
+    left: &RuntimeExpr,
+    right: &RuntimeExpr,
+    batch: &RecordBatch,
+    is_and: bool,
+) -> Result<ArrayRef> {
+    let left_values = left.get_func()(batch)?;
+    let left_bools = as_boolean_array(&left_values)?;
+    let combine = if is_and { and_values } else { or_values };
+    let kernel = if is_and {
+        compute::and_kleene
+    } else {
+        compute::or_kleene
+    };
+
+    // a false value determines the result of AND and a true value the result of OR
+    let undetermined: Vec<bool> = (0..left_bools.len())
+        .map(|i| left_bools.is_null(i) || left_bools.value(i) == is_and)
+        .collect();
+    let count = undetermined.iter().filter(|u| **u).count();
+    if count == 0 {
+        return Ok(left_values.clone());
+    }
+
+    let evaluate_all = || -> Result<ArrayRef> {
+        let right_values = right.get_func()(batch)?;
+        let right_bools = as_boolean_array(&right_values)?;
+        Ok(Arc::new(kernel(left_bools, right_bools)?))
+    };
+    if count == left_bools.len() {
+        return evaluate_all();
+    }
+
+    let mask = BooleanArray::from(undetermined.clone());
+    let masked_columns: Result<Vec<ArrayRef>> = (0..batch.num_columns())
+        .map(|i| filter(batch.column(i), &mask))
+        .collect();
+    let masked_columns = match masked_columns {
+        Ok(columns) => columns,
+        // the batch has columns of a type that cannot be filtered
+        Err(_) => return evaluate_all(),
+    };
+    let masked_batch = RecordBatch::new(batch.schema().clone(), masked_columns);
+    let right_values = right.get_func()(&masked_batch)?;
+    let right_bools = as_boolean_array(&right_values)?;
+
+    let mut builder = BooleanArray::builder(left_bools.len());
+    let mut j = 0;
+    for (i, undetermined) in undetermined.iter().enumerate() {
+        let value = if *undetermined {
+            j += 1;
+            combine(
+                boolean_value(left_bools, i),
+                boolean_value(right_bools, j - 1),
+            )
+        } else {
+            boolean_value(left_bools, i)
+        };
+        match value {
+            Some(value) => builder.append_value(value)?,
+            None => builder.append_null()?,
+        }
+    }
+    Ok(Arc::new(builder.finish()))
+}
+
+/// Determine whether the ordering of two values satisfies a comparison operator
+fn matches_ordering(op: &Operator, ordering: Ordering) -> bool {
+    match op {
+        Operator::Eq => ordering == Ordering::Equal,
+        Operator::NotEq => ordering != Ordering::Equal,
+        Operator::Lt => ordering == Ordering::Less,
+        Operator::LtEq => ordering != Ordering::Greater,
+        Operator::Gt => ordering == Ordering::Greater,
+        Operator::GtEq => ordering != Ordering::Less,
+        _ => unreachable!("{:?} is not a comparison operator", op),
+    }
+}
+
+/// Compare two UTF-8 strings according to a collation. Case-insensitive comparisons
+/// fold the case of each character as the strings are compared, rather than creating
+/// lowercase copies of the strings.
+pub fn compare_strings(left: &[u8], right: &[u8], collation: Collation) -> Ordering {
+    match collation {
+        // the byte order of UTF-8 strings is the order of their code points
+        Collation::Binary => left.cmp(right),
+        Collation::CaseInsensitive if left.is_ascii() && right.is_ascii() => left
+            .iter()
+            .map(u8::to_ascii_lowercase)
+            .cmp(right.iter().map(u8::to_ascii_lowercase)),
+        Collation::CaseInsensitive => {
+            let left = String::from_utf8_lossy(left);
+            let right = String::from_utf8_lossy(right);
+            left.chars()
+                .flat_map(char::to_lowercase)
+                .cmp(right.chars().flat_map(char::to_lowercase))
+        }
+    }
+}
+
+/// Compare the values of two string arrays according to a collation. The result is
+/// null where either value is null.
+pub fn compare_strings_op<F>(
+    left: &BinaryArray,
+    right: &BinaryArray,
+    collation: Collation,
+    op: F,
+) -> Result<BooleanArray>
+where
+    F: Fn(Ordering) -> bool,
+{
+    if left.len() != right.len() {
+        return Err(ExecutionError::ExecutionError(
+            "Cannot compare arrays of different length".to_string(),
+        ));
+    }
+    let mut builder = BooleanArray::builder(left.len());
+    for i in 0..left.len() {
+        if left.is_null(i) || right.is_null(i) {
+            builder.append_null()?;
+        } else {
+            let ordering = compare_strings(left.value(i), right.value(i), collation);
+            builder.append_value(op(ordering))?;
+        }
+    }
+    Ok(builder.finish())
+}
+
 macro_rules! comparison_ops {
-    ($LEFT:expr, $RIGHT:expr, $BATCH:expr, $OP:ident) => {{
+    ($LEFT:expr, $RIGHT:expr, $BATCH:expr, $NAN_ORDERING:expr, $COLLATION:expr, $OP:expr) => {{
         let left_values = $LEFT.get_func()($BATCH)?;
         let right_values = $RIGHT.get_func()($BATCH)?;
         match (left_values.data_type(), right_values.data_type()) {
             (DataType::Int8, DataType::Int8) => {
-                binary_op!(left_values, right_values, $OP, Int8Array)
+                compare_op!(left_values, right_values, $OP, Int8Array)
             }
             (DataType::Int16, DataType::Int16) => {
-                binary_op!(left_values, right_values, $OP, Int16Array)
+                compare_op!(left_values, right_values, $OP, Int16Array)
             }
             (DataType::Int32, DataType::Int32) => {
-                binary_op!(left_values, right_values, $OP, Int32Array)
+                compare_op!(left_values, right_values, $OP, Int32Array)
             }
             (DataType::Int64, DataType::Int64) => {
-                binary_op!(left_values, right_values, $OP, Int64Array)
+                compare_op!(left_values, right_values, $OP, Int64Array)
             }
             (DataType::UInt8, DataType::UInt8) => {
-                binary_op!(left_values, right_values, $OP, UInt8Array)
+                compare_op!(left_values, right_values, $OP, UInt8Array)
             }
             (DataType::UInt16, DataType::UInt16) => {
-                binary_op!(left_values, right_values, $OP, UInt16Array)
+                compare_op!(left_values, right_values, $OP, UInt16Array)
             }
             (DataType::UInt32, DataType::UInt32) => {
-                binary_op!(left_values, right_values, $OP, UInt32Array)
+                compare_op!(left_values, right_values, $OP, UInt32Array)
             }
             (DataType::UInt64, DataType::UInt64) => {
-                binary_op!(left_values, right_values, $OP, UInt64Array)
+                compare_op!(left_values, right_values, $OP, UInt64Array)
             }
             (DataType::Float32, DataType::Float32) => {
-                binary_op!(left_values, right_values, $OP, Float32Array)
+                compare_float_op!(
+                    left_values,
+                    right_values,
+                    $OP,
+                    $NAN_ORDERING,
+                    Float32Array
+                )
             }
             (DataType::Float64, DataType::Float64) => {
-                binary_op!(left_values, right_values, $OP, Float64Array)
+                compare_float_op!(
+                    left_values,
+                    right_values,
+                    $OP,
+                    $NAN_ORDERING,
+                    Float64Array
+                )
+            }
+            (DataType::Date(DateUnit::Day), DataType::Date(DateUnit::Day)) => {
+                compare_op!(left_values, right_values, $OP, Date32Array)
             }
-            //TODO other types
-            _ => Err(ExecutionError::ExecutionError(format!("comparison_ops"))),
+            (
+                DataType::Date(DateUnit::Millisecond),
+                DataType::Date(DateUnit::Millisecond),
+            ) => compare_op!(left_values, right_values, $OP, Date64Array),
+            (DataType::Time32(TimeUnit::Second), DataType::Time32(TimeUnit::Second)) => {
+                compare_op!(left_values, right_values, $OP, Time32SecondArray)
+            }
+            (
+                DataType::Time32(TimeUnit::Millisecond),
+                DataType::Time32(TimeUnit::Millisecond),
+            ) => compare_op!(left_values, right_values, $OP, Time32MillisecondArray),
+            (
+                DataType::Time64(TimeUnit::Microsecond),
+                DataType::Time64(TimeUnit::Microsecond),
+            ) => compare_op!(left_values, right_values, $OP, Time64MicrosecondArray),
+            (
+                DataType::Time64(TimeUnit::Nanosecond),
+                DataType::Time64(TimeUnit::Nanosecond),
+            ) => compare_op!(left_values, right_values, $OP, Time64NanosecondArray),
+            // timestamps are stored in UTC, so values with different timezones can be
+            // compared directly
+            (DataType::Timestamp(left_unit, _), DataType::Timestamp(right_unit, _))
+                if left_unit == right_unit =>
+            {
+                match left_unit {
+                    TimeUnit::Second => {
+                        compare_op!(left_values, right_values, $OP, TimestampSecondArray)
+                    }
+                    TimeUnit::Millisecond => compare_op!(
+                        left_values,
+                        right_values,
+                        $OP,
+                        TimestampMillisecondArray
+                    ),
+                    TimeUnit::Microsecond => compare_op!(
+                        left_values,
+                        right_values,
+                        $OP,
+                        TimestampMicrosecondArray
+                    ),
+                    TimeUnit::Nanosecond => compare_op!(
+                        left_values,
+                        right_values,
+                        $OP,
+                        TimestampNanosecondArray
+                    ),
+                }
+            }
+            (DataType::Utf8, DataType::Utf8) => {
+                let ll = left_values.as_any().downcast_ref::<BinaryArray>().unwrap();
+                let rr = right_values.as_any().downcast_ref::<BinaryArray>().unwrap();
+                Ok(Arc::new(compare_strings_op(&ll, &rr, $COLLATION, $OP)?))
+            }
+            // binary values are compared byte by byte, regardless of the collation
+            (DataType::Binary, DataType::Binary) => {
+                let ll = left_values.as_any().downcast_ref::<BinaryArray>().unwrap();
+                let rr = right_values.as_any().downcast_ref::<BinaryArray>().unwrap();
+                let collation = Collation::Binary;
+                Ok(Arc::new(compare_strings_op(&ll, &rr, collation, $OP)?))
+            }
+            (left_type, right_type) => Err(ExecutionError::ExecutionError(format!(
+                "Cannot compare values of type {:?} and {:?}",
+                left_type, right_type
+            ))),
         }
     }};
 }
 
-macro_rules! boolean_ops {
-    ($LEFT:expr, $RIGHT:expr, $BATCH:expr, $OP:ident) => {{
-        let left_values = $LEFT.get_func()($BATCH)?;
-        let right_values = $RIGHT.get_func()($BATCH)?;
-        Ok(Arc::new(compute::$OP(
-            left_values.as_any().downcast_ref::<BooleanArray>().unwrap(),
-            right_values
-                .as_any()
-                .downcast_ref::<BooleanArray>()
-                .unwrap(),
-        )?))
-    }};
+/// Determine whether values of the given type are nested, i.e. lists, structs or unions
+fn is_nested(data_type: &DataType) -> bool {
+    match data_type {
+        DataType::List(_)
+        | DataType::FixedSizeList(_, _)
+        | DataType::Struct(_)
+        | DataType::Union(_, _) => true,
+        _ => false,
+    }
+}
+
+/// Compile a comparison of values of a nested type, which are compared deeply. Nested
+/// values can be compared for equality but have no order.
+fn compile_nested_comparison(
+    name: String,
+    op: Operator,
+    left: RuntimeExpr,
+    right: RuntimeExpr,
+) -> Result<RuntimeExpr> {
+    let equal = match op {
+        Operator::Eq => true,
+        Operator::NotEq => false,
+        _ => {
+            return Err(ExecutionError::ExecutionError(format!(
+                "Values of type {:?} cannot be compared with {:?}",
+                left.get_type(),
+                op
+            )));
+        }
+    };
+    Ok(RuntimeExpr::Compiled {
+        name,
+        f: Arc::new(move |batch: &RecordBatch| {
+            let left_values = left.get_func()(batch)?;
+            let right_values = right.get_func()(batch)?;
+            let result = if equal {
+                compute::eq_any(&*left_values, &*right_values)?
+            } else {
+                compute::neq_any(&*left_values, &*right_values)?
+            };
+            Ok(Arc::new(result) as ArrayRef)
+        }),
+        t: DataType::Boolean,
+    })
+}
+
+/// Determine whether a literal can be compared with the values of an array of the given
+/// type by `compare_scalar`, without first creating an array of the literal value
+fn is_comparable_scalar(value: &ScalarValue, data_type: &DataType) -> bool {
+    match value.get_datatype() {
+        DataType::Int8
+        | DataType::Int16
+        | DataType::Int32
+        | DataType::Int64
+        | DataType::UInt8
+        | DataType::UInt16
+        | DataType::UInt32
+        | DataType::UInt64
+        | DataType::Float32
+        | DataType::Float64 => &value.get_datatype() == data_type,
+        _ => false,
+    }
+}
+
+/// Compare the values of an array with a literal value of the same type. The result
+/// is null where the array value is null.
+pub fn compare_scalar<F>(
+    array: &ArrayRef,
+    scalar: &ScalarValue,
+    nan_ordering: NanOrdering,
+    op: F,
+) -> Result<ArrayRef>
+where
+    F: Fn(Ordering) -> bool,
+{
+    match (array.data_type(), scalar) {
+        (DataType::Int8, ScalarValue::Int8(v)) => {
+            compare_scalar_op!(array, v, op, Int8Array)
+        }
+        (DataType::Int16, ScalarValue::Int16(v)) => {
+            compare_scalar_op!(array, v, op, Int16Array)
+        }
+        (DataType::Int32, ScalarValue::Int32(v)) => {
+            compare_scalar_op!(array, v, op, Int32Array)
+        }
+        (DataType::Int64, ScalarValue::Int64(v)) => {
+            compare_scalar_op!(array, v, op, Int64Array)
+        }
+        (DataType::UInt8, ScalarValue::UInt8(v)) => {
+            compare_scalar_op!(array, v, op, UInt8Array)
+        }
+        (DataType::UInt16, ScalarValue::UInt16(v)) => {
+            compare_scalar_op!(array, v, op, UInt16Array)
+        }
+        (DataType::UInt32, ScalarValue::UInt32(v)) => {
+            compare_scalar_op!(array, v, op, UInt32Array)
+        }
+        (DataType::UInt64, ScalarValue::UInt64(v)) => {
+            compare_scalar_op!(array, v, op, UInt64Array)
+        }
+        (DataType::Float32, ScalarValue::Float32(v)) => {
+            compare_float_scalar_op!(array, v, op, nan_ordering, Float32Array)
+        }
+        (DataType::Float64, ScalarValue::Float64(v)) => {
+            compare_float_scalar_op!(array, v, op, nan_ordering, Float64Array)
+        }
+        _ => Err(ExecutionError::ExecutionError(format!(
+            "Cannot compare {:?} values with {:?}",
+            array.data_type(),
+            scalar
+        ))),
+    }
 }
 
 macro_rules! literal_array {
@@ -231,7 +870,7 @@ macro_rules! literal_array {
         let nn = *$VALUE;
         Ok(RuntimeExpr::Compiled {
             name: format!("{}", nn),
-            f: Rc::new(move |batch: &RecordBatch| {
+            f: Arc::new(move |batch: &RecordBatch| {
                 let capacity = batch.num_rows();
                 let mut builder = $ARRAY_TYPE::builder(capacity);
                 for _ in 0..capacity {
@@ -245,56 +884,12 @@ macro_rules! literal_array {
     }};
 }
 
-/// Casts a column to an array with a different data type
-macro_rules! cast_column {
-    ($INDEX:expr, $FROM_TYPE:ty, $TO_TYPE:ident, $DT:ty) => {{
-        Rc::new(move |batch: &RecordBatch| {
-            // get data and cast to known type
-            match batch.column($INDEX).as_any().downcast_ref::<$FROM_TYPE>() {
-                Some(array) => {
-                    // create builder for desired type
-                    let mut builder = $TO_TYPE::builder(batch.num_rows());
-                    for i in 0..batch.num_rows() {
-                        if array.is_null(i) {
-                            builder.append_null()?;
-                        } else {
-                            builder.append_value(array.value(i) as $DT)?;
-                        }
-                    }
-                    Ok(Arc::new(builder.finish()) as ArrayRef)
-                }
-                None => Err(ExecutionError::InternalError(format!(
-                    "Column at index {} is not of expected type",
-                    $INDEX
-                ))),
-            }
-        })
-    }};
-}
-
-macro_rules! cast_column_outer {
-    ($INDEX:expr, $FROM_TYPE:ty, $TO_TYPE:expr) => {{
-        match $TO_TYPE {
-            DataType::UInt8 => cast_column!($INDEX, $FROM_TYPE, UInt8Array, u8),
-            DataType::UInt16 => cast_column!($INDEX, $FROM_TYPE, UInt16Array, u16),
-            DataType::UInt32 => cast_column!($INDEX, $FROM_TYPE, UInt32Array, u32),
-            DataType::UInt64 => cast_column!($INDEX, $FROM_TYPE, UInt64Array, u64),
-            DataType::Int8 => cast_column!($INDEX, $FROM_TYPE, Int8Array, i8),
-            DataType::Int16 => cast_column!($INDEX, $FROM_TYPE, Int16Array, i16),
-            DataType::Int32 => cast_column!($INDEX, $FROM_TYPE, Int32Array, i32),
-            DataType::Int64 => cast_column!($INDEX, $FROM_TYPE, Int64Array, i64),
-            DataType::Float32 => cast_column!($INDEX, $FROM_TYPE, Float32Array, f32),
-            DataType::Float64 => cast_column!($INDEX, $FROM_TYPE, Float64Array, f64),
-            _ => unimplemented!(),
-        }
-    }};
-}
-
 /// Compiles a scalar expression into a closure
 pub fn compile_scalar_expr(
     ctx: &ExecutionContext,
     expr: &Expr,
     input_schema: &Schema,
+    config: &ExecutionConfig,
 ) -> Result<RuntimeExpr> {
     match expr {
         &Expr::Literal(ref value) => match value {
@@ -315,6 +910,42 @@ pub fn compile_scalar_expr(
             ScalarValue::UInt64(n) => literal_array!(n, UInt64Array, UInt64),
             ScalarValue::Float32(n) => literal_array!(n, Float32Array, Float32),
             ScalarValue::Float64(n) => literal_array!(n, Float64Array, Float64),
+            ScalarValue::Boolean(b) => literal_array!(b, BooleanArray, Boolean),
+            ScalarValue::Null => Ok(RuntimeExpr::Compiled {
+                name: "NULL".to_string(),
+                f: Arc::new(|batch: &RecordBatch| {
+                    Ok(Arc::new(NullArray::new(batch.num_rows())) as ArrayRef)
+                }),
+                t: DataType::Null,
+            }),
+            ScalarValue::Utf8(s) => {
+                let s = s.clone();
+                Ok(RuntimeExpr::Compiled {
+                    name: format!("'{}'", s),
+                    f: Arc::new(move |batch: &RecordBatch| {
+                        let mut builder = BinaryBuilder::new(batch.num_rows());
+                        for _ in 0..batch.num_rows() {
+                            builder.append_string(&s)?;
+                        }
+                        Ok(Arc::new(builder.finish()) as ArrayRef)
+                    }),
+                    t: DataType::Utf8,
+                })
+            }
+            ScalarValue::Binary(b) => {
+                let b = b.clone();
+                Ok(RuntimeExpr::Compiled {
+                    name: format!("{:?}", value),
+                    f: Arc::new(move |batch: &RecordBatch| {
+                        let mut builder = BinaryBuilder::new_binary(batch.num_rows());
+                        for _ in 0..batch.num_rows() {
+                            builder.append_bytes(&b)?;
+                        }
+                        Ok(Arc::new(builder.finish()) as ArrayRef)
+                    }),
+                    t: DataType::Binary,
+                })
+            }
             other => Err(ExecutionError::ExecutionError(format!(
                 "No support for literal type {:?}",
                 other
@@ -322,193 +953,257 @@ pub fn compile_scalar_expr(
         },
         &Expr::Column(index) => Ok(RuntimeExpr::Compiled {
             name: input_schema.field(index).name().clone(),
-            f: Rc::new(move |batch: &RecordBatch| Ok((*batch.column(index)).clone())),
+            f: Arc::new(move |batch: &RecordBatch| Ok((*batch.column(index)).clone())),
             t: input_schema.field(index).data_type().clone(),
         }),
+        &Expr::Alias(ref expr, ref alias) => {
+            let compiled_expr = compile_scalar_expr(ctx, expr, input_schema, config)?;
+            Ok(RuntimeExpr::Compiled {
+                name: alias.clone(),
+                f: compiled_expr.get_func(),
+                t: compiled_expr.get_type(),
+            })
+        }
+        &Expr::GetField { ref expr, ref name } => {
+            let compiled_expr = compile_scalar_expr(ctx, expr, input_schema, config)?;
+            let field = match compiled_expr.get_type() {
+                DataType::Struct(fields) => fields
+                    .iter()
+                    .enumerate()
+                    .find(|(_, f)| f.name() == name)
+                    .map(|(i, f)| (i, f.data_type().clone())),
+                _ => None,
+            };
+            let (index, t) = match field {
+                Some(field) => field,
+                None => {
+                    return Err(ExecutionError::ExecutionError(format!(
+                        "Invalid field '{}' of type {:?}",
+                        name,
+                        compiled_expr.get_type()
+                    )));
+                }
+            };
+            let f = compiled_expr.get_func();
+            Ok(RuntimeExpr::Compiled {
+                name: name.clone(),
+                // the child array is shared with the struct array rather than copied
+                f: Arc::new(move |batch: &RecordBatch| {
+                    let value = f(batch)?;
+                    match value.as_any().downcast_ref::<StructArray>() {
+                        Some(array) => Ok(array.column(index).clone()),
+                        None => Err(ExecutionError::ExecutionError(
+                            "Field access on a value that is not a struct".to_string(),
+                        )),
+                    }
+                }),
+                t,
+            })
+        }
         &Expr::Cast {
             ref expr,
             ref data_type,
-        } => match expr.as_ref() {
-            &Expr::Column(index) => {
-                let col = input_schema.field(index);
-                Ok(RuntimeExpr::Compiled {
-                    name: col.name().clone(),
-                    t: col.data_type().clone(),
-                    f: match col.data_type() {
-                        DataType::Int8 => {
-                            cast_column_outer!(index, Int8Array, &data_type)
-                        }
-                        DataType::Int16 => {
-                            cast_column_outer!(index, Int16Array, &data_type)
-                        }
-                        DataType::Int32 => {
-                            cast_column_outer!(index, Int32Array, &data_type)
-                        }
-                        DataType::Int64 => {
-                            cast_column_outer!(index, Int64Array, &data_type)
-                        }
-                        DataType::UInt8 => {
-                            cast_column_outer!(index, UInt8Array, &data_type)
-                        }
-                        DataType::UInt16 => {
-                            cast_column_outer!(index, UInt16Array, &data_type)
-                        }
-                        DataType::UInt32 => {
-                            cast_column_outer!(index, UInt32Array, &data_type)
-                        }
-                        DataType::UInt64 => {
-                            cast_column_outer!(index, UInt64Array, &data_type)
-                        }
-                        DataType::Float32 => {
-                            cast_column_outer!(index, Float32Array, &data_type)
-                        }
-                        DataType::Float64 => {
-                            cast_column_outer!(index, Float64Array, &data_type)
-                        }
-                        _ => panic!("unsupported CAST operation"), /*TODO */
-                                                                   /*Err(ExecutionError::NotImplemented(format!(
-                                                                       "CAST column from {:?} to {:?}",
-                                                                       col.data_type(),
-                                                                       data_type
-                                                                   )))*/
-                    },
-                })
-            }
-            &Expr::Literal(ref value) => {
-                //NOTE this is all very inefficient and needs to be optimized - tracking
-                // issue is https://github.com/andygrove/datafusion/issues/191
-                match value {
-                    ScalarValue::Int64(n) => {
-                        let nn = *n;
-                        match data_type {
-                            DataType::Float64 => Ok(RuntimeExpr::Compiled {
-                                name: "lit".to_string(),
-                                f: Rc::new(move |batch: &RecordBatch| {
-                                    let mut b = Float64Array::builder(batch.num_rows());
-                                    for _ in 0..batch.num_rows() {
-                                        b.append_value(nn as f64)?;
-                                    }
-                                    Ok(Arc::new(b.finish()) as ArrayRef)
-                                }),
-                                t: data_type.clone(),
-                            }),
-                            other => Err(ExecutionError::NotImplemented(format!(
-                                "CAST from Int64 to {:?}",
-                                other
-                            ))),
-                        }
+        } => {
+            let input_expr = compile_scalar_expr(ctx, expr, input_schema, config)?;
+            let to_type = data_type.clone();
+            Ok(RuntimeExpr::Compiled {
+                name: input_expr.get_name().clone(),
+                f: Arc::new(move |batch: &RecordBatch| {
+                    let values = input_expr.get_func()(batch)?;
+                    Ok(compute::cast(&values, &to_type)?)
+                }),
+                t: data_type.clone(),
+            })
+        }
+        &Expr::Not(ref expr) => {
+            let input_expr = compile_scalar_expr(ctx, expr, input_schema, config)?;
+            Ok(RuntimeExpr::Compiled {
+                name: format!("NOT {:?}", expr),
+                f: Arc::new(move |batch: &RecordBatch| {
+                    let input_values = input_expr.get_func()(batch)?;
+                    match input_values.as_any().downcast_ref::<BooleanArray>() {
+                        Some(array) => Ok(Arc::new(compute::not(array)?) as ArrayRef),
+                        None => Err(ExecutionError::ExecutionError(
+                            "NOT can only be applied to a boolean expression".to_string(),
+                        )),
                     }
-                    other => Err(ExecutionError::NotImplemented(format!(
-                        "CAST from {:?} to {:?}",
-                        other, data_type
-                    ))),
-                }
-            }
-            other => Err(ExecutionError::General(format!(
-                "CAST not implemented for expression {:?}",
-                other
-            ))),
-        },
+                }),
+                t: DataType::Boolean,
+            })
+        }
         &Expr::BinaryExpr {
             ref left,
             ref op,
             ref right,
         } => {
-            let left_expr = compile_scalar_expr(ctx, left, input_schema)?;
-            let right_expr = compile_scalar_expr(ctx, right, input_schema)?;
+            let left_expr = compile_scalar_expr(ctx, left, input_schema, config)?;
+            let right_expr = compile_scalar_expr(ctx, right, input_schema, config)?;
             let name = format!("{:?} {:?} {:?}", left, op, right);
             let op_type = left_expr.get_type().clone();
+            let overflow_mode = config.overflow_mode();
+            let error_on_zero = config.error_on_divide_by_zero();
+            let nan_ordering = config.nan_ordering();
+            let collation = config.collation();
             match op {
-                &Operator::Eq => Ok(RuntimeExpr::Compiled {
-                    name,
-                    f: Rc::new(move |batch: &RecordBatch| {
-                        comparison_ops!(left_expr, right_expr, batch, eq)
-                    }),
-                    t: DataType::Boolean,
-                }),
-                &Operator::NotEq => Ok(RuntimeExpr::Compiled {
-                    name,
-                    f: Rc::new(move |batch: &RecordBatch| {
-                        comparison_ops!(left_expr, right_expr, batch, neq)
-                    }),
-                    t: DataType::Boolean,
-                }),
-                &Operator::Lt => Ok(RuntimeExpr::Compiled {
-                    name,
-                    f: Rc::new(move |batch: &RecordBatch| {
-                        comparison_ops!(left_expr, right_expr, batch, lt)
-                    }),
-                    t: DataType::Boolean,
-                }),
-                &Operator::LtEq => Ok(RuntimeExpr::Compiled {
-                    name,
-                    f: Rc::new(move |batch: &RecordBatch| {
-                        comparison_ops!(left_expr, right_expr, batch, lt_eq)
-                    }),
-                    t: DataType::Boolean,
-                }),
-                &Operator::Gt => Ok(RuntimeExpr::Compiled {
-                    name,
-                    f: Rc::new(move |batch: &RecordBatch| {
-                        comparison_ops!(left_expr, right_expr, batch, gt)
-                    }),
-                    t: DataType::Boolean,
-                }),
-                &Operator::GtEq => Ok(RuntimeExpr::Compiled {
-                    name,
-                    f: Rc::new(move |batch: &RecordBatch| {
-                        comparison_ops!(left_expr, right_expr, batch, gt_eq)
-                    }),
-                    t: DataType::Boolean,
-                }),
+                &Operator::Eq
+                | &Operator::NotEq
+                | &Operator::Lt
+                | &Operator::LtEq
+                | &Operator::Gt
+                | &Operator::GtEq => {
+                    let operator = op.clone();
+                    // comparisons with a literal use the scalar kernels, so that an
+                    // array of the literal value is not created for every batch
+                    let right_type = right_expr.get_type();
+                    match (left.as_ref(), right.as_ref()) {
+                        (_, Expr::Literal(value))
+                            if is_comparable_scalar(value, &op_type) =>
+                        {
+                            let value = value.clone();
+                            return Ok(RuntimeExpr::Compiled {
+                                name,
+                                f: Arc::new(move |batch: &RecordBatch| {
+                                    let values = left_expr.get_func()(batch)?;
+                                    compare_scalar(&values, &value, nan_ordering, |o| {
+                                        matches_ordering(&operator, o)
+                                    })
+                                }),
+                                t: DataType::Boolean,
+                            });
+                        }
+                        (Expr::Literal(value), _)
+                            if is_comparable_scalar(value, &right_type) =>
+                        {
+                            let value = value.clone();
+                            return Ok(RuntimeExpr::Compiled {
+                                name,
+                                f: Arc::new(move |batch: &RecordBatch| {
+                                    let values = right_expr.get_func()(batch)?;
+                                    // the literal is on the left of the operator
+                                    compare_scalar(&values, &value, nan_ordering, |o| {
+                                        matches_ordering(&operator, o.reverse())
+                                    })
+                                }),
+                                t: DataType::Boolean,
+                            });
+                        }
+                        _ => {}
+                    }
+                    if is_nested(&op_type) {
+                        return compile_nested_comparison(
+                            name, operator, left_expr, right_expr,
+                        );
+                    }
+                    Ok(RuntimeExpr::Compiled {
+                        name,
+                        f: Arc::new(move |batch: &RecordBatch| {
+                            comparison_ops!(
+                                left_expr,
+                                right_expr,
+                                batch,
+                                nan_ordering,
+                                collation,
+                                |o| matches_ordering(&operator, o)
+                            )
+                        }),
+                        t: DataType::Boolean,
+                    })
+                }
                 &Operator::And => Ok(RuntimeExpr::Compiled {
                     name,
-                    f: Rc::new(move |batch: &RecordBatch| {
-                        boolean_ops!(left_expr, right_expr, batch, and)
+                    f: Arc::new(move |batch: &RecordBatch| {
+                        evaluate_short_circuit(&left_expr, &right_expr, batch, true)
                     }),
                     t: DataType::Boolean,
                 }),
                 &Operator::Or => Ok(RuntimeExpr::Compiled {
                     name,
-                    f: Rc::new(move |batch: &RecordBatch| {
-                        boolean_ops!(left_expr, right_expr, batch, or)
+                    f: Arc::new(move |batch: &RecordBatch| {
+                        evaluate_short_circuit(&left_expr, &right_expr, batch, false)
                     }),
                     t: DataType::Boolean,
                 }),
-                &Operator::Plus => Ok(RuntimeExpr::Compiled {
-                    name,
-                    f: Rc::new(move |batch: &RecordBatch| {
-                        math_ops!(left_expr, right_expr, batch, add)
-                    }),
-                    t: op_type,
-                }),
-                &Operator::Minus => Ok(RuntimeExpr::Compiled {
-                    name,
-                    f: Rc::new(move |batch: &RecordBatch| {
-                        math_ops!(left_expr, right_expr, batch, subtract)
-                    }),
-                    t: op_type,
-                }),
-                &Operator::Multiply => Ok(RuntimeExpr::Compiled {
-                    name,
-                    f: Rc::new(move |batch: &RecordBatch| {
-                        math_ops!(left_expr, right_expr, batch, multiply)
-                    }),
-                    t: op_type,
-                }),
-                &Operator::Divide => Ok(RuntimeExpr::Compiled {
-                    name,
-                    f: Rc::new(move |batch: &RecordBatch| {
-                        math_ops!(left_expr, right_expr, batch, divide)
-                    }),
-                    t: op_type,
-                }),
+                &Operator::Plus
+                | &Operator::Minus
+                | &Operator::Multiply
+                | &Operator::Divide
+                | &Operator::Modulus => {
+                    let operator = op.clone();
+                    Ok(RuntimeExpr::Compiled {
+                        name,
+                        f: Arc::new(move |batch: &RecordBatch| {
+                            math_ops!(
+                                left_expr,
+                                right_expr,
+                                batch,
+                                operator,
+                                overflow_mode,
+                                error_on_zero
+                            )
+                        }),
+                        t: op_type,
+                    })
+                }
                 other => Err(ExecutionError::ExecutionError(format!(
                     "operator: {:?}",
                     other
                 ))),
             }
         }
+        &Expr::ScalarFunction {
+            ref name,
+            ref args,
+            ref return_type,
+        } => {
+            let compiled_args = args
+                .iter()
+                .map(|e| compile_scalar_expr(ctx, e, input_schema, config))
+                .collect::<Result<Vec<RuntimeExpr>>>()?;
+            if let Some(function) = list_function(name) {
+                return Ok(RuntimeExpr::Compiled {
+                    name: name.clone(),
+                    f: Arc::new(move |batch: &RecordBatch| {
+                        let arg_values = compiled_args
+                            .iter()
+                            .map(|e| e.get_func()(batch))
+                            .collect::<Result<Vec<ArrayRef>>>()?;
+                        function.invoke(&arg_values)
+                    }),
+                    t: return_type.clone(),
+                });
+            }
+
+            let udf = match ctx.get_udf(name) {
+                Some(udf) => udf,
+                None => {
+                    return Err(ExecutionError::General(format!(
+                        "Invalid function '{}'",
+                        name
+                    )));
+                }
+            };
+            let function_name = name.clone();
+            Ok(RuntimeExpr::Compiled {
+                name: name.clone(),
+                f: Arc::new(move |batch: &RecordBatch| {
+                    let arg_values = compiled_args
+                        .iter()
+                        .map(|e| e.get_func()(batch))
+                        .collect::<Result<Vec<ArrayRef>>>()?;
+                    let result = udf.invoke(&arg_values)?;
+                    if result.len() != batch.num_rows() {
+                        return Err(ExecutionError::ExecutionError(format!(
+                            "Function '{}' returned {} values for a batch of {} rows",
+                            function_name,
+                            result.len(),
+                            batch.num_rows()
+                        )));
+                    }
+                    Ok(result)
+                }),
+                t: return_type.clone(),
+            })
+        }
         other => Err(ExecutionError::ExecutionError(format!(
             "expression {:?}",
             other
@@ -22,17 +22,68 @@ use std::rc::Rc;
 use std::sync::Arc;
 
 use arrow::array::*;
-use arrow::datatypes::{DataType, Schema};
+use arrow::compute;
+use arrow::datatypes::Schema;
 use arrow::record_batch::RecordBatch;
 
 use super::error::{ExecutionError, Result};
 use super::expression::RuntimeExpr;
+use super::physicalplan::ExecutionPlan;
 use super::relation::Relation;
+use super::scheduler::{BatchFunction, BatchPipeline, Scheduler};
+
+/// Execution plan for a filter
+pub struct FilterExec {
+    input: Arc<ExecutionPlan>,
+    expr: RuntimeExpr,
+    scheduler: Option<Arc<Scheduler>>,
+}
+
+impl FilterExec {
+    pub fn new(input: Arc<ExecutionPlan>, expr: RuntimeExpr) -> Self {
+        Self {
+            input,
+            expr,
+            scheduler: None,
+        }
+    }
+
+    /// Evaluate the predicate and filter each batch as a task of the given scheduler
+    pub fn with_scheduler(mut self, scheduler: Arc<Scheduler>) -> Self {
+        self.scheduler = Some(scheduler);
+        self
+    }
+}
+
+impl ExecutionPlan for FilterExec {
+    fn schema(&self) -> Arc<Schema> {
+        self.input.schema()
+    }
+
+    fn children(&self) -> Vec<Arc<ExecutionPlan>> {
+        vec![self.input.clone()]
+    }
+
+    fn output_partitions(&self) -> usize {
+        self.input.output_partitions()
+    }
+
+    fn execute(&self, partition: usize) -> Result<Rc<RefCell<Relation>>> {
+        let input = self.input.execute(partition)?;
+        let mut relation =
+            FilterRelation::new(input, self.expr.clone(), self.input.schema());
+        if let Some(ref scheduler) = self.scheduler {
+            relation = relation.with_scheduler(scheduler.clone());
+        }
+        Ok(Rc::new(RefCell::new(relation)))
+    }
+}
 
 pub struct FilterRelation {
     schema: Arc<Schema>,
     input: Rc<RefCell<Relation>>,
-    expr: RuntimeExpr,
+    filter: BatchFunction,
+    pipeline: Option<BatchPipeline>,
 }
 
 impl FilterRelation {
@@ -44,38 +95,47 @@ impl FilterRelation {
         Self {
             schema,
             input,
-            expr,
+            filter: Arc::new(move |batch| filter_batch(batch, &expr)),
+            pipeline: None,
         }
     }
+
+    /// Evaluate the predicate and filter each batch as a task of the given scheduler
+    pub fn with_scheduler(mut self, scheduler: Arc<Scheduler>) -> Self {
+        self.pipeline = Some(BatchPipeline::new(scheduler));
+        self
+    }
+}
+
+/// Evaluate the filter expression against a batch and keep the selected rows
+fn filter_batch(batch: &RecordBatch, expr: &RuntimeExpr) -> Result<RecordBatch> {
+    match expr.get_func()(batch)?
+        .as_any()
+        .downcast_ref::<BooleanArray>()
+    {
+        Some(filter_bools) => {
+            let filtered_columns: Result<Vec<ArrayRef>> = (0..batch.num_columns())
+                .map(|i| filter(batch.column(i), &filter_bools))
+                .collect();
+
+            Ok(RecordBatch::new(
+                Arc::new(Schema::empty()),
+                filtered_columns?,
+            ))
+        }
+        _ => Err(ExecutionError::ExecutionError(
+            "Filter expression did not evaluate to boolean".to_string(),
+        )),
+    }
 }
 
 impl Relation for FilterRelation {
     fn next(&mut self) -> Result<Option<RecordBatch>> {
+        if let Some(ref mut pipeline) = self.pipeline {
+            return pipeline.next(&self.input, &self.filter);
+        }
         match self.input.borrow_mut().next()? {
-            Some(batch) => {
-                // evaluate the filter expression against the batch
-                match self.expr.get_func()(&batch)?
-                    .as_any()
-                    .downcast_ref::<BooleanArray>()
-                {
-                    Some(filter_bools) => {
-                        let filtered_columns: Result<Vec<ArrayRef>> = (0..batch
-                            .num_columns())
-                            .map(|i| filter(batch.column(i), &filter_bools))
-                            .collect();
-
-                        let filtered_batch: RecordBatch = RecordBatch::new(
-                            Arc::new(Schema::empty()),
-                            filtered_columns?,
-                        );
-
-                        Ok(Some(filtered_batch))
-                    }
-                    _ => Err(ExecutionError::ExecutionError(
-                        "Filter expression did not evaluate to boolean".to_string(),
-                    )),
-                }
-            }
+            Some(batch) => Ok(Some((self.filter)(&batch)?)),
             None => Ok(None),
         }
     }
@@ -85,128 +145,10 @@ impl Relation for FilterRelation {
     }
 }
 
-//TODO: move into Arrow array_ops
-fn filter(array: &Arc<Array>, filter: &BooleanArray) -> Result<ArrayRef> {
-    let a = array.as_ref();
-
-    //TODO use macros
-    match a.data_type() {
-        DataType::UInt8 => {
-            let b = a.as_any().downcast_ref::<UInt8Array>().unwrap();
-            let mut builder = UInt8Array::builder(b.len());
-            for i in 0..b.len() {
-                if filter.value(i) {
-                    builder.append_value(b.value(i))?;
-                }
-            }
-            Ok(Arc::new(builder.finish()))
-        }
-        DataType::UInt16 => {
-            let b = a.as_any().downcast_ref::<UInt16Array>().unwrap();
-            let mut builder = UInt16Array::builder(b.len());
-            for i in 0..b.len() {
-                if filter.value(i) {
-                    builder.append_value(b.value(i))?;
-                }
-            }
-            Ok(Arc::new(builder.finish()))
-        }
-        DataType::UInt32 => {
-            let b = a.as_any().downcast_ref::<UInt32Array>().unwrap();
-            let mut builder = UInt32Array::builder(b.len());
-            for i in 0..b.len() {
-                if filter.value(i) {
-                    builder.append_value(b.value(i))?;
-                }
-            }
-            Ok(Arc::new(builder.finish()))
-        }
-        DataType::UInt64 => {
-            let b = a.as_any().downcast_ref::<UInt64Array>().unwrap();
-            let mut builder = UInt64Array::builder(b.len());
-            for i in 0..b.len() {
-                if filter.value(i) {
-                    builder.append_value(b.value(i))?;
-                }
-            }
-            Ok(Arc::new(builder.finish()))
-        }
-        DataType::Int8 => {
-            let b = a.as_any().downcast_ref::<Int8Array>().unwrap();
-            let mut builder = Int8Array::builder(b.len());
-            for i in 0..b.len() {
-                if filter.value(i) {
-                    builder.append_value(b.value(i))?;
-                }
-            }
-            Ok(Arc::new(builder.finish()))
-        }
-        DataType::Int16 => {
-            let b = a.as_any().downcast_ref::<Int16Array>().unwrap();
-            let mut builder = Int16Array::builder(b.len());
-            for i in 0..b.len() {
-                if filter.value(i) {
-                    builder.append_value(b.value(i))?;
-                }
-            }
-            Ok(Arc::new(builder.finish()))
-        }
-        DataType::Int32 => {
-            let b = a.as_any().downcast_ref::<Int32Array>().unwrap();
-            let mut builder = Int32Array::builder(b.len());
-            for i in 0..b.len() {
-                if filter.value(i) {
-                    builder.append_value(b.value(i))?;
-                }
-            }
-            Ok(Arc::new(builder.finish()))
-        }
-        DataType::Int64 => {
-            let b = a.as_any().downcast_ref::<Int64Array>().unwrap();
-            let mut builder = Int64Array::builder(b.len());
-            for i in 0..b.len() {
-                if filter.value(i) {
-                    builder.append_value(b.value(i))?;
-                }
-            }
-            Ok(Arc::new(builder.finish()))
-        }
-        DataType::Float32 => {
-            let b = a.as_any().downcast_ref::<Float32Array>().unwrap();
-            let mut builder = Float32Array::builder(b.len());
-            for i in 0..b.len() {
-                if filter.value(i) {
-                    builder.append_value(b.value(i))?;
-                }
-            }
-            Ok(Arc::new(builder.finish()))
-        }
-        DataType::Float64 => {
-            let b = a.as_any().downcast_ref::<Float64Array>().unwrap();
-            let mut builder = Float64Array::builder(b.len());
-            for i in 0..b.len() {
-                if filter.value(i) {
-                    builder.append_value(b.value(i))?;
-                }
-            }
-            Ok(Arc::new(builder.finish()))
-        }
-        DataType::Utf8 => {
-            //TODO: this is inefficient and we should improve the Arrow impl to help make
-            // this more concise
-            let b = a.as_any().downcast_ref::<BinaryArray>().unwrap();
-            let mut values: Vec<String> = Vec::with_capacity(b.len());
-            for i in 0..b.len() {
-                if filter.value(i) {
-                    values.push(b.get_string(i));
-                }
-            }
-            let tmp: Vec<&str> = values.iter().map(|s| s.as_str()).collect();
-            Ok(Arc::new(BinaryArray::from(tmp)))
-        }
-        other => Err(ExecutionError::ExecutionError(format!(
-            "filter not supported for {:?}",
-            other
-        ))),
-    }
+/// Filter an array with a boolean mask, using the Arrow filter kernel, which supports
+/// arrays of every type including nested types. Rows for which the predicate evaluated
+/// to null are not selected, so that comparisons with null values exclude rows as in
+/// SQL.
+pub fn filter(array: &ArrayRef, filter: &BooleanArray) -> Result<ArrayRef> {
+    Ok(compute::filter(array, filter)?)
 }
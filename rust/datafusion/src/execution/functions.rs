@@ -0,0 +1,137 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Registry of the built-in aggregate functions, which is consulted by both the SQL
+//! planner and the expression compiler so that a function can be added in one place
+
+use arrow::datatypes::DataType;
+
+use super::expression::AggregateType;
+
+/// How the return type of an aggregate function is determined
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReturnType {
+    /// The function returns values of the same type as its argument
+    SameAsArgument,
+    /// The function always returns values of this type
+    Fixed(DataType),
+}
+
+/// Meta-data of a built-in aggregate function
+#[derive(Debug)]
+pub struct AggregateFunctionMeta {
+    name: &'static str,
+    aggregate_type: AggregateType,
+    return_type: ReturnType,
+    counts_rows: bool,
+    final_function: Option<&'static str>,
+}
+
+impl AggregateFunctionMeta {
+    pub fn name(&self) -> &str {
+        self.name
+    }
+
+    pub fn aggregate_type(&self) -> &AggregateType {
+        &self.aggregate_type
+    }
+
+    /// Get the return type of the function for an argument of the given type
+    pub fn return_type(&self, arg_type: &DataType) -> DataType {
+        match &self.return_type {
+            ReturnType::SameAsArgument => arg_type.clone(),
+            ReturnType::Fixed(data_type) => data_type.clone(),
+        }
+    }
+
+    /// Whether the function can be called as `f(*)` or `f(1)` to aggregate all rows,
+    /// as in `COUNT(*)`
+    pub fn counts_rows(&self) -> bool {
+        self.counts_rows
+    }
+
+    /// Get the name of the function that combines the partial results of this function
+    /// for several partitions, or `None` if partial results cannot be combined
+    pub fn final_function(&self) -> Option<&str> {
+        self.final_function
+    }
+}
+
+static AGGREGATE_FUNCTIONS: &[AggregateFunctionMeta] = &[
+    AggregateFunctionMeta {
+        name: "MIN",
+        aggregate_type: AggregateType::Min,
+        return_type: ReturnType::SameAsArgument,
+        counts_rows: false,
+        final_function: Some("MIN"),
+    },
+    AggregateFunctionMeta {
+        name: "MAX",
+        aggregate_type: AggregateType::Max,
+        return_type: ReturnType::SameAsArgument,
+        counts_rows: false,
+        final_function: Some("MAX"),
+    },
+    AggregateFunctionMeta {
+        name: "SUM",
+        aggregate_type: AggregateType::Sum,
+        return_type: ReturnType::SameAsArgument,
+        counts_rows: false,
+        final_function: Some("SUM"),
+    },
+    AggregateFunctionMeta {
+        name: "AVG",
+        aggregate_type: AggregateType::Avg,
+        return_type: ReturnType::SameAsArgument,
+        counts_rows: false,
+        final_function: None,
+    },
+    AggregateFunctionMeta {
+        name: "COUNT",
+        aggregate_type: AggregateType::Count,
+        return_type: ReturnType::Fixed(DataType::UInt64),
+        counts_rows: true,
+        // the count of all partitions is the sum of the partial counts
+        final_function: Some("SUM"),
+    },
+];
+
+/// Look up a built-in aggregate function by name, ignoring case
+pub fn aggregate_function(name: &str) -> Option<&'static AggregateFunctionMeta> {
+    AGGREGATE_FUNCTIONS
+        .iter()
+        .find(|f| f.name.eq_ignore_ascii_case(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lookup_aggregate_function() {
+        let count = aggregate_function("count").unwrap();
+        assert_eq!("COUNT", count.name());
+        assert_eq!(DataType::UInt64, count.return_type(&DataType::Int32));
+        assert!(count.counts_rows());
+
+        let max = aggregate_function("Max").unwrap();
+        assert_eq!(DataType::Int32, max.return_type(&DataType::Int32));
+        assert_eq!(Some("MAX"), max.final_function());
+
+        assert!(aggregate_function("sqrt").is_none());
+    }
+}
@@ -0,0 +1,212 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Hashing of the rows of a set of columns, which is shared by the operators that
+//! group or partition rows by the values of expressions
+
+use std::hash::Hasher;
+
+use arrow::array::*;
+use arrow::compute::dictionary_indices;
+use arrow::datatypes::DataType;
+
+use fnv::FnvHasher;
+
+use super::error::{ExecutionError, Result};
+
+/// The hash of a null value, so that all null values hash the same way regardless of
+/// the (undefined) contents of their slot
+const NULL_HASH: u64 = 0x9e37_79b9_7f4a_7c15;
+
+/// Combine the hash of a row so far with the hash of the value of the next column
+fn combine_hashes(hash: u64, value_hash: u64) -> u64 {
+    hash.wrapping_mul(37).wrapping_add(value_hash)
+}
+
+/// Hash a fixed width value by mixing its bits (the finalizer of SplitMix64), which is
+/// much cheaper than feeding the bytes of the value to a general purpose hasher
+fn hash_u64(value: u64) -> u64 {
+    let mut h = value;
+    h = (h ^ (h >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    h = (h ^ (h >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    h ^ (h >> 31)
+}
+
+/// Hash a variable width value
+fn hash_bytes(value: &[u8]) -> u64 {
+    let mut hasher = FnvHasher::default();
+    hasher.write(value);
+    hasher.finish()
+}
+
+/// Add the hash of the values of an array to the hash of each row. Arrays without
+/// nulls skip the validity check for every value.
+macro_rules! hash_array {
+    ($ARRAY:expr, $ARRAY_TYPE:ident, $HASHES:expr, $VALUE:ident => $HASHED:expr) => {{
+        let array = $ARRAY.as_any().downcast_ref::<$ARRAY_TYPE>().unwrap();
+        if array.null_count() == 0 {
+            for (i, hash) in $HASHES.iter_mut().enumerate() {
+                let $VALUE = array.value(i);
+                *hash = combine_hashes(*hash, $HASHED);
+            }
+        } else {
+            for (i, hash) in $HASHES.iter_mut().enumerate() {
+                let value_hash = if array.is_null(i) {
+                    NULL_HASH
+                } else {
+                    let $VALUE = array.value(i);
+                    $HASHED
+                };
+                *hash = combine_hashes(*hash, value_hash);
+            }
+        }
+    }};
+}
+
+/// Compute the hash of each row of a set of columns of equal length. Rows with equal
+/// values have equal hashes, where null values are equal to each other. The values of
+/// the columns are hashed one column at a time with a specialized loop for each type.
+pub fn create_hashes(arrays: &[ArrayRef]) -> Result<Vec<u64>> {
+    let num_rows = arrays.get(0).map(|a| a.len()).unwrap_or(0);
+    let mut hashes = vec![0; num_rows];
+    for array in arrays {
+        if array.len() != num_rows {
+            return Err(ExecutionError::InternalError(format!(
+                "Cannot hash columns of different lengths {} and {}",
+                num_rows,
+                array.len()
+            )));
+        }
+        match array.data_type() {
+            DataType::Boolean => {
+                hash_array!(array, BooleanArray, hashes, v => hash_u64(v as u64))
+            }
+            DataType::UInt8 => {
+                hash_array!(array, UInt8Array, hashes, v => hash_u64(v as u64))
+            }
+            DataType::UInt16 => {
+                hash_array!(array, UInt16Array, hashes, v => hash_u64(v as u64))
+            }
+            DataType::UInt32 => {
+                hash_array!(array, UInt32Array, hashes, v => hash_u64(v as u64))
+            }
+            DataType::UInt64 => hash_array!(array, UInt64Array, hashes, v => hash_u64(v)),
+            DataType::Int8 => {
+                hash_array!(array, Int8Array, hashes, v => hash_u64(v as u64))
+            }
+            DataType::Int16 => {
+                hash_array!(array, Int16Array, hashes, v => hash_u64(v as u64))
+            }
+            DataType::Int32 => {
+                hash_array!(array, Int32Array, hashes, v => hash_u64(v as u64))
+            }
+            DataType::Int64 => {
+                hash_array!(array, Int64Array, hashes, v => hash_u64(v as u64))
+            }
+            DataType::Float32 => {
+                hash_array!(
+                    array, Float32Array, hashes, v => hash_u64(v.to_bits() as u64)
+                )
+            }
+            DataType::Float64 => {
+                hash_array!(array, Float64Array, hashes, v => hash_u64(v.to_bits()))
+            }
+            DataType::Utf8 | DataType::Binary => {
+                hash_array!(array, BinaryArray, hashes, v => hash_bytes(v))
+            }
+            DataType::Dictionary(_, _) => {
+                // each value of the dictionary is hashed once, and rows hash the same
+                // as they would if the values were not dictionary encoded
+                let (values, indices) = dictionary_indices(array)?;
+                let value_hashes = create_hashes(&[values])?;
+                for (hash, index) in hashes.iter_mut().zip(indices) {
+                    let value_hash = index.map_or(NULL_HASH, |index| value_hashes[index]);
+                    *hash = combine_hashes(*hash, value_hash);
+                }
+            }
+            other => {
+                return Err(ExecutionError::NotImplemented(format!(
+                    "Hashing is not supported for type {:?}",
+                    other
+                )));
+            }
+        }
+    }
+    Ok(hashes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::builder::BinaryBuilder;
+    use std::sync::Arc;
+
+    #[test]
+    fn equal_rows_have_equal_hashes() {
+        let a: ArrayRef = Arc::new(Int32Array::from(vec![1, 2, 1, 2]));
+        let b: ArrayRef = Arc::new(BinaryArray::from(vec!["x", "x", "x", "y"]));
+        let hashes = create_hashes(&[a, b]).unwrap();
+        assert_eq!(4, hashes.len());
+        assert_eq!(hashes[0], hashes[2]);
+        assert_ne!(hashes[0], hashes[1]);
+        assert_ne!(hashes[1], hashes[3]);
+    }
+
+    #[test]
+    fn column_order_affects_hash() {
+        let a: ArrayRef = Arc::new(Int64Array::from(vec![1, 2]));
+        let b: ArrayRef = Arc::new(Int64Array::from(vec![2, 1]));
+        let hashes = create_hashes(&[a, b]).unwrap();
+        assert_ne!(hashes[0], hashes[1]);
+    }
+
+    #[test]
+    fn null_values_have_equal_hashes() {
+        let a: ArrayRef = Arc::new(UInt32Array::from(vec![Some(1), None, Some(1), None]));
+        let b: ArrayRef = Arc::new(Float64Array::from(vec![None, None, None, Some(0.0)]));
+        let hashes = create_hashes(&[a, b]).unwrap();
+        assert_eq!(hashes[0], hashes[2]);
+        assert_ne!(hashes[0], hashes[1]);
+        assert_ne!(hashes[1], hashes[3]);
+    }
+
+    #[test]
+    fn dictionary_values_hash_as_values() {
+        let a: ArrayRef = Arc::new(
+            Int8DictionaryArray::try_from_strings(&[Some("x"), None, Some("y")]).unwrap(),
+        );
+        let mut b = BinaryBuilder::new(3);
+        b.append_string("x").unwrap();
+        b.append_null().unwrap();
+        b.append_string("y").unwrap();
+        let b: ArrayRef = Arc::new(b.finish());
+        assert_eq!(create_hashes(&[b]).unwrap(), create_hashes(&[a]).unwrap());
+    }
+
+    #[test]
+    fn hash_columns_of_different_lengths() {
+        let a: ArrayRef = Arc::new(Int8Array::from(vec![1, 2]));
+        let b: ArrayRef = Arc::new(Int8Array::from(vec![1]));
+        assert!(create_hashes(&[a, b]).is_err());
+    }
+
+    #[test]
+    fn hash_unsupported_type() {
+        let a: ArrayRef = Arc::new(NullArray::new(2));
+        assert!(create_hashes(&[a]).is_err());
+    }
+}
@@ -26,8 +26,45 @@ use arrow::datatypes::{DataType, Schema};
 use arrow::record_batch::RecordBatch;
 
 use super::error::{ExecutionError, Result};
+use super::filter::filter;
+use super::physicalplan::ExecutionPlan;
 use super::relation::Relation;
 
+/// Execution plan for a limit, which is applied to each partition of its input separately
+pub struct LimitExec {
+    input: Arc<ExecutionPlan>,
+    limit: usize,
+}
+
+impl LimitExec {
+    pub fn new(input: Arc<ExecutionPlan>, limit: usize) -> Self {
+        Self { input, limit }
+    }
+}
+
+impl ExecutionPlan for LimitExec {
+    fn schema(&self) -> Arc<Schema> {
+        self.input.schema()
+    }
+
+    fn children(&self) -> Vec<Arc<ExecutionPlan>> {
+        vec![self.input.clone()]
+    }
+
+    fn output_partitions(&self) -> usize {
+        self.input.output_partitions()
+    }
+
+    fn execute(&self, partition: usize) -> Result<Rc<RefCell<Relation>>> {
+        let input = self.input.execute(partition)?;
+        Ok(Rc::new(RefCell::new(LimitRelation::new(
+            input,
+            self.limit,
+            self.input.schema(),
+        ))))
+    }
+}
+
 pub struct LimitRelation {
     input: Rc<RefCell<Relation>>,
     schema: Arc<Schema>,
@@ -48,17 +85,18 @@ impl LimitRelation {
 
 impl Relation for LimitRelation {
     fn next(&mut self) -> Result<Option<RecordBatch>> {
-        match self.input.borrow_mut().next()? {
-            Some(batch) => {
-                let capacity = self.limit - self.num_consumed_rows;
+        let capacity = self.limit - self.num_consumed_rows;
 
-                if capacity <= 0 {
-                    return Ok(None);
-                }
+        // stop before reading another batch from the input once the limit is reached
+        if capacity == 0 {
+            return Ok(None);
+        }
 
+        match self.input.borrow_mut().next()? {
+            Some(batch) => {
                 if batch.num_rows() >= capacity {
                     let limited_columns: Result<Vec<ArrayRef>> = (0..batch.num_columns())
-                        .map(|i| limit(batch.column(i).as_ref(), capacity))
+                        .map(|i| limit(batch.column(i), capacity))
                         .collect();
 
                     let limited_batch: RecordBatch =
@@ -81,7 +119,8 @@ impl Relation for LimitRelation {
 }
 
 //TODO: move into Arrow array_ops
-fn limit(a: &Array, num_rows_to_read: usize) -> Result<ArrayRef> {
+fn limit(array: &ArrayRef, num_rows_to_read: usize) -> Result<ArrayRef> {
+    let a = array.as_ref();
     //TODO use macros
     match a.data_type() {
         DataType::UInt8 => {
@@ -174,6 +213,15 @@ fn limit(a: &Array, num_rows_to_read: usize) -> Result<ArrayRef> {
             let tmp: Vec<&str> = values.iter().map(|s| s.as_str()).collect();
             Ok(Arc::new(BinaryArray::from(tmp)))
         }
+        DataType::Null
+        | DataType::Binary
+        | DataType::List(_)
+        | DataType::Struct(_)
+        | DataType::Dictionary(_, _) => {
+            let selection: Vec<bool> =
+                (0..a.len()).map(|i| i < num_rows_to_read).collect();
+            filter(array, &BooleanArray::from(selection))
+        }
         other => Err(ExecutionError::ExecutionError(format!(
             "filter not supported for {:?}",
             other
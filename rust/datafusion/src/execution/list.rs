@@ -0,0 +1,272 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Built-in scalar functions on list values. The return type of a list function can
+//! depend on the element type of its list argument, which a user-defined function
+//! cannot express, so these functions are resolved by the SQL planner and the
+//! expression compiler directly.
+//!
+//! Elements of a list are numbered from one, as in SQL arrays, and accessing an
+//! element outside of a list produces a null value.
+
+use std::sync::Arc;
+
+use arrow::array::*;
+use arrow::compute;
+use arrow::datatypes::DataType;
+
+use super::error::{ExecutionError, Result};
+
+/// A built-in function on lists
+#[derive(Debug, Clone, PartialEq)]
+pub enum ListFunction {
+    /// `array_length(list)` returns the number of elements of a list
+    Length,
+    /// `array_element(list, index)` returns the element of a list at a one-based index
+    Element,
+}
+
+/// Look up a built-in list function by name, ignoring case
+pub fn list_function(name: &str) -> Option<ListFunction> {
+    if name.eq_ignore_ascii_case("array_length") {
+        Some(ListFunction::Length)
+    } else if name.eq_ignore_ascii_case("array_element") {
+        Some(ListFunction::Element)
+    } else {
+        None
+    }
+}
+
+impl ListFunction {
+    pub fn name(&self) -> &str {
+        match self {
+            ListFunction::Length => "array_length",
+            ListFunction::Element => "array_element",
+        }
+    }
+
+    /// Get the types that arguments of the given types must be cast to before the
+    /// function is called
+    pub fn coerce_types(&self, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+        let expected_args = match self {
+            ListFunction::Length => 1,
+            ListFunction::Element => 2,
+        };
+        if arg_types.len() != expected_args {
+            return Err(ExecutionError::General(format!(
+                "Function '{}' expects {} argument(s) but was called with {}",
+                self.name(),
+                expected_args,
+                arg_types.len()
+            )));
+        }
+        match arg_types[0] {
+            DataType::List(_) => {}
+            ref other => {
+                return Err(ExecutionError::General(format!(
+                    "Function '{}' expects a list argument but was called with {:?}",
+                    self.name(),
+                    other
+                )));
+            }
+        }
+        match self {
+            ListFunction::Length => Ok(arg_types.to_vec()),
+            ListFunction::Element => Ok(vec![arg_types[0].clone(), DataType::Int64]),
+        }
+    }
+
+    /// Get the return type of the function for a list of the given type
+    pub fn return_type(&self, list_type: &DataType) -> Result<DataType> {
+        match (self, list_type) {
+            (ListFunction::Length, DataType::List(_)) => Ok(DataType::Int32),
+            (ListFunction::Element, DataType::List(ref t)) => Ok(t.as_ref().clone()),
+            (_, other) => Err(ExecutionError::General(format!(
+                "Function '{}' expects a list argument but was called with {:?}",
+                self.name(),
+                other
+            ))),
+        }
+    }
+
+    /// Evaluate the function for arguments that have been cast to the types returned
+    /// by `coerce_types`
+    pub fn invoke(&self, args: &[ArrayRef]) -> Result<ArrayRef> {
+        match self {
+            ListFunction::Length => array_length(&args[0]),
+            ListFunction::Element => {
+                match args[1].as_any().downcast_ref::<Int64Array>() {
+                    Some(indices) => array_element(&args[0], indices),
+                    None => Err(ExecutionError::InternalError(
+                        "array_element expects an Int64 index".to_string(),
+                    )),
+                }
+            }
+        }
+    }
+}
+
+/// Get the number of elements of each list of a list array. Binary values, which
+/// have the type of a list of bytes, are also accepted.
+pub fn array_length(array: &ArrayRef) -> Result<ArrayRef> {
+    let mut builder = Int32Array::builder(array.len());
+    if let Some(list) = array.as_any().downcast_ref::<ListArray>() {
+        for i in 0..list.len() {
+            if list.is_null(i) {
+                builder.append_null()?;
+            } else {
+                builder.append_value(list.value_length(i))?;
+            }
+        }
+    } else if let Some(binary) = array.as_any().downcast_ref::<BinaryArray>() {
+        for i in 0..binary.len() {
+            if binary.is_null(i) {
+                builder.append_null()?;
+            } else {
+                builder.append_value(binary.value_length(i))?;
+            }
+        }
+    } else {
+        return Err(ExecutionError::ExecutionError(format!(
+            "array_length is not supported for {:?}",
+            array.data_type()
+        )));
+    }
+    Ok(Arc::new(builder.finish()))
+}
+
+/// Get the element of each list of a list array at the corresponding one-based index.
+/// The result is null where the list or the index is null, or the index is outside
+/// of the list.
+pub fn array_element(array: &ArrayRef, indices: &Int64Array) -> Result<ArrayRef> {
+    let list = match array.as_any().downcast_ref::<ListArray>() {
+        Some(list) => list,
+        None => {
+            return Err(ExecutionError::ExecutionError(format!(
+                "array_element is not supported for {:?}",
+                array.data_type()
+            )));
+        }
+    };
+
+    // the position of each selected element in the values of the list array
+    let positions: Vec<Option<usize>> = (0..list.len())
+        .map(|i| {
+            if list.is_null(i) || indices.is_null(i) {
+                return None;
+            }
+            let index = indices.value(i);
+            if index < 1 || index > list.value_length(i) as i64 {
+                None
+            } else {
+                Some(list.value_offset(i) as usize + index as usize - 1)
+            }
+        })
+        .collect();
+
+    take(&list.values(), &positions)
+}
+
+/// Select the values of an array at the given positions, producing null values for
+/// positions that are `None`
+pub fn take(values: &ArrayRef, positions: &[Option<usize>]) -> Result<ArrayRef> {
+    let indices = UInt32Array::from(
+        positions
+            .iter()
+            .map(|position| position.map(|j| j as u32))
+            .collect::<Vec<Option<u32>>>(),
+    );
+    Ok(compute::take(values, &indices)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::builder::{Int32Builder, ListBuilder};
+
+    fn lists() -> ArrayRef {
+        // [[1, 2, 3], null, [], [4, null]]
+        let mut builder = ListBuilder::new(Int32Builder::new(8));
+        for v in &[1, 2, 3] {
+            builder.values().append_value(*v).unwrap();
+        }
+        builder.append(true).unwrap();
+        builder.append(false).unwrap();
+        builder.append(true).unwrap();
+        builder.values().append_value(4).unwrap();
+        builder.values().append_null().unwrap();
+        builder.append(true).unwrap();
+        Arc::new(builder.finish())
+    }
+
+    #[test]
+    fn lookup_list_function() {
+        assert_eq!(Some(ListFunction::Length), list_function("ARRAY_LENGTH"));
+        assert_eq!(Some(ListFunction::Element), list_function("array_element"));
+        assert_eq!(None, list_function("sqrt"));
+
+        let list_type = DataType::List(Box::new(DataType::Float64));
+        assert_eq!(
+            vec![list_type.clone(), DataType::Int64],
+            ListFunction::Element
+                .coerce_types(&[list_type.clone(), DataType::Int32])
+                .unwrap()
+        );
+        assert_eq!(
+            DataType::Float64,
+            ListFunction::Element.return_type(&list_type).unwrap()
+        );
+        assert_eq!(
+            DataType::Int32,
+            ListFunction::Length.return_type(&list_type).unwrap()
+        );
+        assert!(ListFunction::Length
+            .coerce_types(&[DataType::Int32])
+            .is_err());
+        assert!(ListFunction::Element.coerce_types(&[list_type]).is_err());
+    }
+
+    #[test]
+    fn length_of_lists() {
+        let lengths = array_length(&lists()).unwrap();
+        let lengths = lengths.as_any().downcast_ref::<Int32Array>().unwrap();
+        assert_eq!(4, lengths.len());
+        assert_eq!(3, lengths.value(0));
+        assert!(lengths.is_null(1));
+        assert_eq!(0, lengths.value(2));
+        assert_eq!(2, lengths.value(3));
+    }
+
+    #[test]
+    fn element_of_lists() {
+        let indices = Int64Array::from(vec![Some(3), Some(1), Some(1), Some(2)]);
+        let elements = array_element(&lists(), &indices).unwrap();
+        let elements = elements.as_any().downcast_ref::<Int32Array>().unwrap();
+        assert_eq!(3, elements.value(0));
+        // null list, empty list and null element
+        assert!(elements.is_null(1));
+        assert!(elements.is_null(2));
+        assert!(elements.is_null(3));
+
+        let indices = Int64Array::from(vec![Some(0), Some(1), None, Some(1)]);
+        let elements = array_element(&lists(), &indices).unwrap();
+        let elements = elements.as_any().downcast_ref::<Int32Array>().unwrap();
+        assert!(elements.is_null(0));
+        assert!(elements.is_null(2));
+        assert_eq!(4, elements.value(3));
+    }
+}
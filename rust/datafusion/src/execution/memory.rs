@@ -0,0 +1,194 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Tracking of the memory used by the operators of a query
+
+use std::sync::{Arc, Mutex};
+
+use arrow::array_data::ArrayData;
+use arrow::record_batch::RecordBatch;
+
+use super::error::{ExecutionError, Result};
+
+/// Tracks the memory used by the operators of a query, so that a query that needs more
+/// memory than the configured limit fails with `ExecutionError::ResourcesExhausted`
+/// instead of exhausting the memory of the process. A memory manager is shared by all
+/// partitions of a query, which may be executed on different threads.
+#[derive(Debug)]
+pub struct MemoryManager {
+    limit: Option<usize>,
+    used: Mutex<usize>,
+}
+
+impl MemoryManager {
+    /// Create a memory manager that allows at most `limit` bytes to be reserved, or
+    /// any amount if there is no limit
+    pub fn new(limit: Option<usize>) -> Self {
+        Self {
+            limit,
+            used: Mutex::new(0),
+        }
+    }
+
+    /// Get the maximum number of bytes that can be reserved
+    pub fn limit(&self) -> Option<usize> {
+        self.limit
+    }
+
+    /// Get the number of bytes that are currently reserved
+    pub fn used(&self) -> usize {
+        *self.used.lock().unwrap()
+    }
+
+    fn try_allocate(&self, bytes: usize, consumer: &str) -> Result<()> {
+        let mut used = self.used.lock().unwrap();
+        match self.limit {
+            Some(limit) if bytes > limit.saturating_sub(*used) => {
+                let message = format!(
+                    "Failed to reserve {} bytes for {} because {} of the {} bytes \
+                     available to the query are already in use",
+                    bytes, consumer, *used, limit
+                );
+                warn!("{}", message);
+                Err(ExecutionError::ResourcesExhausted(message))
+            }
+            _ => {
+                *used += bytes;
+                Ok(())
+            }
+        }
+    }
+
+    fn free(&self, bytes: usize) {
+        let mut used = self.used.lock().unwrap();
+        *used -= bytes.min(*used);
+    }
+}
+
+/// Memory reserved by an operator from a memory manager, which is released when the
+/// reservation is dropped
+#[derive(Debug)]
+pub struct MemoryReservation {
+    manager: Arc<MemoryManager>,
+    consumer: String,
+    size: usize,
+}
+
+impl MemoryReservation {
+    /// Create an empty reservation for the named operator
+    pub fn new(manager: Arc<MemoryManager>, consumer: &str) -> Self {
+        Self {
+            manager,
+            consumer: consumer.to_string(),
+            size: 0,
+        }
+    }
+
+    /// Get the number of bytes reserved
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Reserve additional memory, failing if the query would exceed its memory limit
+    pub fn try_grow(&mut self, bytes: usize) -> Result<()> {
+        self.manager.try_allocate(bytes, &self.consumer)?;
+        self.size += bytes;
+        Ok(())
+    }
+
+    /// Release part of the reserved memory
+    pub fn shrink(&mut self, bytes: usize) {
+        let bytes = bytes.min(self.size);
+        self.manager.free(bytes);
+        self.size -= bytes;
+    }
+
+    /// Release all of the reserved memory
+    pub fn free(&mut self) {
+        let size = self.size;
+        self.shrink(size);
+    }
+}
+
+impl Drop for MemoryReservation {
+    fn drop(&mut self) {
+        self.free();
+    }
+}
+
+/// Estimate the memory used by a batch from the sizes of the buffers of its arrays
+pub fn batch_memory_size(batch: &RecordBatch) -> usize {
+    (0..batch.num_columns())
+        .map(|i| array_memory_size(&batch.column(i).data()))
+        .sum()
+}
+
+fn array_memory_size(data: &ArrayData) -> usize {
+    let buffers: usize = data.buffers().iter().map(|buffer| buffer.len()).sum();
+    let children: usize = data
+        .child_data()
+        .iter()
+        .map(|child| array_memory_size(child))
+        .sum();
+    buffers + children
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reserve_within_limit() {
+        let manager = Arc::new(MemoryManager::new(Some(100)));
+        let mut a = MemoryReservation::new(manager.clone(), "a");
+        let mut b = MemoryReservation::new(manager.clone(), "b");
+        a.try_grow(60).unwrap();
+        b.try_grow(40).unwrap();
+        assert_eq!(100, manager.used());
+
+        b.shrink(30);
+        assert_eq!(10, b.size());
+        assert_eq!(70, manager.used());
+
+        drop(a);
+        assert_eq!(10, manager.used());
+    }
+
+    #[test]
+    fn reserve_beyond_limit() {
+        let manager = Arc::new(MemoryManager::new(Some(100)));
+        let mut a = MemoryReservation::new(manager.clone(), "a");
+        a.try_grow(80).unwrap();
+        match a.try_grow(21) {
+            Err(ExecutionError::ResourcesExhausted(_)) => {}
+            other => panic!("Expected ResourcesExhausted, found {:?}", other),
+        }
+        assert_eq!(80, a.size());
+        assert_eq!(80, manager.used());
+
+        a.free();
+        assert_eq!(0, manager.used());
+    }
+
+    #[test]
+    fn reserve_without_limit() {
+        let manager = Arc::new(MemoryManager::new(None));
+        let mut a = MemoryReservation::new(manager.clone(), "a");
+        a.try_grow(usize::max_value() / 2).unwrap();
+        assert_eq!(None, manager.limit());
+    }
+}
@@ -0,0 +1,928 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Execution of a merge, which executes the partitions of its input concurrently on a
+//! pool of threads and combines their results into a single partition, optionally
+//! preserving the sort order of the partitions
+
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::rc::Rc;
+use std::sync::atomic::{self, AtomicUsize};
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+
+use arrow::array::*;
+use arrow::compute;
+use arrow::datatypes::{DataType, DateUnit, Schema, TimeUnit};
+use arrow::record_batch::RecordBatch;
+
+use super::context::{Collation, NanOrdering};
+use super::error::{ExecutionError, Result};
+use super::expression::{compare_strings, FloatOrdering, RuntimeExpr};
+use super::physicalplan::ExecutionPlan;
+use super::relation::Relation;
+
+/// Execution plan for merging the partitions of its input into a single partition. The
+/// order of the batches in the output is not deterministic.
+pub struct MergeExec {
+    input: Arc<ExecutionPlan>,
+    concurrency: usize,
+}
+
+impl MergeExec {
+    /// Create a merge that executes at most `concurrency` input partitions at a time
+    pub fn new(input: Arc<ExecutionPlan>, concurrency: usize) -> Self {
+        Self { input, concurrency }
+    }
+}
+
+impl ExecutionPlan for MergeExec {
+    fn schema(&self) -> Arc<Schema> {
+        self.input.schema()
+    }
+
+    fn children(&self) -> Vec<Arc<ExecutionPlan>> {
+        vec![self.input.clone()]
+    }
+
+    fn output_partitions(&self) -> usize {
+        1
+    }
+
+    fn execute(&self, partition: usize) -> Result<Rc<RefCell<Relation>>> {
+        if partition != 0 {
+            return Err(ExecutionError::InternalError(format!(
+                "Invalid partition {} for merge with a single partition",
+                partition
+            )));
+        }
+        Ok(Rc::new(RefCell::new(MergeRelation::new(
+            self.input.clone(),
+            self.concurrency,
+        ))))
+    }
+}
+
+/// A relation that receives the batches produced by worker threads, which each execute
+/// input partitions until there are none left
+pub struct MergeRelation {
+    schema: Arc<Schema>,
+    receiver: Receiver<Result<RecordBatch>>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl MergeRelation {
+    pub fn new(input: Arc<ExecutionPlan>, concurrency: usize) -> Self {
+        let schema = input.schema();
+        let num_workers = concurrency.max(1).min(input.output_partitions());
+        let next_partition = Arc::new(AtomicUsize::new(0));
+
+        // the channel is bounded so that workers do not get too far ahead of the
+        // consumer of this relation
+        let (sender, receiver) = mpsc::sync_channel(num_workers);
+
+        let workers = (0..num_workers)
+            .map(|_| {
+                let input = input.clone();
+                let next_partition = next_partition.clone();
+                let sender = sender.clone();
+                thread::spawn(move || execute_partitions(input, next_partition, sender))
+            })
+            .collect();
+
+        Self {
+            schema,
+            receiver,
+            workers,
+        }
+    }
+}
+
+impl Relation for MergeRelation {
+    fn next(&mut self) -> Result<Option<RecordBatch>> {
+        match self.receiver.recv() {
+            Ok(result) => result.map(Some),
+            Err(_) => {
+                // every worker has dropped its sender, so all partitions are finished
+                for worker in self.workers.drain(..) {
+                    if worker.join().is_err() {
+                        return Err(ExecutionError::ExecutionError(
+                            "A worker thread panicked while executing a partition"
+                                .to_string(),
+                        ));
+                    }
+                }
+                Ok(None)
+            }
+        }
+    }
+
+    fn schema(&self) -> &Arc<Schema> {
+        &self.schema
+    }
+}
+
+/// Execute partitions of the input, claiming them one at a time, until there are none
+/// left or the results are no longer wanted
+fn execute_partitions(
+    input: Arc<ExecutionPlan>,
+    next_partition: Arc<AtomicUsize>,
+    sender: SyncSender<Result<RecordBatch>>,
+) {
+    loop {
+        let partition = next_partition.fetch_add(1, atomic::Ordering::SeqCst);
+        if partition >= input.output_partitions()
+            || !execute_partition(&input, partition, &sender)
+        {
+            return;
+        }
+    }
+}
+
+/// Execute a single partition, sending its batches to the merge relation. Returns false
+/// if an error occurred or the merge relation has been dropped.
+fn execute_partition(
+    input: &Arc<ExecutionPlan>,
+    partition: usize,
+    sender: &SyncSender<Result<RecordBatch>>,
+) -> bool {
+    debug!("Executing partition {}", partition);
+    let relation = match input.execute(partition) {
+        Ok(relation) => relation,
+        Err(e) => {
+            warn!("Failed to execute partition {}: {:?}", partition, e);
+            let _ = sender.send(Err(e));
+            return false;
+        }
+    };
+
+    let mut batch_count = 0;
+    let mut row_count = 0;
+    loop {
+        let result = relation.borrow_mut().next();
+        match result {
+            Ok(Some(batch)) => {
+                trace!(
+                    "Partition {} produced a batch of {} rows",
+                    partition,
+                    batch.num_rows()
+                );
+                batch_count += 1;
+                row_count += batch.num_rows();
+                if sender.send(Ok(batch)).is_err() {
+                    debug!("Stopped executing partition {} early", partition);
+                    return false;
+                }
+            }
+            Ok(None) => {
+                debug!(
+                    "Finished executing partition {}: {} rows in {} batches",
+                    partition, row_count, batch_count
+                );
+                return true;
+            }
+            Err(e) => {
+                warn!("Failed to execute partition {}: {:?}", partition, e);
+                let _ = sender.send(Err(e));
+                return false;
+            }
+        }
+    }
+}
+
+/// A sort key used to merge sorted partitions
+#[derive(Clone)]
+pub struct SortExpr {
+    /// The expression to sort by
+    pub expr: RuntimeExpr,
+    /// Whether values are sorted in ascending or descending order
+    pub asc: bool,
+    /// Whether null values are sorted before or after all other values, regardless of
+    /// the direction of the sort
+    pub nulls_first: bool,
+}
+
+/// Execution plan for merging partitions that are each sorted by the same sort keys
+/// into a single sorted partition (a k-way merge). Every input partition is executed on
+/// its own thread.
+pub struct SortPreservingMergeExec {
+    input: Arc<ExecutionPlan>,
+    sort_expr: Vec<SortExpr>,
+    nan_ordering: NanOrdering,
+    collation: Collation,
+    batch_size: usize,
+}
+
+impl SortPreservingMergeExec {
+    pub fn new(
+        input: Arc<ExecutionPlan>,
+        sort_expr: Vec<SortExpr>,
+        nan_ordering: NanOrdering,
+        collation: Collation,
+        batch_size: usize,
+    ) -> Self {
+        Self {
+            input,
+            sort_expr,
+            nan_ordering,
+            collation,
+            batch_size,
+        }
+    }
+}
+
+impl ExecutionPlan for SortPreservingMergeExec {
+    fn schema(&self) -> Arc<Schema> {
+        self.input.schema()
+    }
+
+    fn children(&self) -> Vec<Arc<ExecutionPlan>> {
+        vec![self.input.clone()]
+    }
+
+    fn output_partitions(&self) -> usize {
+        1
+    }
+
+    fn execute(&self, partition: usize) -> Result<Rc<RefCell<Relation>>> {
+        if partition != 0 {
+            return Err(ExecutionError::InternalError(format!(
+                "Invalid partition {} for merge with a single partition",
+                partition
+            )));
+        }
+        Ok(Rc::new(RefCell::new(SortPreservingMergeRelation::new(
+            self.input.clone(),
+            self.sort_expr.clone(),
+            self.nan_ordering,
+            self.collation,
+            self.batch_size,
+        ))))
+    }
+}
+
+/// The position of a sorted merge in one of its input partitions
+struct MergeCursor {
+    receiver: Receiver<Result<RecordBatch>>,
+    /// The current batch, and its index in the batches for the output being built
+    batch: Option<(Rc<RecordBatch>, usize)>,
+    /// The values of the sort keys for the current batch
+    keys: Vec<ArrayRef>,
+    row: usize,
+    finished: bool,
+}
+
+impl MergeCursor {
+    /// Move to the next non-empty batch if the current batch has been consumed
+    fn advance(
+        &mut self,
+        sort_expr: &Vec<SortExpr>,
+        batches: &mut Vec<Rc<RecordBatch>>,
+    ) -> Result<()> {
+        while !self.finished && !self.has_row() {
+            match self.receiver.recv() {
+                Ok(Ok(batch)) => {
+                    if batch.num_rows() == 0 {
+                        continue;
+                    }
+                    self.keys = sort_expr
+                        .iter()
+                        .map(|e| e.expr.get_func()(&batch))
+                        .collect::<Result<Vec<ArrayRef>>>()?;
+                    let batch = Rc::new(batch);
+                    self.batch = Some((batch.clone(), batches.len()));
+                    batches.push(batch);
+                    self.row = 0;
+                }
+                Ok(Err(e)) => return Err(e),
+                Err(_) => {
+                    self.batch = None;
+                    self.finished = true;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn has_row(&self) -> bool {
+        match self.batch {
+            Some((ref batch, _)) => self.row < batch.num_rows(),
+            None => false,
+        }
+    }
+}
+
+/// A relation that merges sorted input partitions, each produced by a worker thread
+pub struct SortPreservingMergeRelation {
+    schema: Arc<Schema>,
+    sort_expr: Vec<SortExpr>,
+    nan_ordering: NanOrdering,
+    collation: Collation,
+    batch_size: usize,
+    cursors: Vec<MergeCursor>,
+}
+
+impl SortPreservingMergeRelation {
+    pub fn new(
+        input: Arc<ExecutionPlan>,
+        sort_expr: Vec<SortExpr>,
+        nan_ordering: NanOrdering,
+        collation: Collation,
+        batch_size: usize,
+    ) -> Self {
+        let cursors = (0..input.output_partitions())
+            .map(|partition| {
+                // each input only needs to be read as fast as the merge consumes it
+                let (sender, receiver) = mpsc::sync_channel(1);
+                let input = input.clone();
+                thread::spawn(move || execute_partition(&input, partition, &sender));
+                MergeCursor {
+                    receiver,
+                    batch: None,
+                    keys: vec![],
+                    row: 0,
+                    finished: false,
+                }
+            })
+            .collect();
+
+        Self {
+            schema: input.schema(),
+            sort_expr,
+            nan_ordering,
+            collation,
+            batch_size: batch_size.max(1),
+            cursors,
+        }
+    }
+
+    /// Find the input whose current row sorts first, preferring earlier inputs when
+    /// rows are equal
+    fn min_cursor(&self) -> Result<Option<usize>> {
+        let mut min: Option<usize> = None;
+        for (i, cursor) in self.cursors.iter().enumerate() {
+            if !cursor.has_row() {
+                continue;
+            }
+            min = match min {
+                Some(j)
+                    if self.compare(&self.cursors[j], cursor)? != Ordering::Greater =>
+                {
+                    Some(j)
+                }
+                _ => Some(i),
+            };
+        }
+        Ok(min)
+    }
+
+    /// Compare the current rows of two cursors by the sort keys
+    fn compare(&self, left: &MergeCursor, right: &MergeCursor) -> Result<Ordering> {
+        compare_rows(
+            &self.sort_expr,
+            &left.keys,
+            left.row,
+            &right.keys,
+            right.row,
+            self.nan_ordering,
+            self.collation,
+        )
+    }
+}
+
+impl Relation for SortPreservingMergeRelation {
+    fn next(&mut self) -> Result<Option<RecordBatch>> {
+        // the batches that rows of the output are taken from, and the (batch, row)
+        // position of each row of the output
+        let mut batches: Vec<Rc<RecordBatch>> = vec![];
+        let mut indices: Vec<(usize, usize)> = vec![];
+
+        for cursor in self.cursors.iter_mut() {
+            if let Some((ref batch, ref mut index)) = cursor.batch {
+                *index = batches.len();
+                batches.push(batch.clone());
+            }
+        }
+
+        while indices.len() < self.batch_size {
+            for cursor in self.cursors.iter_mut() {
+                cursor.advance(&self.sort_expr, &mut batches)?;
+            }
+            match self.min_cursor()? {
+                Some(i) => {
+                    let cursor = &mut self.cursors[i];
+                    if let Some((_, index)) = cursor.batch {
+                        indices.push((index, cursor.row));
+                    }
+                    cursor.row += 1;
+                }
+                None => break,
+            }
+        }
+
+        if indices.is_empty() {
+            return Ok(None);
+        }
+
+        let columns = take_rows(&batches, &indices, self.schema.fields().len())?;
+        Ok(Some(RecordBatch::new(self.schema.clone(), columns)))
+    }
+
+    fn schema(&self) -> &Arc<Schema> {
+        &self.schema
+    }
+}
+
+/// Compare two rows by their sort keys, which are the values of the sort expressions
+/// evaluated on the batches that the rows belong to
+pub fn compare_rows(
+    sort_expr: &[SortExpr],
+    left_keys: &[ArrayRef],
+    left_row: usize,
+    right_keys: &[ArrayRef],
+    right_row: usize,
+    nan_ordering: NanOrdering,
+    collation: Collation,
+) -> Result<Ordering> {
+    for (i, sort_expr) in sort_expr.iter().enumerate() {
+        let (left, right) = (&left_keys[i], &right_keys[i]);
+        let nulls = (left.is_null(left_row), right.is_null(right_row));
+        let ordering = match nulls {
+            (true, true) => Ordering::Equal,
+            (true, false) if sort_expr.nulls_first => Ordering::Less,
+            (true, false) => Ordering::Greater,
+            (false, true) if sort_expr.nulls_first => Ordering::Greater,
+            (false, true) => Ordering::Less,
+            (false, false) => {
+                let ordering = compare_values(
+                    left,
+                    left_row,
+                    right,
+                    right_row,
+                    nan_ordering,
+                    collation,
+                )?;
+                if sort_expr.asc {
+                    ordering
+                } else {
+                    ordering.reverse()
+                }
+            }
+        };
+        if ordering != Ordering::Equal {
+            return Ok(ordering);
+        }
+    }
+    Ok(Ordering::Equal)
+}
+
+macro_rules! compare_primitive {
+    ($LEFT:expr, $LEFT_ROW:expr, $RIGHT:expr, $RIGHT_ROW:expr, $ARRAY_TYPE:ident) => {{
+        let left = $LEFT.as_any().downcast_ref::<$ARRAY_TYPE>().unwrap();
+        let right = $RIGHT.as_any().downcast_ref::<$ARRAY_TYPE>().unwrap();
+        Ok(left
+            .value($LEFT_ROW)
+            .partial_cmp(&right.value($RIGHT_ROW))
+            .unwrap_or(Ordering::Equal))
+    }};
+}
+
+macro_rules! compare_float {
+    ($LEFT:expr, $LEFT_ROW:expr, $RIGHT:expr, $RIGHT_ROW:expr, $NAN_ORDERING:expr, $ARRAY_TYPE:ident) => {{
+        let left = $LEFT.as_any().downcast_ref::<$ARRAY_TYPE>().unwrap();
+        let right = $RIGHT.as_any().downcast_ref::<$ARRAY_TYPE>().unwrap();
+        Ok(left
+            .value($LEFT_ROW)
+            .compare(right.value($RIGHT_ROW), $NAN_ORDERING))
+    }};
+}
+
+/// Compare two non-null values of arrays with the same type
+fn compare_values(
+    left: &ArrayRef,
+    left_row: usize,
+    right: &ArrayRef,
+    right_row: usize,
+    nan_ordering: NanOrdering,
+    collation: Collation,
+) -> Result<Ordering> {
+    match left.data_type() {
+        DataType::Boolean => {
+            compare_primitive!(left, left_row, right, right_row, BooleanArray)
+        }
+        DataType::UInt8 => {
+            compare_primitive!(left, left_row, right, right_row, UInt8Array)
+        }
+        DataType::UInt16 => {
+            compare_primitive!(left, left_row, right, right_row, UInt16Array)
+        }
+        DataType::UInt32 => {
+            compare_primitive!(left, left_row, right, right_row, UInt32Array)
+        }
+        DataType::UInt64 => {
+            compare_primitive!(left, left_row, right, right_row, UInt64Array)
+        }
+        DataType::Int8 => compare_primitive!(left, left_row, right, right_row, Int8Array),
+        DataType::Int16 => {
+            compare_primitive!(left, left_row, right, right_row, Int16Array)
+        }
+        DataType::Int32 => {
+            compare_primitive!(left, left_row, right, right_row, Int32Array)
+        }
+        DataType::Int64 => {
+            compare_primitive!(left, left_row, right, right_row, Int64Array)
+        }
+        DataType::Float32 => {
+            compare_float!(left, left_row, right, right_row, nan_ordering, Float32Array)
+        }
+        DataType::Float64 => {
+            compare_float!(left, left_row, right, right_row, nan_ordering, Float64Array)
+        }
+        DataType::Date(DateUnit::Day) => {
+            compare_primitive!(left, left_row, right, right_row, Date32Array)
+        }
+        DataType::Date(DateUnit::Millisecond) => {
+            compare_primitive!(left, left_row, right, right_row, Date64Array)
+        }
+        DataType::Time32(TimeUnit::Second) => {
+            compare_primitive!(left, left_row, right, right_row, Time32SecondArray)
+        }
+        DataType::Time32(TimeUnit::Millisecond) => {
+            compare_primitive!(left, left_row, right, right_row, Time32MillisecondArray)
+        }
+        DataType::Time64(TimeUnit::Microsecond) => {
+            compare_primitive!(left, left_row, right, right_row, Time64MicrosecondArray)
+        }
+        DataType::Time64(TimeUnit::Nanosecond) => {
+            compare_primitive!(left, left_row, right, right_row, Time64NanosecondArray)
+        }
+        DataType::Timestamp(TimeUnit::Second, _) => {
+            compare_primitive!(left, left_row, right, right_row, TimestampSecondArray)
+        }
+        DataType::Timestamp(TimeUnit::Millisecond, _) => compare_primitive!(
+            left,
+            left_row,
+            right,
+            right_row,
+            TimestampMillisecondArray
+        ),
+        DataType::Timestamp(TimeUnit::Microsecond, _) => compare_primitive!(
+            left,
+            left_row,
+            right,
+            right_row,
+            TimestampMicrosecondArray
+        ),
+        DataType::Timestamp(TimeUnit::Nanosecond, _) => {
+            compare_primitive!(left, left_row, right, right_row, TimestampNanosecondArray)
+        }
+        DataType::Utf8 => {
+            let left = left.as_any().downcast_ref::<BinaryArray>().unwrap();
+            let right = right.as_any().downcast_ref::<BinaryArray>().unwrap();
+            Ok(compare_strings(
+                left.value(left_row),
+                right.value(right_row),
+                collation,
+            ))
+        }
+        DataType::Binary => {
+            let left = left.as_any().downcast_ref::<BinaryArray>().unwrap();
+            let right = right.as_any().downcast_ref::<BinaryArray>().unwrap();
+            Ok(left.value(left_row).cmp(right.value(right_row)))
+        }
+        other => Err(ExecutionError::NotImplemented(format!(
+            "Sorting is not supported for type {:?}",
+            other
+        ))),
+    }
+}
+
+/// Build the columns of a batch from the rows of batches at the given (batch, row)
+/// positions, by concatenating the columns of the batches and taking the rows from them
+pub fn take_rows(
+    batches: &[Rc<RecordBatch>],
+    indices: &[(usize, usize)],
+    num_columns: usize,
+) -> Result<Vec<ArrayRef>> {
+    let mut starts = Vec::with_capacity(batches.len());
+    let mut num_rows = 0;
+    for batch in batches {
+        starts.push(num_rows);
+        num_rows += batch.num_rows();
+    }
+    let indices = UInt32Array::from(
+        indices
+            .iter()
+            .map(|(batch, row)| (starts[*batch] + row) as u32)
+            .collect::<Vec<u32>>(),
+    );
+    (0..num_columns)
+        .map(|i| {
+            let column = match batches {
+                [batch] => batch.column(i).clone(),
+                _ => {
+                    let columns: Vec<ArrayRef> = batches
+                        .iter()
+                        .map(|batch| batch.column(i).clone())
+                        .collect();
+                    compute::concat(&columns)?
+                }
+            };
+            Ok(compute::take(&column, &indices)?)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution::expression::CompiledExpr;
+    use crate::execution::relation::ValuesRelation;
+    use crate::logicalplan::ScalarValue;
+    use arrow::array::UInt64Array;
+    use arrow::datatypes::{DataType, Field};
+
+    /// Execution plan with one partition per value, where each partition produces a
+    /// single row containing its value
+    struct TestExec {
+        schema: Arc<Schema>,
+        values: Vec<u64>,
+    }
+
+    impl ExecutionPlan for TestExec {
+        fn schema(&self) -> Arc<Schema> {
+            self.schema.clone()
+        }
+
+        fn children(&self) -> Vec<Arc<ExecutionPlan>> {
+            vec![]
+        }
+
+        fn output_partitions(&self) -> usize {
+            self.values.len()
+        }
+
+        fn execute(&self, partition: usize) -> Result<Rc<RefCell<Relation>>> {
+            match self.values[partition] {
+                0 => Err(ExecutionError::General("partition failed".to_string())),
+                n => Ok(Rc::new(RefCell::new(ValuesRelation::new(
+                    self.schema.clone(),
+                    vec![vec![ScalarValue::UInt64(n)]],
+                )))),
+            }
+        }
+    }
+
+    fn test_exec(values: Vec<u64>) -> Arc<ExecutionPlan> {
+        Arc::new(TestExec {
+            schema: Arc::new(Schema::new(vec![Field::new("a", DataType::UInt64, false)])),
+            values,
+        })
+    }
+
+    fn collect_values(relation: Rc<RefCell<Relation>>) -> Result<Vec<u64>> {
+        let mut values = vec![];
+        loop {
+            let batch = relation.borrow_mut().next()?;
+            match batch {
+                Some(batch) => {
+                    let array = batch
+                        .column(0)
+                        .as_any()
+                        .downcast_ref::<UInt64Array>()
+                        .unwrap();
+                    for i in 0..batch.num_rows() {
+                        values.push(array.value(i));
+                    }
+                }
+                None => break,
+            }
+        }
+        values.sort();
+        Ok(values)
+    }
+
+    #[test]
+    fn merge_partitions() {
+        for concurrency in 1..5 {
+            let merge = MergeExec::new(test_exec((1..11).collect()), concurrency);
+            assert_eq!(1, merge.output_partitions());
+            let values = collect_values(merge.execute(0).unwrap()).unwrap();
+            assert_eq!((1..11).collect::<Vec<u64>>(), values);
+        }
+    }
+
+    #[test]
+    fn merge_no_partitions() {
+        let merge = MergeExec::new(test_exec(vec![]), 4);
+        let values = collect_values(merge.execute(0).unwrap()).unwrap();
+        assert!(values.is_empty());
+    }
+
+    #[test]
+    fn merge_partition_error() {
+        let merge = MergeExec::new(test_exec(vec![1, 2, 0, 3]), 2);
+        assert!(collect_values(merge.execute(0).unwrap()).is_err());
+    }
+
+    #[test]
+    fn merge_invalid_partition() {
+        let merge = MergeExec::new(test_exec(vec![1]), 2);
+        assert!(merge.execute(1).is_err());
+    }
+
+    #[test]
+    fn sort_preserving_merge() {
+        let input = batches_exec(vec![
+            vec![vec![1, 4], vec![7, 10]],
+            vec![vec![2, 5, 8]],
+            vec![],
+            vec![vec![], vec![3], vec![6, 9]],
+        ]);
+        for batch_size in 1..5 {
+            let merge = SortPreservingMergeExec::new(
+                input.clone(),
+                sort_expr(true),
+                NanOrdering::NanLast,
+                Collation::Binary,
+                batch_size,
+            );
+            assert_eq!(1, merge.output_partitions());
+            let (values, batches) = collect_batches(merge.execute(0).unwrap());
+            assert_eq!((1..11).collect::<Vec<u64>>(), values);
+            assert!(batches.iter().all(|n| *n <= batch_size));
+        }
+    }
+
+    #[test]
+    fn sort_preserving_merge_descending() {
+        let input = batches_exec(vec![vec![vec![9, 5, 5], vec![1]], vec![vec![8, 6, 2]]]);
+        let merge = SortPreservingMergeExec::new(
+            input,
+            sort_expr(false),
+            NanOrdering::NanLast,
+            Collation::Binary,
+            1024,
+        );
+        let (values, _) = collect_batches(merge.execute(0).unwrap());
+        assert_eq!(vec![9, 8, 6, 5, 5, 2, 1], values);
+    }
+
+    fn sort_expr(asc: bool) -> Vec<SortExpr> {
+        let f: CompiledExpr =
+            Arc::new(|batch: &RecordBatch| Ok((*batch.column(0)).clone()));
+        vec![SortExpr {
+            expr: RuntimeExpr::Compiled {
+                name: "a".to_string(),
+                f,
+                t: DataType::UInt64,
+            },
+            asc,
+            nulls_first: asc,
+        }]
+    }
+
+    #[test]
+    fn compare_float_values() {
+        let values: ArrayRef = Arc::new(Float64Array::from(vec![
+            std::f64::NAN,
+            std::f64::INFINITY,
+            -0.0,
+            0.0,
+            -std::f64::NAN,
+        ]));
+        let compare = |left, right, nan_ordering| {
+            compare_values(
+                &values,
+                left,
+                &values,
+                right,
+                nan_ordering,
+                Collation::Binary,
+            )
+            .unwrap()
+        };
+
+        assert_eq!(Ordering::Greater, compare(0, 1, NanOrdering::NanLast));
+        assert_eq!(Ordering::Equal, compare(0, 4, NanOrdering::NanLast));
+        assert_eq!(Ordering::Equal, compare(2, 3, NanOrdering::NanLast));
+
+        assert_eq!(Ordering::Greater, compare(0, 1, NanOrdering::TotalOrder));
+        assert_eq!(Ordering::Less, compare(4, 2, NanOrdering::TotalOrder));
+        assert_eq!(Ordering::Less, compare(2, 3, NanOrdering::TotalOrder));
+        assert_eq!(Ordering::Equal, compare(0, 0, NanOrdering::TotalOrder));
+    }
+
+    #[test]
+    fn compare_string_values() {
+        let values: ArrayRef =
+            Arc::new(BinaryArray::from(vec!["apple", "Apple", "banana"]));
+        let compare = |left, right, collation| {
+            compare_values(
+                &values,
+                left,
+                &values,
+                right,
+                NanOrdering::NanLast,
+                collation,
+            )
+            .unwrap()
+        };
+
+        assert_eq!(Ordering::Greater, compare(0, 1, Collation::Binary));
+        assert_eq!(Ordering::Less, compare(1, 2, Collation::Binary));
+        assert_eq!(Ordering::Equal, compare(0, 1, Collation::CaseInsensitive));
+        assert_eq!(Ordering::Greater, compare(2, 1, Collation::CaseInsensitive));
+    }
+
+    /// Collect the values of a relation in the order they are produced, along with the
+    /// number of rows in each batch
+    fn collect_batches(relation: Rc<RefCell<Relation>>) -> (Vec<u64>, Vec<usize>) {
+        let mut values = vec![];
+        let mut batches = vec![];
+        while let Some(batch) = relation.borrow_mut().next().unwrap() {
+            let array = batch
+                .column(0)
+                .as_any()
+                .downcast_ref::<UInt64Array>()
+                .unwrap();
+            for i in 0..batch.num_rows() {
+                values.push(array.value(i));
+            }
+            batches.push(batch.num_rows());
+        }
+        (values, batches)
+    }
+
+    /// Execution plan where each partition produces the given batches of values
+    struct BatchesExec {
+        schema: Arc<Schema>,
+        partitions: Vec<Vec<Vec<u64>>>,
+    }
+
+    impl ExecutionPlan for BatchesExec {
+        fn schema(&self) -> Arc<Schema> {
+            self.schema.clone()
+        }
+
+        fn children(&self) -> Vec<Arc<ExecutionPlan>> {
+            vec![]
+        }
+
+        fn output_partitions(&self) -> usize {
+            self.partitions.len()
+        }
+
+        fn execute(&self, partition: usize) -> Result<Rc<RefCell<Relation>>> {
+            Ok(Rc::new(RefCell::new(BatchesRelation {
+                schema: self.schema.clone(),
+                batches: self.partitions[partition].clone(),
+            })))
+        }
+    }
+
+    struct BatchesRelation {
+        schema: Arc<Schema>,
+        batches: Vec<Vec<u64>>,
+    }
+
+    impl Relation for BatchesRelation {
+        fn next(&mut self) -> Result<Option<RecordBatch>> {
+            if self.batches.is_empty() {
+                return Ok(None);
+            }
+            let values = self.batches.remove(0);
+            Ok(Some(RecordBatch::new(
+                self.schema.clone(),
+                vec![Arc::new(UInt64Array::from(values)) as ArrayRef],
+            )))
+        }
+
+        fn schema(&self) -> &Arc<Schema> {
+            &self.schema
+        }
+    }
+
+    fn batches_exec(partitions: Vec<Vec<Vec<u64>>>) -> Arc<ExecutionPlan> {
+        Arc::new(BatchesExec {
+            schema: Arc::new(Schema::new(vec![Field::new("a", DataType::UInt64, false)])),
+            partitions,
+        })
+    }
+}
@@ -16,12 +16,33 @@
 // under the License.
 
 pub mod aggregate;
+pub mod binary;
+pub mod catalog;
 pub mod context;
+pub mod dataframe;
 pub mod datasource;
+pub mod deserialize;
 pub mod error;
 pub mod expression;
 pub mod filter;
+pub mod functions;
+pub mod hash;
 pub mod limit;
+pub mod list;
+pub mod memory;
+pub mod merge;
+pub mod object_store;
+pub mod parquet;
+pub mod partitioned;
 pub mod physicalplan;
+pub mod pretty;
 pub mod projection;
+pub mod pruning;
 pub mod relation;
+pub mod repartition;
+pub mod row;
+pub mod scheduler;
+pub mod sort;
+pub mod stream;
+pub mod udf;
+pub mod unnest;
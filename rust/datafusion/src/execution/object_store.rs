@@ -0,0 +1,116 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Object stores, which give access to the files of a table wherever they are stored.
+//! Table locations can be local paths or URLs such as `s3://bucket/key`, where the URL
+//! scheme selects the object store that is used.
+
+use std::fs::{self, File};
+use std::io::{Read, Seek};
+
+use super::datasource::list_files;
+use super::error::Result;
+
+/// A reader of the contents of an object, which can seek to any position in it
+pub trait ObjectReader: Read + Seek + Send {}
+
+impl<T: Read + Seek + Send> ObjectReader for T {}
+
+/// A store of objects, such as files on a local disk or in a remote storage service
+pub trait ObjectStore: Send + Sync {
+    /// Find the objects that a path refers to, in sorted order. The path can refer to a
+    /// single object or to a directory (or prefix) of objects.
+    fn list(&self, path: &str) -> Result<Vec<String>>;
+    /// Get the size of an object in bytes
+    fn size(&self, path: &str) -> Result<u64>;
+    /// Open an object for reading
+    fn open(&self, path: &str) -> Result<Box<ObjectReader>>;
+}
+
+/// Object store for files on the local file system. Paths can optionally have a
+/// `file://` prefix.
+pub struct LocalFileSystem;
+
+impl LocalFileSystem {
+    fn local_path(path: &str) -> &str {
+        if path.starts_with("file://") {
+            &path["file://".len()..]
+        } else {
+            path
+        }
+    }
+}
+
+impl ObjectStore for LocalFileSystem {
+    fn list(&self, path: &str) -> Result<Vec<String>> {
+        list_files(Self::local_path(path))
+    }
+
+    fn size(&self, path: &str) -> Result<u64> {
+        Ok(fs::metadata(Self::local_path(path))?.len())
+    }
+
+    fn open(&self, path: &str) -> Result<Box<ObjectReader>> {
+        Ok(Box::new(File::open(Self::local_path(path))?))
+    }
+}
+
+/// Get the scheme of a URL such as `s3://bucket/key`, or `None` for a local path
+pub fn url_scheme(path: &str) -> Option<&str> {
+    match path.find("://") {
+        Some(i) if i > 0 && path[..i].chars().all(|c| c.is_ascii_alphanumeric()) => {
+            Some(&path[..i])
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    #[test]
+    fn scheme_of_url() {
+        assert_eq!(Some("s3"), url_scheme("s3://bucket/key.csv"));
+        assert_eq!(Some("file"), url_scheme("file:///data/file.csv"));
+        assert_eq!(None, url_scheme("/data/file.csv"));
+        assert_eq!(None, url_scheme("data/a://b.csv"));
+    }
+
+    #[test]
+    fn read_local_file() {
+        let dir = env::temp_dir().join("datafusion_read_local_file");
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("file.csv");
+        fs::write(&path, "a,b\n1,2\n").unwrap();
+        let filename = path.to_str().unwrap();
+        let url = format!("file://{}", filename);
+
+        let store = LocalFileSystem;
+        assert_eq!(vec![filename], store.list(&url).unwrap());
+        assert_eq!(8, store.size(&url).unwrap());
+        let mut contents = String::new();
+        store
+            .open(&url)
+            .unwrap()
+            .read_to_string(&mut contents)
+            .unwrap();
+        assert_eq!("a,b\n1,2\n", contents);
+        assert!(store.open("file:///does_not_exist.csv").is_err());
+    }
+}
@@ -0,0 +1,804 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Parquet data source
+
+use std::cell::RefCell;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::rc::Rc;
+use std::string::String;
+use std::sync::Arc;
+
+use arrow::array::ArrayRef;
+use arrow::builder::*;
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+
+use parquet::basic::{LogicalType, Type as PhysicalType};
+use parquet::column::reader::{ColumnReader, ColumnReaderImpl};
+use parquet::data_type::{ByteArray, DataType as ParquetDataType, Int96};
+use parquet::errors::ParquetError;
+use parquet::file::reader::{FileReader, Length, SerializedFileReader, TryClone};
+use parquet::file::statistics::Statistics as ParquetStatistics;
+use parquet::schema::types::ColumnDescriptor;
+
+use super::datasource::{
+    ColumnStatistics, ConcatDataSource, DataSource, Statistics, TableProvider,
+};
+use super::error::{ExecutionError, Result};
+use super::object_store::{LocalFileSystem, ObjectReader, ObjectStore};
+use super::pruning::PruningPredicate;
+use crate::logicalplan::ScalarValue;
+
+/// Parquet data source that reads some of the row groups of a file, decoding only the
+/// projected columns
+pub struct ParquetDataSource {
+    store: Arc<ObjectStore>,
+    filename: String,
+    schema: Arc<Schema>,
+    projection: Vec<usize>,
+    row_groups: Vec<usize>,
+    batch_size: usize,
+    reader: Option<SerializedFileReader<ObjectFile>>,
+    max_def_levels: Vec<i16>,
+    next_row_group: usize,
+    column_readers: Vec<ColumnReader>,
+    rows_remaining: usize,
+}
+
+impl ParquetDataSource {
+    /// Create a data source for the given row groups of a file. The file is not opened
+    /// until the first batch is read.
+    pub fn new(
+        filename: &str,
+        schema: Arc<Schema>,
+        projection: &Option<Vec<usize>>,
+        row_groups: Vec<usize>,
+        batch_size: usize,
+    ) -> Self {
+        Self::with_object_store(
+            Arc::new(LocalFileSystem),
+            filename,
+            schema,
+            projection,
+            row_groups,
+            batch_size,
+        )
+    }
+
+    /// Create a data source for the given row groups of a file that is read from an
+    /// object store
+    pub fn with_object_store(
+        store: Arc<ObjectStore>,
+        filename: &str,
+        schema: Arc<Schema>,
+        projection: &Option<Vec<usize>>,
+        row_groups: Vec<usize>,
+        batch_size: usize,
+    ) -> Self {
+        let projection = match projection {
+            Some(p) => p.clone(),
+            None => (0..schema.fields().len()).collect(),
+        };
+
+        let projected_fields: Vec<Field> = projection
+            .iter()
+            .map(|i| schema.fields()[*i].clone())
+            .collect();
+
+        Self {
+            store,
+            filename: filename.to_string(),
+            schema: Arc::new(Schema::new(projected_fields)),
+            projection,
+            row_groups,
+            batch_size,
+            reader: None,
+            max_def_levels: vec![],
+            next_row_group: 0,
+            column_readers: vec![],
+            rows_remaining: 0,
+        }
+    }
+
+    /// Open the file if it has not been opened yet
+    fn open(&mut self) -> Result<()> {
+        if self.reader.is_none() {
+            let file = ObjectFile::try_new(self.store.clone(), &self.filename)?;
+            let reader = SerializedFileReader::new(file)?;
+            let metadata = reader.metadata();
+            let schema_descr = metadata.file_metadata().schema_descr_ptr();
+            self.max_def_levels = self
+                .projection
+                .iter()
+                .map(|i| schema_descr.column(*i).max_def_level())
+                .collect();
+            self.reader = Some(reader);
+        }
+        Ok(())
+    }
+
+    /// Create readers for the projected columns of the next row group that has any rows.
+    /// Returns false if there are no row groups left.
+    fn next_row_group(&mut self) -> Result<bool> {
+        while self.rows_remaining == 0 {
+            if self.next_row_group >= self.row_groups.len() {
+                return Ok(false);
+            }
+            let reader = self.reader.as_ref().unwrap();
+            let row_group = reader.get_row_group(self.row_groups[self.next_row_group])?;
+            self.next_row_group += 1;
+
+            let mut column_readers = Vec::with_capacity(self.projection.len());
+            for i in &self.projection {
+                column_readers.push(row_group.get_column_reader(*i)?);
+            }
+            self.column_readers = column_readers;
+            self.rows_remaining = row_group.metadata().num_rows() as usize;
+        }
+        Ok(true)
+    }
+}
+
+impl DataSource for ParquetDataSource {
+    fn schema(&self) -> &Arc<Schema> {
+        &self.schema
+    }
+
+    fn next(&mut self) -> Result<Option<RecordBatch>> {
+        self.open()?;
+        if !self.next_row_group()? {
+            return Ok(None);
+        }
+
+        let batch_size = self.batch_size.min(self.rows_remaining);
+        let mut columns: Vec<ArrayRef> = Vec::with_capacity(self.column_readers.len());
+        for (i, reader) in self.column_readers.iter_mut().enumerate() {
+            columns.push(read_column(
+                reader,
+                self.schema.field(i).data_type(),
+                self.max_def_levels[i],
+                batch_size,
+            )?);
+        }
+        self.rows_remaining -= batch_size;
+
+        Ok(Some(RecordBatch::new(self.schema.clone(), columns)))
+    }
+}
+
+/// A file that is read from an object store, which the Parquet reader can clone to read
+/// several column chunks of a row group independently
+struct ObjectFile {
+    store: Arc<ObjectStore>,
+    filename: String,
+    len: u64,
+    position: u64,
+    reader: Box<ObjectReader>,
+}
+
+impl ObjectFile {
+    fn try_new(store: Arc<ObjectStore>, filename: &str) -> Result<Self> {
+        let len = store
+            .size(filename)
+            .map_err(|e| ExecutionError::data_source(filename, e))?;
+        let reader = store
+            .open(filename)
+            .map_err(|e| ExecutionError::data_source(filename, e))?;
+        Ok(Self {
+            store,
+            filename: filename.to_string(),
+            len,
+            position: 0,
+            reader,
+        })
+    }
+}
+
+impl Read for ObjectFile {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.reader.read(buf)?;
+        self.position += n as u64;
+        Ok(n)
+    }
+}
+
+impl Seek for ObjectFile {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.position = self.reader.seek(pos)?;
+        Ok(self.position)
+    }
+}
+
+impl Length for ObjectFile {
+    fn len(&self) -> u64 {
+        self.len
+    }
+}
+
+impl TryClone for ObjectFile {
+    fn try_clone(&self) -> parquet::errors::Result<Self> {
+        let mut file = Self::try_new(self.store.clone(), &self.filename)
+            .map_err(|e| ParquetError::General(format!("{:?}", e)))?;
+        file.seek(SeekFrom::Start(self.position))?;
+        Ok(file)
+    }
+}
+
+/// Represents one or more Parquet files with the same schema, where each row group is a
+/// separate partition
+pub struct ParquetProvider {
+    store: Arc<ObjectStore>,
+    schema: Arc<Schema>,
+    files: Vec<ParquetFile>,
+    /// The index of the file and of the row group within the file for each partition
+    partitions: Vec<(usize, usize)>,
+    statistics: Statistics,
+}
+
+/// Metadata of a single Parquet file
+struct ParquetFile {
+    filename: String,
+    num_rows: usize,
+    total_byte_size: usize,
+    row_group_statistics: Vec<Vec<ColumnStatistics>>,
+}
+
+impl ParquetFile {
+    /// Read the metadata of a file, returning it along with the fields of the file
+    fn try_new(store: Arc<ObjectStore>, filename: &str) -> Result<(Self, Vec<Field>)> {
+        let reader = SerializedFileReader::new(ObjectFile::try_new(store, filename)?)?;
+        let metadata = reader.metadata();
+        let schema_descr = metadata.file_metadata().schema_descr_ptr();
+
+        let fields = (0..schema_descr.num_columns())
+            .map(|i| to_arrow_field(&schema_descr.column(i)))
+            .collect::<Result<Vec<Field>>>()?;
+
+        let row_groups = metadata.row_groups();
+        let row_group_statistics = row_groups
+            .iter()
+            .map(|row_group| {
+                fields
+                    .iter()
+                    .enumerate()
+                    .map(|(i, field)| {
+                        to_column_statistics(
+                            row_group.column(i).statistics(),
+                            field.data_type(),
+                        )
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let file = Self {
+            filename: filename.to_string(),
+            num_rows: metadata.file_metadata().num_rows() as usize,
+            total_byte_size: row_groups
+                .iter()
+                .map(|row_group| row_group.total_byte_size() as usize)
+                .sum(),
+            row_group_statistics,
+        };
+        Ok((file, fields))
+    }
+}
+
+impl ParquetProvider {
+    /// Create a provider for a Parquet file, reading the schema from the file metadata
+    pub fn try_new(filename: &str) -> Result<Self> {
+        Self::try_new_partitioned(&[filename])
+    }
+
+    /// Create a provider for a table that is stored in several Parquet files, which
+    /// must all have the same schema
+    pub fn try_new_partitioned(filenames: &[&str]) -> Result<Self> {
+        Self::try_new_with_object_store(Arc::new(LocalFileSystem), filenames)
+    }
+
+    /// Create a provider for one or more Parquet files with the same schema that are
+    /// read from an object store, such as files in a remote storage service
+    pub fn try_new_with_object_store(
+        store: Arc<ObjectStore>,
+        filenames: &[&str],
+    ) -> Result<Self> {
+        let mut schema: Option<Vec<Field>> = None;
+        let mut files = Vec::with_capacity(filenames.len());
+        for filename in filenames {
+            let (file, fields) = ParquetFile::try_new(store.clone(), filename)?;
+            match schema {
+                Some(ref expected) if *expected != fields => {
+                    return Err(ExecutionError::General(format!(
+                        "Parquet file '{}' has a different schema than '{}'",
+                        filename, filenames[0]
+                    )));
+                }
+                Some(_) => {}
+                None => schema = Some(fields),
+            }
+            files.push(file);
+        }
+
+        let schema = match schema {
+            Some(fields) => Arc::new(Schema::new(fields)),
+            None => {
+                return Err(ExecutionError::General(
+                    "No Parquet files to read".to_string(),
+                ));
+            }
+        };
+
+        let partitions = files
+            .iter()
+            .enumerate()
+            .flat_map(|(i, file)| {
+                (0..file.row_group_statistics.len()).map(move |row_group| (i, row_group))
+            })
+            .collect();
+
+        let statistics = Statistics {
+            num_rows: Some(files.iter().map(|file| file.num_rows).sum()),
+            total_byte_size: Some(files.iter().map(|file| file.total_byte_size).sum()),
+            column_statistics: None,
+        };
+
+        Ok(Self {
+            store,
+            schema,
+            files,
+            partitions,
+            statistics,
+        })
+    }
+
+    fn open(
+        &self,
+        file: usize,
+        row_groups: Vec<usize>,
+        projection: &Option<Vec<usize>>,
+        batch_size: usize,
+    ) -> ParquetDataSource {
+        ParquetDataSource::with_object_store(
+            self.store.clone(),
+            &self.files[file].filename,
+            self.schema.clone(),
+            projection,
+            row_groups,
+            batch_size,
+        )
+    }
+}
+
+impl TableProvider for ParquetProvider {
+    fn schema(&self) -> &Arc<Schema> {
+        &self.schema
+    }
+
+    fn scan(
+        &self,
+        projection: &Option<Vec<usize>>,
+        batch_size: usize,
+    ) -> Result<Rc<RefCell<DataSource>>> {
+        let sources = self
+            .files
+            .iter()
+            .enumerate()
+            .map(|(i, file)| {
+                let row_groups = (0..file.row_group_statistics.len()).collect();
+                Box::new(self.open(i, row_groups, projection, batch_size))
+                    as Box<DataSource>
+            })
+            .collect();
+        Ok(Rc::new(RefCell::new(ConcatDataSource::new(sources))))
+    }
+
+    fn partitions(&self) -> usize {
+        self.partitions.len().max(1)
+    }
+
+    fn scan_partition(
+        &self,
+        projection: &Option<Vec<usize>>,
+        batch_size: usize,
+        partition: usize,
+    ) -> Result<Rc<RefCell<DataSource>>> {
+        let source = match self.partitions.get(partition) {
+            Some((file, row_group)) => {
+                self.open(*file, vec![*row_group], projection, batch_size)
+            }
+            None => self.open(0, vec![], projection, batch_size),
+        };
+        Ok(Rc::new(RefCell::new(source)))
+    }
+
+    fn scan_partition_with_predicate(
+        &self,
+        projection: &Option<Vec<usize>>,
+        predicate: &PruningPredicate,
+        batch_size: usize,
+        partition: usize,
+    ) -> Result<Rc<RefCell<DataSource>>> {
+        if let Some((file, row_group)) = self.partitions.get(partition) {
+            let statistics = &self.files[*file].row_group_statistics[*row_group];
+            if !predicate.may_match(statistics) {
+                debug!(
+                    "Skipping row group {} of {}, which cannot match the predicate",
+                    row_group, self.files[*file].filename
+                );
+                let source = self.open(*file, vec![], projection, batch_size);
+                return Ok(Rc::new(RefCell::new(source)));
+            }
+        }
+        self.scan_partition(projection, batch_size, partition)
+    }
+
+    fn statistics(&self) -> Statistics {
+        self.statistics.clone()
+    }
+
+    fn is_static(&self) -> bool {
+        true
+    }
+}
+
+macro_rules! min_max {
+    ($STATS:expr, $VARIANT:ident) => {
+        Some((
+            ScalarValue::$VARIANT(*$STATS.min()),
+            ScalarValue::$VARIANT(*$STATS.max()),
+        ))
+    };
+    ($STATS:expr, $VARIANT:ident, $TY:ty) => {
+        Some((
+            ScalarValue::$VARIANT(*$STATS.min() as $TY),
+            ScalarValue::$VARIANT(*$STATS.max() as $TY),
+        ))
+    };
+}
+
+/// Convert the statistics of a column chunk, using the Arrow type of the column for the
+/// min and max values. Min and max values are not used for unsigned integers, which
+/// older writers compared as signed values, or for binary and INT96 columns.
+fn to_column_statistics(
+    statistics: Option<&ParquetStatistics>,
+    data_type: &DataType,
+) -> ColumnStatistics {
+    let statistics = match statistics {
+        Some(statistics) => statistics,
+        None => return ColumnStatistics::default(),
+    };
+
+    let min_max = if statistics.has_min_max_set() {
+        match (statistics, data_type) {
+            (ParquetStatistics::Boolean(s), DataType::Boolean) => min_max!(s, Boolean),
+            (ParquetStatistics::Int32(s), DataType::Int8) => min_max!(s, Int8, i8),
+            (ParquetStatistics::Int32(s), DataType::Int16) => min_max!(s, Int16, i16),
+            (ParquetStatistics::Int32(s), DataType::Int32) => min_max!(s, Int32),
+            (ParquetStatistics::Int64(s), DataType::Int64) => min_max!(s, Int64),
+            (ParquetStatistics::Float(s), DataType::Float32) => min_max!(s, Float32),
+            (ParquetStatistics::Double(s), DataType::Float64) => min_max!(s, Float64),
+            _ => None,
+        }
+    } else {
+        None
+    };
+
+    let (min_value, max_value) = match min_max {
+        Some((min, max)) => (Some(min), Some(max)),
+        None => (None, None),
+    };
+    ColumnStatistics {
+        null_count: Some(statistics.null_count() as usize),
+        min_value,
+        max_value,
+    }
+}
+
+/// Create the Arrow field for a Parquet column. Only columns that are not repeated are
+/// supported.
+fn to_arrow_field(column: &ColumnDescriptor) -> Result<Field> {
+    if column.max_rep_level() > 0 {
+        return Err(ExecutionError::NotImplemented(format!(
+            "Repeated Parquet column '{}' is not supported",
+            column.path()
+        )));
+    }
+
+    let data_type = match (column.physical_type(), column.logical_type()) {
+        (PhysicalType::BOOLEAN, _) => DataType::Boolean,
+        (PhysicalType::INT32, LogicalType::INT_8) => DataType::Int8,
+        (PhysicalType::INT32, LogicalType::INT_16) => DataType::Int16,
+        (PhysicalType::INT32, LogicalType::UINT_8) => DataType::UInt8,
+        (PhysicalType::INT32, LogicalType::UINT_16) => DataType::UInt16,
+        (PhysicalType::INT32, LogicalType::UINT_32) => DataType::UInt32,
+        (PhysicalType::INT32, _) => DataType::Int32,
+        (PhysicalType::INT64, LogicalType::UINT_64) => DataType::UInt64,
+        (PhysicalType::INT64, _) => DataType::Int64,
+        // INT96 timestamps are read as the number of nanoseconds since the epoch
+        (PhysicalType::INT96, _) => DataType::Int64,
+        (PhysicalType::FLOAT, _) => DataType::Float32,
+        (PhysicalType::DOUBLE, _) => DataType::Float64,
+        (PhysicalType::BYTE_ARRAY, _) | (PhysicalType::FIXED_LEN_BYTE_ARRAY, _) => {
+            DataType::Utf8
+        }
+    };
+
+    Ok(Field::new(
+        column.name(),
+        data_type,
+        column.max_def_level() > 0,
+    ))
+}
+
+/// Read `batch_size` values from a column, with `None` for each null value
+fn read_batch<T: ParquetDataType>(
+    reader: &mut ColumnReaderImpl<T>,
+    max_def_level: i16,
+    batch_size: usize,
+) -> Result<Vec<Option<T::T>>> {
+    let mut values: Vec<T::T> = vec![Default::default(); batch_size];
+
+    if max_def_level == 0 {
+        let (values_read, _) =
+            reader.read_batch(batch_size, None, None, &mut values[..])?;
+        values.truncate(values_read);
+        return Ok(values.into_iter().map(Some).collect());
+    }
+
+    // values are only stored for the levels that are defined
+    let mut def_levels = vec![0; batch_size];
+    let (_, levels_read) = reader.read_batch(
+        batch_size,
+        Some(&mut def_levels[..]),
+        None,
+        &mut values[..],
+    )?;
+    let mut values = values.into_iter();
+    Ok(def_levels[..levels_read]
+        .iter()
+        .map(|level| {
+            if *level == max_def_level {
+                values.next()
+            } else {
+                None
+            }
+        })
+        .collect())
+}
+
+macro_rules! read_primitive {
+    ($READER:expr, $BUILDER:ident, $MAX_DEF_LEVEL:expr, $BATCH_SIZE:expr, $CONVERT:expr) => {{
+        let values = read_batch($READER, $MAX_DEF_LEVEL, $BATCH_SIZE)?;
+        let mut builder = $BUILDER::new(values.len());
+        for value in values {
+            builder.append_option(value.map($CONVERT))?;
+        }
+        Ok(Arc::new(builder.finish()) as ArrayRef)
+    }};
+}
+
+/// Read `batch_size` values from a column into an array of the given type
+fn read_column(
+    reader: &mut ColumnReader,
+    data_type: &DataType,
+    max_def_level: i16,
+    batch_size: usize,
+) -> Result<ArrayRef> {
+    match (reader, data_type) {
+        (ColumnReader::BoolColumnReader(r), DataType::Boolean) => {
+            read_primitive!(r, BooleanBuilder, max_def_level, batch_size, |v| v)
+        }
+        (ColumnReader::Int32ColumnReader(r), DataType::Int8) => {
+            read_primitive!(r, Int8Builder, max_def_level, batch_size, |v| v as i8)
+        }
+        (ColumnReader::Int32ColumnReader(r), DataType::Int16) => {
+            read_primitive!(r, Int16Builder, max_def_level, batch_size, |v| v as i16)
+        }
+        (ColumnReader::Int32ColumnReader(r), DataType::Int32) => {
+            read_primitive!(r, Int32Builder, max_def_level, batch_size, |v| v)
+        }
+        (ColumnReader::Int32ColumnReader(r), DataType::UInt8) => {
+            read_primitive!(r, UInt8Builder, max_def_level, batch_size, |v| v as u8)
+        }
+        (ColumnReader::Int32ColumnReader(r), DataType::UInt16) => {
+            read_primitive!(r, UInt16Builder, max_def_level, batch_size, |v| v as u16)
+        }
+        (ColumnReader::Int32ColumnReader(r), DataType::UInt32) => {
+            read_primitive!(r, UInt32Builder, max_def_level, batch_size, |v| v as u32)
+        }
+        (ColumnReader::Int64ColumnReader(r), DataType::Int64) => {
+            read_primitive!(r, Int64Builder, max_def_level, batch_size, |v| v)
+        }
+        (ColumnReader::Int64ColumnReader(r), DataType::UInt64) => {
+            read_primitive!(r, UInt64Builder, max_def_level, batch_size, |v| v as u64)
+        }
+        (ColumnReader::Int96ColumnReader(r), DataType::Int64) => {
+            read_primitive!(r, Int64Builder, max_def_level, batch_size, |v| {
+                int96_to_nanos(&v)
+            })
+        }
+        (ColumnReader::FloatColumnReader(r), DataType::Float32) => {
+            read_primitive!(r, Float32Builder, max_def_level, batch_size, |v| v)
+        }
+        (ColumnReader::DoubleColumnReader(r), DataType::Float64) => {
+            read_primitive!(r, Float64Builder, max_def_level, batch_size, |v| v)
+        }
+        (ColumnReader::ByteArrayColumnReader(r), DataType::Utf8) => {
+            let values = read_batch(r, max_def_level, batch_size)?;
+            build_binary_array(values)
+        }
+        (ColumnReader::FixedLenByteArrayColumnReader(r), DataType::Utf8) => {
+            let values = read_batch(r, max_def_level, batch_size)?;
+            build_binary_array(values)
+        }
+        (_, data_type) => Err(ExecutionError::InternalError(format!(
+            "Cannot read Parquet column as {:?}",
+            data_type
+        ))),
+    }
+}
+
+/// Build a binary array from byte array values
+fn build_binary_array(values: Vec<Option<ByteArray>>) -> Result<ArrayRef> {
+    let mut builder = BinaryBuilder::new(values.len());
+    for value in values {
+        match value {
+            Some(value) => {
+                for byte in value.data() {
+                    builder.append_value(*byte)?;
+                }
+                builder.append(true)?;
+            }
+            None => builder.append_null()?,
+        }
+    }
+    Ok(Arc::new(builder.finish()) as ArrayRef)
+}
+
+/// Convert an INT96 timestamp, which holds the nanoseconds within a day followed by the
+/// Julian day, to the number of nanoseconds since the epoch
+fn int96_to_nanos(value: &Int96) -> i64 {
+    const JULIAN_DAY_OF_EPOCH: i64 = 2_440_588;
+    const NANOS_PER_DAY: i64 = 86_400_000_000_000;
+
+    let data = value.data();
+    let nanos = ((data[1] as i64) << 32) + data[0] as i64;
+    (data[2] as i64 - JULIAN_DAY_OF_EPOCH) * NANOS_PER_DAY + nanos
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logicalplan::{Expr, Operator};
+    use arrow::array::{BinaryArray, BooleanArray, Int32Array};
+    use std::env;
+
+    #[test]
+    fn read_alltypes_plain() {
+        let provider =
+            ParquetProvider::try_new(&test_file("alltypes_plain.parquet")).unwrap();
+        assert_eq!(11, provider.schema().fields().len());
+        assert_eq!(&DataType::Int32, provider.schema().field(0).data_type());
+        assert_eq!(&DataType::Boolean, provider.schema().field(1).data_type());
+        assert_eq!(Some(8), provider.statistics().num_rows);
+
+        let ds = provider.scan(&Some(vec![0, 1, 9]), 1024).unwrap();
+        let mut ds = ds.borrow_mut();
+        assert_eq!(3, ds.schema().fields().len());
+        let batch = ds.next().unwrap().unwrap();
+        assert_eq!(8, batch.num_rows());
+
+        let id = batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<Int32Array>()
+            .unwrap();
+        let bool_col = batch
+            .column(1)
+            .as_any()
+            .downcast_ref::<BooleanArray>()
+            .unwrap();
+        let string_col = batch
+            .column(2)
+            .as_any()
+            .downcast_ref::<BinaryArray>()
+            .unwrap();
+        assert_eq!(4, id.value(0));
+        assert_eq!(true, bool_col.value(0));
+        assert_eq!("0", string_col.get_string(0));
+        assert_eq!(5, id.value(1));
+        assert_eq!(false, bool_col.value(1));
+        assert_eq!("1", string_col.get_string(1));
+
+        assert!(ds.next().unwrap().is_none());
+    }
+
+    #[test]
+    fn read_in_batches() {
+        let provider =
+            ParquetProvider::try_new(&test_file("alltypes_plain.parquet")).unwrap();
+        let ds = provider.scan_partition(&Some(vec![0]), 3, 0).unwrap();
+        let mut row_counts = vec![];
+        while let Some(batch) = ds.borrow_mut().next().unwrap() {
+            row_counts.push(batch.num_rows());
+        }
+        assert_eq!(vec![3, 3, 2], row_counts);
+    }
+
+    #[test]
+    fn skip_row_groups_by_statistics() {
+        let provider =
+            ParquetProvider::try_new(&test_file("alltypes_plain.parquet")).unwrap();
+        let row_count = |predicate: &Expr| {
+            let predicate = PruningPredicate::try_new(predicate).unwrap();
+            let ds = provider
+                .scan_partition_with_predicate(&Some(vec![0]), &predicate, 1024, 0)
+                .unwrap();
+            let mut rows = 0;
+            while let Some(batch) = ds.borrow_mut().next().unwrap() {
+                rows += batch.num_rows();
+            }
+            rows
+        };
+
+        let id_gt = |n: i32| Expr::BinaryExpr {
+            left: Rc::new(Expr::Column(0)),
+            op: Operator::Gt,
+            right: Rc::new(Expr::Literal(ScalarValue::Int32(n))),
+        };
+        assert_eq!(8, row_count(&id_gt(5)));
+        assert_eq!(0, row_count(&id_gt(100)));
+    }
+
+    #[test]
+    fn missing_file() {
+        assert!(ParquetProvider::try_new("does_not_exist.parquet").is_err());
+    }
+
+    #[test]
+    fn read_multiple_files() {
+        let filename = test_file("alltypes_plain.parquet");
+        let provider =
+            ParquetProvider::try_new_partitioned(&[filename.as_str(), filename.as_str()])
+                .unwrap();
+        assert_eq!(2, provider.partitions());
+        assert_eq!(Some(16), provider.statistics().num_rows);
+
+        let ds = provider.scan(&Some(vec![0]), 5).unwrap();
+        let mut row_counts = vec![];
+        while let Some(batch) = ds.borrow_mut().next().unwrap() {
+            row_counts.push(batch.num_rows());
+        }
+        assert_eq!(vec![5, 3, 5, 3], row_counts);
+
+        assert!(ParquetProvider::try_new_partitioned(&[]).is_err());
+    }
+
+    #[test]
+    fn convert_int96() {
+        let mut value = Int96::new();
+        value.set_data(0, 0, 2_440_589);
+        assert_eq!(86_400_000_000_000, int96_to_nanos(&value));
+    }
+
+    fn test_file(filename: &str) -> String {
+        let dir = env::var("PARQUET_TEST_DATA")
+            .expect("PARQUET_TEST_DATA environment variable is not set");
+        format!("{}/{}", dir, filename)
+    }
+}
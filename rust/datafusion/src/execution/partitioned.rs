@@ -0,0 +1,480 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Tables that are stored in Hive-style `key=value` partition directories
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use arrow::array::ArrayRef;
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+
+use super::super::logicalplan::ScalarValue;
+use super::datasource::{
+    ColumnStatistics, ConcatDataSource, DataSource, Statistics, TableProvider,
+};
+use super::error::{ExecutionError, Result};
+use super::pruning::{
+    parse_partition_path, Partition, PruningPredicate, DEFAULT_PARTITION_NAME,
+};
+use super::relation::scalars_to_array;
+
+/// Represents a table that is stored in partition directories such as
+/// `events/date=2019-01-01/part-0.parquet`. The keys of the partition directories are
+/// exposed as columns after the columns of the files, with the type `Int64` when all of
+/// their values are integers and `Utf8` otherwise.
+pub struct PartitionedTable {
+    schema: Arc<Schema>,
+    num_file_columns: usize,
+    partitions: Vec<Partition>,
+    providers: Vec<Arc<TableProvider>>,
+    /// The index of the partition and the partition of its provider, for each partition
+    /// that the table can be scanned as
+    scan_partitions: Vec<(usize, usize)>,
+}
+
+impl PartitionedTable {
+    /// Create a table from the partition directories below `path`, using
+    /// `create_provider` to create a provider for the files of each partition. The
+    /// providers must all have the same schema.
+    pub fn try_new<F>(path: &str, create_provider: F) -> Result<Self>
+    where
+        F: Fn(&[&str]) -> Result<Arc<TableProvider>>,
+    {
+        let root = Path::new(path);
+        let mut files = BTreeMap::new();
+        find_files(root, &mut files)?;
+        if files.is_empty() {
+            return Err(ExecutionError::General(format!(
+                "No files found in '{}'",
+                path
+            )));
+        }
+
+        // the partition path of each directory is relative to the table directory
+        let directories: Vec<(String, Vec<String>)> = files
+            .into_iter()
+            .map(|(dir, filenames)| {
+                let relative = Path::new(&dir)
+                    .strip_prefix(root)
+                    .map(|p| p.to_string_lossy().to_string())
+                    .unwrap_or(dir);
+                (relative, filenames)
+            })
+            .collect();
+
+        let partition_schema = infer_partition_schema(&directories);
+        let mut partitions = Vec::with_capacity(directories.len());
+        let mut providers: Vec<Arc<TableProvider>> =
+            Vec::with_capacity(directories.len());
+        for (dir, filenames) in &directories {
+            let filenames: Vec<&str> = filenames.iter().map(|f| f.as_str()).collect();
+            let provider = create_provider(&filenames)?;
+            if let Some(first) = providers.first() {
+                if first.schema().fields() != provider.schema().fields() {
+                    return Err(ExecutionError::General(format!(
+                        "Partition '{}' has a different schema than '{}'",
+                        dir, partitions[0].path
+                    )));
+                }
+            }
+            partitions.push(Partition::try_new(dir, &partition_schema)?);
+            providers.push(provider);
+        }
+
+        let file_fields = providers[0].schema().fields().clone();
+        let num_file_columns = file_fields.len();
+        let mut fields = file_fields;
+        fields.extend(partition_schema.fields().iter().cloned());
+
+        let scan_partitions = providers
+            .iter()
+            .enumerate()
+            .flat_map(|(i, provider)| (0..provider.partitions()).map(move |j| (i, j)))
+            .collect();
+
+        Ok(Self {
+            schema: Arc::new(Schema::new(fields)),
+            num_file_columns,
+            partitions,
+            providers,
+            scan_partitions,
+        })
+    }
+
+    /// Wrap a data source for the files of a partition so that it also returns the
+    /// projected partition columns
+    fn open<F>(
+        &self,
+        partition: usize,
+        projection: &Option<Vec<usize>>,
+        scan: F,
+    ) -> Result<PartitionDataSource>
+    where
+        F: FnOnce(&Option<Vec<usize>>) -> Result<Rc<RefCell<DataSource>>>,
+    {
+        let projection = match projection {
+            Some(p) => p.clone(),
+            None => (0..self.schema.fields().len()).collect(),
+        };
+
+        let mut file_projection = vec![];
+        let mut columns = vec![];
+        for i in &projection {
+            if *i < self.num_file_columns {
+                columns.push(PartitionColumn::File(file_projection.len()));
+                file_projection.push(*i);
+            } else {
+                let value =
+                    &self.partitions[partition].values[*i - self.num_file_columns];
+                columns.push(PartitionColumn::Value(value.clone()));
+            }
+        }
+        if file_projection.is_empty() {
+            // read a single column to know how many rows each batch has
+            file_projection.push(0);
+        }
+
+        let fields = projection
+            .iter()
+            .map(|i| self.schema.field(*i).clone())
+            .collect();
+        Ok(PartitionDataSource {
+            schema: Arc::new(Schema::new(fields)),
+            input: scan(&Some(file_projection))?,
+            columns,
+        })
+    }
+}
+
+impl TableProvider for PartitionedTable {
+    fn schema(&self) -> &Arc<Schema> {
+        &self.schema
+    }
+
+    fn scan(
+        &self,
+        projection: &Option<Vec<usize>>,
+        batch_size: usize,
+    ) -> Result<Rc<RefCell<DataSource>>> {
+        let sources = self
+            .providers
+            .iter()
+            .enumerate()
+            .map(|(i, provider)| {
+                let source = self.open(i, projection, |file_projection| {
+                    provider.scan(file_projection, batch_size)
+                })?;
+                Ok(Box::new(source) as Box<DataSource>)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Rc::new(RefCell::new(ConcatDataSource::new(sources))))
+    }
+
+    fn partitions(&self) -> usize {
+        self.scan_partitions.len().max(1)
+    }
+
+    fn scan_partition(
+        &self,
+        projection: &Option<Vec<usize>>,
+        batch_size: usize,
+        partition: usize,
+    ) -> Result<Rc<RefCell<DataSource>>> {
+        let (i, j) = self.scan_partitions[partition];
+        let source = self.open(i, projection, |file_projection| {
+            self.providers[i].scan_partition(file_projection, batch_size, j)
+        })?;
+        Ok(Rc::new(RefCell::new(source)))
+    }
+
+    fn scan_partition_with_predicate(
+        &self,
+        projection: &Option<Vec<usize>>,
+        predicate: &PruningPredicate,
+        batch_size: usize,
+        partition: usize,
+    ) -> Result<Rc<RefCell<DataSource>>> {
+        let (i, j) = self.scan_partitions[partition];
+
+        // the partition columns have a single value in each partition
+        let mut statistics = vec![ColumnStatistics::default(); self.num_file_columns];
+        for value in &self.partitions[i].values {
+            statistics.push(match value {
+                ScalarValue::Null | ScalarValue::TypedNull(_) => {
+                    ColumnStatistics::default()
+                }
+                value => ColumnStatistics {
+                    null_count: Some(0),
+                    min_value: Some(value.clone()),
+                    max_value: Some(value.clone()),
+                },
+            });
+        }
+
+        if !predicate.may_match(&statistics) {
+            debug!(
+                "Skipping partition {}, which cannot match the predicate",
+                self.partitions[i].path
+            );
+            let source = self.open(i, projection, |_| {
+                Ok(Rc::new(RefCell::new(EmptyDataSource {
+                    schema: self.schema.clone(),
+                })))
+            })?;
+            return Ok(Rc::new(RefCell::new(source)));
+        }
+
+        let source = self.open(i, projection, |file_projection| {
+            self.providers[i].scan_partition_with_predicate(
+                file_projection,
+                predicate,
+                batch_size,
+                j,
+            )
+        })?;
+        Ok(Rc::new(RefCell::new(source)))
+    }
+
+    fn statistics(&self) -> Statistics {
+        let num_rows = self
+            .providers
+            .iter()
+            .map(|provider| provider.statistics().num_rows)
+            .sum();
+        let total_byte_size = self
+            .providers
+            .iter()
+            .map(|provider| provider.statistics().total_byte_size)
+            .sum();
+        Statistics {
+            num_rows,
+            total_byte_size,
+            column_statistics: None,
+        }
+    }
+
+    fn is_static(&self) -> bool {
+        true
+    }
+}
+
+/// A column returned by a partition data source
+enum PartitionColumn {
+    /// The column at the given index of the batches read from the files
+    File(usize),
+    /// A partition column, which has the same value in every row
+    Value(ScalarValue),
+}
+
+/// Data source that adds the values of the partition columns to the batches read from
+/// the files of a partition
+struct PartitionDataSource {
+    schema: Arc<Schema>,
+    input: Rc<RefCell<DataSource>>,
+    columns: Vec<PartitionColumn>,
+}
+
+impl DataSource for PartitionDataSource {
+    fn schema(&self) -> &Arc<Schema> {
+        &self.schema
+    }
+
+    fn next(&mut self) -> Result<Option<RecordBatch>> {
+        let batch = match self.input.borrow_mut().next()? {
+            Some(batch) => batch,
+            None => return Ok(None),
+        };
+
+        let columns = self
+            .columns
+            .iter()
+            .zip(self.schema.fields())
+            .map(|(column, field)| match column {
+                PartitionColumn::File(i) => Ok(batch.column(*i).clone()),
+                PartitionColumn::Value(value) => {
+                    scalars_to_array(&vec![value; batch.num_rows()], field.data_type())
+                }
+            })
+            .collect::<Result<Vec<ArrayRef>>>()?;
+        Ok(Some(RecordBatch::new(self.schema.clone(), columns)))
+    }
+}
+
+/// Data source for a partition that is skipped
+struct EmptyDataSource {
+    schema: Arc<Schema>,
+}
+
+impl DataSource for EmptyDataSource {
+    fn schema(&self) -> &Arc<Schema> {
+        &self.schema
+    }
+
+    fn next(&mut self) -> Result<Option<RecordBatch>> {
+        Ok(None)
+    }
+}
+
+/// Find the files below a directory, grouped by the directory they are in. Hidden files
+/// and directories (starting with `.` or `_`) are ignored.
+fn find_files(dir: &Path, files: &mut BTreeMap<String, Vec<String>>) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with('.') || name.starts_with('_') {
+            continue;
+        }
+
+        let path = entry.path();
+        if entry.file_type()?.is_dir() {
+            find_files(&path, files)?;
+        } else {
+            files
+                .entry(dir.to_string_lossy().to_string())
+                .or_insert_with(Vec::new)
+                .push(path.to_string_lossy().to_string());
+        }
+    }
+
+    for filenames in files.values_mut() {
+        filenames.sort();
+    }
+    Ok(())
+}
+
+/// Infer the schema of the partition columns from the partition path of each directory,
+/// using the keys of the first directory
+fn infer_partition_schema(directories: &[(String, Vec<String>)]) -> Schema {
+    let paths: Vec<Vec<(String, String)>> = directories
+        .iter()
+        .map(|(dir, _)| parse_partition_path(dir))
+        .collect();
+
+    let fields = paths[0]
+        .iter()
+        .map(|(key, _)| {
+            let is_integer = paths.iter().all(|components| {
+                components
+                    .iter()
+                    .filter(|(k, _)| k == key)
+                    .all(|(_, value)| {
+                        value == DEFAULT_PARTITION_NAME || value.parse::<i64>().is_ok()
+                    })
+            });
+            let data_type = if is_integer {
+                DataType::Int64
+            } else {
+                DataType::Utf8
+            };
+            Field::new(key, data_type, true)
+        })
+        .collect();
+    Schema::new(fields)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution::datasource::CsvProvider;
+    use crate::logicalplan::{Expr, Operator};
+    use arrow::array::{BinaryArray, Int64Array};
+    use std::env;
+
+    #[test]
+    fn scan_partitioned_table() {
+        let table = test_table("datafusion_scan_partitioned_table");
+        assert_eq!(3, table.schema().fields().len());
+        assert_eq!("year", table.schema().field(1).name());
+        assert_eq!(&DataType::Int64, table.schema().field(1).data_type());
+        assert_eq!("region", table.schema().field(2).name());
+        assert_eq!(&DataType::Utf8, table.schema().field(2).data_type());
+        assert_eq!(3, table.partitions());
+
+        let ds = table.scan_partition(&Some(vec![2, 0, 1]), 1024, 0).unwrap();
+        let batch = ds.borrow_mut().next().unwrap().unwrap();
+        assert_eq!(2, batch.num_rows());
+        let region = batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<BinaryArray>()
+            .unwrap();
+        let year = batch
+            .column(2)
+            .as_any()
+            .downcast_ref::<Int64Array>()
+            .unwrap();
+        assert_eq!("eu", region.get_string(1));
+        assert_eq!(2018, year.value(1));
+    }
+
+    #[test]
+    fn skip_partitions_by_predicate() {
+        let table = test_table("datafusion_skip_partitions_by_predicate");
+        let predicate = PruningPredicate::try_new(&Expr::BinaryExpr {
+            left: Rc::new(Expr::Column(1)),
+            op: Operator::GtEq,
+            right: Rc::new(Expr::Literal(ScalarValue::Int64(2019))),
+        })
+        .unwrap();
+
+        let row_counts: Vec<usize> = (0..table.partitions())
+            .map(|partition| {
+                let ds = table
+                    .scan_partition_with_predicate(&None, &predicate, 1024, partition)
+                    .unwrap();
+                let mut rows = 0;
+                while let Some(batch) = ds.borrow_mut().next().unwrap() {
+                    rows += batch.num_rows();
+                }
+                rows
+            })
+            .collect();
+        assert_eq!(vec![0, 1, 1], row_counts);
+    }
+
+    /// Create a table with the partitions `year=2018/region=eu`, `year=2019/region=eu`
+    /// and `year=2019/region=us`
+    fn test_table(name: &str) -> PartitionedTable {
+        let root = env::temp_dir().join(name);
+        let contents = vec![
+            ("year=2018/region=eu", "amount\n1\n2\n"),
+            ("year=2019/region=eu", "amount\n3\n"),
+            ("year=2019/region=us", "amount\n4\n"),
+        ];
+        for (dir, data) in contents {
+            let dir = root.join(dir);
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join("part-0.csv"), data).unwrap();
+        }
+        fs::write(root.join("_SUCCESS"), "").unwrap();
+
+        let schema = Schema::new(vec![Field::new("amount", DataType::Int64, false)]);
+        PartitionedTable::try_new(root.to_str().unwrap(), |filenames| {
+            Ok(
+                Arc::new(CsvProvider::new_partitioned(filenames, &schema, true))
+                    as Arc<TableProvider>,
+            )
+        })
+        .unwrap()
+    }
+}
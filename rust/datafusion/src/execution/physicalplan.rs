@@ -15,8 +15,37 @@
 // specific language governing permissions and limitations
 // under the License.
 
-use super::super::logicalplan::LogicalPlan;
+use std::cell::RefCell;
 use std::rc::Rc;
+use std::sync::Arc;
+
+use arrow::datatypes::Schema;
+
+use super::super::logicalplan::LogicalPlan;
+use super::error::Result;
+use super::relation::Relation;
+
+/// An execution plan is a physical query plan, created from an optimized logical plan,
+/// that describes how a query will be executed.
+///
+/// The output of an execution plan is split into one or more partitions that can be
+/// executed independently and concurrently, so plans must be safe to share between
+/// threads. The relation created for a partition is consumed on the thread that
+/// executed it.
+pub trait ExecutionPlan: Send + Sync {
+    /// Get the schema of the relation that this plan produces
+    fn schema(&self) -> Arc<Schema>;
+
+    /// Get the inputs of this plan
+    fn children(&self) -> Vec<Arc<ExecutionPlan>>;
+
+    /// Get the number of partitions that this plan produces
+    fn output_partitions(&self) -> usize;
+
+    /// Execute one partition of this plan, creating a new relation that produces the
+    /// results for that partition
+    fn execute(&self, partition: usize) -> Result<Rc<RefCell<Relation>>>;
+}
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum PhysicalPlan {
@@ -0,0 +1,300 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Formatting of record batches as ASCII tables, with one column per field of the
+//! schema. Numeric columns are aligned to the right, other columns to the left, and
+//! null values are shown as `NULL`. Lists are shown as `[1, 2, 3]` and structs as
+//! `{a: 1, b: 2}`. Dictionary columns are shown as their values.
+
+use arrow::array::*;
+use arrow::compute::unpack_dictionary;
+use arrow::datatypes::DataType;
+use arrow::record_batch::RecordBatch;
+
+use super::error::{ExecutionError, Result};
+
+/// Print record batches to stdout as a table
+pub fn print_batches(batches: &[RecordBatch]) -> Result<()> {
+    println!("{}", pretty_format_batches(batches)?);
+    Ok(())
+}
+
+/// Format record batches as a table. The batches must have the same schema, and no
+/// table is produced if there are no batches.
+pub fn pretty_format_batches(batches: &[RecordBatch]) -> Result<String> {
+    let schema = match batches.first() {
+        Some(batch) => batch.schema().clone(),
+        None => return Ok(String::new()),
+    };
+
+    let header: Vec<String> = schema.fields().iter().map(|f| f.name().clone()).collect();
+    let right_align: Vec<bool> = schema
+        .fields()
+        .iter()
+        .map(|f| is_numeric(f.data_type()))
+        .collect();
+
+    let mut rows = vec![];
+    for batch in batches {
+        if batch.schema().fields() != schema.fields() {
+            return Err(ExecutionError::General(
+                "Cannot format batches with different schemas as one table".to_string(),
+            ));
+        }
+        // dictionary columns are unpacked once rather than for every value
+        let columns = (0..batch.num_columns())
+            .map(|i| batch.column(i))
+            .map(|column| match column.data_type() {
+                DataType::Dictionary(_, _) => unpack_dictionary(column),
+                _ => Ok(column.clone()),
+            })
+            .collect::<arrow::error::Result<Vec<_>>>()?;
+        for row in 0..batch.num_rows() {
+            let values = columns
+                .iter()
+                .map(|column| array_value_to_string(column, row))
+                .collect::<Result<Vec<_>>>()?;
+            rows.push(values);
+        }
+    }
+
+    let mut widths: Vec<usize> = header.iter().map(|h| h.chars().count()).collect();
+    for row in &rows {
+        for (i, value) in row.iter().enumerate() {
+            widths[i] = widths[i].max(value.chars().count());
+        }
+    }
+
+    let separator = widths
+        .iter()
+        .map(|w| format!("+{}", "-".repeat(w + 2)))
+        .collect::<String>()
+        + "+";
+    let format_row = |values: &[String], align: bool| {
+        let mut line = String::new();
+        for (i, value) in values.iter().enumerate() {
+            let padding = " ".repeat(widths[i] - value.chars().count());
+            if align && right_align[i] {
+                line.push_str(&format!("| {}{} ", padding, value));
+            } else {
+                line.push_str(&format!("| {}{} ", value, padding));
+            }
+        }
+        line + "|"
+    };
+
+    let mut lines = vec![separator.clone(), format_row(&header, false)];
+    lines.push(separator.clone());
+    for row in &rows {
+        lines.push(format_row(row, true));
+    }
+    lines.push(separator);
+    Ok(lines.join("\n"))
+}
+
+fn is_numeric(data_type: &DataType) -> bool {
+    match data_type {
+        DataType::Int8
+        | DataType::Int16
+        | DataType::Int32
+        | DataType::Int64
+        | DataType::UInt8
+        | DataType::UInt16
+        | DataType::UInt32
+        | DataType::UInt64
+        | DataType::Float16
+        | DataType::Float32
+        | DataType::Float64 => true,
+        DataType::Dictionary(_, value_type) => is_numeric(value_type),
+        _ => false,
+    }
+}
+
+macro_rules! primitive_to_string {
+    ($ARRAY:expr, $ROW:expr, $ARRAY_TYPE:ident) => {{
+        let array = $ARRAY.as_any().downcast_ref::<$ARRAY_TYPE>().unwrap();
+        format!("{}", array.value($ROW))
+    }};
+}
+
+/// Format a single value of an array as a string, showing null values as `NULL`
+pub fn array_value_to_string(array: &ArrayRef, row: usize) -> Result<String> {
+    if array.is_null(row) {
+        return Ok("NULL".to_string());
+    }
+    let value = match array.data_type() {
+        DataType::Boolean => primitive_to_string!(array, row, BooleanArray),
+        DataType::Int8 => primitive_to_string!(array, row, Int8Array),
+        DataType::Int16 => primitive_to_string!(array, row, Int16Array),
+        DataType::Int32 => primitive_to_string!(array, row, Int32Array),
+        DataType::Int64 => primitive_to_string!(array, row, Int64Array),
+        DataType::UInt8 => primitive_to_string!(array, row, UInt8Array),
+        DataType::UInt16 => primitive_to_string!(array, row, UInt16Array),
+        DataType::UInt32 => primitive_to_string!(array, row, UInt32Array),
+        DataType::UInt64 => primitive_to_string!(array, row, UInt64Array),
+        DataType::Float32 => primitive_to_string!(array, row, Float32Array),
+        DataType::Float64 => primitive_to_string!(array, row, Float64Array),
+        DataType::Utf8 => {
+            let array = array.as_any().downcast_ref::<BinaryArray>().unwrap();
+            String::from_utf8_lossy(array.value(row)).to_string()
+        }
+        DataType::Binary => {
+            let array = array.as_any().downcast_ref::<BinaryArray>().unwrap();
+            array
+                .value(row)
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect::<String>()
+        }
+        DataType::List(_) => {
+            let list = array.as_any().downcast_ref::<ListArray>().unwrap();
+            let values = list.values();
+            let start = list.value_offset(row) as usize;
+            let elements = (start..start + list.value_length(row) as usize)
+                .map(|i| array_value_to_string(&values, i))
+                .collect::<Result<Vec<_>>>()?;
+            format!("[{}]", elements.join(", "))
+        }
+        DataType::Struct(ref fields) => {
+            let array = array.as_any().downcast_ref::<StructArray>().unwrap();
+            let values = fields
+                .iter()
+                .enumerate()
+                .map(|(i, field)| {
+                    let value = array_value_to_string(array.column(i), row)?;
+                    Ok(format!("{}: {}", field.name(), value))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            format!("{{{}}}", values.join(", "))
+        }
+        other => {
+            return Err(ExecutionError::NotImplemented(format!(
+                "Formatting values of type {:?} is not supported",
+                other
+            )));
+        }
+    };
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::builder::{BinaryBuilder, Int32Builder, ListBuilder};
+    use arrow::datatypes::{Field, Schema};
+    use std::sync::Arc;
+
+    #[test]
+    fn format_batches() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("name", DataType::Utf8, true),
+            Field::new("amount", DataType::Int32, true),
+        ]));
+        let mut names = BinaryBuilder::new(16);
+        names.append_string("apple").unwrap();
+        names.append_null().unwrap();
+        let first = RecordBatch::new(
+            schema.clone(),
+            vec![
+                Arc::new(names.finish()),
+                Arc::new(Int32Array::from(vec![Some(5), Some(1200)])),
+            ],
+        );
+        let mut names = BinaryBuilder::new(16);
+        names.append_string("kiwi").unwrap();
+        let amounts: Vec<Option<i32>> = vec![None];
+        let second = RecordBatch::new(
+            schema,
+            vec![
+                Arc::new(names.finish()),
+                Arc::new(Int32Array::from(amounts)),
+            ],
+        );
+
+        let expected = vec![
+            "+-------+--------+",
+            "| name  | amount |",
+            "+-------+--------+",
+            "| apple |      5 |",
+            "| NULL  |   1200 |",
+            "| kiwi  |   NULL |",
+            "+-------+--------+",
+        ];
+        assert_eq!(
+            expected.join("\n"),
+            pretty_format_batches(&[first, second]).unwrap()
+        );
+    }
+
+    #[test]
+    fn format_lists() {
+        let list_type = DataType::List(Box::new(DataType::Int32));
+        let schema = Arc::new(Schema::new(vec![Field::new("tags", list_type, true)]));
+        let mut tags = ListBuilder::new(Int32Builder::new(8));
+        tags.values().append_value(1).unwrap();
+        tags.values().append_null().unwrap();
+        tags.append(true).unwrap();
+        tags.append(false).unwrap();
+        tags.append(true).unwrap();
+        let batch = RecordBatch::new(schema, vec![Arc::new(tags.finish())]);
+
+        let expected = vec![
+            "+-----------+",
+            "| tags      |",
+            "+-----------+",
+            "| [1, NULL] |",
+            "| NULL      |",
+            "| []        |",
+            "+-----------+",
+        ];
+        assert_eq!(
+            expected.join("\n"),
+            pretty_format_batches(&[batch]).unwrap()
+        );
+    }
+
+    #[test]
+    fn format_dictionaries() {
+        let dictionary_type =
+            DataType::Dictionary(Box::new(DataType::Int8), Box::new(DataType::Utf8));
+        let schema =
+            Arc::new(Schema::new(vec![Field::new("city", dictionary_type, true)]));
+        let cities =
+            Int8DictionaryArray::try_from_strings(&[Some("Paris"), None, Some("Paris")])
+                .unwrap();
+        let batch = RecordBatch::new(schema, vec![Arc::new(cities)]);
+
+        let expected = vec![
+            "+-------+",
+            "| city  |",
+            "+-------+",
+            "| Paris |",
+            "| NULL  |",
+            "| Paris |",
+            "+-------+",
+        ];
+        assert_eq!(
+            expected.join("\n"),
+            pretty_format_batches(&[batch]).unwrap()
+        );
+    }
+
+    #[test]
+    fn format_no_batches() {
+        assert_eq!("", pretty_format_batches(&[]).unwrap());
+    }
+}
@@ -27,12 +27,68 @@ use arrow::record_batch::RecordBatch;
 
 use super::error::Result;
 use super::expression::RuntimeExpr;
+use super::physicalplan::ExecutionPlan;
 use super::relation::Relation;
+use super::scheduler::{BatchFunction, BatchPipeline, Scheduler};
+
+/// Execution plan for a projection
+pub struct ProjectionExec {
+    input: Arc<ExecutionPlan>,
+    expr: Vec<RuntimeExpr>,
+    schema: Arc<Schema>,
+    scheduler: Option<Arc<Scheduler>>,
+}
+
+impl ProjectionExec {
+    pub fn new(
+        input: Arc<ExecutionPlan>,
+        expr: Vec<RuntimeExpr>,
+        schema: Arc<Schema>,
+    ) -> Self {
+        Self {
+            input,
+            expr,
+            schema,
+            scheduler: None,
+        }
+    }
+
+    /// Evaluate the expressions for each batch as a task of the given scheduler
+    pub fn with_scheduler(mut self, scheduler: Arc<Scheduler>) -> Self {
+        self.scheduler = Some(scheduler);
+        self
+    }
+}
+
+impl ExecutionPlan for ProjectionExec {
+    fn schema(&self) -> Arc<Schema> {
+        self.schema.clone()
+    }
+
+    fn children(&self) -> Vec<Arc<ExecutionPlan>> {
+        vec![self.input.clone()]
+    }
+
+    fn output_partitions(&self) -> usize {
+        self.input.output_partitions()
+    }
+
+    fn execute(&self, partition: usize) -> Result<Rc<RefCell<Relation>>> {
+        let input = self.input.execute(partition)?;
+        let mut relation =
+            ProjectRelation::new(input, self.expr.clone(), self.schema.clone());
+        if let Some(ref scheduler) = self.scheduler {
+            relation = relation.with_scheduler(scheduler.clone());
+        }
+        Ok(Rc::new(RefCell::new(relation)))
+    }
+}
 
 pub struct ProjectRelation {
     schema: Arc<Schema>,
     input: Rc<RefCell<Relation>>,
-    expr: Vec<RuntimeExpr>,
+    project: BatchFunction,
+    pipeline: Option<BatchPipeline>,
 }
 
 impl ProjectRelation {
@@ -43,31 +99,40 @@ impl ProjectRelation {
     ) -> Self {
         ProjectRelation {
             input,
-            expr,
+            project: Arc::new(move |batch| project_batch(batch, &expr)),
+            pipeline: None,
             schema,
         }
     }
+
+    /// Evaluate the expressions for each batch as a task of the given scheduler
+    pub fn with_scheduler(mut self, scheduler: Arc<Scheduler>) -> Self {
+        self.pipeline = Some(BatchPipeline::new(scheduler));
+        self
+    }
+}
+
+/// Evaluate the projected expressions against a batch
+fn project_batch(batch: &RecordBatch, expr: &[RuntimeExpr]) -> Result<RecordBatch> {
+    let projected_columns: Result<Vec<ArrayRef>> =
+        expr.iter().map(|e| e.get_func()(batch)).collect();
+
+    let schema = Schema::new(
+        expr.iter()
+            .map(|e| Field::new(&e.get_name(), e.get_type(), true))
+            .collect(),
+    );
+
+    Ok(RecordBatch::new(Arc::new(schema), projected_columns?))
 }
 
 impl Relation for ProjectRelation {
     fn next(&mut self) -> Result<Option<RecordBatch>> {
+        if let Some(ref mut pipeline) = self.pipeline {
+            return pipeline.next(&self.input, &self.project);
+        }
         match self.input.borrow_mut().next()? {
-            Some(batch) => {
-                let projected_columns: Result<Vec<ArrayRef>> =
-                    self.expr.iter().map(|e| e.get_func()(&batch)).collect();
-
-                let schema = Schema::new(
-                    self.expr
-                        .iter()
-                        .map(|e| Field::new(&e.get_name(), e.get_type(), true))
-                        .collect(),
-                );
-
-                let projected_batch: RecordBatch =
-                    RecordBatch::new(Arc::new(schema), projected_columns?);
-
-                Ok(Some(projected_batch))
-            }
+            Some(batch) => Ok(Some((self.project)(&batch)?)),
             None => Ok(None),
         }
     }
@@ -87,9 +152,8 @@ mod tests {
     use super::*;
     use arrow::datatypes::{DataType, Field, Schema};
 
-    #[test]
-    fn project_first_column() {
-        let schema = Arc::new(Schema::new(vec![
+    fn aggregate_test_schema() -> Arc<Schema> {
+        Arc::new(Schema::new(vec![
             Field::new("c1", DataType::Utf8, false),
             Field::new("c2", DataType::UInt32, false),
             Field::new("c3", DataType::Int8, false),
@@ -103,7 +167,12 @@ mod tests {
             Field::new("c10", DataType::Float32, false),
             Field::new("c11", DataType::Float64, false),
             Field::new("c12", DataType::Utf8, false),
-        ]));
+        ]))
+    }
+
+    #[test]
+    fn project_first_column() {
+        let schema = aggregate_test_schema();
 
         let ds = CsvDataSource::new(
             "../../testing/data/csv/aggregate_test_100.csv",
@@ -117,11 +186,13 @@ mod tests {
         ))));
         let context = ExecutionContext::new();
 
-        let projection_expr =
-            vec![
-                expression::compile_expr(&context, &Expr::Column(0), schema.as_ref())
-                    .unwrap(),
-            ];
+        let projection_expr = vec![expression::compile_expr(
+            &context,
+            &Expr::Column(0),
+            schema.as_ref(),
+            context.config(),
+        )
+        .unwrap()];
 
         let mut projection = ProjectRelation::new(relation, projection_expr, schema);
         let batch = projection.next().unwrap().unwrap();
@@ -130,4 +201,42 @@ mod tests {
         assert_eq!("c1", batch.schema().field(0).name());
     }
 
+    #[test]
+    fn project_batches_with_scheduler() {
+        let schema = aggregate_test_schema();
+        let context = ExecutionContext::new();
+        let projection_expr = vec![expression::compile_expr(
+            &context,
+            &Expr::Column(1),
+            schema.as_ref(),
+            context.config(),
+        )
+        .unwrap()];
+
+        let ds = CsvDataSource::new(
+            "../../testing/data/csv/aggregate_test_100.csv",
+            schema.clone(),
+            true,
+            &None,
+            10,
+        );
+        let relation = Rc::new(RefCell::new(DataSourceRelation::new(Rc::new(
+            RefCell::new(ds),
+        ))));
+        let mut projection = ProjectRelation::new(relation, projection_expr, schema)
+            .with_scheduler(Arc::new(Scheduler::new(4)));
+
+        let mut values = vec![];
+        while let Some(batch) = projection.next().unwrap() {
+            assert_eq!(1, batch.num_columns());
+            let c2 = batch
+                .column(0)
+                .as_any()
+                .downcast_ref::<arrow::array::UInt32Array>()
+                .unwrap();
+            values.extend((0..c2.len()).map(|i| c2.value(i)));
+        }
+        assert_eq!(100, values.len());
+        assert_eq!(vec![2, 5, 1, 1, 5], values[0..5].to_vec());
+    }
 }
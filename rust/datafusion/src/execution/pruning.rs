@@ -0,0 +1,528 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Pruning of table partitions and other data that cannot contain rows matching a
+//! predicate
+
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use arrow::datatypes::{DataType, Schema};
+
+use super::super::logicalplan::{Expr, Operator, ScalarValue};
+use super::super::optimizer::simplify_filters::{cast_scalar, compare_scalars, simplify};
+use super::super::optimizer::utils;
+use super::datasource::ColumnStatistics;
+use super::error::{ExecutionError, Result};
+
+/// Directory name used for partitions where the partition column is null
+pub const DEFAULT_PARTITION_NAME: &str = "__HIVE_DEFAULT_PARTITION__";
+
+/// A partition of a table that is stored in a `key=value` directory, such as
+/// `/data/sales/year=2019/month=3`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Partition {
+    /// Path of the partition directory
+    pub path: String,
+    /// Values of the partition columns, in the order of the partition schema
+    pub values: Vec<ScalarValue>,
+}
+
+impl Partition {
+    /// Create a partition from its path, parsing the value of each column in
+    /// `partition_schema` from the `key=value` components of the path
+    pub fn try_new(path: &str, partition_schema: &Schema) -> Result<Self> {
+        let components = parse_partition_path(path);
+        let values = partition_schema
+            .fields()
+            .iter()
+            .map(
+                |field| match components.iter().find(|(key, _)| key == field.name()) {
+                    Some((_, value)) => parse_partition_value(value, field.data_type()),
+                    None => Err(ExecutionError::General(format!(
+                        "Partition column '{}' not found in path '{}'",
+                        field.name(),
+                        path
+                    ))),
+                },
+            )
+            .collect::<Result<Vec<ScalarValue>>>()?;
+
+        Ok(Self {
+            path: path.to_string(),
+            values,
+        })
+    }
+}
+
+/// Parse the `key=value` components of a partition path, in the order they appear.
+/// Keys and values are percent-decoded, since characters such as `/`, `=` and spaces
+/// are escaped in directory names.
+pub fn parse_partition_path(path: &str) -> Vec<(String, String)> {
+    path.split('/')
+        .filter_map(|component| {
+            let mut parts = component.splitn(2, '=');
+            match (parts.next(), parts.next()) {
+                (Some(key), Some(value)) if !key.is_empty() => {
+                    Some((percent_decode(key), percent_decode(value)))
+                }
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// Decode the `%XX` escapes in a component of a partition path. Malformed escapes,
+/// and escapes that do not decode to UTF-8, are left as they are.
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = if bytes[i] == b'%' {
+            s.get(i + 1..i + 3)
+                .filter(|hex| hex.bytes().all(|b| b.is_ascii_hexdigit()))
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        } else {
+            None
+        };
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8(decoded).unwrap_or_else(|_| s.to_string())
+}
+
+/// Parse the value of a partition column from its directory name
+pub fn parse_partition_value(value: &str, data_type: &DataType) -> Result<ScalarValue> {
+    if value == DEFAULT_PARTITION_NAME {
+        return Ok(ScalarValue::Null);
+    }
+
+    macro_rules! parse_value {
+        ($VARIANT:ident) => {{
+            match value.parse() {
+                Ok(v) => Ok(ScalarValue::$VARIANT(v)),
+                Err(_) => Err(ExecutionError::General(format!(
+                    "Cannot parse partition value '{}' as {:?}",
+                    value, data_type
+                ))),
+            }
+        }};
+    }
+
+    match data_type {
+        DataType::Boolean => parse_value!(Boolean),
+        DataType::Int8 => parse_value!(Int8),
+        DataType::Int16 => parse_value!(Int16),
+        DataType::Int32 => parse_value!(Int32),
+        DataType::Int64 => parse_value!(Int64),
+        DataType::UInt8 => parse_value!(UInt8),
+        DataType::UInt16 => parse_value!(UInt16),
+        DataType::UInt32 => parse_value!(UInt32),
+        DataType::UInt64 => parse_value!(UInt64),
+        DataType::Float32 => parse_value!(Float32),
+        DataType::Float64 => parse_value!(Float64),
+        DataType::Utf8 => Ok(ScalarValue::Utf8(Arc::new(value.to_string()))),
+        other => Err(ExecutionError::NotImplemented(format!(
+            "Partition columns of type {:?} are not supported",
+            other
+        ))),
+    }
+}
+
+/// Returns the partitions that may contain rows matching `predicate`.
+///
+/// `partition_columns[i]` is the index in the table schema of the column whose value
+/// is stored in `Partition::values[i]`. Only the conjuncts of the predicate that
+/// reference nothing but partition columns are considered; a partition is pruned when
+/// one of them evaluates to false for that partition's values.
+pub fn prune_partitions(
+    partitions: &[Partition],
+    partition_columns: &[usize],
+    predicate: &Expr,
+) -> Result<Vec<Partition>> {
+    let mut conjuncts = vec![];
+    utils::split_conjunction(predicate, &mut conjuncts);
+
+    let partition_column_set: HashSet<usize> =
+        partition_columns.iter().cloned().collect();
+    let conjuncts: Vec<Expr> = conjuncts
+        .into_iter()
+        .filter(|expr| {
+            let mut columns = HashSet::new();
+            utils::expr_to_column_indices(expr, &mut columns);
+            !columns.is_empty() && columns.is_subset(&partition_column_set)
+        })
+        .collect();
+
+    if conjuncts.is_empty() {
+        return Ok(partitions.to_vec());
+    }
+
+    let num_columns = partition_columns.iter().max().map(|i| i + 1).unwrap_or(0);
+
+    let mut result = vec![];
+    for partition in partitions {
+        // replace references to partition columns with the values for this partition
+        let input_expr: Vec<Expr> = (0..num_columns)
+            .map(|i| match partition_columns.iter().position(|c| *c == i) {
+                Some(j) => Expr::Literal(partition.values[j].clone()),
+                None => Expr::Column(i),
+            })
+            .collect();
+
+        let mut matches = true;
+        for expr in &conjuncts {
+            let expr = utils::replace_columns(expr, &input_expr)?;
+            if let Expr::Literal(ScalarValue::Boolean(false)) = simplify(&expr) {
+                matches = false;
+                break;
+            }
+        }
+
+        if matches {
+            result.push(partition.clone());
+        }
+    }
+
+    Ok(result)
+}
+
+/// The conjuncts of a predicate that compare a column to a literal value, which are used
+/// to skip data (such as the row groups of a Parquet file) whose column statistics show
+/// that it cannot contain matching rows
+#[derive(Debug, Clone, PartialEq)]
+pub struct PruningPredicate {
+    comparisons: Vec<ColumnComparison>,
+}
+
+/// A comparison between a column, which may be cast to a wider type, and a literal
+#[derive(Debug, Clone, PartialEq)]
+struct ColumnComparison {
+    column: usize,
+    cast: Option<DataType>,
+    op: Operator,
+    value: ScalarValue,
+}
+
+impl PruningPredicate {
+    /// Create a pruning predicate from a predicate over the columns of a table. Returns
+    /// `None` if none of the conjuncts of the predicate can be used for pruning.
+    pub fn try_new(predicate: &Expr) -> Option<Self> {
+        let mut conjuncts = vec![];
+        utils::split_conjunction(predicate, &mut conjuncts);
+        let comparisons: Vec<ColumnComparison> =
+            conjuncts.iter().filter_map(to_column_comparison).collect();
+        if comparisons.is_empty() {
+            None
+        } else {
+            Some(Self { comparisons })
+        }
+    }
+
+    /// Determine whether data with the given statistics for each column of the table
+    /// may contain rows that match the predicate
+    pub fn may_match(&self, statistics: &[ColumnStatistics]) -> bool {
+        self.comparisons
+            .iter()
+            .all(|comparison| comparison.may_match(statistics))
+    }
+}
+
+impl ColumnComparison {
+    fn may_match(&self, statistics: &[ColumnStatistics]) -> bool {
+        let (min, max) = match statistics.get(self.column) {
+            Some(ColumnStatistics {
+                min_value: Some(min),
+                max_value: Some(max),
+                ..
+            }) => (min, max),
+            _ => return true,
+        };
+
+        let (min, max) = match self.cast {
+            Some(ref data_type) => {
+                match (cast_scalar(min, data_type), cast_scalar(max, data_type)) {
+                    (Some(min), Some(max)) => (min, max),
+                    _ => return true,
+                }
+            }
+            None => (min.clone(), max.clone()),
+        };
+
+        let (min, max) = match (
+            compare_scalars(&min, &self.value),
+            compare_scalars(&max, &self.value),
+        ) {
+            (Some(min), Some(max)) => (min, max),
+            _ => return true,
+        };
+
+        match self.op {
+            Operator::Eq => min != Ordering::Greater && max != Ordering::Less,
+            Operator::NotEq => min != Ordering::Equal || max != Ordering::Equal,
+            Operator::Lt => min == Ordering::Less,
+            Operator::LtEq => min != Ordering::Greater,
+            Operator::Gt => max == Ordering::Greater,
+            Operator::GtEq => max != Ordering::Less,
+            _ => true,
+        }
+    }
+}
+
+/// Convert a conjunct of the form `column op literal` or `literal op column` into a
+/// comparison, where the column may be cast to Int64 or Float64 (which is how the SQL
+/// planner compares columns to literals of a wider type)
+fn to_column_comparison(expr: &Expr) -> Option<ColumnComparison> {
+    let (left, op, right) = match expr {
+        Expr::BinaryExpr { left, op, right } => (left, op, right),
+        _ => return None,
+    };
+
+    let (column, op, value) = match (left.as_ref(), right.as_ref()) {
+        (_, Expr::Literal(value)) => (left.as_ref(), op.clone(), value),
+        (Expr::Literal(value), _) => (right.as_ref(), swap_operator(op)?, value),
+        _ => return None,
+    };
+
+    let (column, cast) = match column {
+        Expr::Column(i) => (*i, None),
+        Expr::Cast { expr, data_type } => match (expr.as_ref(), data_type) {
+            (Expr::Column(i), DataType::Int64) | (Expr::Column(i), DataType::Float64) => {
+                (*i, Some(data_type.clone()))
+            }
+            _ => return None,
+        },
+        _ => return None,
+    };
+
+    match op {
+        Operator::Eq
+        | Operator::NotEq
+        | Operator::Lt
+        | Operator::LtEq
+        | Operator::Gt
+        | Operator::GtEq => Some(ColumnComparison {
+            column,
+            cast,
+            op,
+            value: value.clone(),
+        }),
+        _ => None,
+    }
+}
+
+/// Get the operator to use when the operands of a comparison are swapped
+fn swap_operator(op: &Operator) -> Option<Operator> {
+    match op {
+        Operator::Eq => Some(Operator::Eq),
+        Operator::NotEq => Some(Operator::NotEq),
+        Operator::Lt => Some(Operator::Gt),
+        Operator::LtEq => Some(Operator::GtEq),
+        Operator::Gt => Some(Operator::Lt),
+        Operator::GtEq => Some(Operator::LtEq),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::datatypes::Field;
+    use std::rc::Rc;
+
+    #[test]
+    fn parse_path() {
+        assert_eq!(
+            vec![
+                ("year".to_string(), "2019".to_string()),
+                ("month".to_string(), "03".to_string()),
+            ],
+            parse_partition_path("/data/sales/year=2019/month=03/")
+        );
+        assert!(parse_partition_path("/data/sales").is_empty());
+    }
+
+    #[test]
+    fn parse_escaped_path() {
+        assert_eq!(
+            vec![
+                ("city".to_string(), "New York".to_string()),
+                ("the key".to_string(), "a=b".to_string()),
+                ("bad".to_string(), "100%".to_string()),
+                ("invalid".to_string(), "%zz%2".to_string()),
+            ],
+            parse_partition_path(
+                "/data/city=New%20York/the%20key=a%3Db/bad=100%25/invalid=%zz%2"
+            )
+        );
+    }
+
+    #[test]
+    fn parse_partition() {
+        let partition =
+            Partition::try_new("/data/year=2019/region=us%2Feast", &partition_schema())
+                .unwrap();
+        assert_eq!(
+            vec![
+                ScalarValue::Int32(2019),
+                ScalarValue::Utf8(Arc::new("us/east".to_string())),
+            ],
+            partition.values
+        );
+    }
+
+    #[test]
+    fn parse_partition_errors() {
+        assert!(Partition::try_new("/data/year=2019", &partition_schema()).is_err());
+        assert!(
+            Partition::try_new("/data/year=abc/region=us", &partition_schema()).is_err()
+        );
+    }
+
+    #[test]
+    fn parse_null_partition() {
+        let partition = Partition::try_new(
+            "/data/year=__HIVE_DEFAULT_PARTITION__/region=eu",
+            &partition_schema(),
+        )
+        .unwrap();
+        assert_eq!(ScalarValue::Null, partition.values[0]);
+    }
+
+    #[test]
+    fn prune_by_equality() {
+        // table schema is (amount, year, region) and the predicate is
+        // `CAST(year AS Int64) = 2019 AND amount > 10`
+        let predicate = Expr::BinaryExpr {
+            left: Rc::new(
+                Expr::Cast {
+                    expr: Rc::new(Expr::Column(1)),
+                    data_type: DataType::Int64,
+                }
+                .eq(&Expr::Literal(ScalarValue::Int64(2019))),
+            ),
+            op: Operator::And,
+            right: Rc::new(Expr::Column(0).gt(&Expr::Literal(ScalarValue::Int64(10)))),
+        };
+
+        let pruned = prune_partitions(&test_partitions(), &[1, 2], &predicate).unwrap();
+        let paths: Vec<&str> = pruned.iter().map(|p| p.path.as_str()).collect();
+        assert_eq!(
+            vec!["/data/year=2019/region=us", "/data/year=2019/region=eu"],
+            paths
+        );
+    }
+
+    #[test]
+    fn prune_by_string_comparison() {
+        let predicate = Expr::Column(2).eq(&Expr::Literal(ScalarValue::Utf8(Arc::new(
+            "eu".to_string(),
+        ))));
+
+        let pruned = prune_partitions(&test_partitions(), &[1, 2], &predicate).unwrap();
+        let paths: Vec<&str> = pruned.iter().map(|p| p.path.as_str()).collect();
+        assert_eq!(vec!["/data/year=2019/region=eu"], paths);
+    }
+
+    #[test]
+    fn no_pruning_without_partition_predicates() {
+        let predicate = Expr::Column(0).gt(&Expr::Literal(ScalarValue::Int64(10)));
+        let pruned = prune_partitions(&test_partitions(), &[1, 2], &predicate).unwrap();
+        assert_eq!(test_partitions(), pruned);
+    }
+
+    #[test]
+    fn prune_by_statistics() {
+        // `CAST(#0 AS Int64) > 10 AND 5 > #1`
+        let predicate = Expr::BinaryExpr {
+            left: Rc::new(
+                Expr::Cast {
+                    expr: Rc::new(Expr::Column(0)),
+                    data_type: DataType::Int64,
+                }
+                .gt(&Expr::Literal(ScalarValue::Int64(10))),
+            ),
+            op: Operator::And,
+            right: Rc::new(Expr::Literal(ScalarValue::Int32(5)).gt(&Expr::Column(1))),
+        };
+        let predicate = PruningPredicate::try_new(&predicate).unwrap();
+
+        assert!(predicate.may_match(&[int32_statistics(0, 11), int32_statistics(0, 1)]));
+        assert!(!predicate.may_match(&[int32_statistics(0, 10), int32_statistics(0, 1)]));
+        assert!(!predicate.may_match(&[int32_statistics(0, 11), int32_statistics(5, 9)]));
+
+        // data without statistics is never pruned
+        assert!(
+            predicate.may_match(&[ColumnStatistics::default(), int32_statistics(0, 1)])
+        );
+        assert!(predicate.may_match(&[]));
+    }
+
+    #[test]
+    fn prune_by_equality_statistics() {
+        let predicate = PruningPredicate::try_new(
+            &Expr::Column(0).eq(&Expr::Literal(ScalarValue::Int32(3))),
+        )
+        .unwrap();
+        assert!(predicate.may_match(&[int32_statistics(3, 3)]));
+        assert!(predicate.may_match(&[int32_statistics(1, 5)]));
+        assert!(!predicate.may_match(&[int32_statistics(4, 5)]));
+        assert!(!predicate.may_match(&[int32_statistics(1, 2)]));
+    }
+
+    #[test]
+    fn no_pruning_predicate() {
+        let predicate = Expr::Column(0).eq(&Expr::Column(1));
+        assert_eq!(None, PruningPredicate::try_new(&predicate));
+    }
+
+    fn int32_statistics(min: i32, max: i32) -> ColumnStatistics {
+        ColumnStatistics {
+            null_count: None,
+            min_value: Some(ScalarValue::Int32(min)),
+            max_value: Some(ScalarValue::Int32(max)),
+        }
+    }
+
+    fn partition_schema() -> Schema {
+        Schema::new(vec![
+            Field::new("year", DataType::Int32, true),
+            Field::new("region", DataType::Utf8, true),
+        ])
+    }
+
+    fn test_partitions() -> Vec<Partition> {
+        vec![
+            "/data/year=2018/region=us",
+            "/data/year=2019/region=us",
+            "/data/year=2019/region=eu",
+        ]
+        .into_iter()
+        .map(|path| Partition::try_new(path, &partition_schema()).unwrap())
+        .collect()
+    }
+}
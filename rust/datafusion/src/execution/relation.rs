@@ -19,11 +19,16 @@ use std::cell::RefCell;
 use std::rc::Rc;
 use std::sync::Arc;
 
-use arrow::datatypes::Schema;
+use arrow::array::*;
+use arrow::builder::*;
+use arrow::datatypes::{DataType, Schema};
 use arrow::record_batch::RecordBatch;
 
-use super::datasource::DataSource;
-use super::error::Result;
+use super::super::logicalplan::ScalarValue;
+use super::datasource::{DataSource, TableProvider};
+use super::error::{ExecutionError, Result};
+use super::physicalplan::ExecutionPlan;
+use super::pruning::PruningPredicate;
 
 /// trait for all relations (a relation is essentially just an iterator over rows with
 /// a known schema)
@@ -55,3 +60,369 @@ impl Relation for DataSourceRelation {
         &self.schema
     }
 }
+
+/// Execution plan for scanning a data source
+pub struct DataSourceExec {
+    provider: Arc<TableProvider>,
+    projection: Option<Vec<usize>>,
+    predicate: Option<PruningPredicate>,
+    batch_size: usize,
+    schema: Arc<Schema>,
+}
+
+impl DataSourceExec {
+    pub fn new(
+        provider: Arc<TableProvider>,
+        projection: Option<Vec<usize>>,
+        batch_size: usize,
+    ) -> Self {
+        let schema = match projection {
+            Some(ref p) => Arc::new(Schema::new(
+                p.iter()
+                    .map(|i| provider.schema().field(*i).clone())
+                    .collect(),
+            )),
+            None => provider.schema().clone(),
+        };
+        Self {
+            provider,
+            projection,
+            predicate: None,
+            batch_size,
+            schema,
+        }
+    }
+
+    /// Set a predicate that the data source can use to skip data that cannot contain
+    /// matching rows
+    pub fn with_predicate(mut self, predicate: PruningPredicate) -> Self {
+        self.predicate = Some(predicate);
+        self
+    }
+}
+
+impl ExecutionPlan for DataSourceExec {
+    fn schema(&self) -> Arc<Schema> {
+        self.schema.clone()
+    }
+
+    fn children(&self) -> Vec<Arc<ExecutionPlan>> {
+        vec![]
+    }
+
+    fn output_partitions(&self) -> usize {
+        self.provider.partitions()
+    }
+
+    fn execute(&self, partition: usize) -> Result<Rc<RefCell<Relation>>> {
+        let ds = match self.predicate {
+            Some(ref predicate) => self.provider.scan_partition_with_predicate(
+                &self.projection,
+                predicate,
+                self.batch_size,
+                partition,
+            ),
+            None => {
+                self.provider
+                    .scan_partition(&self.projection, self.batch_size, partition)
+            }
+        }?;
+        Ok(Rc::new(RefCell::new(DataSourceRelation::new(ds))))
+    }
+}
+
+/// A relation that does not produce any rows
+pub struct EmptyRelation {
+    schema: Arc<Schema>,
+}
+
+impl EmptyRelation {
+    pub fn new(schema: Arc<Schema>) -> Self {
+        Self { schema }
+    }
+}
+
+impl Relation for EmptyRelation {
+    fn next(&mut self) -> Result<Option<RecordBatch>> {
+        Ok(None)
+    }
+
+    fn schema(&self) -> &Arc<Schema> {
+        &self.schema
+    }
+}
+
+/// Execution plan for a relation that does not produce any rows
+pub struct EmptyExec {
+    schema: Arc<Schema>,
+}
+
+impl EmptyExec {
+    pub fn new(schema: Arc<Schema>) -> Self {
+        Self { schema }
+    }
+}
+
+impl ExecutionPlan for EmptyExec {
+    fn schema(&self) -> Arc<Schema> {
+        self.schema.clone()
+    }
+
+    fn children(&self) -> Vec<Arc<ExecutionPlan>> {
+        vec![]
+    }
+
+    fn output_partitions(&self) -> usize {
+        1
+    }
+
+    fn execute(&self, _partition: usize) -> Result<Rc<RefCell<Relation>>> {
+        Ok(Rc::new(RefCell::new(EmptyRelation::new(
+            self.schema.clone(),
+        ))))
+    }
+}
+
+/// Execution plan for a relation containing rows of literal values
+pub struct ValuesExec {
+    schema: Arc<Schema>,
+    values: Vec<Vec<ScalarValue>>,
+}
+
+impl ValuesExec {
+    pub fn new(schema: Arc<Schema>, values: Vec<Vec<ScalarValue>>) -> Self {
+        Self { schema, values }
+    }
+}
+
+impl ExecutionPlan for ValuesExec {
+    fn schema(&self) -> Arc<Schema> {
+        self.schema.clone()
+    }
+
+    fn children(&self) -> Vec<Arc<ExecutionPlan>> {
+        vec![]
+    }
+
+    fn output_partitions(&self) -> usize {
+        1
+    }
+
+    fn execute(&self, _partition: usize) -> Result<Rc<RefCell<Relation>>> {
+        Ok(Rc::new(RefCell::new(ValuesRelation::new(
+            self.schema.clone(),
+            self.values.clone(),
+        ))))
+    }
+}
+
+/// A relation that produces a single batch containing rows of literal values
+pub struct ValuesRelation {
+    schema: Arc<Schema>,
+    values: Vec<Vec<ScalarValue>>,
+    done: bool,
+}
+
+impl ValuesRelation {
+    pub fn new(schema: Arc<Schema>, values: Vec<Vec<ScalarValue>>) -> Self {
+        Self {
+            schema,
+            values,
+            done: false,
+        }
+    }
+}
+
+impl Relation for ValuesRelation {
+    fn next(&mut self) -> Result<Option<RecordBatch>> {
+        if self.done {
+            return Ok(None);
+        }
+        self.done = true;
+
+        let columns = self
+            .schema
+            .fields()
+            .iter()
+            .enumerate()
+            .map(|(i, field)| {
+                let column: Vec<&ScalarValue> =
+                    self.values.iter().map(|row| &row[i]).collect();
+                scalars_to_array(&column, field.data_type())
+            })
+            .collect::<Result<Vec<ArrayRef>>>()?;
+
+        Ok(Some(RecordBatch::new(self.schema.clone(), columns)))
+    }
+
+    fn schema(&self) -> &Arc<Schema> {
+        &self.schema
+    }
+}
+
+/// Build an array of the given type from a list of scalar values
+pub fn scalars_to_array(
+    values: &Vec<&ScalarValue>,
+    data_type: &DataType,
+) -> Result<ArrayRef> {
+    macro_rules! build_array {
+        ($BUILDER:ident, $VARIANT:ident) => {{
+            let mut builder = $BUILDER::new(values.len());
+            for value in values {
+                match value {
+                    ScalarValue::$VARIANT(v) => builder.append_value(*v)?,
+                    ScalarValue::Null | ScalarValue::TypedNull(_) => {
+                        builder.append_null()?
+                    }
+                    other => {
+                        return Err(ExecutionError::InternalError(format!(
+                            "Expected {:?} value but found {:?}",
+                            data_type, other
+                        )));
+                    }
+                }
+            }
+            Ok(Arc::new(builder.finish()) as ArrayRef)
+        }};
+    }
+
+    match data_type {
+        DataType::Boolean => build_array!(BooleanBuilder, Boolean),
+        DataType::Int8 => build_array!(Int8Builder, Int8),
+        DataType::Int16 => build_array!(Int16Builder, Int16),
+        DataType::Int32 => build_array!(Int32Builder, Int32),
+        DataType::Int64 => build_array!(Int64Builder, Int64),
+        DataType::UInt8 => build_array!(UInt8Builder, UInt8),
+        DataType::UInt16 => build_array!(UInt16Builder, UInt16),
+        DataType::UInt32 => build_array!(UInt32Builder, UInt32),
+        DataType::UInt64 => build_array!(UInt64Builder, UInt64),
+        DataType::Float32 => build_array!(Float32Builder, Float32),
+        DataType::Float64 => build_array!(Float64Builder, Float64),
+        DataType::Utf8 => {
+            let mut builder = BinaryBuilder::new(values.len());
+            for value in values {
+                match value {
+                    ScalarValue::Utf8(v) => builder.append_string(v)?,
+                    ScalarValue::Null | ScalarValue::TypedNull(_) => {
+                        builder.append_null()?
+                    }
+                    other => {
+                        return Err(ExecutionError::InternalError(format!(
+                            "Expected Utf8 value but found {:?}",
+                            other
+                        )));
+                    }
+                }
+            }
+            Ok(Arc::new(builder.finish()) as ArrayRef)
+        }
+        DataType::Binary => {
+            let mut builder = BinaryBuilder::new_binary(values.len());
+            for value in values {
+                match value {
+                    ScalarValue::Binary(v) => builder.append_bytes(v)?,
+                    ScalarValue::Null | ScalarValue::TypedNull(_) => {
+                        builder.append_null()?
+                    }
+                    other => {
+                        return Err(ExecutionError::InternalError(format!(
+                            "Expected Binary value but found {:?}",
+                            other
+                        )));
+                    }
+                }
+            }
+            Ok(Arc::new(builder.finish()) as ArrayRef)
+        }
+        other => Err(ExecutionError::NotImplemented(format!(
+            "Literal values of type {:?} are not supported",
+            other
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::datatypes::Field;
+
+    #[test]
+    fn values_relation() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::UInt64, true),
+            Field::new("b", DataType::Utf8, true),
+        ]));
+        let mut relation = ValuesRelation::new(
+            schema,
+            vec![
+                vec![
+                    ScalarValue::UInt64(1),
+                    ScalarValue::Utf8(Arc::new("x".to_string())),
+                ],
+                vec![ScalarValue::Null, ScalarValue::Null],
+            ],
+        );
+
+        let batch = relation.next().unwrap().unwrap();
+        assert_eq!(2, batch.num_rows());
+
+        let a = batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<UInt64Array>()
+            .unwrap();
+        assert_eq!(1, a.value(0));
+        assert!(a.is_null(1));
+
+        let b = batch
+            .column(1)
+            .as_any()
+            .downcast_ref::<BinaryArray>()
+            .unwrap();
+        assert_eq!("x", String::from_utf8(b.value(0).to_vec()).unwrap());
+        assert!(b.is_null(1));
+
+        assert!(relation.next().unwrap().is_none());
+    }
+
+    #[test]
+    fn binary_values_relation() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Binary, true),
+            Field::new("b", DataType::Boolean, true),
+        ]));
+        let mut relation = ValuesRelation::new(
+            schema,
+            vec![
+                vec![
+                    ScalarValue::Binary(Arc::new(vec![1, 2, 3])),
+                    ScalarValue::TypedNull(DataType::Boolean),
+                ],
+                vec![
+                    ScalarValue::TypedNull(DataType::Binary),
+                    ScalarValue::Boolean(true),
+                ],
+            ],
+        );
+
+        let batch = relation.next().unwrap().unwrap();
+        assert_eq!(2, batch.num_rows());
+
+        let a = batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<BinaryArray>()
+            .unwrap();
+        assert_eq!(&DataType::Binary, a.data_type());
+        assert_eq!(&[1, 2, 3], a.value(0));
+        assert!(a.is_null(1));
+
+        let b = batch
+            .column(1)
+            .as_any()
+            .downcast_ref::<BooleanArray>()
+            .unwrap();
+        assert!(b.is_null(0));
+        assert_eq!(true, b.value(1));
+    }
+}
@@ -0,0 +1,455 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Execution of a repartition, which redistributes the rows of its input into a
+//! different number of partitions
+
+use std::cell::RefCell;
+use std::panic::{self, AssertUnwindSafe};
+use std::rc::Rc;
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use arrow::array::*;
+use arrow::datatypes::Schema;
+use arrow::record_batch::RecordBatch;
+
+use super::error::{ExecutionError, Result};
+use super::expression::RuntimeExpr;
+use super::filter::filter;
+use super::hash::create_hashes;
+use super::physicalplan::ExecutionPlan;
+use super::relation::Relation;
+
+/// The way that rows are distributed between the output partitions of a repartition
+#[derive(Clone)]
+pub enum Partitioning {
+    /// Distribute whole batches between the given number of partitions in turn
+    RoundRobinBatch(usize),
+    /// Distribute rows between the given number of partitions by the hash of the
+    /// values of the expressions, so that equal values are in the same partition
+    Hash(Vec<RuntimeExpr>, usize),
+}
+
+impl Partitioning {
+    /// Get the number of partitions that rows are distributed between
+    pub fn partition_count(&self) -> usize {
+        match self {
+            Partitioning::RoundRobinBatch(n) => *n,
+            Partitioning::Hash(_, n) => *n,
+        }
+    }
+}
+
+type Receivers = Vec<Option<Receiver<Result<RecordBatch>>>>;
+
+/// The number of batches that are buffered for each output partition before the input
+/// threads wait for the partition to be read
+const PARTITION_BUFFER_SIZE: usize = 16;
+
+/// Execution plan for repartitioning its input. All input partitions are executed on
+/// separate threads as soon as the first output partition is executed, and each output
+/// partition can only be executed once. At most `PARTITION_BUFFER_SIZE` batches are
+/// buffered for each output partition, so the output partitions should be read
+/// concurrently.
+pub struct RepartitionExec {
+    input: Arc<ExecutionPlan>,
+    partitioning: Partitioning,
+    receivers: Mutex<Option<Receivers>>,
+}
+
+impl RepartitionExec {
+    /// Create a repartition of the input, which fails if the partitioning has no
+    /// output partitions
+    pub fn try_new(
+        input: Arc<ExecutionPlan>,
+        partitioning: Partitioning,
+    ) -> Result<Self> {
+        if partitioning.partition_count() == 0 {
+            return Err(ExecutionError::General(
+                "Cannot repartition into 0 partitions".to_string(),
+            ));
+        }
+        Ok(Self {
+            input,
+            partitioning,
+            receivers: Mutex::new(None),
+        })
+    }
+
+    /// Start executing the input partitions, returning the receiving end of a channel
+    /// for each output partition
+    fn start(&self) -> Receivers {
+        let partition_count = self.partitioning.partition_count();
+        let (senders, receivers): (Vec<SyncSender<Result<RecordBatch>>>, Vec<_>) = (0
+            ..partition_count)
+            .map(|_| mpsc::sync_channel(PARTITION_BUFFER_SIZE))
+            .unzip();
+
+        for input_partition in 0..self.input.output_partitions() {
+            let input = self.input.clone();
+            let partitioning = self.partitioning.clone();
+            let senders = senders.clone();
+            thread::spawn(move || {
+                repartition(input, input_partition, partitioning, senders)
+            });
+        }
+
+        receivers.into_iter().map(Some).collect()
+    }
+}
+
+impl ExecutionPlan for RepartitionExec {
+    fn schema(&self) -> Arc<Schema> {
+        self.input.schema()
+    }
+
+    fn children(&self) -> Vec<Arc<ExecutionPlan>> {
+        vec![self.input.clone()]
+    }
+
+    fn output_partitions(&self) -> usize {
+        self.partitioning.partition_count()
+    }
+
+    fn execute(&self, partition: usize) -> Result<Rc<RefCell<Relation>>> {
+        let mut receivers = match self.receivers.lock() {
+            Ok(receivers) => receivers,
+            Err(_) => {
+                return Err(ExecutionError::InternalError(
+                    "Repartition state is poisoned".to_string(),
+                ))
+            }
+        };
+        if receivers.is_none() {
+            *receivers = Some(self.start());
+        }
+
+        match receivers
+            .as_mut()
+            .and_then(|r| r.get_mut(partition))
+            .and_then(|r| r.take())
+        {
+            Some(receiver) => Ok(Rc::new(RefCell::new(RepartitionRelation {
+                schema: self.input.schema(),
+                receiver,
+            }))),
+            None => Err(ExecutionError::InternalError(format!(
+                "Partition {} of repartition is invalid or has already been executed",
+                partition
+            ))),
+        }
+    }
+}
+
+/// A relation that receives the batches for one output partition of a repartition
+pub struct RepartitionRelation {
+    schema: Arc<Schema>,
+    receiver: Receiver<Result<RecordBatch>>,
+}
+
+impl Relation for RepartitionRelation {
+    fn next(&mut self) -> Result<Option<RecordBatch>> {
+        match self.receiver.recv() {
+            Ok(result) => result.map(Some),
+            // every input partition has been sent
+            Err(_) => Ok(None),
+        }
+    }
+
+    fn schema(&self) -> &Arc<Schema> {
+        &self.schema
+    }
+}
+
+/// Execute one input partition, sending its rows to the output partitions. If the
+/// input fails, every output partition receives an error.
+fn repartition(
+    input: Arc<ExecutionPlan>,
+    input_partition: usize,
+    partitioning: Partitioning,
+    senders: Vec<SyncSender<Result<RecordBatch>>>,
+) {
+    debug!("Repartitioning input partition {}", input_partition);
+    let result = panic::catch_unwind(AssertUnwindSafe(|| -> Result<()> {
+        let relation = input.execute(input_partition)?;
+        // start each input partition at a different output partition
+        let mut next_output = input_partition;
+        loop {
+            let batch = match relation.borrow_mut().next()? {
+                Some(batch) => batch,
+                None => return Ok(()),
+            };
+            trace!(
+                "Input partition {} produced a batch of {} rows",
+                input_partition,
+                batch.num_rows()
+            );
+
+            // send errors are ignored because they only mean that an output partition
+            // is no longer being read
+            match partitioning {
+                Partitioning::RoundRobinBatch(n) => {
+                    let _ = senders[next_output % n].send(Ok(batch));
+                    next_output += 1;
+                }
+                Partitioning::Hash(ref expr, n) => {
+                    for (i, batch) in
+                        hash_partition(&batch, expr, n)?.into_iter().enumerate()
+                    {
+                        if let Some(batch) = batch {
+                            let _ = senders[i].send(Ok(batch));
+                        }
+                    }
+                }
+            }
+        }
+    }));
+
+    let message = match result {
+        Ok(Ok(())) => {
+            debug!(
+                "Finished repartitioning input partition {}",
+                input_partition
+            );
+            return;
+        }
+        Ok(Err(e)) => format!("Failed to repartition input: {:?}", e),
+        Err(_) => "Thread panicked while repartitioning input".to_string(),
+    };
+    warn!("{}", message);
+    for sender in &senders {
+        let _ = sender.send(Err(ExecutionError::ExecutionError(message.clone())));
+    }
+}
+
+/// Split a batch into one batch per output partition by the hash of the values of the
+/// expressions, with `None` for partitions that do not receive any rows
+fn hash_partition(
+    batch: &RecordBatch,
+    expr: &Vec<RuntimeExpr>,
+    partition_count: usize,
+) -> Result<Vec<Option<RecordBatch>>> {
+    let arrays = expr
+        .iter()
+        .map(|e| e.get_func()(batch))
+        .collect::<Result<Vec<ArrayRef>>>()?;
+    let hashes = if arrays.is_empty() {
+        vec![0; batch.num_rows()]
+    } else {
+        create_hashes(&arrays)?
+    };
+    let partitions: Vec<usize> = hashes
+        .iter()
+        .map(|hash| (hash % partition_count as u64) as usize)
+        .collect();
+
+    (0..partition_count)
+        .map(|p| {
+            let mask: Vec<bool> = partitions.iter().map(|i| *i == p).collect();
+            if !mask.contains(&true) {
+                return Ok(None);
+            }
+            let mask = BooleanArray::from(mask);
+            let columns = batch
+                .columns()
+                .iter()
+                .map(|column| filter(column, &mask))
+                .collect::<Result<Vec<ArrayRef>>>()?;
+            Ok(Some(RecordBatch::new(batch.schema().clone(), columns)))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution::expression::CompiledExpr;
+    use crate::execution::relation::ValuesRelation;
+    use crate::logicalplan::ScalarValue;
+    use arrow::datatypes::{DataType, Field};
+
+    #[test]
+    fn round_robin() {
+        let repartition = RepartitionExec::try_new(
+            test_exec(vec![1, 2, 3, 4, 5]),
+            Partitioning::RoundRobinBatch(3),
+        )
+        .unwrap();
+        assert_eq!(3, repartition.output_partitions());
+
+        // each input partition starts at a different output partition
+        let partitions = collect_partitions(&repartition);
+        assert_eq!(vec![2, 2, 1], row_counts(&partitions));
+
+        let mut values: Vec<u32> = partitions.into_iter().flatten().collect();
+        values.sort();
+        assert_eq!(vec![1, 2, 3, 4, 5], values);
+    }
+
+    #[test]
+    fn hash() {
+        let values = vec![1, 2, 3, 1, 2, 3, 1, 2, 3, 7];
+        let repartition = RepartitionExec::try_new(
+            test_exec(values.clone()),
+            Partitioning::Hash(vec![column_expr(0)], 4),
+        )
+        .unwrap();
+        assert_eq!(4, repartition.output_partitions());
+
+        let partitions = collect_partitions(&repartition);
+
+        // every row is in exactly one partition
+        let mut actual: Vec<u32> = partitions.iter().flatten().cloned().collect();
+        actual.sort();
+        let mut expected = values;
+        expected.sort();
+        assert_eq!(expected, actual);
+
+        // equal values are in the same partition
+        for value in &[1, 2, 3, 7] {
+            let count = partitions
+                .iter()
+                .filter(|partition| partition.contains(value))
+                .count();
+            assert_eq!(1, count);
+        }
+    }
+
+    #[test]
+    fn execute_partition_twice() {
+        let repartition = RepartitionExec::try_new(
+            test_exec(vec![1]),
+            Partitioning::RoundRobinBatch(2),
+        )
+        .unwrap();
+        assert!(repartition.execute(0).is_ok());
+        assert!(repartition.execute(0).is_err());
+        assert!(repartition.execute(2).is_err());
+    }
+
+    #[test]
+    fn zero_partitions() {
+        assert!(RepartitionExec::try_new(
+            test_exec(vec![1]),
+            Partitioning::RoundRobinBatch(0)
+        )
+        .is_err());
+        assert!(RepartitionExec::try_new(
+            test_exec(vec![1]),
+            Partitioning::Hash(vec![column_expr(0)], 0)
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn more_batches_than_buffer() {
+        // the input threads wait while the partitions are read concurrently
+        let values: Vec<u32> = (0..4 * PARTITION_BUFFER_SIZE as u32).collect();
+        let repartition = Arc::new(
+            RepartitionExec::try_new(
+                test_exec(values.clone()),
+                Partitioning::RoundRobinBatch(2),
+            )
+            .unwrap(),
+        );
+        let readers: Vec<_> = (0..2)
+            .map(|p| {
+                let repartition = repartition.clone();
+                thread::spawn(move || collect_partition(repartition.as_ref(), p))
+            })
+            .collect();
+        let mut actual: Vec<u32> = readers
+            .into_iter()
+            .flat_map(|reader| reader.join().unwrap())
+            .collect();
+        actual.sort();
+        assert_eq!(values, actual);
+    }
+
+    fn test_schema() -> Arc<Schema> {
+        Arc::new(Schema::new(vec![Field::new("a", DataType::UInt32, false)]))
+    }
+
+    /// Execution plan with one partition per value, where each partition produces a
+    /// single row containing its value
+    struct TestExec {
+        values: Vec<u32>,
+    }
+
+    impl ExecutionPlan for TestExec {
+        fn schema(&self) -> Arc<Schema> {
+            test_schema()
+        }
+
+        fn children(&self) -> Vec<Arc<ExecutionPlan>> {
+            vec![]
+        }
+
+        fn output_partitions(&self) -> usize {
+            self.values.len()
+        }
+
+        fn execute(&self, partition: usize) -> Result<Rc<RefCell<Relation>>> {
+            Ok(Rc::new(RefCell::new(ValuesRelation::new(
+                test_schema(),
+                vec![vec![ScalarValue::UInt32(self.values[partition])]],
+            ))))
+        }
+    }
+
+    fn test_exec(values: Vec<u32>) -> Arc<ExecutionPlan> {
+        Arc::new(TestExec { values })
+    }
+
+    fn column_expr(index: usize) -> RuntimeExpr {
+        let f: CompiledExpr =
+            Arc::new(move |batch: &RecordBatch| Ok((*batch.column(index)).clone()));
+        RuntimeExpr::Compiled {
+            name: "a".to_string(),
+            f,
+            t: DataType::UInt32,
+        }
+    }
+
+    fn collect_partitions(plan: &ExecutionPlan) -> Vec<Vec<u32>> {
+        (0..plan.output_partitions())
+            .map(|p| collect_partition(plan, p))
+            .collect()
+    }
+
+    fn collect_partition(plan: &ExecutionPlan, partition: usize) -> Vec<u32> {
+        let relation = plan.execute(partition).unwrap();
+        let mut values = vec![];
+        while let Some(batch) = relation.borrow_mut().next().unwrap() {
+            let array = batch
+                .column(0)
+                .as_any()
+                .downcast_ref::<UInt32Array>()
+                .unwrap();
+            for i in 0..batch.num_rows() {
+                values.push(array.value(i));
+            }
+        }
+        values
+    }
+
+    fn row_counts(partitions: &Vec<Vec<u32>>) -> Vec<usize> {
+        partitions.iter().map(|p| p.len()).collect()
+    }
+}
@@ -0,0 +1,220 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Row-oriented access to the values of record batches, for callers such as table
+//! renderers that consume query results one row at a time
+
+use std::sync::Arc;
+
+use arrow::array::*;
+use arrow::datatypes::DataType;
+use arrow::record_batch::RecordBatch;
+
+use super::super::logicalplan::ScalarValue;
+use super::error::{ExecutionError, Result};
+
+/// Read the rows of a record batch
+pub trait RowAccess {
+    /// Iterate over the rows of the batch
+    fn rows(&self) -> Rows;
+}
+
+impl RowAccess for RecordBatch {
+    fn rows(&self) -> Rows {
+        Rows {
+            batch: self,
+            index: 0,
+        }
+    }
+}
+
+/// Iterator over the rows of a record batch
+pub struct Rows<'a> {
+    batch: &'a RecordBatch,
+    index: usize,
+}
+
+impl<'a> Iterator for Rows<'a> {
+    type Item = Row<'a>;
+
+    fn next(&mut self) -> Option<Row<'a>> {
+        if self.index == self.batch.num_rows() {
+            return None;
+        }
+        let row = Row {
+            batch: self.batch,
+            index: self.index,
+        };
+        self.index += 1;
+        Some(row)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.batch.num_rows() - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+/// A single row of a record batch, which reads the values of its columns on demand
+pub struct Row<'a> {
+    batch: &'a RecordBatch,
+    index: usize,
+}
+
+macro_rules! primitive_value {
+    ($ARRAY:expr, $INDEX:expr, $ARRAY_TYPE:ident, $SCALAR:ident) => {{
+        let array = $ARRAY.as_any().downcast_ref::<$ARRAY_TYPE>().unwrap();
+        ScalarValue::$SCALAR(array.value($INDEX))
+    }};
+}
+
+impl<'a> Row<'a> {
+    /// Get the index of this row in its batch
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Get the number of columns in this row
+    pub fn num_columns(&self) -> usize {
+        self.batch.num_columns()
+    }
+
+    /// Determine whether the value of a column is null
+    pub fn is_null(&self, column: usize) -> bool {
+        self.batch.column(column).is_null(self.index)
+    }
+
+    /// Get the value of a column. Null values are returned as a typed null.
+    pub fn value(&self, column: usize) -> Result<ScalarValue> {
+        if column >= self.batch.num_columns() {
+            return Err(ExecutionError::InvalidColumn(format!(
+                "Column index {} is out of bounds for a row with {} columns",
+                column,
+                self.batch.num_columns()
+            )));
+        }
+        let array = self.batch.column(column);
+        let i = self.index;
+        if array.is_null(i) {
+            return Ok(ScalarValue::TypedNull(array.data_type().clone()));
+        }
+        let value = match array.data_type() {
+            DataType::Boolean => primitive_value!(array, i, BooleanArray, Boolean),
+            DataType::Int8 => primitive_value!(array, i, Int8Array, Int8),
+            DataType::Int16 => primitive_value!(array, i, Int16Array, Int16),
+            DataType::Int32 => primitive_value!(array, i, Int32Array, Int32),
+            DataType::Int64 => primitive_value!(array, i, Int64Array, Int64),
+            DataType::UInt8 => primitive_value!(array, i, UInt8Array, UInt8),
+            DataType::UInt16 => primitive_value!(array, i, UInt16Array, UInt16),
+            DataType::UInt32 => primitive_value!(array, i, UInt32Array, UInt32),
+            DataType::UInt64 => primitive_value!(array, i, UInt64Array, UInt64),
+            DataType::Float32 => primitive_value!(array, i, Float32Array, Float32),
+            DataType::Float64 => primitive_value!(array, i, Float64Array, Float64),
+            DataType::Utf8 => {
+                let array = array.as_any().downcast_ref::<BinaryArray>().unwrap();
+                let value = String::from_utf8(array.value(i).to_vec()).map_err(|e| {
+                    ExecutionError::ExecutionError(format!("Invalid UTF-8 string: {}", e))
+                })?;
+                ScalarValue::Utf8(Arc::new(value))
+            }
+            DataType::Binary => {
+                let array = array.as_any().downcast_ref::<BinaryArray>().unwrap();
+                ScalarValue::Binary(Arc::new(array.value(i).to_vec()))
+            }
+            other => {
+                return Err(ExecutionError::NotImplemented(format!(
+                    "Reading values of type {:?} from a row is not supported",
+                    other
+                )));
+            }
+        };
+        Ok(value)
+    }
+
+    /// Get the value of the column with the given name
+    pub fn value_by_name(&self, name: &str) -> Result<ScalarValue> {
+        match self.batch.schema().column_with_name(name) {
+            Some((column, _)) => self.value(column),
+            None => Err(ExecutionError::InvalidColumn(format!(
+                "No column named '{}'",
+                name
+            ))),
+        }
+    }
+
+    /// Get the values of all columns
+    pub fn values(&self) -> Result<Vec<ScalarValue>> {
+        (0..self.num_columns())
+            .map(|column| self.value(column))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::builder::BinaryBuilder;
+    use arrow::datatypes::{Field, Schema};
+
+    fn batch() -> RecordBatch {
+        let schema = Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("name", DataType::Utf8, true),
+        ]);
+        let mut names = BinaryBuilder::new(16);
+        names.append_string("a").unwrap();
+        names.append_null().unwrap();
+        RecordBatch::new(
+            Arc::new(schema),
+            vec![
+                Arc::new(Int32Array::from(vec![1, 2])),
+                Arc::new(names.finish()),
+            ],
+        )
+    }
+
+    #[test]
+    fn read_rows() {
+        let batch = batch();
+        let rows: Vec<Row> = batch.rows().collect();
+        assert_eq!(2, rows.len());
+        assert_eq!(1, rows[1].index());
+        assert_eq!(2, rows[1].num_columns());
+
+        assert_eq!(
+            vec![
+                ScalarValue::Int32(1),
+                ScalarValue::Utf8(Arc::new("a".to_string()))
+            ],
+            rows[0].values().unwrap()
+        );
+        assert!(rows[1].is_null(1));
+        assert_eq!(
+            ScalarValue::TypedNull(DataType::Utf8),
+            rows[1].value_by_name("name").unwrap()
+        );
+        assert_eq!(ScalarValue::Int32(2), rows[1].value_by_name("id").unwrap());
+    }
+
+    #[test]
+    fn read_invalid_columns() {
+        let batch = batch();
+        let row = batch.rows().next().unwrap();
+        assert!(row.value(2).is_err());
+        assert!(row.value_by_name("missing").is_err());
+    }
+}
@@ -0,0 +1,320 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Work-stealing task scheduler that operators use to process batches on a pool of
+//! threads shared by all partitions of a query.
+//!
+//! Partitions are executed by relations that are not thread safe, so each partition is
+//! still driven by a single thread. The work that an operator performs on each batch is
+//! independent of the other batches though, so operators submit that work to the
+//! scheduler as tasks. When one partition is much larger than the others, the tasks of
+//! that partition are stolen by the threads that would otherwise be idle.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::panic::{self, AssertUnwindSafe};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+
+use arrow::record_batch::RecordBatch;
+
+use super::error::{ExecutionError, Result};
+use super::relation::Relation;
+
+/// A task that is executed once by one of the threads of a scheduler
+trait Job: Send {
+    fn run(self: Box<Self>);
+}
+
+impl<F: FnOnce() + Send> Job for F {
+    fn run(self: Box<Self>) {
+        (*self)()
+    }
+}
+
+/// The number of queued tasks, which the threads of a scheduler wait on when they have
+/// nothing to do
+struct State {
+    queued: isize,
+    shutdown: bool,
+}
+
+/// State that is shared by the scheduler and its threads
+struct Shared {
+    queues: Vec<Mutex<VecDeque<Box<Job>>>>,
+    state: Mutex<State>,
+    available: Condvar,
+}
+
+impl Shared {
+    /// Take a task from the front of the queue of a thread, or steal one from the back
+    /// of the queue of another thread
+    fn find_job(&self, index: usize) -> Option<Box<Job>> {
+        if let Some(job) = self.queues[index].lock().unwrap().pop_front() {
+            return Some(job);
+        }
+        let n = self.queues.len();
+        (1..n).find_map(|i| self.queues[(index + i) % n].lock().unwrap().pop_back())
+    }
+
+    fn run_worker(&self, index: usize) {
+        loop {
+            if let Some(job) = self.find_job(index) {
+                self.state.lock().unwrap().queued -= 1;
+                // a task that panics only fails the query that submitted it, which
+                // notices because the task never sends its result
+                let _ = panic::catch_unwind(AssertUnwindSafe(move || job.run()));
+                continue;
+            }
+            let mut state = self.state.lock().unwrap();
+            while state.queued <= 0 && !state.shutdown {
+                state = self.available.wait(state).unwrap();
+            }
+            if state.shutdown && state.queued <= 0 {
+                return;
+            }
+        }
+    }
+}
+
+/// A pool of threads that execute tasks, where each thread has its own queue of tasks
+/// and steals tasks from the other queues when its own queue is empty. The threads stop
+/// when the scheduler is dropped.
+pub struct Scheduler {
+    shared: Arc<Shared>,
+    next_queue: AtomicUsize,
+}
+
+impl Scheduler {
+    /// Create a scheduler with the given number of threads
+    pub fn new(num_threads: usize) -> Self {
+        let num_threads = num_threads.max(1);
+        let shared = Arc::new(Shared {
+            queues: (0..num_threads)
+                .map(|_| Mutex::new(VecDeque::new()))
+                .collect(),
+            state: Mutex::new(State {
+                queued: 0,
+                shutdown: false,
+            }),
+            available: Condvar::new(),
+        });
+        for index in 0..num_threads {
+            let shared = shared.clone();
+            thread::Builder::new()
+                .name(format!("datafusion-worker-{}", index))
+                .spawn(move || shared.run_worker(index))
+                .expect("failed to spawn scheduler thread");
+        }
+        Self {
+            shared,
+            next_queue: AtomicUsize::new(0),
+        }
+    }
+
+    /// Get the number of threads of this scheduler
+    pub fn num_threads(&self) -> usize {
+        self.shared.queues.len()
+    }
+
+    /// Submit a task to be executed by one of the threads of this scheduler. Tasks are
+    /// distributed over the queues of the threads in turn.
+    pub fn spawn<F>(&self, f: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        let index = self.next_queue.fetch_add(1, Ordering::Relaxed) % self.num_threads();
+        self.shared.queues[index]
+            .lock()
+            .unwrap()
+            .push_back(Box::new(f));
+        self.shared.state.lock().unwrap().queued += 1;
+        self.shared.available.notify_one();
+    }
+}
+
+impl Drop for Scheduler {
+    fn drop(&mut self) {
+        self.shared.state.lock().unwrap().shutdown = true;
+        self.shared.available.notify_all();
+    }
+}
+
+/// A function that an operator applies to each batch of its input
+pub type BatchFunction = Arc<Fn(&RecordBatch) -> Result<RecordBatch> + Send + Sync>;
+
+/// Applies a function to the batches of a relation as scheduler tasks, keeping up to one
+/// batch per scheduler thread in flight and returning the results in the order of the
+/// input batches.
+pub struct BatchPipeline {
+    scheduler: Arc<Scheduler>,
+    pending: VecDeque<Receiver<Result<RecordBatch>>>,
+    input_done: bool,
+}
+
+impl BatchPipeline {
+    pub fn new(scheduler: Arc<Scheduler>) -> Self {
+        Self {
+            scheduler,
+            pending: VecDeque::new(),
+            input_done: false,
+        }
+    }
+
+    /// Read batches from the input until enough tasks are in flight, and then wait for
+    /// the result of the oldest task
+    pub fn next(
+        &mut self,
+        input: &Rc<RefCell<Relation>>,
+        f: &BatchFunction,
+    ) -> Result<Option<RecordBatch>> {
+        while !self.input_done && self.pending.len() < self.scheduler.num_threads() {
+            match input.borrow_mut().next()? {
+                Some(batch) => {
+                    let (sender, receiver) = mpsc::sync_channel(1);
+                    let f = f.clone();
+                    self.scheduler.spawn(move || {
+                        let _ = sender.send(f(&batch));
+                    });
+                    self.pending.push_back(receiver);
+                }
+                None => self.input_done = true,
+            }
+        }
+        match self.pending.pop_front() {
+            Some(receiver) => match receiver.recv() {
+                Ok(result) => result.map(Some),
+                Err(_) => Err(ExecutionError::ExecutionError(
+                    "Task failed while processing a batch".to_string(),
+                )),
+            },
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Int32Array, UInt32Array};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use std::time::Duration;
+
+    use super::super::datasource::{MemTable, TableProvider};
+    use super::super::relation::DataSourceRelation;
+
+    #[test]
+    fn run_tasks_on_all_threads() {
+        let scheduler = Scheduler::new(4);
+        assert_eq!(4, scheduler.num_threads());
+        let (sender, receiver) = mpsc::channel();
+        for i in 0..100 {
+            let sender = sender.clone();
+            scheduler.spawn(move || sender.send(i).unwrap());
+        }
+        let mut results: Vec<usize> = receiver.iter().take(100).collect();
+        results.sort();
+        assert_eq!((0..100).collect::<Vec<usize>>(), results);
+    }
+
+    #[test]
+    fn steal_tasks_from_busy_thread() {
+        let scheduler = Scheduler::new(2);
+        let (sender, receiver) = mpsc::channel();
+        // the first task blocks its thread, so the tasks that are queued behind it must
+        // be stolen by the other thread
+        let (release, blocked) = mpsc::channel::<()>();
+        scheduler.spawn(move || blocked.recv().unwrap());
+        for i in 0..10 {
+            let sender = sender.clone();
+            scheduler.spawn(move || sender.send(i).unwrap());
+        }
+        let mut results = vec![];
+        for _ in 0..10 {
+            results.push(receiver.recv_timeout(Duration::from_secs(10)).unwrap());
+        }
+        results.sort();
+        assert_eq!((0..10).collect::<Vec<usize>>(), results);
+        release.send(()).unwrap();
+    }
+
+    #[test]
+    fn panicking_task_does_not_stop_thread() {
+        let scheduler = Scheduler::new(1);
+        scheduler.spawn(|| panic!("task failed"));
+        let (sender, receiver) = mpsc::channel();
+        scheduler.spawn(move || sender.send(1).unwrap());
+        assert_eq!(1, receiver.recv_timeout(Duration::from_secs(10)).unwrap());
+    }
+
+    fn test_relation(num_batches: i32) -> Rc<RefCell<Relation>> {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, false)]));
+        let batches = (0..num_batches)
+            .map(|i| {
+                RecordBatch::new(
+                    schema.clone(),
+                    vec![Arc::new(Int32Array::from(vec![i, i + 1]))],
+                )
+            })
+            .collect();
+        let table = MemTable::try_new(&schema, batches).unwrap();
+        Rc::new(RefCell::new(DataSourceRelation::new(
+            table.scan(&None, 1024).unwrap(),
+        )))
+    }
+
+    #[test]
+    fn pipeline_preserves_batch_order() {
+        let input = test_relation(20);
+        let f: BatchFunction = Arc::new(|batch| {
+            let a = batch
+                .column(0)
+                .as_any()
+                .downcast_ref::<Int32Array>()
+                .unwrap();
+            // later batches finish first
+            thread::sleep(Duration::from_millis(20 - a.value(0) as u64));
+            let schema = Schema::new(vec![Field::new("b", DataType::UInt32, false)]);
+            Ok(RecordBatch::new(
+                Arc::new(schema),
+                vec![Arc::new(UInt32Array::from(vec![a.value(0) as u32 * 10]))],
+            ))
+        });
+        let mut pipeline = BatchPipeline::new(Arc::new(Scheduler::new(4)));
+        let mut results = vec![];
+        while let Some(batch) = pipeline.next(&input, &f).unwrap() {
+            let b = batch
+                .column(0)
+                .as_any()
+                .downcast_ref::<UInt32Array>()
+                .unwrap();
+            results.push(b.value(0));
+        }
+        assert_eq!((0..20).map(|i| i * 10).collect::<Vec<u32>>(), results);
+    }
+
+    #[test]
+    fn pipeline_reports_failed_task() {
+        let input = test_relation(3);
+        let f: BatchFunction = Arc::new(|_| panic!("task failed"));
+        let mut pipeline = BatchPipeline::new(Arc::new(Scheduler::new(2)));
+        assert!(pipeline.next(&input, &f).is_err());
+    }
+}
@@ -0,0 +1,369 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Execution of a sort, which sorts each partition of its input in memory
+
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use arrow::array::*;
+use arrow::compute;
+use arrow::datatypes::Schema;
+use arrow::record_batch::RecordBatch;
+
+use super::context::{Collation, NanOrdering};
+use super::error::Result;
+use super::memory::{batch_memory_size, MemoryManager, MemoryReservation};
+use super::merge::{compare_rows, SortExpr};
+use super::physicalplan::ExecutionPlan;
+use super::relation::Relation;
+
+/// Execution plan for sorting each partition of its input separately. The sorted
+/// partitions can be combined with a `SortPreservingMergeExec`.
+pub struct SortExec {
+    input: Arc<ExecutionPlan>,
+    sort_expr: Vec<SortExpr>,
+    nan_ordering: NanOrdering,
+    collation: Collation,
+    batch_size: usize,
+    memory_manager: Arc<MemoryManager>,
+}
+
+impl SortExec {
+    pub fn new(
+        input: Arc<ExecutionPlan>,
+        sort_expr: Vec<SortExpr>,
+        nan_ordering: NanOrdering,
+        collation: Collation,
+        batch_size: usize,
+        memory_manager: Arc<MemoryManager>,
+    ) -> Self {
+        Self {
+            input,
+            sort_expr,
+            nan_ordering,
+            collation,
+            batch_size,
+            memory_manager,
+        }
+    }
+}
+
+impl ExecutionPlan for SortExec {
+    fn schema(&self) -> Arc<Schema> {
+        self.input.schema()
+    }
+
+    fn children(&self) -> Vec<Arc<ExecutionPlan>> {
+        vec![self.input.clone()]
+    }
+
+    fn output_partitions(&self) -> usize {
+        self.input.output_partitions()
+    }
+
+    fn execute(&self, partition: usize) -> Result<Rc<RefCell<Relation>>> {
+        Ok(Rc::new(RefCell::new(SortRelation {
+            input: self.input.execute(partition)?,
+            schema: self.input.schema(),
+            sort_expr: self.sort_expr.clone(),
+            nan_ordering: self.nan_ordering,
+            collation: self.collation,
+            batch_size: self.batch_size.max(1),
+            memory: MemoryReservation::new(self.memory_manager.clone(), "sort"),
+            sorted: None,
+            offset: 0,
+        })))
+    }
+}
+
+/// A relation that reads all of the batches of its input and returns their rows in
+/// sorted order. The sort is stable, so rows with equal sort keys keep the order in
+/// which they were read.
+pub struct SortRelation {
+    input: Rc<RefCell<Relation>>,
+    schema: Arc<Schema>,
+    sort_expr: Vec<SortExpr>,
+    nan_ordering: NanOrdering,
+    collation: Collation,
+    batch_size: usize,
+    memory: MemoryReservation,
+    /// The columns of all of the input batches concatenated, and the index of each row
+    /// of the columns in sorted order, once the input has been read
+    sorted: Option<(Vec<ArrayRef>, Vec<u32>)>,
+    /// The number of sorted rows that have been returned
+    offset: usize,
+}
+
+impl SortRelation {
+    /// Read all of the batches of the input and sort their rows
+    fn sort(&mut self) -> Result<(Vec<ArrayRef>, Vec<u32>)> {
+        let mut batches: Vec<RecordBatch> = vec![];
+        let mut keys: Vec<Vec<ArrayRef>> = vec![];
+        let mut indices: Vec<(usize, usize)> = vec![];
+        while let Some(batch) = self.input.borrow_mut().next()? {
+            if batch.num_rows() == 0 {
+                continue;
+            }
+            self.memory.try_grow(batch_memory_size(&batch))?;
+            keys.push(
+                self.sort_expr
+                    .iter()
+                    .map(|e| e.expr.get_func()(&batch))
+                    .collect::<Result<Vec<ArrayRef>>>()?,
+            );
+            indices.extend((0..batch.num_rows()).map(|row| (batches.len(), row)));
+            batches.push(batch);
+        }
+
+        // the comparison fails for types that cannot be sorted, which is the same for
+        // every pair of rows, so the first error is returned after the sort
+        let mut error = None;
+        indices.sort_by(|(left_batch, left_row), (right_batch, right_row)| {
+            match compare_rows(
+                &self.sort_expr,
+                &keys[*left_batch],
+                *left_row,
+                &keys[*right_batch],
+                *right_row,
+                self.nan_ordering,
+                self.collation,
+            ) {
+                Ok(ordering) => ordering,
+                Err(e) => {
+                    error.get_or_insert(e);
+                    Ordering::Equal
+                }
+            }
+        });
+        if let Some(e) = error {
+            return Err(e);
+        }
+        if batches.is_empty() {
+            return Ok((vec![], vec![]));
+        }
+
+        // only the sort keys need to be compared, and the other columns are copied by
+        // position, so that columns of any type can be sorted
+        let mut starts = Vec::with_capacity(batches.len());
+        let mut num_rows = 0;
+        for batch in &batches {
+            starts.push(num_rows);
+            num_rows += batch.num_rows();
+        }
+        let indices: Vec<u32> = indices
+            .iter()
+            .map(|(batch, row)| (starts[*batch] + row) as u32)
+            .collect();
+        let columns = (0..self.schema.fields().len())
+            .map(|i| match batches.as_slice() {
+                [batch] => Ok(batch.column(i).clone()),
+                _ => {
+                    let columns: Vec<ArrayRef> = batches
+                        .iter()
+                        .map(|batch| batch.column(i).clone())
+                        .collect();
+                    Ok(compute::concat(&columns)?)
+                }
+            })
+            .collect::<Result<Vec<ArrayRef>>>()?;
+        Ok((columns, indices))
+    }
+}
+
+impl Relation for SortRelation {
+    fn next(&mut self) -> Result<Option<RecordBatch>> {
+        if self.sorted.is_none() {
+            self.sorted = Some(self.sort()?);
+        }
+        let (columns, indices) = match self.sorted {
+            Some(ref sorted) => sorted,
+            None => return Ok(None),
+        };
+
+        if self.offset >= indices.len() {
+            self.memory.free();
+            return Ok(None);
+        }
+        let end = (self.offset + self.batch_size).min(indices.len());
+        let indices = UInt32Array::from(indices[self.offset..end].to_vec());
+        self.offset = end;
+
+        let columns = columns
+            .iter()
+            .map(|column| Ok(compute::take(column, &indices)?))
+            .collect::<Result<Vec<ArrayRef>>>()?;
+        Ok(Some(RecordBatch::new(self.schema.clone(), columns)))
+    }
+
+    fn schema(&self) -> &Arc<Schema> {
+        &self.schema
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution::expression::{CompiledExpr, RuntimeExpr};
+    use crate::execution::merge::SortPreservingMergeExec;
+    use crate::execution::relation::ValuesRelation;
+    use crate::logicalplan::ScalarValue;
+    use arrow::datatypes::{DataType, Field};
+
+    /// Execution plan where each partition produces a single batch of values
+    struct PartitionsExec {
+        schema: Arc<Schema>,
+        partitions: Vec<Vec<Option<i64>>>,
+    }
+
+    impl ExecutionPlan for PartitionsExec {
+        fn schema(&self) -> Arc<Schema> {
+            self.schema.clone()
+        }
+
+        fn children(&self) -> Vec<Arc<ExecutionPlan>> {
+            vec![]
+        }
+
+        fn output_partitions(&self) -> usize {
+            self.partitions.len()
+        }
+
+        fn execute(&self, partition: usize) -> Result<Rc<RefCell<Relation>>> {
+            let values = self.partitions[partition]
+                .iter()
+                .map(|v| match v {
+                    Some(n) => vec![ScalarValue::Int64(*n)],
+                    None => vec![ScalarValue::Null],
+                })
+                .collect();
+            Ok(Rc::new(RefCell::new(ValuesRelation::new(
+                self.schema.clone(),
+                values,
+            ))))
+        }
+    }
+
+    fn partitions_exec(partitions: Vec<Vec<Option<i64>>>) -> Arc<ExecutionPlan> {
+        Arc::new(PartitionsExec {
+            schema: Arc::new(Schema::new(vec![Field::new("a", DataType::Int64, true)])),
+            partitions,
+        })
+    }
+
+    fn sort_expr(asc: bool, nulls_first: bool) -> Vec<SortExpr> {
+        let f: CompiledExpr =
+            Arc::new(|batch: &RecordBatch| Ok((*batch.column(0)).clone()));
+        vec![SortExpr {
+            expr: RuntimeExpr::Compiled {
+                name: "a".to_string(),
+                f,
+                t: DataType::Int64,
+            },
+            asc,
+            nulls_first,
+        }]
+    }
+
+    fn collect_values(relation: Rc<RefCell<Relation>>) -> Vec<Option<i64>> {
+        let mut values = vec![];
+        while let Some(batch) = relation.borrow_mut().next().unwrap() {
+            let array = batch
+                .column(0)
+                .as_any()
+                .downcast_ref::<Int64Array>()
+                .unwrap();
+            for i in 0..batch.num_rows() {
+                if array.is_null(i) {
+                    values.push(None);
+                } else {
+                    values.push(Some(array.value(i)));
+                }
+            }
+        }
+        values
+    }
+
+    fn sort(
+        input: Arc<ExecutionPlan>,
+        asc: bool,
+        nulls_first: bool,
+        memory_manager: Arc<MemoryManager>,
+    ) -> SortExec {
+        SortExec::new(
+            input,
+            sort_expr(asc, nulls_first),
+            NanOrdering::NanLast,
+            Collation::Binary,
+            2,
+            memory_manager,
+        )
+    }
+
+    #[test]
+    fn sort_partition() {
+        let input = partitions_exec(vec![vec![Some(3), None, Some(1), Some(2)]]);
+        let memory_manager = Arc::new(MemoryManager::new(None));
+
+        let exec = sort(input.clone(), true, false, memory_manager.clone());
+        let values = collect_values(exec.execute(0).unwrap());
+        assert_eq!(vec![Some(1), Some(2), Some(3), None], values);
+        assert_eq!(0, memory_manager.used());
+
+        let exec = sort(input.clone(), false, true, memory_manager.clone());
+        let values = collect_values(exec.execute(0).unwrap());
+        assert_eq!(vec![None, Some(3), Some(2), Some(1)], values);
+
+        let exec = sort(input, true, true, memory_manager);
+        let values = collect_values(exec.execute(0).unwrap());
+        assert_eq!(vec![None, Some(1), Some(2), Some(3)], values);
+    }
+
+    #[test]
+    fn sort_and_merge_partitions() {
+        let input = partitions_exec(vec![
+            vec![Some(5), Some(1)],
+            vec![],
+            vec![None, Some(4), Some(2)],
+            vec![Some(3)],
+        ]);
+        let memory_manager = Arc::new(MemoryManager::new(None));
+        let sort = Arc::new(sort(input, true, false, memory_manager));
+        assert_eq!(4, sort.output_partitions());
+        let merge = SortPreservingMergeExec::new(
+            sort,
+            sort_expr(true, false),
+            NanOrdering::NanLast,
+            Collation::Binary,
+            1024,
+        );
+        let values = collect_values(merge.execute(0).unwrap());
+        let expected = vec![Some(1), Some(2), Some(3), Some(4), Some(5), None];
+        assert_eq!(expected, values);
+    }
+
+    #[test]
+    fn sort_exceeds_memory_limit() {
+        let input = partitions_exec(vec![vec![Some(1), Some(2)]]);
+        let memory_manager = Arc::new(MemoryManager::new(Some(1)));
+        let exec = sort(input, true, false, memory_manager.clone());
+        assert!(exec.execute(0).unwrap().borrow_mut().next().is_err());
+        assert_eq!(0, memory_manager.used());
+    }
+}
@@ -0,0 +1,131 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Asynchronous execution of a physical plan, producing a stream of batches
+
+use std::sync::Arc;
+use std::thread;
+
+use arrow::datatypes::Schema;
+use arrow::record_batch::RecordBatch;
+use futures::sync::mpsc::{self, Receiver, Sender};
+use futures::{Async, Future, Poll, Sink, Stream};
+
+use super::error::{ExecutionError, Result};
+use super::physicalplan::ExecutionPlan;
+
+/// A stream of the batches produced by executing a plan on a background thread, so that
+/// the consumer of the results does not block while the query runs
+pub struct RecordBatchStream {
+    schema: Arc<Schema>,
+    receiver: Receiver<Result<RecordBatch>>,
+}
+
+impl RecordBatchStream {
+    /// Start executing the first partition of a plan on a new thread. The thread stops
+    /// early if the stream is dropped before all results have been consumed.
+    pub fn new(plan: Arc<ExecutionPlan>) -> Self {
+        let schema = plan.schema();
+        let (sender, receiver) = mpsc::channel(1);
+        thread::spawn(move || execute_plan(plan, sender));
+        Self { schema, receiver }
+    }
+
+    /// Get the schema of the batches produced by this stream
+    pub fn schema(&self) -> &Arc<Schema> {
+        &self.schema
+    }
+}
+
+impl Stream for RecordBatchStream {
+    type Item = RecordBatch;
+    type Error = ExecutionError;
+
+    fn poll(&mut self) -> Poll<Option<RecordBatch>, ExecutionError> {
+        match self.receiver.poll() {
+            Ok(Async::Ready(Some(Ok(batch)))) => Ok(Async::Ready(Some(batch))),
+            Ok(Async::Ready(Some(Err(e)))) => Err(e),
+            Ok(Async::Ready(None)) => Ok(Async::Ready(None)),
+            Ok(Async::NotReady) => Ok(Async::NotReady),
+            Err(_) => Err(ExecutionError::InternalError(
+                "Failed to receive batch from execution thread".to_string(),
+            )),
+        }
+    }
+}
+
+/// Execute a plan, sending each batch to the stream until the results are exhausted,
+/// an error occurs, or the stream is dropped
+fn execute_plan(plan: Arc<ExecutionPlan>, sender: Sender<Result<RecordBatch>>) {
+    let relation = match plan.execute(0) {
+        Ok(relation) => relation,
+        Err(e) => {
+            warn!("Query failed: {:?}", e);
+            let _ = sender.send(Err(e)).wait();
+            return;
+        }
+    };
+
+    let mut sender = sender;
+    loop {
+        let item = match relation.borrow_mut().next() {
+            Ok(Some(batch)) => Ok(batch),
+            Ok(None) => {
+                info!("Query finished");
+                return;
+            }
+            Err(e) => {
+                warn!("Query failed: {:?}", e);
+                Err(e)
+            }
+        };
+        let is_err = item.is_err();
+        sender = match sender.send(item).wait() {
+            Ok(sender) if !is_err => sender,
+            Ok(_) => return,
+            Err(_) => {
+                debug!("Stopped executing query because the stream was dropped");
+                return;
+            }
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution::relation::ValuesExec;
+    use crate::logicalplan::ScalarValue;
+    use arrow::datatypes::{DataType, Field};
+
+    #[test]
+    fn stream_batches() {
+        let schema =
+            Arc::new(Schema::new(vec![Field::new("a", DataType::UInt64, false)]));
+        let plan = Arc::new(ValuesExec::new(
+            schema,
+            vec![vec![ScalarValue::UInt64(1)], vec![ScalarValue::UInt64(2)]],
+        ));
+
+        let stream = RecordBatchStream::new(plan);
+        assert_eq!(1, stream.schema().fields().len());
+
+        let batches = stream.collect().wait().unwrap();
+        assert_eq!(1, batches.len());
+        assert_eq!(2, batches[0].num_rows());
+    }
+}
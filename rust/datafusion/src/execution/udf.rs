@@ -0,0 +1,129 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! User-defined functions (UDFs), which can be registered with an execution context and
+//! called from SQL. Scalar functions compute a value for each row, while table functions
+//! are called in the FROM clause of a query and produce a table.
+
+use std::sync::Arc;
+
+use arrow::array::ArrayRef;
+use arrow::datatypes::DataType;
+
+use super::super::logicalplan::{FunctionMeta, FunctionType, ScalarValue, Signature};
+use super::datasource::TableProvider;
+use super::error::Result;
+
+/// Implementation of a scalar function, which computes one value for each row from the
+/// arrays of argument values, or returns an error that fails the query
+pub type ScalarFunctionImpl = Arc<Fn(&[ArrayRef]) -> Result<ArrayRef> + Send + Sync>;
+
+/// A scalar function that has been registered with an execution context
+#[derive(Clone)]
+pub struct ScalarUdf {
+    name: String,
+    signatures: Vec<Signature>,
+    return_type: DataType,
+    fun: ScalarFunctionImpl,
+}
+
+impl ScalarUdf {
+    /// Create a function that takes arguments of the given types. Arguments of other
+    /// types are cast to these types when the function is called from SQL.
+    pub fn new(
+        name: &str,
+        arg_types: Vec<DataType>,
+        return_type: DataType,
+        fun: ScalarFunctionImpl,
+    ) -> Self {
+        Self::with_signatures(name, vec![Signature::Exact(arg_types)], return_type, fun)
+    }
+
+    /// Create a function with several overloads, which accepts arguments that match any
+    /// of the signatures. The arguments of a call are cast to the types of the best
+    /// matching signature.
+    pub fn with_signatures(
+        name: &str,
+        signatures: Vec<Signature>,
+        return_type: DataType,
+        fun: ScalarFunctionImpl,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            signatures,
+            return_type,
+            fun,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn signatures(&self) -> &Vec<Signature> {
+        &self.signatures
+    }
+
+    pub fn return_type(&self) -> &DataType {
+        &self.return_type
+    }
+
+    /// Call the function with the arrays of argument values
+    pub fn invoke(&self, args: &[ArrayRef]) -> Result<ArrayRef> {
+        (self.fun)(args)
+    }
+
+    /// Get the meta-data that is used to plan calls to the function
+    pub fn function_meta(&self) -> FunctionMeta {
+        FunctionMeta::with_signatures(
+            self.name.clone(),
+            self.signatures.clone(),
+            self.return_type.clone(),
+            FunctionType::Scalar,
+        )
+    }
+}
+
+/// Implementation of a table function, which creates a table from the values of its
+/// arguments
+pub type TableFunctionImpl =
+    Arc<Fn(&[ScalarValue]) -> Result<Arc<TableProvider>> + Send + Sync>;
+
+/// A table function that has been registered with an execution context
+#[derive(Clone)]
+pub struct TableFunction {
+    name: String,
+    fun: TableFunctionImpl,
+}
+
+impl TableFunction {
+    pub fn new(name: &str, fun: TableFunctionImpl) -> Self {
+        Self {
+            name: name.to_string(),
+            fun,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Call the function with the values of its arguments, which must be literals
+    pub fn invoke(&self, args: &[ScalarValue]) -> Result<Arc<TableProvider>> {
+        (self.fun)(args)
+    }
+}
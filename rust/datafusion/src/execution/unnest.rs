@@ -0,0 +1,196 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Execution of an unnest, which expands each element of a list column into its own
+//! row and repeats the values of the other columns for each element
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use arrow::array::*;
+use arrow::datatypes::Schema;
+use arrow::record_batch::RecordBatch;
+
+use super::error::{ExecutionError, Result};
+use super::list::take;
+use super::physicalplan::ExecutionPlan;
+use super::relation::Relation;
+
+/// Execution plan for an unnest of a list column
+pub struct UnnestExec {
+    input: Arc<ExecutionPlan>,
+    column: usize,
+    schema: Arc<Schema>,
+}
+
+impl UnnestExec {
+    pub fn new(input: Arc<ExecutionPlan>, column: usize, schema: Arc<Schema>) -> Self {
+        Self {
+            input,
+            column,
+            schema,
+        }
+    }
+}
+
+impl ExecutionPlan for UnnestExec {
+    fn schema(&self) -> Arc<Schema> {
+        self.schema.clone()
+    }
+
+    fn children(&self) -> Vec<Arc<ExecutionPlan>> {
+        vec![self.input.clone()]
+    }
+
+    fn output_partitions(&self) -> usize {
+        self.input.output_partitions()
+    }
+
+    fn execute(&self, partition: usize) -> Result<Rc<RefCell<Relation>>> {
+        let input = self.input.execute(partition)?;
+        Ok(Rc::new(RefCell::new(UnnestRelation {
+            input,
+            column: self.column,
+            schema: self.schema.clone(),
+        })))
+    }
+}
+
+pub struct UnnestRelation {
+    input: Rc<RefCell<Relation>>,
+    column: usize,
+    schema: Arc<Schema>,
+}
+
+impl Relation for UnnestRelation {
+    fn next(&mut self) -> Result<Option<RecordBatch>> {
+        match self.input.borrow_mut().next()? {
+            Some(batch) => Ok(Some(unnest(&batch, self.column, &self.schema)?)),
+            None => Ok(None),
+        }
+    }
+
+    fn schema(&self) -> &Arc<Schema> {
+        &self.schema
+    }
+}
+
+/// Unnest a list column of a batch, producing one row for each element of each list
+pub fn unnest(
+    batch: &RecordBatch,
+    column: usize,
+    schema: &Arc<Schema>,
+) -> Result<RecordBatch> {
+    let list = match batch.column(column).as_any().downcast_ref::<ListArray>() {
+        Some(list) => list,
+        None => {
+            return Err(ExecutionError::ExecutionError(format!(
+                "Cannot unnest column of type {:?}, which is not a list",
+                batch.column(column).data_type()
+            )));
+        }
+    };
+
+    // the input row of each output row, and the position of its element in the
+    // values of the list array
+    let mut rows = vec![];
+    let mut elements = vec![];
+    for i in 0..list.len() {
+        if list.is_valid(i) {
+            let offset = list.value_offset(i) as usize;
+            for j in 0..list.value_length(i) as usize {
+                rows.push(Some(i));
+                elements.push(Some(offset + j));
+            }
+        }
+    }
+
+    let columns = (0..batch.num_columns())
+        .map(|i| {
+            if i == column {
+                take(&list.values(), &elements)
+            } else {
+                take(batch.column(i), &rows)
+            }
+        })
+        .collect::<Result<Vec<ArrayRef>>>()?;
+    Ok(RecordBatch::new(schema.clone(), columns))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::builder::{BinaryBuilder, Int32Builder, ListBuilder};
+    use arrow::datatypes::{DataType, Field};
+
+    #[test]
+    fn unnest_list_column() {
+        // [1, 2], null, [], [3]
+        let mut lists = ListBuilder::new(Int32Builder::new(8));
+        lists.values().append_value(1).unwrap();
+        lists.values().append_value(2).unwrap();
+        lists.append(true).unwrap();
+        lists.append(false).unwrap();
+        lists.append(true).unwrap();
+        lists.values().append_value(3).unwrap();
+        lists.append(true).unwrap();
+
+        let mut names = BinaryBuilder::new(16);
+        for name in &["a", "b", "c", "d"] {
+            names.append_string(name).unwrap();
+        }
+
+        let input_schema = Schema::new(vec![
+            Field::new("name", DataType::Utf8, false),
+            Field::new("values", DataType::List(Box::new(DataType::Int32)), true),
+        ]);
+        let batch = RecordBatch::new(
+            Arc::new(input_schema),
+            vec![Arc::new(names.finish()), Arc::new(lists.finish())],
+        );
+
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("name", DataType::Utf8, false),
+            Field::new("values", DataType::Int32, true),
+        ]));
+        let unnested = unnest(&batch, 1, &schema).unwrap();
+        assert_eq!(3, unnested.num_rows());
+
+        let names = unnested
+            .column(0)
+            .as_any()
+            .downcast_ref::<BinaryArray>()
+            .unwrap();
+        let values = unnested
+            .column(1)
+            .as_any()
+            .downcast_ref::<Int32Array>()
+            .unwrap();
+        let rows: Vec<(String, i32)> = (0..3)
+            .map(|i| (names.get_string(i), values.value(i)))
+            .collect();
+        assert_eq!(
+            vec![
+                ("a".to_string(), 1),
+                ("a".to_string(), 2),
+                ("d".to_string(), 3)
+            ],
+            rows
+        );
+    }
+}
@@ -20,6 +20,8 @@
 
 extern crate arrow;
 #[macro_use]
+extern crate log;
+#[macro_use]
 extern crate serde_derive;
 extern crate serde_json;
 extern crate sqlparser;
@@ -17,23 +17,68 @@
 
 //! Logical query plan
 
+use std::collections::HashSet;
 use std::fmt;
 use std::fmt::{Error, Formatter};
+use std::hash::{Hash, Hasher};
+use std::mem;
+use std::ops::{Add, Div, Mul, Sub};
 use std::rc::Rc;
 use std::sync::Arc;
 
 use arrow::datatypes::*;
 
+use super::execution::error::ExecutionError;
+use super::optimizer::optimizer::ExprRewriter;
+use super::optimizer::utils;
+use super::sqlplanner::exprlist_to_fields;
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum FunctionType {
     Scalar,
     Aggregate,
 }
 
+/// The types of the arguments that a function accepts
+#[derive(Debug, Clone, PartialEq)]
+pub enum Signature {
+    /// Arguments of exactly these types
+    Exact(Vec<DataType>),
+    /// A fixed number of arguments that all have the same type, which is one of the
+    /// given types
+    Uniform(usize, Vec<DataType>),
+    /// One or more arguments that all have the same type, which is one of the given
+    /// types
+    Variadic(Vec<DataType>),
+    /// A fixed number of arguments of any types
+    Any(usize),
+}
+
+impl Signature {
+    /// Get the lists of argument types that this signature accepts for the given
+    /// number of arguments
+    fn candidates(&self, arg_types: &[DataType]) -> Vec<Vec<DataType>> {
+        match self {
+            Signature::Exact(types) if types.len() == arg_types.len() => {
+                vec![types.clone()]
+            }
+            Signature::Uniform(n, types) if *n == arg_types.len() => {
+                types.iter().map(|t| vec![t.clone(); *n]).collect()
+            }
+            Signature::Variadic(types) if !arg_types.is_empty() => types
+                .iter()
+                .map(|t| vec![t.clone(); arg_types.len()])
+                .collect(),
+            Signature::Any(n) if *n == arg_types.len() => vec![arg_types.to_vec()],
+            _ => vec![],
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct FunctionMeta {
     name: String,
-    args: Vec<Field>,
+    signatures: Vec<Signature>,
     return_type: DataType,
     function_type: FunctionType,
 }
@@ -44,10 +89,26 @@ impl FunctionMeta {
         args: Vec<Field>,
         return_type: DataType,
         function_type: FunctionType,
+    ) -> Self {
+        let arg_types = args.iter().map(|f| f.data_type().clone()).collect();
+        Self::with_signatures(
+            name,
+            vec![Signature::Exact(arg_types)],
+            return_type,
+            function_type,
+        )
+    }
+    /// Create the meta-data of a function that has several overloads, each of which
+    /// accepts arguments matching one of the signatures
+    pub fn with_signatures(
+        name: String,
+        signatures: Vec<Signature>,
+        return_type: DataType,
+        function_type: FunctionType,
     ) -> Self {
         FunctionMeta {
             name,
-            args,
+            signatures,
             return_type,
             function_type,
         }
@@ -55,8 +116,42 @@ impl FunctionMeta {
     pub fn name(&self) -> &String {
         &self.name
     }
-    pub fn args(&self) -> &Vec<Field> {
-        &self.args
+    pub fn signatures(&self) -> &Vec<Signature> {
+        &self.signatures
+    }
+    /// Find the overload that best matches the types of the arguments of a call and get
+    /// the types that the arguments must be cast to. The best match is the one that
+    /// needs the fewest casts, and the first signature wins if several match equally
+    /// well.
+    pub fn resolve(&self, arg_types: &[DataType]) -> Result<Vec<DataType>, String> {
+        let mut best: Option<(usize, Vec<DataType>)> = None;
+        for signature in &self.signatures {
+            for candidate in signature.candidates(arg_types) {
+                let coercible = candidate
+                    .iter()
+                    .zip(arg_types)
+                    .all(|(to, from)| to == from || can_coerce_from(to, from));
+                if !coercible {
+                    continue;
+                }
+                let casts = candidate
+                    .iter()
+                    .zip(arg_types)
+                    .filter(|(to, from)| to != from)
+                    .count();
+                match best {
+                    Some((best_casts, _)) if best_casts <= casts => {}
+                    _ => best = Some((casts, candidate)),
+                }
+            }
+        }
+        match best {
+            Some((_, types)) => Ok(types),
+            None => Err(format!(
+                "No signature of function '{}' matches the argument types {:?}",
+                self.name, arg_types
+            )),
+        }
     }
     pub fn return_type(&self) -> &DataType {
         &self.return_type
@@ -66,7 +161,7 @@ impl FunctionMeta {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Operator {
     Eq,
     NotEq,
@@ -109,8 +204,73 @@ pub enum ScalarValue {
     UInt16(u16),
     UInt32(u32),
     UInt64(u64),
-    Utf8(Rc<String>),
+    Utf8(Arc<String>),
     Struct(Vec<ScalarValue>),
+    /// Days since the UNIX epoch
+    Date32(i32),
+    /// Milliseconds since the UNIX epoch
+    Date64(i64),
+    /// Time of day in seconds or milliseconds
+    Time32(i32, TimeUnit),
+    /// Time of day in microseconds or nanoseconds
+    Time64(i64, TimeUnit),
+    /// Time elapsed since the UNIX epoch, in the given unit, along with the timezone of
+    /// the value, if any
+    Timestamp(i64, TimeUnit, Option<String>),
+    /// Variable length binary value
+    Binary(Arc<Vec<u8>>),
+    /// Null value of a known type, such as a null boolean
+    TypedNull(DataType),
+    /// List of values, along with the type of the list elements
+    List(Vec<ScalarValue>, DataType),
+}
+
+// Floating point values are compared by value, so a NaN literal is not equal to itself
+// and plans that contain one are never equal. This is acceptable for comparing plans.
+impl Eq for ScalarValue {}
+
+impl Hash for ScalarValue {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        mem::discriminant(self).hash(state);
+        match self {
+            ScalarValue::Null => {}
+            ScalarValue::Boolean(v) => v.hash(state),
+            // 0.0 and -0.0 are equal, so they must have the same hash
+            ScalarValue::Float32(v) => (*v + 0.0).to_bits().hash(state),
+            ScalarValue::Float64(v) => (*v + 0.0).to_bits().hash(state),
+            ScalarValue::Int8(v) => v.hash(state),
+            ScalarValue::Int16(v) => v.hash(state),
+            ScalarValue::Int32(v) => v.hash(state),
+            ScalarValue::Int64(v) => v.hash(state),
+            ScalarValue::UInt8(v) => v.hash(state),
+            ScalarValue::UInt16(v) => v.hash(state),
+            ScalarValue::UInt32(v) => v.hash(state),
+            ScalarValue::UInt64(v) => v.hash(state),
+            ScalarValue::Utf8(v) => v.hash(state),
+            ScalarValue::Struct(v) => v.hash(state),
+            ScalarValue::Date32(v) => v.hash(state),
+            ScalarValue::Date64(v) => v.hash(state),
+            ScalarValue::Time32(v, unit) => {
+                v.hash(state);
+                unit.hash(state);
+            }
+            ScalarValue::Time64(v, unit) => {
+                v.hash(state);
+                unit.hash(state);
+            }
+            ScalarValue::Timestamp(v, unit, tz) => {
+                v.hash(state);
+                unit.hash(state);
+                tz.hash(state);
+            }
+            ScalarValue::Binary(v) => v.hash(state),
+            ScalarValue::TypedNull(data_type) => data_type.hash(state),
+            ScalarValue::List(v, data_type) => {
+                v.hash(state);
+                data_type.hash(state);
+            }
+        }
+    }
 }
 
 impl ScalarValue {
@@ -128,33 +288,142 @@ impl ScalarValue {
             ScalarValue::Float32(_) => DataType::Float32,
             ScalarValue::Float64(_) => DataType::Float64,
             ScalarValue::Utf8(_) => DataType::Utf8,
+            ScalarValue::Date32(_) => DataType::Date(DateUnit::Day),
+            ScalarValue::Date64(_) => DataType::Date(DateUnit::Millisecond),
+            ScalarValue::Time32(_, ref unit) => DataType::Time32(unit.clone()),
+            ScalarValue::Time64(_, ref unit) => DataType::Time64(unit.clone()),
+            ScalarValue::Timestamp(_, ref unit, ref tz) => {
+                DataType::Timestamp(unit.clone(), tz.clone())
+            }
+            ScalarValue::Binary(_) => DataType::Binary,
+            ScalarValue::TypedNull(ref data_type) => data_type.clone(),
+            ScalarValue::List(_, ref data_type) => {
+                DataType::List(Box::new(data_type.clone()))
+            }
             ScalarValue::Struct(_) => unimplemented!(),
-            ScalarValue::Null => unimplemented!(),
+            ScalarValue::Null => DataType::Null,
+        }
+    }
+
+    /// Convert a numeric value to another numeric type, so that literals can be coerced
+    /// when a plan is built instead of being cast when the plan is executed. Returns
+    /// `None` for values that are not numeric and for types that are not numeric.
+    pub fn cast_to(&self, data_type: &DataType) -> Option<ScalarValue> {
+        if let ScalarValue::Float32(n) = self {
+            return match data_type {
+                DataType::Float32 => Some(ScalarValue::Float32(*n)),
+                DataType::Float64 => Some(ScalarValue::Float64(*n as f64)),
+                _ => None,
+            };
         }
+        let n: i128 = match self {
+            ScalarValue::Int8(n) => *n as i128,
+            ScalarValue::Int16(n) => *n as i128,
+            ScalarValue::Int32(n) => *n as i128,
+            ScalarValue::Int64(n) => *n as i128,
+            ScalarValue::UInt8(n) => *n as i128,
+            ScalarValue::UInt16(n) => *n as i128,
+            ScalarValue::UInt32(n) => *n as i128,
+            ScalarValue::UInt64(n) => *n as i128,
+            _ => return None,
+        };
+        match data_type {
+            DataType::Int8 => Some(ScalarValue::Int8(n as i8)),
+            DataType::Int16 => Some(ScalarValue::Int16(n as i16)),
+            DataType::Int32 => Some(ScalarValue::Int32(n as i32)),
+            DataType::Int64 => Some(ScalarValue::Int64(n as i64)),
+            DataType::UInt8 => Some(ScalarValue::UInt8(n as u8)),
+            DataType::UInt16 => Some(ScalarValue::UInt16(n as u16)),
+            DataType::UInt32 => Some(ScalarValue::UInt32(n as u32)),
+            DataType::UInt64 => Some(ScalarValue::UInt64(n as u64)),
+            DataType::Float32 => Some(ScalarValue::Float32(n as f32)),
+            DataType::Float64 => Some(ScalarValue::Float64(n as f64)),
+            _ => None,
+        }
+    }
+}
+
+macro_rules! scalar_from {
+    ($TY:ty, $SCALAR:ident) => {
+        impl From<$TY> for ScalarValue {
+            fn from(value: $TY) -> Self {
+                ScalarValue::$SCALAR(value)
+            }
+        }
+    };
+}
+
+scalar_from!(bool, Boolean);
+scalar_from!(i8, Int8);
+scalar_from!(i16, Int16);
+scalar_from!(i32, Int32);
+scalar_from!(i64, Int64);
+scalar_from!(u8, UInt8);
+scalar_from!(u16, UInt16);
+scalar_from!(u32, UInt32);
+scalar_from!(u64, UInt64);
+scalar_from!(f32, Float32);
+scalar_from!(f64, Float64);
+
+impl<'a> From<&'a str> for ScalarValue {
+    fn from(value: &'a str) -> Self {
+        ScalarValue::Utf8(Arc::new(value.to_string()))
+    }
+}
+
+impl From<String> for ScalarValue {
+    fn from(value: String) -> Self {
+        ScalarValue::Utf8(Arc::new(value))
+    }
+}
+
+impl<'a> From<&'a [u8]> for ScalarValue {
+    fn from(value: &'a [u8]) -> Self {
+        ScalarValue::Binary(Arc::new(value.to_vec()))
+    }
+}
+
+impl From<Vec<u8>> for ScalarValue {
+    fn from(value: Vec<u8>) -> Self {
+        ScalarValue::Binary(Arc::new(value))
     }
 }
 
 /// Relation Expression
-#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 pub enum Expr {
     /// index into a value within the row or complex value
     Column(usize),
+    /// column referenced by name, which is resolved to a column index against the
+    /// input schema when the expression is added to a plan
+    UnresolvedColumn(String),
     /// literal value
     Literal(ScalarValue),
+    /// expression with a different name in the output schema
+    Alias(Rc<Expr>, String),
     /// binary expression e.g. "age > 21"
     BinaryExpr {
         left: Rc<Expr>,
         op: Operator,
         right: Rc<Expr>,
     },
+    /// unary NOT
+    Not(Rc<Expr>),
     /// unary IS NOT NULL
     IsNotNull(Rc<Expr>),
     /// unary IS NULL
     IsNull(Rc<Expr>),
     /// cast a value to a different type
     Cast { expr: Rc<Expr>, data_type: DataType },
-    /// sort expression
-    Sort { expr: Rc<Expr>, asc: bool },
+    /// sort expression, which places null values before or after all other values
+    /// regardless of the direction of the sort
+    Sort {
+        expr: Rc<Expr>,
+        asc: bool,
+        nulls_first: bool,
+    },
+    /// access a field of a struct value by name, e.g. "address.city"
+    GetField { expr: Rc<Expr>, name: String },
     /// scalar function
     ScalarFunction {
         name: String,
@@ -173,10 +442,16 @@ impl Expr {
     pub fn get_type(&self, schema: &Schema) -> DataType {
         match self {
             Expr::Column(n) => schema.field(*n).data_type().clone(),
+            Expr::UnresolvedColumn(name) => match schema.column_with_name(name) {
+                Some((_, field)) => field.data_type().clone(),
+                None => panic!("Invalid column name '{}'", name),
+            },
             Expr::Literal(l) => l.get_datatype(),
+            Expr::Alias(expr, _) => expr.get_type(schema),
             Expr::Cast { data_type, .. } => data_type.clone(),
             Expr::ScalarFunction { return_type, .. } => return_type.clone(),
             Expr::AggregateFunction { return_type, .. } => return_type.clone(),
+            Expr::Not(_) => DataType::Boolean,
             Expr::IsNull(_) => DataType::Boolean,
             Expr::IsNotNull(_) => DataType::Boolean,
             Expr::BinaryExpr {
@@ -197,6 +472,13 @@ impl Expr {
                 }
             }
             Expr::Sort { ref expr, .. } => expr.get_type(schema),
+            Expr::GetField { ref expr, ref name } => {
+                let data_type = expr.get_type(schema);
+                match struct_field(&data_type, name) {
+                    Some(field) => field.data_type().clone(),
+                    None => panic!("Invalid field '{}' of type {:?}", name, data_type),
+                }
+            }
         }
     }
 
@@ -268,27 +550,137 @@ impl Expr {
             right: Rc::new(other.clone()),
         }
     }
+
+    pub fn and(&self, other: &Expr) -> Expr {
+        Expr::BinaryExpr {
+            left: Rc::new(self.clone()),
+            op: Operator::And,
+            right: Rc::new(other.clone()),
+        }
+    }
+
+    pub fn or(&self, other: &Expr) -> Expr {
+        Expr::BinaryExpr {
+            left: Rc::new(self.clone()),
+            op: Operator::Or,
+            right: Rc::new(other.clone()),
+        }
+    }
+
+    /// Give this expression a different name in the output schema
+    pub fn alias(&self, name: &str) -> Expr {
+        Expr::Alias(Rc::new(self.clone()), name.to_string())
+    }
+
+    /// Access a field of this struct expression by name
+    pub fn get_field(&self, name: &str) -> Expr {
+        Expr::GetField {
+            expr: Rc::new(self.clone()),
+            name: name.to_string(),
+        }
+    }
+
+    /// Serialize this expression to JSON
+    pub fn to_json(&self) -> Result<String, ExecutionError> {
+        serde_json::to_string(self).map_err(|e| {
+            ExecutionError::General(format!("Failed to serialize expression: {}", e))
+        })
+    }
+
+    /// Deserialize an expression from JSON that was produced by `to_json`
+    pub fn from_json(json: &str) -> Result<Expr, ExecutionError> {
+        serde_json::from_str(json).map_err(|e| {
+            ExecutionError::General(format!("Failed to deserialize expression: {}", e))
+        })
+    }
+
+    /// Visit this expression and its sub-expressions depth first. Returns false if the
+    /// visitor stopped the traversal early.
+    pub fn accept<V: ExprVisitor>(&self, visitor: &mut V) -> bool {
+        if !visitor.pre_visit(self) {
+            return false;
+        }
+        for expr in utils::expr_sub_expressions(self) {
+            if !expr.accept(visitor) {
+                return false;
+            }
+        }
+        visitor.post_visit(self)
+    }
 }
 
+/// Visits the nodes of an expression tree, for use with `Expr::accept`. A visitor that
+/// needs to report an error can record it and return false to stop the traversal.
+pub trait ExprVisitor {
+    /// Invoked before the children of an expression are visited. Returning false
+    /// stops the traversal.
+    fn pre_visit(&mut self, expr: &Expr) -> bool;
+
+    /// Invoked after the children of an expression have been visited. Returning false
+    /// stops the traversal.
+    fn post_visit(&mut self, _expr: &Expr) -> bool {
+        true
+    }
+}
+
+macro_rules! binary_operator {
+    ($TRAIT:ident, $METHOD:ident, $OP:ident) => {
+        impl $TRAIT for Expr {
+            type Output = Expr;
+
+            fn $METHOD(self, other: Expr) -> Expr {
+                Expr::BinaryExpr {
+                    left: Rc::new(self),
+                    op: Operator::$OP,
+                    right: Rc::new(other),
+                }
+            }
+        }
+    };
+}
+
+binary_operator!(Add, add, Plus);
+binary_operator!(Sub, sub, Minus);
+binary_operator!(Mul, mul, Multiply);
+binary_operator!(Div, div, Divide);
+
 impl fmt::Debug for Expr {
     fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
         match self {
             Expr::Column(i) => write!(f, "#{}", i),
+            Expr::UnresolvedColumn(name) => write!(f, "#{}", name),
             Expr::Literal(v) => write!(f, "{:?}", v),
+            Expr::Alias(expr, alias) => write!(f, "{:?} AS {}", expr, alias),
             Expr::Cast { expr, data_type } => {
                 write!(f, "CAST({:?} AS {:?})", expr, data_type)
             }
+            Expr::Not(expr) => write!(f, "NOT {:?}", expr),
             Expr::IsNull(expr) => write!(f, "{:?} IS NULL", expr),
             Expr::IsNotNull(expr) => write!(f, "{:?} IS NOT NULL", expr),
             Expr::BinaryExpr { left, op, right } => {
                 write!(f, "{:?} {:?} {:?}", left, op, right)
             }
-            Expr::Sort { expr, asc } => {
+            Expr::GetField { expr, name } => write!(f, "{:?}.{}", expr, name),
+            Expr::Sort {
+                expr,
+                asc,
+                nulls_first,
+            } => {
                 if *asc {
-                    write!(f, "{:?} ASC", expr)
+                    write!(f, "{:?} ASC", expr)?;
                 } else {
-                    write!(f, "{:?} DESC", expr)
+                    write!(f, "{:?} DESC", expr)?;
                 }
+                // nulls are placed after all other values in an ascending sort by
+                // default, as if they were greater than all other values
+                if *nulls_first == *asc {
+                    if *nulls_first {
+                        write!(f, " NULLS FIRST")?;
+                    } else {
+                        write!(f, " NULLS LAST")?;
+                    }
+                }
+                Ok(())
             }
             Expr::ScalarFunction { name, ref args, .. } => {
                 write!(f, "{}(", name)?;
@@ -316,9 +708,19 @@ impl fmt::Debug for Expr {
     }
 }
 
+/// Create an expression that references a column of the input relation by name
+pub fn col(name: &str) -> Expr {
+    Expr::UnresolvedColumn(name.to_string())
+}
+
+/// Create a literal expression, e.g. `lit(5)` or `lit("a")`
+pub fn lit<T: Into<ScalarValue>>(value: T) -> Expr {
+    Expr::Literal(value.into())
+}
+
 /// The LogicalPlan represents different types of relations (such as Projection,
 /// Selection, etc) and can be created by the SQL query planner and the DataFrame API.
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 pub enum LogicalPlan {
     /// A Projection (essentially a SELECT with an expression list)
     Projection {
@@ -341,21 +743,111 @@ pub enum LogicalPlan {
         input: Rc<LogicalPlan>,
         schema: Arc<Schema>,
     },
-    /// A table scan against a table that has been registered on a context
+    /// A table scan against a table that has been registered on a context, or
+    /// against the table returned by a table function
     TableScan {
         schema_name: String,
         table_name: String,
+        /// Arguments the table function named `table_name` is invoked with, when
+        /// this scan reads from a table function rather than a registered table
+        function_args: Option<Vec<ScalarValue>>,
         schema: Arc<Schema>,
         projection: Option<Vec<usize>>,
+        /// Maximum number of rows that need to be read from the table, if known
+        limit: Option<usize>,
     },
     /// An empty relation with an empty schema
     EmptyRelation { schema: Arc<Schema> },
+    /// A relation containing rows of literal values, such as the result of an
+    /// aggregate query that was answered from data source statistics
+    Values {
+        values: Vec<Vec<ScalarValue>>,
+        schema: Arc<Schema>,
+    },
     // Represents the maximum number of records to return
     Limit {
         expr: Expr,
         input: Rc<LogicalPlan>,
         schema: Arc<Schema>,
     },
+    /// A join of two relations, which produces the columns of the left relation
+    /// followed by the columns of the right relation
+    Join {
+        left: Rc<LogicalPlan>,
+        right: Rc<LogicalPlan>,
+        join_type: JoinType,
+        /// Pairs of column indexes into the left and right schemas whose values must be
+        /// equal for rows to be joined
+        on: Vec<(usize, usize)>,
+        /// Predicate over the combined schema that joined rows must also satisfy
+        filter: Option<Expr>,
+        schema: Arc<Schema>,
+    },
+    /// Expands each element of a list column into its own row, repeating the values of
+    /// the other columns. Rows with a null or empty list produce no rows.
+    Unnest {
+        input: Rc<LogicalPlan>,
+        /// Index of the list column in the input schema
+        column: usize,
+        schema: Arc<Schema>,
+    },
+}
+
+/// The type of a join, which determines the rows that are produced for rows that do not
+/// have a match on the other side of the join
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum JoinType {
+    /// Only rows that have a match on both sides
+    Inner,
+    /// All rows of the left relation, with nulls for unmatched right columns
+    Left,
+    /// All rows of the right relation, with nulls for unmatched left columns
+    Right,
+    /// All rows of both relations
+    Full,
+}
+
+/// Compute the schema of a join, which contains the fields of the left schema followed
+/// by the fields of the right schema. Fields of a side that may have no matching row
+/// are nullable.
+pub fn join_schema(left: &Schema, right: &Schema, join_type: JoinType) -> Schema {
+    let (left_nullable, right_nullable) = match join_type {
+        JoinType::Inner => (false, false),
+        JoinType::Left => (false, true),
+        JoinType::Right => (true, false),
+        JoinType::Full => (true, true),
+    };
+    let nullable_fields = |schema: &Schema, nullable: bool| -> Vec<Field> {
+        schema
+            .fields()
+            .iter()
+            .map(|f| {
+                Field::new(f.name(), f.data_type().clone(), nullable || f.is_nullable())
+            })
+            .collect()
+    };
+    let mut fields = nullable_fields(left, left_nullable);
+    fields.extend(nullable_fields(right, right_nullable));
+    Schema::new(fields)
+}
+
+/// Compute the schema of an unnest, in which the list column is replaced by a column
+/// of its element type with the same name
+pub fn unnest_schema(schema: &Schema, column: usize) -> Result<Schema, ExecutionError> {
+    let mut fields = schema.fields().clone();
+    let field = schema.field(column);
+    let element_type = match field.data_type() {
+        DataType::List(ref t) => t.as_ref().clone(),
+        other => {
+            return Err(ExecutionError::General(format!(
+                "Cannot unnest column '{}' of type {:?}, which is not a list",
+                field.name(),
+                other
+            )));
+        }
+    };
+    fields[column] = Field::new(field.name(), element_type, true);
+    Ok(Schema::new(fields))
 }
 
 impl LogicalPlan {
@@ -363,18 +855,121 @@ impl LogicalPlan {
     pub fn schema(&self) -> &Arc<Schema> {
         match self {
             LogicalPlan::EmptyRelation { schema } => &schema,
+            LogicalPlan::Values { schema, .. } => &schema,
             LogicalPlan::TableScan { schema, .. } => &schema,
             LogicalPlan::Projection { schema, .. } => &schema,
             LogicalPlan::Selection { input, .. } => input.schema(),
             LogicalPlan::Aggregate { schema, .. } => &schema,
             LogicalPlan::Sort { schema, .. } => &schema,
             LogicalPlan::Limit { schema, .. } => &schema,
+            LogicalPlan::Join { schema, .. } => &schema,
+            LogicalPlan::Unnest { schema, .. } => &schema,
+        }
+    }
+
+    /// Serialize this plan to JSON, so that it can be inspected or compared by tools
+    /// that are not written in Rust
+    pub fn to_json(&self) -> Result<String, ExecutionError> {
+        serde_json::to_string(self).map_err(|e| {
+            ExecutionError::General(format!("Failed to serialize plan: {}", e))
+        })
+    }
+
+    /// Serialize this plan to indented JSON, which is easier to read and to diff
+    pub fn to_json_pretty(&self) -> Result<String, ExecutionError> {
+        serde_json::to_string_pretty(self).map_err(|e| {
+            ExecutionError::General(format!("Failed to serialize plan: {}", e))
+        })
+    }
+
+    /// Deserialize a plan from JSON that was produced by `to_json`
+    pub fn from_json(json: &str) -> Result<LogicalPlan, ExecutionError> {
+        serde_json::from_str(json).map_err(|e| {
+            ExecutionError::General(format!("Failed to deserialize plan: {}", e))
+        })
+    }
+
+    /// Get the expressions of this plan node, not including those of its inputs
+    pub fn expressions(&self) -> Vec<&Expr> {
+        match self {
+            LogicalPlan::Projection { expr, .. } | LogicalPlan::Sort { expr, .. } => {
+                expr.iter().collect()
+            }
+            LogicalPlan::Selection { expr, .. } | LogicalPlan::Limit { expr, .. } => {
+                vec![expr]
+            }
+            LogicalPlan::Aggregate {
+                group_expr,
+                aggr_expr,
+                ..
+            } => group_expr.iter().chain(aggr_expr.iter()).collect(),
+            LogicalPlan::Join { filter, .. } => filter.iter().collect(),
+            LogicalPlan::TableScan { .. }
+            | LogicalPlan::EmptyRelation { .. }
+            | LogicalPlan::Values { .. }
+            | LogicalPlan::Unnest { .. } => vec![],
         }
     }
+
+    /// Visit this plan and its inputs depth first. Returns false if the visitor stopped
+    /// the traversal early.
+    pub fn accept<V: PlanVisitor>(&self, visitor: &mut V) -> bool {
+        if !visitor.pre_visit(self) {
+            return false;
+        }
+        for input in utils::inputs(self) {
+            if !input.accept(visitor) {
+                return false;
+            }
+        }
+        visitor.post_visit(self)
+    }
+}
+
+/// Visits the nodes of a logical plan, for use with `LogicalPlan::accept`. A visitor that
+/// needs to report an error can record it and return false to stop the traversal.
+pub trait PlanVisitor {
+    /// Invoked before the inputs of a plan are visited. Returning false stops the
+    /// traversal.
+    fn pre_visit(&mut self, plan: &LogicalPlan) -> bool;
+
+    /// Invoked after the inputs of a plan have been visited. Returning false stops the
+    /// traversal.
+    fn post_visit(&mut self, _plan: &LogicalPlan) -> bool {
+        true
+    }
 }
 
 impl LogicalPlan {
-    fn fmt_with_indent(&self, f: &mut Formatter, indent: usize) -> Result<(), Error> {
+    /// Format this plan for EXPLAIN output, with one indented line per plan node and
+    /// columns referenced by name rather than by index. In verbose mode, the output
+    /// schema of each node is also shown.
+    pub fn explain(&self, verbose: bool) -> String {
+        struct Explain<'a> {
+            plan: &'a LogicalPlan,
+            verbose: bool,
+        }
+
+        impl<'a> fmt::Display for Explain<'a> {
+            fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+                self.plan.fmt_with_indent(f, 0, true, self.verbose)
+            }
+        }
+
+        let explain = Explain {
+            plan: self,
+            verbose,
+        };
+        format!("{}", explain)
+    }
+
+    fn fmt_with_indent(
+        &self,
+        f: &mut Formatter,
+        indent: usize,
+        named: bool,
+        verbose: bool,
+    ) -> Result<(), Error> {
         if indent > 0 {
             writeln!(f)?;
             for _ in 0..indent {
@@ -382,33 +977,41 @@ impl LogicalPlan {
             }
         }
         match *self {
-            LogicalPlan::EmptyRelation { .. } => write!(f, "EmptyRelation"),
+            LogicalPlan::EmptyRelation { .. } => write!(f, "EmptyRelation")?,
+            LogicalPlan::Values { ref values, .. } => write!(f, "Values: {:?}", values)?,
             LogicalPlan::TableScan {
                 ref table_name,
+                ref function_args,
                 ref projection,
+                ref limit,
                 ..
-            } => write!(f, "TableScan: {} projection={:?}", table_name, projection),
+            } => {
+                write!(f, "TableScan: {}", table_name)?;
+                if let Some(args) = function_args {
+                    let args: Vec<String> =
+                        args.iter().map(|a| format!("{:?}", a)).collect();
+                    write!(f, "({})", args.join(", "))?;
+                }
+                write!(f, " projection={:?}", projection)?;
+                if let Some(n) = limit {
+                    write!(f, " limit={}", n)?;
+                }
+            }
             LogicalPlan::Projection {
                 ref expr,
                 ref input,
                 ..
             } => {
                 write!(f, "Projection: ")?;
-                for i in 0..expr.len() {
-                    if i > 0 {
-                        write!(f, ", ")?;
-                    }
-                    write!(f, "{:?}", expr[i])?;
-                }
-                input.fmt_with_indent(f, indent + 1)
+                fmt_exprs(f, expr, input.schema(), named)?;
             }
             LogicalPlan::Selection {
                 ref expr,
                 ref input,
                 ..
             } => {
-                write!(f, "Selection: {:?}", expr)?;
-                input.fmt_with_indent(f, indent + 1)
+                write!(f, "Selection: ")?;
+                fmt_expr(f, expr, input.schema(), named)?;
             }
             LogicalPlan::Aggregate {
                 ref input,
@@ -416,12 +1019,11 @@ impl LogicalPlan {
                 ref aggr_expr,
                 ..
             } => {
-                write!(
-                    f,
-                    "Aggregate: groupBy=[{:?}], aggr=[{:?}]",
-                    group_expr, aggr_expr
-                )?;
-                input.fmt_with_indent(f, indent + 1)
+                write!(f, "Aggregate: groupBy=[[")?;
+                fmt_exprs(f, group_expr, input.schema(), named)?;
+                write!(f, "]], aggr=[[")?;
+                fmt_exprs(f, aggr_expr, input.schema(), named)?;
+                write!(f, "]]")?;
             }
             LogicalPlan::Sort {
                 ref input,
@@ -429,33 +1031,385 @@ impl LogicalPlan {
                 ..
             } => {
                 write!(f, "Sort: ")?;
-                for i in 0..expr.len() {
-                    if i > 0 {
-                        write!(f, ", ")?;
-                    }
-                    write!(f, "{:?}", expr[i])?;
-                }
-                input.fmt_with_indent(f, indent + 1)
+                fmt_exprs(f, expr, input.schema(), named)?;
             }
             LogicalPlan::Limit {
                 ref input,
                 ref expr,
                 ..
             } => {
-                write!(f, "Limit: {:?}", expr)?;
-                input.fmt_with_indent(f, indent + 1)
+                write!(f, "Limit: ")?;
+                fmt_expr(f, expr, input.schema(), named)?;
+            }
+            LogicalPlan::Join {
+                ref left,
+                ref right,
+                ref join_type,
+                ref on,
+                ref filter,
+                ref schema,
+            } => {
+                write!(f, "Join: type={:?}, on=[", join_type)?;
+                for (i, (l, r)) in on.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    fmt_expr(f, &Expr::Column(*l), left.schema(), named)?;
+                    write!(f, " = ")?;
+                    fmt_expr(f, &Expr::Column(*r), right.schema(), named)?;
+                }
+                write!(f, "]")?;
+                if let Some(filter) = filter {
+                    write!(f, ", filter=")?;
+                    fmt_expr(f, filter, schema, named)?;
+                }
             }
+            LogicalPlan::Unnest {
+                ref input, column, ..
+            } => {
+                write!(f, "Unnest: ")?;
+                fmt_expr(f, &Expr::Column(column), input.schema(), named)?;
+            }
+        }
+        if verbose {
+            write!(f, " schema=[{}]", self.schema())?;
+        }
+        for input in utils::inputs(self) {
+            input.fmt_with_indent(f, indent + 1, named, verbose)?;
         }
+        Ok(())
     }
 }
 
+/// Format an expression, optionally replacing column indexes with the names of the
+/// columns in the given schema
+fn fmt_expr(
+    f: &mut Formatter,
+    expr: &Expr,
+    schema: &Schema,
+    named: bool,
+) -> Result<(), Error> {
+    struct ColumnNamer<'a> {
+        schema: &'a Schema,
+    }
+
+    impl<'a> ExprRewriter for ColumnNamer<'a> {
+        fn mutate(&mut self, expr: Expr) -> arrow::error::Result<Expr> {
+            match expr {
+                Expr::Column(i) if i < self.schema.fields().len() => {
+                    Ok(Expr::UnresolvedColumn(self.schema.field(i).name().clone()))
+                }
+                _ => Ok(expr),
+            }
+        }
+    }
+
+    if named {
+        match utils::rewrite_expr(expr, &mut ColumnNamer { schema }) {
+            Ok(named_expr) => write!(f, "{:?}", named_expr),
+            Err(_) => write!(f, "{:?}", expr),
+        }
+    } else {
+        write!(f, "{:?}", expr)
+    }
+}
+
+/// Format a comma separated list of expressions
+fn fmt_exprs(
+    f: &mut Formatter,
+    expr: &Vec<Expr>,
+    schema: &Schema,
+    named: bool,
+) -> Result<(), Error> {
+    for i in 0..expr.len() {
+        if i > 0 {
+            write!(f, ", ")?;
+        }
+        fmt_expr(f, &expr[i], schema, named)?;
+    }
+    Ok(())
+}
+
 impl fmt::Debug for LogicalPlan {
     fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
-        self.fmt_with_indent(f, 0)
+        self.fmt_with_indent(f, 0, false, false)
     }
 }
 
+/// Builds a logical plan one relation at a time, validating each expression against
+/// the schema of its input. Columns that are referenced by name are resolved to column
+/// indexes as they are added.
+pub struct LogicalPlanBuilder {
+    plan: LogicalPlan,
+}
+
+impl LogicalPlanBuilder {
+    /// Create a builder that starts from an existing plan
+    pub fn from(plan: &LogicalPlan) -> Self {
+        Self { plan: plan.clone() }
+    }
+
+    /// Scan a table, reading only the projected columns (or all columns when the
+    /// projection is `None`)
+    pub fn scan(
+        schema_name: &str,
+        table_name: &str,
+        table_schema: &Schema,
+        projection: Option<Vec<usize>>,
+    ) -> Result<Self, ExecutionError> {
+        let schema = match projection {
+            Some(ref p) => {
+                let fields = p
+                    .iter()
+                    .map(|i| match table_schema.fields().get(*i) {
+                        Some(field) => Ok(field.clone()),
+                        None => Err(ExecutionError::InvalidColumn(format!(
+                            "Invalid column index {} in projection of table {}",
+                            i, table_name
+                        ))),
+                    })
+                    .collect::<Result<Vec<Field>, ExecutionError>>()?;
+                Schema::new(fields)
+            }
+            None => table_schema.clone(),
+        };
+        Ok(Self::from(&LogicalPlan::TableScan {
+            schema_name: schema_name.to_string(),
+            table_name: table_name.to_string(),
+            function_args: None,
+            schema: Arc::new(schema),
+            projection,
+            limit: None,
+        }))
+    }
+
+    /// Keep only the rows for which a boolean predicate is true
+    pub fn filter(&self, expr: Expr) -> Result<Self, ExecutionError> {
+        let expr = self.validate(&expr)?;
+        match expr.get_type(self.plan.schema()) {
+            DataType::Boolean => Ok(Self::from(&LogicalPlan::Selection {
+                expr,
+                input: Rc::new(self.plan.clone()),
+            })),
+            other => Err(ExecutionError::General(format!(
+                "Filter predicate {:?} has type {:?} but must be Boolean",
+                expr, other
+            ))),
+        }
+    }
+
+    /// Compute one output column per expression
+    pub fn project(&self, expr: Vec<Expr>) -> Result<Self, ExecutionError> {
+        let expr = self.validate_list(&expr)?;
+        if let Some(e) = expr.iter().find(|e| utils::contains_aggregate(e)) {
+            return Err(ExecutionError::General(format!(
+                "Projection cannot compute the aggregate expression {:?}",
+                e
+            )));
+        }
+        let schema = Schema::new(exprlist_to_fields(&expr, self.plan.schema()));
+        Ok(Self::from(&LogicalPlan::Projection {
+            expr,
+            input: Rc::new(self.plan.clone()),
+            schema: Arc::new(schema),
+        }))
+    }
+
+    /// Compute the aggregate expressions for each group of rows that have the same
+    /// values for the grouping expressions. The output has one column per grouping
+    /// expression followed by one column per aggregate expression.
+    pub fn aggregate(
+        &self,
+        group_expr: Vec<Expr>,
+        aggr_expr: Vec<Expr>,
+    ) -> Result<Self, ExecutionError> {
+        let group_expr = self.validate_list(&group_expr)?;
+        let aggr_expr = self.validate_list(&aggr_expr)?;
+        if let Some(e) = group_expr.iter().find(|e| utils::contains_aggregate(e)) {
+            return Err(ExecutionError::General(format!(
+                "Cannot group by the aggregate expression {:?}",
+                e
+            )));
+        }
+        for e in &aggr_expr {
+            match e {
+                Expr::AggregateFunction { args, .. }
+                    if !args.iter().any(utils::contains_aggregate) => {}
+                _ => {
+                    return Err(ExecutionError::General(format!(
+                        "Expression {:?} is not a valid aggregate expression",
+                        e
+                    )));
+                }
+            }
+        }
+        let mut all_expr = group_expr.clone();
+        all_expr.extend(aggr_expr.iter().cloned());
+        let schema = Schema::new(exprlist_to_fields(&all_expr, self.plan.schema()));
+        Ok(Self::from(&LogicalPlan::Aggregate {
+            input: Rc::new(self.plan.clone()),
+            group_expr,
+            aggr_expr,
+            schema: Arc::new(schema),
+        }))
+    }
+
+    /// Sort the rows by the given expressions. Expressions that are not sort
+    /// expressions are sorted in ascending order, with null values last.
+    pub fn sort(&self, expr: Vec<Expr>) -> Result<Self, ExecutionError> {
+        let expr = self
+            .validate_list(&expr)?
+            .into_iter()
+            .map(|e| match e {
+                Expr::Sort { .. } => e,
+                _ => Expr::Sort {
+                    expr: Rc::new(e),
+                    asc: true,
+                    nulls_first: false,
+                },
+            })
+            .collect();
+        Ok(Self::from(&LogicalPlan::Sort {
+            expr,
+            input: Rc::new(self.plan.clone()),
+            schema: self.plan.schema().clone(),
+        }))
+    }
+
+    /// Produce at most `n` rows
+    pub fn limit(&self, n: usize) -> Result<Self, ExecutionError> {
+        Ok(Self::from(&LogicalPlan::Limit {
+            expr: Expr::Literal(ScalarValue::UInt64(n as u64)),
+            input: Rc::new(self.plan.clone()),
+            schema: self.plan.schema().clone(),
+        }))
+    }
+
+    /// Join with another plan. Each pair of `on` expressions is a column of this plan
+    /// and a column of the right plan, which must have the same type. The optional
+    /// filter is evaluated against the combined schema.
+    pub fn join(
+        &self,
+        right: &LogicalPlan,
+        join_type: JoinType,
+        on: Vec<(Expr, Expr)>,
+        filter: Option<Expr>,
+    ) -> Result<Self, ExecutionError> {
+        let right = LogicalPlanBuilder::from(right);
+        let on = on
+            .iter()
+            .map(|(l, r)| match (self.validate(l)?, right.validate(r)?) {
+                (Expr::Column(i), Expr::Column(j)) => {
+                    let left_type = self.plan.schema().field(i).data_type();
+                    let right_type = right.plan.schema().field(j).data_type();
+                    if left_type == right_type {
+                        Ok((i, j))
+                    } else {
+                        Err(ExecutionError::General(format!(
+                            "Cannot join on columns #{} and #{} with different \
+                             types {:?} and {:?}",
+                            i, j, left_type, right_type
+                        )))
+                    }
+                }
+                (l, r) => Err(ExecutionError::General(format!(
+                    "Join keys must be columns but found {:?} and {:?}",
+                    l, r
+                ))),
+            })
+            .collect::<Result<Vec<(usize, usize)>, ExecutionError>>()?;
+        let schema = Arc::new(join_schema(
+            self.plan.schema(),
+            right.plan.schema(),
+            join_type,
+        ));
+
+        // the filter is validated against a relation with the combined schema
+        let filter = match filter {
+            Some(expr) => {
+                let combined = Self::from(&LogicalPlan::EmptyRelation {
+                    schema: schema.clone(),
+                });
+                let expr = combined.validate(&expr)?;
+                match expr.get_type(&schema) {
+                    DataType::Boolean => Some(expr),
+                    other => {
+                        return Err(ExecutionError::General(format!(
+                            "Join filter {:?} has type {:?} but must be Boolean",
+                            expr, other
+                        )));
+                    }
+                }
+            }
+            None => None,
+        };
+
+        Ok(Self::from(&LogicalPlan::Join {
+            left: Rc::new(self.plan.clone()),
+            right: Rc::new(right.plan),
+            join_type,
+            on,
+            filter,
+            schema,
+        }))
+    }
+
+    /// Get the plan that has been built
+    pub fn build(&self) -> Result<LogicalPlan, ExecutionError> {
+        Ok(self.plan.clone())
+    }
+
+    /// Resolve the columns that an expression references by name, check that every
+    /// column and struct field exists in the schema of the plan, and coerce the
+    /// operands of binary expressions to a common type
+    fn validate(&self, expr: &Expr) -> Result<Expr, ExecutionError> {
+        let schema = self.plan.schema();
+        let expr = utils::resolve_columns(expr, schema)?;
+        let mut columns = HashSet::new();
+        utils::expr_to_column_indices(&expr, &mut columns);
+        if let Some(i) = columns.iter().find(|i| **i >= schema.fields().len()) {
+            return Err(ExecutionError::InvalidColumn(format!(
+                "Invalid column index {} in expression {:?}",
+                i, expr
+            )));
+        }
+        validate_fields(&expr, schema)?;
+        Ok(utils::coerce_binary_exprs(&expr, schema)?)
+    }
+
+    fn validate_list(&self, expr: &Vec<Expr>) -> Result<Vec<Expr>, ExecutionError> {
+        expr.iter().map(|e| self.validate(e)).collect()
+    }
+}
+
+/// Check that every field accessed by an expression exists in the struct type of the
+/// expression that it is accessed from, since `Expr::get_type` cannot fail
+fn validate_fields(expr: &Expr, schema: &Schema) -> Result<(), ExecutionError> {
+    for e in utils::expr_sub_expressions(expr) {
+        validate_fields(e, schema)?;
+    }
+    if let Expr::GetField { expr, name } = expr {
+        let data_type = expr.get_type(schema);
+        if struct_field(&data_type, name).is_none() {
+            return Err(ExecutionError::InvalidColumn(format!(
+                "Invalid field '{}' of type {:?} in expression {:?}",
+                name, data_type, expr
+            )));
+        }
+    }
+    Ok(())
+}
+
 //TODO move to Arrow DataType impl?
+/// Get the field with the given name of a struct type, or `None` if the type is not a
+/// struct or has no such field
+pub fn struct_field<'a>(data_type: &'a DataType, name: &str) -> Option<&'a Field> {
+    match data_type {
+        DataType::Struct(fields) => fields.iter().find(|f| f.name() == name),
+        _ => None,
+    }
+}
+
 pub fn get_supertype(l: &DataType, r: &DataType) -> Option<DataType> {
     match _get_supertype(l, r) {
         Some(dt) => Some(dt),
@@ -559,15 +1513,31 @@ fn _get_supertype(l: &DataType, r: &DataType) -> Option<DataType> {
         (Float64, Float64) => Some(Float64),
 
         (Utf8, Utf8) => Some(Utf8),
+        (Binary, Binary) => Some(Binary),
 
         (Boolean, Boolean) => Some(Boolean),
 
+        (List(l), List(r)) => {
+            get_supertype(l, r).map(|data_type| List(Box::new(data_type)))
+        }
+        // nested types other than lists are only compatible with the same type
+        (FixedSizeList(_, _), _) | (Struct(_), _) | (Union(_, _), _) if l == r => {
+            Some(l.clone())
+        }
+
+        // dictionary values are compared by the values of their keys
+        (Dictionary(_, value_type), _) => get_supertype(value_type, r),
+
         _ => None,
     }
 }
 
 pub fn can_coerce_from(left: &DataType, other: &DataType) -> bool {
     use self::DataType::*;
+    if let Dictionary(_, value_type) = other {
+        // casting a dictionary casts the values of its keys
+        return **value_type == *left || can_coerce_from(left, value_type);
+    }
     match left {
         Int8 => match other {
             Int8 => true,
@@ -621,6 +1591,511 @@ pub fn can_coerce_from(left: &DataType, other: &DataType) -> bool {
 mod tests {
     use super::*;
     use serde_json;
+    use std::collections::hash_map::DefaultHasher;
+
+    #[test]
+    fn resolve_function_overloads() {
+        let round = FunctionMeta::with_signatures(
+            "round".to_string(),
+            vec![
+                Signature::Exact(vec![DataType::Float64]),
+                Signature::Exact(vec![DataType::Float64, DataType::Int64]),
+            ],
+            DataType::Float64,
+            FunctionType::Scalar,
+        );
+        assert_eq!(
+            vec![DataType::Float64],
+            round.resolve(&[DataType::Int32]).unwrap()
+        );
+        assert_eq!(
+            vec![DataType::Float64, DataType::Int64],
+            round.resolve(&[DataType::Float32, DataType::Int8]).unwrap()
+        );
+        assert!(round.resolve(&[]).is_err());
+        assert!(round.resolve(&[DataType::Utf8]).is_err());
+
+        // the overload that needs the fewest casts is chosen
+        let max = FunctionMeta::with_signatures(
+            "greatest".to_string(),
+            vec![Signature::Variadic(vec![
+                DataType::Int64,
+                DataType::Float64,
+            ])],
+            DataType::Float64,
+            FunctionType::Scalar,
+        );
+        assert_eq!(
+            vec![DataType::Float64; 3],
+            max.resolve(&[DataType::Float64, DataType::Int32, DataType::Float64])
+                .unwrap()
+        );
+        assert_eq!(
+            vec![DataType::Int64; 2],
+            max.resolve(&[DataType::Int64, DataType::Int32]).unwrap()
+        );
+
+        let any = FunctionMeta::with_signatures(
+            "coalesce".to_string(),
+            vec![Signature::Any(2)],
+            DataType::Utf8,
+            FunctionType::Scalar,
+        );
+        let arg_types = vec![DataType::Utf8, DataType::Boolean];
+        assert_eq!(arg_types, any.resolve(&arg_types).unwrap());
+        assert!(any.resolve(&[DataType::Utf8]).is_err());
+    }
+
+    #[test]
+    fn expr_helpers() {
+        let expr = (col("a") + lit(1)) * lit(2.5) / col("b") - lit(1u8);
+        assert_eq!(
+            "#a Plus Int32(1) Multiply Float64(2.5) Divide #b Minus UInt8(1)",
+            format!("{:?}", expr)
+        );
+
+        let expr = col("a")
+            .eq(&lit("x"))
+            .and(&col("b").gt(&lit(1i64)).or(&col("c")))
+            .alias("p");
+        assert_eq!(
+            "#a Eq Utf8(\"x\") And #b Gt Int64(1) Or #c AS p",
+            format!("{:?}", expr)
+        );
+        assert_eq!(Expr::Literal(ScalarValue::Boolean(true)), lit(true));
+    }
+
+    #[test]
+    fn scalar_value_types() {
+        assert_eq!(
+            DataType::Date(DateUnit::Day),
+            ScalarValue::Date32(17897).get_datatype()
+        );
+        assert_eq!(
+            DataType::Date(DateUnit::Millisecond),
+            ScalarValue::Date64(1546300800000).get_datatype()
+        );
+        assert_eq!(
+            DataType::Time32(TimeUnit::Second),
+            ScalarValue::Time32(3600, TimeUnit::Second).get_datatype()
+        );
+        assert_eq!(
+            DataType::Time64(TimeUnit::Nanosecond),
+            ScalarValue::Time64(3600, TimeUnit::Nanosecond).get_datatype()
+        );
+        assert_eq!(
+            DataType::Timestamp(TimeUnit::Millisecond, None),
+            ScalarValue::Timestamp(1546300800000, TimeUnit::Millisecond, None)
+                .get_datatype()
+        );
+        assert_eq!(
+            DataType::Timestamp(TimeUnit::Second, Some("+01:00".to_string())),
+            ScalarValue::Timestamp(
+                1546300800,
+                TimeUnit::Second,
+                Some("+01:00".to_string())
+            )
+            .get_datatype()
+        );
+        assert_eq!(
+            DataType::Binary,
+            ScalarValue::from(vec![1u8, 2, 3]).get_datatype()
+        );
+        assert_eq!(
+            DataType::Boolean,
+            ScalarValue::TypedNull(DataType::Boolean).get_datatype()
+        );
+        assert_eq!(
+            DataType::List(Box::new(DataType::Int32)),
+            ScalarValue::List(
+                vec![ScalarValue::Int32(1), ScalarValue::Int32(2)],
+                DataType::Int32
+            )
+            .get_datatype()
+        );
+    }
+
+    #[test]
+    fn cast_scalar_values() {
+        assert_eq!(
+            Some(ScalarValue::Int64(21)),
+            ScalarValue::Int32(21).cast_to(&DataType::Int64)
+        );
+        assert_eq!(
+            Some(ScalarValue::Float64(3.0)),
+            ScalarValue::UInt64(3).cast_to(&DataType::Float64)
+        );
+        assert_eq!(
+            Some(ScalarValue::Float64(0.5)),
+            ScalarValue::Float32(0.5).cast_to(&DataType::Float64)
+        );
+        assert_eq!(None, ScalarValue::Float64(0.5).cast_to(&DataType::Int64));
+        assert_eq!(None, ScalarValue::from("1").cast_to(&DataType::Int64));
+    }
+
+    #[test]
+    fn build_plan() {
+        let schema = Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("name", DataType::Utf8, false),
+            Field::new("age", DataType::Int64, false),
+        ]);
+        let max_id = Expr::AggregateFunction {
+            name: "MAX".to_string(),
+            args: vec![col("id")],
+            return_type: DataType::Int32,
+        };
+
+        let scan =
+            LogicalPlanBuilder::scan("default", "person", &schema, Some(vec![0, 2]))
+                .unwrap();
+        let plan = scan
+            .filter(col("age").gt(&Expr::Literal(ScalarValue::Int64(21))))
+            .unwrap()
+            .aggregate(vec![col("age")], vec![max_id.clone()])
+            .unwrap()
+            .sort(vec![col("MAX")])
+            .unwrap()
+            .limit(10)
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(
+            "Limit: UInt64(10)\
+             \n  Sort: #1 ASC\
+             \n    Aggregate: groupBy=[[#1]], aggr=[[MAX(#0)]]\
+             \n      Selection: #1 Gt Int64(21)\
+             \n        TableScan: person projection=Some([0, 2])",
+            format!("{:?}", plan)
+        );
+        assert_eq!("MAX", plan.schema().field(1).name());
+
+        // each step is validated against the schema of its input
+        assert!(
+            LogicalPlanBuilder::scan("default", "person", &schema, Some(vec![3]))
+                .is_err()
+        );
+        assert!(scan.filter(col("name")).is_err());
+        assert!(scan.filter(col("id")).is_err());
+        assert!(scan.project(vec![Expr::Column(2)]).is_err());
+        assert!(scan.project(vec![max_id.clone()]).is_err());
+        assert!(scan.aggregate(vec![], vec![col("id")]).is_err());
+        assert!(scan.aggregate(vec![max_id], vec![]).is_err());
+
+        // the operands of binary expressions are coerced to a common type
+        let plan = scan
+            .filter(col("age").gt(&lit(21)))
+            .unwrap()
+            .project(vec![col("id") + col("age")])
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(
+            "Projection: CAST(#0 AS Int64) Plus #1\
+             \n  Selection: #1 Gt Int64(21)\
+             \n    TableScan: person projection=Some([0, 2])",
+            format!("{:?}", plan)
+        );
+        assert!(scan.filter(col("age").gt(&lit("21"))).is_err());
+    }
+
+    #[test]
+    fn build_plan_with_invalid_field() {
+        let schema = Schema::new(vec![Field::new(
+            "s",
+            DataType::Struct(vec![Field::new("a", DataType::Int32, false)]),
+            false,
+        )]);
+        let scan = LogicalPlanBuilder::scan("default", "t", &schema, None).unwrap();
+
+        assert!(scan.filter(col("s").get_field("a").eq(&lit(1i32))).is_ok());
+        assert!(scan
+            .filter(col("s").get_field("nope").eq(&lit(1i32)))
+            .is_err());
+        assert!(scan
+            .project(vec![col("s").get_field("a").get_field("b")])
+            .is_err());
+        assert!(scan.project(vec![col("nope").get_field("a")]).is_err());
+    }
+
+    #[test]
+    fn dictionary_coercion() {
+        let dictionary =
+            DataType::Dictionary(Box::new(DataType::Int8), Box::new(DataType::Int32));
+        assert_eq!(
+            Some(DataType::Int64),
+            get_supertype(&dictionary, &DataType::Int64)
+        );
+        assert_eq!(
+            Some(DataType::Int32),
+            get_supertype(&DataType::Int16, &dictionary)
+        );
+        assert_eq!(None, get_supertype(&dictionary, &DataType::Utf8));
+        assert!(can_coerce_from(&DataType::Int32, &dictionary));
+        assert!(can_coerce_from(&DataType::Float64, &dictionary));
+        assert!(!can_coerce_from(&DataType::Int16, &dictionary));
+    }
+
+    #[test]
+    fn build_join() {
+        let person = Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("name", DataType::Utf8, false),
+        ]);
+        let orders = Schema::new(vec![
+            Field::new("person_id", DataType::Int32, false),
+            Field::new("amount", DataType::Float64, true),
+        ]);
+        let left = LogicalPlanBuilder::scan("default", "person", &person, None).unwrap();
+        let right = LogicalPlanBuilder::scan("default", "orders", &orders, None)
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let plan = left
+            .join(
+                &right,
+                JoinType::Left,
+                vec![(col("id"), col("person_id"))],
+                Some(col("amount").gt(&lit(100))),
+            )
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(
+            "Join: type=Left, on=[#0 = #0], filter=#3 Gt Float64(100.0)\
+             \n  TableScan: person projection=None\
+             \n  TableScan: orders projection=None",
+            format!("{:?}", plan)
+        );
+
+        // the columns of the right side of a left join are nullable
+        let schema = plan.schema();
+        assert_eq!(4, schema.fields().len());
+        assert!(!schema.field(0).is_nullable());
+        assert!(schema.field(2).is_nullable());
+        assert_eq!("person_id", schema.field(2).name());
+
+        assert!(left
+            .join(
+                &right,
+                JoinType::Inner,
+                vec![(col("name"), col("person_id"))],
+                None
+            )
+            .is_err());
+        assert!(left
+            .join(&right, JoinType::Inner, vec![(col("id"), lit(1))], None)
+            .is_err());
+        assert!(left
+            .join(&right, JoinType::Inner, vec![], Some(col("amount")))
+            .is_err());
+    }
+
+    #[test]
+    fn explain_plan() {
+        let schema = Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("state", DataType::Utf8, false),
+            Field::new("salary", DataType::Float64, true),
+        ]);
+        let plan = LogicalPlanBuilder::scan("default", "employee", &schema, None)
+            .unwrap()
+            .filter(col("state").eq(&lit("CO")))
+            .unwrap()
+            .project(vec![col("id"), col("salary").alias("pay")])
+            .unwrap()
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            "Projection: #0, #2 AS pay\
+             \n  Selection: #1 Eq Utf8(\"CO\")\
+             \n    TableScan: employee projection=None",
+            format!("{:?}", plan)
+        );
+        assert_eq!(
+            "Projection: #id, #salary AS pay\
+             \n  Selection: #state Eq Utf8(\"CO\")\
+             \n    TableScan: employee projection=None",
+            plan.explain(false)
+        );
+        assert_eq!(
+            "Projection: #id, #salary AS pay schema=[id: Int32, pay: Float64]\
+             \n  Selection: #state Eq Utf8(\"CO\") \
+             schema=[id: Int32, state: Utf8, salary: Float64]\
+             \n    TableScan: employee projection=None \
+             schema=[id: Int32, state: Utf8, salary: Float64]",
+            plan.explain(true)
+        );
+    }
+
+    #[test]
+    fn visit_plan() {
+        struct NodeCounter {
+            pre: Vec<String>,
+            post: Vec<String>,
+            stop_at: Option<&'static str>,
+        }
+
+        impl PlanVisitor for NodeCounter {
+            fn pre_visit(&mut self, plan: &LogicalPlan) -> bool {
+                let name = match plan {
+                    LogicalPlan::Limit { .. } => "Limit",
+                    LogicalPlan::Selection { .. } => "Selection",
+                    LogicalPlan::TableScan { .. } => "TableScan",
+                    _ => "Other",
+                };
+                self.pre.push(name.to_string());
+                self.stop_at != Some(name)
+            }
+
+            fn post_visit(&mut self, plan: &LogicalPlan) -> bool {
+                self.post.push(format!("{}", plan.expressions().len()));
+                true
+            }
+        }
+
+        let schema = Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("state", DataType::Utf8, false),
+        ]);
+        let plan = LogicalPlanBuilder::scan("default", "employee", &schema, None)
+            .unwrap()
+            .filter(col("state").eq(&lit("CO")))
+            .unwrap()
+            .limit(10)
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let mut visitor = NodeCounter {
+            pre: vec![],
+            post: vec![],
+            stop_at: None,
+        };
+        assert!(plan.accept(&mut visitor));
+        assert_eq!(vec!["Limit", "Selection", "TableScan"], visitor.pre);
+        assert_eq!(vec!["0", "1", "1"], visitor.post);
+
+        let mut visitor = NodeCounter {
+            pre: vec![],
+            post: vec![],
+            stop_at: Some("Selection"),
+        };
+        assert!(!plan.accept(&mut visitor));
+        assert_eq!(vec!["Limit", "Selection"], visitor.pre);
+        assert!(visitor.post.is_empty());
+    }
+
+    #[test]
+    fn visit_expr() {
+        struct FindLiteral {
+            visited: usize,
+        }
+
+        impl ExprVisitor for FindLiteral {
+            fn pre_visit(&mut self, expr: &Expr) -> bool {
+                self.visited += 1;
+                match expr {
+                    Expr::Literal(_) => false,
+                    _ => true,
+                }
+            }
+        }
+
+        let expr = col("a").gt(&col("b")).and(&col("c").eq(&lit(1i64)));
+        let mut visitor = FindLiteral { visited: 0 };
+        assert!(!expr.accept(&mut visitor));
+        assert_eq!(7, visitor.visited);
+
+        let expr = col("a").gt(&col("b"));
+        let mut visitor = FindLiteral { visited: 0 };
+        assert!(expr.accept(&mut visitor));
+        assert_eq!(3, visitor.visited);
+    }
+
+    #[test]
+    fn plan_json_round_trip() {
+        let schema = Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("name", DataType::Utf8, false),
+        ]);
+        let plan = LogicalPlanBuilder::scan("default", "person", &schema, None)
+            .unwrap()
+            .filter(col("name").eq(&lit("a")).and(&col("id").gt(&lit(1))))
+            .unwrap()
+            .project(vec![(col("id") + lit(1)).alias("next_id")])
+            .unwrap()
+            .sort(vec![col("next_id")])
+            .unwrap()
+            .limit(5)
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let json = plan.to_json().unwrap();
+        let deserialized = LogicalPlan::from_json(&json).unwrap();
+        assert_eq!(format!("{:?}", plan), format!("{:?}", deserialized));
+        assert_eq!(plan, deserialized);
+        assert_eq!(json, deserialized.to_json().unwrap());
+        assert_eq!(
+            deserialized.to_json_pretty().unwrap(),
+            plan.to_json_pretty().unwrap()
+        );
+
+        assert!(LogicalPlan::from_json("{\"Unknown\":{}}").is_err());
+    }
+
+    #[test]
+    fn plan_equality_and_hashing() {
+        fn hash<T: Hash>(value: &T) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            value.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        let schema = Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("salary", DataType::Float64, false),
+        ]);
+        let plan = |salary: f64| {
+            LogicalPlanBuilder::scan("default", "employee", &schema, None)
+                .unwrap()
+                .filter(col("salary").gt(&lit(salary)))
+                .unwrap()
+                .project(vec![col("id")])
+                .unwrap()
+                .build()
+                .unwrap()
+        };
+
+        assert_eq!(plan(1000.0), plan(1000.0));
+        assert_eq!(hash(&plan(1000.0)), hash(&plan(1000.0)));
+        assert_ne!(plan(1000.0), plan(2000.0));
+
+        assert_eq!(lit(0.0), lit(-0.0));
+        assert_eq!(hash(&lit(0.0)), hash(&lit(-0.0)));
+        assert_ne!(lit(1i32), lit(1i64));
+
+        let mut plans = HashSet::new();
+        plans.insert(plan(1000.0));
+        plans.insert(plan(1000.0));
+        plans.insert(plan(2000.0));
+        assert_eq!(2, plans.len());
+    }
+
+    #[test]
+    fn expr_json_round_trip() {
+        let expr = Expr::Cast {
+            expr: Rc::new(col("a") * lit(2.5)),
+            data_type: DataType::Int64,
+        }
+        .alias("b");
+        let json = expr.to_json().unwrap();
+        assert_eq!(expr, Expr::from_json(&json).unwrap());
+        assert!(Expr::from_json("").is_err());
+    }
 
     #[test]
     fn serialize_plan() {
@@ -640,8 +2115,10 @@ mod tests {
         let plan = LogicalPlan::TableScan {
             schema_name: "".to_string(),
             table_name: "people".to_string(),
+            function_args: None,
             schema: Arc::new(schema),
             projection: Some(vec![0, 1, 4]),
+            limit: None,
         };
 
         let serialized = serde_json::to_string(&plan).unwrap();
@@ -658,7 +2135,8 @@ mod tests {
              {\"name\":\"street\",\"data_type\":\"Utf8\",\"nullable\":false},\
              {\"name\":\"zip\",\"data_type\":\"UInt16\",\"nullable\":false}]},\"nullable\":false}\
              ]},\
-             \"projection\":[0,1,4]}}",
+             \"projection\":[0,1,4],\
+             \"limit\":null}}",
             serialized
         );
     }
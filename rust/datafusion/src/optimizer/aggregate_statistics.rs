@@ -0,0 +1,289 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Aggregate Statistics optimizer rule answers simple aggregate queries from data
+//! source statistics instead of scanning the data
+
+use crate::execution::datasource::Statistics;
+use crate::logicalplan::{Expr, LogicalPlan, ScalarValue};
+use crate::optimizer::optimizer::{OptimizerRule, PlanRewriter};
+use crate::optimizer::utils;
+use crate::sqlplanner::SchemaProvider;
+use arrow::error::Result;
+use std::rc::Rc;
+use std::sync::Arc;
+
+/// Aggregate Statistics optimizer rule replaces an ungrouped aggregate consisting only
+/// of COUNT expressions directly over a table scan (such as `SELECT COUNT(*) FROM t`)
+/// with a single row of values, when the data source knows its exact row count
+pub struct AggregateStatistics {
+    schema_provider: Arc<SchemaProvider>,
+}
+
+impl OptimizerRule for AggregateStatistics {
+    fn optimize(&mut self, plan: &LogicalPlan) -> Result<Rc<LogicalPlan>> {
+        utils::rewrite_plan(plan, self)
+    }
+}
+
+impl PlanRewriter for AggregateStatistics {
+    fn mutate(&mut self, plan: Rc<LogicalPlan>) -> Result<Rc<LogicalPlan>> {
+        match plan.as_ref() {
+            LogicalPlan::Aggregate {
+                input,
+                group_expr,
+                aggr_expr,
+                schema,
+            } if group_expr.is_empty() => {
+                match self.count_from_statistics(aggr_expr, input) {
+                    Some(values) => Ok(Rc::new(LogicalPlan::Values {
+                        values: vec![values],
+                        schema: schema.clone(),
+                    })),
+                    None => Ok(plan.clone()),
+                }
+            }
+            _ => Ok(plan),
+        }
+    }
+}
+
+impl AggregateStatistics {
+    pub fn new(schema_provider: Arc<SchemaProvider>) -> Self {
+        Self { schema_provider }
+    }
+
+    /// Compute the values of a list of COUNT expressions from the statistics of the
+    /// table being scanned, returning `None` if any of them cannot be answered exactly
+    fn count_from_statistics(
+        &self,
+        aggr_expr: &Vec<Expr>,
+        input: &LogicalPlan,
+    ) -> Option<Vec<ScalarValue>> {
+        let (schema_name, table_name, schema, projection) = match input {
+            // a limit would make the row count of the table inaccurate, and table
+            // functions are not registered so have no statistics
+            LogicalPlan::TableScan {
+                schema_name,
+                table_name,
+                function_args: None,
+                schema,
+                projection,
+                limit: None,
+            } => (schema_name, table_name, schema, projection),
+            _ => return None,
+        };
+
+        let name = format!("{}.{}", schema_name, table_name);
+        let statistics = self.schema_provider.get_table_statistics(&name)?;
+        let num_rows = statistics.num_rows?;
+
+        aggr_expr
+            .iter()
+            .map(|e| match e {
+                Expr::AggregateFunction { name, args, .. }
+                    if name.to_lowercase() == "count" && args.len() == 1 =>
+                {
+                    match args[0] {
+                        Expr::Column(i) => {
+                            // COUNT only includes non-null values
+                            let table_index = match projection {
+                                Some(p) => *p.get(i)?,
+                                None => i,
+                            };
+                            let non_nullable = !schema.fields().get(i)?.is_nullable();
+                            if non_nullable
+                                || null_count(&statistics, table_index) == Some(0)
+                            {
+                                Some(ScalarValue::UInt64(num_rows as u64))
+                            } else {
+                                None
+                            }
+                        }
+                        _ => None,
+                    }
+                }
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// Get the number of null values in a column, if known
+fn null_count(statistics: &Statistics, column: usize) -> Option<usize> {
+    statistics
+        .column_statistics
+        .as_ref()
+        .and_then(|columns| columns.get(column))
+        .and_then(|c| c.null_count)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::execution::datasource::ColumnStatistics;
+    use crate::logicalplan::Expr::*;
+    use crate::logicalplan::FunctionMeta;
+    use crate::logicalplan::LogicalPlan::*;
+    use arrow::datatypes::{DataType, Field, Schema};
+
+    #[test]
+    fn count_from_row_count() {
+        let plan = count_plan(Column(0), None);
+        assert_optimized_plan_eq(&plan, "Values: [[UInt64(100)]]");
+    }
+
+    #[test]
+    fn count_nullable_column_without_null_count() {
+        let plan = count_plan(Column(1), None);
+        assert_optimized_plan_eq(
+            &plan,
+            "Aggregate: groupBy=[[]], aggr=[[COUNT(#1)]]\n  TableScan: test projection=None",
+        );
+    }
+
+    #[test]
+    fn count_nullable_column_with_null_count() {
+        let plan = count_plan(Column(2), None);
+        assert_optimized_plan_eq(&plan, "Values: [[UInt64(100)]]");
+    }
+
+    #[test]
+    fn count_with_limit_not_optimized() {
+        let plan = count_plan(Column(0), Some(10));
+        assert_optimized_plan_eq(
+            &plan,
+            "Aggregate: groupBy=[[]], aggr=[[COUNT(#0)]]\n  TableScan: test projection=None limit=10",
+        );
+    }
+
+    #[test]
+    fn count_after_selection_not_optimized() {
+        let plan = Aggregate {
+            group_expr: vec![],
+            aggr_expr: vec![count(Column(0))],
+            schema: count_schema(),
+            input: Rc::new(Selection {
+                expr: Column(0).gt(&Literal(ScalarValue::UInt32(1))),
+                input: Rc::new(test_table_scan(None)),
+            }),
+        };
+        assert_optimized_plan_eq(
+            &plan,
+            "Aggregate: groupBy=[[]], aggr=[[COUNT(#0)]]\
+             \n  Selection: #0 Gt UInt32(1)\
+             \n    TableScan: test projection=None",
+        );
+    }
+
+    #[test]
+    fn count_without_statistics_not_optimized() {
+        let plan = count_plan(Column(0), None);
+        let mut rule = AggregateStatistics::new(Arc::new(MockSchemaProvider {
+            statistics: Statistics::default(),
+        }));
+        let optimized_plan = rule.optimize(&plan).unwrap();
+        assert_eq!(
+            "Aggregate: groupBy=[[]], aggr=[[COUNT(#0)]]\n  TableScan: test projection=None",
+            format!("{:?}", optimized_plan)
+        );
+    }
+
+    fn assert_optimized_plan_eq(plan: &LogicalPlan, expected: &str) {
+        let statistics = Statistics {
+            num_rows: Some(100),
+            total_byte_size: None,
+            column_statistics: Some(vec![
+                ColumnStatistics::default(),
+                ColumnStatistics::default(),
+                ColumnStatistics {
+                    null_count: Some(0),
+                    min_value: None,
+                    max_value: None,
+                },
+            ]),
+        };
+        let mut rule =
+            AggregateStatistics::new(Arc::new(MockSchemaProvider { statistics }));
+        let optimized_plan = rule.optimize(plan).unwrap();
+        let formatted_plan = format!("{:?}", optimized_plan);
+        assert_eq!(formatted_plan, expected);
+    }
+
+    fn count(expr: Expr) -> Expr {
+        AggregateFunction {
+            name: "COUNT".to_string(),
+            args: vec![expr],
+            return_type: DataType::UInt64,
+        }
+    }
+
+    fn count_schema() -> Arc<Schema> {
+        Arc::new(Schema::new(vec![Field::new(
+            "COUNT",
+            DataType::UInt64,
+            true,
+        )]))
+    }
+
+    fn count_plan(expr: Expr, limit: Option<usize>) -> LogicalPlan {
+        Aggregate {
+            group_expr: vec![],
+            aggr_expr: vec![count(expr)],
+            schema: count_schema(),
+            input: Rc::new(test_table_scan(limit)),
+        }
+    }
+
+    struct MockSchemaProvider {
+        statistics: Statistics,
+    }
+
+    impl SchemaProvider for MockSchemaProvider {
+        fn get_table_meta(&self, _name: &str) -> Option<Arc<Schema>> {
+            None
+        }
+
+        fn get_function_meta(&self, _name: &str) -> Option<Arc<FunctionMeta>> {
+            None
+        }
+
+        fn get_table_statistics(&self, name: &str) -> Option<Statistics> {
+            match name {
+                "default.test" => Some(self.statistics.clone()),
+                _ => None,
+            }
+        }
+    }
+
+    /// all tests share a common table where only the first column is not nullable
+    fn test_table_scan(limit: Option<usize>) -> LogicalPlan {
+        TableScan {
+            schema_name: "default".to_string(),
+            table_name: "test".to_string(),
+            function_args: None,
+            schema: Arc::new(Schema::new(vec![
+                Field::new("a", DataType::UInt32, false),
+                Field::new("b", DataType::UInt32, true),
+                Field::new("c", DataType::UInt32, true),
+            ])),
+            projection: None,
+            limit,
+        }
+    }
+}
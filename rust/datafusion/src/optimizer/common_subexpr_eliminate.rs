@@ -0,0 +1,579 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Common Subexpression Eliminate optimizer rule ensures that expressions that appear
+//! more than once in a projection or selection are only evaluated once per batch
+
+use crate::logicalplan::{Expr, LogicalPlan, Operator};
+use crate::optimizer::optimizer::OptimizerRule;
+use crate::optimizer::utils;
+use arrow::datatypes::{Field, Schema};
+use arrow::error::Result;
+use std::collections::HashSet;
+use std::rc::Rc;
+use std::sync::Arc;
+
+/// Common Subexpression Eliminate optimizer rule factors subexpressions that are shared
+/// between the expressions of a projection (or within a selection predicate) into an
+/// intermediate projection, so that they are computed once and then referenced by
+/// column index.
+///
+/// This rule should run after projection push down because the intermediate projection
+/// passes through the input columns that are needed by the original expressions.
+///
+/// A selection directly over a table scan is left alone, so that its predicate can
+/// still be pushed into the scan by the physical planner. Subexpressions that are only
+/// evaluated on the right side of `AND` or `OR` are not extracted, because they may be
+/// guarded by the left side, as in `b <> 0 AND a / b > 1`.
+pub struct CommonSubexprEliminate {}
+
+impl OptimizerRule for CommonSubexprEliminate {
+    fn optimize(&mut self, plan: &LogicalPlan) -> Result<Rc<LogicalPlan>> {
+        self.optimize_plan(plan)
+    }
+}
+
+impl CommonSubexprEliminate {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    fn optimize_plan(&self, plan: &LogicalPlan) -> Result<Rc<LogicalPlan>> {
+        match plan {
+            LogicalPlan::Projection {
+                expr,
+                input,
+                schema,
+            } => {
+                let input = self.optimize_plan(input)?;
+                let common = find_common_subexprs(expr);
+                if common.is_empty() {
+                    return Ok(Rc::new(LogicalPlan::Projection {
+                        expr: expr.clone(),
+                        input,
+                        schema: schema.clone(),
+                    }));
+                }
+
+                let (input, expr) = extract_common_subexprs(expr, &common, input, false)?;
+                Ok(Rc::new(LogicalPlan::Projection {
+                    expr,
+                    input,
+                    schema: schema.clone(),
+                }))
+            }
+            LogicalPlan::Selection { expr, input } => {
+                if let LogicalPlan::TableScan { .. } = **input {
+                    return Ok(Rc::new(plan.clone()));
+                }
+                let input = self.optimize_plan(input)?;
+                let predicate = vec![expr.clone()];
+                let common = find_common_subexprs(&predicate);
+                if common.is_empty() {
+                    return Ok(Rc::new(LogicalPlan::Selection {
+                        expr: expr.clone(),
+                        input,
+                    }));
+                }
+
+                // all input columns are passed through the intermediate projection so
+                // that the selection output can be projected back to the original schema
+                let schema = input.schema().clone();
+                let (input, mut predicate) =
+                    extract_common_subexprs(&predicate, &common, input, true)?;
+                let selection = Rc::new(LogicalPlan::Selection {
+                    expr: predicate.remove(0),
+                    input,
+                });
+
+                Ok(Rc::new(LogicalPlan::Projection {
+                    expr: (0..schema.fields().len()).map(Expr::Column).collect(),
+                    input: selection,
+                    schema,
+                }))
+            }
+            LogicalPlan::Aggregate {
+                input,
+                group_expr,
+                aggr_expr,
+                schema,
+            } => Ok(Rc::new(LogicalPlan::Aggregate {
+                input: self.optimize_plan(input)?,
+                group_expr: group_expr.clone(),
+                aggr_expr: aggr_expr.clone(),
+                schema: schema.clone(),
+            })),
+            LogicalPlan::Sort {
+                expr,
+                input,
+                schema,
+            } => Ok(Rc::new(LogicalPlan::Sort {
+                expr: expr.clone(),
+                input: self.optimize_plan(input)?,
+                schema: schema.clone(),
+            })),
+            LogicalPlan::Limit {
+                expr,
+                input,
+                schema,
+            } => Ok(Rc::new(LogicalPlan::Limit {
+                expr: expr.clone(),
+                input: self.optimize_plan(input)?,
+                schema: schema.clone(),
+            })),
+            LogicalPlan::Join {
+                left,
+                right,
+                join_type,
+                on,
+                filter,
+                schema,
+            } => Ok(Rc::new(LogicalPlan::Join {
+                left: self.optimize_plan(left)?,
+                right: self.optimize_plan(right)?,
+                join_type: *join_type,
+                on: on.clone(),
+                filter: filter.clone(),
+                schema: schema.clone(),
+            })),
+            LogicalPlan::Unnest {
+                input,
+                column,
+                schema,
+            } => Ok(Rc::new(LogicalPlan::Unnest {
+                input: self.optimize_plan(input)?,
+                column: *column,
+                schema: schema.clone(),
+            })),
+            LogicalPlan::TableScan { .. }
+            | LogicalPlan::EmptyRelation { .. }
+            | LogicalPlan::Values { .. } => Ok(Rc::new(plan.clone())),
+        }
+    }
+}
+
+/// Determine whether an expression is worth computing only once. Column references and
+/// literals are cheap and aggregates cannot be evaluated by a projection.
+fn is_candidate(expr: &Expr) -> bool {
+    match expr {
+        Expr::BinaryExpr { .. }
+        | Expr::Cast { .. }
+        | Expr::Not(_)
+        | Expr::IsNull(_)
+        | Expr::IsNotNull(_)
+        | Expr::ScalarFunction { .. } => !utils::contains_aggregate(expr),
+        _ => false,
+    }
+}
+
+/// The number of times that a candidate subexpression occurs, and whether any of its
+/// occurrences is evaluated for every row
+struct SubexprCount {
+    expr: Expr,
+    count: usize,
+    unguarded: bool,
+}
+
+/// Count the number of times each candidate subexpression occurs. An occurrence is
+/// guarded when it is on the right side of an `AND` or `OR`, which is only evaluated
+/// for the rows that the left side does not determine.
+fn count_subexprs(expr: &Expr, guarded: bool, counts: &mut Vec<SubexprCount>) {
+    if is_candidate(expr) {
+        match counts.iter_mut().find(|c| c.expr == *expr) {
+            Some(c) => {
+                c.count += 1;
+                c.unguarded |= !guarded;
+            }
+            None => counts.push(SubexprCount {
+                expr: expr.clone(),
+                count: 1,
+                unguarded: !guarded,
+            }),
+        }
+    }
+    match expr {
+        Expr::BinaryExpr {
+            left,
+            op: Operator::And,
+            right,
+        }
+        | Expr::BinaryExpr {
+            left,
+            op: Operator::Or,
+            right,
+        } => {
+            count_subexprs(left, guarded, counts);
+            count_subexprs(right, true, counts);
+        }
+        _ => utils::expr_sub_expressions(expr)
+            .into_iter()
+            .for_each(|e| count_subexprs(e, guarded, counts)),
+    }
+}
+
+/// Find the largest subexpressions that occur more than once, and that are evaluated
+/// for every row by at least one of their occurrences, so that computing them for
+/// every row does not evaluate them where they were guarded
+fn find_common_subexprs(expr: &Vec<Expr>) -> Vec<Expr> {
+    let mut counts = vec![];
+    expr.iter()
+        .for_each(|e| count_subexprs(e, false, &mut counts));
+
+    let mut common = vec![];
+    expr.iter()
+        .for_each(|e| collect_common_subexprs(e, &counts, &mut common));
+    common
+}
+
+fn collect_common_subexprs(
+    expr: &Expr,
+    counts: &Vec<SubexprCount>,
+    common: &mut Vec<Expr>,
+) {
+    if counts
+        .iter()
+        .any(|c| c.count > 1 && c.unguarded && c.expr == *expr)
+    {
+        // no need to look at the children since they will only be evaluated as part of
+        // this expression
+        if !common.contains(expr) {
+            common.push(expr.clone());
+        }
+    } else {
+        utils::expr_sub_expressions(expr)
+            .into_iter()
+            .for_each(|e| collect_common_subexprs(e, counts, common));
+    }
+}
+
+/// Replace occurrences of the common subexpressions with references to column
+/// `offset + i`, where `i` is the index of the subexpression in `common`
+fn replace_common_subexprs(
+    expr: &Expr,
+    common: &Vec<Expr>,
+    offset: usize,
+) -> Result<Expr> {
+    match common.iter().position(|e| e == expr) {
+        Some(i) => Ok(Expr::Column(offset + i)),
+        None => {
+            let children = utils::expr_sub_expressions(expr)
+                .into_iter()
+                .map(|e| replace_common_subexprs(e, common, offset))
+                .collect::<Result<Vec<Expr>>>()?;
+            utils::rewrite_expression(expr, &children)
+        }
+    }
+}
+
+/// Create an intermediate projection on top of `input` that computes the common
+/// subexpressions, and rewrite `expr` to reference the intermediate projection. When
+/// `all_columns` is false, only the input columns that are still referenced after the
+/// rewrite are passed through.
+fn extract_common_subexprs(
+    expr: &Vec<Expr>,
+    common: &Vec<Expr>,
+    input: Rc<LogicalPlan>,
+    all_columns: bool,
+) -> Result<(Rc<LogicalPlan>, Vec<Expr>)> {
+    let input_schema = input.schema().clone();
+    let num_input_columns = input_schema.fields().len();
+
+    // first rewrite the expressions with the common subexpressions appended after the
+    // input columns
+    let rewritten = expr
+        .iter()
+        .map(|e| replace_common_subexprs(e, common, num_input_columns))
+        .collect::<Result<Vec<Expr>>>()?;
+
+    // determine which input columns are needed by the rewritten expressions
+    let mut columns: Vec<usize> = if all_columns {
+        (0..num_input_columns).collect()
+    } else {
+        let mut accum: HashSet<usize> = HashSet::new();
+        utils::exprlist_to_column_indices(&rewritten, &mut accum);
+        accum
+            .into_iter()
+            .filter(|i| *i < num_input_columns)
+            .collect()
+    };
+    columns.sort();
+
+    let mut projection_expr: Vec<Expr> =
+        columns.iter().map(|i| Expr::Column(*i)).collect();
+    let mut projection_fields: Vec<Field> = columns
+        .iter()
+        .map(|i| input_schema.field(*i).clone())
+        .collect();
+    for e in common {
+        projection_expr.push(e.clone());
+        projection_fields.push(Field::new(
+            &format!("{:?}", e),
+            e.get_type(&input_schema),
+            true,
+        ));
+    }
+
+    // map the column indexes used by the rewritten expressions to the output of the
+    // intermediate projection
+    let mut mapping: Vec<Expr> = (0..num_input_columns + common.len())
+        .map(Expr::Column)
+        .collect();
+    for (new_index, i) in columns.iter().enumerate() {
+        mapping[*i] = Expr::Column(new_index);
+    }
+    for i in 0..common.len() {
+        mapping[num_input_columns + i] = Expr::Column(columns.len() + i);
+    }
+    let new_expr = rewritten
+        .iter()
+        .map(|e| utils::replace_columns(e, &mapping))
+        .collect::<Result<Vec<Expr>>>()?;
+
+    let projection = LogicalPlan::Projection {
+        expr: projection_expr,
+        input,
+        schema: Arc::new(Schema::new(projection_fields)),
+    };
+
+    Ok((Rc::new(projection), new_expr))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::logicalplan::Expr::*;
+    use crate::logicalplan::LogicalPlan::*;
+    use crate::logicalplan::{Operator, ScalarValue};
+    use arrow::datatypes::DataType;
+
+    #[test]
+    fn common_subexpr_in_projection() {
+        // SELECT (a * b) + 1, (a * b) - 1 FROM test
+        let a_times_b = binary_expr(Column(0), Operator::Multiply, Column(1));
+        let projection = Projection {
+            expr: vec![
+                binary_expr(a_times_b.clone(), Operator::Plus, lit(1)),
+                binary_expr(a_times_b.clone(), Operator::Minus, lit(1)),
+            ],
+            input: Rc::new(test_table_scan()),
+            schema: Arc::new(Schema::new(vec![
+                Field::new("x", DataType::Int64, true),
+                Field::new("y", DataType::Int64, true),
+            ])),
+        };
+
+        let optimized_plan = optimize(&projection);
+        assert_eq!(
+            "Projection: #0 Plus Int64(1), #0 Minus Int64(1)\
+             \n  Projection: #0 Multiply #1\
+             \n    TableScan: test projection=None",
+            format!("{:?}", optimized_plan)
+        );
+
+        // the schema of the original projection is preserved
+        assert_eq!(
+            projection.schema().fields(),
+            optimized_plan.schema().fields()
+        );
+    }
+
+    #[test]
+    fn common_subexpr_passes_through_input_columns() {
+        // SELECT c, (a * b) + c, (a * b) - 1 FROM test
+        let a_times_b = binary_expr(Column(0), Operator::Multiply, Column(1));
+        let projection = Projection {
+            expr: vec![
+                Column(2),
+                binary_expr(a_times_b.clone(), Operator::Plus, Column(2)),
+                binary_expr(a_times_b.clone(), Operator::Minus, lit(1)),
+            ],
+            input: Rc::new(test_table_scan()),
+            schema: Arc::new(Schema::new(vec![
+                Field::new("c", DataType::Int64, true),
+                Field::new("x", DataType::Int64, true),
+                Field::new("y", DataType::Int64, true),
+            ])),
+        };
+
+        assert_optimized_plan_eq(
+            &projection,
+            "Projection: #0, #1 Plus #0, #1 Minus Int64(1)\
+             \n  Projection: #2, #0 Multiply #1\
+             \n    TableScan: test projection=None",
+        );
+    }
+
+    #[test]
+    fn common_subexpr_in_selection() {
+        // WHERE a * b > 1 AND a * b < 10
+        let a_times_b = binary_expr(Column(0), Operator::Multiply, Column(1));
+        let selection = Selection {
+            expr: binary_expr(
+                binary_expr(a_times_b.clone(), Operator::Gt, lit(1)),
+                Operator::And,
+                binary_expr(a_times_b.clone(), Operator::Lt, lit(10)),
+            ),
+            input: Rc::new(test_projection()),
+        };
+
+        let optimized_plan = optimize(&selection);
+        assert_eq!(
+            "Projection: #0, #1, #2\
+             \n  Selection: #3 Gt Int64(1) And #3 Lt Int64(10)\
+             \n    Projection: #0, #1, #2, #0 Multiply #1\
+             \n      Projection: #0, #1, #2\
+             \n        TableScan: test projection=None",
+            format!("{:?}", optimized_plan)
+        );
+        assert_eq!(3, optimized_plan.schema().fields().len());
+    }
+
+    #[test]
+    fn selection_over_table_scan_is_unchanged() {
+        // the predicate stays directly over the scan, so that it can be pushed into it
+        let a_times_b = binary_expr(Column(0), Operator::Multiply, Column(1));
+        let selection = Selection {
+            expr: binary_expr(
+                binary_expr(a_times_b.clone(), Operator::Gt, lit(1)),
+                Operator::And,
+                binary_expr(a_times_b.clone(), Operator::Lt, lit(10)),
+            ),
+            input: Rc::new(test_table_scan()),
+        };
+
+        assert_optimized_plan_eq(
+            &selection,
+            "Selection: #0 Multiply #1 Gt Int64(1) And #0 Multiply #1 Lt Int64(10)\
+             \n  TableScan: test projection=None",
+        );
+    }
+
+    #[test]
+    fn guarded_subexpr_is_not_extracted() {
+        // WHERE b <> 0 AND (a / b > 1 OR a / b < -1)
+        let a_div_b = binary_expr(Column(0), Operator::Divide, Column(1));
+        let selection = Selection {
+            expr: binary_expr(
+                binary_expr(Column(1), Operator::NotEq, lit(0)),
+                Operator::And,
+                binary_expr(
+                    binary_expr(a_div_b.clone(), Operator::Gt, lit(1)),
+                    Operator::Or,
+                    binary_expr(a_div_b.clone(), Operator::Lt, lit(-1)),
+                ),
+            ),
+            input: Rc::new(test_projection()),
+        };
+
+        assert_optimized_plan_eq(
+            &selection,
+            "Selection: #1 NotEq Int64(0) And #0 Divide #1 Gt Int64(1) \
+             Or #0 Divide #1 Lt Int64(-1)\
+             \n  Projection: #0, #1, #2\
+             \n    TableScan: test projection=None",
+        );
+
+        // an occurrence that is evaluated for every row can be shared
+        let selection = Selection {
+            expr: binary_expr(
+                binary_expr(a_div_b.clone(), Operator::Gt, lit(1)),
+                Operator::Or,
+                binary_expr(a_div_b.clone(), Operator::Lt, lit(-1)),
+            ),
+            input: Rc::new(test_projection()),
+        };
+        assert_optimized_plan_eq(
+            &selection,
+            "Projection: #0, #1, #2\
+             \n  Selection: #3 Gt Int64(1) Or #3 Lt Int64(-1)\
+             \n    Projection: #0, #1, #2, #0 Divide #1\
+             \n      Projection: #0, #1, #2\
+             \n        TableScan: test projection=None",
+        );
+    }
+
+    #[test]
+    fn no_common_subexpr() {
+        let projection = Projection {
+            expr: vec![
+                binary_expr(Column(0), Operator::Plus, lit(1)),
+                binary_expr(Column(0), Operator::Minus, lit(1)),
+            ],
+            input: Rc::new(test_table_scan()),
+            schema: Arc::new(Schema::new(vec![
+                Field::new("x", DataType::Int64, true),
+                Field::new("y", DataType::Int64, true),
+            ])),
+        };
+
+        assert_optimized_plan_eq(
+            &projection,
+            "Projection: #0 Plus Int64(1), #0 Minus Int64(1)\
+             \n  TableScan: test projection=None",
+        );
+    }
+
+    fn assert_optimized_plan_eq(plan: &LogicalPlan, expected: &str) {
+        let optimized_plan = optimize(plan);
+        let formatted_plan = format!("{:?}", optimized_plan);
+        assert_eq!(formatted_plan, expected);
+    }
+
+    fn optimize(plan: &LogicalPlan) -> Rc<LogicalPlan> {
+        let mut rule = CommonSubexprEliminate::new();
+        rule.optimize(plan).unwrap()
+    }
+
+    fn binary_expr(left: Expr, op: Operator, right: Expr) -> Expr {
+        BinaryExpr {
+            left: Rc::new(left),
+            op,
+            right: Rc::new(right),
+        }
+    }
+
+    fn lit(n: i64) -> Expr {
+        Literal(ScalarValue::Int64(n))
+    }
+
+    /// A projection of all of the columns of the test table
+    fn test_projection() -> LogicalPlan {
+        let scan = test_table_scan();
+        Projection {
+            expr: vec![Column(0), Column(1), Column(2)],
+            schema: scan.schema().clone(),
+            input: Rc::new(scan),
+        }
+    }
+
+    /// all tests share a common table
+    fn test_table_scan() -> LogicalPlan {
+        TableScan {
+            schema_name: "default".to_string(),
+            table_name: "test".to_string(),
+            function_args: None,
+            schema: Arc::new(Schema::new(vec![
+                Field::new("a", DataType::Int64, false),
+                Field::new("b", DataType::Int64, false),
+                Field::new("c", DataType::Int64, false),
+            ])),
+            projection: None,
+            limit: None,
+        }
+    }
+}
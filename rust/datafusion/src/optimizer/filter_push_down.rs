@@ -0,0 +1,395 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Filter Push Down optimizer rule ensures that predicates are applied as early as
+//! possible, directly on top of the data source where that is safe
+
+use crate::logicalplan::Expr;
+use crate::logicalplan::LogicalPlan;
+use crate::optimizer::optimizer::OptimizerRule;
+use crate::optimizer::utils;
+use arrow::error::Result;
+use std::collections::HashSet;
+use std::rc::Rc;
+
+/// Filter Push Down optimizer rule moves Selection predicates down the plan (below
+/// projections, sorts, and aggregates when the predicate only references grouping
+/// columns) so that rows are discarded directly on top of the table scan
+pub struct FilterPushDown {}
+
+impl OptimizerRule for FilterPushDown {
+    fn optimize(&mut self, plan: &LogicalPlan) -> Result<Rc<LogicalPlan>> {
+        self.optimize_plan(plan, vec![])
+    }
+}
+
+impl FilterPushDown {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    /// Optimize a plan, where `predicates` are conjuncts (referencing this plan's output
+    /// columns) that have been pushed down from the parent and still need to be applied
+    fn optimize_plan(
+        &self,
+        plan: &LogicalPlan,
+        predicates: Vec<Expr>,
+    ) -> Result<Rc<LogicalPlan>> {
+        match plan {
+            LogicalPlan::Selection { expr, input } => {
+                // merge this predicate with the ones pushed down from above and keep
+                // pushing them all down
+                let mut predicates = predicates;
+                utils::split_conjunction(expr, &mut predicates);
+                self.optimize_plan(input, predicates)
+            }
+            LogicalPlan::Projection {
+                expr,
+                input,
+                schema,
+            } => {
+                // rewrite the predicates in terms of the projection's input
+                let pushed = predicates
+                    .iter()
+                    .map(|p| utils::replace_columns(p, expr))
+                    .collect::<Result<Vec<Expr>>>()?;
+
+                Ok(Rc::new(LogicalPlan::Projection {
+                    expr: expr.clone(),
+                    input: self.optimize_plan(input, pushed)?,
+                    schema: schema.clone(),
+                }))
+            }
+            LogicalPlan::Sort {
+                expr,
+                input,
+                schema,
+            } => Ok(Rc::new(LogicalPlan::Sort {
+                expr: expr.clone(),
+                input: self.optimize_plan(input, predicates)?,
+                schema: schema.clone(),
+            })),
+            LogicalPlan::Aggregate {
+                input,
+                group_expr,
+                aggr_expr,
+                schema,
+            } => {
+                // predicates that only reference grouping columns can be evaluated
+                // before the aggregate, but predicates on aggregate values cannot. A
+                // global aggregate produces a row even for empty input, so nothing is
+                // pushed below it.
+                let mut pushed = vec![];
+                let mut kept = vec![];
+                for predicate in predicates {
+                    let mut columns: HashSet<usize> = HashSet::new();
+                    utils::expr_to_column_indices(&predicate, &mut columns);
+                    if !group_expr.is_empty()
+                        && columns.iter().all(|i| *i < group_expr.len())
+                    {
+                        pushed.push(utils::replace_columns(&predicate, group_expr)?);
+                    } else {
+                        kept.push(predicate);
+                    }
+                }
+
+                let aggregate = Rc::new(LogicalPlan::Aggregate {
+                    input: self.optimize_plan(input, pushed)?,
+                    group_expr: group_expr.clone(),
+                    aggr_expr: aggr_expr.clone(),
+                    schema: schema.clone(),
+                });
+
+                Ok(add_selection(aggregate, kept))
+            }
+            LogicalPlan::Limit {
+                expr,
+                input,
+                schema,
+            } => {
+                // filtering before the limit would change the result so the predicates
+                // have to stay above it
+                let limit = Rc::new(LogicalPlan::Limit {
+                    expr: expr.clone(),
+                    input: self.optimize_plan(input, vec![])?,
+                    schema: schema.clone(),
+                });
+
+                Ok(add_selection(limit, predicates))
+            }
+            LogicalPlan::Join {
+                left,
+                right,
+                join_type,
+                on,
+                filter,
+                schema,
+            } => {
+                // predicates are not pushed into the inputs of a join yet, but
+                // selections within each input can still be pushed down
+                let join = Rc::new(LogicalPlan::Join {
+                    left: self.optimize_plan(left, vec![])?,
+                    right: self.optimize_plan(right, vec![])?,
+                    join_type: *join_type,
+                    on: on.clone(),
+                    filter: filter.clone(),
+                    schema: schema.clone(),
+                });
+
+                Ok(add_selection(join, predicates))
+            }
+            LogicalPlan::Unnest {
+                input,
+                column,
+                schema,
+            } => {
+                // the other columns have the same values before the unnest, so only the
+                // predicates on the unnested column have to stay above it
+                let mut pushed = vec![];
+                let mut kept = vec![];
+                for predicate in predicates {
+                    let mut columns: HashSet<usize> = HashSet::new();
+                    utils::expr_to_column_indices(&predicate, &mut columns);
+                    if columns.contains(column) {
+                        kept.push(predicate);
+                    } else {
+                        pushed.push(predicate);
+                    }
+                }
+
+                let unnest = Rc::new(LogicalPlan::Unnest {
+                    input: self.optimize_plan(input, pushed)?,
+                    column: *column,
+                    schema: schema.clone(),
+                });
+
+                Ok(add_selection(unnest, kept))
+            }
+            LogicalPlan::TableScan { .. }
+            | LogicalPlan::EmptyRelation { .. }
+            | LogicalPlan::Values { .. } => {
+                // this is as far down as predicates can go
+                Ok(add_selection(Rc::new(plan.clone()), predicates))
+            }
+        }
+    }
+}
+
+/// Wrap a plan in a Selection that applies all of the predicates, if there are any
+fn add_selection(plan: Rc<LogicalPlan>, predicates: Vec<Expr>) -> Rc<LogicalPlan> {
+    match utils::combine_conjunction(predicates) {
+        Some(expr) => Rc::new(LogicalPlan::Selection { expr, input: plan }),
+        None => plan,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::logicalplan::Expr::*;
+    use crate::logicalplan::LogicalPlan::*;
+    use crate::logicalplan::{join_schema, JoinType, Operator, ScalarValue};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use std::sync::Arc;
+
+    #[test]
+    fn selection_before_projection() {
+        let projection = Projection {
+            expr: vec![Column(1), Column(0)],
+            input: Rc::new(test_table_scan()),
+            schema: Arc::new(Schema::new(vec![
+                Field::new("b", DataType::UInt32, false),
+                Field::new("a", DataType::UInt32, false),
+            ])),
+        };
+
+        let selection = Selection {
+            expr: Column(0).gt(&lit(1)),
+            input: Rc::new(projection),
+        };
+
+        assert_optimized_plan_eq(
+            &selection,
+            "Projection: #1, #0\n  Selection: #1 Gt UInt32(1)\n    TableScan: test projection=None",
+        );
+    }
+
+    #[test]
+    fn merge_selections() {
+        let selection = Selection {
+            expr: Column(0).eq(&lit(1)),
+            input: Rc::new(Selection {
+                expr: Column(1).eq(&lit(2)),
+                input: Rc::new(test_table_scan()),
+            }),
+        };
+
+        assert_optimized_plan_eq(
+            &selection,
+            "Selection: #0 Eq UInt32(1) And #1 Eq UInt32(2)\n  TableScan: test projection=None",
+        );
+    }
+
+    #[test]
+    fn selection_not_pushed_below_limit() {
+        let limit = Limit {
+            expr: Literal(ScalarValue::UInt32(10)),
+            input: Rc::new(test_table_scan()),
+            schema: test_table_scan().schema().clone(),
+        };
+
+        let selection = Selection {
+            expr: Column(0).eq(&lit(1)),
+            input: Rc::new(limit),
+        };
+
+        assert_optimized_plan_eq(
+            &selection,
+            "Selection: #0 Eq UInt32(1)\n  Limit: UInt32(10)\n    TableScan: test projection=None",
+        );
+    }
+
+    #[test]
+    fn selection_on_group_by_column_before_aggregate() {
+        let aggregate = Aggregate {
+            group_expr: vec![Column(2)],
+            aggr_expr: vec![AggregateFunction {
+                name: "MAX".to_string(),
+                args: vec![Column(1)],
+                return_type: DataType::UInt32,
+            }],
+            schema: Arc::new(Schema::new(vec![
+                Field::new("c", DataType::UInt32, false),
+                Field::new("MAX(b)", DataType::UInt32, false),
+            ])),
+            input: Rc::new(test_table_scan()),
+        };
+
+        let selection = Selection {
+            expr: BinaryExpr {
+                left: Rc::new(Column(0).gt(&lit(1))),
+                op: Operator::And,
+                right: Rc::new(Column(1).gt(&lit(10))),
+            },
+            input: Rc::new(aggregate),
+        };
+
+        assert_optimized_plan_eq(
+            &selection,
+            "Selection: #1 Gt UInt32(10)\
+             \n  Aggregate: groupBy=[[#2]], aggr=[[MAX(#1)]]\
+             \n    Selection: #2 Gt UInt32(1)\
+             \n      TableScan: test projection=None",
+        );
+    }
+
+    #[test]
+    fn selection_not_pushed_below_global_aggregate() {
+        let aggregate = Aggregate {
+            group_expr: vec![],
+            aggr_expr: vec![AggregateFunction {
+                name: "COUNT".to_string(),
+                args: vec![Column(0)],
+                return_type: DataType::UInt64,
+            }],
+            schema: Arc::new(Schema::new(vec![Field::new(
+                "COUNT(a)",
+                DataType::UInt64,
+                false,
+            )])),
+            input: Rc::new(test_table_scan()),
+        };
+
+        let selection = Selection {
+            expr: Literal(ScalarValue::Boolean(false)),
+            input: Rc::new(aggregate),
+        };
+
+        assert_optimized_plan_eq(
+            &selection,
+            "Selection: Boolean(false)             \n  Aggregate: groupBy=[[]], aggr=[[COUNT(#0)]]             \n    TableScan: test projection=None",
+        );
+    }
+
+    #[test]
+    fn selection_not_pushed_below_join() {
+        let left = Selection {
+            expr: Column(0).gt(&lit(1)),
+            input: Rc::new(Projection {
+                expr: vec![Column(1), Column(0)],
+                input: Rc::new(test_table_scan()),
+                schema: Arc::new(Schema::new(vec![
+                    Field::new("b", DataType::UInt32, false),
+                    Field::new("a", DataType::UInt32, false),
+                ])),
+            }),
+        };
+        let right = test_table_scan();
+        let schema = join_schema(left.schema(), right.schema(), JoinType::Inner);
+        let join = Join {
+            left: Rc::new(left),
+            right: Rc::new(right),
+            join_type: JoinType::Inner,
+            on: vec![(1, 0)],
+            filter: None,
+            schema: Arc::new(schema),
+        };
+
+        let selection = Selection {
+            expr: Column(4).eq(&lit(2)),
+            input: Rc::new(join),
+        };
+
+        assert_optimized_plan_eq(
+            &selection,
+            "Selection: #4 Eq UInt32(2)\
+             \n  Join: type=Inner, on=[#1 = #0]\
+             \n    Projection: #1, #0\
+             \n      Selection: #1 Gt UInt32(1)\
+             \n        TableScan: test projection=None\
+             \n    TableScan: test projection=None",
+        );
+    }
+
+    fn assert_optimized_plan_eq(plan: &LogicalPlan, expected: &str) {
+        let mut rule = FilterPushDown::new();
+        let optimized_plan = rule.optimize(plan).unwrap();
+        let formatted_plan = format!("{:?}", optimized_plan);
+        assert_eq!(formatted_plan, expected);
+    }
+
+    fn lit(n: u32) -> Expr {
+        Literal(ScalarValue::UInt32(n))
+    }
+
+    /// all tests share a common table
+    fn test_table_scan() -> LogicalPlan {
+        TableScan {
+            schema_name: "default".to_string(),
+            table_name: "test".to_string(),
+            function_args: None,
+            schema: Arc::new(Schema::new(vec![
+                Field::new("a", DataType::UInt32, false),
+                Field::new("b", DataType::UInt32, false),
+                Field::new("c", DataType::UInt32, false),
+            ])),
+            projection: None,
+            limit: None,
+        }
+    }
+}
@@ -0,0 +1,260 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Limit Push Down optimizer rule ensures that data sources stop reading once enough
+//! rows have been produced to satisfy a LIMIT
+
+use crate::logicalplan::{Expr, LogicalPlan, ScalarValue};
+use crate::optimizer::optimizer::OptimizerRule;
+use arrow::error::Result;
+use std::rc::Rc;
+
+/// Limit Push Down optimizer rule pushes a LIMIT through any relations that do not
+/// change the number of rows (such as projections) and into the table scan
+pub struct LimitPushDown {}
+
+impl OptimizerRule for LimitPushDown {
+    fn optimize(&mut self, plan: &LogicalPlan) -> Result<Rc<LogicalPlan>> {
+        self.optimize_plan(plan, None)
+    }
+}
+
+impl LimitPushDown {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    /// Optimize a plan, where `limit` is the maximum number of rows that the parent
+    /// relations will consume from this plan
+    fn optimize_plan(
+        &self,
+        plan: &LogicalPlan,
+        limit: Option<usize>,
+    ) -> Result<Rc<LogicalPlan>> {
+        match plan {
+            LogicalPlan::Limit {
+                expr,
+                input,
+                schema,
+            } => {
+                // the limit relation is kept in case the data source does not honor
+                // the limit exactly
+                let limit = match (limit, literal_to_usize(expr)) {
+                    (Some(a), Some(b)) => Some(a.min(b)),
+                    (a, b) => a.or(b),
+                };
+
+                Ok(Rc::new(LogicalPlan::Limit {
+                    expr: expr.clone(),
+                    input: self.optimize_plan(input, limit)?,
+                    schema: schema.clone(),
+                }))
+            }
+            LogicalPlan::Projection {
+                expr,
+                input,
+                schema,
+            } => Ok(Rc::new(LogicalPlan::Projection {
+                expr: expr.clone(),
+                input: self.optimize_plan(input, limit)?,
+                schema: schema.clone(),
+            })),
+            LogicalPlan::TableScan {
+                schema_name,
+                table_name,
+                function_args,
+                schema,
+                projection,
+                limit: scan_limit,
+            } => {
+                let limit = match (limit, scan_limit) {
+                    (Some(a), Some(b)) => Some(a.min(*b)),
+                    (a, b) => a.or(*b),
+                };
+
+                Ok(Rc::new(LogicalPlan::TableScan {
+                    schema_name: schema_name.to_string(),
+                    table_name: table_name.to_string(),
+                    function_args: function_args.clone(),
+                    schema: schema.clone(),
+                    projection: projection.clone(),
+                    limit,
+                }))
+            }
+            // selections, sorts and aggregates need to see all of their input rows so
+            // the limit cannot be pushed any further down
+            LogicalPlan::Selection { expr, input } => {
+                Ok(Rc::new(LogicalPlan::Selection {
+                    expr: expr.clone(),
+                    input: self.optimize_plan(input, None)?,
+                }))
+            }
+            LogicalPlan::Sort {
+                expr,
+                input,
+                schema,
+            } => Ok(Rc::new(LogicalPlan::Sort {
+                expr: expr.clone(),
+                input: self.optimize_plan(input, None)?,
+                schema: schema.clone(),
+            })),
+            LogicalPlan::Aggregate {
+                input,
+                group_expr,
+                aggr_expr,
+                schema,
+            } => Ok(Rc::new(LogicalPlan::Aggregate {
+                input: self.optimize_plan(input, None)?,
+                group_expr: group_expr.clone(),
+                aggr_expr: aggr_expr.clone(),
+                schema: schema.clone(),
+            })),
+            // a join can produce any number of rows for each input row
+            LogicalPlan::Join {
+                left,
+                right,
+                join_type,
+                on,
+                filter,
+                schema,
+            } => Ok(Rc::new(LogicalPlan::Join {
+                left: self.optimize_plan(left, None)?,
+                right: self.optimize_plan(right, None)?,
+                join_type: *join_type,
+                on: on.clone(),
+                filter: filter.clone(),
+                schema: schema.clone(),
+            })),
+            // an unnest can produce any number of rows for each input row
+            LogicalPlan::Unnest {
+                input,
+                column,
+                schema,
+            } => Ok(Rc::new(LogicalPlan::Unnest {
+                input: self.optimize_plan(input, None)?,
+                column: *column,
+                schema: schema.clone(),
+            })),
+            LogicalPlan::EmptyRelation { .. } | LogicalPlan::Values { .. } => {
+                Ok(Rc::new(plan.clone()))
+            }
+        }
+    }
+}
+
+/// Get the value of a non-negative integer literal
+fn literal_to_usize(expr: &Expr) -> Option<usize> {
+    match expr {
+        Expr::Literal(ScalarValue::Int8(n)) if *n >= 0 => Some(*n as usize),
+        Expr::Literal(ScalarValue::Int16(n)) if *n >= 0 => Some(*n as usize),
+        Expr::Literal(ScalarValue::Int32(n)) if *n >= 0 => Some(*n as usize),
+        Expr::Literal(ScalarValue::Int64(n)) if *n >= 0 => Some(*n as usize),
+        Expr::Literal(ScalarValue::UInt8(n)) => Some(*n as usize),
+        Expr::Literal(ScalarValue::UInt16(n)) => Some(*n as usize),
+        Expr::Literal(ScalarValue::UInt32(n)) => Some(*n as usize),
+        Expr::Literal(ScalarValue::UInt64(n)) => Some(*n as usize),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::logicalplan::Expr::*;
+    use crate::logicalplan::LogicalPlan::*;
+    use arrow::datatypes::{DataType, Field, Schema};
+    use std::sync::Arc;
+
+    #[test]
+    fn limit_pushed_through_projection() {
+        let projection = Projection {
+            expr: vec![Column(0)],
+            input: Rc::new(test_table_scan()),
+            schema: Arc::new(Schema::new(vec![Field::new("a", DataType::UInt32, false)])),
+        };
+
+        let limit = Limit {
+            expr: Literal(ScalarValue::Int64(10)),
+            schema: projection.schema().clone(),
+            input: Rc::new(projection),
+        };
+
+        assert_optimized_plan_eq(
+            &limit,
+            "Limit: Int64(10)\n  Projection: #0\n    TableScan: test projection=None limit=10",
+        );
+    }
+
+    #[test]
+    fn nested_limits_use_smallest_value() {
+        let limit = Limit {
+            expr: Literal(ScalarValue::Int64(100)),
+            schema: test_table_scan().schema().clone(),
+            input: Rc::new(Limit {
+                expr: Literal(ScalarValue::Int64(10)),
+                schema: test_table_scan().schema().clone(),
+                input: Rc::new(test_table_scan()),
+            }),
+        };
+
+        assert_optimized_plan_eq(
+            &limit,
+            "Limit: Int64(100)\n  Limit: Int64(10)\n    TableScan: test projection=None limit=10",
+        );
+    }
+
+    #[test]
+    fn limit_not_pushed_below_selection() {
+        let limit = Limit {
+            expr: Literal(ScalarValue::Int64(10)),
+            schema: test_table_scan().schema().clone(),
+            input: Rc::new(Selection {
+                expr: Column(0).gt(&Literal(ScalarValue::UInt32(1))),
+                input: Rc::new(test_table_scan()),
+            }),
+        };
+
+        assert_optimized_plan_eq(
+            &limit,
+            "Limit: Int64(10)\n  Selection: #0 Gt UInt32(1)\n    TableScan: test projection=None",
+        );
+    }
+
+    fn assert_optimized_plan_eq(plan: &LogicalPlan, expected: &str) {
+        let mut rule = LimitPushDown::new();
+        let optimized_plan = rule.optimize(plan).unwrap();
+        let formatted_plan = format!("{:?}", optimized_plan);
+        assert_eq!(formatted_plan, expected);
+    }
+
+    /// all tests share a common table
+    fn test_table_scan() -> LogicalPlan {
+        TableScan {
+            schema_name: "default".to_string(),
+            table_name: "test".to_string(),
+            function_args: None,
+            schema: Arc::new(Schema::new(vec![
+                Field::new("a", DataType::UInt32, false),
+                Field::new("b", DataType::UInt32, false),
+                Field::new("c", DataType::UInt32, false),
+            ])),
+            projection: None,
+            limit: None,
+        }
+    }
+}
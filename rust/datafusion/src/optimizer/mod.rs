@@ -15,5 +15,12 @@
 // specific language governing permissions and limitations
 // under the License.
 
+pub mod aggregate_statistics;
+pub mod common_subexpr_eliminate;
+pub mod filter_push_down;
+pub mod limit_push_down;
 pub mod optimizer;
 pub mod projection_push_down;
+pub mod remove_redundant_projections;
+pub mod simplify_filters;
+pub mod utils;
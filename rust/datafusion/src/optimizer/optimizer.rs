@@ -17,7 +17,7 @@
 
 //! Query optimizer traits
 
-use crate::logicalplan::LogicalPlan;
+use crate::logicalplan::{Expr, LogicalPlan};
 use arrow::error::Result;
 use std::rc::Rc;
 
@@ -25,3 +25,27 @@ use std::rc::Rc;
 pub trait OptimizerRule {
     fn optimize(&mut self, plan: &LogicalPlan) -> Result<Rc<LogicalPlan>>;
 }
+
+/// Rewrites an expression tree from the bottom up, for use with `utils::rewrite_expr`
+pub trait ExprRewriter {
+    /// Invoked before the children of an expression are rewritten. Returning false
+    /// leaves the children unchanged, but `mutate` is still invoked for the expression.
+    fn pre_visit(&mut self, _expr: &Expr) -> Result<bool> {
+        Ok(true)
+    }
+
+    /// Invoked after the children of an expression have been rewritten
+    fn mutate(&mut self, expr: Expr) -> Result<Expr>;
+}
+
+/// Rewrites a logical plan from the bottom up, for use with `utils::rewrite_plan`
+pub trait PlanRewriter {
+    /// Invoked before the inputs of a plan are rewritten. Returning false leaves the
+    /// inputs unchanged, but `mutate` is still invoked for the plan.
+    fn pre_visit(&mut self, _plan: &LogicalPlan) -> Result<bool> {
+        Ok(true)
+    }
+
+    /// Invoked after the inputs of a plan have been rewritten
+    fn mutate(&mut self, plan: Rc<LogicalPlan>) -> Result<Rc<LogicalPlan>>;
+}
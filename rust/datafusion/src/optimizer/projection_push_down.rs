@@ -21,6 +21,7 @@
 use crate::logicalplan::Expr;
 use crate::logicalplan::LogicalPlan;
 use crate::optimizer::optimizer::OptimizerRule;
+use crate::optimizer::utils;
 use arrow::datatypes::{Field, Schema};
 use arrow::error::{ArrowError, Result};
 use std::collections::{HashMap, HashSet};
@@ -57,7 +58,7 @@ impl ProjectionPushDown {
                 schema,
             } => {
                 // collect all columns referenced by projection expressions
-                self.collect_exprs(&expr, accum);
+                utils::exprlist_to_column_indices(&expr, accum);
 
                 // push projection down
                 let input = self.optimize_plan(&input, accum, mapping)?;
@@ -73,7 +74,7 @@ impl ProjectionPushDown {
             }
             LogicalPlan::Selection { expr, input } => {
                 // collect all columns referenced by filter expression
-                self.collect_expr(expr, accum);
+                utils::expr_to_column_indices(expr, accum);
 
                 // push projection down
                 let input = self.optimize_plan(&input, accum, mapping)?;
@@ -93,8 +94,8 @@ impl ProjectionPushDown {
                 schema,
             } => {
                 // collect all columns referenced by grouping and aggregate expressions
-                self.collect_exprs(&group_expr, accum);
-                self.collect_exprs(&aggr_expr, accum);
+                utils::exprlist_to_column_indices(&group_expr, accum);
+                utils::exprlist_to_column_indices(&aggr_expr, accum);
 
                 // push projection down
                 let input = self.optimize_plan(&input, accum, mapping)?;
@@ -116,7 +117,7 @@ impl ProjectionPushDown {
                 schema,
             } => {
                 // collect all columns referenced by sort expressions
-                self.collect_exprs(&expr, accum);
+                utils::exprlist_to_column_indices(&expr, accum);
 
                 // push projection down
                 let input = self.optimize_plan(&input, accum, mapping)?;
@@ -131,14 +132,39 @@ impl ProjectionPushDown {
                 }))
             }
             LogicalPlan::EmptyRelation { schema } => {
+                // an empty relation has no columns to remove but the mapping still needs
+                // to be populated so that expressions can be rewritten
+                for i in 0..schema.fields().len() {
+                    mapping.insert(i, i);
+                }
+
                 Ok(Rc::new(LogicalPlan::EmptyRelation {
                     schema: schema.clone(),
                 }))
             }
+            LogicalPlan::Join { schema, .. } => {
+                // the columns of each input are numbered separately from the columns
+                // of the join, so the projection is not pushed into the inputs yet
+                for i in 0..schema.fields().len() {
+                    mapping.insert(i, i);
+                }
+
+                Ok(Rc::new(plan.clone()))
+            }
+            LogicalPlan::Values { schema, .. } => {
+                // literal rows are cheap so all of their columns are kept
+                for i in 0..schema.fields().len() {
+                    mapping.insert(i, i);
+                }
+
+                Ok(Rc::new(plan.clone()))
+            }
             LogicalPlan::TableScan {
                 schema_name,
                 table_name,
+                function_args,
                 schema,
+                limit,
                 ..
             } => {
                 // once we reach the table scan, we can use the accumulated set of column indexes as
@@ -149,6 +175,12 @@ impl ProjectionPushDown {
                 // sort the projection otherwise we get non-deterministic behavior
                 projection.sort();
 
+                // the scan has to produce at least one column, even if no columns are
+                // referenced (e.g. `SELECT COUNT(1)`), so that the number of rows is known
+                if projection.is_empty() {
+                    projection.push(0);
+                }
+
                 // create the projected schema
                 let mut projected_fields: Vec<Field> =
                     Vec::with_capacity(projection.len());
@@ -175,42 +207,54 @@ impl ProjectionPushDown {
                 Ok(Rc::new(LogicalPlan::TableScan {
                     schema_name: schema_name.to_string(),
                     table_name: table_name.to_string(),
+                    function_args: function_args.clone(),
                     schema: Arc::new(projected_schema),
                     projection: Some(projection),
+                    limit: limit.clone(),
                 }))
             }
             LogicalPlan::Limit {
                 expr,
                 input,
                 schema,
-            } => Ok(Rc::new(LogicalPlan::Limit {
-                expr: expr.clone(),
-                input: input.clone(),
-                schema: schema.clone(),
-            })),
-        }
-    }
-
-    fn collect_exprs(&self, expr: &Vec<Expr>, accum: &mut HashSet<usize>) {
-        expr.iter().for_each(|e| self.collect_expr(e, accum));
-    }
+            } => {
+                // a limit does not reference any columns so just push projection down
+                let input = self.optimize_plan(&input, accum, mapping)?;
 
-    fn collect_expr(&self, expr: &Expr, accum: &mut HashSet<usize>) {
-        match expr {
-            Expr::Column(i) => {
-                accum.insert(*i);
+                Ok(Rc::new(LogicalPlan::Limit {
+                    expr: expr.clone(),
+                    input,
+                    schema: schema.clone(),
+                }))
             }
-            Expr::Literal(_) => { /* not needed */ }
-            Expr::IsNull(e) => self.collect_expr(e, accum),
-            Expr::IsNotNull(e) => self.collect_expr(e, accum),
-            Expr::BinaryExpr { left, right, .. } => {
-                self.collect_expr(left, accum);
-                self.collect_expr(right, accum);
+            LogicalPlan::Unnest {
+                input,
+                column,
+                schema,
+            } => {
+                // the list column is needed to produce the rows even if its elements
+                // are not referenced
+                accum.insert(*column);
+
+                // push projection down
+                let input = self.optimize_plan(&input, accum, mapping)?;
+
+                // the unnest keeps the columns of its input in place, so its schema
+                // only contains the columns that are still produced by the input
+                let mut fields: Vec<(usize, Field)> = mapping
+                    .iter()
+                    .map(|(i, j)| (*j, schema.field(*i).clone()))
+                    .collect();
+                fields.sort_by_key(|(j, _)| *j);
+                let projected_schema =
+                    Schema::new(fields.into_iter().map(|(_, f)| f).collect());
+
+                Ok(Rc::new(LogicalPlan::Unnest {
+                    input,
+                    column: self.new_index(mapping, column)?,
+                    schema: Arc::new(projected_schema),
+                }))
             }
-            Expr::Cast { expr, .. } => self.collect_expr(expr, accum),
-            Expr::Sort { expr, .. } => self.collect_expr(expr, accum),
-            Expr::AggregateFunction { args, .. } => self.collect_exprs(args, accum),
-            Expr::ScalarFunction { args, .. } => self.collect_exprs(args, accum),
         }
     }
 
@@ -228,7 +272,16 @@ impl ProjectionPushDown {
     fn rewrite_expr(&self, expr: &Expr, mapping: &HashMap<usize, usize>) -> Result<Expr> {
         match expr {
             Expr::Column(i) => Ok(Expr::Column(self.new_index(mapping, i)?)),
+            Expr::UnresolvedColumn(name) => Err(ArrowError::ComputeError(format!(
+                "Unresolved column '{}' in projection push down",
+                name
+            ))),
             Expr::Literal(_) => Ok(expr.clone()),
+            Expr::Alias(e, alias) => Ok(Expr::Alias(
+                Rc::new(self.rewrite_expr(e, mapping)?),
+                alias.to_string(),
+            )),
+            Expr::Not(e) => Ok(Expr::Not(Rc::new(self.rewrite_expr(e, mapping)?))),
             Expr::IsNull(e) => Ok(Expr::IsNull(Rc::new(self.rewrite_expr(e, mapping)?))),
             Expr::IsNotNull(e) => {
                 Ok(Expr::IsNotNull(Rc::new(self.rewrite_expr(e, mapping)?)))
@@ -242,9 +295,18 @@ impl ProjectionPushDown {
                 expr: Rc::new(self.rewrite_expr(expr, mapping)?),
                 data_type: data_type.clone(),
             }),
-            Expr::Sort { expr, asc } => Ok(Expr::Sort {
+            Expr::Sort {
+                expr,
+                asc,
+                nulls_first,
+            } => Ok(Expr::Sort {
                 expr: Rc::new(self.rewrite_expr(expr, mapping)?),
                 asc: *asc,
+                nulls_first: *nulls_first,
+            }),
+            Expr::GetField { expr, name } => Ok(Expr::GetField {
+                expr: Rc::new(self.rewrite_expr(expr, mapping)?),
+                name: name.to_string(),
             }),
             Expr::AggregateFunction {
                 name,
@@ -283,6 +345,7 @@ mod tests {
     use super::*;
     use crate::logicalplan::Expr::*;
     use crate::logicalplan::LogicalPlan::*;
+    use crate::logicalplan::ScalarValue;
     use arrow::datatypes::{DataType, Field, Schema};
     use std::borrow::Borrow;
     use std::rc::Rc;
@@ -369,6 +432,32 @@ mod tests {
         );
     }
 
+    #[test]
+    fn limit_with_literal_projection() {
+        let table_scan = test_table_scan();
+
+        let projection = Projection {
+            expr: vec![Literal(ScalarValue::Int64(0))],
+            input: Rc::new(table_scan),
+            schema: Arc::new(Schema::new(vec![Field::new(
+                "lit",
+                DataType::Int64,
+                true,
+            )])),
+        };
+
+        let limit = Limit {
+            expr: Literal(ScalarValue::Int64(2)),
+            schema: projection.schema().clone(),
+            input: Rc::new(projection),
+        };
+
+        assert_optimized_plan_eq(
+            &limit,
+            "Limit: Int64(2)\n  Projection: Int64(0)\n    TableScan: test projection=Some([0])",
+        );
+    }
+
     #[test]
     fn table_scan_projected_schema() {
         let table_scan = test_table_scan();
@@ -413,12 +502,14 @@ mod tests {
         TableScan {
             schema_name: "default".to_string(),
             table_name: "test".to_string(),
+            function_args: None,
             schema: Arc::new(Schema::new(vec![
                 Field::new("a", DataType::UInt32, false),
                 Field::new("b", DataType::UInt32, false),
                 Field::new("c", DataType::UInt32, false),
             ])),
             projection: None,
+            limit: None,
         }
     }
 }
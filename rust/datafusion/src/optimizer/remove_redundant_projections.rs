@@ -0,0 +1,190 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Remove Redundant Projections optimizer rule removes projections that do not change
+//! their input
+
+use crate::logicalplan::{Expr, LogicalPlan};
+use crate::optimizer::optimizer::{OptimizerRule, PlanRewriter};
+use crate::optimizer::utils;
+use arrow::error::Result;
+use std::rc::Rc;
+
+/// Remove Redundant Projections optimizer rule replaces any projection that selects
+/// every column of its input, in order and with the same field names, with the input
+pub struct RemoveRedundantProjections {}
+
+impl OptimizerRule for RemoveRedundantProjections {
+    fn optimize(&mut self, plan: &LogicalPlan) -> Result<Rc<LogicalPlan>> {
+        utils::rewrite_plan(plan, self)
+    }
+}
+
+impl PlanRewriter for RemoveRedundantProjections {
+    fn mutate(&mut self, plan: Rc<LogicalPlan>) -> Result<Rc<LogicalPlan>> {
+        match plan.as_ref() {
+            LogicalPlan::Projection {
+                expr,
+                input,
+                schema,
+            } if is_identity(expr, input)
+                && schema.fields() == input.schema().fields() =>
+            {
+                Ok(input.clone())
+            }
+            _ => Ok(plan),
+        }
+    }
+}
+
+impl RemoveRedundantProjections {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+/// Determine whether a list of expressions is `#0, #1, ..., #n-1` where `n` is the
+/// number of columns in the input
+fn is_identity(expr: &Vec<Expr>, input: &LogicalPlan) -> bool {
+    expr.len() == input.schema().fields().len()
+        && expr.iter().enumerate().all(|(i, e)| match e {
+            Expr::Column(j) => i == *j,
+            _ => false,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::logicalplan::Expr::*;
+    use crate::logicalplan::LogicalPlan::*;
+    use crate::logicalplan::ScalarValue;
+    use arrow::datatypes::{DataType, Field, Schema};
+    use std::sync::Arc;
+
+    #[test]
+    fn remove_identity_projection() {
+        let projection = Projection {
+            expr: vec![Column(0), Column(1), Column(2)],
+            schema: test_table_scan().schema().clone(),
+            input: Rc::new(test_table_scan()),
+        };
+
+        let limit = Limit {
+            expr: Literal(ScalarValue::Int64(10)),
+            schema: projection.schema().clone(),
+            input: Rc::new(projection),
+        };
+
+        assert_optimized_plan_eq(
+            &limit,
+            "Limit: Int64(10)\n  TableScan: test projection=None",
+        );
+    }
+
+    #[test]
+    fn remove_nested_identity_projections() {
+        let projection = Projection {
+            expr: vec![Column(0), Column(1), Column(2)],
+            schema: test_table_scan().schema().clone(),
+            input: Rc::new(Projection {
+                expr: vec![Column(0), Column(1), Column(2)],
+                schema: test_table_scan().schema().clone(),
+                input: Rc::new(test_table_scan()),
+            }),
+        };
+
+        assert_optimized_plan_eq(&projection, "TableScan: test projection=None");
+    }
+
+    #[test]
+    fn keep_reordering_projection() {
+        let projection = Projection {
+            expr: vec![Column(1), Column(0), Column(2)],
+            schema: Arc::new(Schema::new(vec![
+                Field::new("b", DataType::UInt32, false),
+                Field::new("a", DataType::UInt32, false),
+                Field::new("c", DataType::UInt32, false),
+            ])),
+            input: Rc::new(test_table_scan()),
+        };
+
+        assert_optimized_plan_eq(
+            &projection,
+            "Projection: #1, #0, #2\n  TableScan: test projection=None",
+        );
+    }
+
+    #[test]
+    fn keep_partial_projection() {
+        let projection = Projection {
+            expr: vec![Column(0), Column(1)],
+            schema: Arc::new(Schema::new(vec![
+                Field::new("a", DataType::UInt32, false),
+                Field::new("b", DataType::UInt32, false),
+            ])),
+            input: Rc::new(test_table_scan()),
+        };
+
+        assert_optimized_plan_eq(
+            &projection,
+            "Projection: #0, #1\n  TableScan: test projection=None",
+        );
+    }
+
+    #[test]
+    fn keep_renaming_projection() {
+        let projection = Projection {
+            expr: vec![Column(0), Column(1), Column(2)],
+            schema: Arc::new(Schema::new(vec![
+                Field::new("x", DataType::UInt32, false),
+                Field::new("y", DataType::UInt32, false),
+                Field::new("z", DataType::UInt32, false),
+            ])),
+            input: Rc::new(test_table_scan()),
+        };
+
+        assert_optimized_plan_eq(
+            &projection,
+            "Projection: #0, #1, #2\n  TableScan: test projection=None",
+        );
+    }
+
+    fn assert_optimized_plan_eq(plan: &LogicalPlan, expected: &str) {
+        let mut rule = RemoveRedundantProjections::new();
+        let optimized_plan = rule.optimize(plan).unwrap();
+        let formatted_plan = format!("{:?}", optimized_plan);
+        assert_eq!(formatted_plan, expected);
+    }
+
+    /// all tests share a common table
+    fn test_table_scan() -> LogicalPlan {
+        TableScan {
+            schema_name: "default".to_string(),
+            table_name: "test".to_string(),
+            function_args: None,
+            schema: Arc::new(Schema::new(vec![
+                Field::new("a", DataType::UInt32, false),
+                Field::new("b", DataType::UInt32, false),
+                Field::new("c", DataType::UInt32, false),
+            ])),
+            projection: None,
+            limit: None,
+        }
+    }
+}
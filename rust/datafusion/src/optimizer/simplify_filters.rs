@@ -0,0 +1,344 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Simplify Filters optimizer rule removes redundant logic from selection predicates
+
+use crate::logicalplan::{Expr, LogicalPlan, Operator, ScalarValue};
+use crate::optimizer::optimizer::{OptimizerRule, PlanRewriter};
+use crate::optimizer::utils;
+use arrow::datatypes::DataType;
+use arrow::error::Result;
+use std::cmp::Ordering;
+use std::rc::Rc;
+
+/// Simplify Filters optimizer rule folds constant comparisons and boolean logic in
+/// selection predicates, removes selections that are always true, and replaces
+/// selections that are always false with an empty relation
+pub struct SimplifyFilters {}
+
+impl OptimizerRule for SimplifyFilters {
+    fn optimize(&mut self, plan: &LogicalPlan) -> Result<Rc<LogicalPlan>> {
+        utils::rewrite_plan(plan, self)
+    }
+}
+
+impl PlanRewriter for SimplifyFilters {
+    fn mutate(&mut self, plan: Rc<LogicalPlan>) -> Result<Rc<LogicalPlan>> {
+        match plan.as_ref() {
+            LogicalPlan::Selection { expr, input } => match simplify(expr) {
+                Expr::Literal(ScalarValue::Boolean(true)) => Ok(input.clone()),
+                Expr::Literal(ScalarValue::Boolean(false)) => {
+                    Ok(Rc::new(LogicalPlan::EmptyRelation {
+                        schema: input.schema().clone(),
+                    }))
+                }
+                expr => Ok(Rc::new(LogicalPlan::Selection {
+                    expr,
+                    input: input.clone(),
+                })),
+            },
+            _ => Ok(plan),
+        }
+    }
+}
+
+impl SimplifyFilters {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+/// Simplify an expression by folding constant comparisons, casts, and boolean logic
+pub fn simplify(expr: &Expr) -> Expr {
+    match expr {
+        Expr::BinaryExpr { left, op, right } => {
+            let left = simplify(left);
+            let right = simplify(right);
+            match (op, as_bool(&left), as_bool(&right)) {
+                // note that these rules also hold when the other side evaluates to null
+                (Operator::And, Some(false), _) | (Operator::And, _, Some(false)) => {
+                    lit_bool(false)
+                }
+                (Operator::And, Some(true), _) => right,
+                (Operator::And, _, Some(true)) => left,
+                (Operator::Or, Some(true), _) | (Operator::Or, _, Some(true)) => {
+                    lit_bool(true)
+                }
+                (Operator::Or, Some(false), _) => right,
+                (Operator::Or, _, Some(false)) => left,
+                _ => {
+                    let folded = match (&left, &right) {
+                        (Expr::Literal(l), Expr::Literal(r)) => fold_comparison(op, l, r),
+                        _ => None,
+                    };
+                    match folded {
+                        Some(b) => lit_bool(b),
+                        None => binary_expr(left, op, right),
+                    }
+                }
+            }
+        }
+        Expr::Not(e) => match simplify(e) {
+            Expr::Not(inner) => inner.as_ref().clone(),
+            Expr::Literal(ScalarValue::Boolean(b)) => lit_bool(!b),
+            e => Expr::Not(Rc::new(e)),
+        },
+        Expr::Cast { expr, data_type } => match simplify(expr) {
+            Expr::Literal(value) => match cast_scalar(&value, data_type) {
+                Some(value) => Expr::Literal(value),
+                None => Expr::Cast {
+                    expr: Rc::new(Expr::Literal(value)),
+                    data_type: data_type.clone(),
+                },
+            },
+            e => Expr::Cast {
+                expr: Rc::new(e),
+                data_type: data_type.clone(),
+            },
+        },
+        Expr::IsNull(e) => match simplify(e) {
+            Expr::Literal(ScalarValue::Null)
+            | Expr::Literal(ScalarValue::TypedNull(_)) => lit_bool(true),
+            Expr::Literal(_) => lit_bool(false),
+            e => Expr::IsNull(Rc::new(e)),
+        },
+        Expr::IsNotNull(e) => match simplify(e) {
+            Expr::Literal(ScalarValue::Null)
+            | Expr::Literal(ScalarValue::TypedNull(_)) => lit_bool(false),
+            Expr::Literal(_) => lit_bool(true),
+            e => Expr::IsNotNull(Rc::new(e)),
+        },
+        _ => expr.clone(),
+    }
+}
+
+fn as_bool(expr: &Expr) -> Option<bool> {
+    match expr {
+        Expr::Literal(ScalarValue::Boolean(b)) => Some(*b),
+        _ => None,
+    }
+}
+
+fn lit_bool(b: bool) -> Expr {
+    Expr::Literal(ScalarValue::Boolean(b))
+}
+
+fn binary_expr(left: Expr, op: &Operator, right: Expr) -> Expr {
+    Expr::BinaryExpr {
+        left: Rc::new(left),
+        op: op.clone(),
+        right: Rc::new(right),
+    }
+}
+
+/// Cast a numeric literal value to another numeric type
+pub fn cast_scalar(value: &ScalarValue, data_type: &DataType) -> Option<ScalarValue> {
+    macro_rules! cast_to {
+        ($VALUE:expr) => {{
+            match data_type {
+                DataType::Int8 => Some(ScalarValue::Int8($VALUE as i8)),
+                DataType::Int16 => Some(ScalarValue::Int16($VALUE as i16)),
+                DataType::Int32 => Some(ScalarValue::Int32($VALUE as i32)),
+                DataType::Int64 => Some(ScalarValue::Int64($VALUE as i64)),
+                DataType::UInt8 => Some(ScalarValue::UInt8($VALUE as u8)),
+                DataType::UInt16 => Some(ScalarValue::UInt16($VALUE as u16)),
+                DataType::UInt32 => Some(ScalarValue::UInt32($VALUE as u32)),
+                DataType::UInt64 => Some(ScalarValue::UInt64($VALUE as u64)),
+                DataType::Float32 => Some(ScalarValue::Float32($VALUE as f32)),
+                DataType::Float64 => Some(ScalarValue::Float64($VALUE as f64)),
+                _ => None,
+            }
+        }};
+    }
+
+    match value {
+        ScalarValue::Int8(v) => cast_to!(*v),
+        ScalarValue::Int16(v) => cast_to!(*v),
+        ScalarValue::Int32(v) => cast_to!(*v),
+        ScalarValue::Int64(v) => cast_to!(*v),
+        ScalarValue::UInt8(v) => cast_to!(*v),
+        ScalarValue::UInt16(v) => cast_to!(*v),
+        ScalarValue::UInt32(v) => cast_to!(*v),
+        ScalarValue::UInt64(v) => cast_to!(*v),
+        ScalarValue::Float32(v) => cast_to!(*v),
+        ScalarValue::Float64(v) => cast_to!(*v),
+        _ => None,
+    }
+}
+
+/// Evaluate a comparison between two literal values, returning `None` if the operator
+/// is not a comparison or the values cannot be compared
+fn fold_comparison(op: &Operator, l: &ScalarValue, r: &ScalarValue) -> Option<bool> {
+    let ordering = compare_scalars(l, r)?;
+    match op {
+        Operator::Eq => Some(ordering == Ordering::Equal),
+        Operator::NotEq => Some(ordering != Ordering::Equal),
+        Operator::Lt => Some(ordering == Ordering::Less),
+        Operator::LtEq => Some(ordering != Ordering::Greater),
+        Operator::Gt => Some(ordering == Ordering::Greater),
+        Operator::GtEq => Some(ordering != Ordering::Less),
+        _ => None,
+    }
+}
+
+/// Compare two scalar values of the same type
+pub fn compare_scalars(l: &ScalarValue, r: &ScalarValue) -> Option<Ordering> {
+    match (l, r) {
+        (ScalarValue::Boolean(l), ScalarValue::Boolean(r)) => l.partial_cmp(r),
+        (ScalarValue::Int8(l), ScalarValue::Int8(r)) => l.partial_cmp(r),
+        (ScalarValue::Int16(l), ScalarValue::Int16(r)) => l.partial_cmp(r),
+        (ScalarValue::Int32(l), ScalarValue::Int32(r)) => l.partial_cmp(r),
+        (ScalarValue::Int64(l), ScalarValue::Int64(r)) => l.partial_cmp(r),
+        (ScalarValue::UInt8(l), ScalarValue::UInt8(r)) => l.partial_cmp(r),
+        (ScalarValue::UInt16(l), ScalarValue::UInt16(r)) => l.partial_cmp(r),
+        (ScalarValue::UInt32(l), ScalarValue::UInt32(r)) => l.partial_cmp(r),
+        (ScalarValue::UInt64(l), ScalarValue::UInt64(r)) => l.partial_cmp(r),
+        (ScalarValue::Float32(l), ScalarValue::Float32(r)) => l.partial_cmp(r),
+        (ScalarValue::Float64(l), ScalarValue::Float64(r)) => l.partial_cmp(r),
+        (ScalarValue::Utf8(l), ScalarValue::Utf8(r)) => l.partial_cmp(r),
+        (ScalarValue::Date32(l), ScalarValue::Date32(r)) => l.partial_cmp(r),
+        (ScalarValue::Date64(l), ScalarValue::Date64(r)) => l.partial_cmp(r),
+        (ScalarValue::Time32(l, lu), ScalarValue::Time32(r, ru)) if lu == ru => {
+            l.partial_cmp(r)
+        }
+        (ScalarValue::Time64(l, lu), ScalarValue::Time64(r, ru)) if lu == ru => {
+            l.partial_cmp(r)
+        }
+        // timestamps are instants in UTC whatever their timezone, so only the units
+        // need to match
+        (ScalarValue::Timestamp(l, lu, _), ScalarValue::Timestamp(r, ru, _))
+            if lu == ru =>
+        {
+            l.partial_cmp(r)
+        }
+        (ScalarValue::Binary(l), ScalarValue::Binary(r)) => l.partial_cmp(r),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::logicalplan::Expr::*;
+    use crate::logicalplan::LogicalPlan::*;
+    use arrow::datatypes::{DataType, Field, Schema};
+    use std::sync::Arc;
+
+    #[test]
+    fn and_true() {
+        let selection = Selection {
+            expr: binary_expr(Column(0).gt(&lit(1)), &Operator::And, lit_bool(true)),
+            input: Rc::new(test_table_scan()),
+        };
+
+        assert_optimized_plan_eq(
+            &selection,
+            "Selection: #0 Gt Int64(1)\n  TableScan: test projection=None",
+        );
+    }
+
+    #[test]
+    fn always_true_selection_is_removed() {
+        let selection = Selection {
+            expr: binary_expr(Column(0).gt(&lit(1)), &Operator::Or, lit(1).eq(&lit(1))),
+            input: Rc::new(test_table_scan()),
+        };
+
+        assert_optimized_plan_eq(&selection, "TableScan: test projection=None");
+    }
+
+    #[test]
+    fn always_false_selection_is_replaced() {
+        let selection = Selection {
+            expr: binary_expr(Column(0).gt(&lit(1)), &Operator::And, lit(1).eq(&lit(2))),
+            input: Rc::new(test_table_scan()),
+        };
+
+        let optimized_plan = optimize(&selection);
+        assert_eq!("EmptyRelation", format!("{:?}", optimized_plan));
+        assert_eq!(3, optimized_plan.schema().fields().len());
+    }
+
+    #[test]
+    fn double_negation() {
+        let selection = Selection {
+            expr: Not(Rc::new(Not(Rc::new(Column(0).lt_eq(&lit(5)))))),
+            input: Rc::new(test_table_scan()),
+        };
+
+        assert_optimized_plan_eq(
+            &selection,
+            "Selection: #0 LtEq Int64(5)\n  TableScan: test projection=None",
+        );
+    }
+
+    #[test]
+    fn cast_literal() {
+        let selection = Selection {
+            expr: Cast {
+                expr: Rc::new(Literal(ScalarValue::Int32(7))),
+                data_type: DataType::Int64,
+            }
+            .gt(&lit(10)),
+            input: Rc::new(test_table_scan()),
+        };
+
+        assert_optimized_plan_eq(&selection, "EmptyRelation");
+    }
+
+    #[test]
+    fn not_literal() {
+        let selection = Selection {
+            expr: Not(Rc::new(lit(3).gt(&lit(2)))),
+            input: Rc::new(test_table_scan()),
+        };
+
+        assert_optimized_plan_eq(&selection, "EmptyRelation");
+    }
+
+    fn assert_optimized_plan_eq(plan: &LogicalPlan, expected: &str) {
+        let optimized_plan = optimize(plan);
+        let formatted_plan = format!("{:?}", optimized_plan);
+        assert_eq!(formatted_plan, expected);
+    }
+
+    fn optimize(plan: &LogicalPlan) -> Rc<LogicalPlan> {
+        let mut rule = SimplifyFilters::new();
+        rule.optimize(plan).unwrap()
+    }
+
+    fn lit(n: i64) -> Expr {
+        Literal(ScalarValue::Int64(n))
+    }
+
+    /// all tests share a common table
+    fn test_table_scan() -> LogicalPlan {
+        TableScan {
+            schema_name: "default".to_string(),
+            table_name: "test".to_string(),
+            function_args: None,
+            schema: Arc::new(Schema::new(vec![
+                Field::new("a", DataType::Int64, false),
+                Field::new("b", DataType::Int64, false),
+                Field::new("c", DataType::Int64, false),
+            ])),
+            projection: None,
+            limit: None,
+        }
+    }
+}
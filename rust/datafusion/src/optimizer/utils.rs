@@ -0,0 +1,692 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Collection of utility functions that are leveraged by the query optimizer rules
+
+use std::collections::HashSet;
+use std::rc::Rc;
+
+use arrow::datatypes::{DataType, Schema};
+use arrow::error::{ArrowError, Result};
+
+use crate::logicalplan::{get_supertype, Expr, ExprVisitor, LogicalPlan, Operator};
+use crate::optimizer::optimizer::{ExprRewriter, PlanRewriter};
+
+/// Recursively walk a list of expression trees, collecting the unique set of column
+/// indexes referenced in the expression
+pub fn exprlist_to_column_indices(expr: &Vec<Expr>, accum: &mut HashSet<usize>) {
+    expr.iter().for_each(|e| expr_to_column_indices(e, accum));
+}
+
+/// Recursively walk an expression tree, collecting the unique set of column indexes
+/// referenced in the expression
+pub fn expr_to_column_indices(expr: &Expr, accum: &mut HashSet<usize>) {
+    struct ColumnCollector<'a> {
+        accum: &'a mut HashSet<usize>,
+    }
+
+    impl<'a> ExprVisitor for ColumnCollector<'a> {
+        fn pre_visit(&mut self, expr: &Expr) -> bool {
+            if let Expr::Column(i) = expr {
+                self.accum.insert(*i);
+            }
+            true
+        }
+    }
+
+    expr.accept(&mut ColumnCollector { accum });
+}
+
+/// Returns the direct children of an expression
+pub fn expr_sub_expressions(expr: &Expr) -> Vec<&Expr> {
+    match expr {
+        Expr::BinaryExpr { left, right, .. } => vec![left.as_ref(), right.as_ref()],
+        Expr::Alias(e, _) | Expr::Not(e) | Expr::IsNull(e) | Expr::IsNotNull(e) => {
+            vec![e.as_ref()]
+        }
+        Expr::Cast { expr, .. }
+        | Expr::Sort { expr, .. }
+        | Expr::GetField { expr, .. } => vec![expr.as_ref()],
+        Expr::ScalarFunction { args, .. } | Expr::AggregateFunction { args, .. } => {
+            args.iter().collect()
+        }
+        Expr::Column(_) | Expr::UnresolvedColumn(_) | Expr::Literal(_) => vec![],
+    }
+}
+
+/// Determine whether an expression contains an aggregate function
+pub fn contains_aggregate(expr: &Expr) -> bool {
+    match expr {
+        Expr::AggregateFunction { .. } => true,
+        _ => expr_sub_expressions(expr)
+            .into_iter()
+            .any(contains_aggregate),
+    }
+}
+
+/// Returns a copy of an expression with its children replaced by `expressions`, which
+/// must be in the same order as returned by `expr_sub_expressions`
+pub fn rewrite_expression(expr: &Expr, expressions: &Vec<Expr>) -> Result<Expr> {
+    let num_children = expr_sub_expressions(expr).len();
+    if expressions.len() != num_children {
+        return Err(ArrowError::ComputeError(format!(
+            "Expression {:?} has {} children but {} were provided",
+            expr,
+            num_children,
+            expressions.len()
+        )));
+    }
+    match expr {
+        Expr::BinaryExpr { op, .. } => Ok(Expr::BinaryExpr {
+            left: Rc::new(expressions[0].clone()),
+            op: op.clone(),
+            right: Rc::new(expressions[1].clone()),
+        }),
+        Expr::Alias(_, alias) => Ok(Expr::Alias(
+            Rc::new(expressions[0].clone()),
+            alias.to_string(),
+        )),
+        Expr::Not(_) => Ok(Expr::Not(Rc::new(expressions[0].clone()))),
+        Expr::IsNull(_) => Ok(Expr::IsNull(Rc::new(expressions[0].clone()))),
+        Expr::IsNotNull(_) => Ok(Expr::IsNotNull(Rc::new(expressions[0].clone()))),
+        Expr::Cast { data_type, .. } => Ok(Expr::Cast {
+            expr: Rc::new(expressions[0].clone()),
+            data_type: data_type.clone(),
+        }),
+        Expr::Sort {
+            asc, nulls_first, ..
+        } => Ok(Expr::Sort {
+            expr: Rc::new(expressions[0].clone()),
+            asc: *asc,
+            nulls_first: *nulls_first,
+        }),
+        Expr::GetField { name, .. } => Ok(Expr::GetField {
+            expr: Rc::new(expressions[0].clone()),
+            name: name.to_string(),
+        }),
+        Expr::ScalarFunction {
+            name, return_type, ..
+        } => Ok(Expr::ScalarFunction {
+            name: name.to_string(),
+            args: expressions.clone(),
+            return_type: return_type.clone(),
+        }),
+        Expr::AggregateFunction {
+            name, return_type, ..
+        } => Ok(Expr::AggregateFunction {
+            name: name.to_string(),
+            args: expressions.clone(),
+            return_type: return_type.clone(),
+        }),
+        Expr::Column(_) | Expr::UnresolvedColumn(_) | Expr::Literal(_) => {
+            Ok(expr.clone())
+        }
+    }
+}
+
+/// Rewrite an expression tree from the bottom up by calling `rewriter.mutate` on each
+/// expression after its children have been rewritten
+pub fn rewrite_expr<R: ExprRewriter>(expr: &Expr, rewriter: &mut R) -> Result<Expr> {
+    let expr = if rewriter.pre_visit(expr)? {
+        let children = expr_sub_expressions(expr)
+            .into_iter()
+            .map(|e| rewrite_expr(e, rewriter))
+            .collect::<Result<Vec<Expr>>>()?;
+        rewrite_expression(expr, &children)?
+    } else {
+        expr.clone()
+    };
+    rewriter.mutate(expr)
+}
+
+/// Returns the inputs of a logical plan
+pub fn inputs(plan: &LogicalPlan) -> Vec<&Rc<LogicalPlan>> {
+    match plan {
+        LogicalPlan::Projection { input, .. }
+        | LogicalPlan::Selection { input, .. }
+        | LogicalPlan::Aggregate { input, .. }
+        | LogicalPlan::Sort { input, .. }
+        | LogicalPlan::Limit { input, .. }
+        | LogicalPlan::Unnest { input, .. } => vec![input],
+        LogicalPlan::Join { left, right, .. } => vec![left, right],
+        LogicalPlan::TableScan { .. }
+        | LogicalPlan::EmptyRelation { .. }
+        | LogicalPlan::Values { .. } => vec![],
+    }
+}
+
+/// Returns a copy of a logical plan with its inputs replaced by `inputs`, which must be
+/// in the same order as returned by `inputs`
+pub fn with_new_inputs(
+    plan: &LogicalPlan,
+    inputs: &Vec<Rc<LogicalPlan>>,
+) -> Result<LogicalPlan> {
+    let num_inputs = self::inputs(plan).len();
+    if inputs.len() != num_inputs {
+        return Err(ArrowError::ComputeError(format!(
+            "Plan {:?} has {} inputs but {} were provided",
+            plan,
+            num_inputs,
+            inputs.len()
+        )));
+    }
+    match plan {
+        LogicalPlan::Projection { expr, schema, .. } => Ok(LogicalPlan::Projection {
+            expr: expr.clone(),
+            input: inputs[0].clone(),
+            schema: schema.clone(),
+        }),
+        LogicalPlan::Selection { expr, .. } => Ok(LogicalPlan::Selection {
+            expr: expr.clone(),
+            input: inputs[0].clone(),
+        }),
+        LogicalPlan::Aggregate {
+            group_expr,
+            aggr_expr,
+            schema,
+            ..
+        } => Ok(LogicalPlan::Aggregate {
+            input: inputs[0].clone(),
+            group_expr: group_expr.clone(),
+            aggr_expr: aggr_expr.clone(),
+            schema: schema.clone(),
+        }),
+        LogicalPlan::Sort { expr, schema, .. } => Ok(LogicalPlan::Sort {
+            expr: expr.clone(),
+            input: inputs[0].clone(),
+            schema: schema.clone(),
+        }),
+        LogicalPlan::Limit { expr, schema, .. } => Ok(LogicalPlan::Limit {
+            expr: expr.clone(),
+            input: inputs[0].clone(),
+            schema: schema.clone(),
+        }),
+        LogicalPlan::Join {
+            join_type,
+            on,
+            filter,
+            schema,
+            ..
+        } => Ok(LogicalPlan::Join {
+            left: inputs[0].clone(),
+            right: inputs[1].clone(),
+            join_type: *join_type,
+            on: on.clone(),
+            filter: filter.clone(),
+            schema: schema.clone(),
+        }),
+        LogicalPlan::Unnest { column, schema, .. } => Ok(LogicalPlan::Unnest {
+            input: inputs[0].clone(),
+            column: *column,
+            schema: schema.clone(),
+        }),
+        LogicalPlan::TableScan { .. }
+        | LogicalPlan::EmptyRelation { .. }
+        | LogicalPlan::Values { .. } => Ok(plan.clone()),
+    }
+}
+
+/// Rewrite a logical plan from the bottom up by calling `rewriter.mutate` on each plan
+/// after its inputs have been rewritten
+pub fn rewrite_plan<R: PlanRewriter>(
+    plan: &LogicalPlan,
+    rewriter: &mut R,
+) -> Result<Rc<LogicalPlan>> {
+    let plan = if rewriter.pre_visit(plan)? {
+        let new_inputs = inputs(plan)
+            .into_iter()
+            .map(|input| rewrite_plan(input, rewriter))
+            .collect::<Result<Vec<Rc<LogicalPlan>>>>()?;
+        Rc::new(with_new_inputs(plan, &new_inputs)?)
+    } else {
+        Rc::new(plan.clone())
+    };
+    rewriter.mutate(plan)
+}
+
+/// Split a predicate into its conjuncts, so that `a AND b AND c` becomes `[a, b, c]`
+pub fn split_conjunction(expr: &Expr, accum: &mut Vec<Expr>) {
+    match expr {
+        Expr::BinaryExpr {
+            left,
+            op: Operator::And,
+            right,
+        } => {
+            split_conjunction(left, accum);
+            split_conjunction(right, accum);
+        }
+        other => accum.push(other.clone()),
+    }
+}
+
+/// Combine a list of predicates into a single predicate by AND-ing them together,
+/// returning `None` if the list is empty
+pub fn combine_conjunction(predicates: Vec<Expr>) -> Option<Expr> {
+    predicates
+        .into_iter()
+        .fold(None, |acc, predicate| match acc {
+            Some(left) => Some(Expr::BinaryExpr {
+                left: Rc::new(left),
+                op: Operator::And,
+                right: Rc::new(predicate),
+            }),
+            None => Some(predicate),
+        })
+}
+
+/// Rewrite an expression by replacing every column reference `#i` with the expression
+/// `input_expr[i]`. This is used to move an expression below a relation that computes
+/// its output columns from `input_expr`, such as a projection.
+pub fn replace_columns(expr: &Expr, input_expr: &Vec<Expr>) -> Result<Expr> {
+    struct ColumnReplacer<'a> {
+        input_expr: &'a Vec<Expr>,
+    }
+
+    impl<'a> ExprRewriter for ColumnReplacer<'a> {
+        fn mutate(&mut self, expr: Expr) -> Result<Expr> {
+            match expr {
+                Expr::Column(i) => match self.input_expr.get(i) {
+                    // the alias only names the output column of the input relation
+                    Some(Expr::Alias(e, _)) => Ok(e.as_ref().clone()),
+                    Some(e) => Ok(e.clone()),
+                    None => Err(ArrowError::ComputeError(format!(
+                        "Invalid column index {} when replacing columns",
+                        i
+                    ))),
+                },
+                Expr::UnresolvedColumn(ref name) => Err(ArrowError::ComputeError(
+                    format!("Unresolved column '{}' when replacing columns", name),
+                )),
+                _ => Ok(expr),
+            }
+        }
+    }
+
+    rewrite_expr(expr, &mut ColumnReplacer { input_expr })
+}
+
+/// Rewrite an expression by replacing every column that is referenced by name with the
+/// index of that column in `schema`
+pub fn resolve_columns(expr: &Expr, schema: &Schema) -> Result<Expr> {
+    struct ColumnResolver<'a> {
+        schema: &'a Schema,
+    }
+
+    impl<'a> ExprRewriter for ColumnResolver<'a> {
+        fn mutate(&mut self, expr: Expr) -> Result<Expr> {
+            match expr {
+                Expr::UnresolvedColumn(ref name) => {
+                    match self.schema.column_with_name(name) {
+                        Some((i, _)) => Ok(Expr::Column(i)),
+                        None => {
+                            let candidates: Vec<String> = self
+                                .schema
+                                .fields()
+                                .iter()
+                                .map(|f| f.name().clone())
+                                .collect();
+                            Err(ArrowError::ComputeError(unknown_column_message(
+                                name,
+                                &candidates,
+                            )))
+                        }
+                    }
+                }
+                _ => Ok(expr),
+            }
+        }
+    }
+
+    rewrite_expr(expr, &mut ColumnResolver { schema })
+}
+
+/// Describe a column name that could not be resolved, suggesting the candidate names
+/// that are most similar to it, or listing all candidates if none are similar
+pub fn unknown_column_message(name: &str, candidates: &[String]) -> String {
+    let similar = similar_names(name, candidates);
+    let quoted = |names: &[&String]| {
+        names
+            .iter()
+            .map(|n| format!("'{}'", n))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    match similar.len() {
+        0 if candidates.is_empty() => {
+            format!("Invalid identifier '{}'. The input has no columns", name)
+        }
+        0 => {
+            let all: Vec<&String> = candidates.iter().collect();
+            format!(
+                "Invalid identifier '{}'. Valid columns are {}",
+                name,
+                quoted(&all)
+            )
+        }
+        1 => format!(
+            "Invalid identifier '{}'. Did you mean {}?",
+            name,
+            quoted(&similar)
+        ),
+        _ => format!(
+            "Invalid identifier '{}'. Did you mean one of {}?",
+            name,
+            quoted(&similar)
+        ),
+    }
+}
+
+/// Find the candidate names that are similar to a name, closest first. Names are
+/// compared without regard to case, and are similar if they are within an edit
+/// distance of a third of the length of the name.
+pub fn similar_names<'a>(name: &str, candidates: &'a [String]) -> Vec<&'a String> {
+    let name = name.to_lowercase();
+    let max_distance = (name.chars().count() / 3).max(1);
+    let mut similar: Vec<(usize, &String)> = candidates
+        .iter()
+        .map(|c| (edit_distance(&name, &c.to_lowercase()), c))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .collect();
+    similar.sort_by_key(|(distance, _)| *distance);
+    let mut names: Vec<&String> = vec![];
+    for (_, c) in similar {
+        if !names.contains(&c) {
+            names.push(c);
+        }
+    }
+    names
+}
+
+/// Compute the Levenshtein distance between two strings
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + if ca == *cb { 0 } else { 1 };
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Rewrite an expression so that both sides of every binary expression have the same
+/// type, by casting them to their common supertype. Numeric literals are converted
+/// to the supertype directly.
+pub fn coerce_binary_exprs(expr: &Expr, schema: &Schema) -> Result<Expr> {
+    struct BinaryExprCoercion<'a> {
+        schema: &'a Schema,
+    }
+
+    impl<'a> BinaryExprCoercion<'a> {
+        fn cast(&self, expr: &Expr, data_type: &DataType) -> Result<Expr> {
+            if let Expr::Literal(value) = expr {
+                if let Some(value) = value.cast_to(data_type) {
+                    return Ok(Expr::Literal(value));
+                }
+            }
+            expr.cast_to(data_type, self.schema)
+                .map_err(ArrowError::ComputeError)
+        }
+    }
+
+    impl<'a> ExprRewriter for BinaryExprCoercion<'a> {
+        fn mutate(&mut self, expr: Expr) -> Result<Expr> {
+            match expr {
+                Expr::BinaryExpr {
+                    ref left,
+                    ref op,
+                    ref right,
+                } => {
+                    let left_type = left.get_type(self.schema);
+                    let right_type = right.get_type(self.schema);
+                    if left_type == right_type {
+                        return Ok(expr.clone());
+                    }
+                    match get_supertype(&left_type, &right_type) {
+                        Some(supertype) => Ok(Expr::BinaryExpr {
+                            left: Rc::new(self.cast(left, &supertype)?),
+                            op: op.clone(),
+                            right: Rc::new(self.cast(right, &supertype)?),
+                        }),
+                        None => Err(ArrowError::ComputeError(format!(
+                            "No common supertype found for binary operator {:?} \
+                             with input types {:?} and {:?}",
+                            op, left_type, right_type
+                        ))),
+                    }
+                }
+                _ => Ok(expr),
+            }
+        }
+    }
+
+    rewrite_expr(expr, &mut BinaryExprCoercion { schema })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logicalplan::{col, ScalarValue};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use std::sync::Arc;
+
+    #[test]
+    fn split_and_combine_conjunction() {
+        let a = Expr::Column(0).gt(&Expr::Literal(ScalarValue::Int64(1)));
+        let b = Expr::Column(1).eq(&Expr::Literal(ScalarValue::Int64(2)));
+        let c = Expr::IsNotNull(Rc::new(Expr::Column(2)));
+
+        let predicate = combine_conjunction(vec![a.clone(), b.clone(), c.clone()]);
+        assert_eq!(
+            "#0 Gt Int64(1) And #1 Eq Int64(2) And #2 IS NOT NULL",
+            format!("{:?}", predicate.clone().unwrap())
+        );
+
+        let mut conjuncts = vec![];
+        split_conjunction(&predicate.unwrap(), &mut conjuncts);
+        assert_eq!(vec![a, b, c], conjuncts);
+    }
+
+    #[test]
+    fn combine_empty_conjunction() {
+        assert!(combine_conjunction(vec![]).is_none());
+    }
+
+    #[test]
+    fn replace_column_references() {
+        let expr = Expr::Column(1).lt(&Expr::Column(0));
+        let input_expr = vec![
+            Expr::Literal(ScalarValue::Int64(10)),
+            Expr::Cast {
+                expr: Rc::new(Expr::Column(3)),
+                data_type: arrow::datatypes::DataType::Int64,
+            },
+        ];
+        let rewritten = replace_columns(&expr, &input_expr).unwrap();
+        assert_eq!("CAST(#3 AS Int64) Lt Int64(10)", format!("{:?}", rewritten));
+
+        let mut accum = HashSet::new();
+        expr_to_column_indices(&rewritten, &mut accum);
+        assert_eq!(1, accum.len());
+        assert!(accum.contains(&3));
+    }
+
+    #[test]
+    fn rewrite_sub_expressions() {
+        let expr = Expr::Column(0).gt(&Expr::Literal(ScalarValue::Int64(1)));
+        let children: Vec<Expr> = expr_sub_expressions(&expr)
+            .into_iter()
+            .map(|e| match e {
+                Expr::Column(_) => Expr::Column(5),
+                other => other.clone(),
+            })
+            .collect();
+        let rewritten = rewrite_expression(&expr, &children).unwrap();
+        assert_eq!("#5 Gt Int64(1)", format!("{:?}", rewritten));
+
+        assert!(rewrite_expression(&expr, &vec![]).is_err());
+    }
+
+    #[test]
+    fn rewrite_expr_bottom_up() {
+        /// Replaces every column reference with a literal and records the order in
+        /// which expressions were mutated
+        struct ColumnToLiteral {
+            visited: Vec<String>,
+        }
+
+        impl ExprRewriter for ColumnToLiteral {
+            fn mutate(&mut self, expr: Expr) -> Result<Expr> {
+                self.visited.push(format!("{:?}", expr));
+                match expr {
+                    Expr::Column(i) => Ok(Expr::Literal(ScalarValue::Int64(i as i64))),
+                    other => Ok(other),
+                }
+            }
+        }
+
+        let expr = Expr::Column(3).gt(&Expr::Literal(ScalarValue::Int64(1)));
+        let mut rewriter = ColumnToLiteral { visited: vec![] };
+        let rewritten = rewrite_expr(&expr, &mut rewriter).unwrap();
+
+        assert_eq!("Int64(3) Gt Int64(1)", format!("{:?}", rewritten));
+        assert_eq!(
+            vec!["#3", "Int64(1)", "Int64(3) Gt Int64(1)"],
+            rewriter.visited
+        );
+    }
+
+    #[test]
+    fn rewrite_expr_skip_children() {
+        struct SkipNot {}
+
+        impl ExprRewriter for SkipNot {
+            fn pre_visit(&mut self, expr: &Expr) -> Result<bool> {
+                match expr {
+                    Expr::Not(_) => Ok(false),
+                    _ => Ok(true),
+                }
+            }
+
+            fn mutate(&mut self, expr: Expr) -> Result<Expr> {
+                match expr {
+                    Expr::Column(_) => Ok(Expr::Column(0)),
+                    other => Ok(other),
+                }
+            }
+        }
+
+        let expr = Expr::BinaryExpr {
+            left: Rc::new(Expr::Not(Rc::new(Expr::Column(1)))),
+            op: Operator::And,
+            right: Rc::new(Expr::Column(2)),
+        };
+        let rewritten = rewrite_expr(&expr, &mut SkipNot {}).unwrap();
+        assert_eq!("NOT #1 And #0", format!("{:?}", rewritten));
+    }
+
+    #[test]
+    fn rewrite_plan_bottom_up() {
+        /// Replaces table scans with empty relations
+        struct ScanToEmpty {}
+
+        impl PlanRewriter for ScanToEmpty {
+            fn mutate(&mut self, plan: Rc<LogicalPlan>) -> Result<Rc<LogicalPlan>> {
+                match plan.as_ref() {
+                    LogicalPlan::TableScan { schema, .. } => {
+                        Ok(Rc::new(LogicalPlan::EmptyRelation {
+                            schema: schema.clone(),
+                        }))
+                    }
+                    _ => Ok(plan),
+                }
+            }
+        }
+
+        let schema =
+            Arc::new(Schema::new(vec![Field::new("a", DataType::UInt32, false)]));
+        let plan = LogicalPlan::Selection {
+            expr: Expr::Column(0).gt(&Expr::Literal(ScalarValue::UInt32(1))),
+            input: Rc::new(LogicalPlan::TableScan {
+                schema_name: "default".to_string(),
+                table_name: "test".to_string(),
+                function_args: None,
+                schema,
+                projection: None,
+                limit: None,
+            }),
+        };
+
+        let rewritten = rewrite_plan(&plan, &mut ScanToEmpty {}).unwrap();
+        assert_eq!(
+            "Selection: #0 Gt UInt32(1)\n  EmptyRelation",
+            format!("{:?}", rewritten)
+        );
+    }
+
+    #[test]
+    fn replace_invalid_column_reference() {
+        assert!(replace_columns(&Expr::Column(2), &vec![]).is_err());
+    }
+
+    #[test]
+    fn resolve_column_names() {
+        let schema = Schema::new(vec![
+            Field::new("a", DataType::UInt32, false),
+            Field::new("b", DataType::Utf8, false),
+        ]);
+        let expr = col("b").eq(&col("a"));
+        let resolved = resolve_columns(&expr, &schema).unwrap();
+        assert_eq!("#1 Eq #0", format!("{:?}", resolved));
+
+        assert!(resolve_columns(&col("c"), &schema).is_err());
+    }
+
+    #[test]
+    fn suggest_similar_column_names() {
+        let candidates: Vec<String> = vec!["customer_id", "customer_name", "order_id"]
+            .into_iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(
+            vec!["customer_id"],
+            similar_names("Customer_ID", &candidates)
+        );
+        assert_eq!(
+            vec!["customer_id"],
+            similar_names("customerid", &candidates)
+        );
+        assert!(similar_names("price", &candidates).is_empty());
+
+        assert_eq!(
+            "Invalid identifier 'ordr_id'. Did you mean 'order_id'?",
+            unknown_column_message("ordr_id", &candidates)
+        );
+        assert_eq!(
+            "Invalid identifier 'price'. Valid columns are 'customer_id', \
+             'customer_name', 'order_id'",
+            unknown_column_message("price", &candidates)
+        );
+        assert_eq!(
+            "Invalid identifier 'price'. The input has no columns",
+            unknown_column_message("price", &[])
+        );
+    }
+}
@@ -22,26 +22,221 @@ use std::rc::Rc;
 use std::string::String;
 use std::sync::Arc;
 
+use super::execution::binary::decode_hex;
+use super::execution::catalog::DEFAULT_SCHEMA;
+use super::execution::datasource::Statistics;
 use super::execution::error::*;
+use super::execution::functions::aggregate_function;
+use super::execution::list::{list_function, ListFunction};
 use super::logicalplan::*;
+use super::optimizer::utils;
 
 use arrow::datatypes::*;
 
 use sqlparser::sqlast::*;
 
-pub trait SchemaProvider {
+/// Provides the schemas of tables and the metadata of functions to the query planner.
+/// Table names may be qualified by the name of the schema the table belongs to, such as
+/// `analytics.events`.
+pub trait SchemaProvider: Send + Sync {
     fn get_table_meta(&self, name: &str) -> Option<Arc<Schema>>;
     fn get_function_meta(&self, name: &str) -> Option<Arc<FunctionMeta>>;
+
+    /// Get statistics for a table, for use by the query optimizer
+    fn get_table_statistics(&self, _name: &str) -> Option<Statistics> {
+        None
+    }
+
+    /// Get the schema of the table produced by calling a table function with the given
+    /// argument values. Returns `None` if there is no table function with this name.
+    fn get_table_function_meta(
+        &self,
+        _name: &str,
+        _args: &[ScalarValue],
+    ) -> Option<Result<Arc<Schema>>> {
+        None
+    }
+}
+
+/// A schema in which each field may be qualified by the name of the relation that it
+/// belongs to, so that columns with the same name from different relations, such as
+/// `a.id` and `b.id` in a join, can be told apart
+#[derive(Debug, Clone)]
+pub struct QualifiedSchema {
+    schema: Arc<Schema>,
+    qualifiers: Vec<Option<String>>,
+}
+
+impl QualifiedSchema {
+    /// Create a schema with one (optional) qualifier per field
+    pub fn new(schema: Arc<Schema>, qualifiers: Vec<Option<String>>) -> Result<Self> {
+        if schema.fields().len() != qualifiers.len() {
+            return Err(ExecutionError::InternalError(format!(
+                "Schema has {} fields but {} qualifiers were provided",
+                schema.fields().len(),
+                qualifiers.len()
+            )));
+        }
+        Ok(QualifiedSchema { schema, qualifiers })
+    }
+
+    /// Create a schema in which no field is qualified
+    pub fn unqualified(schema: Arc<Schema>) -> Self {
+        let qualifiers = vec![None; schema.fields().len()];
+        QualifiedSchema { schema, qualifiers }
+    }
+
+    /// Create a schema in which every field is qualified by the given relation name
+    pub fn qualified(relation: &str, schema: Arc<Schema>) -> Self {
+        let qualifiers = vec![Some(relation.to_string()); schema.fields().len()];
+        QualifiedSchema { schema, qualifiers }
+    }
+
+    /// Combine the schemas of the two inputs of a join, keeping their qualifiers
+    pub fn join(&self, right: &QualifiedSchema, join_type: JoinType) -> Self {
+        let mut qualifiers = self.qualifiers.clone();
+        qualifiers.extend(right.qualifiers.iter().cloned());
+        QualifiedSchema {
+            schema: Arc::new(join_schema(&self.schema, &right.schema, join_type)),
+            qualifiers,
+        }
+    }
+
+    /// Replace the underlying schema with one that has the same number of fields,
+    /// keeping the qualifiers
+    pub fn with_schema(&self, schema: Arc<Schema>) -> Self {
+        QualifiedSchema {
+            schema,
+            qualifiers: self.qualifiers.clone(),
+        }
+    }
+
+    /// Get the underlying schema
+    pub fn schema(&self) -> &Arc<Schema> {
+        &self.schema
+    }
+
+    /// Get the qualifier of the field at the given index
+    pub fn qualifier(&self, i: usize) -> Option<&str> {
+        self.qualifiers[i].as_ref().map(|q| q.as_str())
+    }
+
+    /// Get the name of the field at the given index, qualified by its relation name
+    /// if another field has the same name
+    pub fn field_name(&self, i: usize) -> String {
+        let name = self.schema.field(i).name();
+        let duplicated = self
+            .schema
+            .fields()
+            .iter()
+            .enumerate()
+            .any(|(j, f)| j != i && f.name() == name);
+        match self.qualifier(i) {
+            Some(qualifier) if duplicated => format!("{}.{}", qualifier, name),
+            _ => name.clone(),
+        }
+    }
+
+    /// Find the index of a field by name, optionally qualified by a relation name. An
+    /// unqualified name is ambiguous if it matches fields from more than one relation.
+    pub fn index_of(&self, qualifier: Option<&str>, name: &str) -> Result<usize> {
+        let matches: Vec<usize> = self
+            .schema
+            .fields()
+            .iter()
+            .enumerate()
+            .filter(|(i, f)| {
+                f.name() == name
+                    && (qualifier.is_none() || self.qualifier(*i) == qualifier)
+            })
+            .map(|(i, _)| i)
+            .collect();
+
+        match matches.first() {
+            Some(&index) => {
+                let mut relations: Vec<&str> =
+                    matches.iter().filter_map(|i| self.qualifier(*i)).collect();
+                relations.sort();
+                relations.dedup();
+                if matches
+                    .iter()
+                    .any(|i| self.qualifier(*i) != self.qualifier(index))
+                {
+                    let references: Vec<String> = relations
+                        .iter()
+                        .map(|r| format!("'{}.{}'", r, name))
+                        .collect();
+                    Err(ExecutionError::plan(
+                        format!(
+                            "Ambiguous reference to column '{}', which could refer to {}",
+                            name,
+                            references.join(" or ")
+                        ),
+                        name,
+                    ))
+                } else {
+                    Ok(index)
+                }
+            }
+            None => {
+                // suggest the columns of the relation that the name is qualified by, or
+                // of all relations if no such relation exists
+                let name = match qualifier {
+                    Some(qualifier) => format!("{}.{}", qualifier, name),
+                    None => name.to_string(),
+                };
+                let qualified_name = |i: usize| match self.qualifier(i) {
+                    Some(q) if qualifier.is_some() => {
+                        format!("{}.{}", q, self.schema.field(i).name())
+                    }
+                    _ => self.field_name(i),
+                };
+                let in_relation: Vec<String> = (0..self.schema.fields().len())
+                    .filter(|i| self.qualifier(*i) == qualifier && qualifier.is_some())
+                    .map(qualified_name)
+                    .collect();
+                let candidates = if in_relation.is_empty() {
+                    (0..self.schema.fields().len())
+                        .map(qualified_name)
+                        .collect()
+                } else {
+                    in_relation
+                };
+                Err(ExecutionError::plan(
+                    utils::unknown_column_message(&name, &candidates),
+                    &name,
+                ))
+            }
+        }
+    }
+
+    /// Get the fields produced by a list of expressions evaluated against this schema,
+    /// naming columns by their qualified name if their name alone is ambiguous
+    pub fn exprlist_to_fields(&self, expr: &Vec<Expr>) -> Vec<Field> {
+        expr.iter()
+            .map(|e| match e {
+                Expr::Column(i) => {
+                    let field = self.schema.field(*i);
+                    Field::new(
+                        &self.field_name(*i),
+                        field.data_type().clone(),
+                        field.is_nullable(),
+                    )
+                }
+                _ => expr_to_field(e, &self.schema),
+            })
+            .collect()
+    }
 }
 
 /// SQL query planner
 pub struct SqlToRel {
-    schema_provider: Rc<SchemaProvider>,
+    schema_provider: Arc<SchemaProvider>,
 }
 
 impl SqlToRel {
     /// Create a new query planner
-    pub fn new(schema_provider: Rc<SchemaProvider>) -> Self {
+    pub fn new(schema_provider: Arc<SchemaProvider>) -> Self {
         SqlToRel { schema_provider }
     }
 
@@ -66,21 +261,46 @@ impl SqlToRel {
                     }),
                 };
 
-                let input_schema = input.schema();
+                let input_schema = match relation {
+                    &Some(ref r) => self.relation_schema(r, &input),
+                    &None => QualifiedSchema::unqualified(input.schema().clone()),
+                };
 
                 // selection first
                 let selection_plan = match selection {
                     &Some(ref filter_expr) => Some(LogicalPlan::Selection {
-                        expr: self.sql_to_rex(&filter_expr, &input_schema.clone())?,
+                        expr: self.sql_to_rex(&filter_expr, &input_schema)?,
                         input: input.clone(),
                     }),
                     _ => None,
                 };
 
-                let expr: Vec<Expr> = projection
-                    .iter()
-                    .map(|e| self.sql_to_rex(&e, &input_schema))
-                    .collect::<Result<Vec<Expr>>>()?;
+                let mut expr: Vec<Expr> = vec![];
+                let mut unnest_column: Option<usize> = None;
+                for e in projection {
+                    match e {
+                        // a wildcard selects all columns of the input relation
+                        ASTNode::SQLWildcard => {
+                            let columns = 0..input_schema.schema().fields().len();
+                            expr.extend(columns.map(Expr::Column));
+                        }
+                        // an unnest selects the elements of a list column, one per row
+                        ASTNode::SQLFunction { id, args }
+                            if id.eq_ignore_ascii_case("unnest") =>
+                        {
+                            if unnest_column.is_some() {
+                                return Err(ExecutionError::General(
+                                    "Only one UNNEST is supported in a projection"
+                                        .to_string(),
+                                ));
+                            }
+                            let column = self.unnest_column(args, &input_schema)?;
+                            unnest_column = Some(column);
+                            expr.push(Expr::Column(column));
+                        }
+                        _ => expr.push(self.sql_to_rex(e, &input_schema)?),
+                    }
+                }
 
                 // collect aggregate expressions
                 let aggr_expr: Vec<Expr> = expr
@@ -93,6 +313,13 @@ impl SqlToRel {
                     .collect();
 
                 if aggr_expr.len() > 0 {
+                    if unnest_column.is_some() {
+                        return Err(ExecutionError::General(
+                            "UNNEST cannot be combined with aggregate functions"
+                                .to_string(),
+                        ));
+                    }
+
                     let aggregate_input: Rc<LogicalPlan> = match selection_plan {
                         Some(s) => Rc::new(s),
                         _ => input.clone(),
@@ -111,7 +338,7 @@ impl SqlToRel {
                     aggr_expr.iter().for_each(|x| all_fields.push(x.clone()));
 
                     let aggr_schema =
-                        Schema::new(exprlist_to_fields(&all_fields, input_schema));
+                        Schema::new(input_schema.exprlist_to_fields(&all_fields));
 
                     //TODO: selection, projection, everything else
                     Ok(Rc::new(LogicalPlan::Aggregate {
@@ -121,15 +348,27 @@ impl SqlToRel {
                         schema: Arc::new(aggr_schema),
                     }))
                 } else {
-                    let projection_input: Rc<LogicalPlan> = match selection_plan {
+                    let mut projection_input: Rc<LogicalPlan> = match selection_plan {
                         Some(s) => Rc::new(s),
                         _ => input.clone(),
                     };
 
-                    let projection_schema = Arc::new(Schema::new(exprlist_to_fields(
-                        &expr,
-                        input_schema.as_ref(),
-                    )));
+                    // the projection is applied to the unnested rows, in which the
+                    // list column is replaced by its elements
+                    let mut input_schema = input_schema;
+                    if let Some(column) = unnest_column {
+                        let schema =
+                            Arc::new(unnest_schema(projection_input.schema(), column)?);
+                        input_schema = input_schema.with_schema(schema.clone());
+                        projection_input = Rc::new(LogicalPlan::Unnest {
+                            input: projection_input,
+                            column,
+                            schema,
+                        });
+                    }
+
+                    let projection_schema =
+                        Arc::new(Schema::new(input_schema.exprlist_to_fields(&expr)));
 
                     let projection = LogicalPlan::Projection {
                         expr: expr,
@@ -146,15 +385,19 @@ impl SqlToRel {
                     let order_by_plan = match order_by {
                         &Some(ref order_by_expr) => {
                             let input_schema = projection.schema();
+                            let qualified_schema =
+                                QualifiedSchema::unqualified(input_schema.clone());
                             let order_by_rex: Result<Vec<Expr>> = order_by_expr
                                 .iter()
                                 .map(|e| {
                                     Ok(Expr::Sort {
                                         expr: Rc::new(
-                                            self.sql_to_rex(&e.expr, &input_schema)
+                                            self.sql_to_rex(&e.expr, &qualified_schema)
                                                 .unwrap(),
                                         ),
                                         asc: e.asc,
+                                        // nulls sort after all other values
+                                        nulls_first: !e.asc,
                                     })
                                 })
                                 .collect();
@@ -171,8 +414,10 @@ impl SqlToRel {
                     let limit_plan = match limit {
                         &Some(ref limit_expr) => {
                             let input_schema = order_by_plan.schema();
-                            let limit_rex =
-                                self.sql_to_rex(&limit_expr, &input_schema.clone())?;
+                            let limit_rex = self.sql_to_rex(
+                                &limit_expr,
+                                &QualifiedSchema::unqualified(input_schema.clone()),
+                            )?;
 
                             LogicalPlan::Limit {
                                 expr: limit_rex,
@@ -190,15 +435,75 @@ impl SqlToRel {
             &ASTNode::SQLIdentifier(ref id) => {
                 match self.schema_provider.get_table_meta(id.as_ref()) {
                     Some(schema) => Ok(Rc::new(LogicalPlan::TableScan {
-                        schema_name: String::from("default"),
+                        schema_name: DEFAULT_SCHEMA.to_string(),
                         table_name: id.clone(),
+                        function_args: None,
                         schema: schema.clone(),
                         projection: None,
+                        limit: None,
+                    })),
+                    None => Err(ExecutionError::plan(
+                        format!("no schema found for table {}", id),
+                        id,
+                    )),
+                }
+            }
+
+            &ASTNode::SQLCompoundIdentifier(ref ids) => match ids.as_slice() {
+                // a table that is qualified by the name of the schema it belongs to
+                [schema_name, table_name] => {
+                    let name = format!("{}.{}", schema_name, table_name);
+                    match self.schema_provider.get_table_meta(&name) {
+                        Some(schema) => Ok(Rc::new(LogicalPlan::TableScan {
+                            schema_name: schema_name.clone(),
+                            table_name: table_name.clone(),
+                            function_args: None,
+                            schema: schema.clone(),
+                            projection: None,
+                            limit: None,
+                        })),
+                        None => Err(ExecutionError::plan(
+                            format!("no schema found for table {}", name),
+                            &name,
+                        )),
+                    }
+                }
+                _ => Err(ExecutionError::NotImplemented(format!(
+                    "Unsupported table name '{}'",
+                    ids.join(".")
+                ))),
+            },
+
+            &ASTNode::SQLFunction { ref id, ref args } => {
+                // a function in the FROM clause is a table function, which must be
+                // called with literal values
+                let empty_schema =
+                    QualifiedSchema::unqualified(Arc::new(Schema::empty()));
+                let values = args
+                    .iter()
+                    .map(|a| match self.sql_to_rex(a, &empty_schema)? {
+                        Expr::Literal(value) => Ok(value),
+                        other => Err(ExecutionError::General(format!(
+                            "Arguments of table function '{}' must be literal values, \
+                             not {:?}",
+                            id, other
+                        ))),
+                    })
+                    .collect::<Result<Vec<ScalarValue>>>()?;
+
+                match self.schema_provider.get_table_function_meta(id, &values) {
+                    Some(schema) => Ok(Rc::new(LogicalPlan::TableScan {
+                        schema_name: DEFAULT_SCHEMA.to_string(),
+                        table_name: id.clone(),
+                        function_args: Some(values),
+                        schema: schema?,
+                        projection: None,
+                        limit: None,
                     })),
-                    None => Err(ExecutionError::General(format!(
-                        "no schema found for table {}",
-                        id
-                    ))),
+                    None => Err(ExecutionError::plan(
+                        format!("Invalid table function '{}'", id),
+                        id,
+                    )),
                 }
             }
 
@@ -209,8 +514,27 @@ impl SqlToRel {
         }
     }
 
+    /// Get the schema of a relation in the FROM clause, qualifying the fields of a
+    /// table by the table name
+    fn relation_schema(&self, relation: &ASTNode, plan: &LogicalPlan) -> QualifiedSchema {
+        match relation {
+            ASTNode::SQLIdentifier(ref id) => {
+                QualifiedSchema::qualified(id, plan.schema().clone())
+            }
+            ASTNode::SQLCompoundIdentifier(ref ids) if ids.len() == 2 => {
+                QualifiedSchema::qualified(&ids[1], plan.schema().clone())
+            }
+            _ => QualifiedSchema::unqualified(plan.schema().clone()),
+        }
+    }
+
     /// Generate a relational expression from a SQL expression
-    pub fn sql_to_rex(&self, sql: &ASTNode, schema: &Schema) -> Result<Expr> {
+    pub fn sql_to_rex(
+        &self,
+        sql: &ASTNode,
+        qualified_schema: &QualifiedSchema,
+    ) -> Result<Expr> {
+        let schema = qualified_schema.schema().as_ref();
         match sql {
             &ASTNode::SQLValue(sqlparser::sqlast::Value::Long(n)) => {
                 Ok(Expr::Literal(ScalarValue::Int64(n)))
@@ -219,18 +543,18 @@ impl SqlToRel {
                 Ok(Expr::Literal(ScalarValue::Float64(n)))
             }
             &ASTNode::SQLValue(sqlparser::sqlast::Value::SingleQuotedString(ref s)) => {
-                Ok(Expr::Literal(ScalarValue::Utf8(Rc::new(s.clone()))))
+                Ok(Expr::Literal(ScalarValue::Utf8(Arc::new(s.clone()))))
+            }
+            &ASTNode::SQLValue(sqlparser::sqlast::Value::Null) => {
+                Ok(Expr::Literal(ScalarValue::Null))
             }
 
             &ASTNode::SQLIdentifier(ref id) => {
-                match schema.fields().iter().position(|c| c.name().eq(id)) {
-                    Some(index) => Ok(Expr::Column(index)),
-                    None => Err(ExecutionError::ExecutionError(format!(
-                        "Invalid identifier '{}' for schema {}",
-                        id,
-                        schema.to_string()
-                    ))),
-                }
+                Ok(Expr::Column(qualified_schema.index_of(None, id)?))
+            }
+
+            &ASTNode::SQLCompoundIdentifier(ref ids) => {
+                self.compound_identifier_to_rex(ids, qualified_schema)
             }
 
             &ASTNode::SQLWildcard => {
@@ -243,17 +567,17 @@ impl SqlToRel {
                 ref expr,
                 ref data_type,
             } => Ok(Expr::Cast {
-                expr: Rc::new(self.sql_to_rex(&expr, schema)?),
+                expr: Rc::new(self.sql_to_rex(&expr, qualified_schema)?),
                 data_type: convert_data_type(data_type)?,
             }),
 
-            &ASTNode::SQLIsNull(ref expr) => {
-                Ok(Expr::IsNull(Rc::new(self.sql_to_rex(expr, schema)?)))
-            }
+            &ASTNode::SQLIsNull(ref expr) => Ok(Expr::IsNull(Rc::new(
+                self.sql_to_rex(expr, qualified_schema)?,
+            ))),
 
-            &ASTNode::SQLIsNotNull(ref expr) => {
-                Ok(Expr::IsNotNull(Rc::new(self.sql_to_rex(expr, schema)?)))
-            }
+            &ASTNode::SQLIsNotNull(ref expr) => Ok(Expr::IsNotNull(Rc::new(
+                self.sql_to_rex(expr, qualified_schema)?,
+            ))),
 
             &ASTNode::SQLBinaryExpr {
                 ref left,
@@ -279,8 +603,8 @@ impl SqlToRel {
                     &SQLOperator::NotLike => Operator::NotLike,
                 };
 
-                let left_expr = self.sql_to_rex(&left, &schema)?;
-                let right_expr = self.sql_to_rex(&right, &schema)?;
+                let left_expr = self.sql_to_rex(&left, qualified_schema)?;
+                let right_expr = self.sql_to_rex(&right, qualified_schema)?;
                 let left_type = left_expr.get_type(schema);
                 let right_type = right_expr.get_type(schema);
 
@@ -305,58 +629,74 @@ impl SqlToRel {
             //                asc,
             //            }),
             &ASTNode::SQLFunction { ref id, ref args } => {
-                //TODO: fix this hack
-                match id.to_lowercase().as_ref() {
-                    "min" | "max" | "sum" | "avg" => {
-                        let rex_args = args
-                            .iter()
-                            .map(|a| self.sql_to_rex(a, schema))
-                            .collect::<Result<Vec<Expr>>>()?;
-
-                        // return type is same as the argument type for these aggregate
-                        // functions
-                        let return_type = rex_args[0].get_type(schema).clone();
-
-                        Ok(Expr::AggregateFunction {
-                            name: id.clone(),
-                            args: rex_args,
-                            return_type,
-                        })
+                // hex literals such as X'DEADBEEF' are parsed as calls to from_hex
+                if id.eq_ignore_ascii_case("from_hex") {
+                    if let [ASTNode::SQLValue(
+                        sqlparser::sqlast::Value::SingleQuotedString(s),
+                    )] = args.as_slice()
+                    {
+                        return match decode_hex(s) {
+                            Some(bytes) => {
+                                Ok(Expr::Literal(ScalarValue::Binary(Arc::new(bytes))))
+                            }
+                            None => Err(ExecutionError::plan(
+                                format!("Invalid hex literal '{}'", s),
+                                s,
+                            )),
+                        };
                     }
-                    "count" => {
+                }
+                if let Some(function) = list_function(id) {
+                    return self.list_function_to_rex(&function, args, qualified_schema);
+                }
+                match aggregate_function(id) {
+                    Some(aggregate) => {
                         let rex_args = args
                             .iter()
                             .map(|a| match a {
-                                // this feels hacky but translate COUNT(1)/COUNT(*) to
-                                // COUNT(first_column)
-                                ASTNode::SQLValue(sqlparser::sqlast::Value::Long(1)) => {
+                                // translate COUNT(1)/COUNT(*) to COUNT(first_column)
+                                ASTNode::SQLValue(sqlparser::sqlast::Value::Long(1))
+                                | ASTNode::SQLWildcard
+                                    if aggregate.counts_rows() =>
+                                {
                                     Ok(Expr::Column(0))
                                 }
-                                ASTNode::SQLWildcard => Ok(Expr::Column(0)),
-                                _ => self.sql_to_rex(a, schema),
+                                _ => self.sql_to_rex(a, qualified_schema),
                             })
                             .collect::<Result<Vec<Expr>>>()?;
+                        if rex_args.len() != 1 {
+                            return Err(ExecutionError::plan(
+                                format!(
+                                    "Aggregate function '{}' expects one argument",
+                                    id
+                                ),
+                                id,
+                            ));
+                        }
 
                         Ok(Expr::AggregateFunction {
                             name: id.clone(),
+                            return_type: aggregate
+                                .return_type(&rex_args[0].get_type(schema)),
                             args: rex_args,
-                            return_type: DataType::UInt64,
                         })
                     }
-                    _ => match self.schema_provider.get_function_meta(id) {
+                    None => match self.schema_provider.get_function_meta(id) {
                         Some(fm) => {
                             let rex_args = args
                                 .iter()
-                                .map(|a| self.sql_to_rex(a, schema))
+                                .map(|a| self.sql_to_rex(a, qualified_schema))
                                 .collect::<Result<Vec<Expr>>>()?;
 
-                            let mut safe_args: Vec<Expr> = vec![];
-                            for i in 0..rex_args.len() {
-                                safe_args.push(
-                                    rex_args[i]
-                                        .cast_to(fm.args()[i].data_type(), schema)?,
-                                );
-                            }
+                            // cast the arguments to the types of the best matching
+                            // signature of the function
+                            let arg_types: Vec<DataType> =
+                                rex_args.iter().map(|e| e.get_type(schema)).collect();
+                            let safe_args = rex_args
+                                .iter()
+                                .zip(fm.resolve(&arg_types)?)
+                                .map(|(e, data_type)| Ok(e.cast_to(&data_type, schema)?))
+                                .collect::<Result<Vec<Expr>>>()?;
 
                             Ok(Expr::ScalarFunction {
                                 name: id.clone(),
@@ -364,10 +704,10 @@ impl SqlToRel {
                                 return_type: fm.return_type().clone(),
                             })
                         }
-                        _ => Err(ExecutionError::General(format!(
-                            "Invalid function '{}'",
-                            id
-                        ))),
+                        _ => Err(ExecutionError::plan(
+                            format!("Invalid function '{}'", id),
+                            id,
+                        )),
                     },
                 }
             }
@@ -378,6 +718,109 @@ impl SqlToRel {
             ))),
         }
     }
+
+    /// Generate a relational expression from a compound identifier, which is either a
+    /// column qualified by the name of its relation, such as `customer.address`, or an
+    /// access to fields of a struct column, such as `address.city` or
+    /// `customer.address.city`. A qualified column takes precedence over a field of a
+    /// column with the same name as the relation.
+    fn compound_identifier_to_rex(
+        &self,
+        ids: &[String],
+        qualified_schema: &QualifiedSchema,
+    ) -> Result<Expr> {
+        let qualified_column = qualified_schema.index_of(Some(ids[0].as_str()), &ids[1]);
+        let (column, fields) = match qualified_column {
+            Ok(i) => (i, &ids[2..]),
+            Err(e) => match qualified_schema.index_of(None, &ids[0]) {
+                Ok(i) if is_struct(qualified_schema.schema().field(i).data_type()) => {
+                    (i, &ids[1..])
+                }
+                _ => return Err(e),
+            },
+        };
+
+        let schema = qualified_schema.schema().as_ref();
+        let mut expr = Expr::Column(column);
+        for name in fields {
+            let data_type = expr.get_type(schema);
+            if struct_field(&data_type, name).is_none() {
+                return Err(ExecutionError::plan(
+                    format!(
+                        "Invalid field '{}' in '{}' of type {:?}",
+                        name,
+                        ids.join("."),
+                        data_type
+                    ),
+                    name,
+                ));
+            }
+            expr = expr.get_field(name);
+        }
+        Ok(expr)
+    }
+
+    /// Resolve the argument of an `UNNEST`, which must be a list column of the input
+    fn unnest_column(
+        &self,
+        args: &[ASTNode],
+        qualified_schema: &QualifiedSchema,
+    ) -> Result<usize> {
+        if args.len() != 1 {
+            return Err(ExecutionError::General(format!(
+                "UNNEST expects 1 argument but was called with {}",
+                args.len()
+            )));
+        }
+        match self.sql_to_rex(&args[0], qualified_schema)? {
+            Expr::Column(i) => match qualified_schema.schema().field(i).data_type() {
+                DataType::List(_) => Ok(i),
+                other => Err(ExecutionError::General(format!(
+                    "UNNEST expects a list column but was called with {:?}",
+                    other
+                ))),
+            },
+            other => Err(ExecutionError::General(format!(
+                "UNNEST expects a list column but was called with {:?}",
+                other
+            ))),
+        }
+    }
+
+    /// Generate a call to a built-in list function, whose return type is determined
+    /// by the type of its list argument
+    fn list_function_to_rex(
+        &self,
+        function: &ListFunction,
+        args: &[ASTNode],
+        qualified_schema: &QualifiedSchema,
+    ) -> Result<Expr> {
+        let schema = qualified_schema.schema().as_ref();
+        let rex_args = args
+            .iter()
+            .map(|a| self.sql_to_rex(a, qualified_schema))
+            .collect::<Result<Vec<Expr>>>()?;
+        let arg_types: Vec<DataType> =
+            rex_args.iter().map(|e| e.get_type(schema)).collect();
+        let safe_args = rex_args
+            .iter()
+            .zip(function.coerce_types(&arg_types)?)
+            .map(|(e, data_type)| Ok(e.cast_to(&data_type, schema)?))
+            .collect::<Result<Vec<Expr>>>()?;
+
+        Ok(Expr::ScalarFunction {
+            name: function.name().to_string(),
+            return_type: function.return_type(&arg_types[0])?,
+            args: safe_args,
+        })
+    }
+}
+
+fn is_struct(data_type: &DataType) -> bool {
+    match data_type {
+        DataType::Struct(_) => true,
+        _ => false,
+    }
 }
 
 /// Convert SQL data type to relational representation of data type
@@ -390,6 +833,7 @@ pub fn convert_data_type(sql: &SQLType) -> Result<DataType> {
         SQLType::Float(_) | SQLType::Real => Ok(DataType::Float64),
         SQLType::Double => Ok(DataType::Float64),
         SQLType::Char(_) | SQLType::Varchar(_) => Ok(DataType::Utf8),
+        SQLType::Date => Ok(DataType::Date(DateUnit::Day)),
         other => Err(ExecutionError::NotImplemented(format!(
             "Unsupported SQL type {:?}",
             other
@@ -401,6 +845,10 @@ pub fn expr_to_field(e: &Expr, input_schema: &Schema) -> Field {
     match e {
         Expr::Column(i) => input_schema.fields()[*i].clone(),
         Expr::Literal(ref lit) => Field::new("lit", lit.get_datatype(), true),
+        Expr::Alias(ref expr, ref alias) => {
+            let field = expr_to_field(expr, input_schema);
+            Field::new(alias, field.data_type().clone(), field.is_nullable())
+        }
         Expr::ScalarFunction {
             ref name,
             ref return_type,
@@ -412,6 +860,9 @@ pub fn expr_to_field(e: &Expr, input_schema: &Schema) -> Field {
             ..
         } => Field::new(name, return_type.clone(), true),
         Expr::Cast { ref data_type, .. } => Field::new("cast", data_type.clone(), true),
+        Expr::GetField { ref name, .. } => {
+            Field::new(name, e.get_type(input_schema), true)
+        }
         Expr::BinaryExpr {
             ref left,
             ref right,
@@ -435,33 +886,6 @@ pub fn exprlist_to_fields(expr: &Vec<Expr>, input_schema: &Schema) -> Vec<Field>
         .collect()
 }
 
-fn collect_expr(e: &Expr, accum: &mut HashSet<usize>) {
-    match e {
-        Expr::Column(i) => {
-            accum.insert(*i);
-        }
-        Expr::Cast { ref expr, .. } => collect_expr(expr, accum),
-        Expr::Literal(_) => {}
-        Expr::IsNotNull(ref expr) => collect_expr(expr, accum),
-        Expr::IsNull(ref expr) => collect_expr(expr, accum),
-        Expr::BinaryExpr {
-            ref left,
-            ref right,
-            ..
-        } => {
-            collect_expr(left, accum);
-            collect_expr(right, accum);
-        }
-        Expr::AggregateFunction { ref args, .. } => {
-            args.iter().for_each(|e| collect_expr(e, accum));
-        }
-        Expr::ScalarFunction { ref args, .. } => {
-            args.iter().for_each(|e| collect_expr(e, accum));
-        }
-        Expr::Sort { ref expr, .. } => collect_expr(expr, accum),
-    }
-}
-
 pub fn push_down_projection(
     plan: &Rc<LogicalPlan>,
     projection: &HashSet<usize>,
@@ -476,8 +900,8 @@ pub fn push_down_projection(
         } => {
             //TODO: apply projection first
             let mut accum: HashSet<usize> = HashSet::new();
-            group_expr.iter().for_each(|e| collect_expr(e, &mut accum));
-            aggr_expr.iter().for_each(|e| collect_expr(e, &mut accum));
+            utils::exprlist_to_column_indices(group_expr, &mut accum);
+            utils::exprlist_to_column_indices(aggr_expr, &mut accum);
             Rc::new(LogicalPlan::Aggregate {
                 input: push_down_projection(&input, &accum),
                 group_expr: group_expr.clone(),
@@ -490,7 +914,7 @@ pub fn push_down_projection(
             ref input,
         } => {
             let mut accum: HashSet<usize> = projection.clone();
-            collect_expr(expr, &mut accum);
+            utils::expr_to_column_indices(expr, &mut accum);
             Rc::new(LogicalPlan::Selection {
                 expr: expr.clone(),
                 input: push_down_projection(&input, &accum),
@@ -499,18 +923,25 @@ pub fn push_down_projection(
         LogicalPlan::TableScan {
             ref schema_name,
             ref table_name,
+            ref function_args,
             ref schema,
+            ref limit,
             ..
         } => Rc::new(LogicalPlan::TableScan {
             schema_name: schema_name.to_string(),
             table_name: table_name.to_string(),
+            function_args: function_args.clone(),
             schema: schema.clone(),
             projection: Some(projection.iter().cloned().collect()),
+            limit: limit.clone(),
         }),
         LogicalPlan::Projection { .. } => plan.clone(),
         LogicalPlan::Sort { .. } => plan.clone(),
         LogicalPlan::Limit { .. } => plan.clone(),
         LogicalPlan::EmptyRelation { .. } => plan.clone(),
+        LogicalPlan::Values { .. } => plan.clone(),
+        LogicalPlan::Join { .. } => plan.clone(),
+        LogicalPlan::Unnest { .. } => plan.clone(),
     }
 }
 
@@ -538,6 +969,23 @@ mod tests {
         );
     }
 
+    #[test]
+    fn select_wildcard() {
+        let sql = "SELECT * FROM person WHERE age > 21";
+        let expected = "Projection: #0, #1, #2, #3, #4, #5\
+                        \n  Selection: CAST(#3 AS Int64) Gt Int64(21)\
+                        \n    TableScan: person projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn select_from_table_function() {
+        let sql = "SELECT value FROM range(0, 10)";
+        let expected = "Projection: #0\
+                        \n  TableScan: range(Int64(0), Int64(10)) projection=None";
+        quick_test(sql, expected);
+    }
+
     #[test]
     fn select_simple_selection() {
         let sql = "SELECT id, first_name, last_name \
@@ -548,6 +996,73 @@ mod tests {
         quick_test(sql, expected);
     }
 
+    #[test]
+    fn select_qualified_identifiers() {
+        let sql = "SELECT person.id, first_name \
+                   FROM person WHERE person.state = 'CO'";
+        let expected = "Projection: #0, #1\
+                        \n  Selection: #4 Eq Utf8(\"CO\")\
+                        \n    TableScan: person projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn qualified_schema_resolution() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::UInt32, false),
+            Field::new("name", DataType::Utf8, false),
+        ]));
+        let a = QualifiedSchema::qualified("a", schema.clone());
+        let b = QualifiedSchema::qualified("b", schema.clone());
+        let joined = a.join(&b, JoinType::Inner);
+
+        assert_eq!(0, joined.index_of(Some("a"), "id").unwrap());
+        assert_eq!(2, joined.index_of(Some("b"), "id").unwrap());
+        assert!(joined.index_of(None, "id").is_err());
+        assert!(joined.index_of(Some("c"), "id").is_err());
+        assert_eq!("b.id", joined.field_name(2));
+
+        let fields = joined.exprlist_to_fields(&vec![Expr::Column(0), Expr::Column(3)]);
+        assert_eq!("a.id", fields[0].name());
+        assert_eq!("b.name", fields[1].name());
+
+        // columns with the same name but no qualifier resolve to the first match
+        let unqualified = QualifiedSchema::unqualified(joined.schema().clone());
+        assert_eq!(0, unqualified.index_of(None, "id").unwrap());
+        assert_eq!("id", unqualified.field_name(2));
+    }
+
+    #[test]
+    fn unresolved_column_diagnostics() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::UInt32, false),
+            Field::new("name", DataType::Utf8, false),
+        ]));
+        let a = QualifiedSchema::qualified("a", schema.clone());
+        let joined = a.join(&QualifiedSchema::qualified("b", schema), JoinType::Inner);
+
+        let message = |result: Result<usize>| match result {
+            Err(ExecutionError::PlanError(e)) => e.message,
+            other => panic!("Expected a planning error but got {:?}", other),
+        };
+        assert_eq!(
+            "Ambiguous reference to column 'id', which could refer to 'a.id' or 'b.id'",
+            message(joined.index_of(None, "id"))
+        );
+        assert_eq!(
+            "Invalid identifier 'nme'. Did you mean 'name'?",
+            message(a.index_of(None, "nme"))
+        );
+        assert_eq!(
+            "Invalid identifier 'b.nam'. Did you mean 'b.name'?",
+            message(joined.index_of(Some("b"), "nam"))
+        );
+        assert_eq!(
+            "Invalid identifier 'age'. Valid columns are 'id', 'name'",
+            message(a.index_of(None, "age"))
+        );
+    }
+
     #[test]
     fn select_compound_selection() {
         let sql = "SELECT id, first_name, last_name \
@@ -615,6 +1130,23 @@ mod tests {
         quick_test(sql, expected);
     }
 
+    #[test]
+    fn select_aggregate_case_insensitive() {
+        let sql = "SELECT min(age), Count(*) FROM person";
+        let expected = "Aggregate: groupBy=[[]], aggr=[[min(#3), Count(#0)]]\
+                        \n  TableScan: person projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn select_aggregate_with_wrong_number_of_args() {
+        use sqlparser::dialect::*;
+        let sql = "SELECT MAX(age, id) FROM person";
+        let planner = SqlToRel::new(Arc::new(MockSchemaProvider {}));
+        let ast = Parser::parse_sql(&GenericSqlDialect {}, sql.to_string()).unwrap();
+        assert!(planner.sql_to_rel(&ast).is_err());
+    }
+
     #[test]
     fn select_scalar_func() {
         let sql = "SELECT sqrt(age) FROM person";
@@ -623,6 +1155,15 @@ mod tests {
         quick_test(sql, expected);
     }
 
+    #[test]
+    fn select_scalar_func_with_wrong_number_of_args() {
+        use sqlparser::dialect::*;
+        let sql = "SELECT sqrt(age, 2) FROM person";
+        let planner = SqlToRel::new(Arc::new(MockSchemaProvider {}));
+        let ast = Parser::parse_sql(&GenericSqlDialect {}, sql.to_string()).unwrap();
+        assert!(planner.sql_to_rel(&ast).is_err());
+    }
+
     #[test]
     fn select_order_by() {
         let sql = "SELECT id FROM person ORDER BY id";
@@ -644,14 +1185,14 @@ mod tests {
     #[test]
     fn test_collect_expr() {
         let mut accum: HashSet<usize> = HashSet::new();
-        collect_expr(
+        utils::expr_to_column_indices(
             &Expr::Cast {
                 expr: Rc::new(Expr::Column(3)),
                 data_type: DataType::Float64,
             },
             &mut accum,
         );
-        collect_expr(
+        utils::expr_to_column_indices(
             &Expr::Cast {
                 expr: Rc::new(Expr::Column(3)),
                 data_type: DataType::Float64,
@@ -667,7 +1208,7 @@ mod tests {
     fn quick_test(sql: &str, expected: &str) {
         use sqlparser::dialect::*;
         let dialect = GenericSqlDialect {};
-        let planner = SqlToRel::new(Rc::new(MockSchemaProvider {}));
+        let planner = SqlToRel::new(Arc::new(MockSchemaProvider {}));
         let ast = Parser::parse_sql(&dialect, sql.to_string()).unwrap();
         let plan = planner.sql_to_rel(&ast).unwrap();
         assert_eq!(expected, format!("{:?}", plan));
@@ -701,6 +1242,21 @@ mod tests {
                 _ => None,
             }
         }
+
+        fn get_table_function_meta(
+            &self,
+            name: &str,
+            _args: &[ScalarValue],
+        ) -> Option<Result<Arc<Schema>>> {
+            match name {
+                "range" => Some(Ok(Arc::new(Schema::new(vec![Field::new(
+                    "value",
+                    DataType::Int64,
+                    false,
+                )])))),
+                _ => None,
+            }
+        }
     }
 
 }
@@ -16,115 +16,1991 @@
 // under the License.
 
 use std::cell::RefCell;
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::io::Cursor;
 use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
 use std::sync::Arc;
+use std::thread;
 
 extern crate arrow;
 extern crate datafusion;
 
 use arrow::array::*;
-use arrow::datatypes::{DataType, Field, Schema};
+use arrow::array_data::ArrayData;
+use arrow::buffer::Buffer;
+use arrow::builder::{Int32Builder, ListBuilder};
+use arrow::compute;
+use arrow::datatypes::{DataType, Field, Schema, TimeUnit, ToByteSlice};
+use arrow::record_batch::RecordBatch;
+use futures::{Future, Stream};
 
-use datafusion::execution::context::ExecutionContext;
+use datafusion::execution::context::{
+    row_count, Collation, ExecutionConfig, ExecutionContext, NanOrdering, OverflowMode,
+};
+use datafusion::execution::datasource::{
+    CsvReadOptions, DataSource, FilteredTable, MemTable, StreamTable, TableProvider,
+};
+use datafusion::execution::error::{ExecutionError, Result};
+use datafusion::execution::memory::batch_memory_size;
+use datafusion::execution::object_store::{ObjectReader, ObjectStore};
+use datafusion::execution::physicalplan::ExecutionPlan;
 use datafusion::execution::relation::Relation;
+use datafusion::logicalplan::{
+    col, lit, Expr, LogicalPlan, Operator, ScalarValue, Signature,
+};
 
-const DEFAULT_BATCH_SIZE: usize = 1024 * 1024;
+#[test]
+fn csv_query_with_predicate() {
+    let mut ctx = ExecutionContext::new();
+    register_aggregate_csv(&mut ctx);
+    let sql = "SELECT c1, c12 FROM aggregate_test_100 WHERE c12 > 0.376 AND c12 < 0.4";
+    let actual = execute(&mut ctx, sql);
+    let expected = "\"e\"\t0.39144436569161134\n\"d\"\t0.38870280983958583\n".to_string();
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn csv_query_with_always_true_predicate() {
+    let mut ctx = ExecutionContext::new();
+    register_aggregate_csv(&mut ctx);
+    let sql = "SELECT c1, c12 FROM aggregate_test_100 WHERE 1 = 1 AND c12 > 0.376 AND c12 < 0.4";
+    let actual = execute(&mut ctx, sql);
+    let expected = "\"e\"\t0.39144436569161134\n\"d\"\t0.38870280983958583\n".to_string();
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn csv_query_with_always_false_predicate() {
+    let mut ctx = ExecutionContext::new();
+    register_aggregate_csv(&mut ctx);
+    let sql = "SELECT c1, c12 FROM aggregate_test_100 WHERE c12 > 0.376 AND 1 = 2";
+    let actual = execute(&mut ctx, sql);
+    let expected = "".to_string();
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn csv_query_group_by_int_min_max() {
+    let mut ctx = ExecutionContext::new();
+    register_aggregate_csv(&mut ctx);
+    let sql =
+        "SELECT c2, MIN(c12), MAX(c12) FROM aggregate_test_100 GROUP BY c2 ORDER BY c2";
+    let actual = execute(&mut ctx, sql);
+    let expected = "1\t0.05636955101974106\t0.9965400387585364\n2\t0.16301110515739792\t0.991517828651004\n3\t0.047343434291126085\t0.9293883502480845\n4\t0.02182578039211991\t0.9237877978193884\n5\t0.01479305307777301\t0.9723580396501548\n".to_string();
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn csv_query_group_by_string_min_max() {
+    let mut ctx = ExecutionContext::new();
+    register_aggregate_csv(&mut ctx);
+    //TODO add ORDER BY once supported, to make this test determistic
+    let sql = "SELECT c2, MIN(c12), MAX(c12) FROM aggregate_test_100 GROUP BY c1";
+    let actual = execute(&mut ctx, sql);
+    let expected =
+        "\"d\"\t0.061029375346466685\t0.9748360509016578\n\"c\"\t0.0494924465469434\t0.991517828651004\n\"b\"\t0.04893135681998029\t0.9185813970744787\n\"a\"\t0.02182578039211991\t0.9800193410444061\n\"e\"\t0.01479305307777301\t0.9965400387585364\n".to_string();
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn csv_query_cast() {
+    let mut ctx = ExecutionContext::new();
+    register_aggregate_csv(&mut ctx);
+    let sql = "SELECT CAST(c12 AS float) FROM aggregate_test_100 WHERE c12 > 0.376 AND c12 < 0.4";
+    let actual = execute(&mut ctx, sql);
+    let expected = "0.39144436569161134\n0.38870280983958583\n".to_string();
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn cast_query() {
+    let mut ctx = ExecutionContext::new();
+    let schema = Schema::new(vec![
+        Field::new("a", DataType::Int64, true),
+        Field::new("s", DataType::Utf8, false),
+    ]);
+    let batch = RecordBatch::new(
+        Arc::new(schema.clone()),
+        vec![
+            Arc::new(Int64Array::from(vec![Some(1), None, Some(100000)])),
+            Arc::new(BinaryArray::from(vec!["10", " 20 ", "x"])),
+        ],
+    );
+    ctx.register_table("t", &schema, vec![batch]).unwrap();
+
+    let sql = "SELECT CAST(a AS VARCHAR(10)) FROM t WHERE a < 10";
+    let actual = execute(&mut ctx, sql);
+    assert_eq!("\"1\"\n".to_string(), actual);
+    let actual = execute(&mut ctx, "SELECT CAST(s AS INT) FROM t WHERE a < 10");
+    assert_eq!("10\n".to_string(), actual);
+    let actual = execute(&mut ctx, "SELECT CAST(a AS DOUBLE) FROM t WHERE a > 10");
+    assert_eq!("100000.0\n".to_string(), actual);
+
+    // values that cannot be represented in the target type are errors
+    assert!(ctx.sql_collect("SELECT CAST(s AS INT) FROM t").is_err());
+    let sql = "SELECT CAST(a AS SMALLINT) FROM t";
+    assert!(ctx.sql_collect(sql).is_err());
+}
+
+#[test]
+fn csv_query_common_subexpr() {
+    let mut ctx = ExecutionContext::new();
+    register_aggregate_csv(&mut ctx);
+    let sql = "SELECT c2 * c2 + c2, c2 * c2 - c2 FROM aggregate_test_100 LIMIT 3";
+    let actual = execute(&mut ctx, sql);
+    let expected = "6\t2\n30\t20\n2\t0\n".to_string();
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn csv_query_limit() {
+    let mut ctx = ExecutionContext::new();
+    register_aggregate_csv(&mut ctx);
+    let sql = "SELECT 0 FROM aggregate_test_100 LIMIT 2";
+    let actual = execute(&mut ctx, sql);
+    let expected = "0\n0\n".to_string();
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn csv_query_null_literal() {
+    let mut ctx = ExecutionContext::new();
+    register_aggregate_csv(&mut ctx);
+    let sql = "SELECT NULL AS x, c2 FROM aggregate_test_100 LIMIT 2";
+    let actual = execute(&mut ctx, sql);
+    let expected = "NULL\t2\nNULL\t5\n".to_string();
+    assert_eq!(expected, actual);
+
+    let sql = "SELECT NULL AS x FROM aggregate_test_100 WHERE c2 = 5";
+    let results = ctx.sql_collect(sql).unwrap();
+    let x = results[0].column(0);
+    assert_eq!(&DataType::Null, x.data_type());
+    assert_eq!(x.len(), x.null_count());
+}
+
+#[test]
+fn csv_query_limit_bigger_than_nbr_of_rows() {
+    let mut ctx = ExecutionContext::new();
+    register_aggregate_csv(&mut ctx);
+    let sql = "SELECT c2 FROM aggregate_test_100 LIMIT 200";
+    let actual = execute(&mut ctx, sql);
+    let expected = "2\n5\n1\n1\n5\n4\n3\n3\n1\n4\n1\n4\n3\n2\n1\n1\n2\n1\n3\n2\n4\n1\n5\n4\n2\n1\n4\n5\n2\n3\n4\n2\n1\n5\n3\n1\n2\n3\n3\n3\n2\n4\n1\n3\n2\n5\n2\n1\n4\n1\n4\n2\n5\n4\n2\n3\n4\n4\n4\n5\n4\n2\n1\n2\n4\n2\n3\n5\n1\n1\n4\n2\n1\n2\n1\n1\n5\n4\n5\n2\n3\n2\n4\n1\n3\n4\n3\n2\n5\n3\n3\n2\n5\n5\n4\n1\n3\n3\n4\n4\n".to_string();
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn csv_query_limit_with_same_nbr_of_rows() {
+    let mut ctx = ExecutionContext::new();
+    register_aggregate_csv(&mut ctx);
+    let sql = "SELECT c2 FROM aggregate_test_100 LIMIT 100";
+    let actual = execute(&mut ctx, sql);
+    let expected = "2\n5\n1\n1\n5\n4\n3\n3\n1\n4\n1\n4\n3\n2\n1\n1\n2\n1\n3\n2\n4\n1\n5\n4\n2\n1\n4\n5\n2\n3\n4\n2\n1\n5\n3\n1\n2\n3\n3\n3\n2\n4\n1\n3\n2\n5\n2\n1\n4\n1\n4\n2\n5\n4\n2\n3\n4\n4\n4\n5\n4\n2\n1\n2\n4\n2\n3\n5\n1\n1\n4\n2\n1\n2\n1\n1\n5\n4\n5\n2\n3\n2\n4\n1\n3\n4\n3\n2\n5\n3\n3\n2\n5\n5\n4\n1\n3\n3\n4\n4\n".to_string();
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn csv_query_limit_zero() {
+    let mut ctx = ExecutionContext::new();
+    register_aggregate_csv(&mut ctx);
+    let sql = "SELECT 0 FROM aggregate_test_100 LIMIT 0";
+    let actual = execute(&mut ctx, sql);
+    let expected = "".to_string();
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn csv_query_limit_with_order_by() {
+    let mut ctx = ExecutionContext::new();
+    register_aggregate_csv(&mut ctx);
+    let sql = "SELECT c7 FROM aggregate_test_100 ORDER BY c7 ASC LIMIT 2";
+    let actual = execute(&mut ctx, sql);
+    let expected = "0\n2\n".to_string();
+    assert_eq!(expected, actual);
+
+    // the sorted partitions are merged before the limit is applied
+    let mut ctx = ExecutionContext::new();
+    register_partitioned_aggregate_csv(&mut ctx, 4);
+    let actual = execute(&mut ctx, sql);
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn order_by_query() {
+    let schema = Schema::new(vec![
+        Field::new("a", DataType::Utf8, false),
+        Field::new("b", DataType::Int32, true),
+    ]);
+    let batch = |a: Vec<&str>, b: Vec<Option<i32>>| {
+        RecordBatch::new(
+            Arc::new(schema.clone()),
+            vec![
+                Arc::new(BinaryArray::from(a)),
+                Arc::new(Int32Array::from(b)),
+            ],
+        )
+    };
+    // each batch of the table is scanned as a separate partition
+    let batches = vec![
+        batch(vec!["x", "y"], vec![Some(3), None]),
+        batch(vec!["z", "w"], vec![Some(1), Some(3)]),
+    ];
+    let mut ctx = ExecutionContext::new();
+    ctx.register_table("t", &schema, batches).unwrap();
+    let first_column = |ctx: &mut ExecutionContext, sql: &str| {
+        let actual = execute(ctx, sql);
+        actual
+            .lines()
+            .map(|line| line.split('\t').next().unwrap().to_string())
+            .collect::<Vec<String>>()
+            .join(",")
+    };
+
+    // nulls are sorted after all other values, as if they were greater
+    let actual = first_column(&mut ctx, "SELECT a, b FROM t ORDER BY b, a");
+    assert_eq!("\"z\",\"w\",\"x\",\"y\"", actual);
+    let actual = first_column(&mut ctx, "SELECT a, b FROM t ORDER BY b DESC, a");
+    assert_eq!("\"y\",\"w\",\"x\",\"z\"", actual);
+    let actual = first_column(&mut ctx, "SELECT a, b FROM t ORDER BY a DESC LIMIT 3");
+    assert_eq!("\"z\",\"y\",\"x\"", actual);
+}
+
+#[test]
+fn order_by_query_with_unsortable_columns() {
+    let list_type = DataType::List(Box::new(DataType::Int32));
+    let schema = Schema::new(vec![
+        Field::new("id", DataType::Int32, false),
+        Field::new("tags", list_type, true),
+    ]);
+    let batch = |ids: Vec<i32>, lists: Vec<Vec<i32>>| {
+        let mut tags = ListBuilder::new(Int32Builder::new(8));
+        for list in lists {
+            tags.values().append_slice(&list).unwrap();
+            tags.append(true).unwrap();
+        }
+        RecordBatch::new(
+            Arc::new(schema.clone()),
+            vec![Arc::new(Int32Array::from(ids)), Arc::new(tags.finish())],
+        )
+    };
+    // each batch of the table is scanned as a separate partition
+    let batches = vec![
+        batch(vec![1, 3], vec![vec![1], vec![3, 3, 3]]),
+        batch(vec![2], vec![vec![2, 2]]),
+    ];
+    let mut ctx = ExecutionContext::new();
+    ctx.register_table("t", &schema, batches).unwrap();
+
+    // only the sort keys are compared, and the other columns are carried along
+    let sql = "SELECT NULL AS x, tags, id FROM t ORDER BY id DESC";
+    let batches = ctx.sql_collect(sql).unwrap();
+    let mut lengths = vec![];
+    for batch in &batches {
+        assert_eq!(&DataType::Null, batch.column(0).data_type());
+        let tags = batch
+            .column(1)
+            .as_any()
+            .downcast_ref::<ListArray>()
+            .unwrap();
+        lengths.extend((0..tags.len()).map(|i| tags.value_length(i)));
+    }
+    assert_eq!(vec![3, 2, 1], lengths);
+
+    // list values cannot be compared, so they cannot be sort keys
+    assert!(ctx.sql_collect("SELECT tags FROM t ORDER BY tags").is_err());
+}
+
+#[test]
+fn csv_query_stream() {
+    let mut ctx = ExecutionContext::new();
+    register_partitioned_aggregate_csv(&mut ctx, 2);
+    let sql = "SELECT c1, c12 FROM aggregate_test_100 WHERE c12 > 0.376 AND c12 < 0.4";
+    let stream = ctx.sql_stream(sql).unwrap();
+    assert_eq!(2, stream.schema().fields().len());
+
+    let batches = stream.collect().wait().unwrap();
+    let rows: usize = batches.iter().map(|batch| batch.num_rows()).sum();
+    assert_eq!(4, rows);
+}
+
+#[test]
+fn create_physical_plan() {
+    let mut ctx = ExecutionContext::new();
+    register_aggregate_csv(&mut ctx);
+    let scan_schema = Arc::new(Schema::new(vec![
+        Field::new("c1", DataType::Utf8, false),
+        Field::new("c2", DataType::UInt32, false),
+    ]));
+    let plan = LogicalPlan::Limit {
+        expr: Expr::Literal(ScalarValue::Int64(10)),
+        schema: scan_schema.clone(),
+        input: Rc::new(LogicalPlan::TableScan {
+            schema_name: "default".to_string(),
+            table_name: "aggregate_test_100".to_string(),
+            function_args: None,
+            schema: scan_schema,
+            projection: Some(vec![0, 1]),
+            limit: None,
+        }),
+    };
+
+    let physical_plan = ctx.create_physical_plan(&plan, ctx.config()).unwrap();
+    let schema = physical_plan.schema();
+    assert_eq!(2, schema.fields().len());
+    assert_eq!("c1", schema.field(0).name());
+    assert_eq!("c2", schema.field(1).name());
+
+    let children = physical_plan.children();
+    assert_eq!(1, children.len());
+    assert_eq!(0, children[0].children().len());
+}
+
+#[test]
+fn csv_query_partitioned_min_max() {
+    let sql = "SELECT MIN(c12), MAX(c12) FROM aggregate_test_100";
+    let mut ctx = ExecutionContext::new();
+    register_aggregate_csv(&mut ctx);
+    let expected = execute(&mut ctx, sql);
+
+    let mut ctx =
+        ExecutionContext::with_config(ExecutionConfig::new().with_concurrency(2));
+    register_partitioned_aggregate_csv(&mut ctx, 4);
+    let actual = execute(&mut ctx, sql);
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn csv_query_partitioned_group_by_min_max() {
+    let sql = "SELECT c2, MIN(c12), MAX(c12) FROM aggregate_test_100 GROUP BY c2";
+    let mut ctx = ExecutionContext::new();
+    register_aggregate_csv(&mut ctx);
+    let expected = sorted_lines(&execute(&mut ctx, sql));
+
+    let mut ctx =
+        ExecutionContext::with_config(ExecutionConfig::new().with_concurrency(3));
+    register_partitioned_aggregate_csv(&mut ctx, 4);
+    let actual = sorted_lines(&execute(&mut ctx, sql));
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn csv_query_partitioned_limit() {
+    let mut ctx = ExecutionContext::new();
+    register_partitioned_aggregate_csv(&mut ctx, 3);
+    let sql = "SELECT c2 FROM aggregate_test_100 LIMIT 150";
+    let actual = execute(&mut ctx, sql);
+    assert_eq!(150, actual.lines().count());
+}
+
+#[test]
+fn csv_query_partitioned_filter() {
+    let mut ctx = ExecutionContext::new();
+    register_partitioned_aggregate_csv(&mut ctx, 2);
+    let sql = "SELECT c1, c12 FROM aggregate_test_100 WHERE c12 > 0.376 AND c12 < 0.4";
+    let actual = sorted_lines(&execute(&mut ctx, sql));
+    let expected = vec![
+        "\"d\"\t0.38870280983958583",
+        "\"d\"\t0.38870280983958583",
+        "\"e\"\t0.39144436569161134",
+        "\"e\"\t0.39144436569161134",
+    ];
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn csv_query_with_batch_size() {
+    let mut ctx =
+        ExecutionContext::with_config(ExecutionConfig::new().with_batch_size(30));
+    register_aggregate_csv(&mut ctx);
+    let sql = "SELECT c1 FROM aggregate_test_100";
+    let config = ctx.config().clone();
+    assert_eq!(vec![30, 30, 30, 10], batch_sizes(&mut ctx, sql, &config));
+    let config = ExecutionConfig::new().with_batch_size(50);
+    assert_eq!(vec![50, 50], batch_sizes(&mut ctx, sql, &config));
+}
+
+#[test]
+fn csv_query_without_optimizer() {
+    let config = ExecutionConfig::new().with_optimizer(false);
+    let mut ctx = ExecutionContext::with_config(config);
+    register_aggregate_csv(&mut ctx);
+    let sql = "SELECT c1, c12 FROM aggregate_test_100 WHERE c12 > 0.376 AND c12 < 0.4";
+    let plan = format!("{:?}", ctx.create_logical_plan(&sql).unwrap());
+    assert!(plan.ends_with("TableScan: aggregate_test_100 projection=None"));
+    let actual = execute(&mut ctx, sql);
+    let expected = "\"e\"\t0.39144436569161134\n\"d\"\t0.38870280983958583\n".to_string();
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn parquet_query() {
+    let mut ctx = ExecutionContext::new();
+    let filename = format!("{}/alltypes_plain.parquet", parquet_test_data());
+    ctx.register_parquet("alltypes_plain", &filename).unwrap();
+    let sql = "SELECT MIN(id), MAX(id) FROM alltypes_plain";
+    let actual = execute(&mut ctx, sql);
+    assert_eq!("0\t7\n".to_string(), actual);
+}
+
+#[test]
+fn parquet_query_directory() {
+    let dir = env::temp_dir().join("datafusion_parquet_query_directory");
+    fs::create_dir_all(&dir).unwrap();
+    let filename = format!("{}/alltypes_plain.parquet", parquet_test_data());
+    fs::copy(&filename, dir.join("part-0.parquet")).unwrap();
+    fs::copy(&filename, dir.join("part-1.parquet")).unwrap();
+
+    let mut ctx = ExecutionContext::new();
+    ctx.register_parquet("alltypes_plain", dir.to_str().unwrap())
+        .unwrap();
+    let sql = "SELECT id FROM alltypes_plain WHERE id > 5";
+    let batches = ctx.sql_collect(sql).unwrap();
+    assert_eq!(4, row_count(&batches));
+}
+
+#[test]
+fn parquet_query_with_partitions() {
+    let dir = env::temp_dir().join("datafusion_parquet_query_with_partitions");
+    let filename = format!("{}/alltypes_plain.parquet", parquet_test_data());
+    for year in &[2018, 2019] {
+        let partition = dir.join(format!("year={}", year));
+        fs::create_dir_all(&partition).unwrap();
+        fs::copy(&filename, partition.join("part-0.parquet")).unwrap();
+    }
+
+    let mut ctx = ExecutionContext::new();
+    ctx.register_parquet_with_partitions("alltypes_plain", dir.to_str().unwrap())
+        .unwrap();
+    let sql = "SELECT MIN(id), MAX(id) FROM alltypes_plain WHERE year = 2019";
+    let actual = execute(&mut ctx, sql);
+    assert_eq!("0\t7\n".to_string(), actual);
+
+    let sql = "SELECT year, COUNT(id) FROM alltypes_plain GROUP BY year";
+    let batches = ctx.sql_collect(sql).unwrap();
+    assert_eq!(2, row_count(&batches));
+}
+
+#[test]
+fn parquet_query_with_predicate() {
+    let mut ctx = ExecutionContext::new();
+    let filename = format!("{}/alltypes_plain.parquet", parquet_test_data());
+    ctx.register_parquet("alltypes_plain", &filename).unwrap();
+    let sql = "SELECT MIN(id), MAX(id) FROM alltypes_plain WHERE id > 4";
+    let actual = execute(&mut ctx, sql);
+    assert_eq!("5\t7\n".to_string(), actual);
+    // the only row group is skipped using its statistics
+    let sql = "SELECT id FROM alltypes_plain WHERE id > 100";
+    let batches = ctx.sql_collect(sql).unwrap();
+    assert_eq!(0, row_count(&batches));
+}
+
+#[test]
+fn csv_query_directory() {
+    let dir = env::temp_dir().join("datafusion_csv_query_directory");
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("part-0.csv"), "c1,c2\na,1\nb,2\n").unwrap();
+    fs::write(dir.join("part-1.csv"), "c1,c2\nc,3\n").unwrap();
+    let schema = Schema::new(vec![
+        Field::new("c1", DataType::Utf8, false),
+        Field::new("c2", DataType::Int32, false),
+    ]);
+
+    let mut ctx = ExecutionContext::new();
+    ctx.register_csv("t", dir.to_str().unwrap(), &schema, true)
+        .unwrap();
+    let sql = "SELECT MIN(c2), MAX(c2) FROM t";
+    let actual = execute(&mut ctx, sql);
+    assert_eq!("1\t3\n".to_string(), actual);
+
+    let pattern = format!("{}/*-1.csv", dir.to_str().unwrap());
+    ctx.register_csv("t", &pattern, &schema, true).unwrap();
+    let actual = execute(&mut ctx, sql);
+    assert_eq!("3\t3\n".to_string(), actual);
+}
+
+#[test]
+fn csv_query_with_options() {
+    let path = env::temp_dir().join("datafusion_csv_query_with_options.tsv");
+    fs::write(
+        &path,
+        "# prices in cents\nname\tprice\n'apple\\'s'\t10\n# discontinued\n'pear'\t20\n",
+    )
+    .unwrap();
+
+    let mut ctx = ExecutionContext::new();
+    let schema = Schema::new(vec![
+        Field::new("name", DataType::Utf8, false),
+        Field::new("price", DataType::Int32, false),
+    ]);
+    let options = CsvReadOptions::new()
+        .with_delimiter(b'\t')
+        .with_quote(b'\'')
+        .with_escape(b'\\')
+        .with_comment(b'#');
+    let filename = path.to_str().unwrap();
+    ctx.register_csv_with_options("prices", filename, &schema, &options)
+        .unwrap();
+    let sql = "SELECT name, price FROM prices WHERE price > 5";
+    let actual = execute(&mut ctx, sql);
+    assert_eq!("\"apple's\"\t10\n\"pear\"\t20\n".to_string(), actual);
+}
+
+#[test]
+fn create_external_table_query() {
+    let path = env::temp_dir().join("datafusion_create_external_table_query.csv");
+    fs::write(&path, "a,b\n1,x\n2,y\n3,z\n").unwrap();
+
+    let mut ctx = ExecutionContext::new();
+    let sql = format!(
+        "CREATE EXTERNAL TABLE t (a INT NOT NULL, b VARCHAR(10)) \
+         STORED AS CSV WITH HEADER ROW LOCATION '{}'",
+        path.to_str().unwrap()
+    );
+    assert!(ctx.execute_sql(&sql).unwrap().is_none());
+    assert!(ctx.sql(&sql).is_err());
+
+    let relation = ctx.execute_sql("SELECT b FROM t WHERE a > 1").unwrap();
+    assert_eq!("\"y\"\n\"z\"\n".to_string(), result_str(&relation.unwrap()));
+}
+
+#[test]
+fn csv_query_with_date_column() {
+    let path = env::temp_dir().join("datafusion_csv_query_with_date_column.csv");
+    fs::write(&path, "d,v\n2020-01-03,3\n2019-12-31,1\n,4\n2020-01-02,2\n").unwrap();
+
+    let mut ctx = ExecutionContext::new();
+    let sql = format!(
+        "CREATE EXTERNAL TABLE t (d DATE, v INT NOT NULL) \
+         STORED AS CSV WITH HEADER ROW LOCATION '{}'",
+        path.to_str().unwrap()
+    );
+    ctx.execute_sql(&sql).unwrap();
+
+    let sql = "SELECT v, d FROM t WHERE d >= CAST('2020-01-01' AS DATE) ORDER BY d";
+    let actual = execute(&mut ctx, sql);
+    assert_eq!("2\t2020-01-02\n3\t2020-01-03\n".to_string(), actual);
+
+    let sql = "SELECT v, d FROM t WHERE d < CAST('2020-01-03' AS DATE) ORDER BY d DESC";
+    let actual = execute(&mut ctx, sql);
+    assert_eq!("2\t2020-01-02\n1\t2019-12-31\n".to_string(), actual);
+
+    // null dates are sorted last
+    let sql = "SELECT v, d FROM t ORDER BY d";
+    let actual = execute(&mut ctx, sql);
+    let expected = "1\t2019-12-31\n2\t2020-01-02\n3\t2020-01-03\n4\t\n";
+    assert_eq!(expected.to_string(), actual);
+}
+
+#[test]
+fn timestamp_query() {
+    let timestamp_type =
+        DataType::Timestamp(TimeUnit::Millisecond, Some("+05:30".to_string()));
+    let schema = Schema::new(vec![
+        Field::new("id", DataType::Int32, false),
+        Field::new("created", timestamp_type.clone(), false),
+        Field::new("updated", timestamp_type.clone(), false),
+    ]);
+    let timestamps = |values: Vec<i64>| {
+        let data = ArrayData::builder(timestamp_type.clone())
+            .len(values.len())
+            .add_buffer(Buffer::from(values.to_byte_slice()))
+            .build();
+        make_array(data)
+    };
+    let batch = RecordBatch::new(
+        Arc::new(schema.clone()),
+        vec![
+            Arc::new(Int32Array::from(vec![1, 2, 3, 4])),
+            timestamps(vec![300, 100, 200, 400]),
+            timestamps(vec![300, 150, 250, 500]),
+        ],
+    );
+    let mut ctx = ExecutionContext::new();
+    ctx.register_table("t", &schema, vec![batch]).unwrap();
+
+    let sql = "SELECT id, created FROM t WHERE created < updated ORDER BY created DESC";
+    let batches = ctx.sql_collect(sql).unwrap();
+    let mut ids = vec![];
+    for batch in &batches {
+        // the timezone of the timestamps is kept
+        assert_eq!(&timestamp_type, batch.column(1).data_type());
+        let array = batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<Int32Array>()
+            .unwrap();
+        ids.extend((0..array.len()).map(|i| array.value(i)));
+    }
+    assert_eq!(vec![4, 3, 2], ids);
+}
+
+#[test]
+fn csv_query_with_null_values() {
+    let path = env::temp_dir().join("datafusion_csv_query_with_null_values.csv");
+    fs::write(&path, "c1,c2\na,1\nNULL,\\N\nc,NULL\n,3\n").unwrap();
+
+    let mut ctx = ExecutionContext::new();
+    let schema = Schema::new(vec![
+        Field::new("c1", DataType::Utf8, true),
+        Field::new("c2", DataType::Int32, true),
+    ]);
+    let options = CsvReadOptions::new().with_null_values(&["NULL", "\\N"]);
+    let filename = path.to_str().unwrap();
+    ctx.register_csv_with_options("t", filename, &schema, &options)
+        .unwrap();
+
+    let sql = "SELECT c1 FROM t WHERE c2 IS NULL";
+    let batches = ctx.sql_collect(sql).unwrap();
+    assert_eq!(2, row_count(&batches));
+    let sql = "SELECT c2 FROM t WHERE c1 IS NULL";
+    let batches = ctx.sql_collect(sql).unwrap();
+    assert_eq!(1, row_count(&batches));
+}
+
+#[test]
+fn memory_table_query() {
+    let mut ctx = ExecutionContext::new();
+    let schema = Schema::new(vec![
+        Field::new("a", DataType::Int32, false),
+        Field::new("b", DataType::Int32, false),
+    ]);
+    let batch = |a: Vec<i32>, b: Vec<i32>| {
+        RecordBatch::new(
+            Arc::new(schema.clone()),
+            vec![Arc::new(Int32Array::from(a)), Arc::new(Int32Array::from(b))],
+        )
+    };
+    let batches = vec![
+        batch(vec![1, 2, 3], vec![10, 20, 30]),
+        batch(vec![4, 5, 6], vec![40, 50, 60]),
+    ];
+    ctx.register_table("t", &schema, batches).unwrap();
+    let sql = "SELECT MIN(b), MAX(b) FROM t WHERE a > 1";
+    let actual = execute(&mut ctx, sql);
+    assert_eq!("20\t60\n".to_string(), actual);
+}
+
+#[test]
+fn register_result_query() {
+    let mut ctx = ExecutionContext::new();
+    let schema = Schema::new(vec![
+        Field::new("a", DataType::Int32, false),
+        Field::new("b", DataType::Int32, false),
+    ]);
+    let batch = RecordBatch::new(
+        Arc::new(schema.clone()),
+        vec![
+            Arc::new(Int32Array::from(vec![1, 2, 3, 4, 5])),
+            Arc::new(Int32Array::from(vec![10, 20, 30, 40, 50])),
+        ],
+    );
+    ctx.register_table("t", &schema, vec![batch]).unwrap();
+
+    let relation = ctx.sql("SELECT a, b FROM t WHERE a > 2").unwrap();
+    ctx.register_result("tmp", relation).unwrap();
+
+    let actual = execute(&mut ctx, "SELECT MIN(b), MAX(b) FROM tmp");
+    assert_eq!("30\t50\n".to_string(), actual);
+    let actual = execute(&mut ctx, "SELECT a FROM tmp WHERE b < 40");
+    assert_eq!("3\n".to_string(), actual);
+}
+
+#[test]
+fn schema_qualified_table_query() {
+    let mut ctx = ExecutionContext::new();
+    let schema = Schema::new(vec![Field::new("id", DataType::Int32, false)]);
+    let table = |values: Vec<i32>| {
+        RecordBatch::new(
+            Arc::new(schema.clone()),
+            vec![Arc::new(Int32Array::from(values))],
+        )
+    };
+    ctx.register_table("analytics.events", &schema, vec![table(vec![1, 2, 3])])
+        .unwrap();
+    ctx.register_table("staging.events", &schema, vec![table(vec![10, 20])])
+        .unwrap();
+
+    let actual = execute(&mut ctx, "SELECT MAX(id) FROM analytics.events");
+    assert_eq!("3\n".to_string(), actual);
+    let actual = execute(&mut ctx, "SELECT MAX(id) FROM staging.events");
+    assert_eq!("20\n".to_string(), actual);
+    let actual = execute(
+        &mut ctx,
+        "SELECT events.id FROM staging.events WHERE id > 10",
+    );
+    assert_eq!("20\n".to_string(), actual);
+
+    assert!(ctx.table("analytics.events").is_ok());
+    assert!(ctx.table("events").is_err());
+    assert!(ctx.sql("SELECT id FROM events").is_err());
+}
+
+#[test]
+fn information_schema_columns_query() {
+    let mut ctx = ExecutionContext::new();
+    let schema = Schema::new(vec![
+        Field::new("id", DataType::Int32, false),
+        Field::new("name", DataType::Utf8, true),
+    ]);
+    ctx.register_table("analytics.events", &schema, vec![])
+        .unwrap();
+
+    let sql = "SELECT table_schema, table_name, column_name, ordinal_position, \
+               data_type, is_nullable FROM information_schema.columns";
+    let actual = execute(&mut ctx, sql);
+    let expected = "\"analytics\"\t\"events\"\t\"id\"\t1\t\"INTEGER\"\t\"NO\"\n\
+                    \"analytics\"\t\"events\"\t\"name\"\t2\t\"VARCHAR\"\t\"YES\"\n";
+    assert_eq!(expected.to_string(), actual);
+
+    // the columns of tables that are registered later are included
+    let schema = Schema::new(vec![Field::new("a", DataType::Float64, false)]);
+    ctx.register_table("t", &schema, vec![]).unwrap();
+    let sql = "SELECT table_name, column_name FROM information_schema.columns \
+               WHERE ordinal_position = 1";
+    let actual = execute(&mut ctx, sql);
+    assert_eq!("\"events\"\t\"id\"\n\"t\"\t\"a\"\n".to_string(), actual);
+}
+
+#[test]
+fn plan_error_position() {
+    let mut ctx = ExecutionContext::new();
+    let schema = Schema::new(vec![Field::new("id", DataType::Int32, false)]);
+    ctx.register_table("t", &schema, vec![]).unwrap();
+
+    match ctx.create_logical_plan("SELECT id FROM t WHERE age > 1") {
+        Err(ExecutionError::PlanError(e)) => {
+            assert_eq!(Some("age".to_string()), e.context);
+            assert_eq!(Some(23), e.position);
+        }
+        other => panic!("Expected a planning error but got {:?}", other),
+    }
+    match ctx.create_logical_plan("SELECT id FROM missing") {
+        Err(ExecutionError::PlanError(e)) => assert_eq!(Some(15), e.position),
+        other => panic!("Expected a planning error but got {:?}", other),
+    }
+}
+
+#[test]
+fn integer_overflow_query() {
+    let schema = Schema::new(vec![
+        Field::new("a", DataType::Int8, false),
+        Field::new("b", DataType::Int8, false),
+    ]);
+    let context = |mode: OverflowMode| {
+        let batch = RecordBatch::new(
+            Arc::new(schema.clone()),
+            vec![
+                Arc::new(Int8Array::from(vec![100, -100])),
+                Arc::new(Int8Array::from(vec![100, 100])),
+            ],
+        );
+        let config = ExecutionConfig::new().with_overflow_mode(mode);
+        let mut ctx = ExecutionContext::with_config(config);
+        ctx.register_table("t", &schema, vec![batch]).unwrap();
+        ctx
+    };
+    let sql = "SELECT a + b, a - b FROM t";
+
+    let mut ctx = context(OverflowMode::Checked);
+    assert!(ctx.sql_collect(sql).is_err());
+    let actual = execute(&mut ctx, "SELECT a - b FROM t WHERE a > 0");
+    assert_eq!("0\n".to_string(), actual);
+
+    let mut ctx = context(OverflowMode::Wrapping);
+    assert_eq!("-56\t0\n0\t56\n".to_string(), execute(&mut ctx, sql));
+
+    let mut ctx = context(OverflowMode::Saturating);
+    assert_eq!("127\t0\n0\t-128\n".to_string(), execute(&mut ctx, sql));
+}
+
+#[test]
+fn divide_by_zero_query() {
+    let schema = Schema::new(vec![
+        Field::new("a", DataType::Int32, false),
+        Field::new("b", DataType::Int32, false),
+        Field::new("c", DataType::Float64, false),
+    ]);
+    let context = |config: ExecutionConfig| {
+        let batch = RecordBatch::new(
+            Arc::new(schema.clone()),
+            vec![
+                Arc::new(Int32Array::from(vec![7, -7, 6])),
+                Arc::new(Int32Array::from(vec![2, 0, 4])),
+                Arc::new(Float64Array::from(vec![3.0, 1.5, 0.0])),
+            ],
+        );
+        let mut ctx = ExecutionContext::with_config(config);
+        ctx.register_table("t", &schema, vec![batch]).unwrap();
+        ctx
+    };
+
+    // division and modulo by zero produce null by default
+    let ctx = context(ExecutionConfig::new());
+    let batches = ctx
+        .sql_collect("SELECT a / b, a % b, c / c, c % c FROM t")
+        .unwrap();
+    assert_eq!(1, batches.len());
+    let batch = &batches[0];
+    let quotients = batch
+        .column(0)
+        .as_any()
+        .downcast_ref::<Int32Array>()
+        .unwrap();
+    assert_eq!((3, 1), (quotients.value(0), quotients.value(2)));
+    assert!(quotients.is_null(1));
+    let remainders = batch
+        .column(1)
+        .as_any()
+        .downcast_ref::<Int32Array>()
+        .unwrap();
+    assert_eq!((1, 2), (remainders.value(0), remainders.value(2)));
+    assert!(remainders.is_null(1));
+    let quotients = batch
+        .column(2)
+        .as_any()
+        .downcast_ref::<Float64Array>()
+        .unwrap();
+    assert_eq!((1.0, 1.0), (quotients.value(0), quotients.value(1)));
+    assert!(quotients.is_null(2));
+    let remainders = batch
+        .column(3)
+        .as_any()
+        .downcast_ref::<Float64Array>()
+        .unwrap();
+    assert_eq!(0.0, remainders.value(1));
+    assert!(remainders.is_null(2));
+
+    let ctx = context(ExecutionConfig::new().with_error_on_divide_by_zero(true));
+    assert!(ctx.sql_collect("SELECT a / b FROM t").is_err());
+    assert!(ctx.sql_collect("SELECT a % b FROM t").is_err());
+    assert!(ctx.sql_collect("SELECT a / b FROM t WHERE b > 0").is_ok());
+}
+
+#[test]
+fn null_predicate_query() {
+    let mut ctx = ExecutionContext::new();
+    let schema = Schema::new(vec![
+        Field::new("a", DataType::Int32, true),
+        Field::new("b", DataType::Int32, true),
+    ]);
+    let batch = RecordBatch::new(
+        Arc::new(schema.clone()),
+        vec![
+            Arc::new(Int32Array::from(vec![Some(1), None, Some(3)])),
+            Arc::new(Int32Array::from(vec![Some(1), Some(1), None])),
+        ],
+    );
+    ctx.register_table("t", &schema, vec![batch]).unwrap();
+
+    // comparisons with null are unknown, so the rows are excluded
+    let actual = execute(&mut ctx, "SELECT a FROM t WHERE a < 2");
+    assert_eq!("1\n".to_string(), actual);
+    let actual = execute(&mut ctx, "SELECT a FROM t WHERE a <> 3");
+    assert_eq!("1\n".to_string(), actual);
+
+    // false AND unknown is false, and true OR unknown is true
+    let actual = execute(&mut ctx, "SELECT a FROM t WHERE a < 2 AND b = 1");
+    assert_eq!("1\n".to_string(), actual);
+    let sql = "SELECT a FROM t WHERE a > 1 OR b = 1";
+    assert_eq!(3, row_count(&ctx.sql_collect(sql).unwrap()));
+
+    // null values in the selected rows are preserved
+    let batches = ctx.sql_collect("SELECT a FROM t WHERE b = 1").unwrap();
+    assert_eq!(1, batches.len());
+    let a = batches[0]
+        .column(0)
+        .as_any()
+        .downcast_ref::<Int32Array>()
+        .unwrap();
+    assert_eq!(2, a.len());
+    assert_eq!(1, a.value(0));
+    assert!(a.is_null(1));
+}
+
+#[test]
+fn null_aggregate_query() {
+    let mut ctx = ExecutionContext::new();
+    let schema = Schema::new(vec![
+        Field::new("g", DataType::Utf8, false),
+        Field::new("a", DataType::Int32, true),
+    ]);
+    let batch1 = RecordBatch::new(
+        Arc::new(schema.clone()),
+        vec![
+            Arc::new(BinaryArray::from(vec!["x", "y", "x", "y"])),
+            Arc::new(Int32Array::from(vec![Some(1), None, Some(3), None])),
+        ],
+    );
+    let batch2 = RecordBatch::new(
+        Arc::new(schema.clone()),
+        vec![
+            Arc::new(BinaryArray::from(vec!["x", "y"])),
+            Arc::new(Int32Array::from(vec![None, Some(5)])),
+        ],
+    );
+    ctx.register_table("t", &schema, vec![batch1, batch2])
+        .unwrap();
+
+    // null values are not counted, and are ignored by the other aggregates
+    let sql = "SELECT COUNT(a), SUM(a), MIN(a), MAX(a) FROM t";
+    let actual = execute(&mut ctx, sql);
+    assert_eq!("3\t9\t1\t5\n".to_string(), actual);
+
+    let sql = "SELECT g, COUNT(a), SUM(a) FROM t GROUP BY g";
+    let actual = execute(&mut ctx, sql);
+    assert_eq!(vec!["\"x\"\t2\t4", "\"y\"\t1\t5"], sorted_lines(&actual));
+}
+
+#[test]
+fn group_by_null_key_query() {
+    let mut ctx = ExecutionContext::new();
+    let schema = Schema::new(vec![
+        Field::new("k", DataType::Int32, true),
+        Field::new("a", DataType::Int32, false),
+    ]);
+    let batch1 = RecordBatch::new(
+        Arc::new(schema.clone()),
+        vec![
+            Arc::new(Int32Array::from(vec![Some(1), None, Some(0), None])),
+            Arc::new(Int32Array::from(vec![1, 2, 3, 4])),
+        ],
+    );
+    let batch2 = RecordBatch::new(
+        Arc::new(schema.clone()),
+        vec![
+            Arc::new(Int32Array::from(vec![None, Some(1)])),
+            Arc::new(Int32Array::from(vec![6, 5])),
+        ],
+    );
+    ctx.register_table("t", &schema, vec![batch1, batch2])
+        .unwrap();
+
+    // all null keys form a single group, which is separate from the group of zero
+    let batches = ctx
+        .sql_collect("SELECT k, MAX(a) FROM t GROUP BY k")
+        .unwrap();
+    let mut groups = vec![];
+    for batch in &batches {
+        let k = batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<Int32Array>()
+            .unwrap();
+        let max = batch
+            .column(1)
+            .as_any()
+            .downcast_ref::<Int32Array>()
+            .unwrap();
+        for i in 0..batch.num_rows() {
+            let key = if k.is_null(i) { None } else { Some(k.value(i)) };
+            groups.push((key, max.value(i)));
+        }
+    }
+    groups.sort();
+    assert_eq!(vec![(None, 6), (Some(0), 3), (Some(1), 5)], groups);
+}
+
+#[test]
+fn group_by_dictionary_query() {
+    let mut ctx = ExecutionContext::new();
+    let city_type =
+        DataType::Dictionary(Box::new(DataType::Int8), Box::new(DataType::Utf8));
+    let schema = Schema::new(vec![
+        Field::new("city", city_type.clone(), true),
+        Field::new("a", DataType::Int32, false),
+    ]);
+    // the batches have different dictionaries, so the same city has different keys
+    let batch1 = RecordBatch::new(
+        Arc::new(schema.clone()),
+        vec![
+            Arc::new(
+                Int8DictionaryArray::try_from_strings(&[
+                    Some("Paris"),
+                    Some("Oslo"),
+                    None,
+                    Some("Paris"),
+                ])
+                .unwrap(),
+            ),
+            Arc::new(Int32Array::from(vec![1, 2, 3, 4])),
+        ],
+    );
+    let batch2 = RecordBatch::new(
+        Arc::new(schema.clone()),
+        vec![
+            Arc::new(
+                Int8DictionaryArray::try_from_strings(&[
+                    Some("Oslo"),
+                    Some("Paris"),
+                    None,
+                ])
+                .unwrap(),
+            ),
+            Arc::new(Int32Array::from(vec![10, 20, 30])),
+        ],
+    );
+    ctx.register_table("t", &schema, vec![batch1, batch2])
+        .unwrap();
+
+    // the groups are dictionary encoded like the column
+    let batches = ctx
+        .sql_collect("SELECT city, MAX(a) FROM t GROUP BY city")
+        .unwrap();
+    let mut groups = vec![];
+    for batch in &batches {
+        assert_eq!(&city_type, batch.column(0).data_type());
+        let city = compute::cast(batch.column(0), &DataType::Utf8).unwrap();
+        let city = city.as_any().downcast_ref::<BinaryArray>().unwrap();
+        let max = batch
+            .column(1)
+            .as_any()
+            .downcast_ref::<Int32Array>()
+            .unwrap();
+        for i in 0..batch.num_rows() {
+            let key = if city.is_null(i) {
+                None
+            } else {
+                Some(city.get_string(i))
+            };
+            groups.push((key, max.value(i)));
+        }
+    }
+    groups.sort();
+    assert_eq!(
+        vec![
+            (None, 30),
+            (Some("Oslo".to_string()), 10),
+            (Some("Paris".to_string()), 20)
+        ],
+        groups
+    );
+
+    // dictionary values are compared with other values by their values
+    let sql = "SELECT a FROM t WHERE city = 'Paris'";
+    let actual = execute(&mut ctx, sql);
+    assert_eq!(vec!["1", "20", "4"], sorted_lines(&actual));
+}
 
 #[test]
-fn csv_query_with_predicate() {
+fn literal_comparison_query() {
     let mut ctx = ExecutionContext::new();
-    register_aggregate_csv(&mut ctx);
-    let sql = "SELECT c1, c12 FROM aggregate_test_100 WHERE c12 > 0.376 AND c12 < 0.4";
+    let schema = Schema::new(vec![Field::new("a", DataType::Int64, true)]);
+    let batch = RecordBatch::new(
+        Arc::new(schema.clone()),
+        vec![Arc::new(Int64Array::from(vec![
+            Some(1),
+            None,
+            Some(2),
+            Some(3),
+        ]))],
+    );
+    ctx.register_table("t", &schema, vec![batch]).unwrap();
+
+    // the literal may be on either side of the operator
+    let actual = execute(&mut ctx, "SELECT a FROM t WHERE a < 2");
+    assert_eq!("1\n".to_string(), actual);
+    let actual = execute(&mut ctx, "SELECT a FROM t WHERE 2 > a");
+    assert_eq!("1\n".to_string(), actual);
+    let actual = execute(&mut ctx, "SELECT a FROM t WHERE 2 <= a");
+    assert_eq!("2\n3\n".to_string(), actual);
+    let actual = execute(&mut ctx, "SELECT a FROM t WHERE a <> 2");
+    assert_eq!("1\n3\n".to_string(), actual);
+}
+
+#[test]
+fn short_circuit_predicate_query() {
+    let config = ExecutionConfig::new().with_error_on_divide_by_zero(true);
+    let mut ctx = ExecutionContext::with_config(config);
+    let schema = Schema::new(vec![
+        Field::new("a", DataType::Int32, false),
+        Field::new("b", DataType::Int32, false),
+    ]);
+    let batch = RecordBatch::new(
+        Arc::new(schema.clone()),
+        vec![
+            Arc::new(Int32Array::from(vec![6, 5, 4])),
+            Arc::new(Int32Array::from(vec![2, 0, 4])),
+        ],
+    );
+    ctx.register_table("t", &schema, vec![batch]).unwrap();
+
+    // the division is only evaluated on the rows that the left side does not determine
+    let actual = execute(&mut ctx, "SELECT a FROM t WHERE b <> 0 AND a / b > 1");
+    assert_eq!("6\n".to_string(), actual);
+    let actual = execute(&mut ctx, "SELECT a FROM t WHERE b = 0 OR a / b > 1");
+    assert_eq!("6\n5\n".to_string(), actual);
+    assert!(ctx.sql_collect("SELECT a FROM t WHERE a / b > 1").is_err());
+}
+
+#[test]
+fn nan_comparison_query() {
+    let schema = Schema::new(vec![Field::new("c", DataType::Float64, false)]);
+    let context = |nan_ordering: NanOrdering| {
+        let batch = RecordBatch::new(
+            Arc::new(schema.clone()),
+            vec![Arc::new(Float64Array::from(vec![
+                std::f64::NAN,
+                1.0,
+                std::f64::INFINITY,
+                -std::f64::NAN,
+            ]))],
+        );
+        let config = ExecutionConfig::new().with_nan_ordering(nan_ordering);
+        let mut ctx = ExecutionContext::with_config(config);
+        ctx.register_table("t", &schema, vec![batch]).unwrap();
+        ctx
+    };
+    let count =
+        |ctx: &ExecutionContext, sql: &str| row_count(&ctx.sql_collect(sql).unwrap());
+
+    // NaN values are equal to each other and greater than all other values
+    let ctx = context(NanOrdering::NanLast);
+    assert_eq!(3, count(&ctx, "SELECT c FROM t WHERE c > 2.0"));
+    assert_eq!(4, count(&ctx, "SELECT c FROM t WHERE c = c"));
+
+    // negative NaN values are less than all other values
+    let ctx = context(NanOrdering::TotalOrder);
+    assert_eq!(2, count(&ctx, "SELECT c FROM t WHERE c > 2.0"));
+    assert_eq!(1, count(&ctx, "SELECT c FROM t WHERE c < 0.0"));
+}
+
+#[test]
+fn case_insensitive_string_query() {
+    let schema = Schema::new(vec![Field::new("name", DataType::Utf8, false)]);
+    let context = |collation: Collation| {
+        let batch = RecordBatch::new(
+            Arc::new(schema.clone()),
+            vec![Arc::new(BinaryArray::from(vec!["Alice", "bob", "ALICE"]))],
+        );
+        let config = ExecutionConfig::new().with_collation(collation);
+        let mut ctx = ExecutionContext::with_config(config);
+        ctx.register_table("t", &schema, vec![batch]).unwrap();
+        ctx
+    };
+
+    let mut ctx = context(Collation::Binary);
+    assert!(execute(&mut ctx, "SELECT name FROM t WHERE name = 'alice'").is_empty());
+    let actual = execute(&mut ctx, "SELECT name FROM t WHERE name > 'a'");
+    assert_eq!("\"bob\"\n".to_string(), actual);
+
+    let mut ctx = context(Collation::CaseInsensitive);
+    let actual = execute(&mut ctx, "SELECT name FROM t WHERE name = 'alice'");
+    assert_eq!("\"Alice\"\n\"ALICE\"\n".to_string(), actual);
+    let actual = execute(&mut ctx, "SELECT name FROM t WHERE name > 'a'");
+    assert_eq!("\"Alice\"\n\"bob\"\n\"ALICE\"\n".to_string(), actual);
+}
+
+#[test]
+fn query_config_overrides_context_config() {
+    let schema = Schema::new(vec![
+        Field::new("a", DataType::Int8, false),
+        Field::new("b", DataType::Int8, false),
+        Field::new("c", DataType::Float64, false),
+        Field::new("name", DataType::Utf8, false),
+    ]);
+    let batch = RecordBatch::new(
+        Arc::new(schema.clone()),
+        vec![
+            Arc::new(Int8Array::from(vec![100, -100])),
+            Arc::new(Int8Array::from(vec![100, 0])),
+            Arc::new(Float64Array::from(vec![-std::f64::NAN, 1.0])),
+            Arc::new(BinaryArray::from(vec!["Alice", "bob"])),
+        ],
+    );
+    let mut ctx = ExecutionContext::new();
+    ctx.register_table("t", &schema, vec![batch]).unwrap();
+    let count = |sql: &str, config: &ExecutionConfig| {
+        row_count(&collect_with_config(&ctx, sql, config).unwrap())
+    };
+
+    let sql = "SELECT a + b FROM t WHERE a > 0";
+    assert!(ctx.sql_collect(sql).is_err());
+    let config = ExecutionConfig::new().with_overflow_mode(OverflowMode::Wrapping);
+    let relation = ctx.sql_with_config(sql, &config).unwrap();
+    assert_eq!("-56\n".to_string(), result_str(&relation));
+
+    let sql = "SELECT a / b FROM t";
+    assert!(ctx.sql_collect(sql).is_ok());
+    let config = ExecutionConfig::new().with_error_on_divide_by_zero(true);
+    assert!(collect_with_config(&ctx, sql, &config).is_err());
+
+    let sql = "SELECT c FROM t WHERE c > 2.0";
+    assert_eq!(1, count(sql, &ExecutionConfig::new()));
+    let config = ExecutionConfig::new().with_nan_ordering(NanOrdering::TotalOrder);
+    assert_eq!(0, count(sql, &config));
+
+    let sql = "SELECT name FROM t WHERE name = 'alice'";
+    assert_eq!(0, count(sql, &ExecutionConfig::new()));
+    let config = ExecutionConfig::new().with_collation(Collation::CaseInsensitive);
+    assert_eq!(1, count(sql, &config));
+}
+
+#[test]
+fn binary_query() {
+    let schema = Schema::new(vec![
+        Field::new("id", DataType::Int32, false),
+        Field::new("payload", DataType::Binary, false),
+    ]);
+    let payloads: Vec<&[u8]> = vec![&[0xde, 0xad], &[0xbe, 0xef], &[0xde, 0xad]];
+    let batch = RecordBatch::new(
+        Arc::new(schema.clone()),
+        vec![
+            Arc::new(Int32Array::from(vec![1, 2, 3])),
+            Arc::new(BinaryArray::from(payloads)),
+        ],
+    );
+    let mut ctx = ExecutionContext::new();
+    ctx.register_table("blobs", &schema, vec![batch]).unwrap();
+
+    let sql = "SELECT id, payload FROM blobs WHERE payload = X'DEAD'";
     let actual = execute(&mut ctx, sql);
-    let expected = "\"e\"\t0.39144436569161134\n\"d\"\t0.38870280983958583\n".to_string();
-    assert_eq!(expected, actual);
+    assert_eq!("1\tdead\n3\tdead\n".to_string(), actual);
+
+    let sql = "SELECT payload, COUNT(id) FROM blobs GROUP BY payload";
+    let actual = execute(&mut ctx, sql);
+    assert_eq!(vec!["beef\t1", "dead\t2"], sorted_lines(&actual));
+
+    let sql = "SELECT length(payload), substring(payload, 2), substring(payload, 1, 1) \
+               FROM blobs WHERE payload <> x'dead'";
+    let actual = execute(&mut ctx, sql);
+    assert_eq!("2\tef\tbe\n".to_string(), actual);
+
+    assert!(ctx
+        .create_logical_plan("SELECT id FROM blobs WHERE payload = X'ABC'")
+        .is_err());
 }
 
 #[test]
-fn csv_query_group_by_int_min_max() {
+fn list_functions_query() {
+    let list_type = DataType::List(Box::new(DataType::Int32));
+    let schema = Schema::new(vec![
+        Field::new("id", DataType::Int32, false),
+        Field::new("tags", list_type, true),
+    ]);
+    let mut tags = ListBuilder::new(Int32Builder::new(8));
+    for list in &[vec![1, 2, 3], vec![4], vec![5, 6]] {
+        for value in list {
+            tags.values().append_value(*value).unwrap();
+        }
+        tags.append(true).unwrap();
+    }
+    let batch = RecordBatch::new(
+        Arc::new(schema.clone()),
+        vec![
+            Arc::new(Int32Array::from(vec![1, 2, 3])),
+            Arc::new(tags.finish()),
+        ],
+    );
     let mut ctx = ExecutionContext::new();
-    register_aggregate_csv(&mut ctx);
-    //TODO add ORDER BY once supported, to make this test determistic
-    let sql = "SELECT c2, MIN(c12), MAX(c12) FROM aggregate_test_100 GROUP BY c2";
+    ctx.register_table("t", &schema, vec![batch]).unwrap();
+
+    let sql = "SELECT id, array_length(tags), array_element(tags, 2) FROM t \
+               WHERE array_length(tags) > 1";
     let actual = execute(&mut ctx, sql);
-    let expected = "4\t0.02182578039211991\t0.9237877978193884\n2\t0.16301110515739792\t0.991517828651004\n5\t0.01479305307777301\t0.9723580396501548\n3\t0.047343434291126085\t0.9293883502480845\n1\t0.05636955101974106\t0.9965400387585364\n".to_string();
-    assert_eq!(expected, actual);
+    assert_eq!("1\t3\t2\n3\t2\t6\n".to_string(), actual);
+
+    let sql = "SELECT id FROM t WHERE ARRAY_ELEMENT(tags, 1) = 4";
+    let actual = execute(&mut ctx, sql);
+    assert_eq!("2\n".to_string(), actual);
+
+    let plan = ctx.create_logical_plan("SELECT array_element(tags, 1) FROM t");
+    assert_eq!(
+        &DataType::Int32,
+        plan.unwrap().schema().field(0).data_type()
+    );
+    assert!(ctx
+        .create_logical_plan("SELECT array_length(id) FROM t")
+        .is_err());
 }
 
 #[test]
-fn csv_query_group_by_string_min_max() {
+fn unnest_query() {
+    let list_type = DataType::List(Box::new(DataType::Int32));
+    let schema = Schema::new(vec![
+        Field::new("id", DataType::Int32, false),
+        Field::new("tags", list_type, true),
+    ]);
+    // [1, 2], [], null, [3]
+    let mut tags = ListBuilder::new(Int32Builder::new(8));
+    tags.values().append_value(1).unwrap();
+    tags.values().append_value(2).unwrap();
+    tags.append(true).unwrap();
+    tags.append(true).unwrap();
+    tags.append(false).unwrap();
+    tags.values().append_value(3).unwrap();
+    tags.append(true).unwrap();
+    let batch = RecordBatch::new(
+        Arc::new(schema.clone()),
+        vec![
+            Arc::new(Int32Array::from(vec![1, 2, 3, 4])),
+            Arc::new(tags.finish()),
+        ],
+    );
     let mut ctx = ExecutionContext::new();
-    register_aggregate_csv(&mut ctx);
-    //TODO add ORDER BY once supported, to make this test determistic
-    let sql = "SELECT c2, MIN(c12), MAX(c12) FROM aggregate_test_100 GROUP BY c1";
+    ctx.register_table("t", &schema, vec![batch]).unwrap();
+
+    let sql = "SELECT id, UNNEST(tags) FROM t";
     let actual = execute(&mut ctx, sql);
-    let expected =
-        "\"d\"\t0.061029375346466685\t0.9748360509016578\n\"c\"\t0.0494924465469434\t0.991517828651004\n\"b\"\t0.04893135681998029\t0.9185813970744787\n\"a\"\t0.02182578039211991\t0.9800193410444061\n\"e\"\t0.01479305307777301\t0.9965400387585364\n".to_string();
-    assert_eq!(expected, actual);
+    assert_eq!("1\t1\n1\t2\n4\t3\n".to_string(), actual);
+
+    let sql = "SELECT unnest(tags) FROM t WHERE id > 1";
+    let actual = execute(&mut ctx, sql);
+    assert_eq!("3\n".to_string(), actual);
+
+    let plan = ctx.create_logical_plan("SELECT UNNEST(tags) FROM t");
+    assert_eq!(
+        &DataType::Int32,
+        plan.unwrap().schema().field(0).data_type()
+    );
+    assert!(ctx.create_logical_plan("SELECT UNNEST(id) FROM t").is_err());
+    assert!(ctx
+        .create_logical_plan("SELECT UNNEST(tags), UNNEST(tags) FROM t")
+        .is_err());
+    assert!(ctx
+        .create_logical_plan("SELECT COUNT(id), UNNEST(tags) FROM t")
+        .is_err());
 }
 
 #[test]
-fn csv_query_cast() {
+fn list_equality_query() {
+    let list_type = DataType::List(Box::new(DataType::Int32));
+    let schema = Schema::new(vec![
+        Field::new("id", DataType::Int32, false),
+        Field::new("a", list_type.clone(), true),
+        Field::new("b", list_type, true),
+    ]);
+    let mut a = ListBuilder::new(Int32Builder::new(8));
+    let mut b = ListBuilder::new(Int32Builder::new(8));
+    for (x, y) in &[
+        (vec![1, 2], vec![1, 2]),
+        (vec![3], vec![3, 4]),
+        (vec![], vec![]),
+    ] {
+        a.values().append_slice(x).unwrap();
+        a.append(true).unwrap();
+        b.values().append_slice(y).unwrap();
+        b.append(true).unwrap();
+    }
+    let batch = RecordBatch::new(
+        Arc::new(schema.clone()),
+        vec![
+            Arc::new(Int32Array::from(vec![1, 2, 3])),
+            Arc::new(a.finish()),
+            Arc::new(b.finish()),
+        ],
+    );
     let mut ctx = ExecutionContext::new();
-    register_aggregate_csv(&mut ctx);
-    let sql = "SELECT CAST(c12 AS float) FROM aggregate_test_100 WHERE c12 > 0.376 AND c12 < 0.4";
+    ctx.register_table("t", &schema, vec![batch]).unwrap();
+
+    let actual = execute(&mut ctx, "SELECT id FROM t WHERE a = b");
+    assert_eq!("1\n3\n".to_string(), actual);
+    let actual = execute(&mut ctx, "SELECT id FROM t WHERE a <> b");
+    assert_eq!("2\n".to_string(), actual);
+
+    // lists have no order
+    assert!(ctx.sql_collect("SELECT id FROM t WHERE a < b").is_err());
+}
+
+#[test]
+fn struct_field_query() {
+    let address_type = DataType::Struct(vec![
+        Field::new("city", DataType::Utf8, false),
+        Field::new("zip", DataType::Int32, false),
+    ]);
+    let schema = Schema::new(vec![
+        Field::new("id", DataType::Int32, false),
+        Field::new("address", address_type, false),
+    ]);
+    let address = StructArray::from(vec![
+        (
+            Field::new("city", DataType::Utf8, false),
+            Arc::new(BinaryArray::from(vec!["Paris", "Oslo", "Lima"])) as ArrayRef,
+        ),
+        (
+            Field::new("zip", DataType::Int32, false),
+            Arc::new(Int32Array::from(vec![75001, 150, 15001])) as ArrayRef,
+        ),
+    ]);
+    let batch = RecordBatch::new(
+        Arc::new(schema.clone()),
+        vec![Arc::new(Int32Array::from(vec![1, 2, 3])), Arc::new(address)],
+    );
+    let mut ctx = ExecutionContext::new();
+    ctx.register_table("customer", &schema, vec![batch])
+        .unwrap();
+
+    let sql = "SELECT id, address.city FROM customer WHERE address.zip > 1000";
     let actual = execute(&mut ctx, sql);
-    let expected = "0.39144436569161134\n0.38870280983958583\n".to_string();
-    assert_eq!(expected, actual);
+    assert_eq!("1\t\"Paris\"\n3\t\"Lima\"\n".to_string(), actual);
+
+    let sql = "SELECT customer.address.zip FROM customer WHERE id = 2";
+    let actual = execute(&mut ctx, sql);
+    assert_eq!("150\n".to_string(), actual);
+
+    let plan = ctx
+        .create_logical_plan("SELECT address.city FROM customer")
+        .unwrap();
+    assert_eq!("city", plan.schema().field(0).name());
+    assert_eq!(&DataType::Utf8, plan.schema().field(0).data_type());
+    assert!(ctx
+        .create_logical_plan("SELECT address.country FROM customer")
+        .is_err());
 }
 
 #[test]
-fn csv_query_limit() {
+fn scalar_udf_query() {
     let mut ctx = ExecutionContext::new();
-    register_aggregate_csv(&mut ctx);
-    let sql = "SELECT 0 FROM aggregate_test_100 LIMIT 2";
+    let schema = Schema::new(vec![
+        Field::new("a", DataType::Int32, false),
+        Field::new("b", DataType::Int32, false),
+    ]);
+    let batch = RecordBatch::new(
+        Arc::new(schema.clone()),
+        vec![
+            Arc::new(Int32Array::from(vec![1, 10, 100])),
+            Arc::new(Int32Array::from(vec![2, 20, 200])),
+        ],
+    );
+    ctx.register_table("t", &schema, vec![batch]).unwrap();
+
+    fn my_add(args: &[ArrayRef]) -> Result<ArrayRef> {
+        let a = args[0].as_any().downcast_ref::<Int32Array>().unwrap();
+        let b = args[1].as_any().downcast_ref::<Int32Array>().unwrap();
+        let values: Vec<i32> = (0..a.len()).map(|i| a.value(i) + b.value(i)).collect();
+        Ok(Arc::new(Int32Array::from(values)))
+    }
+    let arg_types = vec![DataType::Int32, DataType::Int32];
+    ctx.register_udf("my_add", arg_types, DataType::Int32, my_add);
+    let sql = "SELECT a, MY_ADD(a, b) FROM t WHERE a > 5";
     let actual = execute(&mut ctx, sql);
-    let expected = "0\n0\n".to_string();
-    assert_eq!(expected, actual);
+    assert_eq!("10\t30\n100\t300\n".to_string(), actual);
+
+    assert!(ctx.sql("SELECT my_add(a) FROM t").is_err());
+    assert!(ctx.sql("SELECT unknown(a) FROM t").is_err());
+
+    // an error returned by a function fails the query
+    ctx.register_udf("my_fail", vec![DataType::Int32], DataType::Int32, |_| {
+        Err(ExecutionError::General("my_fail failed".to_string()))
+    });
+    assert!(ctx.sql_collect("SELECT my_fail(a) FROM t").is_err());
 }
 
 #[test]
-fn csv_query_limit_bigger_than_nbr_of_rows() {
+fn scalar_udf_with_overloads_query() {
     let mut ctx = ExecutionContext::new();
-    register_aggregate_csv(&mut ctx);
-    let sql = "SELECT c2 FROM aggregate_test_100 LIMIT 200";
+    let schema = Schema::new(vec![Field::new("x", DataType::Float64, false)]);
+    let batch = RecordBatch::new(
+        Arc::new(schema.clone()),
+        vec![Arc::new(Float64Array::from(vec![1.25, 2.5]))],
+    );
+    ctx.register_table("t", &schema, vec![batch]).unwrap();
+
+    fn my_round(args: &[ArrayRef]) -> Result<ArrayRef> {
+        let x = args[0].as_any().downcast_ref::<Float64Array>().unwrap();
+        let digits = args
+            .get(1)
+            .map(|a| a.as_any().downcast_ref::<Int64Array>().unwrap());
+        let values: Vec<f64> = (0..x.len())
+            .map(|i| {
+                let scale = digits.map_or(1.0, |d| 10f64.powi(d.value(i) as i32));
+                (x.value(i) * scale).round() / scale
+            })
+            .collect();
+        Ok(Arc::new(Float64Array::from(values)))
+    }
+    let signatures = vec![
+        Signature::Exact(vec![DataType::Float64]),
+        Signature::Exact(vec![DataType::Float64, DataType::Int64]),
+    ];
+    ctx.register_udf_with_signatures("my_round", signatures, DataType::Float64, my_round);
+    let sql = "SELECT my_round(x), my_round(x, 1) FROM t";
     let actual = execute(&mut ctx, sql);
-    let expected = "2\n5\n1\n1\n5\n4\n3\n3\n1\n4\n1\n4\n3\n2\n1\n1\n2\n1\n3\n2\n4\n1\n5\n4\n2\n1\n4\n5\n2\n3\n4\n2\n1\n5\n3\n1\n2\n3\n3\n3\n2\n4\n1\n3\n2\n5\n2\n1\n4\n1\n4\n2\n5\n4\n2\n3\n4\n4\n4\n5\n4\n2\n1\n2\n4\n2\n3\n5\n1\n1\n4\n2\n1\n2\n1\n1\n5\n4\n5\n2\n3\n2\n4\n1\n3\n4\n3\n2\n5\n3\n3\n2\n5\n5\n4\n1\n3\n3\n4\n4\n".to_string();
-    assert_eq!(expected, actual);
+    assert_eq!("1.0\t1.3\n3.0\t2.5\n".to_string(), actual);
+
+    assert!(ctx.sql("SELECT my_round(x, 1, 2) FROM t").is_err());
+}
+
+/// Table provider with a single column containing the numbers from zero up to a limit
+struct NumbersTable {
+    schema: Arc<Schema>,
+    limit: i32,
+}
+
+impl TableProvider for NumbersTable {
+    fn schema(&self) -> &Arc<Schema> {
+        &self.schema
+    }
+
+    fn scan(
+        &self,
+        _projection: &Option<Vec<usize>>,
+        batch_size: usize,
+    ) -> Result<Rc<RefCell<DataSource>>> {
+        Ok(Rc::new(RefCell::new(NumbersDataSource {
+            schema: self.schema.clone(),
+            next: 0,
+            limit: self.limit,
+            batch_size: batch_size as i32,
+        })))
+    }
+
+    /// Apply filters of the form `n < value` by lowering the limit
+    fn push_down_filters(&self, filters: &[Expr]) -> Result<Option<FilteredTable>> {
+        let bounds: Vec<Option<i32>> = filters.iter().map(upper_bound).collect();
+        let limit = bounds.iter().filter_map(|bound| *bound).min();
+        Ok(limit.map(|limit| FilteredTable {
+            provider: Arc::new(NumbersTable {
+                schema: self.schema.clone(),
+                limit: self.limit.min(limit),
+            }),
+            handled: bounds.iter().map(|bound| bound.is_some()).collect(),
+        }))
+    }
+}
+
+/// Get the value of a filter of the form `n < value`
+fn upper_bound(filter: &Expr) -> Option<i32> {
+    match filter {
+        Expr::BinaryExpr {
+            left,
+            op: Operator::Lt,
+            right,
+        } => {
+            let column = match left.as_ref() {
+                Expr::Cast { expr, .. } => expr.as_ref(),
+                other => other,
+            };
+            match (column, right.as_ref()) {
+                (Expr::Column(0), Expr::Literal(ScalarValue::Int32(v))) => Some(*v),
+                (Expr::Column(0), Expr::Literal(ScalarValue::Int64(v))) => {
+                    Some(*v as i32)
+                }
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+struct NumbersDataSource {
+    schema: Arc<Schema>,
+    next: i32,
+    limit: i32,
+    batch_size: i32,
+}
+
+impl DataSource for NumbersDataSource {
+    fn schema(&self) -> &Arc<Schema> {
+        &self.schema
+    }
+
+    fn next(&mut self) -> Result<Option<RecordBatch>> {
+        if self.next >= self.limit {
+            return Ok(None);
+        }
+        let end = self.limit.min(self.next + self.batch_size);
+        let values: Vec<i32> = (self.next..end).collect();
+        self.next = end;
+        Ok(Some(RecordBatch::new(
+            self.schema.clone(),
+            vec![Arc::new(Int32Array::from(values))],
+        )))
+    }
 }
 
 #[test]
-fn csv_query_limit_with_same_nbr_of_rows() {
+fn custom_table_provider_query() {
+    let mut ctx =
+        ExecutionContext::with_config(ExecutionConfig::new().with_batch_size(7));
+    let provider = NumbersTable {
+        schema: Arc::new(Schema::new(vec![Field::new("n", DataType::Int32, false)])),
+        limit: 100,
+    };
+    ctx.register_table_provider("numbers", Arc::new(provider));
+    let sql = "SELECT MIN(n), MAX(n) FROM numbers WHERE n > 41";
+    let actual = execute(&mut ctx, sql);
+    assert_eq!("42\t99\n".to_string(), actual);
+
+    // the provider applies `n < 50` and the filter on `n > 41` is evaluated afterwards
+    let sql = "SELECT MIN(n), MAX(n) FROM numbers WHERE n > 41 AND n < 50";
+    let actual = execute(&mut ctx, sql);
+    assert_eq!("42\t49\n".to_string(), actual);
+
+    let sql = "SELECT n FROM numbers WHERE n < 5";
+    let batches = ctx.sql_collect(sql).unwrap();
+    assert_eq!(5, row_count(&batches));
+}
+
+#[test]
+fn table_function_query() {
     let mut ctx = ExecutionContext::new();
-    register_aggregate_csv(&mut ctx);
-    let sql = "SELECT c2 FROM aggregate_test_100 LIMIT 100";
+    let actual = execute(&mut ctx, "SELECT * FROM range(3)");
+    assert_eq!("0\n1\n2\n".to_string(), actual);
+    let sql = "SELECT MIN(value), MAX(value) FROM range(5, 1000000) WHERE value > 41";
     let actual = execute(&mut ctx, sql);
-    let expected = "2\n5\n1\n1\n5\n4\n3\n3\n1\n4\n1\n4\n3\n2\n1\n1\n2\n1\n3\n2\n4\n1\n5\n4\n2\n1\n4\n5\n2\n3\n4\n2\n1\n5\n3\n1\n2\n3\n3\n3\n2\n4\n1\n3\n2\n5\n2\n1\n4\n1\n4\n2\n5\n4\n2\n3\n4\n4\n4\n5\n4\n2\n1\n2\n4\n2\n3\n5\n1\n1\n4\n2\n1\n2\n1\n1\n5\n4\n5\n2\n3\n2\n4\n1\n3\n4\n3\n2\n5\n3\n3\n2\n5\n5\n4\n1\n3\n3\n4\n4\n".to_string();
-    assert_eq!(expected, actual);
+    assert_eq!("42\t999999\n".to_string(), actual);
+
+    ctx.register_table_function("numbers", |args| match args {
+        [ScalarValue::Int64(limit)] => Ok(Arc::new(NumbersTable {
+            schema: Arc::new(Schema::new(vec![Field::new("n", DataType::Int32, false)])),
+            limit: *limit as i32,
+        }) as Arc<TableProvider>),
+        _ => Err(ExecutionError::General("Invalid arguments".to_string())),
+    });
+    let actual = execute(&mut ctx, "SELECT MAX(n) FROM numbers(10)");
+    assert_eq!("9\n".to_string(), actual);
+
+    assert!(ctx.sql("SELECT * FROM numbers('a')").is_err());
+    assert!(ctx.sql("SELECT * FROM unknown(1)").is_err());
+
+    // the tables returned by table functions only live for the query that calls them
+    let sql = "SELECT table_name FROM information_schema.columns";
+    assert_eq!("".to_string(), execute(&mut ctx, sql));
 }
 
 #[test]
-fn csv_query_limit_zero() {
+fn stream_table_query() {
+    let schema = Schema::new(vec![Field::new("n", DataType::Int32, false)]);
+    let (sender, receiver) = mpsc::channel();
+    let producer_schema = Arc::new(schema.clone());
+    let producer = thread::spawn(move || {
+        for i in 0..10 {
+            let values: Vec<i32> = (i * 10..(i + 1) * 10).collect();
+            let batch = RecordBatch::new(
+                producer_schema.clone(),
+                vec![Arc::new(Int32Array::from(values))],
+            );
+            sender.send(batch).unwrap();
+        }
+    });
+
     let mut ctx = ExecutionContext::new();
-    register_aggregate_csv(&mut ctx);
-    let sql = "SELECT 0 FROM aggregate_test_100 LIMIT 0";
+    let provider = StreamTable::from_receiver(&schema, receiver);
+    ctx.register_table_provider("numbers", Arc::new(provider));
+    let sql = "SELECT MIN(n), MAX(n) FROM numbers WHERE n > 41";
     let actual = execute(&mut ctx, sql);
-    let expected = "".to_string();
-    assert_eq!(expected, actual);
+    assert_eq!("42\t99\n".to_string(), actual);
+    producer.join().unwrap();
+}
+
+/// Table provider that counts how often a table held in memory is scanned
+struct CountingTable {
+    table: MemTable,
+    scans: Arc<AtomicUsize>,
+}
+
+impl TableProvider for CountingTable {
+    fn schema(&self) -> &Arc<Schema> {
+        self.table.schema()
+    }
+
+    fn scan(
+        &self,
+        projection: &Option<Vec<usize>>,
+        batch_size: usize,
+    ) -> Result<Rc<RefCell<DataSource>>> {
+        self.scans.fetch_add(1, Ordering::SeqCst);
+        self.table.scan(projection, batch_size)
+    }
+
+    fn is_static(&self) -> bool {
+        true
+    }
 }
 
-//TODO Uncomment the following test when ORDER BY is implemented to be able to test ORDER BY + LIMIT
-/*
 #[test]
-fn csv_query_limit_with_order_by() {
+fn query_result_cache() {
+    let schema = Schema::new(vec![Field::new("n", DataType::Int32, false)]);
+    let batch = RecordBatch::new(
+        Arc::new(schema.clone()),
+        vec![Arc::new(Int32Array::from(vec![1, 2, 3, 4]))],
+    );
+    let scans = Arc::new(AtomicUsize::new(0));
+    let provider = CountingTable {
+        table: MemTable::try_new(&schema, vec![batch]).unwrap(),
+        scans: scans.clone(),
+    };
+
+    let config = ExecutionConfig::new().with_result_cache(true);
+    let mut ctx = ExecutionContext::with_config(config);
+    ctx.register_table_provider("t", Arc::new(provider));
+    let sql = "SELECT SUM(n) FROM t WHERE n > 1";
+    assert_eq!("9\n".to_string(), execute(&mut ctx, sql));
+    assert_eq!("9\n".to_string(), execute(&mut ctx, sql));
+    assert_eq!(1, scans.load(Ordering::SeqCst));
+
+    // a different query is not answered from the cache
+    let actual = execute(&mut ctx, "SELECT SUM(n) FROM t");
+    assert_eq!("10\n".to_string(), actual);
+    assert_eq!(2, scans.load(Ordering::SeqCst));
+
+    // results are not cached when the cache is disabled for a query
+    let relation = ctx.sql_with_config(sql, &ExecutionConfig::new()).unwrap();
+    assert_eq!("9\n".to_string(), result_str(&relation));
+    assert_eq!(3, scans.load(Ordering::SeqCst));
+
+    ctx.clear_result_cache();
+    assert_eq!("9\n".to_string(), execute(&mut ctx, sql));
+    assert_eq!(4, scans.load(Ordering::SeqCst));
+
+    // settings that can change the results of a query are part of the cache key
+    let config = ExecutionConfig::new()
+        .with_result_cache(true)
+        .with_error_on_divide_by_zero(true);
+    let relation = ctx.sql_with_config(sql, &config).unwrap();
+    assert_eq!("9\n".to_string(), result_str(&relation));
+    assert_eq!(5, scans.load(Ordering::SeqCst));
+    let relation = ctx.sql_with_config(sql, &config).unwrap();
+    assert_eq!("9\n".to_string(), result_str(&relation));
+    assert_eq!(5, scans.load(Ordering::SeqCst));
+
+    // registering a function clears the cache
+    ctx.register_udf("f", vec![DataType::Int32], DataType::Int32, |args| {
+        Ok(args[0].clone())
+    });
+    assert_eq!("9\n".to_string(), execute(&mut ctx, sql));
+    assert_eq!(6, scans.load(Ordering::SeqCst));
+    ctx.register_table_function("g", |_| {
+        Err(ExecutionError::General("g failed".to_string()))
+    });
+    assert_eq!("9\n".to_string(), execute(&mut ctx, sql));
+    assert_eq!(7, scans.load(Ordering::SeqCst));
+}
+
+#[test]
+fn query_result_cache_eviction() {
+    let schema = Schema::new(vec![Field::new("n", DataType::Int32, false)]);
+    let batch = RecordBatch::new(
+        Arc::new(schema.clone()),
+        vec![Arc::new(Int32Array::from(vec![1, 2, 3, 4]))],
+    );
+    let scans = Arc::new(AtomicUsize::new(0));
+    let create_context = |config: ExecutionConfig| {
+        let provider = CountingTable {
+            table: MemTable::try_new(&schema, vec![batch.clone()]).unwrap(),
+            scans: scans.clone(),
+        };
+        let mut ctx = ExecutionContext::with_config(config.with_result_cache(true));
+        ctx.register_table_provider("t", Arc::new(provider));
+        ctx
+    };
+    // each query returns three rows, so all results have the same size
+    let q1 = "SELECT n FROM t WHERE n > 1";
+    let q2 = "SELECT n FROM t WHERE n < 4";
+    let q3 = "SELECT n FROM t WHERE n <> 2";
+    let ctx = create_context(ExecutionConfig::new());
+    let size: usize = collect_with_config(&ctx, q1, &ExecutionConfig::new())
+        .unwrap()
+        .iter()
+        .map(batch_memory_size)
+        .sum();
+
+    // the least recently used result is evicted when the cache is full
+    let mut ctx = create_context(ExecutionConfig::new().with_result_cache_size(2 * size));
+    scans.store(0, Ordering::SeqCst);
+    execute(&mut ctx, q1);
+    execute(&mut ctx, q2);
+    execute(&mut ctx, q1);
+    assert_eq!(2, scans.load(Ordering::SeqCst));
+    execute(&mut ctx, q3);
+    assert_eq!(3, scans.load(Ordering::SeqCst));
+    assert_eq!("2\n3\n4\n".to_string(), execute(&mut ctx, q1));
+    assert_eq!(3, scans.load(Ordering::SeqCst));
+    assert_eq!("1\n2\n3\n".to_string(), execute(&mut ctx, q2));
+    assert_eq!(4, scans.load(Ordering::SeqCst));
+
+    // cached results count against the memory limit
+    let mut ctx = create_context(ExecutionConfig::new().with_memory_limit(size));
+    scans.store(0, Ordering::SeqCst);
+    execute(&mut ctx, q1);
+    execute(&mut ctx, q1);
+    assert_eq!(1, scans.load(Ordering::SeqCst));
+    execute(&mut ctx, q2);
+    execute(&mut ctx, q1);
+    assert_eq!(3, scans.load(Ordering::SeqCst));
+
+    // results that are larger than the cache are not cached
+    let mut ctx = create_context(ExecutionConfig::new().with_result_cache_size(size - 1));
+    scans.store(0, Ordering::SeqCst);
+    execute(&mut ctx, q1);
+    execute(&mut ctx, q1);
+    assert_eq!(2, scans.load(Ordering::SeqCst));
+}
+
+/// Object store that holds the contents of each object in memory
+struct MemoryStore {
+    objects: HashMap<String, Vec<u8>>,
+}
+
+impl ObjectStore for MemoryStore {
+    fn list(&self, path: &str) -> Result<Vec<String>> {
+        let mut paths: Vec<String> = self
+            .objects
+            .keys()
+            .filter(|key| key.starts_with(path))
+            .cloned()
+            .collect();
+        paths.sort();
+        Ok(paths)
+    }
+
+    fn size(&self, path: &str) -> Result<u64> {
+        Ok(self.get(path)?.len() as u64)
+    }
+
+    fn open(&self, path: &str) -> Result<Box<ObjectReader>> {
+        Ok(Box::new(Cursor::new(self.get(path)?.clone())))
+    }
+}
+
+impl MemoryStore {
+    fn get(&self, path: &str) -> Result<&Vec<u8>> {
+        self.objects
+            .get(path)
+            .ok_or_else(|| ExecutionError::General(format!("No object '{}'", path)))
+    }
+}
+
+#[test]
+fn object_store_query() {
+    let mut objects = HashMap::new();
+    objects.insert("mem://csv/part-0.csv".to_string(), b"c1\n1\n2\n".to_vec());
+    objects.insert("mem://csv/part-1.csv".to_string(), b"c1\n3\n".to_vec());
+    let filename = format!("{}/alltypes_plain.parquet", parquet_test_data());
+    let contents = fs::read(filename).unwrap();
+    objects.insert("mem://alltypes_plain.parquet".to_string(), contents);
+
+    let mut ctx = ExecutionContext::new();
+    ctx.register_object_store("mem", Arc::new(MemoryStore { objects }));
+    let schema = Schema::new(vec![Field::new("c1", DataType::Int32, false)]);
+    ctx.register_csv("t", "mem://csv/", &schema, true).unwrap();
+    let actual = execute(&mut ctx, "SELECT MIN(c1), MAX(c1) FROM t");
+    assert_eq!("1\t3\n".to_string(), actual);
+
+    ctx.register_parquet("alltypes_plain", "mem://alltypes_plain.parquet")
+        .unwrap();
+    let sql = "SELECT id FROM alltypes_plain WHERE id > 5";
+    let batches = ctx.sql_collect(sql).unwrap();
+    assert_eq!(2, row_count(&batches));
+
+    // there is no object store for the scheme
+    let result = ctx.register_parquet("t", "s3://bucket/file.parquet");
+    assert!(result.is_err());
+    let result = ctx.register_csv("t", "s3://bucket/file.csv", &schema, true);
+    assert!(result.is_err());
+}
+
+#[test]
+fn csv_query_missing_file() {
+    let mut ctx = ExecutionContext::new();
+    let schema = Schema::new(vec![Field::new("c1", DataType::Int32, false)]);
+    let result = ctx.register_csv("t", "does_not_exist/*.csv", &schema, true);
+    assert!(result.is_err());
+
+    // a single file is opened when the table is scanned
+    ctx.register_csv("t", "does_not_exist.csv", &schema, true)
+        .unwrap();
+    assert!(ctx.sql_collect("SELECT c1 FROM t").is_err());
+}
+
+#[test]
+fn csv_query_collect() {
     let mut ctx = ExecutionContext::new();
     register_aggregate_csv(&mut ctx);
-    let sql = "SELECT c7 FROM aggregate_test_100 ORDER BY c7 ASC LIMIT 2";
-    let actual = execute(&mut ctx, sql);
-    let expected = "0\n2\n".to_string();
-    assert_eq!(expected, actual);
+    let sql = "SELECT c1, c2 FROM aggregate_test_100";
+    let batches = ctx.sql_collect(sql).unwrap();
+    assert_eq!(100, row_count(&batches));
+
+    let sql = "SELECT c1 FROM aggregate_test_100 LIMIT 3";
+    let plan = ctx.create_logical_plan(sql).unwrap();
+    let batches = ctx.collect(&plan).unwrap();
+    assert_eq!(3, row_count(&batches));
+    assert_eq!(1, batches[0].num_columns());
+}
+
+#[test]
+fn execution_context_is_send_and_sync() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<ExecutionContext>();
+}
+
+#[test]
+fn csv_query_from_multiple_threads() {
+    let mut ctx = ExecutionContext::new();
+    register_aggregate_csv(&mut ctx);
+    let ctx = Arc::new(ctx);
+    let sql = "SELECT c1, c12 FROM aggregate_test_100 WHERE c12 > 0.376 AND c12 < 0.4";
+    let threads: Vec<_> = (0..4)
+        .map(|_| {
+            let ctx = ctx.clone();
+            thread::spawn(move || result_str(&ctx.sql(sql).unwrap()))
+        })
+        .collect();
+    let expected = "\"e\"\t0.39144436569161134\n\"d\"\t0.38870280983958583\n".to_string();
+    for handle in threads {
+        assert_eq!(expected, handle.join().unwrap());
+    }
+}
+
+#[test]
+fn csv_query_group_by_memory_limit() {
+    let mut ctx =
+        ExecutionContext::with_config(ExecutionConfig::new().with_memory_limit(1));
+    register_aggregate_csv(&mut ctx);
+    let sql = "SELECT c2, MIN(c12) FROM aggregate_test_100 GROUP BY c2";
+    let relation = ctx.sql(&sql).unwrap();
+    let result = relation.borrow_mut().next();
+    match result {
+        Err(ExecutionError::ResourcesExhausted(_)) => {}
+        _ => panic!("Expected ResourcesExhausted"),
+    }
+}
+
+#[test]
+fn dataframe_query() {
+    let mut ctx = ExecutionContext::new();
+    register_aggregate_csv(&mut ctx);
+    let df = ctx
+        .table("aggregate_test_100")
+        .unwrap()
+        .filter(col("c12").gt(&Expr::Literal(ScalarValue::Float64(0.376))))
+        .unwrap()
+        .filter(col("c12").lt(&Expr::Literal(ScalarValue::Float64(0.4))))
+        .unwrap()
+        .select(vec![col("c1"), col("c12")])
+        .unwrap();
+    assert_eq!(2, df.schema().fields().len());
+    assert_eq!("c12", df.schema().field(1).name());
+    let expected = "\"e\"\t0.39144436569161134\n\"d\"\t0.38870280983958583\n".to_string();
+    assert_eq!(expected, result_str(&df.execute().unwrap()));
+    assert_eq!(2, row_count(&df.collect().unwrap()));
+
+    // invalid column names and predicates are reported when the plan is built
+    assert!(ctx.table("no_such_table").is_err());
+    let df = ctx.table("aggregate_test_100").unwrap();
+    assert!(df.select(vec![col("c99")]).is_err());
+    assert!(df.filter(col("c1")).is_err());
+}
+
+#[test]
+fn dataframe_expr_helpers_query() {
+    let mut ctx = ExecutionContext::new();
+    register_aggregate_csv(&mut ctx);
+    let predicate = col("c12").gt(&lit(0.376)).and(&col("c12").lt(&lit(0.4)));
+    let df = ctx
+        .table("aggregate_test_100")
+        .unwrap()
+        .filter(predicate)
+        .unwrap()
+        .select(vec![col("c1"), (col("c12") * lit(2)).alias("c12_doubled")])
+        .unwrap();
+    assert_eq!("c12_doubled", df.schema().field(1).name());
+    let expected = "\"e\"\t0.7828887313832227\n\"d\"\t0.7774056196791717\n".to_string();
+    assert_eq!(expected, result_str(&df.execute().unwrap()));
 }
-*/
 
 fn aggr_test_schema() -> Arc<Schema> {
     Arc::new(Schema::new(vec![
@@ -154,21 +2030,73 @@ fn register_aggregate_csv(ctx: &mut ExecutionContext) {
     );
 }
 
+/// Register the aggregate test data as a table with one partition per copy of the file
+fn register_partitioned_aggregate_csv(ctx: &mut ExecutionContext, partitions: usize) {
+    let schema = aggr_test_schema();
+    let filenames = vec!["../../testing/data/csv/aggregate_test_100.csv"; partitions];
+    ctx.register_partitioned_csv("aggregate_test_100", &filenames, &schema, true);
+}
+
 fn register_csv(
     ctx: &mut ExecutionContext,
     name: &str,
     filename: &str,
     schema: &Arc<Schema>,
 ) {
-    ctx.register_csv(name, filename, &schema, true);
+    ctx.register_csv(name, filename, &schema, true).unwrap();
+}
+
+/// Get the directory that contains the Parquet test files
+fn parquet_test_data() -> String {
+    env::var("PARQUET_TEST_DATA")
+        .expect("PARQUET_TEST_DATA environment variable is not set")
 }
 
 /// Execute query and return result set as tab delimited string
 fn execute(ctx: &mut ExecutionContext, sql: &str) -> String {
-    let results = ctx.sql(&sql, DEFAULT_BATCH_SIZE).unwrap();
+    let results = ctx.sql(&sql).unwrap();
     result_str(&results)
 }
 
+/// Execute a query with a configuration that overrides the configuration of the context
+/// and collect all of the results into memory
+fn collect_with_config(
+    ctx: &ExecutionContext,
+    sql: &str,
+    config: &ExecutionConfig,
+) -> Result<Vec<RecordBatch>> {
+    let relation = ctx.sql_with_config(sql, config)?;
+    let mut results = relation.borrow_mut();
+    let mut batches = vec![];
+    while let Some(batch) = results.next()? {
+        batches.push(batch);
+    }
+    Ok(batches)
+}
+
+/// Execute a query and get the number of rows in each batch of the results
+fn batch_sizes(
+    ctx: &mut ExecutionContext,
+    sql: &str,
+    config: &ExecutionConfig,
+) -> Vec<usize> {
+    let relation = ctx.sql_with_config(&sql, config).unwrap();
+    let mut results = relation.borrow_mut();
+    let mut sizes = vec![];
+    while let Some(batch) = results.next().unwrap() {
+        sizes.push(batch.num_rows());
+    }
+    sizes
+}
+
+/// Split a result set into lines and sort them, for queries where the order of the
+/// results is not deterministic
+fn sorted_lines(results: &str) -> Vec<&str> {
+    let mut lines: Vec<&str> = results.lines().collect();
+    lines.sort();
+    lines
+}
+
 fn result_str(results: &Rc<RefCell<Relation>>) -> String {
     let mut relation = results.borrow_mut();
     let mut str = String::new();
@@ -234,6 +2162,22 @@ fn result_str(results: &Rc<RefCell<Relation>>) -> String {
 
                         str.push_str(&format!("{:?}", s));
                     }
+                    DataType::Binary => {
+                        let array =
+                            column.as_any().downcast_ref::<BinaryArray>().unwrap();
+                        for byte in array.value(row_index) {
+                            str.push_str(&format!("{:02x}", byte));
+                        }
+                    }
+                    DataType::Date(_) => {
+                        let array =
+                            arrow::compute::cast(column, &DataType::Utf8).unwrap();
+                        let array = array.as_any().downcast_ref::<BinaryArray>().unwrap();
+                        let s =
+                            String::from_utf8(array.value(row_index).to_vec()).unwrap();
+                        str.push_str(&s);
+                    }
+                    DataType::Null => str.push_str("NULL"),
                     _ => str.push_str("???"),
                 }
             }
@@ -217,6 +217,7 @@ impl ParquetTypeConverter {
 
     fn to_byte_array(&self) -> Result<DataType> {
         match self.schema.get_basic_info().logical_type() {
+            LogicalType::NONE => Ok(DataType::Binary),
             LogicalType::UTF8 => Ok(DataType::Utf8),
             other => Err(ArrowError(format!(
                 "Unable to convert parquet logical type {}",
@@ -349,6 +350,7 @@ mod tests {
             OPTIONAL DOUBLE  double;
             OPTIONAL FLOAT   float;
             OPTIONAL BINARY  string (UTF8);
+            OPTIONAL BINARY  bytes;
         }
         ";
         let parquet_group_type = parse_message_type(message_type).unwrap();
@@ -366,6 +368,7 @@ mod tests {
             Field::new("double", DataType::Float64, true),
             Field::new("float", DataType::Float32, true),
             Field::new("string", DataType::Utf8, true),
+            Field::new("bytes", DataType::Binary, true),
         ];
 
         assert_eq!(&arrow_fields, converted_arrow_schema.fields());